@@ -13,9 +13,21 @@ if [ -z "$RESOURCE_DIR" ]; then
     exit 1
 fi
 
+# Owner of the admin socket directory; defaults to the console user
+ADMIN_USER="${2:-$(stat -f %Su /dev/console)}"
+# Optional file the app watches for stage names
+PROGRESS_FILE="$3"
+
+stage() {
+    if [ -n "$PROGRESS_FILE" ]; then
+        echo "$1" >> "$PROGRESS_FILE"
+    fi
+}
+
 INSTALL_DIR="/usr/local/bin/my-little-apps"
 CONFIG_DIR="/usr/local/etc/my-little-apps"
 LOG_DIR="/usr/local/var/log/my-little-apps"
+RUN_DIR="/usr/local/var/run/my-little-apps"
 LAUNCH_DAEMONS_DIR="/Library/LaunchDaemons"
 
 echo "Installing My Little Apps proxy service..."
@@ -24,11 +36,18 @@ echo "Resource dir: $RESOURCE_DIR"
 ARCH=$(uname -m)
 echo "Architecture: $ARCH"
 
+stage "creating_directories"
 echo "Creating directories..."
 mkdir -p "$INSTALL_DIR"
 mkdir -p "$CONFIG_DIR"
 mkdir -p "$LOG_DIR"
 
+echo "Creating admin socket directory..."
+mkdir -p "$RUN_DIR"
+chown "$ADMIN_USER" "$RUN_DIR"
+chmod 700 "$RUN_DIR"
+
+stage "copying_binaries"
 echo "Copying Caddy binary..."
 if [ "$ARCH" = "arm64" ]; then
     cp "$RESOURCE_DIR/caddy-darwin-arm64" "$INSTALL_DIR/caddy"
@@ -37,11 +56,12 @@ else
 fi
 chmod +x "$INSTALL_DIR/caddy"
 
+stage "writing_config"
 echo "Creating Caddyfile..."
-cat > "$CONFIG_DIR/Caddyfile" << 'EOF'
+cat > "$CONFIG_DIR/Caddyfile" << EOF
 {
     auto_https off
-    admin localhost:2019
+    admin unix/$RUN_DIR/caddy-admin.sock|0666
 }
 
 :80 {
@@ -49,10 +69,12 @@ cat > "$CONFIG_DIR/Caddyfile" << 'EOF'
 }
 EOF
 
+stage "writing_plists"
 echo "Installing Caddy LaunchDaemon..."
 cp "$RESOURCE_DIR/com.my-little-apps.caddy.plist" \
     "$LAUNCH_DAEMONS_DIR/com.my-little-apps.caddy.plist"
 
+stage "loading_daemons"
 echo "Loading LaunchDaemon..."
 launchctl unload "$LAUNCH_DAEMONS_DIR/com.my-little-apps.caddy.plist" 2>/dev/null || true
 launchctl load "$LAUNCH_DAEMONS_DIR/com.my-little-apps.caddy.plist"
@@ -10,7 +10,10 @@ set -e
 INSTALL_DIR="/usr/local/bin/my-little-apps"
 CONFIG_DIR="/usr/local/etc/my-little-apps"
 LOG_DIR="/usr/local/var/log/my-little-apps"
+RUN_DIR="/usr/local/var/run/my-little-apps"
 LAUNCH_DAEMONS_DIR="/Library/LaunchDaemons"
+# Optional domain whose /etc/resolver entry points at the built-in DNS responder
+RESOLVER_DOMAIN="$1"
 
 echo "Uninstalling My Little Apps proxy service..."
 
@@ -29,6 +32,14 @@ rm -rf "$CONFIG_DIR"
 echo "Removing logs..."
 rm -rf "$LOG_DIR"
 
+echo "Removing admin socket..."
+rm -rf "$RUN_DIR"
+
+if [ -n "$RESOLVER_DOMAIN" ]; then
+    echo "Removing resolver for $RESOLVER_DOMAIN..."
+    rm -f "/etc/resolver/$RESOLVER_DOMAIN"
+fi
+
 echo ""
 echo "========================================"
 echo "Uninstallation complete!"
@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const FILE: &str = "last-accessed.json";
+
+/// Where the managed Caddy writes its access log, next to its stdout and stderr logs.
+pub const CADDY_ACCESS_LOG: &str = "/usr/local/var/log/my-little-apps/access.log";
+
+// Most of the log read per pass; a backlog is worked through over the next passes
+const MAX_READ: u64 = 4 * 1024 * 1024;
+
+// The fields of a Caddy access log entry we read
+#[derive(Deserialize)]
+struct Entry {
+    ts: f64,
+    request: EntryRequest,
+}
+
+#[derive(Deserialize)]
+struct EntryRequest {
+    host: String,
+}
+
+/// The Host and time (unix ms) of an access log line; None for anything else.
+pub fn parse_line(line: &str) -> Option<(String, i64)> {
+    let entry: Entry = serde_json::from_str(line).ok()?;
+    Some((entry.request.host, (entry.ts * 1000.0) as i64))
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Saved {
+    // Unix ms of the last request, by app id
+    apps: HashMap<String, i64>,
+    // Bytes of the Caddy access log already read
+    log_offset: u64,
+}
+
+/// When each app last served a request through the proxy, kept across restarts.
+pub struct AccessTracker {
+    path: Option<PathBuf>,
+    saved: Mutex<Saved>,
+}
+
+impl AccessTracker {
+    pub fn with_data_dir(dir: PathBuf) -> Self {
+        let path = dir.join(FILE);
+        let saved = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            path: Some(path),
+            saved: Mutex::new(saved),
+        }
+    }
+
+    pub fn last_accessed(&self) -> HashMap<String, i64> {
+        self.saved
+            .lock()
+            .map(|saved| saved.apps.clone())
+            .unwrap_or_default()
+    }
+
+    /// Records requests by app id, returning the apps whose last access moved forward.
+    pub fn record(&self, accesses: impl IntoIterator<Item = (String, i64)>) -> Vec<(String, i64)> {
+        let mut latest: HashMap<String, i64> = HashMap::new();
+        for (app_id, at) in accesses {
+            let entry = latest.entry(app_id).or_insert(at);
+            *entry = (*entry).max(at);
+        }
+        let Ok(mut saved) = self.saved.lock() else {
+            return Vec::new();
+        };
+        let mut changed = Vec::new();
+        for (app_id, at) in latest {
+            if saved.apps.get(&app_id).is_none_or(|last| *last < at) {
+                saved.apps.insert(app_id.clone(), at);
+                changed.push((app_id, at));
+            }
+        }
+        if !changed.is_empty() {
+            self.save(&saved);
+        }
+        changed
+    }
+
+    pub fn forget(&self, app_id: &str) {
+        if let Ok(mut saved) = self.saved.lock() {
+            if saved.apps.remove(app_id).is_some() {
+                self.save(&saved);
+            }
+        }
+    }
+
+    /// Hosts and times of the requests Caddy logged since the last call.
+    pub fn read_caddy_log(&self, log: &Path) -> Vec<(String, i64)> {
+        let Ok(mut saved) = self.saved.lock() else {
+            return Vec::new();
+        };
+        let (chunk, next) = match read_from(log, saved.log_offset) {
+            Ok(read) => read,
+            // Not there until Caddy serves its first request
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+            Err(e) => {
+                log::warn!("Failed to read the proxy access log: {}", e);
+                return Vec::new();
+            }
+        };
+        if next != saved.log_offset {
+            saved.log_offset = next;
+            self.save(&saved);
+        }
+        String::from_utf8_lossy(&chunk)
+            .lines()
+            .filter_map(parse_line)
+            .collect()
+    }
+
+    fn save(&self, saved: &Saved) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        match serde_json::to_string(saved) {
+            Ok(content) => {
+                if let Err(e) = std::fs::write(path, content) {
+                    log::error!("Failed to save last accessed times: {}", e);
+                }
+            }
+            Err(e) => log::error!("Failed to serialize last accessed times: {}", e),
+        }
+    }
+}
+
+impl Default for AccessTracker {
+    fn default() -> Self {
+        Self {
+            path: None,
+            saved: Mutex::new(Saved::default()),
+        }
+    }
+}
+
+// Complete lines from `offset` on and the offset after them. A file shorter than the
+// offset was rotated, so it's read from the start.
+fn read_from(path: &Path, offset: u64) -> std::io::Result<(Vec<u8>, u64)> {
+    let mut file = File::open(path)?;
+    let start = if file.metadata()?.len() < offset {
+        0
+    } else {
+        offset
+    };
+    file.seek(SeekFrom::Start(start))?;
+    let mut chunk = Vec::new();
+    file.take(MAX_READ).read_to_end(&mut chunk)?;
+    // A line Caddy is still writing is left for the next read, unless it alone fills a read
+    let complete = match chunk.iter().rposition(|&b| b == b'\n') {
+        Some(end) => end + 1,
+        None if chunk.len() as u64 == MAX_READ => chunk.len(),
+        None => 0,
+    };
+    chunk.truncate(complete);
+    Ok((chunk, start + complete as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        let line = r#"{"level":"info","ts":1760000000.5,"logger":"http.log.access","request":{"remote_ip":"127.0.0.1","host":"shop.local","method":"GET","uri":"/"},"status":200}"#;
+        assert_eq!(
+            parse_line(line),
+            Some(("shop.local".to_string(), 1_760_000_000_500))
+        );
+        assert_eq!(parse_line(r#"{"level":"info","msg":"serving"}"#), None);
+        assert_eq!(parse_line("not json"), None);
+    }
+
+    #[test]
+    fn test_record_keeps_latest() {
+        let tracker = AccessTracker::default();
+        let changed = tracker.record([
+            ("app1".to_string(), 2000),
+            ("app1".to_string(), 1000),
+            ("app2".to_string(), 500),
+        ]);
+        assert_eq!(changed.len(), 2);
+        assert_eq!(tracker.last_accessed()["app1"], 2000);
+
+        assert!(tracker.record([("app1".to_string(), 1500)]).is_empty());
+        assert_eq!(
+            tracker.record([("app1".to_string(), 3000)]),
+            [("app1".to_string(), 3000)]
+        );
+        tracker.forget("app2");
+        assert!(!tracker.last_accessed().contains_key("app2"));
+    }
+
+    #[test]
+    fn test_read_caddy_log() {
+        let dir = std::env::temp_dir().join(format!("mla-access-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let log = dir.join("access.log");
+        let entry =
+            |host: &str, ts: u32| format!(r#"{{"ts":{},"request":{{"host":"{}"}}}}"#, ts, host);
+        let tracker = AccessTracker::default();
+        assert!(tracker.read_caddy_log(&log).is_empty());
+
+        // The unfinished last line waits for the next read
+        std::fs::write(&log, format!("{}\n{}", entry("a.local", 1), "{\"ts\":2")).unwrap();
+        assert_eq!(
+            tracker.read_caddy_log(&log),
+            [("a.local".to_string(), 1000)]
+        );
+        assert!(tracker.read_caddy_log(&log).is_empty());
+
+        // Rotated: the new file is shorter than what was already read
+        std::fs::write(&log, format!("{}\n", entry("b", 3))).unwrap();
+        assert_eq!(tracker.read_caddy_log(&log), [("b".to_string(), 3000)]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
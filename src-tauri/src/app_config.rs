@@ -0,0 +1,299 @@
+use serde::Serialize;
+use std::path::Path;
+use std::time::Duration;
+
+// Interactive login shells can be slow to source (nvm, conda); don't block saving forever
+const RESOLVE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ConfigIssue {
+    /// "path", "command", "build_command", "prerequisites", "path_entries" or "shell"
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    fn new(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            message: message.into(),
+        }
+    }
+}
+
+/// Shells an app can pick to run in instead of the login shell.
+pub const SHELLS: [&str; 3] = ["zsh", "bash", "sh"];
+
+/// Flags a shell pick that isn't one of `SHELLS`; None is the login shell.
+pub fn check_shell(shell: Option<&str>) -> Option<ConfigIssue> {
+    let shell = shell.filter(|shell| !SHELLS.contains(shell))?;
+    Some(ConfigIssue::new(
+        "shell",
+        format!("{} is not one of {}", shell, SHELLS.join(", ")),
+    ))
+}
+
+/// Checks an app's path and command before it is saved. An empty list means it looks runnable.
+#[allow(clippy::too_many_arguments)]
+pub async fn validate(
+    path: &str,
+    command: &str,
+    build_command: Option<&str>,
+    prerequisites: Option<&str>,
+    path_entries: Option<&str>,
+    devcontainer: bool,
+    shell: &str,
+    shell_args: &[&str],
+) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    let dir = Path::new(path);
+    if !dir.is_dir() {
+        issues.push(ConfigIssue::new(
+            "path",
+            format!("{} is not a directory", path),
+        ));
+    }
+
+    for message in crate::prerequisites::invalid(prerequisites.unwrap_or_default()) {
+        issues.push(ConfigIssue::new("prerequisites", message));
+    }
+    for message in crate::path_entries::invalid(path_entries.unwrap_or_default()) {
+        issues.push(ConfigIssue::new("path_entries", message));
+    }
+    let extra_path = crate::path_entries::resolve(path_entries.unwrap_or_default(), dir);
+
+    let Some(executable) = command_executable(command) else {
+        issues.push(ConfigIssue::new("command", "Command is empty"));
+        return issues;
+    };
+
+    let build_command = build_command.filter(|c| !c.trim().is_empty());
+    if devcontainer {
+        // Executables live in the container image, so only the config itself can be checked
+        if let Err(e) = crate::devcontainer::load(dir) {
+            issues.push(ConfigIssue::new("command", e));
+        }
+    } else {
+        if let Some(build_executable) = build_command.and_then(command_executable) {
+            issues.extend(
+                check_executable(
+                    "build_command",
+                    build_executable,
+                    dir,
+                    &extra_path,
+                    shell,
+                    shell_args,
+                )
+                .await,
+            );
+        }
+
+        // A binary the build step produces doesn't have to exist yet
+        let built_path = build_command.is_some() && executable.contains('/');
+        if !built_path {
+            issues.extend(
+                check_executable("command", executable, dir, &extra_path, shell, shell_args).await,
+            );
+        }
+    }
+
+    if let Some(script) = package_script(command) {
+        if let Some(message) = check_package_script(dir, &script, command) {
+            issues.push(ConfigIssue::new("command", message));
+        }
+    }
+
+    issues
+}
+
+async fn check_executable(
+    field: &'static str,
+    executable: &str,
+    dir: &Path,
+    extra_path: &[String],
+    shell: &str,
+    shell_args: &[&str],
+) -> Option<ConfigIssue> {
+    if executable.contains('/') {
+        return (!dir.join(executable).exists())
+            .then(|| ConfigIssue::new(field, format!("{} does not exist", executable)));
+    }
+
+    // Tools installed into the project's virtualenv are put on PATH at launch
+    let in_venv =
+        crate::python::venv_dir(dir).is_some_and(|venv| venv.join("bin").join(executable).exists());
+    if in_venv {
+        return None;
+    }
+    // So are the app's own PATH entries
+    let on_extra_path = extra_path
+        .iter()
+        .any(|entry| Path::new(entry).join(executable).exists());
+    if on_extra_path {
+        return None;
+    }
+
+    match resolve_executable(executable, dir, shell, shell_args).await {
+        Ok(true) => None,
+        Ok(false) => Some(ConfigIssue::new(
+            field,
+            format!("{} was not found on PATH", executable),
+        )),
+        Err(e) => {
+            log::warn!("Could not resolve {}: {}", executable, e);
+            None
+        }
+    }
+}
+
+// First word of the command, skipping `FOO=bar` environment assignments
+fn command_executable(command: &str) -> Option<&str> {
+    command
+        .split_whitespace()
+        .find(|token| !is_env_assignment(token))
+}
+
+fn is_env_assignment(token: &str) -> bool {
+    token.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty()
+            && !name.starts_with(|c: char| c.is_ascii_digit())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+// Script a package manager invocation runs, e.g. "bun run dev" -> "dev", "npm start" -> "start"
+fn package_script(command: &str) -> Option<String> {
+    // Only the first command of a chain like `npm run build && npm start` is checked
+    let first = command.split(['&', '|', ';']).next()?;
+    let mut tokens = first
+        .split_whitespace()
+        .skip_while(|token| is_env_assignment(token));
+
+    if !matches!(tokens.next()?, "bun" | "npm" | "pnpm" | "yarn") {
+        return None;
+    }
+    match tokens.next()? {
+        "run" | "run-script" => tokens
+            .find(|token| !token.starts_with('-'))
+            .map(String::from),
+        script @ ("start" | "dev") => Some(script.to_string()),
+        _ => None,
+    }
+}
+
+fn check_package_script(dir: &Path, script: &str, command: &str) -> Option<String> {
+    let content = match std::fs::read_to_string(dir.join("package.json")) {
+        Ok(content) => content,
+        Err(_) => return Some(format!("No package.json found for script \"{}\"", script)),
+    };
+    let package: serde_json::Value = match serde_json::from_str(&content) {
+        Ok(package) => package,
+        Err(e) => return Some(format!("Failed to parse package.json: {}", e)),
+    };
+
+    if package
+        .get("scripts")
+        .and_then(|scripts| scripts.get(script))
+        .is_some()
+    {
+        return None;
+    }
+    // `npm start` falls back to `node server.js` without a start script
+    if script == "start" && command.contains("npm") && dir.join("server.js").exists() {
+        return None;
+    }
+
+    let mut available: Vec<&str> = package
+        .get("scripts")
+        .and_then(|s| s.as_object())
+        .map(|scripts| scripts.keys().map(String::as_str).collect())
+        .unwrap_or_default();
+    available.sort_unstable();
+    Some(if available.is_empty() {
+        format!("package.json has no \"{}\" script", script)
+    } else {
+        format!(
+            "package.json has no \"{}\" script (available: {})",
+            script,
+            available.join(", ")
+        )
+    })
+}
+
+// Asks the same login shell apps start in, so PATH additions from shell profiles count
+async fn resolve_executable(
+    executable: &str,
+    dir: &Path,
+    shell: &str,
+    shell_args: &[&str],
+) -> Result<bool, String> {
+    let mut cmd = tokio::process::Command::new(shell);
+    cmd.args(shell_args)
+        .arg(r#"command -v -- "$MY_APP_EXE""#)
+        .env("MY_APP_EXE", executable)
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true);
+    if dir.is_dir() {
+        cmd.current_dir(dir);
+    }
+
+    let output = tokio::time::timeout(RESOLVE_TIMEOUT, cmd.output())
+        .await
+        .map_err(|_| "Timed out waiting for the login shell".to_string())?
+        .map_err(|e| format!("Failed to run {}: {}", shell, e))?;
+    Ok(output.status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_executable() {
+        assert_eq!(command_executable("bun start"), Some("bun"));
+        assert_eq!(
+            command_executable("NODE_ENV=dev PORT=1 ./bin/server"),
+            Some("./bin/server")
+        );
+        assert_eq!(command_executable("   "), None);
+    }
+
+    #[test]
+    fn test_check_shell() {
+        assert_eq!(check_shell(None), None);
+        assert_eq!(check_shell(Some("bash")), None);
+        assert_eq!(check_shell(Some("fish")).unwrap().field, "shell");
+    }
+
+    #[test]
+    fn test_package_script() {
+        assert_eq!(package_script("bun run dev"), Some("dev".to_string()));
+        assert_eq!(
+            package_script("npm run --silent build && npm start"),
+            Some("build".to_string())
+        );
+        assert_eq!(package_script("bun start"), Some("start".to_string()));
+        assert_eq!(package_script("FOO=1 pnpm dev"), Some("dev".to_string()));
+        assert_eq!(package_script("bun install"), None);
+        assert_eq!(package_script("cargo run"), None);
+    }
+
+    #[test]
+    fn test_check_package_script() {
+        let dir = std::env::temp_dir().join(format!("mla-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("package.json"),
+            r#"{"scripts": {"dev": "vite", "build": "vite build"}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(check_package_script(&dir, "dev", "bun run dev"), None);
+        assert_eq!(
+            check_package_script(&dir, "serve", "bun run serve"),
+            Some("package.json has no \"serve\" script (available: build, dev)".to_string())
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
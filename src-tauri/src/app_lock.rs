@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::error::AppError;
+
+// LocalAuthentication through JXA's Objective-C bridge, so no native bindings are needed.
+// Policy 2 is LAPolicyDeviceOwnerAuthentication: Touch ID, falling back to the login password
+const SYSTEM_AUTH_SCRIPT: &str = r#"
+function run(argv) {
+    ObjC.import("LocalAuthentication");
+    const context = $.LAContext.alloc.init;
+    let result = null;
+    context.evaluatePolicyLocalizedReasonReply(2, argv[0], (success, error) => {
+        result = success ? "ok" : ObjC.unwrap(error.localizedDescription);
+    });
+    while (result === null) {
+        $.NSRunLoop.currentRunLoop.runUntilDate($.NSDate.dateWithTimeIntervalSinceNow(0.1));
+    }
+    return result;
+}
+"#;
+
+/// Optional lock in front of destructive actions, for a machine left running a demo.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppLock {
+    pub enabled: bool,
+    // "salt:sha256" of the passcode; None authenticates with Touch ID or the login password
+    pub passcode: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LockMethod {
+    System,
+    Passcode,
+}
+
+/// What the frontend may know about the lock; the passcode hash stays in the backend.
+#[derive(Debug, Clone, Serialize)]
+pub struct AppLockStatus {
+    pub enabled: bool,
+    pub method: LockMethod,
+}
+
+impl AppLock {
+    /// An enabled lock; an empty or missing passcode means system authentication.
+    pub fn new(passcode: Option<&str>) -> Self {
+        let passcode = passcode.filter(|p| !p.is_empty()).map(|passcode| {
+            let salt = Uuid::new_v4().simple().to_string();
+            format!("{}:{}", salt, hash(&salt, passcode))
+        });
+        Self {
+            enabled: true,
+            passcode,
+        }
+    }
+
+    pub fn status(&self) -> AppLockStatus {
+        AppLockStatus {
+            enabled: self.enabled,
+            method: match self.passcode {
+                Some(_) => LockMethod::Passcode,
+                None => LockMethod::System,
+            },
+        }
+    }
+
+    /// Succeeds right away when the lock is off; `reason` is shown in the Touch ID prompt.
+    pub async fn authenticate(&self, reason: &str, passcode: Option<&str>) -> Result<(), AppError> {
+        if !self.enabled {
+            return Ok(());
+        }
+        match &self.passcode {
+            Some(stored) => {
+                if passcode.is_some_and(|passcode| matches(stored, passcode)) {
+                    Ok(())
+                } else {
+                    Err(AppError::permission_denied("Wrong passcode"))
+                }
+            }
+            None => authenticate_with_system(reason).await,
+        }
+    }
+}
+
+fn hash(salt: &str, passcode: &str) -> String {
+    format!("{:x}", Sha256::digest(format!("{}{}", salt, passcode)))
+}
+
+fn matches(stored: &str, passcode: &str) -> bool {
+    stored
+        .split_once(':')
+        .is_some_and(|(salt, expected)| hash(salt, passcode) == expected)
+}
+
+async fn authenticate_with_system(reason: &str) -> Result<(), AppError> {
+    let output = tokio::process::Command::new("osascript")
+        .args(["-l", "JavaScript", "-e", SYSTEM_AUTH_SCRIPT, reason])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run osascript: {}", e))?;
+    let result = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if output.status.success() && result == "ok" {
+        return Ok(());
+    }
+    let message = if result.is_empty() {
+        String::from_utf8_lossy(&output.stderr).trim().to_string()
+    } else {
+        result
+    };
+    Err(AppError::permission_denied(format!(
+        "Authentication failed: {}",
+        message
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_passcode() {
+        let lock = AppLock::new(Some("1234"));
+        let stored = lock.passcode.clone().unwrap();
+        assert!(!stored.contains("1234"));
+        assert!(matches(&stored, "1234"));
+        assert!(!matches(&stored, "4321"));
+        assert_eq!(lock.status().method, LockMethod::Passcode);
+
+        // Same passcode, different salt
+        assert_ne!(AppLock::new(Some("1234")).passcode.unwrap(), stored);
+        assert_eq!(AppLock::new(Some("")).status().method, LockMethod::System);
+    }
+}
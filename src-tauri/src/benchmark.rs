@@ -0,0 +1,176 @@
+use bytes::Bytes;
+use http_body_util::{BodyExt, Empty};
+use hyper::client::conn::http1::SendRequest;
+use hyper::Request;
+use hyper_util::rt::TokioIo;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+
+pub const MAX_DURATION_SECS: u64 = 60;
+pub const MAX_CONCURRENCY: usize = 256;
+
+// A single request slower than this counts as an error rather than stalling its worker
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct Latency {
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+/// Result of a load test; latencies are in milliseconds.
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkReport {
+    pub requests: u64,
+    /// Failed connections, timeouts and 5xx responses
+    pub errors: u64,
+    pub status_codes: BTreeMap<u16, u64>,
+    pub duration_ms: u64,
+    pub requests_per_sec: f64,
+    pub latency: Latency,
+}
+
+#[derive(Default)]
+struct WorkerResult {
+    latencies: Vec<Duration>,
+    status_codes: BTreeMap<u16, u64>,
+    errors: u64,
+}
+
+/// Sends `GET /` to the app on `port` from `concurrency` keep-alive connections for `duration`.
+pub async fn run(
+    port: i32,
+    duration: Duration,
+    concurrency: usize,
+) -> Result<BenchmarkReport, String> {
+    // Fail fast when nothing listens instead of reporting a run made only of errors
+    connect(port).await?;
+
+    let started = Instant::now();
+    let deadline = started + duration;
+    let workers: Vec<_> = (0..concurrency)
+        .map(|_| tauri::async_runtime::spawn(worker(port, deadline)))
+        .collect();
+
+    let mut total = WorkerResult::default();
+    for handle in workers {
+        let result = handle
+            .await
+            .map_err(|e| format!("Benchmark worker failed: {}", e))?;
+        total.latencies.extend(result.latencies);
+        total.errors += result.errors;
+        for (status, count) in result.status_codes {
+            *total.status_codes.entry(status).or_default() += count;
+        }
+    }
+    let elapsed = started.elapsed();
+
+    let requests = total.latencies.len() as u64;
+    Ok(BenchmarkReport {
+        requests,
+        errors: total.errors,
+        status_codes: total.status_codes,
+        duration_ms: elapsed.as_millis() as u64,
+        requests_per_sec: requests as f64 / elapsed.as_secs_f64().max(0.001),
+        latency: percentiles(total.latencies),
+    })
+}
+
+async fn connect(port: i32) -> Result<SendRequest<Empty<Bytes>>, String> {
+    let stream = TcpStream::connect(format!("127.0.0.1:{}", port))
+        .await
+        .map_err(|e| format!("Failed to connect to app on port {}: {}", port, e))?;
+    let (sender, conn) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+        .await
+        .map_err(|e| format!("Failed to talk to app on port {}: {}", port, e))?;
+    tauri::async_runtime::spawn(async move {
+        let _ = conn.await;
+    });
+    Ok(sender)
+}
+
+async fn worker(port: i32, deadline: Instant) -> WorkerResult {
+    let mut result = WorkerResult::default();
+    let mut sender = None;
+
+    while Instant::now() < deadline {
+        let mut current = match sender.take() {
+            Some(current) => current,
+            None => match connect(port).await {
+                Ok(current) => current,
+                Err(_) => {
+                    result.errors += 1;
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    continue;
+                }
+            },
+        };
+
+        let request = Request::get("/")
+            .header(hyper::header::HOST, format!("localhost:{}", port))
+            .body(Empty::new())
+            .expect("static request parts are valid");
+        let started = Instant::now();
+        let exchange = async {
+            let response = current.send_request(request).await?;
+            let status = response.status().as_u16();
+            response.into_body().collect().await?;
+            Ok::<_, hyper::Error>(status)
+        };
+
+        match tokio::time::timeout(REQUEST_TIMEOUT, exchange).await {
+            Ok(Ok(status)) => {
+                result.latencies.push(started.elapsed());
+                *result.status_codes.entry(status).or_default() += 1;
+                if status >= 500 {
+                    result.errors += 1;
+                }
+                sender = Some(current);
+            }
+            // The connection is dropped and the next request reconnects
+            _ => result.errors += 1,
+        }
+    }
+    result
+}
+
+fn percentiles(mut latencies: Vec<Duration>) -> Latency {
+    if latencies.is_empty() {
+        return Latency::default();
+    }
+    latencies.sort_unstable();
+    let at = |quantile: f64| {
+        let index = ((latencies.len() as f64 * quantile).ceil() as usize).saturating_sub(1);
+        latencies[index.min(latencies.len() - 1)].as_secs_f64() * 1000.0
+    };
+    Latency {
+        p50: at(0.5),
+        p90: at(0.9),
+        p99: at(0.99),
+        max: at(1.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles() {
+        let latencies = (1..=100).rev().map(Duration::from_millis).collect();
+        assert_eq!(
+            percentiles(latencies),
+            Latency {
+                p50: 50.0,
+                p90: 90.0,
+                p99: 99.0,
+                max: 100.0
+            }
+        );
+        assert_eq!(percentiles(Vec::new()), Latency::default());
+    }
+}
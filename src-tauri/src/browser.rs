@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Browser {
+    #[default]
+    Default,
+    Chrome,
+    Firefox,
+    Safari,
+    Arc,
+}
+
+impl Browser {
+    fn app_name(self) -> Option<&'static str> {
+        match self {
+            Browser::Default => None,
+            Browser::Chrome => Some("Google Chrome"),
+            Browser::Firefox => Some("Firefox"),
+            Browser::Safari => Some("Safari"),
+            Browser::Arc => Some("Arc"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct BrowserPreference {
+    pub browser: Browser,
+    pub private: bool,
+}
+
+/// Opens `url` in the chosen browser, in a private/incognito window when asked.
+pub fn open_url(url: &str, preference: BrowserPreference) -> Result<(), String> {
+    let BrowserPreference { browser, private } = preference;
+
+    if browser == Browser::Arc && private {
+        // Arc has no command line flag for this but exposes it through AppleScript
+        let script = format!(
+            r#"tell application "Arc"
+    make new window with properties {{incognito:true}}
+    tell front window to make new tab with properties {{URL:"{}"}}
+    activate
+end tell"#,
+            url.replace('"', "%22")
+        );
+        return run("osascript", &["-e".to_string(), script]);
+    }
+
+    if !cfg!(target_os = "macos") {
+        return match browser.app_name() {
+            Some(app) => open::with(url, app),
+            None => open::that(url),
+        }
+        .map_err(|e| format!("Failed to open browser: {}", e));
+    }
+
+    run("open", &open_args(url, preference)?)
+}
+
+// Arguments for macOS `open`; private windows need a fresh instance (-n) to receive the flag
+fn open_args(url: &str, preference: BrowserPreference) -> Result<Vec<String>, String> {
+    let BrowserPreference { browser, private } = preference;
+    let app = browser.app_name();
+
+    let private_flag = match (private, browser) {
+        (false, _) => None,
+        (true, Browser::Chrome) => Some("--incognito"),
+        (true, Browser::Firefox) => Some("--private-window"),
+        (true, Browser::Default) => {
+            return Err("Choose a specific browser to open private windows".to_string())
+        }
+        (true, Browser::Safari) => {
+            return Err("Safari can't open private windows from other apps".to_string())
+        }
+        (true, Browser::Arc) => None,
+    };
+
+    let mut args = Vec::new();
+    if let Some(app) = app {
+        if private_flag.is_some() {
+            args.push("-n".to_string());
+        }
+        args.push("-a".to_string());
+        args.push(app.to_string());
+    }
+    match private_flag {
+        Some(flag) => args.extend(["--args".to_string(), flag.to_string(), url.to_string()]),
+        None => args.push(url.to_string()),
+    }
+    Ok(args)
+}
+
+fn run(program: &str, args: &[String]) -> Result<(), String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to open browser: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to open browser: {}", stderr.trim()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preference(browser: Browser, private: bool) -> BrowserPreference {
+        BrowserPreference { browser, private }
+    }
+
+    #[test]
+    fn test_open_args() {
+        let url = "http://my-app.local";
+        assert_eq!(
+            open_args(url, preference(Browser::Default, false)).unwrap(),
+            vec![url]
+        );
+        assert_eq!(
+            open_args(url, preference(Browser::Safari, false)).unwrap(),
+            vec!["-a", "Safari", url]
+        );
+        assert_eq!(
+            open_args(url, preference(Browser::Chrome, true)).unwrap(),
+            vec!["-n", "-a", "Google Chrome", "--args", "--incognito", url]
+        );
+        assert_eq!(
+            open_args(url, preference(Browser::Firefox, true)).unwrap(),
+            vec!["-n", "-a", "Firefox", "--args", "--private-window", url]
+        );
+    }
+
+    #[test]
+    fn test_private_unsupported() {
+        let url = "http://my-app.local";
+        assert!(open_args(url, preference(Browser::Default, true)).is_err());
+        assert!(open_args(url, preference(Browser::Safari, true)).is_err());
+    }
+}
@@ -0,0 +1,616 @@
+use bytes::Bytes;
+use http_body_util::{BodyExt, Empty};
+use hyper::body::Incoming;
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{oneshot, Mutex};
+
+use crate::health;
+use crate::inspector::{full_body, strip_hop_by_hop, RelayBody};
+use crate::ip_allowlist;
+use crate::proxy::{ForwardHeaders, MockResponse, ProxyRoute, StaticSite, UpstreamProtocol};
+
+// Routes keyed by subdomain, read on every request
+type SharedRoutes = Arc<RwLock<HashMap<String, ProxyRoute>>>;
+// Unix ms of the latest request by subdomain, since the last take_accessed
+type SharedAccesses = Arc<std::sync::Mutex<HashMap<String, i64>>>;
+
+struct Server {
+    port: u16,
+    shutdown: oneshot::Sender<()>,
+}
+
+/// In-process alternative to Caddy serving the same `<subdomain>.local` routing.
+pub struct BuiltinProxy {
+    routes: SharedRoutes,
+    accessed: SharedAccesses,
+    server: Mutex<Option<Server>>,
+}
+
+impl BuiltinProxy {
+    pub fn new() -> Self {
+        Self {
+            routes: Arc::new(RwLock::new(HashMap::new())),
+            accessed: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            server: Mutex::new(None),
+        }
+    }
+
+    pub async fn start(&self, port: u16) -> Result<(), String> {
+        let mut server = self.server.lock().await;
+        if server.as_ref().is_some_and(|s| s.port == port) {
+            return Ok(());
+        }
+        if let Some(running) = server.take() {
+            let _ = running.shutdown.send(());
+        }
+
+        let listener = TcpListener::bind(("0.0.0.0", port)).await.map_err(|e| {
+            format!(
+                "Failed to start built-in proxy on port {} (is Caddy still running?): {}",
+                port, e
+            )
+        })?;
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        tauri::async_runtime::spawn(run_server(
+            listener,
+            self.routes.clone(),
+            self.accessed.clone(),
+            shutdown_rx,
+        ));
+
+        *server = Some(Server {
+            port,
+            shutdown: shutdown_tx,
+        });
+        log::info!("Built-in proxy listening on port {}", port);
+        Ok(())
+    }
+
+    pub async fn stop(&self) {
+        if let Some(server) = self.server.lock().await.take() {
+            let _ = server.shutdown.send(());
+        }
+    }
+
+    pub async fn running_port(&self) -> Option<u16> {
+        self.server.lock().await.as_ref().map(|s| s.port)
+    }
+
+    /// When each subdomain last got a request, since the previous call.
+    pub fn take_accessed(&self) -> HashMap<String, i64> {
+        self.accessed
+            .lock()
+            .map(|mut accessed| std::mem::take(&mut *accessed))
+            .unwrap_or_default()
+    }
+
+    pub fn set_routes(&self, routes: &HashMap<String, ProxyRoute>) {
+        if let Ok(mut current) = self.routes.write() {
+            *current = routes
+                .values()
+                .filter(|r| !r.disabled)
+                .map(|r| (r.subdomain.clone(), r.clone()))
+                .collect();
+        }
+    }
+}
+
+impl Default for BuiltinProxy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+async fn run_server(
+    listener: TcpListener,
+    routes: SharedRoutes,
+    accessed: SharedAccesses,
+    mut shutdown: oneshot::Receiver<()>,
+) {
+    loop {
+        let (stream, remote) = tokio::select! {
+            _ = &mut shutdown => break,
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    log::error!("Built-in proxy accept failed: {}", e);
+                    continue;
+                }
+            },
+        };
+
+        let (routes, accessed) = (routes.clone(), accessed.clone());
+        tauri::async_runtime::spawn(async move {
+            let service = hyper::service::service_fn(move |req| {
+                handle(req, remote, routes.clone(), accessed.clone())
+            });
+            let _ = hyper::server::conn::http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .with_upgrades()
+                .await;
+        });
+    }
+}
+
+async fn handle(
+    mut req: Request<Incoming>,
+    remote: SocketAddr,
+    routes: SharedRoutes,
+    accessed: SharedAccesses,
+) -> Result<Response<RelayBody>, hyper::Error> {
+    let host = req
+        .headers()
+        .get(hyper::header::HOST)
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+
+    let (route, no_routes) = match routes.read() {
+        Ok(routes) => (find_route(&routes, &host), routes.is_empty()),
+        Err(_) => (None, false),
+    };
+
+    let Some(route) = route else {
+        return Ok(if no_routes {
+            text_response(
+                StatusCode::OK,
+                "My Little Apps proxy is running. No apps configured yet.".to_string(),
+            )
+        } else {
+            text_response(
+                StatusCode::NOT_FOUND,
+                format!("No app is configured for {}", host),
+            )
+        });
+    };
+
+    if !ip_allowlist::allows(&route.allowed_ips, remote.ip()) {
+        return Ok(text_response(
+            StatusCode::FORBIDDEN,
+            "Forbidden".to_string(),
+        ));
+    }
+    if let Ok(mut accessed) = accessed.lock() {
+        accessed.insert(route.subdomain.clone(), health::now_ms());
+    }
+
+    if body_too_large(req.headers(), route.max_body_bytes) {
+        return Ok(text_response(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "Request body too large".to_string(),
+        ));
+    }
+
+    if route.mocks_enabled {
+        if let Some(mock) = find_mock(&route.mocks, req.uri().path()) {
+            return Ok(mock_response(mock));
+        }
+    }
+
+    if let Some(site) = &route.static_site {
+        return Ok(serve_static(site, req.uri().path()).await);
+    }
+
+    if route.upstream != UpstreamProtocol::Http {
+        return Ok(text_response(
+            StatusCode::BAD_GATEWAY,
+            "The built-in proxy only speaks HTTP/1.1 to apps; use Caddy for h2c or HTTPS upstreams"
+                .to_string(),
+        ));
+    }
+
+    if req.headers().contains_key(hyper::header::UPGRADE) {
+        // WebSocket upgrades bypass the inspection relay, same as the Caddy config
+        set_forwarded_headers(req.headers_mut(), remote, &host, route.port, &route.headers);
+        return proxy_upgrade(req, route.port).await;
+    }
+
+    let port = route.relay_port.map(i32::from).unwrap_or(route.port);
+    strip_hop_by_hop(req.headers_mut());
+    set_forwarded_headers(req.headers_mut(), remote, &host, port, &route.headers);
+    proxy_request(req, port).await
+}
+
+async fn proxy_request(
+    req: Request<Incoming>,
+    port: i32,
+) -> Result<Response<RelayBody>, hyper::Error> {
+
+    let mut sender = match connect(port).await {
+        Ok(sender) => sender,
+        Err(e) => return Ok(text_response(StatusCode::BAD_GATEWAY, e)),
+    };
+
+    match sender.send_request(req).await {
+        Ok(response) => {
+            let (mut parts, body) = response.into_parts();
+            strip_hop_by_hop(&mut parts.headers);
+            Ok(Response::from_parts(parts, body.boxed()))
+        }
+        Err(e) => Ok(text_response(
+            StatusCode::BAD_GATEWAY,
+            format!("Request to app on port {} failed: {}", port, e),
+        )),
+    }
+}
+
+async fn proxy_upgrade(
+    mut req: Request<Incoming>,
+    port: i32,
+) -> Result<Response<RelayBody>, hyper::Error> {
+    let client_upgrade = hyper::upgrade::on(&mut req);
+
+    let mut sender = match connect(port).await {
+        Ok(sender) => sender,
+        Err(e) => return Ok(text_response(StatusCode::BAD_GATEWAY, e)),
+    };
+    let mut response = match sender.send_request(req).await {
+        Ok(response) => response,
+        Err(e) => {
+            return Ok(text_response(
+                StatusCode::BAD_GATEWAY,
+                format!("Upgrade request to app on port {} failed: {}", port, e),
+            ))
+        }
+    };
+
+    if response.status() != StatusCode::SWITCHING_PROTOCOLS {
+        return Ok(response.map(|body| body.boxed()));
+    }
+
+    let upstream_upgrade = hyper::upgrade::on(&mut response);
+    tauri::async_runtime::spawn(async move {
+        if let (Ok(client), Ok(upstream)) = (client_upgrade.await, upstream_upgrade.await) {
+            let _ = tokio::io::copy_bidirectional(
+                &mut TokioIo::new(client),
+                &mut TokioIo::new(upstream),
+            )
+            .await;
+        }
+    });
+
+    Ok(response.map(|_| Empty::new().map_err(|never| match never {}).boxed()))
+}
+
+async fn connect(port: i32) -> Result<hyper::client::conn::http1::SendRequest<Incoming>, String> {
+    let stream = TcpStream::connect(format!("127.0.0.1:{}", port))
+        .await
+        .map_err(|e| format!("Failed to connect to app on port {}: {}", port, e))?;
+
+    let (sender, conn) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+        .await
+        .map_err(|e| format!("Failed to talk to app on port {}: {}", port, e))?;
+
+    tauri::async_runtime::spawn(async move {
+        let _ = conn.with_upgrades().await;
+    });
+
+    Ok(sender)
+}
+
+// Same behavior as the Caddy config: file_server, plus try_files to index.html for SPAs
+async fn serve_static(site: &StaticSite, path: &str) -> Response<RelayBody> {
+    let Some(file) = resolve_static_path(Path::new(&site.root), path, site.spa) else {
+        return text_response(StatusCode::NOT_FOUND, format!("{} not found", path));
+    };
+
+    match tokio::fs::read(&file).await {
+        Ok(bytes) => Response::builder()
+            .header(hyper::header::CONTENT_TYPE, content_type(&file))
+            .body(full_body(Bytes::from(bytes)))
+            .unwrap_or_default(),
+        Err(e) => text_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to read {}: {}", file.display(), e),
+        ),
+    }
+}
+
+// Maps a request path onto a file under `root`, refusing anything that climbs out of it
+fn resolve_static_path(root: &Path, path: &str, spa: bool) -> Option<PathBuf> {
+    let mut file = root.to_path_buf();
+    for segment in percent_decode(path)?.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => return None,
+            segment => file.push(segment),
+        }
+    }
+
+    if file.is_dir() {
+        file.push("index.html");
+    }
+    if file.is_file() {
+        return Some(file);
+    }
+
+    let index = root.join("index.html");
+    (spa && index.is_file()).then_some(index)
+}
+
+fn percent_decode(path: &str) -> Option<String> {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+fn content_type(file: &Path) -> &'static str {
+    let extension = file
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "json" | "map" => "application/json",
+        "txt" => "text/plain; charset=utf-8",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "ico" => "image/x-icon",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "wasm" => "application/wasm",
+        _ => "application/octet-stream",
+    }
+}
+
+// Same header handling as the Caddy config generates for the route
+fn set_forwarded_headers(
+    headers: &mut hyper::HeaderMap,
+    remote: SocketAddr,
+    host: &str,
+    port: i32,
+    options: &ForwardHeaders,
+) {
+    let values = [
+        remote.ip().to_string(),
+        "http".to_string(),
+        host.to_string(),
+    ];
+    for ((name, sent), value) in options.forwarded().into_iter().zip(values) {
+        if !sent {
+            headers.remove(name);
+        } else if let Ok(value) = hyper::header::HeaderValue::from_str(&value) {
+            headers.insert(name, value);
+        }
+    }
+    if options.rewrite_host {
+        if let Ok(value) = hyper::header::HeaderValue::from_str(&format!("localhost:{}", port)) {
+            headers.insert(hyper::header::HOST, value);
+        }
+    }
+}
+
+// Goes by Content-Length, so chunked uploads aren't held to the limit
+fn body_too_large(headers: &hyper::HeaderMap, max_bytes: Option<u64>) -> bool {
+    let length = headers
+        .get(hyper::header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    matches!((length, max_bytes), (Some(length), Some(max)) if length > max)
+}
+
+// "my-app.local", "my-app.local:8080" or "my-app.macbook.local" -> "my-app"
+// Drops ".local", or the suffix the DNS responder falls back to when mDNS shadows it
+fn strip_domain_suffix(host: &str) -> Option<&str> {
+    let host = host.trim_end_matches('.');
+    let fallback = host
+        .strip_suffix(crate::dns::FALLBACK_TLD)
+        .and_then(|name| name.strip_suffix('.'));
+    host.strip_suffix(".local").or(fallback)
+}
+
+fn route_subdomain(host: &str) -> Option<&str> {
+    let host = host.split(':').next().unwrap_or_default();
+    let name = strip_domain_suffix(host)?;
+    name.split('.').next()
+}
+
+// "tenant.my-app.macbook.local" -> "my-app", the route a wildcard host would belong to
+fn wildcard_parent(host: &str) -> Option<&str> {
+    let host = host.split(':').next().unwrap_or_default();
+    let name = strip_domain_suffix(host)?;
+    let labels: Vec<&str> = name.split('.').collect();
+    (labels.len() >= 3).then(|| labels[1])
+}
+
+fn find_route(routes: &HashMap<String, ProxyRoute>, host: &str) -> Option<ProxyRoute> {
+    let wildcard = wildcard_parent(host)
+        .and_then(|parent| routes.get(parent))
+        .filter(|route| route.wildcard);
+    wildcard
+        .or_else(|| route_subdomain(host).and_then(|s| routes.get(s)))
+        .cloned()
+}
+
+// Mirrors the subset of Caddy path matchers mocks use: exact, "prefix*" and "*suffix"
+fn find_mock<'a>(mocks: &'a [MockResponse], path: &str) -> Option<&'a MockResponse> {
+    mocks.iter().find(|mock| match mock.path.as_deref() {
+        None | Some("") | Some("*") => true,
+        Some(pattern) => {
+            if let Some(prefix) = pattern.strip_suffix('*') {
+                path.starts_with(prefix)
+            } else if let Some(suffix) = pattern.strip_prefix('*') {
+                path.ends_with(suffix)
+            } else {
+                path == pattern
+            }
+        }
+    })
+}
+
+fn mock_response(mock: &MockResponse) -> Response<RelayBody> {
+    let mut builder = Response::builder().status(mock.status);
+    for (name, value) in &mock.headers {
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+    builder
+        .body(full_body(Bytes::from(mock.body.clone())))
+        .unwrap_or_else(|e| {
+            text_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Invalid mock response: {}", e),
+            )
+        })
+}
+
+fn text_response(status: StatusCode, message: String) -> Response<RelayBody> {
+    Response::builder()
+        .status(status)
+        .header(hyper::header::CONTENT_TYPE, "text/plain; charset=utf-8")
+        .body(full_body(Bytes::from(message)))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock(path: Option<&str>, status: u16) -> MockResponse {
+        MockResponse {
+            path: path.map(String::from),
+            status,
+            headers: Vec::new(),
+            body: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_route_subdomain() {
+        assert_eq!(route_subdomain("my-app.local"), Some("my-app"));
+        assert_eq!(route_subdomain("my-app.local:8080"), Some("my-app"));
+        assert_eq!(route_subdomain("my-app.local."), Some("my-app"));
+        assert_eq!(route_subdomain("my-app.macbook.local"), Some("my-app"));
+        assert_eq!(route_subdomain("my-app.macbook.test"), Some("my-app"));
+        assert_eq!(route_subdomain("my-app.latest"), None);
+        assert_eq!(route_subdomain("localhost:3000"), None);
+
+        let mut routes = HashMap::new();
+        routes.insert(
+            "shop".to_string(),
+            ProxyRoute {
+                wildcard: true,
+                ..ProxyRoute::new("shop", 3000)
+            },
+        );
+        routes.insert("acme".to_string(), ProxyRoute::new("acme", 4000));
+        let port = |host: &str| find_route(&routes, host).map(|r| r.port);
+        assert_eq!(port("shop.macbook.local"), Some(3000));
+        assert_eq!(port("acme.shop.macbook.local:80"), Some(3000));
+        assert_eq!(port("acme.macbook.local"), Some(4000));
+        assert_eq!(port("x.acme.macbook.local"), None);
+        assert_eq!(port("acme.shop.macbook.test"), Some(3000));
+        assert_eq!(port("blog.local"), None);
+    }
+
+    #[test]
+    fn test_set_forwarded_headers() {
+        let remote: SocketAddr = "192.168.1.5:50000".parse().unwrap();
+        let mut headers = hyper::HeaderMap::new();
+        headers.insert(hyper::header::HOST, "shop.local".parse().unwrap());
+        headers.insert("x-forwarded-for", "10.0.0.1".parse().unwrap());
+        set_forwarded_headers(
+            &mut headers,
+            remote,
+            "shop.local",
+            3000,
+            &Default::default(),
+        );
+        assert_eq!(headers["x-forwarded-for"], "192.168.1.5");
+        assert_eq!(headers["x-forwarded-host"], "shop.local");
+        assert_eq!(headers[hyper::header::HOST], "shop.local");
+
+        let options = ForwardHeaders {
+            rewrite_host: true,
+            forwarded_for: false,
+            ..Default::default()
+        };
+        set_forwarded_headers(&mut headers, remote, "shop.local", 3000, &options);
+        assert!(!headers.contains_key("x-forwarded-for"));
+        assert_eq!(headers["x-forwarded-proto"], "http");
+        assert_eq!(headers[hyper::header::HOST], "localhost:3000");
+    }
+
+    #[test]
+    fn test_body_too_large() {
+        let mut headers = hyper::HeaderMap::new();
+        assert!(!body_too_large(&headers, Some(10)));
+        headers.insert(hyper::header::CONTENT_LENGTH, "11".parse().unwrap());
+        assert!(body_too_large(&headers, Some(10)));
+        assert!(!body_too_large(&headers, Some(11)));
+        assert!(!body_too_large(&headers, None));
+    }
+
+    #[test]
+    fn test_resolve_static_path() {
+        let root = std::env::temp_dir().join(format!("mla-static-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("assets")).unwrap();
+        std::fs::write(root.join("index.html"), "<html>").unwrap();
+        std::fs::write(root.join("assets/app 1.js"), "").unwrap();
+
+        assert_eq!(
+            resolve_static_path(&root, "/", false),
+            Some(root.join("index.html"))
+        );
+        assert_eq!(
+            resolve_static_path(&root, "/assets/app%201.js", false),
+            Some(root.join("assets/app 1.js"))
+        );
+        assert_eq!(resolve_static_path(&root, "/settings/profile", false), None);
+        assert_eq!(
+            resolve_static_path(&root, "/settings/profile", true),
+            Some(root.join("index.html"))
+        );
+        assert_eq!(resolve_static_path(&root, "/../etc/passwd", true), None);
+        assert_eq!(
+            content_type(&root.join("assets/app.JS")),
+            "text/javascript; charset=utf-8"
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_find_mock() {
+        let mocks = vec![
+            mock(Some("/api/*"), 503),
+            mock(Some("*.png"), 404),
+            mock(Some("/health"), 200),
+        ];
+        assert_eq!(find_mock(&mocks, "/api/users").map(|m| m.status), Some(503));
+        assert_eq!(
+            find_mock(&mocks, "/img/logo.png").map(|m| m.status),
+            Some(404)
+        );
+        assert_eq!(find_mock(&mocks, "/health").map(|m| m.status), Some(200));
+        assert!(find_mock(&mocks, "/healthz").is_none());
+
+        let catch_all = vec![mock(None, 418)];
+        assert_eq!(
+            find_mock(&catch_all, "/anything").map(|m| m.status),
+            Some(418)
+        );
+    }
+}
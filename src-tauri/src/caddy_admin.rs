@@ -0,0 +1,158 @@
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::{Method, Request};
+use hyper_util::rt::TokioIo;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+
+// The socket lives in a directory owned by the installing user (mode 0700), so only that
+// user and root can reach the admin API
+const ADMIN_SOCKET_DIR: &str = "/usr/local/var/run/my-little-apps";
+const ADMIN_SOCKET: &str = "/usr/local/var/run/my-little-apps/caddy-admin.sock";
+// Installs from before the admin socket keep the TCP endpoint until the service is reinstalled
+const LEGACY_ADMIN_ADDRESS: &str = "localhost:2019";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", content = "address", rename_all = "snake_case")]
+pub enum AdminEndpoint {
+    Unix(String),
+    Tcp(String),
+}
+
+impl AdminEndpoint {
+    /// Endpoint of the managed Caddy as set up by the install script.
+    pub fn detect() -> Self {
+        if cfg!(unix) && Path::new(ADMIN_SOCKET_DIR).is_dir() {
+            AdminEndpoint::Unix(ADMIN_SOCKET.to_string())
+        } else {
+            AdminEndpoint::Tcp(LEGACY_ADMIN_ADDRESS.to_string())
+        }
+    }
+
+    /// Value for the `admin` global option of the generated Caddyfile.
+    pub fn directive(&self) -> String {
+        match self {
+            AdminEndpoint::Unix(path) => format!("unix/{}|0666", path),
+            AdminEndpoint::Tcp(address) => address.clone(),
+        }
+    }
+}
+
+pub struct AdminResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+impl AdminResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+pub async fn request(
+    endpoint: &AdminEndpoint,
+    method: Method,
+    path: &str,
+    content_type: Option<&str>,
+    body: String,
+    timeout: Duration,
+) -> Result<AdminResponse, String> {
+    // Caddy only accepts loopback Host values on its admin socket
+    let mut builder = Request::builder()
+        .method(method)
+        .uri(path)
+        .header(hyper::header::HOST, "127.0.0.1");
+    if let Some(content_type) = content_type {
+        builder = builder.header(hyper::header::CONTENT_TYPE, content_type);
+    }
+    let req = builder
+        .body(Full::new(Bytes::from(body)))
+        .map_err(|e| format!("Invalid Caddy admin request: {}", e))?;
+
+    let exchange = async {
+        match endpoint {
+            #[cfg(unix)]
+            AdminEndpoint::Unix(path) => {
+                let stream = tokio::net::UnixStream::connect(path)
+                    .await
+                    .map_err(connect_error)?;
+                send(stream, req).await
+            }
+            #[cfg(not(unix))]
+            AdminEndpoint::Unix(_) => {
+                Err("Unix sockets are not supported on this platform".to_string())
+            }
+            AdminEndpoint::Tcp(address) => {
+                let stream = TcpStream::connect(address.as_str())
+                    .await
+                    .map_err(connect_error)?;
+                send(stream, req).await
+            }
+        }
+    };
+
+    tokio::time::timeout(timeout, exchange)
+        .await
+        .map_err(|_| "Timed out waiting for the Caddy admin API".to_string())?
+}
+
+async fn send<S>(stream: S, req: Request<Full<Bytes>>) -> Result<AdminResponse, String>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let (mut sender, conn) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+        .await
+        .map_err(connect_error)?;
+
+    tauri::async_runtime::spawn(async move {
+        let _ = conn.await;
+    });
+
+    let response = sender
+        .send_request(req)
+        .await
+        .map_err(|e| format!("Caddy admin request failed: {}", e))?;
+    let status = response.status().as_u16();
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .map_err(|e| format!("Failed to read Caddy admin response: {}", e))?
+        .to_bytes();
+
+    Ok(AdminResponse {
+        status,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+fn connect_error(e: impl std::fmt::Display) -> String {
+    format!(
+        "Failed to connect to Caddy admin API (is the proxy service running?): {}",
+        e
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_admin_directive() {
+        let socket = AdminEndpoint::Unix("/tmp/caddy-admin.sock".to_string());
+        assert_eq!(socket.directive(), "unix//tmp/caddy-admin.sock|0666");
+
+        let tcp = AdminEndpoint::Tcp("localhost:2020".to_string());
+        assert_eq!(tcp.directive(), "localhost:2020");
+    }
+
+    #[test]
+    fn test_admin_endpoint_serialization() {
+        let endpoint: AdminEndpoint =
+            serde_json::from_str(r#"{"kind":"tcp","address":"127.0.0.1:2020"}"#).unwrap();
+        assert_eq!(endpoint, AdminEndpoint::Tcp("127.0.0.1:2020".to_string()));
+    }
+}
@@ -0,0 +1,301 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::proxy::slugify;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ImportedRoute {
+    pub site: String,
+    pub subdomain: String,
+    pub port: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SkippedSite {
+    pub site: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CaddyImport {
+    pub routes: Vec<ImportedRoute>,
+    pub skipped: Vec<SkippedSite>,
+}
+
+/// What importing routes added, by route id, and the sites it left out.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct ImportResult {
+    pub ids: Vec<String>,
+    pub skipped: Vec<SkippedSite>,
+}
+
+/// The subdomain and port to add each route under. Names that slugify to nothing, e.g.
+/// only emoji, are skipped: they would route the bare domain.
+pub fn route_subdomains(routes: Vec<ImportedRoute>) -> (Vec<(String, i32)>, Vec<SkippedSite>) {
+    let mut subdomains = Vec::new();
+    let mut skipped = Vec::new();
+    for route in routes {
+        let subdomain = slugify(&route.subdomain);
+        if subdomain.is_empty() {
+            skipped.push(SkippedSite {
+                site: route.site,
+                reason: format!("{:?} leaves no subdomain", route.subdomain),
+            });
+        } else {
+            subdomains.push((subdomain, route.port));
+        }
+    }
+    (subdomains, skipped)
+}
+
+/// Parses a Caddyfile or Caddy JSON config and collects sites that reverse-proxy to a local port.
+pub fn parse_config(content: &str) -> CaddyImport {
+    match serde_json::from_str::<serde_json::Value>(content) {
+        Ok(config) if config.is_object() => parse_json(&config),
+        _ => parse_caddyfile(content),
+    }
+}
+
+fn parse_caddyfile(content: &str) -> CaddyImport {
+    let mut import = CaddyImport::default();
+    let mut depth = 0usize;
+    let mut site: Option<String> = None;
+    let mut upstream: Option<String> = None;
+
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if depth == 0 && line.ends_with('{') {
+            let address = line.trim_end_matches('{').trim();
+            // A bare "{" opens the global options block
+            site = (!address.is_empty()).then(|| address.to_string());
+            upstream = None;
+        } else if depth == 1 && upstream.is_none() {
+            let mut tokens = line.split_whitespace();
+            if tokens.next() == Some("reverse_proxy") {
+                upstream = tokens
+                    .find(|t| !t.starts_with('@') && !t.starts_with('/') && *t != "{")
+                    .map(String::from);
+            }
+        }
+
+        depth += line.matches('{').count();
+        depth = depth.saturating_sub(line.matches('}').count());
+
+        if depth == 0 {
+            if let Some(address) = site.take() {
+                for address in address.split(',').map(str::trim).filter(|a| !a.is_empty()) {
+                    add_site(&mut import, address, upstream.as_deref());
+                }
+            }
+        }
+    }
+
+    import
+}
+
+/// Every host matched by a Caddy JSON config, e.g. the live one from GET /config/.
+pub fn hosts(config: &serde_json::Value) -> BTreeSet<String> {
+    http_routes(config)
+        .flat_map(route_hosts)
+        .map(|host| host.to_ascii_lowercase())
+        .collect()
+}
+
+fn parse_json(config: &serde_json::Value) -> CaddyImport {
+    let mut import = CaddyImport::default();
+    for route in http_routes(config) {
+        let upstream = find_dial(route);
+        for host in route_hosts(route) {
+            add_site(&mut import, host, upstream.as_deref());
+        }
+    }
+    import
+}
+
+fn http_routes(config: &serde_json::Value) -> impl Iterator<Item = &serde_json::Value> {
+    config
+        .pointer("/apps/http/servers")
+        .and_then(|s| s.as_object())
+        .into_iter()
+        .flat_map(|servers| servers.values())
+        .filter_map(|server| server.get("routes").and_then(|r| r.as_array()))
+        .flatten()
+}
+
+fn route_hosts(route: &serde_json::Value) -> impl Iterator<Item = &str> {
+    route
+        .get("match")
+        .and_then(|m| m.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|m| m.get("host").and_then(|h| h.as_array()))
+        .flatten()
+        .filter_map(|h| h.as_str())
+}
+
+// Reverse proxy handlers are often nested inside subroutes
+fn find_dial(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Object(map) => {
+            if map.get("handler").and_then(|h| h.as_str()) == Some("reverse_proxy") {
+                if let Some(dial) = map
+                    .get("upstreams")
+                    .and_then(|u| u.as_array())
+                    .and_then(|u| u.first())
+                    .and_then(|u| u.get("dial"))
+                    .and_then(|d| d.as_str())
+                {
+                    return Some(dial.to_string());
+                }
+            }
+            map.values().find_map(find_dial)
+        }
+        serde_json::Value::Array(items) => items.iter().find_map(find_dial),
+        _ => None,
+    }
+}
+
+fn add_site(import: &mut CaddyImport, site: &str, upstream: Option<&str>) {
+    let skip = |import: &mut CaddyImport, reason: &str| {
+        import.skipped.push(SkippedSite {
+            site: site.to_string(),
+            reason: reason.to_string(),
+        });
+    };
+
+    let Some(upstream) = upstream else {
+        return skip(import, "No reverse_proxy upstream");
+    };
+    let Some(port) = local_upstream_port(upstream) else {
+        return skip(import, "Upstream is not a local port");
+    };
+    let subdomain = site_subdomain(site);
+    if subdomain.is_empty() {
+        return skip(import, "Site has no hostname");
+    }
+
+    import.routes.push(ImportedRoute {
+        site: site.to_string(),
+        subdomain,
+        port,
+    });
+}
+
+fn local_upstream_port(upstream: &str) -> Option<i32> {
+    let address = upstream
+        .trim_start_matches("http://")
+        .trim_start_matches("h2c://");
+    let (host, port) = address.rsplit_once(':')?;
+    if !matches!(host, "" | "localhost" | "127.0.0.1" | "[::1]") {
+        return None;
+    }
+    port.parse().ok()
+}
+
+fn site_subdomain(site: &str) -> String {
+    let host = site
+        .trim_start_matches("http://")
+        .trim_start_matches("https://");
+    let host = host.split(':').next().unwrap_or("");
+    let host = [".local", ".localhost", ".test"]
+        .iter()
+        .find_map(|suffix| host.strip_suffix(suffix))
+        .unwrap_or(host);
+    if host == "localhost" {
+        return String::new();
+    }
+    slugify(host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_subdomains() {
+        let route = |subdomain: &str| ImportedRoute {
+            site: format!("{}.test", subdomain),
+            subdomain: subdomain.to_string(),
+            port: 3000,
+        };
+        let (subdomains, skipped) = route_subdomains(vec![route("My Shop"), route("🛒")]);
+        assert_eq!(subdomains, [("my-shop".to_string(), 3000)]);
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].site, "🛒.test");
+    }
+
+    #[test]
+    fn test_parse_caddyfile() {
+        let content = r#"
+{
+    admin localhost:2019
+}
+
+http://shop.local, shop.test {
+    encode gzip
+    reverse_proxy /api/* localhost:4000
+}
+
+blog.example.com {
+    reverse_proxy 10.0.0.5:80
+}
+
+:8080 {
+    reverse_proxy 127.0.0.1:9000
+}
+
+docs.localhost {
+    root * /srv/docs
+    file_server
+}
+"#;
+        let import = parse_config(content);
+        assert_eq!(
+            import.routes,
+            vec![
+                ImportedRoute {
+                    site: "http://shop.local".to_string(),
+                    subdomain: "shop".to_string(),
+                    port: 4000,
+                },
+                ImportedRoute {
+                    site: "shop.test".to_string(),
+                    subdomain: "shop".to_string(),
+                    port: 4000,
+                },
+            ]
+        );
+        let skipped: Vec<&str> = import.skipped.iter().map(|s| s.site.as_str()).collect();
+        assert_eq!(skipped, vec!["blog.example.com", ":8080", "docs.localhost"]);
+    }
+
+    #[test]
+    fn test_parse_json_config() {
+        let content = r#"{
+            "apps": {"http": {"servers": {"srv0": {"routes": [{
+                "match": [{"host": ["admin.local"]}],
+                "handle": [{"handler": "subroute", "routes": [{
+                    "handle": [{"handler": "reverse_proxy", "upstreams": [{"dial": "localhost:5173"}]}]
+                }]}]
+            }]}}}}
+        }"#;
+        let import = parse_config(content);
+        assert_eq!(
+            import.routes,
+            vec![ImportedRoute {
+                site: "admin.local".to_string(),
+                subdomain: "admin".to_string(),
+                port: 5173,
+            }]
+        );
+        assert!(import.skipped.is_empty());
+
+        let config: serde_json::Value = serde_json::from_str(content).unwrap();
+        assert_eq!(hosts(&config), BTreeSet::from(["admin.local".to_string()]));
+        assert!(hosts(&serde_json::Value::Null).is_empty());
+    }
+}
@@ -0,0 +1,81 @@
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// The parts of an app's config a running process only picks up when restarted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaunchConfig {
+    pub command: String,
+    pub env: HashMap<String, String>,
+    // None lets the app start on any free port
+    pub port: Option<i32>,
+    pub subdomain: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigChange {
+    Command,
+    Env,
+    Port,
+    Subdomain,
+}
+
+/// What changed in the saved config since the app was started with `launched`.
+pub fn changes(launched: &LaunchConfig, saved: &LaunchConfig) -> Vec<ConfigChange> {
+    let mut changes = Vec::new();
+    if launched.command.trim() != saved.command.trim() {
+        changes.push(ConfigChange::Command);
+    }
+    if launched.env != saved.env {
+        changes.push(ConfigChange::Env);
+    }
+    // Switching to "any port" doesn't make the current one wrong
+    if saved.port.is_some_and(|port| Some(port) != launched.port) {
+        changes.push(ConfigChange::Port);
+    }
+    if subdomain(launched) != subdomain(saved) {
+        changes.push(ConfigChange::Subdomain);
+    }
+    changes
+}
+
+fn subdomain(config: &LaunchConfig) -> Option<&str> {
+    config
+        .subdomain
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changes() {
+        let launched = LaunchConfig {
+            command: "bun dev".to_string(),
+            env: HashMap::from([("API".to_string(), "http://localhost".to_string())]),
+            port: Some(3000),
+            subdomain: Some("shop".to_string()),
+        };
+        let mut saved = LaunchConfig {
+            command: "bun dev ".to_string(),
+            port: None,
+            ..launched.clone()
+        };
+        assert!(changes(&launched, &saved).is_empty());
+
+        saved.env.insert("DEBUG".to_string(), "1".to_string());
+        saved.port = Some(3001);
+        saved.subdomain = Some(String::new());
+        assert_eq!(
+            changes(&launched, &saved),
+            [
+                ConfigChange::Env,
+                ConfigChange::Port,
+                ConfigChange::Subdomain
+            ]
+        );
+    }
+}
@@ -0,0 +1,639 @@
+use crate::error::AppError;
+use crate::health::HealthCheck;
+use crate::keep_running::QuitBehavior;
+use crate::package_meta::PackageMeta;
+use crate::proxy::ProxyRoute;
+use crate::sessions::{Session, SessionApp, Trigger, When};
+use crate::timeline::{AppEvent, AppEventKind};
+use crate::{App, LaunchProfile};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions, SqliteRow};
+use sqlx::Row;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use tokio::sync::OnceCell;
+
+/// File name the frontend opens through tauri-plugin-sql, relative to the app config dir.
+pub const DB_FILE: &str = "my-little-apps.db";
+
+// A session only exists through its apps, so once the last one is gone its triggers go too
+const DELETE_ORPHANED_TRIGGERS: &str = "DELETE FROM session_triggers \
+    WHERE session IS NOT NULL AND session NOT IN (SELECT name FROM sessions)";
+
+// Columns of `apps` that `App` carries; description and notes are frontend-only
+const APP_COLUMNS: &str = "id, name, path, command, port, run_on_startup, created_at, subdomain, \
+     worktree_of, build_command, service, prerequisites, remote_host, devcontainer, path_entries, \
+     shell, kind, spa";
+
+/// Backend access to the database the frontend owns. The sql plugin creates the file and
+/// runs the migrations when the frontend first loads it, so this only ever connects.
+pub struct Db {
+    path: PathBuf,
+    pool: OnceCell<SqlitePool>,
+}
+
+impl Db {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            pool: OnceCell::new(),
+        }
+    }
+
+    async fn pool(&self) -> Result<&SqlitePool, String> {
+        self.pool
+            .get_or_try_init(|| async {
+                let options = SqliteConnectOptions::new()
+                    .filename(&self.path)
+                    .create_if_missing(false);
+                SqlitePoolOptions::new()
+                    .max_connections(2)
+                    .connect_with(options)
+                    .await
+                    .map_err(|e| format!("Failed to open {}: {}", self.path.display(), e))
+            })
+            .await
+    }
+
+    /// All apps, ordered by name like the frontend lists them.
+    pub async fn apps(&self) -> Result<Vec<App>, String> {
+        let rows = sqlx::query(&format!("SELECT {} FROM apps ORDER BY name", APP_COLUMNS))
+            .fetch_all(self.pool().await?)
+            .await
+            .map_err(|e| format!("Failed to load apps: {}", e))?;
+        rows.iter()
+            .map(app_from_row)
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("Failed to read app: {}", e))
+    }
+
+    /// Gives the app `subdomain`, or clears it with `None`. The unique index on
+    /// `apps.subdomain` decides conflicts, which come back as `SubdomainTaken`.
+    pub async fn set_subdomain(&self, id: &str, subdomain: Option<&str>) -> Result<(), AppError> {
+        let pool = self.pool().await?;
+        let result = sqlx::query("UPDATE apps SET subdomain = $1 WHERE id = $2")
+            .bind(subdomain)
+            .bind(id)
+            .execute(pool)
+            .await;
+        match result {
+            Ok(done) if done.rows_affected() == 0 => Err(AppError::unknown_app(id)),
+            Ok(_) => Ok(()),
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+                let subdomain = subdomain.unwrap_or_default();
+                let owner: Option<(String, String)> =
+                    sqlx::query_as("SELECT id, name FROM apps WHERE subdomain = $1")
+                        .bind(subdomain)
+                        .fetch_optional(pool)
+                        .await
+                        .map_err(|e| format!("Failed to look up subdomain owner: {}", e))?;
+                let (app_id, app_name) = owner.unwrap_or_default();
+                Err(AppError::subdomain_taken(subdomain, &app_id, &app_name))
+            }
+            Err(e) => Err(format!("Failed to update subdomain: {}", e).into()),
+        }
+    }
+
+    /// Removes the app with its profiles, health history, timeline and session entries,
+    /// and the triggers of sessions it was the last app of.
+    pub async fn delete_app(&self, id: &str) -> Result<(), String> {
+        let mut tx = self
+            .pool()
+            .await?
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to delete app: {}", e))?;
+        for table in ["app_profiles", "health_checks", "app_events", "sessions"] {
+            sqlx::query(&format!("DELETE FROM {} WHERE app_id = $1", table))
+                .bind(id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to delete app: {}", e))?;
+        }
+        sqlx::query("DELETE FROM apps WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to delete app: {}", e))?;
+        sqlx::query(DELETE_ORPHANED_TRIGGERS)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to delete app: {}", e))?;
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to delete app: {}", e))
+    }
+
+    /// The app's launch profile `name` as currently saved.
+    pub async fn profile(&self, app_id: &str, name: &str) -> Result<Option<LaunchProfile>, String> {
+        let row: Option<(String, String, Option<i32>)> = sqlx::query_as(
+            "SELECT command, env, port FROM app_profiles WHERE app_id = $1 AND name = $2",
+        )
+        .bind(app_id)
+        .bind(name)
+        .fetch_optional(self.pool().await?)
+        .await
+        .map_err(|e| format!("Failed to load profile {}: {}", name, e))?;
+        row.map(|(command, env, port)| {
+            let env = serde_json::from_str(&env)
+                .map_err(|e| format!("Profile {} has invalid env: {}", name, e))?;
+            Ok(LaunchProfile {
+                name: name.to_string(),
+                command,
+                env,
+                port,
+            })
+        })
+        .transpose()
+    }
+
+    /// Health endpoints apps set instead of the default "/".
+    pub async fn health_paths(&self) -> Result<HashMap<String, String>, String> {
+        let rows: Vec<(String, String)> =
+            sqlx::query_as("SELECT id, health_path FROM apps WHERE health_path IS NOT NULL")
+                .fetch_all(self.pool().await?)
+                .await
+                .map_err(|e| format!("Failed to load health paths: {}", e))?;
+        Ok(rows.into_iter().collect())
+    }
+
+    pub async fn set_health_path(&self, id: &str, path: Option<&str>) -> Result<(), AppError> {
+        let done = sqlx::query("UPDATE apps SET health_path = $1 WHERE id = $2")
+            .bind(path)
+            .bind(id)
+            .execute(self.pool().await?)
+            .await
+            .map_err(|e| format!("Failed to update health path: {}", e))?;
+        if done.rows_affected() == 0 {
+            return Err(AppError::unknown_app(id));
+        }
+        Ok(())
+    }
+
+    /// Apps with their own quit behavior instead of the global one.
+    pub async fn quit_behaviors(&self) -> Result<HashMap<String, QuitBehavior>, String> {
+        let rows: Vec<(String, String)> =
+            sqlx::query_as("SELECT id, on_quit FROM apps WHERE on_quit IS NOT NULL")
+                .fetch_all(self.pool().await?)
+                .await
+                .map_err(|e| format!("Failed to load quit behaviors: {}", e))?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|(id, value)| Some((id, QuitBehavior::parse(&value)?)))
+            .collect())
+    }
+
+    pub async fn set_quit_behavior(
+        &self,
+        id: &str,
+        behavior: Option<QuitBehavior>,
+    ) -> Result<(), AppError> {
+        let done = sqlx::query("UPDATE apps SET on_quit = $1 WHERE id = $2")
+            .bind(behavior.map(QuitBehavior::as_str))
+            .bind(id)
+            .execute(self.pool().await?)
+            .await
+            .map_err(|e| format!("Failed to update quit behavior: {}", e))?;
+        if done.rows_affected() == 0 {
+            return Err(AppError::unknown_app(id));
+        }
+        Ok(())
+    }
+
+    /// Apps whose output the window only samples under load, see `log_events`.
+    pub async fn log_sampled_apps(&self) -> Result<HashSet<String>, String> {
+        let rows: Vec<(String,)> = sqlx::query_as("SELECT id FROM apps WHERE sample_logs = 1")
+            .fetch_all(self.pool().await?)
+            .await
+            .map_err(|e| format!("Failed to load log sampling: {}", e))?;
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+
+    pub async fn set_log_sampling(&self, id: &str, sampled: bool) -> Result<(), AppError> {
+        let done = sqlx::query("UPDATE apps SET sample_logs = $1 WHERE id = $2")
+            .bind(sampled)
+            .bind(id)
+            .execute(self.pool().await?)
+            .await
+            .map_err(|e| format!("Failed to update log sampling: {}", e))?;
+        if done.rows_affected() == 0 {
+            return Err(AppError::unknown_app(id));
+        }
+        Ok(())
+    }
+
+    /// Apps that send a notification once they are ready.
+    pub async fn notify_ready_apps(&self) -> Result<HashSet<String>, String> {
+        let rows: Vec<(String,)> = sqlx::query_as("SELECT id FROM apps WHERE notify_ready = 1")
+            .fetch_all(self.pool().await?)
+            .await
+            .map_err(|e| format!("Failed to load ready notifications: {}", e))?;
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+
+    pub async fn set_notify_ready(&self, id: &str, notify: bool) -> Result<(), AppError> {
+        let done = sqlx::query("UPDATE apps SET notify_ready = $1 WHERE id = $2")
+            .bind(notify)
+            .bind(id)
+            .execute(self.pool().await?)
+            .await
+            .map_err(|e| format!("Failed to update ready notification: {}", e))?;
+        if done.rows_affected() == 0 {
+            return Err(AppError::unknown_app(id));
+        }
+        Ok(())
+    }
+
+    /// Apps that run under `sandbox-exec`, see `sandbox`.
+    pub async fn sandboxed_apps(&self) -> Result<HashSet<String>, String> {
+        let rows: Vec<(String,)> = sqlx::query_as("SELECT id FROM apps WHERE sandbox = 1")
+            .fetch_all(self.pool().await?)
+            .await
+            .map_err(|e| format!("Failed to load sandboxed apps: {}", e))?;
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+
+    pub async fn set_sandbox(&self, id: &str, sandboxed: bool) -> Result<(), AppError> {
+        let done = sqlx::query("UPDATE apps SET sandbox = $1 WHERE id = $2")
+            .bind(sandboxed)
+            .bind(id)
+            .execute(self.pool().await?)
+            .await
+            .map_err(|e| format!("Failed to update sandbox: {}", e))?;
+        if done.rows_affected() == 0 {
+            return Err(AppError::unknown_app(id));
+        }
+        Ok(())
+    }
+
+    /// Apps that build before starting when the build they serve is stale, see
+    /// `freshness`.
+    pub async fn rebuild_stale_apps(&self) -> Result<HashSet<String>, String> {
+        let rows: Vec<(String,)> = sqlx::query_as("SELECT id FROM apps WHERE rebuild_stale = 1")
+            .fetch_all(self.pool().await?)
+            .await
+            .map_err(|e| format!("Failed to load stale build settings: {}", e))?;
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+
+    pub async fn set_rebuild_stale(&self, id: &str, rebuild: bool) -> Result<(), AppError> {
+        let done = sqlx::query("UPDATE apps SET rebuild_stale = $1 WHERE id = $2")
+            .bind(rebuild)
+            .bind(id)
+            .execute(self.pool().await?)
+            .await
+            .map_err(|e| format!("Failed to update stale build setting: {}", e))?;
+        if done.rows_affected() == 0 {
+            return Err(AppError::unknown_app(id));
+        }
+        Ok(())
+    }
+
+    /// Caches what the app's package.json says on the app, with engines the runtime it
+    /// starts with doesn't meet. `None` clears it for apps without one.
+    pub async fn set_package_meta(
+        &self,
+        id: &str,
+        meta: Option<&PackageMeta>,
+        engine_warning: Option<&str>,
+    ) -> Result<(), String> {
+        let engines = meta
+            .filter(|meta| !meta.engines.is_empty())
+            .map(|meta| serde_json::to_string(&meta.engines).unwrap_or_default());
+        sqlx::query(
+            "UPDATE apps SET package_name = $1, package_version = $2, package_engines = $3, \
+             engine_warning = $4 WHERE id = $5",
+        )
+        .bind(meta.and_then(|meta| meta.name.as_deref()))
+        .bind(meta.and_then(|meta| meta.version.as_deref()))
+        .bind(engines)
+        .bind(engine_warning)
+        .bind(id)
+        .execute(self.pool().await?)
+        .await
+        .map_err(|e| format!("Failed to save package.json details: {}", e))?;
+        Ok(())
+    }
+
+    /// Saved sessions by name, see `sessions`.
+    pub async fn sessions(&self) -> Result<Vec<Session>, String> {
+        let rows: Vec<(String, String, Option<String>)> =
+            sqlx::query_as("SELECT name, app_id, profile FROM sessions ORDER BY name, app_id")
+                .fetch_all(self.pool().await?)
+                .await
+                .map_err(|e| format!("Failed to load sessions: {}", e))?;
+        let mut sessions: Vec<Session> = Vec::new();
+        for (name, app_id, profile) in rows {
+            let app = SessionApp { app_id, profile };
+            match sessions.last_mut() {
+                Some(session) if session.name == name => session.apps.push(app),
+                _ => sessions.push(Session {
+                    name,
+                    apps: vec![app],
+                }),
+            }
+        }
+        Ok(sessions)
+    }
+
+    /// Saves `apps` as the session `name`, replacing one saved before.
+    pub async fn save_session(&self, name: &str, apps: &[SessionApp]) -> Result<(), String> {
+        let mut tx = self
+            .pool()
+            .await?
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to save session {}: {}", name, e))?;
+        sqlx::query("DELETE FROM sessions WHERE name = $1")
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to save session {}: {}", name, e))?;
+        for app in apps {
+            sqlx::query("INSERT INTO sessions (name, app_id, profile) VALUES ($1, $2, $3)")
+                .bind(name)
+                .bind(&app.app_id)
+                .bind(&app.profile)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to save session {}: {}", name, e))?;
+        }
+        sqlx::query(DELETE_ORPHANED_TRIGGERS)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to save session {}: {}", name, e))?;
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to save session {}: {}", name, e))
+    }
+
+    /// Deletes the session `name` and the triggers that switch to it.
+    pub async fn delete_session(&self, name: &str) -> Result<(), String> {
+        for sql in [
+            "DELETE FROM sessions WHERE name = $1",
+            "DELETE FROM session_triggers WHERE session = $1",
+        ] {
+            sqlx::query(sql)
+                .bind(name)
+                .execute(self.pool().await?)
+                .await
+                .map_err(|e| format!("Failed to delete session {}: {}", name, e))?;
+        }
+        Ok(())
+    }
+
+    /// Triggers that switch sessions, oldest first.
+    pub async fn session_triggers(&self) -> Result<Vec<Trigger>, String> {
+        type Row = (
+            i64,
+            Option<String>,
+            String,
+            i64,
+            Option<String>,
+            Option<String>,
+        );
+        let rows: Vec<Row> = sqlx::query_as(
+            "SELECT id, session, kind, days, at, focus FROM session_triggers ORDER BY id",
+        )
+        .fetch_all(self.pool().await?)
+        .await
+        .map_err(|e| format!("Failed to load session triggers: {}", e))?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|(id, session, kind, days, at, focus)| {
+                let when = match kind.as_str() {
+                    "schedule" => When::Schedule {
+                        days: days as u8,
+                        at: at?,
+                    },
+                    "focus" => When::Focus { mode: focus? },
+                    _ => return None,
+                };
+                Some(Trigger { id, session, when })
+            })
+            .collect())
+    }
+
+    /// Saves `trigger`, returning its id.
+    pub async fn add_session_trigger(&self, trigger: &Trigger) -> Result<i64, String> {
+        let (kind, days, at, focus) = match &trigger.when {
+            When::Schedule { days, at } => ("schedule", *days, Some(at), None),
+            When::Focus { mode } => ("focus", 0, None, Some(mode)),
+        };
+        let done = sqlx::query(
+            "INSERT INTO session_triggers (session, kind, days, at, focus) VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(&trigger.session)
+        .bind(kind)
+        .bind(days)
+        .bind(at)
+        .bind(focus)
+        .execute(self.pool().await?)
+        .await
+        .map_err(|e| format!("Failed to save session trigger: {}", e))?;
+        Ok(done.last_insert_rowid())
+    }
+
+    pub async fn delete_session_trigger(&self, id: i64) -> Result<(), String> {
+        sqlx::query("DELETE FROM session_triggers WHERE id = $1")
+            .bind(id)
+            .execute(self.pool().await?)
+            .await
+            .map_err(|e| format!("Failed to delete session trigger: {}", e))?;
+        Ok(())
+    }
+
+    /// Proxy routes by app id, as they were last changed.
+    pub async fn proxy_routes(&self) -> Result<HashMap<String, ProxyRoute>, String> {
+        let rows: Vec<(String, String)> = sqlx::query_as("SELECT app_id, route FROM proxy_routes")
+            .fetch_all(self.pool().await?)
+            .await
+            .map_err(|e| format!("Failed to load proxy routes: {}", e))?;
+        rows.into_iter()
+            .map(|(app_id, route)| {
+                serde_json::from_str(&route)
+                    .map(|route| (app_id, route))
+                    .map_err(|e| format!("Failed to load proxy routes: {}", e))
+            })
+            .collect()
+    }
+
+    /// Replaces the saved proxy routes with `routes`.
+    pub async fn save_proxy_routes(
+        &self,
+        routes: &HashMap<String, ProxyRoute>,
+    ) -> Result<(), String> {
+        let mut tx = self
+            .pool()
+            .await?
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to save proxy routes: {}", e))?;
+        sqlx::query("DELETE FROM proxy_routes")
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to save proxy routes: {}", e))?;
+        for (app_id, route) in routes {
+            let route = serde_json::to_string(route)
+                .map_err(|e| format!("Failed to save proxy routes: {}", e))?;
+            sqlx::query("INSERT INTO proxy_routes (app_id, route) VALUES ($1, $2)")
+                .bind(app_id)
+                .bind(route)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to save proxy routes: {}", e))?;
+        }
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to save proxy routes: {}", e))
+    }
+
+    /// Saved settings by key, as JSON text.
+    pub async fn settings(&self) -> Result<HashMap<String, String>, String> {
+        let rows: Vec<(String, String)> = sqlx::query_as("SELECT key, value FROM settings")
+            .fetch_all(self.pool().await?)
+            .await
+            .map_err(|e| format!("Failed to load settings: {}", e))?;
+        Ok(rows.into_iter().collect())
+    }
+
+    pub async fn set_setting(&self, key: &str, value: &str) -> Result<(), String> {
+        sqlx::query(
+            "INSERT INTO settings (key, value) VALUES ($1, $2) \
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        )
+        .bind(key)
+        .bind(value)
+        .execute(self.pool().await?)
+        .await
+        .map_err(|e| format!("Failed to save setting {}: {}", key, e))?;
+        Ok(())
+    }
+
+    pub async fn record_health_check(
+        &self,
+        app_id: &str,
+        check: &HealthCheck,
+    ) -> Result<(), String> {
+        sqlx::query(
+            "INSERT INTO health_checks (app_id, checked_at, up, status, response_ms, error) \
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(app_id)
+        .bind(check.checked_at)
+        .bind(check.up)
+        .bind(check.status)
+        .bind(check.response_ms)
+        .bind(&check.error)
+        .execute(self.pool().await?)
+        .await
+        .map_err(|e| format!("Failed to record health check: {}", e))?;
+        Ok(())
+    }
+
+    /// The app's checks since `since` (unix ms), oldest first.
+    pub async fn health_checks(
+        &self,
+        app_id: &str,
+        since: i64,
+    ) -> Result<Vec<HealthCheck>, String> {
+        let rows = sqlx::query(
+            "SELECT checked_at, up, status, response_ms, error FROM health_checks \
+             WHERE app_id = $1 AND checked_at >= $2 ORDER BY checked_at",
+        )
+        .bind(app_id)
+        .bind(since)
+        .fetch_all(self.pool().await?)
+        .await
+        .map_err(|e| format!("Failed to load health checks: {}", e))?;
+        rows.iter()
+            .map(|row| {
+                Ok(HealthCheck {
+                    checked_at: row.try_get("checked_at")?,
+                    up: row.try_get("up")?,
+                    status: row.try_get("status")?,
+                    response_ms: row.try_get("response_ms")?,
+                    error: row.try_get("error")?,
+                })
+            })
+            .collect::<Result<_, sqlx::Error>>()
+            .map_err(|e| format!("Failed to read health check: {}", e))
+    }
+
+    pub async fn prune_health_checks(&self, before: i64) -> Result<(), String> {
+        sqlx::query("DELETE FROM health_checks WHERE checked_at < $1")
+            .bind(before)
+            .execute(self.pool().await?)
+            .await
+            .map_err(|e| format!("Failed to prune health checks: {}", e))?;
+        Ok(())
+    }
+
+    pub async fn record_app_event(&self, event: &AppEvent) -> Result<(), String> {
+        sqlx::query("INSERT INTO app_events (app_id, at, kind, detail) VALUES ($1, $2, $3, $4)")
+            .bind(&event.app_id)
+            .bind(event.at)
+            .bind(event.kind.as_str())
+            .bind(&event.detail)
+            .execute(self.pool().await?)
+            .await
+            .map_err(|e| format!("Failed to record app event: {}", e))?;
+        Ok(())
+    }
+
+    /// The app's timeline since `since` (unix ms), newest first.
+    pub async fn app_events(&self, app_id: &str, since: i64) -> Result<Vec<AppEvent>, String> {
+        let rows: Vec<(i64, String, Option<String>)> = sqlx::query_as(
+            "SELECT at, kind, detail FROM app_events \
+             WHERE app_id = $1 AND at >= $2 ORDER BY at DESC, id DESC",
+        )
+        .bind(app_id)
+        .bind(since)
+        .fetch_all(self.pool().await?)
+        .await
+        .map_err(|e| format!("Failed to load app events: {}", e))?;
+        // Kinds a newer version wrote are skipped
+        Ok(rows
+            .into_iter()
+            .filter_map(|(at, kind, detail)| {
+                Some(AppEvent {
+                    app_id: app_id.to_string(),
+                    at,
+                    kind: AppEventKind::parse(&kind)?,
+                    detail,
+                })
+            })
+            .collect())
+    }
+
+    pub async fn prune_app_events(&self, before: i64) -> Result<(), String> {
+        sqlx::query("DELETE FROM app_events WHERE at < $1")
+            .bind(before)
+            .execute(self.pool().await?)
+            .await
+            .map_err(|e| format!("Failed to prune app events: {}", e))?;
+        Ok(())
+    }
+}
+
+fn app_from_row(row: &SqliteRow) -> Result<App, sqlx::Error> {
+    Ok(App {
+        id: row.try_get("id")?,
+        name: row.try_get("name")?,
+        path: row.try_get("path")?,
+        command: row.try_get("command")?,
+        port: row.try_get("port")?,
+        run_on_startup: row.try_get("run_on_startup")?,
+        created_at: row.try_get("created_at")?,
+        subdomain: row.try_get("subdomain")?,
+        worktree_of: row.try_get("worktree_of")?,
+        build_command: row.try_get("build_command")?,
+        service: row.try_get("service")?,
+        prerequisites: row.try_get("prerequisites")?,
+        remote_host: row.try_get("remote_host")?,
+        devcontainer: row.try_get("devcontainer")?,
+        path_entries: row.try_get("path_entries")?,
+        shell: row.try_get("shell")?,
+        kind: row.try_get("kind")?,
+        spa: row.try_get("spa")?,
+    })
+}
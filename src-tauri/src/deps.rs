@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+// Lockfile and the install command that refreshes it, most specific first
+const LOCKFILES: [(&str, &str); 5] = [
+    ("bun.lock", "bun install"),
+    ("bun.lockb", "bun install"),
+    ("pnpm-lock.yaml", "pnpm install"),
+    ("yarn.lock", "yarn install"),
+    ("package-lock.json", "npm install"),
+];
+
+/// Contents of every lockfile present in `dir`, compared before and after pulling.
+pub fn snapshot_lockfiles(dir: &Path) -> HashMap<&'static str, Vec<u8>> {
+    LOCKFILES
+        .iter()
+        .filter_map(|(name, _)| Some((*name, std::fs::read(dir.join(name)).ok()?)))
+        .collect()
+}
+
+/// Install command to run when a lockfile was added or changed between two snapshots.
+pub fn changed_install_command(
+    before: &HashMap<&'static str, Vec<u8>>,
+    after: &HashMap<&'static str, Vec<u8>>,
+) -> Option<&'static str> {
+    LOCKFILES
+        .iter()
+        .find(|(name, _)| after.get(name).is_some_and(|c| before.get(name) != Some(c)))
+        .map(|(_, install)| *install)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_install_command() {
+        let before = HashMap::from([("bun.lock", b"a".to_vec())]);
+        let same = before.clone();
+        let changed = HashMap::from([("bun.lock", b"b".to_vec())]);
+        let added = HashMap::from([
+            ("bun.lock", b"a".to_vec()),
+            ("package-lock.json", b"{}".to_vec()),
+        ]);
+
+        assert_eq!(changed_install_command(&before, &same), None);
+        assert_eq!(
+            changed_install_command(&before, &changed),
+            Some("bun install")
+        );
+        assert_eq!(
+            changed_install_command(&before, &added),
+            Some("npm install")
+        );
+        assert_eq!(changed_install_command(&before, &HashMap::new()), None);
+    }
+}
@@ -0,0 +1,272 @@
+use crate::terminal::shell_quote;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+// Where the dev containers spec looks for a config, in order
+const CONFIG_PATHS: [&str; 2] = [".devcontainer/devcontainer.json", ".devcontainer.json"];
+
+/// The parts of a devcontainer.json needed to run an app's command inside the container.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DevcontainerConfig {
+    image: Option<String>,
+    // Dockerfile and build context, both resolved against the config's directory
+    dockerfile: Option<PathBuf>,
+    context: PathBuf,
+    workspace_folder: String,
+    env: Vec<(String, String)>,
+    remote_user: Option<String>,
+    run_args: Vec<String>,
+    /// First entry of `forwardPorts`, the port the app listens on inside the container
+    pub forward_port: Option<i32>,
+}
+
+pub fn find(dir: &Path) -> Option<PathBuf> {
+    CONFIG_PATHS
+        .iter()
+        .map(|path| dir.join(path))
+        .find(|path| path.is_file())
+}
+
+pub fn load(dir: &Path) -> Result<DevcontainerConfig, String> {
+    let path = find(dir).ok_or_else(|| format!("No devcontainer.json in {}", dir.display()))?;
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let json: serde_json::Value = serde_json::from_str(&strip_jsonc(&content))
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+    let config_dir = path.parent().unwrap_or(dir);
+    parse(&json, dir, config_dir)
+}
+
+fn parse(
+    json: &serde_json::Value,
+    dir: &Path,
+    config_dir: &Path,
+) -> Result<DevcontainerConfig, String> {
+    if json.get("dockerComposeFile").is_some() {
+        return Err("Docker Compose devcontainers aren't supported".to_string());
+    }
+
+    let folder_name = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let substitute = |value: &str| {
+        value
+            .replace("${localWorkspaceFolderBasename}", &folder_name)
+            .replace("${localWorkspaceFolder}", &dir.to_string_lossy())
+    };
+
+    let build = json.get("build").or_else(|| {
+        // Older configs put dockerFile at the top level
+        json.get("dockerFile").map(|_| json)
+    });
+    let dockerfile = build
+        .and_then(|b| b.get("dockerfile").or_else(|| b.get("dockerFile")))
+        .and_then(|d| d.as_str())
+        .map(|d| config_dir.join(d));
+    let context = build
+        .and_then(|b| b["context"].as_str())
+        .map(|c| config_dir.join(c))
+        .unwrap_or_else(|| config_dir.to_path_buf());
+    let image = json["image"].as_str().map(String::from);
+    if image.is_none() && dockerfile.is_none() {
+        return Err("devcontainer.json has neither an image nor a Dockerfile".to_string());
+    }
+
+    let mut env: Vec<(String, String)> = json["containerEnv"]
+        .as_object()
+        .map(|vars| {
+            vars.iter()
+                .filter_map(|(k, v)| v.as_str().map(|v| (k.clone(), substitute(v))))
+                .collect()
+        })
+        .unwrap_or_default();
+    env.sort();
+
+    Ok(DevcontainerConfig {
+        image,
+        dockerfile,
+        context,
+        workspace_folder: json["workspaceFolder"]
+            .as_str()
+            .map(substitute)
+            .unwrap_or_else(|| format!("/workspaces/{}", folder_name)),
+        env,
+        remote_user: json["remoteUser"]
+            .as_str()
+            .or_else(|| json["containerUser"].as_str())
+            .map(String::from),
+        run_args: json["runArgs"]
+            .as_array()
+            .map(|args| {
+                args.iter()
+                    .filter_map(|a| a.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        forward_port: json["forwardPorts"]
+            .as_array()
+            .and_then(|ports| ports.iter().find_map(|p| p.as_i64()))
+            .map(|p| p as i32),
+    })
+}
+
+/// Local command that runs `command` inside the devcontainer with the project mounted at the
+/// workspace folder, publishing `container_port` as `host_port` so the proxy can reach it.
+/// Meant to run from the project directory; the container is named after `slug`.
+pub fn run_command(
+    config: &DevcontainerConfig,
+    slug: &str,
+    command: &str,
+    env: &HashMap<String, String>,
+    container_port: i32,
+    host_port: i32,
+) -> String {
+    let container = format!("mla-devcontainer-{}", slug);
+    let mut steps = Vec::new();
+
+    let image = match (&config.dockerfile, &config.image) {
+        (Some(dockerfile), _) => {
+            steps.push(format!(
+                "docker build -t {} -f {} {}",
+                container,
+                shell_quote(&dockerfile.to_string_lossy()),
+                shell_quote(&config.context.to_string_lossy())
+            ));
+            container.clone()
+        }
+        (None, Some(image)) => image.clone(),
+        (None, None) => unreachable!("parse rejects configs without an image"),
+    };
+    steps.push(format!(
+        "{{ docker rm -f {} >/dev/null 2>&1; true; }}",
+        container
+    ));
+
+    let mut run = format!(
+        "docker run --rm --name {} -p {}:{} -v \"$PWD\":{} -w {}",
+        container,
+        host_port,
+        container_port,
+        shell_quote(&config.workspace_folder),
+        shell_quote(&config.workspace_folder)
+    );
+    let mut vars: Vec<(&String, &String)> = config.env.iter().map(|(k, v)| (k, v)).collect();
+    let mut app_vars: Vec<(&String, &String)> = env.iter().collect();
+    app_vars.sort();
+    vars.extend(app_vars);
+    for (key, value) in vars {
+        run.push_str(&format!(" -e {}={}", key, shell_quote(value)));
+    }
+    run.push_str(&format!(" -e PORT={}", container_port));
+    if let Some(user) = &config.remote_user {
+        run.push_str(&format!(" -u {}", shell_quote(user)));
+    }
+    for arg in &config.run_args {
+        run.push(' ');
+        run.push_str(&shell_quote(arg));
+    }
+    run.push_str(&format!(
+        " {} sh -lc {}",
+        shell_quote(&image),
+        shell_quote(command)
+    ));
+    steps.push(run);
+
+    steps.join(" && ")
+}
+
+// devcontainer.json is JSONC: drop comments and trailing commas so serde_json can read it
+fn strip_jsonc(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            (',', _) => {
+                // A comma followed only by whitespace before a closing bracket is trailing
+                let rest: String = chars.clone().collect();
+                let next = rest.trim_start().chars().next();
+                if !matches!(next, Some('}') | Some(']')) {
+                    out.push(c);
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_jsonc() {
+        let content = r#"{
+  // the image
+  "image": "node:20", /* inline */
+  "forwardPorts": [3000, "db:5432",],
+  "url": "http://example.com/*x*/",
+}"#;
+        let json: serde_json::Value = serde_json::from_str(&strip_jsonc(content)).unwrap();
+        assert_eq!(json["image"], "node:20");
+        assert_eq!(json["url"], "http://example.com/*x*/");
+        assert_eq!(json["forwardPorts"][0], 3000);
+    }
+
+    #[test]
+    fn test_run_command() {
+        let json = serde_json::json!({
+            "image": "mcr.microsoft.com/devcontainers/javascript-node:20",
+            "forwardPorts": [3000],
+            "containerEnv": { "NODE_ENV": "development" },
+            "remoteUser": "node"
+        });
+        let dir = Path::new("/Users/me/code/shop");
+        let config = parse(&json, dir, &dir.join(".devcontainer")).unwrap();
+        assert_eq!(config.forward_port, Some(3000));
+        assert_eq!(
+            run_command(&config, "shop", "npm run dev", &HashMap::new(), 3000, 41000),
+            "{ docker rm -f mla-devcontainer-shop >/dev/null 2>&1; true; } && \
+             docker run --rm --name mla-devcontainer-shop -p 41000:3000 \
+             -v \"$PWD\":'/workspaces/shop' -w '/workspaces/shop' \
+             -e NODE_ENV='development' -e PORT=3000 -u 'node' \
+             'mcr.microsoft.com/devcontainers/javascript-node:20' sh -lc 'npm run dev'"
+        );
+
+        let compose = serde_json::json!({ "dockerComposeFile": "compose.yml" });
+        assert!(parse(&compose, dir, dir).is_err());
+    }
+}
@@ -0,0 +1,215 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+// Top-level directories counted as build output and as tool caches
+const BUILD_DIRS: [&str; 6] = [".next", "dist", "build", "out", ".output", ".svelte-kit"];
+const CACHE_DIRS: [&str; 5] = [".turbo", ".cache", ".parcel-cache", ".vite", ".eslintcache"];
+
+/// Bytes on disk taken by a project, split into what is safe to regenerate.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct DiskUsage {
+    pub total: u64,
+    pub node_modules: u64,
+    pub build: u64,
+    pub cache: u64,
+    // Unix seconds when this was computed
+    pub computed_at: u64,
+}
+
+/// Artifact directories `clean` may delete; all regenerate from an install or build.
+pub const CLEAN_TARGETS: [&str; 4] = ["node_modules", ".next", "dist", ".turbo"];
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct CleanedTarget {
+    pub target: String,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CleanReport {
+    pub dry_run: bool,
+    /// Targets that existed, with the space deleting them frees
+    pub targets: Vec<CleanedTarget>,
+    pub reclaimed: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Category {
+    NodeModules,
+    Build,
+    Cache,
+    Source,
+}
+
+fn category(name: &str) -> Category {
+    if name == "node_modules" {
+        Category::NodeModules
+    } else if BUILD_DIRS.contains(&name) {
+        Category::Build
+    } else if CACHE_DIRS.contains(&name) {
+        Category::Cache
+    } else {
+        Category::Source
+    }
+}
+
+/// Walks the project at `path`; blocking, so run it off the async runtime.
+pub fn compute(path: &Path) -> Result<DiskUsage, String> {
+    let entries =
+        std::fs::read_dir(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    let mut usage = DiskUsage {
+        computed_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        ..Default::default()
+    };
+    for entry in entries.flatten() {
+        let size = size_of(&entry.path());
+        usage.total += size;
+        match category(&entry.file_name().to_string_lossy()) {
+            Category::NodeModules => usage.node_modules += size,
+            Category::Build => usage.build += size,
+            Category::Cache => usage.cache += size,
+            Category::Source => {}
+        }
+    }
+    Ok(usage)
+}
+
+/// Deletes the chosen artifact directories of the project at `path`, or only measures them
+/// when `dry_run` is set. Symlinked targets are skipped so nothing outside the project is touched.
+pub fn clean(path: &Path, targets: &[String], dry_run: bool) -> Result<CleanReport, String> {
+    let mut report = CleanReport {
+        dry_run,
+        targets: Vec::new(),
+        reclaimed: 0,
+    };
+    for (target, dir) in artifact_dirs(path, targets)? {
+        let bytes = size_of(&dir);
+        if !dry_run {
+            std::fs::remove_dir_all(&dir)
+                .map_err(|e| format!("Failed to delete {}: {}", dir.display(), e))?;
+        }
+        report.reclaimed += bytes;
+        report.targets.push(CleanedTarget { target, bytes });
+    }
+    Ok(report)
+}
+
+/// Like `clean`, but moves the directories to the Trash so they can be put back.
+pub fn trash(path: &Path, targets: &[String]) -> Result<CleanReport, String> {
+    let mut report = CleanReport {
+        dry_run: false,
+        targets: Vec::new(),
+        reclaimed: 0,
+    };
+    for (target, dir) in artifact_dirs(path, targets)? {
+        let bytes = size_of(&dir);
+        trash::delete(&dir)
+            .map_err(|e| format!("Failed to move {} to the Trash: {}", dir.display(), e))?;
+        report.reclaimed += bytes;
+        report.targets.push(CleanedTarget { target, bytes });
+    }
+    Ok(report)
+}
+
+// The targets that exist under `path` as real directories
+fn artifact_dirs(path: &Path, targets: &[String]) -> Result<Vec<(String, PathBuf)>, String> {
+    if let Some(unknown) = targets
+        .iter()
+        .find(|t| !CLEAN_TARGETS.contains(&t.as_str()))
+    {
+        return Err(format!("{} can't be cleaned", unknown));
+    }
+    Ok(targets
+        .iter()
+        .map(|target| (target.clone(), path.join(target)))
+        .filter(|(_, dir)| std::fs::symlink_metadata(dir).is_ok_and(|m| m.is_dir()))
+        .collect())
+}
+
+/// Bytes on disk under `path`, without following symlinks; unreadable entries count as 0.
+pub fn size_of(path: &Path) -> u64 {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    let own = allocated(&metadata);
+    if !metadata.is_dir() {
+        return own;
+    }
+
+    std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|e| size_of(&e.path())).sum::<u64>())
+        .unwrap_or(0)
+        + own
+}
+
+// Allocated blocks rather than file length, so sparse files and small-file overhead match `du`
+#[cfg(unix)]
+fn allocated(metadata: &std::fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.blocks() * 512
+}
+
+#[cfg(not(unix))]
+fn allocated(metadata: &std::fs::Metadata) -> u64 {
+    metadata.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_category() {
+        assert_eq!(category("node_modules"), Category::NodeModules);
+        assert_eq!(category(".next"), Category::Build);
+        assert_eq!(category(".turbo"), Category::Cache);
+        assert_eq!(category("src"), Category::Source);
+    }
+
+    #[test]
+    fn test_compute() {
+        let dir = std::env::temp_dir().join(format!("mla-disk-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("node_modules/react")).unwrap();
+        std::fs::create_dir_all(dir.join("dist")).unwrap();
+        std::fs::write(
+            dir.join("node_modules/react/index.js"),
+            vec![b'x'; 64 * 1024],
+        )
+        .unwrap();
+        std::fs::write(dir.join("dist/app.js"), vec![b'x'; 8 * 1024]).unwrap();
+        std::fs::write(dir.join("package.json"), "{}").unwrap();
+
+        let usage = compute(&dir).unwrap();
+        assert!(usage.node_modules >= 64 * 1024);
+        assert!(usage.build >= 8 * 1024);
+        assert_eq!(usage.cache, 0);
+        assert!(usage.total > usage.node_modules + usage.build);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_clean() {
+        let dir = std::env::temp_dir().join(format!("mla-clean-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("node_modules/react")).unwrap();
+        std::fs::write(dir.join("node_modules/react/index.js"), "x").unwrap();
+        let targets = vec!["node_modules".to_string(), ".next".to_string()];
+
+        let dry = clean(&dir, &targets, true).unwrap();
+        assert_eq!(dry.targets.len(), 1);
+        assert!(dry.reclaimed > 0);
+        assert!(dir.join("node_modules").exists());
+
+        let report = clean(&dir, &targets, false).unwrap();
+        assert_eq!(report.reclaimed, dry.reclaimed);
+        assert!(!dir.join("node_modules").exists());
+
+        assert!(clean(&dir, &["src".to_string()], false).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
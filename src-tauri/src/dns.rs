@@ -1,36 +1,44 @@
 use std::path::PathBuf;
 use std::process::Command;
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+use crate::error::AppError;
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct ProxyServiceStatus {
     pub installed: bool,
     pub caddy_running: bool,
 }
 
 pub fn get_lan_ip() -> Option<String> {
-    if let Ok(output) = Command::new("ipconfig").args(["getifaddr", "en0"]).output() {
-        if output.status.success() {
-            if let Ok(ip) = String::from_utf8(output.stdout) {
-                let ip = ip.trim();
-                if !ip.is_empty() {
-                    return Some(ip.to_string());
-                }
-            }
-        }
-    }
+    lan_address().map(|(_, ip)| ip)
+}
 
-    if let Ok(output) = Command::new("ipconfig").args(["getifaddr", "en1"]).output() {
-        if output.status.success() {
-            if let Ok(ip) = String::from_utf8(output.stdout) {
-                let ip = ip.trim();
-                if !ip.is_empty() {
-                    return Some(ip.to_string());
-                }
-            }
+// The Wi-Fi or Ethernet interface with an address, and that address
+fn lan_address() -> Option<(&'static str, String)> {
+    ["en0", "en1"].into_iter().find_map(|interface| {
+        let output = Command::new("ipconfig")
+            .args(["getifaddr", interface])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
         }
-    }
+        let ip = String::from_utf8(output.stdout).ok()?.trim().to_string();
+        (!ip.is_empty()).then_some((interface, ip))
+    })
+}
 
-    None
+/// Bonjour host name of this Mac, lowercased for use in DNS names.
+pub fn get_local_hostname() -> Option<String> {
+    let output = Command::new("scutil")
+        .args(["--get", "LocalHostName"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let hostname = crate::proxy::slugify(&String::from_utf8_lossy(&output.stdout));
+    (!hostname.is_empty()).then_some(hostname)
 }
 
 pub fn is_service_installed() -> bool {
@@ -53,7 +61,7 @@ pub fn get_service_status() -> ProxyServiceStatus {
     }
 }
 
-pub fn get_resource_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+pub fn get_resource_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, AppError> {
     use tauri::Manager;
 
     let resource_path = app_handle
@@ -70,42 +78,142 @@ pub fn get_resource_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, Strin
         return Ok(dev_path);
     }
 
-    Err("Resource directory not found".to_string())
+    Err(AppError::not_found("Resource directory not found"))
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct InstallProgress {
+    pub stage: String,
+    pub status: &'static str,
+    pub error: Option<String>,
+}
+
+fn emit_install_progress(
+    app_handle: &tauri::AppHandle,
+    stage: &str,
+    status: &'static str,
+    error: Option<String>,
+) {
+    use tauri::Emitter;
+
+    let _ = app_handle.emit(
+        "proxy-install-progress",
+        InstallProgress {
+            stage: stage.to_string(),
+            status,
+            error,
+        },
+    );
 }
 
-pub async fn install_service(app_handle: &tauri::AppHandle) -> Result<(), String> {
+pub async fn install_service(app_handle: &tauri::AppHandle) -> Result<(), AppError> {
+    use tauri::Manager;
+
     let resource_path = get_resource_path(app_handle)?;
     let install_script = resource_path.join("install-proxy.sh");
 
     if !install_script.exists() {
-        return Err(format!("Install script not found at {:?}", install_script));
+        return Err(AppError::not_found(format!(
+            "Install script not found at {:?}",
+            install_script
+        )));
     }
 
     let resource_path_str = resource_path.to_str().ok_or("Invalid resource path")?;
     let install_script_str = install_script.to_str().ok_or("Invalid script path")?;
 
+    // The admin socket directory is handed to the user running the app
+    let user = std::env::var("USER").unwrap_or_default();
+
+    // The script appends one stage name per line here while it runs as root
+    let progress_path = std::env::temp_dir().join(format!(
+        "my-little-apps-install-{}.progress",
+        uuid::Uuid::new_v4()
+    ));
+    std::fs::write(&progress_path, "")
+        .map_err(|e| format!("Failed to create install progress file: {}", e))?;
+    let progress_path_str = progress_path.to_str().ok_or("Invalid progress path")?;
+
     let osascript_command = format!(
-        r#"do shell script "bash '{}' '{}'" with administrator privileges"#,
-        install_script_str, resource_path_str
+        r#"do shell script "bash '{}' '{}' '{}' '{}'" with administrator privileges"#,
+        install_script_str, resource_path_str, user, progress_path_str
     );
 
-    let output = Command::new("osascript")
+    let mut child = tokio::process::Command::new("osascript")
         .args(["-e", &osascript_command])
-        .output()
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
         .map_err(|e| format!("Failed to run install script: {}", e))?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+    let mut stages: Vec<String> = Vec::new();
+    let status = loop {
+        let finished =
+            tokio::time::timeout(std::time::Duration::from_millis(200), child.wait()).await;
+
+        let reported: Vec<String> = std::fs::read_to_string(&progress_path)
+            .unwrap_or_default()
+            .lines()
+            .map(String::from)
+            .collect();
+        for stage in reported.iter().skip(stages.len()) {
+            if let Some(previous) = stages.last() {
+                emit_install_progress(app_handle, previous, "done", None);
+            }
+            emit_install_progress(app_handle, stage, "running", None);
+            stages.push(stage.clone());
+        }
+
+        if let Ok(status) = finished {
+            break status.map_err(|e| format!("Failed to run install script: {}", e))?;
+        }
+    };
+    let _ = std::fs::remove_file(&progress_path);
+
+    let mut stderr = String::new();
+    if let Some(mut pipe) = child.stderr.take() {
+        use tokio::io::AsyncReadExt;
+        let _ = pipe.read_to_string(&mut stderr).await;
+    }
+
+    let last_stage = stages
+        .last()
+        .cloned()
+        .unwrap_or_else(|| "preparing".to_string());
+    if !status.success() {
         if stderr.contains("User canceled") || stderr.contains("canceled") {
-            return Err("Installation was cancelled by user".to_string());
+            let error = "Installation was cancelled by user".to_string();
+            emit_install_progress(app_handle, &last_stage, "failed", Some(error.clone()));
+            return Err(AppError::permission_denied(error));
+        }
+        let error = format!(
+            "Installation failed while {}: {}",
+            last_stage.replace('_', " "),
+            stderr
+        );
+        emit_install_progress(app_handle, &last_stage, "failed", Some(error.clone()));
+        return Err(AppError::spawn_failed(error, stderr));
+    }
+    emit_install_progress(app_handle, &last_stage, "done", None);
+
+    emit_install_progress(app_handle, "verifying", "running", None);
+    let proxy_state = app_handle.state::<crate::proxy::ProxyState>();
+    for _ in 0..10 {
+        if is_caddy_running()
+            && crate::proxy::is_caddy_responsive(&proxy_state.admin_endpoint()).await
+        {
+            emit_install_progress(app_handle, "verifying", "done", None);
+            return Ok(());
         }
-        return Err(format!("Installation failed: {}", stderr));
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
     }
 
-    Ok(())
+    let error = "Installation finished but Caddy is not responding".to_string();
+    emit_install_progress(app_handle, "verifying", "failed", Some(error.clone()));
+    Err(AppError::proxy_unavailable(error))
 }
 
-pub async fn start_service() -> Result<(), String> {
+pub async fn start_service() -> Result<(), AppError> {
     let osascript_command = r#"do shell script "launchctl load -w /Library/LaunchDaemons/com.my-little-apps.caddy.plist 2>/dev/null; exit 0" with administrator privileges"#;
 
     let output = Command::new("osascript")
@@ -116,17 +224,22 @@ pub async fn start_service() -> Result<(), String> {
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         if stderr.contains("User canceled") || stderr.contains("canceled") {
-            return Err("Operation was cancelled by user".to_string());
+            return Err(AppError::permission_denied(
+                "Operation was cancelled by user",
+            ));
         }
         if !stderr.contains("already loaded") {
-            return Err(format!("Failed to start service: {}", stderr));
+            return Err(AppError::spawn_failed(
+                format!("Failed to start service: {}", stderr),
+                stderr,
+            ));
         }
     }
 
     Ok(())
 }
 
-pub async fn stop_service() -> Result<(), String> {
+pub async fn stop_service() -> Result<(), AppError> {
     let osascript_command = r#"do shell script "launchctl unload /Library/LaunchDaemons/com.my-little-apps.caddy.plist 2>/dev/null; exit 0" with administrator privileges"#;
 
     let output = Command::new("osascript")
@@ -137,29 +250,89 @@ pub async fn stop_service() -> Result<(), String> {
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         if stderr.contains("User canceled") || stderr.contains("canceled") {
-            return Err("Operation was cancelled by user".to_string());
+            return Err(AppError::permission_denied(
+                "Operation was cancelled by user",
+            ));
         }
     }
 
     Ok(())
 }
 
-pub async fn uninstall_service(app_handle: &tauri::AppHandle) -> Result<(), String> {
+// Everything install-proxy.sh creates, checked again after uninstalling
+const SERVICE_PATHS: [&str; 5] = [
+    "/Library/LaunchDaemons/com.my-little-apps.caddy.plist",
+    "/usr/local/bin/my-little-apps",
+    "/usr/local/etc/my-little-apps",
+    "/usr/local/var/log/my-little-apps",
+    "/usr/local/var/run/my-little-apps",
+];
+
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct UninstallReport {
+    /// Leftovers removed by the follow-up cleanup pass
+    pub cleaned: Vec<String>,
+    /// Paths that still exist and need to be removed by hand
+    pub remaining: Vec<String>,
+    pub caddy_running: bool,
+}
+
+impl UninstallReport {
+    pub fn is_clean(&self) -> bool {
+        self.remaining.is_empty() && !self.caddy_running
+    }
+}
+
+fn existing_service_paths(resolver_domain: Option<&str>) -> Vec<String> {
+    let resolver = resolver_domain.map(|domain| format!("/etc/resolver/{}", domain));
+    SERVICE_PATHS
+        .iter()
+        .map(|path| path.to_string())
+        .chain(resolver)
+        .filter(|path| PathBuf::from(path).exists())
+        .collect()
+}
+
+fn remove_paths_as_admin(paths: &[String]) -> Result<(), String> {
+    let quoted: Vec<String> = paths.iter().map(|p| format!("'{}'", p)).collect();
+    let osascript_command = format!(
+        r#"do shell script "launchctl unload '{}' 2>/dev/null; rm -rf {}" with administrator privileges"#,
+        SERVICE_PATHS[0],
+        quoted.join(" ")
+    );
+
+    let output = Command::new("osascript")
+        .args(["-e", &osascript_command])
+        .output()
+        .map_err(|e| format!("Failed to remove leftover files: {}", e))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(())
+}
+
+/// Runs the uninstall script, then checks what it left behind and retries removing it once.
+pub async fn uninstall_service(
+    app_handle: &tauri::AppHandle,
+    resolver_domain: Option<&str>,
+) -> Result<UninstallReport, AppError> {
     let resource_path = get_resource_path(app_handle)?;
     let uninstall_script = resource_path.join("uninstall-proxy.sh");
 
     if !uninstall_script.exists() {
-        return Err(format!(
+        return Err(AppError::not_found(format!(
             "Uninstall script not found at {:?}",
             uninstall_script
-        ));
+        )));
     }
 
     let uninstall_script_str = uninstall_script.to_str().ok_or("Invalid script path")?;
 
     let osascript_command = format!(
-        r#"do shell script "bash '{}'" with administrator privileges"#,
-        uninstall_script_str
+        r#"do shell script "bash '{}' '{}'" with administrator privileges"#,
+        uninstall_script_str,
+        resolver_domain.unwrap_or_default()
     );
 
     let output = Command::new("osascript")
@@ -170,10 +343,560 @@ pub async fn uninstall_service(app_handle: &tauri::AppHandle) -> Result<(), Stri
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         if stderr.contains("User canceled") || stderr.contains("canceled") {
-            return Err("Uninstallation was cancelled by user".to_string());
+            return Err(AppError::permission_denied(
+                "Uninstallation was cancelled by user",
+            ));
+        }
+        return Err(AppError::spawn_failed(
+            format!("Uninstallation failed: {}", stderr),
+            stderr,
+        ));
+    }
+
+    let leftovers = existing_service_paths(resolver_domain);
+    if !leftovers.is_empty() {
+        log::warn!(
+            "Uninstall left {} path(s) behind, retrying",
+            leftovers.len()
+        );
+        if let Err(e) = remove_paths_as_admin(&leftovers) {
+            log::error!("Leftover cleanup failed: {}", e);
+        }
+    }
+
+    let remaining = existing_service_paths(resolver_domain);
+    Ok(UninstallReport {
+        cleaned: leftovers
+            .into_iter()
+            .filter(|path| !remaining.contains(path))
+            .collect(),
+        remaining,
+        caddy_running: is_caddy_running(),
+    })
+}
+
+/// Points macOS at the built-in DNS responder for `domain` via /etc/resolver.
+pub async fn install_resolver(domain: &str, port: u16) -> Result<(), AppError> {
+    let osascript_command = format!(
+        r#"do shell script "mkdir -p /etc/resolver && printf 'nameserver 127.0.0.1\\nport {}\\n' > '/etc/resolver/{}'" with administrator privileges"#,
+        port, domain
+    );
+
+    let output = Command::new("osascript")
+        .args(["-e", &osascript_command])
+        .output()
+        .map_err(|e| format!("Failed to install resolver: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("User canceled") || stderr.contains("canceled") {
+            return Err(AppError::permission_denied(
+                "Operation was cancelled by user",
+            ));
         }
-        return Err(format!("Uninstallation failed: {}", stderr));
+        return Err(AppError::spawn_failed(
+            format!("Failed to install resolver: {}", stderr),
+            stderr,
+        ));
     }
 
     Ok(())
 }
+
+pub async fn remove_resolver(domain: &str) -> Result<(), AppError> {
+    let osascript_command = format!(
+        r#"do shell script "rm -f '/etc/resolver/{}'" with administrator privileges"#,
+        domain
+    );
+
+    let output = Command::new("osascript")
+        .args(["-e", &osascript_command])
+        .output()
+        .map_err(|e| format!("Failed to remove resolver: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("User canceled") || stderr.contains("canceled") {
+            return Err(AppError::permission_denied(
+                "Operation was cancelled by user",
+            ));
+        }
+        return Err(AppError::spawn_failed(
+            format!("Failed to remove resolver: {}", stderr),
+            stderr,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Something on this Mac that keeps `.local` app addresses from working, typically set up
+/// by a VPN client, a DNS filter or a corporate profile.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NetworkIssue {
+    // A VPN tunnel carries traffic for the LAN too, so other devices can't reach the apps
+    LanThroughVpn {
+        tunnel: String,
+        lan_interface: String,
+        lan_subnet: String,
+    },
+    // A local DNS proxy (dnscrypt-proxy, NextDNS, ...) answers every lookup
+    DnsOverride {
+        nameserver: String,
+    },
+    // The responder's /etc/resolver entry exists but macOS doesn't use it
+    ResolverBypassed {
+        domain: String,
+    },
+    // http:// requests go to a proxy server that isn't told to leave local names alone
+    ProxyNotExcluded {
+        service: String,
+        bypass: Vec<String>,
+    },
+}
+
+impl NetworkIssue {
+    pub fn message(&self) -> String {
+        match self {
+            NetworkIssue::LanThroughVpn {
+                tunnel, lan_subnet, ..
+            } => format!(
+                "a VPN routes {} through {}, so other devices can't reach your apps",
+                lan_subnet, tunnel
+            ),
+            NetworkIssue::DnsOverride { nameserver } => format!(
+                "a local DNS proxy at {} answers all lookups; have it forward your app domain to 127.0.0.1:{} if addresses don't resolve",
+                nameserver,
+                crate::dns_responder::RESPONDER_PORT
+            ),
+            NetworkIssue::ResolverBypassed { domain } => format!(
+                "macOS ignores /etc/resolver/{}, likely because a VPN or profile took over DNS",
+                domain
+            ),
+            NetworkIssue::ProxyNotExcluded { service, .. } => format!(
+                "{} sends web traffic to a proxy that doesn't exclude *.local",
+                service
+            ),
+        }
+    }
+
+    /// Whether `apply_network_workarounds` can do something about it.
+    pub fn has_workaround(&self) -> bool {
+        !matches!(self, NetworkIssue::DnsOverride { .. })
+    }
+}
+
+const TUNNEL_INTERFACES: [&str; 5] = ["utun", "ipsec", "ppp", "tun", "tap"];
+
+// Bypass entries that already keep .local names away from a proxy
+const LOCAL_BYPASS: [&str; 3] = ["*.local", ".local", "local"];
+
+/// Looks for VPNs, DNS overrides and system proxies that break `.local` routing.
+/// `dns_domain` is the DNS responder's domain when it's enabled.
+pub fn network_issues(dns_domain: Option<&str>) -> Vec<NetworkIssue> {
+    let mut issues = Vec::new();
+
+    if let Some((lan_interface, ip)) = lan_address() {
+        let mask = command_output("ipconfig", &["getoption", lan_interface, "subnet_mask"]);
+        let network = ip
+            .parse()
+            .ok()
+            .zip(mask.and_then(|mask| mask.trim().parse().ok()))
+            .map(|(ip, mask)| lan_network(ip, mask));
+        if let Some((lan_subnet, probe)) = network {
+            let route = command_output("route", &["-n", "get", &probe.to_string()]);
+            if let Some(tunnel) = route.as_deref().and_then(route_interface) {
+                if is_tunnel(&tunnel) {
+                    issues.push(NetworkIssue::LanThroughVpn {
+                        tunnel,
+                        lan_interface: lan_interface.to_string(),
+                        lan_subnet,
+                    });
+                }
+            }
+        }
+    }
+
+    let resolvers = command_output("scutil", &["--dns"]).unwrap_or_default();
+    if let Some(nameserver) = default_nameserver(&resolvers).filter(|ns| is_loopback(ns)) {
+        issues.push(NetworkIssue::DnsOverride { nameserver });
+    }
+    if let Some(domain) = dns_domain {
+        let file = PathBuf::from("/etc/resolver").join(domain);
+        if file.exists() && !has_scoped_resolver(&resolvers, domain) {
+            issues.push(NetworkIssue::ResolverBypassed {
+                domain: domain.to_string(),
+            });
+        }
+    }
+
+    let services = command_output("networksetup", &["-listallnetworkservices"]);
+    for service in network_services(services.as_deref().unwrap_or_default()) {
+        let proxy = command_output("networksetup", &["-getwebproxy", &service]);
+        if !proxy.as_deref().is_some_and(web_proxy_enabled) {
+            continue;
+        }
+        let bypass = command_output("networksetup", &["-getproxybypassdomains", &service])
+            .map(|output| bypass_domains(&output))
+            .unwrap_or_default();
+        if !bypass
+            .iter()
+            .any(|domain| LOCAL_BYPASS.contains(&domain.as_str()))
+        {
+            issues.push(NetworkIssue::ProxyNotExcluded { service, bypass });
+        }
+    }
+
+    issues
+}
+
+/// Works around what it can of `issues` in one administrator prompt: a route keeping the
+/// LAN off the VPN tunnel, a resolver entry macOS prefers over the VPN's, and a
+/// `*.local` proxy exception. Returns what was done. The route lasts until the VPN
+/// reconnects or the Mac restarts.
+pub async fn apply_network_workarounds(issues: &[NetworkIssue]) -> Result<Vec<String>, AppError> {
+    let mut script = Vec::new();
+    let mut applied = Vec::new();
+    for issue in issues {
+        match issue {
+            NetworkIssue::LanThroughVpn {
+                lan_interface,
+                lan_subnet,
+                ..
+            } => {
+                script.push(format!(
+                    "route -n add -net {} -interface {}",
+                    shell_quote(lan_subnet),
+                    shell_quote(lan_interface)
+                ));
+                applied.push(format!("routed {} through {}", lan_subnet, lan_interface));
+            }
+            NetworkIssue::ResolverBypassed { domain } => {
+                script.push(format!(
+                    "printf 'nameserver 127.0.0.1\\nport {}\\nsearch_order 1\\n' > {}",
+                    crate::dns_responder::RESPONDER_PORT,
+                    shell_quote(&format!("/etc/resolver/{}", domain))
+                ));
+                script.push("killall -HUP mDNSResponder".to_string());
+                applied.push(format!("gave /etc/resolver/{} priority", domain));
+            }
+            NetworkIssue::ProxyNotExcluded { service, bypass } => {
+                let domains: Vec<String> = bypass
+                    .iter()
+                    .map(String::as_str)
+                    .chain(["*.local"])
+                    .map(shell_quote)
+                    .collect();
+                script.push(format!(
+                    "networksetup -setproxybypassdomains {} {}",
+                    shell_quote(service),
+                    domains.join(" ")
+                ));
+                applied.push(format!("excluded *.local from {}'s proxy", service));
+            }
+            NetworkIssue::DnsOverride { .. } => {}
+        }
+    }
+    if script.is_empty() {
+        return Ok(applied);
+    }
+
+    run_as_admin(&script.join(" && "), "apply network workarounds").await?;
+    Ok(applied)
+}
+
+// Runs a shell script as root behind the administrator prompt; `action` completes
+// "Failed to ..."
+async fn run_as_admin(script: &str, action: &str) -> Result<(), AppError> {
+    let osascript_command = format!(
+        r#"do shell script "{}" with administrator privileges"#,
+        applescript_escape(script)
+    );
+    let output = tokio::process::Command::new("osascript")
+        .args(["-e", &osascript_command])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to {}: {}", action, e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("User canceled") || stderr.contains("canceled") {
+            return Err(AppError::permission_denied(
+                "Operation was cancelled by user",
+            ));
+        }
+        return Err(AppError::spawn_failed(
+            format!("Failed to {}: {}", action, stderr),
+            stderr,
+        ));
+    }
+    Ok(())
+}
+
+/// Top-level domain the DNS responder moves to when `.local` lookups never reach it.
+pub const FALLBACK_TLD: &str = "test";
+// Looked up to see whether the DNS responder answers; no app is ever called this
+const PROBE_LABEL: &str = "my-little-apps-probe";
+const PROBE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// Whether names under `domain` resolve through the DNS responder. Some macOS versions
+/// hand every `.local` name to multicast DNS first and never ask /etc/resolver.
+pub async fn resolves_through_responder(domain: &str) -> bool {
+    let host = format!("{}.{}", PROBE_LABEL, domain);
+    // mDNSResponder takes a moment to pick up a new /etc/resolver entry
+    for attempt in 0..2 {
+        if attempt > 0 {
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        }
+        let host = host.clone();
+        let lookup = tokio::task::spawn_blocking(move || {
+            use std::net::ToSocketAddrs;
+            (host.as_str(), 80)
+                .to_socket_addrs()
+                .is_ok_and(|mut addrs| addrs.any(|addr| addr.ip().is_loopback()))
+        });
+        if let Ok(Ok(true)) = tokio::time::timeout(PROBE_TIMEOUT, lookup).await {
+            return true;
+        }
+    }
+    false
+}
+
+/// The domain to use instead of a `.local` one that multicast DNS shadows, e.g.
+/// "macbook.local" -> "macbook.test". None when `domain` isn't under `.local`.
+pub fn fallback_domain(domain: &str) -> Option<String> {
+    let name = domain.strip_suffix(".local")?;
+    Some(format!("{}.{}", name, FALLBACK_TLD))
+}
+
+/// Replaces the /etc/resolver entry for `from` with one for `to`, in a single prompt.
+pub async fn move_resolver(from: &str, to: &str, port: u16) -> Result<(), AppError> {
+    let script = format!(
+        "rm -f {} && printf 'nameserver 127.0.0.1\\nport {}\\n' > {}",
+        shell_quote(&format!("/etc/resolver/{}", from)),
+        port,
+        shell_quote(&format!("/etc/resolver/{}", to))
+    );
+    run_as_admin(&script, "move resolver").await
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// The network `ip` is on as "a.b.c.d/n", and another address on it to look up a route for
+fn lan_network(ip: std::net::Ipv4Addr, mask: std::net::Ipv4Addr) -> (String, std::net::Ipv4Addr) {
+    let bits = u32::from(mask);
+    let network = u32::from(ip) & bits;
+    let mut probe = network.wrapping_add(1);
+    if probe == u32::from(ip) {
+        probe = probe.wrapping_add(1);
+    }
+    (
+        format!(
+            "{}/{}",
+            std::net::Ipv4Addr::from(network),
+            bits.count_ones()
+        ),
+        std::net::Ipv4Addr::from(probe),
+    )
+}
+
+// The `interface:` line of `route -n get`
+fn route_interface(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let value = line.trim().strip_prefix("interface:")?;
+        Some(value.trim().to_string())
+    })
+}
+
+fn is_tunnel(interface: &str) -> bool {
+    TUNNEL_INTERFACES
+        .iter()
+        .any(|prefix| interface.starts_with(prefix))
+}
+
+fn is_loopback(address: &str) -> bool {
+    address
+        .parse::<std::net::IpAddr>()
+        .is_ok_and(|ip| ip.is_loopback())
+}
+
+// `scutil --dns` resolvers as key/value lines, leaving out the scoped-query section
+fn resolver_blocks(output: &str) -> Vec<Vec<(&str, &str)>> {
+    let general = output
+        .split("DNS configuration (for scoped queries)")
+        .next()
+        .unwrap_or_default();
+    general
+        .split("resolver #")
+        .skip(1)
+        .map(|block| {
+            block
+                .lines()
+                .filter_map(|line| line.split_once(" : "))
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .collect()
+        })
+        .collect()
+}
+
+// First nameserver of the resolver used for names no other resolver claims
+fn default_nameserver(output: &str) -> Option<String> {
+    resolver_blocks(output)
+        .into_iter()
+        .find(|block| !block.iter().any(|(key, _)| *key == "domain"))?
+        .into_iter()
+        .find(|(key, _)| *key == "nameserver[0]")
+        .map(|(_, value)| value.to_string())
+}
+
+// Whether macOS sends lookups for `domain` to the DNS responder
+fn has_scoped_resolver(output: &str, domain: &str) -> bool {
+    resolver_blocks(output).iter().any(|block| {
+        block.contains(&("domain", domain))
+            && block.contains(&("nameserver[0]", "127.0.0.1"))
+            && block.iter().any(|(key, value)| {
+                *key == "port" && value.parse() == Ok(crate::dns_responder::RESPONDER_PORT)
+            })
+    })
+}
+
+// `networksetup -listallnetworkservices` without its header and disabled services
+fn network_services(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .skip(1)
+        .map(str::trim)
+        .filter(|service| !service.is_empty() && !service.starts_with('*'))
+        .map(String::from)
+        .collect()
+}
+
+fn web_proxy_enabled(output: &str) -> bool {
+    output
+        .lines()
+        .any(|line| line.trim().eq_ignore_ascii_case("Enabled: Yes"))
+}
+
+fn bypass_domains(output: &str) -> Vec<String> {
+    if output.contains("There aren't any bypass domains") {
+        return Vec::new();
+    }
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+// For a shell script inside an AppleScript string literal
+fn applescript_escape(script: &str) -> String {
+    script.replace('\\', r"\\").replace('"', r#"\""#)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCUTIL_DNS: &str = "DNS configuration
+
+resolver #1
+  search domain[0] : lan
+  nameserver[0] : 127.0.0.1
+  if_index : 15 (en0)
+  flags    : Request A records
+  reach    : 0x00030002 (Reachable,Local Address,Directly Reachable Address)
+
+resolver #2
+  domain   : local
+  options  : mdns
+  timeout  : 5
+
+resolver #3
+  domain   : macbook.local
+  nameserver[0] : 127.0.0.1
+  port     : 53535
+  flags    : Request A records, Request AAAA records
+
+DNS configuration (for scoped queries)
+
+resolver #1
+  domain   : corp.local
+  nameserver[0] : 10.0.0.2
+";
+
+    #[test]
+    fn test_resolvers() {
+        assert_eq!(
+            default_nameserver(SCUTIL_DNS),
+            Some("127.0.0.1".to_string())
+        );
+        assert!(has_scoped_resolver(SCUTIL_DNS, "macbook.local"));
+        assert!(!has_scoped_resolver(SCUTIL_DNS, "studio.local"));
+        // Scoped-query resolvers don't answer ordinary lookups
+        assert!(!has_scoped_resolver(SCUTIL_DNS, "corp.local"));
+        assert!(is_loopback("127.0.0.1") && is_loopback("::1"));
+        assert!(!is_loopback("192.168.1.1"));
+    }
+
+    #[test]
+    fn test_lan_routes() {
+        let ip = "192.168.1.20".parse().unwrap();
+        let mask = "255.255.255.0".parse().unwrap();
+        assert_eq!(
+            lan_network(ip, mask),
+            ("192.168.1.0/24".to_string(), "192.168.1.1".parse().unwrap())
+        );
+        let gateway = "10.0.0.1".parse().unwrap();
+        assert_eq!(
+            lan_network(gateway, "255.0.0.0".parse().unwrap()).1,
+            "10.0.0.2".parse::<std::net::Ipv4Addr>().unwrap()
+        );
+
+        let route = "   route to: 192.168.1.1\ndestination: default\n  interface: utun4\n      flags: <UP,DONE>\n";
+        assert_eq!(route_interface(route), Some("utun4".to_string()));
+        assert!(is_tunnel("utun4") && is_tunnel("ppp0"));
+        assert!(!is_tunnel("en0"));
+    }
+
+    #[test]
+    fn test_fallback_domain() {
+        assert_eq!(
+            fallback_domain("macbook.local"),
+            Some("macbook.test".to_string())
+        );
+        assert_eq!(fallback_domain("macbook.test"), None);
+    }
+
+    #[test]
+    fn test_system_proxy() {
+        let services = "An asterisk (*) denotes that a network service is disabled.\nWi-Fi\n*Thunderbolt Bridge\nJohn's iPhone\n";
+        assert_eq!(network_services(services), ["Wi-Fi", "John's iPhone"]);
+        assert!(web_proxy_enabled(
+            "Enabled: Yes\nServer: proxy.corp\nPort: 8080\n"
+        ));
+        assert!(!web_proxy_enabled("Enabled: No\nServer: \nPort: 0\n"));
+        assert_eq!(
+            bypass_domains("169.254/16\n*.corp\n"),
+            ["169.254/16", "*.corp"]
+        );
+        assert!(bypass_domains("There aren't any bypass domains set on Wi-Fi.\n").is_empty());
+
+        assert_eq!(shell_quote("John's iPhone"), r"'John'\''s iPhone'");
+        assert_eq!(
+            applescript_escape(r#"printf 'a\n' "b""#),
+            r#"printf 'a\\n' \"b\""#
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use std::net::Ipv4Addr;
+use tokio::net::UdpSocket;
+use tokio::sync::{oneshot, watch, Mutex};
+
+// Unprivileged port the /etc/resolver entry points at
+pub const RESPONDER_PORT: u16 = 53535;
+const ANSWER_TTL: u32 = 60;
+
+const TYPE_A: u16 = 1;
+const CLASS_IN: u16 = 1;
+const RCODE_FORMAT_ERROR: u8 = 1;
+const RCODE_REFUSED: u8 = 5;
+
+struct Server {
+    // Swapped in place, so a new domain never has to rebind the port
+    domain: watch::Sender<String>,
+    shutdown: oneshot::Sender<()>,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+impl Server {
+    // Waits for the task so the socket is closed once this returns
+    async fn shut_down(self) {
+        let _ = self.shutdown.send(());
+        let _ = self.task.await;
+    }
+}
+
+/// Answers A queries for `*.<domain>` with the loopback address, refusing everything else.
+pub struct DnsResponder {
+    server: Mutex<Option<Server>>,
+}
+
+impl DnsResponder {
+    pub fn new() -> Self {
+        Self {
+            server: Mutex::new(None),
+        }
+    }
+
+    pub async fn start(&self, domain: &str) -> Result<(), String> {
+        let mut server = self.server.lock().await;
+        if let Some(running) = server.as_ref() {
+            if *running.domain.borrow() != domain {
+                running.domain.send_replace(domain.to_string());
+                log::info!("DNS responder answering for *.{}", domain);
+            }
+            return Ok(());
+        }
+
+        let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, RESPONDER_PORT))
+            .await
+            .map_err(|e| {
+                format!(
+                    "Failed to start DNS responder on port {}: {}",
+                    RESPONDER_PORT, e
+                )
+            })?;
+        let (domain_tx, domain_rx) = watch::channel(domain.to_string());
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let task = tauri::async_runtime::spawn(run_server(socket, domain_rx, shutdown_rx));
+
+        *server = Some(Server {
+            domain: domain_tx,
+            shutdown: shutdown_tx,
+            task,
+        });
+        log::info!("DNS responder answering for *.{}", domain);
+        Ok(())
+    }
+
+    pub async fn stop(&self) {
+        if let Some(server) = self.server.lock().await.take() {
+            server.shut_down().await;
+        }
+    }
+
+    pub async fn domain(&self) -> Option<String> {
+        let server = self.server.lock().await;
+        server.as_ref().map(|s| s.domain.borrow().clone())
+    }
+}
+
+impl Default for DnsResponder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+async fn run_server(
+    socket: UdpSocket,
+    domain: watch::Receiver<String>,
+    mut shutdown: oneshot::Receiver<()>,
+) {
+    let mut buf = [0u8; 512];
+    loop {
+        let (len, from) = tokio::select! {
+            _ = &mut shutdown => break,
+            received = socket.recv_from(&mut buf) => match received {
+                Ok(received) => received,
+                Err(e) => {
+                    log::error!("DNS responder receive failed: {}", e);
+                    continue;
+                }
+            },
+        };
+
+        // Not inline in the `if let`, which would hold the borrow across the send
+        let response = build_response(&buf[..len], &domain.borrow(), Ipv4Addr::LOCALHOST);
+        if let Some(response) = response {
+            let _ = socket.send_to(&response, from).await;
+        }
+    }
+}
+
+/// Builds the reply for a single-question query; None for packets not worth answering.
+fn build_response(query: &[u8], domain: &str, address: Ipv4Addr) -> Option<Vec<u8>> {
+    if query.len() < 12 || query[2] & 0x80 != 0 {
+        return None;
+    }
+
+    let question = parse_question(query);
+    let mut response = Vec::with_capacity(query.len() + 16);
+    response.extend_from_slice(&query[..2]);
+    // QR + opcode + AA + RD from the query, no recursion available
+    response.push(0x80 | (query[2] & 0x78) | 0x04 | (query[2] & 0x01));
+
+    let Some((name, qtype, qclass, end)) = question else {
+        response.push(RCODE_FORMAT_ERROR);
+        response.extend_from_slice(&[0; 8]);
+        return Some(response);
+    };
+
+    let in_domain = name == domain || name.ends_with(&format!(".{}", domain));
+    let answer = in_domain && qtype == TYPE_A && qclass == CLASS_IN;
+
+    response.push(if in_domain { 0 } else { RCODE_REFUSED });
+    response.extend_from_slice(&1u16.to_be_bytes());
+    response.extend_from_slice(&u16::from(answer).to_be_bytes());
+    response.extend_from_slice(&[0; 4]);
+    response.extend_from_slice(&query[12..end]);
+
+    if answer {
+        // Name is a pointer back to the question at offset 12
+        response.extend_from_slice(&[0xc0, 0x0c]);
+        response.extend_from_slice(&TYPE_A.to_be_bytes());
+        response.extend_from_slice(&CLASS_IN.to_be_bytes());
+        response.extend_from_slice(&ANSWER_TTL.to_be_bytes());
+        response.extend_from_slice(&4u16.to_be_bytes());
+        response.extend_from_slice(&address.octets());
+    }
+
+    Some(response)
+}
+
+// Returns the lowercased name, type, class and the offset just past the question
+fn parse_question(query: &[u8]) -> Option<(String, u16, u16, usize)> {
+    if u16::from_be_bytes([query[4], query[5]]) != 1 {
+        return None;
+    }
+
+    let mut labels = Vec::new();
+    let mut pos = 12;
+    loop {
+        let len = *query.get(pos)? as usize;
+        pos += 1;
+        if len == 0 {
+            break;
+        }
+        // Compression pointers never appear in a question we need to answer
+        if len & 0xc0 != 0 {
+            return None;
+        }
+        let label = query.get(pos..pos + len)?;
+        labels.push(String::from_utf8_lossy(label).to_lowercase());
+        pos += len;
+    }
+
+    let fields = query.get(pos..pos + 4)?;
+    let qtype = u16::from_be_bytes([fields[0], fields[1]]);
+    let qclass = u16::from_be_bytes([fields[2], fields[3]]);
+    Some((labels.join("."), qtype, qclass, pos + 4))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(name: &str, qtype: u16) -> Vec<u8> {
+        let mut packet = vec![0x12, 0x34, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0];
+        for label in name.split('.') {
+            packet.push(label.len() as u8);
+            packet.extend_from_slice(label.as_bytes());
+        }
+        packet.push(0);
+        packet.extend_from_slice(&qtype.to_be_bytes());
+        packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+        packet
+    }
+
+    #[test]
+    fn test_answers_names_in_domain() {
+        let request = query("My-App.macbook.local", TYPE_A);
+        let response = build_response(&request, "macbook.local", Ipv4Addr::LOCALHOST).unwrap();
+
+        assert_eq!(&response[..2], &[0x12, 0x34]);
+        assert_eq!(response[3] & 0x0f, 0);
+        assert_eq!(&response[6..8], &[0, 1]);
+        assert_eq!(&response[response.len() - 4..], &[127, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_empty_answer_for_other_types() {
+        let request = query("my-app.macbook.local", 28);
+        let response = build_response(&request, "macbook.local", Ipv4Addr::LOCALHOST).unwrap();
+
+        assert_eq!(response[3] & 0x0f, 0);
+        assert_eq!(&response[6..8], &[0, 0]);
+        assert_eq!(response.len(), request.len());
+    }
+
+    #[test]
+    fn test_refuses_other_domains() {
+        let request = query("example.com", TYPE_A);
+        let response = build_response(&request, "macbook.local", Ipv4Addr::LOCALHOST).unwrap();
+
+        assert_eq!(response[3] & 0x0f, RCODE_REFUSED);
+        assert_eq!(&response[6..8], &[0, 0]);
+    }
+
+    #[test]
+    fn test_ignores_responses_and_short_packets() {
+        let mut request = query("my-app.macbook.local", TYPE_A);
+        request[2] |= 0x80;
+        assert!(build_response(&request, "macbook.local", Ipv4Addr::LOCALHOST).is_none());
+        assert!(build_response(&[0; 4], "macbook.local", Ipv4Addr::LOCALHOST).is_none());
+    }
+}
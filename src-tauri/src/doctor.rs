@@ -0,0 +1,450 @@
+use serde::{Deserialize, Serialize};
+use std::io::ErrorKind;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::dns::NetworkIssue;
+use crate::proxy::ProxyBackend;
+
+// Apps start through the same login shell, so a slow one is worth reporting, not waiting on
+const SHELL_TIMEOUT: Duration = Duration::from_secs(5);
+// Apple silicon installs; Intel Homebrew lives in /usr/local/bin, which is on PATH anyway
+const HOMEBREW_BIN: &str = "/opt/homebrew/bin";
+
+/// The checks `run` performs, in the order they're shown.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Check {
+    Bun,
+    Node,
+    Path,
+    ProxyPort,
+    DnsPort,
+    Proxy,
+    Resolver,
+    Network,
+    Docker,
+}
+
+const CHECKS: [Check; 9] = [
+    Check::Bun,
+    Check::Node,
+    Check::Path,
+    Check::ProxyPort,
+    Check::DnsPort,
+    Check::Proxy,
+    Check::Resolver,
+    Check::Network,
+    Check::Docker,
+];
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    // Works, but something may not
+    Warning,
+    Error,
+}
+
+/// Something that can be done about a failed check, performed by `run_doctor_fix`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DoctorFix {
+    InstallProxy,
+    StartProxy,
+    // Writes the /etc/resolver entry again
+    EnableDnsResponder,
+    // Routes, resolver priority and proxy exceptions around VPNs, see `dns::network_issues`
+    NetworkWorkarounds,
+    StartDocker,
+    OpenUrl { url: String },
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct CheckResult {
+    pub check: Check,
+    pub label: &'static str,
+    pub status: CheckStatus,
+    pub message: String,
+    pub fix: Option<DoctorFix>,
+}
+
+/// What the checks need to know about the app's own setup.
+pub struct DoctorContext {
+    pub shell: String,
+    pub shell_args: Vec<&'static str>,
+    pub backend: ProxyBackend,
+    pub proxy_installed: bool,
+    pub proxy_responsive: bool,
+    // Where the proxy accepts requests: 80 for Caddy, the built-in proxy's own port otherwise
+    pub proxy_port: u16,
+    pub dns_domain: Option<String>,
+}
+
+pub async fn run(context: &DoctorContext) -> Vec<CheckResult> {
+    let mut results = Vec::new();
+    for check in CHECKS {
+        results.push(run_check(check, context).await);
+    }
+    results
+}
+
+async fn run_check(check: Check, context: &DoctorContext) -> CheckResult {
+    match check {
+        Check::Bun => check_tool(check, "bun", "https://bun.sh", context).await,
+        Check::Node => check_tool(check, "node", "https://nodejs.org", context).await,
+        Check::Path => check_path(context).await,
+        Check::ProxyPort => check_proxy_port(context).await,
+        Check::DnsPort => check_dns_port().await,
+        Check::Proxy => check_proxy(context),
+        Check::Resolver => check_resolver(context).await,
+        Check::Network => check_network(context).await,
+        Check::Docker => check_docker(context).await,
+    }
+}
+
+fn result(check: Check, label: &'static str, status: CheckStatus, message: String) -> CheckResult {
+    CheckResult {
+        check,
+        label,
+        status,
+        message,
+        fix: None,
+    }
+}
+
+impl CheckResult {
+    fn with_fix(mut self, fix: DoctorFix) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+}
+
+// Stdout of `script` in the login shell, None when it fails
+async fn shell_output(context: &DoctorContext, script: &str) -> Result<Option<String>, String> {
+    let mut cmd = tokio::process::Command::new(&context.shell);
+    cmd.args(&context.shell_args)
+        .arg(script)
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true);
+    let output = tokio::time::timeout(SHELL_TIMEOUT, cmd.output())
+        .await
+        .map_err(|_| {
+            format!(
+                "your {} login shell took over {}s to start",
+                context.shell,
+                SHELL_TIMEOUT.as_secs()
+            )
+        })?
+        .map_err(|e| format!("Failed to run {}: {}", context.shell, e))?;
+    Ok(output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string()))
+}
+
+async fn check_tool(
+    check: Check,
+    name: &'static str,
+    url: &str,
+    context: &DoctorContext,
+) -> CheckResult {
+    match shell_output(context, &format!("command -v {}", name)).await {
+        Ok(Some(path)) if !path.is_empty() => {
+            result(check, name, CheckStatus::Ok, format!("found at {}", path))
+        }
+        Ok(_) => result(
+            check,
+            name,
+            CheckStatus::Warning,
+            format!("{} is not on your PATH", name),
+        )
+        .with_fix(DoctorFix::OpenUrl {
+            url: url.to_string(),
+        }),
+        Err(e) => result(check, name, CheckStatus::Warning, e),
+    }
+}
+
+async fn check_path(context: &DoctorContext) -> CheckResult {
+    let label = "PATH";
+    let path = match shell_output(context, r#"printf '%s' "$PATH""#).await {
+        Ok(Some(path)) => path,
+        Ok(None) => {
+            let message = format!("{} exited with an error", context.shell);
+            return result(Check::Path, label, CheckStatus::Error, message);
+        }
+        Err(e) => return result(Check::Path, label, CheckStatus::Error, e),
+    };
+    let issues = path_issues(&path, |dir| Path::new(dir).is_dir());
+    if issues.is_empty() {
+        let message = format!("{} entries", path.split(':').count());
+        result(Check::Path, label, CheckStatus::Ok, message)
+    } else {
+        result(Check::Path, label, CheckStatus::Warning, issues.join("; "))
+    }
+}
+
+/// Problems with a login shell's PATH; `is_dir` says whether a directory exists.
+fn path_issues(path: &str, is_dir: impl Fn(&str) -> bool) -> Vec<String> {
+    let entries: Vec<&str> = path.split(':').filter(|entry| !entry.is_empty()).collect();
+    if entries.is_empty() {
+        return vec!["PATH is empty".to_string()];
+    }
+    let mut issues = Vec::new();
+    let missing: Vec<&str> = entries
+        .iter()
+        .copied()
+        .filter(|entry| !is_dir(entry))
+        .collect();
+    if !missing.is_empty() {
+        issues.push(format!("missing directories: {}", missing.join(", ")));
+    }
+    if is_dir(HOMEBREW_BIN) && !entries.contains(&HOMEBREW_BIN) {
+        issues.push(format!(
+            "Homebrew is installed but {} is not on PATH",
+            HOMEBREW_BIN
+        ));
+    }
+    issues
+}
+
+// Who listens on `port`, as "node (pid 123)"
+async fn port_holder(port: u16) -> Option<String> {
+    let ports = tokio::task::spawn_blocking(|| crate::process_info::listening_ports(&[]))
+        .await
+        .ok()?
+        .ok()?;
+    ports
+        .into_iter()
+        .find(|listener| listener.socket.port == port)
+        .map(|listener| format!("{} (pid {})", listener.socket.process, listener.socket.pid))
+}
+
+async fn check_proxy_port(context: &DoctorContext) -> CheckResult {
+    let port = context.proxy_port;
+    let label = "proxy port";
+    if context.proxy_responsive {
+        let message = format!("port {} is served by the proxy", port);
+        return result(Check::ProxyPort, label, CheckStatus::Ok, message);
+    }
+    match std::net::TcpListener::bind(("0.0.0.0", port)) {
+        Ok(_) => {
+            let message = format!("port {} is free", port);
+            result(Check::ProxyPort, label, CheckStatus::Ok, message)
+        }
+        Err(e) if e.kind() == ErrorKind::AddrInUse => {
+            let holder = port_holder(port)
+                .await
+                .unwrap_or_else(|| "another process".to_string());
+            let message = format!("port {} is taken by {}", port, holder);
+            result(Check::ProxyPort, label, CheckStatus::Error, message)
+        }
+        // Binding below 1024 can be refused outright, which says nothing about the port
+        Err(e) => {
+            let message = format!("could not check port {}: {}", port, e);
+            result(Check::ProxyPort, label, CheckStatus::Warning, message)
+        }
+    }
+}
+
+async fn check_dns_port() -> CheckResult {
+    let label = "DNS port";
+    match std::net::UdpSocket::bind(("0.0.0.0", 53)) {
+        Ok(_) => result(
+            Check::DnsPort,
+            label,
+            CheckStatus::Ok,
+            "port 53 is free".to_string(),
+        ),
+        Err(e) if e.kind() == ErrorKind::AddrInUse => {
+            let holder = port_holder(53)
+                .await
+                .unwrap_or_else(|| "another process".to_string());
+            let message = format!(
+                "{} runs a DNS server on port 53; if it answers .local names, app addresses may not resolve",
+                holder
+            );
+            result(Check::DnsPort, label, CheckStatus::Warning, message)
+        }
+        Err(e) => {
+            let message = format!("could not check port 53: {}", e);
+            result(Check::DnsPort, label, CheckStatus::Warning, message)
+        }
+    }
+}
+
+fn check_proxy(context: &DoctorContext) -> CheckResult {
+    let label = "proxy";
+    match context.backend {
+        ProxyBackend::Builtin if context.proxy_responsive => {
+            let message = format!("built-in proxy on port {}", context.proxy_port);
+            result(Check::Proxy, label, CheckStatus::Ok, message)
+        }
+        ProxyBackend::Builtin => result(
+            Check::Proxy,
+            label,
+            CheckStatus::Error,
+            "the built-in proxy is not running".to_string(),
+        ),
+        ProxyBackend::Caddy if !context.proxy_installed => result(
+            Check::Proxy,
+            label,
+            CheckStatus::Error,
+            "the proxy service is not installed".to_string(),
+        )
+        .with_fix(DoctorFix::InstallProxy),
+        ProxyBackend::Caddy if !context.proxy_responsive => result(
+            Check::Proxy,
+            label,
+            CheckStatus::Error,
+            "the proxy service is installed but not running".to_string(),
+        )
+        .with_fix(DoctorFix::StartProxy),
+        ProxyBackend::Caddy => result(
+            Check::Proxy,
+            label,
+            CheckStatus::Ok,
+            "Caddy is running".to_string(),
+        ),
+    }
+}
+
+async fn check_resolver(context: &DoctorContext) -> CheckResult {
+    let label = "resolver";
+    let Some(domain) = &context.dns_domain else {
+        let message = "not used; apps resolve over mDNS".to_string();
+        return result(Check::Resolver, label, CheckStatus::Ok, message);
+    };
+    let path = format!("/etc/resolver/{}", domain);
+    let content = std::fs::read_to_string(&path).unwrap_or_default();
+    if !points_at_responder(&content) {
+        let message = format!("{} is missing or points elsewhere", path);
+        return result(Check::Resolver, label, CheckStatus::Error, message)
+            .with_fix(DoctorFix::EnableDnsResponder);
+    }
+    if crate::dns::resolves_through_responder(domain).await {
+        let message = format!("{} points at the DNS responder", path);
+        result(Check::Resolver, label, CheckStatus::Ok, message)
+    } else if crate::dns::fallback_domain(domain).is_some() {
+        // Repairing moves the responder to the fallback domain
+        let message = format!(
+            "macOS answers .local names over multicast DNS before {}; repairing switches app addresses to .{}",
+            path,
+            crate::dns::FALLBACK_TLD
+        );
+        result(Check::Resolver, label, CheckStatus::Error, message)
+            .with_fix(DoctorFix::EnableDnsResponder)
+    } else {
+        let message = format!(
+            "{} is set up, but *.{} lookups don't reach the DNS responder",
+            path, domain
+        );
+        result(Check::Resolver, label, CheckStatus::Error, message)
+            .with_fix(DoctorFix::EnableDnsResponder)
+    }
+}
+
+// Whether a resolver file sends lookups to the built-in responder
+fn points_at_responder(content: &str) -> bool {
+    let mut nameserver = false;
+    let mut port = false;
+    for line in content.lines() {
+        let mut words = line.split_whitespace();
+        match (words.next(), words.next()) {
+            (Some("nameserver"), Some(address)) => nameserver |= address == "127.0.0.1",
+            (Some("port"), Some(value)) => {
+                port |= value.parse() == Ok(crate::dns_responder::RESPONDER_PORT)
+            }
+            _ => {}
+        }
+    }
+    nameserver && port
+}
+
+async fn check_network(context: &DoctorContext) -> CheckResult {
+    let label = "VPN & DNS";
+    let domain = context.dns_domain.clone();
+    let issues = tokio::task::spawn_blocking(move || crate::dns::network_issues(domain.as_deref()))
+        .await
+        .unwrap_or_default();
+    if issues.is_empty() {
+        let message = "nothing in the way of .local addresses".to_string();
+        return result(Check::Network, label, CheckStatus::Ok, message);
+    }
+    let messages: Vec<String> = issues.iter().map(NetworkIssue::message).collect();
+    let check = result(
+        Check::Network,
+        label,
+        CheckStatus::Warning,
+        messages.join("; "),
+    );
+    if issues.iter().any(NetworkIssue::has_workaround) {
+        check.with_fix(DoctorFix::NetworkWorkarounds)
+    } else {
+        check
+    }
+}
+
+async fn check_docker(context: &DoctorContext) -> CheckResult {
+    let label = "Docker";
+    if crate::prerequisites::docker_running().await {
+        return result(Check::Docker, label, CheckStatus::Ok, "running".to_string());
+    }
+    // Only database services and devcontainers need it
+    match shell_output(context, "command -v docker").await {
+        Ok(Some(_)) => result(
+            Check::Docker,
+            label,
+            CheckStatus::Warning,
+            "installed but not running; database services and devcontainers need it".to_string(),
+        )
+        .with_fix(DoctorFix::StartDocker),
+        _ => result(
+            Check::Docker,
+            label,
+            CheckStatus::Warning,
+            "not installed; database services and devcontainers need it".to_string(),
+        )
+        .with_fix(DoctorFix::OpenUrl {
+            url: "https://www.docker.com/products/docker-desktop/".to_string(),
+        }),
+    }
+}
+
+pub async fn start_docker() -> Result<(), String> {
+    let status = tokio::process::Command::new("open")
+        .args(["-a", "Docker"])
+        .status()
+        .await
+        .map_err(|e| format!("Failed to start Docker: {}", e))?;
+    if !status.success() {
+        return Err("Failed to start Docker".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_issues() {
+        let dirs = ["/usr/bin", "/bin", "/opt/homebrew/bin"];
+        let is_dir = |dir: &str| dirs.contains(&dir);
+        assert!(path_issues("/opt/homebrew/bin:/usr/bin:/bin", is_dir).is_empty());
+        assert_eq!(
+            path_issues("/usr/bin:/bin:/Users/me/.old/bin", is_dir),
+            [
+                "missing directories: /Users/me/.old/bin",
+                "Homebrew is installed but /opt/homebrew/bin is not on PATH"
+            ]
+        );
+        assert_eq!(path_issues("", is_dir), ["PATH is empty"]);
+
+        assert!(points_at_responder("nameserver 127.0.0.1\nport 53535\n"));
+        assert!(!points_at_responder("nameserver 127.0.0.1\n"));
+        assert!(!points_at_responder("nameserver 10.0.0.1\nport 53535\n"));
+    }
+}
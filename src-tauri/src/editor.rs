@@ -0,0 +1,247 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Editor {
+    #[serde(rename = "vscode")]
+    VsCode,
+    Cursor,
+    Zed,
+    #[serde(rename = "webstorm")]
+    WebStorm,
+}
+
+const DETECTION_ORDER: [Editor; 4] = [
+    Editor::Cursor,
+    Editor::VsCode,
+    Editor::Zed,
+    Editor::WebStorm,
+];
+
+/// Placeholders a command template can use; `{file}` is the project when there's no file.
+pub const PLACEHOLDERS: [&str; 4] = ["{path}", "{file}", "{line}", "{column}"];
+
+/// Which editor opens projects: a command template wins over the picked editor, and
+/// with neither the first installed editor is used.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct EditorPreference {
+    pub editor: Option<Editor>,
+    // e.g. `subl {file}:{line}`, run in the login shell from the project
+    pub command: Option<String>,
+}
+
+/// A place in a file, e.g. from a stack trace line.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct Location {
+    pub file: PathBuf,
+    pub line: u32,
+    pub column: Option<u32>,
+}
+
+impl Editor {
+    fn bundle_path(self) -> &'static str {
+        match self {
+            Editor::VsCode => "/Applications/Visual Studio Code.app",
+            Editor::Cursor => "/Applications/Cursor.app",
+            Editor::Zed => "/Applications/Zed.app",
+            Editor::WebStorm => "/Applications/WebStorm.app",
+        }
+    }
+
+    // The command line launcher inside the bundle, which can jump to a line
+    fn cli(self) -> PathBuf {
+        let cli = match self {
+            Editor::VsCode => "Contents/Resources/app/bin/code",
+            Editor::Cursor => "Contents/Resources/app/bin/cursor",
+            Editor::Zed => "Contents/MacOS/cli",
+            Editor::WebStorm => "Contents/MacOS/webstorm",
+        };
+        Path::new(self.bundle_path()).join(cli)
+    }
+
+    fn name(self) -> &'static str {
+        let bundle = self.bundle_path().trim_start_matches("/Applications/");
+        bundle.trim_end_matches(".app")
+    }
+
+    pub fn is_installed(self) -> bool {
+        Path::new(self.bundle_path()).exists()
+    }
+
+    pub fn installed() -> Vec<Editor> {
+        DETECTION_ORDER
+            .into_iter()
+            .filter(|e| e.is_installed())
+            .collect()
+    }
+}
+
+/// Rejects templates with placeholders other than `PLACEHOLDERS`; a blank one is None.
+pub fn check_preference(mut preference: EditorPreference) -> Result<EditorPreference, String> {
+    preference.command = preference
+        .command
+        .map(|command| command.trim().to_string())
+        .filter(|command| !command.is_empty());
+    if let Some(command) = &preference.command {
+        let mut rest = command.as_str();
+        while let Some(start) = rest.find('{') {
+            let end = rest[start..]
+                .find('}')
+                .map_or(rest.len(), |end| start + end + 1);
+            let placeholder = &rest[start..end];
+            if !PLACEHOLDERS.contains(&placeholder) {
+                return Err(format!(
+                    "Unknown placeholder {} in the editor command, use {}",
+                    placeholder,
+                    PLACEHOLDERS.join(", ")
+                ));
+            }
+            rest = &rest[end..];
+        }
+    }
+    Ok(preference)
+}
+
+/// Opens `project` in the editor from `preference`, at `location` when given.
+pub fn open(
+    preference: &EditorPreference,
+    project: &Path,
+    location: Option<&Location>,
+    shell: (String, Vec<&'static str>),
+) -> Result<(), String> {
+    let mut command = if let Some(template) = &preference.command {
+        let (shell, shell_args) = shell;
+        let mut command = Command::new(shell);
+        command
+            .args(shell_args)
+            .arg(fill_template(template, project, location));
+        command
+    } else {
+        let editor = preference
+            .editor
+            .or_else(|| Editor::installed().into_iter().next())
+            .ok_or("No supported editor found, set an editor command instead")?;
+        if !editor.is_installed() {
+            return Err(format!("{} isn't installed", editor.name()));
+        }
+        let mut command = Command::new(editor.cli());
+        command.args(editor_args(editor, project, location));
+        command
+    };
+    let mut child = command
+        .current_dir(project)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to open editor: {}", e))?;
+    // Some launchers stay around as the editor itself, so don't wait on them here
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+// The project goes first so the file opens in the project's window
+fn editor_args(editor: Editor, project: &Path, location: Option<&Location>) -> Vec<String> {
+    let mut args = vec![project.to_string_lossy().into_owned()];
+    let Some(location) = location else {
+        return args;
+    };
+    let file = location.file.to_string_lossy();
+    let position = match location.column {
+        Some(column) => format!("{}:{}:{}", file, location.line, column),
+        None => format!("{}:{}", file, location.line),
+    };
+    match editor {
+        Editor::VsCode | Editor::Cursor => args.extend(["--goto".to_string(), position]),
+        Editor::Zed => args.push(position),
+        Editor::WebStorm => {
+            args.extend(["--line".to_string(), location.line.to_string()]);
+            if let Some(column) = location.column {
+                args.extend(["--column".to_string(), column.to_string()]);
+            }
+            args.push(file.into_owned());
+        }
+    }
+    args
+}
+
+fn fill_template(template: &str, project: &Path, location: Option<&Location>) -> String {
+    let quote = |path: &Path| shell_quote(&path.to_string_lossy());
+    let file = location.map_or(project, |location| &location.file);
+    let line = location.map_or(1, |location| location.line);
+    let column = location.and_then(|location| location.column).unwrap_or(1);
+    template
+        .replace("{path}", &quote(project))
+        .replace("{file}", &quote(file))
+        .replace("{line}", &line.to_string())
+        .replace("{column}", &column.to_string())
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(column: Option<u32>) -> Location {
+        Location {
+            file: PathBuf::from("/Users/me/shop/src/app.ts"),
+            line: 12,
+            column,
+        }
+    }
+
+    #[test]
+    fn test_editor_args() {
+        let project = Path::new("/Users/me/shop");
+        assert_eq!(editor_args(Editor::Zed, project, None), ["/Users/me/shop"]);
+        assert_eq!(
+            editor_args(Editor::VsCode, project, Some(&location(Some(5)))),
+            ["/Users/me/shop", "--goto", "/Users/me/shop/src/app.ts:12:5"]
+        );
+        assert_eq!(
+            editor_args(Editor::Zed, project, Some(&location(None))),
+            ["/Users/me/shop", "/Users/me/shop/src/app.ts:12"]
+        );
+        assert_eq!(
+            editor_args(Editor::WebStorm, project, Some(&location(Some(5)))),
+            [
+                "/Users/me/shop",
+                "--line",
+                "12",
+                "--column",
+                "5",
+                "/Users/me/shop/src/app.ts"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_template() {
+        let project = Path::new("/Users/me/my shop");
+        assert_eq!(
+            fill_template("subl {file}:{line}:{column}", project, None),
+            "subl '/Users/me/my shop':1:1"
+        );
+        assert_eq!(
+            fill_template("nvim +{line} {file}", project, Some(&location(None))),
+            "nvim +12 '/Users/me/shop/src/app.ts'"
+        );
+
+        let check = |command: &str| {
+            check_preference(EditorPreference {
+                editor: None,
+                command: Some(command.to_string()),
+            })
+        };
+        assert_eq!(check("  ").unwrap().command, None);
+        assert!(check("idea --line {line} {file}").is_ok());
+        assert!(check("idea --line {lines} {file}").is_err());
+        assert!(check("code {path").is_err());
+    }
+}
@@ -0,0 +1,232 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Where an expected variable is configured.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvSource {
+    Dotenv,
+    Profile,
+    // `FOO=bar bun dev` assignments in front of the command
+    Command,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DriftKind {
+    Missing,
+    Changed,
+    // Configured when the app started, since removed from the config
+    Removed,
+    // In .env but not in the process environment. Frameworks that read .env themselves
+    // load it in memory, where it can't be seen from outside, so this isn't drift.
+    NotExported,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct EnvDrift {
+    pub key: String,
+    pub kind: DriftKind,
+    pub source: Option<EnvSource>,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct EnvDiff {
+    // The process whose environment was compared
+    pub pid: u32,
+    pub matched: usize,
+    pub drift: Vec<EnvDrift>,
+    pub restart_needed: bool,
+}
+
+/// `KEY=value` lines of a .env file. Supports `export`, comments and quoted values,
+/// not interpolation or multi-line values.
+pub fn parse_dotenv(contents: &str) -> Vec<(String, String)> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let key = key.trim();
+            if key.is_empty() || key.starts_with('#') || key.contains(char::is_whitespace) {
+                return None;
+            }
+            Some((key.to_string(), unquote(value.trim())))
+        })
+        .collect()
+}
+
+fn unquote(value: &str) -> String {
+    for quote in ['"', '\''] {
+        if let Some(rest) = value.strip_prefix(quote) {
+            if let Some(end) = rest.find(quote) {
+                return rest[..end].to_string();
+            }
+        }
+    }
+    // Unquoted values end at a comment
+    match value.find(" #") {
+        Some(end) => value[..end].trim_end().to_string(),
+        None => value.to_string(),
+    }
+}
+
+/// Leading `FOO=bar` assignments of a shell command.
+pub fn command_env(command: &str) -> Vec<(String, String)> {
+    command
+        .split_whitespace()
+        .map_while(|token| {
+            let (key, value) = token.split_once('=')?;
+            let valid = !key.is_empty()
+                && !key.starts_with(|c: char| c.is_ascii_digit())
+                && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            valid.then(|| (key.to_string(), unquote(value)))
+        })
+        .collect()
+}
+
+/// What the app's environment should contain, later sources overriding earlier ones the
+/// way they do at launch: .env loaders don't override the environment, and command
+/// assignments override everything.
+pub fn expected_env(
+    dotenv: Vec<(String, String)>,
+    profile: &HashMap<String, String>,
+    command: &str,
+) -> BTreeMap<String, (String, EnvSource)> {
+    let mut expected = BTreeMap::new();
+    for (key, value) in dotenv {
+        expected.insert(key, (value, EnvSource::Dotenv));
+    }
+    for (key, value) in profile {
+        expected.insert(key.clone(), (value.clone(), EnvSource::Profile));
+    }
+    for (key, value) in command_env(command) {
+        expected.insert(key, (value, EnvSource::Command));
+    }
+    expected
+}
+
+/// Compares the expected environment with the process's. `launched` is the profile env
+/// the app was started with, which tells variables removed since apart from inherited ones.
+pub fn diff(
+    pid: u32,
+    expected: &BTreeMap<String, (String, EnvSource)>,
+    launched: &HashMap<String, String>,
+    actual: &BTreeMap<String, String>,
+) -> EnvDiff {
+    let mut matched = 0;
+    let mut drift = Vec::new();
+    for (key, (value, source)) in expected {
+        let kind = match actual.get(key) {
+            Some(current) if current == value => {
+                matched += 1;
+                continue;
+            }
+            Some(_) => DriftKind::Changed,
+            None if *source == EnvSource::Dotenv => DriftKind::NotExported,
+            None => DriftKind::Missing,
+        };
+        drift.push(EnvDrift {
+            key: key.clone(),
+            kind,
+            source: Some(*source),
+            expected: Some(value.clone()),
+            actual: actual.get(key).cloned(),
+        });
+    }
+
+    let mut removed: Vec<&String> = launched
+        .keys()
+        .filter(|key| !expected.contains_key(*key) && actual.contains_key(*key))
+        .collect();
+    removed.sort();
+    drift.extend(removed.into_iter().map(|key| EnvDrift {
+        key: key.clone(),
+        kind: DriftKind::Removed,
+        source: None,
+        expected: None,
+        actual: actual.get(key).cloned(),
+    }));
+
+    EnvDiff {
+        pid,
+        matched,
+        restart_needed: drift.iter().any(|d| d.kind != DriftKind::NotExported),
+        drift,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dotenv() {
+        let vars = parse_dotenv(
+            "# local\nexport API_URL=\"http://localhost:4000\"\nDEBUG=1 # verbose\n\nNAME='my app'\n",
+        );
+        assert_eq!(
+            vars,
+            [
+                ("API_URL".to_string(), "http://localhost:4000".to_string()),
+                ("DEBUG".to_string(), "1".to_string()),
+                ("NAME".to_string(), "my app".to_string()),
+            ]
+        );
+        assert_eq!(
+            command_env("NODE_ENV=production PORT=1 bun start FOO=bar"),
+            [
+                ("NODE_ENV".to_string(), "production".to_string()),
+                ("PORT".to_string(), "1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let profile = HashMap::from([
+            ("API_URL".to_string(), "http://staging".to_string()),
+            ("FLAG".to_string(), "on".to_string()),
+        ]);
+        let expected = expected_env(
+            vec![
+                ("API_URL".to_string(), "http://localhost".to_string()),
+                ("SECRET".to_string(), "s".to_string()),
+            ],
+            &profile,
+            "LOG=debug bun dev",
+        );
+        let launched = HashMap::from([
+            ("API_URL".to_string(), "http://prod".to_string()),
+            ("OLD".to_string(), "1".to_string()),
+        ]);
+        let actual = BTreeMap::from([
+            ("API_URL".to_string(), "http://prod".to_string()),
+            ("LOG".to_string(), "debug".to_string()),
+            ("OLD".to_string(), "1".to_string()),
+            ("HOME".to_string(), "/Users/me".to_string()),
+        ]);
+
+        let diff = diff(1, &expected, &launched, &actual);
+        assert_eq!(diff.matched, 1);
+        assert!(diff.restart_needed);
+        let kinds: Vec<(&str, DriftKind)> = diff
+            .drift
+            .iter()
+            .map(|d| (d.key.as_str(), d.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                ("API_URL", DriftKind::Changed),
+                ("FLAG", DriftKind::Missing),
+                ("SECRET", DriftKind::NotExported),
+                ("OLD", DriftKind::Removed),
+            ]
+        );
+        assert_eq!(diff.drift[0].expected.as_deref(), Some("http://staging"));
+    }
+}
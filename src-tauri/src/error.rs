@@ -0,0 +1,156 @@
+use serde::Serialize;
+use std::fmt;
+
+/// Error returned by Tauri commands. Serialized as `{ "kind": "not_found", "message": ... }`
+/// so the frontend can branch on the kind and still show the message.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AppError {
+    /// An app, route, process or file the command needs doesn't exist
+    NotFound {
+        message: String,
+    },
+    PortUnavailable {
+        port: i32,
+        message: String,
+    },
+    /// Another app already has the subdomain; `app_id` and `app_name` identify it
+    SubdomainTaken {
+        subdomain: String,
+        app_id: String,
+        app_name: String,
+        message: String,
+    },
+    /// Caddy or the built-in proxy isn't running or rejected the change
+    ProxyUnavailable {
+        message: String,
+    },
+    /// The user cancelled an administrator prompt, or the OS refused access
+    PermissionDenied {
+        message: String,
+    },
+    /// A command couldn't be started or exited unsuccessfully; `stderr` is its error output
+    SpawnFailed {
+        message: String,
+        stderr: String,
+    },
+    Other {
+        message: String,
+    },
+}
+
+impl AppError {
+    pub fn not_found(message: impl Into<String>) -> Self {
+        AppError::NotFound {
+            message: message.into(),
+        }
+    }
+
+    pub fn unknown_app(id: &str) -> Self {
+        AppError::not_found(crate::i18n::tf("error.unknown_app", &[("id", &id)]))
+    }
+
+    pub fn port_unavailable(port: i32, message: impl Into<String>) -> Self {
+        AppError::PortUnavailable {
+            port,
+            message: message.into(),
+        }
+    }
+
+    pub fn subdomain_taken(subdomain: &str, app_id: &str, app_name: &str) -> Self {
+        AppError::SubdomainTaken {
+            subdomain: subdomain.to_string(),
+            app_id: app_id.to_string(),
+            app_name: app_name.to_string(),
+            message: crate::i18n::tf(
+                "error.subdomain_taken",
+                &[("subdomain", &subdomain), ("app", &app_name)],
+            ),
+        }
+    }
+
+    pub fn proxy_unavailable(message: impl Into<String>) -> Self {
+        AppError::ProxyUnavailable {
+            message: message.into(),
+        }
+    }
+
+    pub fn permission_denied(message: impl Into<String>) -> Self {
+        AppError::PermissionDenied {
+            message: message.into(),
+        }
+    }
+
+    pub fn spawn_failed(message: impl Into<String>, stderr: impl Into<String>) -> Self {
+        AppError::SpawnFailed {
+            message: message.into(),
+            stderr: stderr.into(),
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::NotFound { message }
+            | AppError::PortUnavailable { message, .. }
+            | AppError::SubdomainTaken { message, .. }
+            | AppError::ProxyUnavailable { message }
+            | AppError::PermissionDenied { message }
+            | AppError::SpawnFailed { message, .. }
+            | AppError::Other { message } => message,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+// Helpers still report plain strings; those surface as `Other` unless the caller classifies them
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Other { message }
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::Other {
+            message: message.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize() {
+        assert_eq!(
+            serde_json::to_value(AppError::port_unavailable(3000, "Port 3000 is in use")).unwrap(),
+            serde_json::json!({
+                "kind": "port_unavailable",
+                "port": 3000,
+                "message": "Port 3000 is in use"
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(AppError::subdomain_taken("shop", "a1", "Shop")).unwrap(),
+            serde_json::json!({
+                "kind": "subdomain_taken",
+                "subdomain": "shop",
+                "app_id": "a1",
+                "app_name": "Shop",
+                "message": "Subdomain \"shop\" is already used by \"Shop\""
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(AppError::from("boom")).unwrap(),
+            serde_json::json!({ "kind": "other", "message": "boom" })
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use crate::watch_ignore::{self, Visit};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+// Commands that serve a finished build instead of the sources, with what the build
+// leaves behind: a directory counts by its newest file
+const SERVERS: [(&str, &str); 9] = [
+    ("next start", ".next/BUILD_ID"),
+    ("vite preview", "dist"),
+    ("astro preview", "dist"),
+    ("nuxt preview", ".output"),
+    ("nuxi preview", ".output"),
+    ("node .output/", ".output"),
+    ("remix-serve", "build"),
+    ("node build", "build"),
+    ("node dist/", "dist"),
+];
+
+const RUNNERS: [&str; 4] = ["npm", "pnpm", "yarn", "bun"];
+
+/// A build older than the sources it was made from, so starting it would serve old code.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct StaleBuild {
+    /// The build output, relative to the project.
+    pub artifact: String,
+    /// The newest source file changed since the build; None when there is no build yet.
+    pub changed: Option<String>,
+}
+
+/// Whether the build `command` serves is missing or older than the project's sources.
+/// None when `command` serves no build or the build is current. Sources are what a
+/// watcher would see, so ignored files like logs don't count.
+pub fn check(project: &Path, command: &str) -> Option<StaleBuild> {
+    let artifact = artifact(project, command)?;
+    let Some(built) = newest(&project.join(artifact)) else {
+        return Some(StaleBuild {
+            artifact: artifact.to_string(),
+            changed: None,
+        });
+    };
+    let output = Path::new(artifact).components().next()?;
+
+    let mut changed: Option<(SystemTime, PathBuf)> = None;
+    watch_ignore::walk(project, |path, visit| {
+        if visit != Visit::File || path.starts_with(output) {
+            return;
+        }
+        let Ok(modified) = project.join(path).metadata().and_then(|m| m.modified()) else {
+            return;
+        };
+        if modified > built
+            && changed
+                .as_ref()
+                .is_none_or(|(newest, _)| modified > *newest)
+        {
+            changed = Some((modified, path.to_path_buf()));
+        }
+    })
+    .ok()?;
+    changed.map(|(_, path)| StaleBuild {
+        artifact: artifact.to_string(),
+        changed: Some(path.to_string_lossy().into_owned()),
+    })
+}
+
+/// The command that rebuilds the project: its package's `build` script.
+pub fn build_command(project: &Path) -> Option<String> {
+    let package = read_package(project)?;
+    package["scripts"]["build"].as_str()?;
+    let runner = crate::workspace::package_runner(project);
+    Some(crate::workspace::run_command(runner, "build"))
+}
+
+// The build output `command` serves. Package scripts are looked up, so `bun start`
+// counts as whatever the `start` script runs.
+fn artifact(project: &Path, command: &str) -> Option<&'static str> {
+    let script = script_name(command).and_then(|name| {
+        let package = read_package(project)?;
+        package["scripts"][name].as_str().map(String::from)
+    });
+    let command = script.as_deref().unwrap_or(command);
+    SERVERS
+        .iter()
+        .find(|(server, _)| mentions(command, server))
+        .map(|(_, artifact)| *artifact)
+}
+
+// The script a package manager command runs, e.g. `start` for `bun start`
+fn script_name(command: &str) -> Option<&str> {
+    let mut words = command.split_whitespace();
+    if !RUNNERS.contains(&words.next()?) {
+        return None;
+    }
+    match words.next()? {
+        "run" | "run-script" => words.next(),
+        script => Some(script),
+    }
+}
+
+// Whether `command` runs `server` as words of its own, so `node build` isn't found in
+// `node builder.js`
+fn mentions(command: &str, server: &str) -> bool {
+    command.match_indices(server).any(|(start, _)| {
+        let before = command[..start].chars().next_back();
+        let after = command[start + server.len()..].chars().next();
+        before.is_none_or(|c| c.is_whitespace() || "&;|(".contains(c))
+            && (server.ends_with('/') || after.is_none_or(|c| c.is_whitespace() || c == '/'))
+    })
+}
+
+fn read_package(project: &Path) -> Option<serde_json::Value> {
+    let content = std::fs::read_to_string(project.join("package.json")).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+// When `path` was last written: a file's own time, a directory's newest file
+fn newest(path: &Path) -> Option<SystemTime> {
+    let metadata = std::fs::symlink_metadata(path).ok()?;
+    if !metadata.is_dir() {
+        return metadata.modified().ok();
+    }
+    std::fs::read_dir(path)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| newest(&entry.path()))
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_artifact() {
+        let dir = std::env::temp_dir().join(format!("mla-artifact-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("package.json"),
+            r#"{"scripts": {"start": "next start -p $PORT", "preview": "vite preview"}}"#,
+        )
+        .unwrap();
+        assert_eq!(artifact(&dir, "bun start"), Some(".next/BUILD_ID"));
+        assert_eq!(artifact(&dir, "npm run preview"), Some("dist"));
+        assert_eq!(artifact(&dir, "node build/index.js"), Some("build"));
+        assert_eq!(artifact(&dir, "node builder.js"), None);
+        assert_eq!(artifact(&dir, "bun dev"), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_check() {
+        let dir = std::env::temp_dir().join(format!("mla-fresh-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::create_dir_all(dir.join("dist")).unwrap();
+        std::fs::write(dir.join(".gitignore"), "*.log\n").unwrap();
+        std::fs::write(dir.join("src/main.ts"), "").unwrap();
+        assert_eq!(
+            check(&dir, "vite preview"),
+            Some(StaleBuild {
+                artifact: "dist".to_string(),
+                changed: None,
+            })
+        );
+
+        let set_modified = |path: &str, time: SystemTime| {
+            let file = std::fs::File::options()
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(dir.join(path))
+                .unwrap();
+            file.set_modified(time).unwrap();
+        };
+        let now = SystemTime::now();
+        set_modified(".gitignore", now - Duration::from_secs(120));
+        set_modified("src/main.ts", now - Duration::from_secs(120));
+        set_modified("dist/index.js", now - Duration::from_secs(60));
+        set_modified("debug.log", now);
+        assert_eq!(check(&dir, "vite preview"), None);
+
+        set_modified("src/main.ts", now);
+        assert_eq!(
+            check(&dir, "vite preview").and_then(|stale| stale.changed),
+            Some("src/main.ts".to_string())
+        );
+        assert_eq!(check(&dir, "vite"), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
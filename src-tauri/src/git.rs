@@ -0,0 +1,296 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct GitInfo {
+    /// None when HEAD is detached
+    pub branch: Option<String>,
+    pub upstream: Option<String>,
+    pub dirty: bool,
+    pub ahead: u32,
+    pub behind: u32,
+}
+
+/// Branch and working tree state of the repo at `path`; None when it isn't a git repo.
+pub async fn get_info(path: &str) -> Result<Option<GitInfo>, String> {
+    if !Path::new(path).is_dir() {
+        return Ok(None);
+    }
+
+    let output = tokio::process::Command::new("git")
+        .args([
+            "status",
+            "--porcelain=v2",
+            "--branch",
+            "--untracked-files=normal",
+        ])
+        .current_dir(path)
+        // Don't let a status check grab the index lock other git processes need
+        .env("GIT_OPTIONAL_LOCKS", "0")
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("not a git repository") {
+            return Ok(None);
+        }
+        return Err(format!("git status failed: {}", stderr.trim()));
+    }
+
+    Ok(Some(parse_status(&String::from_utf8_lossy(&output.stdout))))
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct Worktree {
+    pub path: String,
+    /// None when HEAD is detached
+    pub branch: Option<String>,
+}
+
+/// Branches of a repo and the worktrees they are checked out in.
+#[derive(Debug, Clone, Serialize)]
+pub struct RepoBranches {
+    /// Local and remote branches, without the remote prefix and deduplicated
+    pub branches: Vec<String>,
+    /// Main working tree first
+    pub worktrees: Vec<Worktree>,
+}
+
+/// Branches and worktrees of the repo at `path`; None when it isn't a git repo.
+pub async fn get_branches(path: &str) -> Result<Option<RepoBranches>, String> {
+    if get_info(path).await?.is_none() {
+        return Ok(None);
+    }
+
+    let refs = run(
+        path,
+        &[
+            "for-each-ref",
+            "--format=%(refname)",
+            "refs/heads",
+            "refs/remotes",
+        ],
+    )
+    .await?;
+    let worktrees = run(path, &["worktree", "list", "--porcelain"]).await?;
+
+    Ok(Some(RepoBranches {
+        branches: parse_branches(&refs),
+        worktrees: parse_worktrees(&worktrees),
+    }))
+}
+
+/// Checks out `branch` into a new worktree next to the repo and returns its path. A remote-only
+/// branch gets a local tracking branch; an existing worktree for the branch is reused.
+pub async fn add_worktree(repo: &str, branch: &str) -> Result<String, String> {
+    let worktrees = run(repo, &["worktree", "list", "--porcelain"]).await?;
+    if let Some(existing) = parse_worktrees(&worktrees)
+        .into_iter()
+        .find(|w| w.branch.as_deref() == Some(branch))
+    {
+        return Ok(existing.path);
+    }
+
+    let dir = worktree_dir(Path::new(repo), branch);
+    if dir.exists() {
+        return Err(format!("{} already exists", dir.display()));
+    }
+    let dir = dir.to_string_lossy().to_string();
+    run(repo, &["worktree", "add", &dir, branch]).await?;
+    Ok(dir)
+}
+
+/// Deletes the worktree at `path`, refusing when it has uncommitted changes.
+pub async fn remove_worktree(repo: &str, path: &str) -> Result<(), String> {
+    run(repo, &["worktree", "remove", path]).await?;
+    Ok(())
+}
+
+// Worktrees live beside the repo as `<repo>-<branch>`, e.g. shop-feature-login
+fn worktree_dir(repo: &Path, branch: &str) -> PathBuf {
+    let name = repo
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let slug: String = branch
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    repo.with_file_name(format!("{}-{}", name, slug.trim_matches('-')))
+}
+
+async fn run(path: &str, args: &[&str]) -> Result<String, String> {
+    let output = tokio::process::Command::new("git")
+        .args(args)
+        .current_dir(path)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git {} failed: {}", args[0], stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn parse_branches(refs: &str) -> Vec<String> {
+    let mut branches: Vec<String> = Vec::new();
+    for line in refs.lines() {
+        let branch = match line.strip_prefix("refs/heads/") {
+            Some(local) => local,
+            // refs/remotes/<remote>/<branch>
+            None => match line
+                .strip_prefix("refs/remotes/")
+                .and_then(|r| r.split_once('/'))
+            {
+                Some((_, "HEAD")) | None => continue,
+                Some((_, remote_branch)) => remote_branch,
+            },
+        };
+        if !branches.iter().any(|b| b == branch) {
+            branches.push(branch.to_string());
+        }
+    }
+    branches
+}
+
+fn parse_worktrees(list: &str) -> Vec<Worktree> {
+    let mut worktrees = Vec::new();
+    // Entries are blocks of `key value` lines separated by blank lines
+    for block in list.split("\n\n") {
+        let mut path = None;
+        let mut branch = None;
+        for line in block.lines() {
+            if let Some(p) = line.strip_prefix("worktree ") {
+                path = Some(p.to_string());
+            } else if let Some(b) = line.strip_prefix("branch ") {
+                branch = Some(b.trim_start_matches("refs/heads/").to_string());
+            }
+        }
+        if let Some(path) = path {
+            worktrees.push(Worktree { path, branch });
+        }
+    }
+    worktrees
+}
+
+fn parse_status(status: &str) -> GitInfo {
+    let mut info = GitInfo::default();
+    for line in status.lines() {
+        let Some(header) = line.strip_prefix("# ") else {
+            if !line.is_empty() {
+                info.dirty = true;
+            }
+            continue;
+        };
+
+        let (key, value) = header.split_once(' ').unwrap_or((header, ""));
+        match key {
+            "branch.head" if value != "(detached)" => info.branch = Some(value.to_string()),
+            "branch.upstream" => info.upstream = Some(value.to_string()),
+            "branch.ab" => {
+                for count in value.split_whitespace() {
+                    if let Some(ahead) = count.strip_prefix('+') {
+                        info.ahead = ahead.parse().unwrap_or(0);
+                    } else if let Some(behind) = count.strip_prefix('-') {
+                        info.behind = behind.parse().unwrap_or(0);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_status() {
+        let status = "\
+# branch.oid 3f2a1c
+# branch.head feature/login
+# branch.upstream origin/feature/login
+# branch.ab +2 -1
+1 .M N... 100644 100644 100644 abc abc src/main.ts
+? notes.txt
+";
+        assert_eq!(
+            parse_status(status),
+            GitInfo {
+                branch: Some("feature/login".to_string()),
+                upstream: Some("origin/feature/login".to_string()),
+                dirty: true,
+                ahead: 2,
+                behind: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_branches_and_worktrees() {
+        let refs = "\
+refs/heads/main
+refs/heads/feature/login
+refs/remotes/origin/HEAD
+refs/remotes/origin/main
+refs/remotes/origin/fix-cart
+";
+        assert_eq!(
+            parse_branches(refs),
+            vec!["main", "feature/login", "fix-cart"]
+        );
+
+        let list = "\
+worktree /Users/me/shop
+HEAD 3f2a1c
+branch refs/heads/main
+
+worktree /Users/me/shop-feature-login
+HEAD 9b8e7d
+branch refs/heads/feature/login
+
+worktree /Users/me/shop-review
+HEAD 1a2b3c
+detached
+";
+        assert_eq!(
+            parse_worktrees(list),
+            vec![
+                Worktree {
+                    path: "/Users/me/shop".to_string(),
+                    branch: Some("main".to_string()),
+                },
+                Worktree {
+                    path: "/Users/me/shop-feature-login".to_string(),
+                    branch: Some("feature/login".to_string()),
+                },
+                Worktree {
+                    path: "/Users/me/shop-review".to_string(),
+                    branch: None,
+                },
+            ]
+        );
+        assert_eq!(
+            worktree_dir(Path::new("/Users/me/shop"), "feature/login"),
+            PathBuf::from("/Users/me/shop-feature-login")
+        );
+    }
+
+    #[test]
+    fn test_parse_clean_detached() {
+        let status = "# branch.oid 3f2a1c\n# branch.head (detached)\n";
+        assert_eq!(parse_status(status), GitInfo::default());
+    }
+}
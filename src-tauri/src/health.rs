@@ -0,0 +1,227 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+pub const DEFAULT_PATH: &str = "/";
+// History older than the longest range is pruned, about once an hour
+pub const RETENTION_MS: i64 = 30 * DAY_MS;
+pub const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+const MAX_INCIDENTS: usize = 20;
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+pub enum UptimeRange {
+    #[serde(rename = "24h")]
+    Day,
+    #[serde(rename = "7d")]
+    Week,
+    #[serde(rename = "30d")]
+    Month,
+}
+
+impl UptimeRange {
+    pub fn since(self, now_ms: i64) -> i64 {
+        let days = match self {
+            UptimeRange::Day => 1,
+            UptimeRange::Week => 7,
+            UptimeRange::Month => 30,
+        };
+        now_ms - days * DAY_MS
+    }
+}
+
+/// One ping of an app's health endpoint, as stored in `health_checks`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthCheck {
+    pub checked_at: i64,
+    pub up: bool,
+    pub status: Option<u16>,
+    pub response_ms: Option<i64>,
+    pub error: Option<String>,
+}
+
+/// A run of failed checks; `ended_at` is the first check that passed again.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Incident {
+    pub started_at: i64,
+    pub ended_at: Option<i64>,
+    pub failed_checks: u32,
+    // Why the first check of the run failed
+    pub error: String,
+}
+
+/// Health history of one app over a range. Only time the app was running counts, so
+/// `uptime_percent` is None until it has been checked.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Uptime {
+    pub health_path: String,
+    pub checks: u32,
+    pub uptime_percent: Option<f64>,
+    pub avg_response_ms: Option<f64>,
+    // Most recent first
+    pub incidents: Vec<Incident>,
+}
+
+pub fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+/// Paths are relative to the app's own port and must start with a slash.
+pub fn normalize_path(path: &str) -> Result<Option<String>, String> {
+    let path = path.trim();
+    if path.is_empty() || path == DEFAULT_PATH {
+        return Ok(None);
+    }
+    if !path.starts_with('/') || path.contains(char::is_whitespace) {
+        return Err(format!("{} isn't a path like /health", path));
+    }
+    Ok(Some(path.to_string()))
+}
+
+pub fn client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(CHECK_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| format!("Failed to create health check client: {}", e))
+}
+
+/// GETs `path` from the app on `port`. Anything below 500 counts as up, so apps without
+/// a health endpoint can still be checked on "/" even when it 404s or redirects.
+pub async fn ping(client: &reqwest::Client, port: u16, path: &str) -> HealthCheck {
+    let checked_at = now_ms();
+    let started = Instant::now();
+    let response = client
+        .get(format!("http://127.0.0.1:{}{}", port, path))
+        .send()
+        .await;
+    let response_ms = Some(started.elapsed().as_millis() as i64);
+    match response {
+        Ok(response) => {
+            let status = response.status();
+            HealthCheck {
+                checked_at,
+                up: !status.is_server_error(),
+                status: Some(status.as_u16()),
+                response_ms,
+                error: status
+                    .is_server_error()
+                    .then(|| format!("Answered {}", status)),
+            }
+        }
+        Err(e) => HealthCheck {
+            checked_at,
+            up: false,
+            status: None,
+            response_ms: None,
+            error: Some(if e.is_timeout() {
+                format!("No answer within {}s", CHECK_TIMEOUT.as_secs())
+            } else {
+                "Connection failed".to_string()
+            }),
+        },
+    }
+}
+
+/// Sums up checks ordered oldest first.
+pub fn summarize(health_path: &str, checks: &[HealthCheck]) -> Uptime {
+    let up = checks.iter().filter(|c| c.up).count();
+    let response_times: Vec<i64> = checks
+        .iter()
+        .filter(|c| c.up)
+        .filter_map(|c| c.response_ms)
+        .collect();
+
+    let mut incidents: Vec<Incident> = Vec::new();
+    let mut open: Option<Incident> = None;
+    for check in checks {
+        match (&mut open, check.up) {
+            (Some(incident), false) => incident.failed_checks += 1,
+            (None, false) => {
+                open = Some(Incident {
+                    started_at: check.checked_at,
+                    ended_at: None,
+                    failed_checks: 1,
+                    error: check.error.clone().unwrap_or_default(),
+                })
+            }
+            (Some(_), true) => {
+                if let Some(mut incident) = open.take() {
+                    incident.ended_at = Some(check.checked_at);
+                    incidents.push(incident);
+                }
+            }
+            (None, true) => {}
+        }
+    }
+    incidents.extend(open);
+    incidents.reverse();
+    incidents.truncate(MAX_INCIDENTS);
+
+    Uptime {
+        health_path: health_path.to_string(),
+        checks: checks.len() as u32,
+        uptime_percent: (!checks.is_empty()).then(|| up as f64 * 100.0 / checks.len() as f64),
+        avg_response_ms: (!response_times.is_empty())
+            .then(|| response_times.iter().sum::<i64>() as f64 / response_times.len() as f64),
+        incidents,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(checked_at: i64, up: bool, response_ms: i64) -> HealthCheck {
+        HealthCheck {
+            checked_at,
+            up,
+            status: up.then_some(200),
+            response_ms: up.then_some(response_ms),
+            error: (!up).then(|| "Connection failed".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_summarize() {
+        let checks = [
+            check(1, true, 10),
+            check(2, false, 0),
+            check(3, false, 0),
+            check(4, true, 30),
+            check(5, false, 0),
+        ];
+        let uptime = summarize("/health", &checks);
+        assert_eq!(uptime.checks, 5);
+        assert_eq!(uptime.uptime_percent, Some(40.0));
+        assert_eq!(uptime.avg_response_ms, Some(20.0));
+        assert_eq!(uptime.incidents.len(), 2);
+        // Still failing, so the newest incident is open
+        assert_eq!(uptime.incidents[0].started_at, 5);
+        assert_eq!(uptime.incidents[0].ended_at, None);
+        assert_eq!(uptime.incidents[1].ended_at, Some(4));
+        assert_eq!(uptime.incidents[1].failed_checks, 2);
+
+        let empty = summarize("/", &[]);
+        assert_eq!(empty.uptime_percent, None);
+        assert!(empty.incidents.is_empty());
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(
+            normalize_path(" /healthz "),
+            Ok(Some("/healthz".to_string()))
+        );
+        assert_eq!(normalize_path("/"), Ok(None));
+        assert_eq!(normalize_path(""), Ok(None));
+        assert!(normalize_path("health").is_err());
+        let range: UptimeRange = serde_json::from_str("\"7d\"").unwrap();
+        assert_eq!(range.since(8 * DAY_MS), DAY_MS);
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+// English is both the source and the fallback for keys a translation lacks
+const FALLBACK: &str = include_str!("../locales/en.json");
+const BUNDLED: [(&str, &str); 3] = [
+    ("de", include_str!("../locales/de.json")),
+    ("es", include_str!("../locales/es.json")),
+    ("ru", include_str!("../locales/ru.json")),
+];
+
+/// Strings the backend shows itself: tray items, notifications and error messages.
+pub struct Catalog {
+    language: &'static str,
+    strings: HashMap<String, String>,
+}
+
+impl Catalog {
+    /// The catalog for a locale like "de-DE"; languages without a translation get English.
+    pub fn new(locale: &str) -> Self {
+        let mut strings: HashMap<String, String> =
+            serde_json::from_str(FALLBACK).unwrap_or_default();
+        let language = locale.split(['-', '_']).next().unwrap_or_default();
+        let bundled = BUNDLED
+            .iter()
+            .find(|(code, _)| code.eq_ignore_ascii_case(language));
+        let Some((code, content)) = bundled else {
+            return Self {
+                language: "en",
+                strings,
+            };
+        };
+        match serde_json::from_str::<HashMap<String, String>>(content) {
+            Ok(translated) => strings.extend(translated),
+            Err(e) => log::error!("Failed to load the {} translation: {}", code, e),
+        }
+        Self {
+            language: code,
+            strings,
+        }
+    }
+
+    /// The string for `key` with `{name}` placeholders filled in from `args`.
+    pub fn get(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let Some(template) = self.strings.get(key) else {
+            log::warn!("Missing string {}", key);
+            return key.to_string();
+        };
+        let mut text = template.clone();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{}}}", name), &value.to_string());
+        }
+        text
+    }
+}
+
+fn catalog() -> &'static Catalog {
+    static CATALOG: OnceLock<Catalog> = OnceLock::new();
+    CATALOG.get_or_init(|| {
+        // Tests compare English messages whatever the machine's language
+        let locale = if cfg!(test) {
+            "en".to_string()
+        } else {
+            sys_locale::get_locale().unwrap_or_default()
+        };
+        let catalog = Catalog::new(&locale);
+        log::info!("Using {} strings for locale {:?}", catalog.language, locale);
+        catalog
+    })
+}
+
+/// `key` in the system language.
+pub fn t(key: &str) -> String {
+    catalog().get(key, &[])
+}
+
+/// `key` in the system language, with `{name}` placeholders filled in.
+pub fn tf(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    catalog().get(key, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    fn placeholders(text: &str) -> BTreeSet<&str> {
+        text.split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+            .collect()
+    }
+
+    #[test]
+    fn test_bundled_translations() {
+        let english: HashMap<String, String> = serde_json::from_str(FALLBACK).unwrap();
+        for (code, content) in BUNDLED {
+            let translated: HashMap<String, String> = serde_json::from_str(content).unwrap();
+            let mut keys: Vec<&String> = translated.keys().collect();
+            keys.sort();
+            let mut expected: Vec<&String> = english.keys().collect();
+            expected.sort();
+            assert_eq!(keys, expected, "{} has different keys", code);
+            for (key, text) in &translated {
+                assert_eq!(
+                    placeholders(text),
+                    placeholders(&english[key]),
+                    "{} {}",
+                    code,
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_get() {
+        let german = Catalog::new("de-DE");
+        assert_eq!(german.language, "de");
+        assert_eq!(
+            german.get("tray.app_running", &[("name", &"Shop"), ("port", &3000)]),
+            "Shop (:3000) – Läuft"
+        );
+        assert_eq!(Catalog::new("pt_BR").get("tray.quit", &[]), "Quit");
+        assert_eq!(Catalog::new("").get("nope", &[]), "nope");
+    }
+}
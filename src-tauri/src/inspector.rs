@@ -0,0 +1,486 @@
+use bytes::Bytes;
+use http_body_util::{combinators::BoxBody, BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::{Request, Response};
+use hyper_util::rt::TokioIo;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::Arc;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{oneshot, Mutex};
+
+use crate::otlp::{RequestSpan, TraceContext, TraceExporter};
+use crate::rate_limit::{self, RateLimiter};
+
+// Keep only the most recent requests per app
+const MAX_RECORDED_REQUESTS: usize = 200;
+// Bodies larger than this are forwarded but not recorded
+const MAX_RECORDED_BODY: usize = 64 * 1024;
+
+const HOP_BY_HOP_HEADERS: [&str; 7] = [
+    "connection",
+    "keep-alive",
+    "proxy-connection",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+pub(crate) type RelayBody = BoxBody<Bytes, hyper::Error>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapturedRequest {
+    pub id: String,
+    pub app_id: String,
+    pub method: String,
+    pub path: String,
+    pub status: Option<u16>,
+    pub duration_ms: u64,
+    pub started_at: u64,
+    pub request_headers: Vec<(String, String)>,
+    pub request_body: Option<String>,
+    pub response_headers: Vec<(String, String)>,
+    pub response_body: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReplayOverrides {
+    pub method: Option<String>,
+    pub path: Option<String>,
+    pub headers: Option<Vec<(String, String)>>,
+    pub body: Option<String>,
+    // Send to another app (or an explicit port) instead of the one that received the request
+    pub app_id: Option<String>,
+    pub port: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayResponse {
+    pub status: u16,
+    pub duration_ms: u64,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+// None lets every request through
+type SharedLimiter = Arc<std::sync::Mutex<Option<RateLimiter>>>;
+
+struct Relay {
+    port: u16,
+    upstream_port: Arc<AtomicI32>,
+    limiter: SharedLimiter,
+    shutdown: oneshot::Sender<()>,
+}
+
+type RequestLog = Arc<Mutex<HashMap<String, VecDeque<CapturedRequest>>>>;
+
+pub struct InspectorState {
+    relays: Arc<Mutex<HashMap<String, Relay>>>,
+    requests: RequestLog,
+    traces: TraceExporter,
+}
+
+impl InspectorState {
+    pub fn new() -> Self {
+        Self {
+            relays: Arc::new(Mutex::new(HashMap::new())),
+            requests: Arc::new(Mutex::new(HashMap::new())),
+            traces: TraceExporter::new(),
+        }
+    }
+
+    /// Exports a span per relayed request while configured, see `otlp`.
+    pub fn traces(&self) -> &TraceExporter {
+        &self.traces
+    }
+
+    /// Starts (or reuses) the relay for an app and returns the local port Caddy should proxy to.
+    pub async fn start_relay(&self, app_id: &str, upstream_port: i32) -> Result<u16, String> {
+        let mut relays = self.relays.lock().await;
+
+        if let Some(relay) = relays.get(app_id) {
+            relay.upstream_port.store(upstream_port, Ordering::SeqCst);
+            return Ok(relay.port);
+        }
+
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| format!("Failed to start inspection relay: {}", e))?;
+        let port = listener
+            .local_addr()
+            .map_err(|e| format!("Failed to start inspection relay: {}", e))?
+            .port();
+
+        let upstream = Arc::new(AtomicI32::new(upstream_port));
+        let limiter = SharedLimiter::default();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+
+        tauri::async_runtime::spawn(run_relay(
+            listener,
+            app_id.to_string(),
+            upstream.clone(),
+            limiter.clone(),
+            self.requests.clone(),
+            self.traces.clone(),
+            shutdown_rx,
+        ));
+
+        relays.insert(
+            app_id.to_string(),
+            Relay {
+                port,
+                upstream_port: upstream,
+                limiter,
+                shutdown: shutdown_tx,
+            },
+        );
+
+        Ok(port)
+    }
+
+    pub async fn stop_relay(&self, app_id: &str) {
+        let mut relays = self.relays.lock().await;
+        if let Some(relay) = relays.remove(app_id) {
+            let _ = relay.shutdown.send(());
+        }
+    }
+
+    /// Points an existing relay at a new app port, e.g. after the app was restarted.
+    pub async fn set_upstream_port(&self, app_id: &str, upstream_port: i32) {
+        let relays = self.relays.lock().await;
+        if let Some(relay) = relays.get(app_id) {
+            relay.upstream_port.store(upstream_port, Ordering::SeqCst);
+        }
+    }
+
+    /// Makes an app's relay answer 429 past `per_second` requests a second; None lifts it.
+    pub async fn set_rate_limit(&self, app_id: &str, per_second: Option<u32>) {
+        let relays = self.relays.lock().await;
+        if let Some(relay) = relays.get(app_id) {
+            if let Ok(mut limiter) = relay.limiter.lock() {
+                *limiter = per_second.map(RateLimiter::new);
+            }
+        }
+    }
+
+    pub async fn recent_requests(&self, app_id: &str) -> Vec<CapturedRequest> {
+        let requests = self.requests.lock().await;
+        requests
+            .get(app_id)
+            .map(|r| r.iter().rev().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub async fn forget_requests(&self, app_id: &str) {
+        self.requests.lock().await.remove(app_id);
+    }
+
+    pub async fn find_request(&self, request_id: &str) -> Option<CapturedRequest> {
+        let requests = self.requests.lock().await;
+        requests
+            .values()
+            .flat_map(|r| r.iter())
+            .find(|r| r.id == request_id)
+            .cloned()
+    }
+}
+
+impl Default for InspectorState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+async fn run_relay(
+    listener: TcpListener,
+    app_id: String,
+    upstream_port: Arc<AtomicI32>,
+    limiter: SharedLimiter,
+    requests: RequestLog,
+    traces: TraceExporter,
+    mut shutdown: oneshot::Receiver<()>,
+) {
+    loop {
+        let stream = tokio::select! {
+            _ = &mut shutdown => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    log::error!("Inspection relay accept failed for {}: {}", app_id, e);
+                    continue;
+                }
+            },
+        };
+
+        let app_id = app_id.clone();
+        let upstream_port = upstream_port.clone();
+        let limiter = limiter.clone();
+        let requests = requests.clone();
+        let traces = traces.clone();
+
+        tauri::async_runtime::spawn(async move {
+            let service = hyper::service::service_fn(move |req| {
+                relay_request(
+                    req,
+                    app_id.clone(),
+                    upstream_port.load(Ordering::SeqCst),
+                    limiter.clone(),
+                    requests.clone(),
+                    traces.clone(),
+                )
+            });
+            let _ = hyper::server::conn::http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await;
+        });
+    }
+}
+
+async fn relay_request(
+    req: Request<Incoming>,
+    app_id: String,
+    upstream_port: i32,
+    limiter: SharedLimiter,
+    requests: RequestLog,
+    traces: TraceExporter,
+) -> Result<Response<RelayBody>, hyper::Error> {
+    let started = std::time::Instant::now();
+    let started_ns = crate::otlp::unix_nanos();
+    let (parts, body) = req.into_parts();
+
+    let mut captured = CapturedRequest {
+        id: uuid::Uuid::new_v4().to_string(),
+        app_id,
+        method: parts.method.to_string(),
+        path: parts
+            .uri
+            .path_and_query()
+            .map(|p| p.to_string())
+            .unwrap_or_else(|| "/".to_string()),
+        status: None,
+        duration_ms: 0,
+        started_at: unix_millis(),
+        request_headers: header_pairs(&parts.headers),
+        request_body: None,
+        response_headers: Vec::new(),
+        response_body: None,
+        error: None,
+    };
+
+    // Throttled requests are recorded too, so the inspector shows the client backing off
+    let throttled = limiter.lock().ok().and_then(|mut limiter| {
+        let limiter = limiter.as_mut()?;
+        let wait = limiter.acquire(started).err()?;
+        Some((limiter.per_second(), rate_limit::retry_after_secs(wait)))
+    });
+    if let Some((per_second, retry_after)) = throttled {
+        captured.status = Some(429);
+        captured.error = Some(format!("Rate limited to {} requests/s", per_second));
+        record(&requests, captured).await;
+        return Ok(Response::builder()
+            .status(429)
+            .header(hyper::header::RETRY_AFTER, retry_after)
+            .body(full_body(Bytes::from("Too many requests")))
+            .unwrap_or_default());
+    }
+
+    let body = body.collect().await?.to_bytes();
+    captured.request_body = capture_body(&body);
+
+    let trace = traces.is_active().then(|| {
+        let traceparent = parts
+            .headers
+            .get("traceparent")
+            .and_then(|v| v.to_str().ok());
+        TraceContext::continue_from(traceparent)
+    });
+
+    let mut upstream_req = Request::from_parts(parts, Full::new(body));
+    strip_hop_by_hop(upstream_req.headers_mut());
+    if let Some(context) = &trace {
+        if let Ok(value) = hyper::header::HeaderValue::from_str(&context.traceparent()) {
+            upstream_req.headers_mut().insert("traceparent", value);
+        }
+    }
+
+    let response = match forward(upstream_req, upstream_port).await {
+        Ok(response) => response,
+        Err(e) => {
+            captured.status = Some(502);
+            captured.duration_ms = started.elapsed().as_millis() as u64;
+            captured.error = Some(e.clone());
+            if let Some(context) = trace {
+                traces.export(RequestSpan::new(context, &captured, started_ns));
+            }
+            record(&requests, captured).await;
+            return Ok(Response::builder()
+                .status(502)
+                .body(full_body(Bytes::from(format!("Inspection relay: {}", e))))
+                .unwrap_or_default());
+        }
+    };
+
+    let (mut parts, body) = response.into_parts();
+    strip_hop_by_hop(&mut parts.headers);
+    captured.status = Some(parts.status.as_u16());
+    captured.response_headers = header_pairs(&parts.headers);
+
+    let is_small = parts
+        .headers
+        .get(hyper::header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok())
+        .map(|len| len <= MAX_RECORDED_BODY)
+        .unwrap_or(false);
+
+    let body = if is_small {
+        let bytes = body.collect().await?.to_bytes();
+        captured.response_body = capture_body(&bytes);
+        full_body(bytes)
+    } else {
+        // Streamed or large responses (SSE, downloads) pass through unrecorded
+        body.boxed()
+    };
+
+    captured.duration_ms = started.elapsed().as_millis() as u64;
+    if let Some(context) = trace {
+        traces.export(RequestSpan::new(context, &captured, started_ns));
+    }
+    record(&requests, captured).await;
+
+    Ok(Response::from_parts(parts, body))
+}
+
+/// Re-sends a recorded request straight to `upstream_port`, bypassing the relay.
+pub async fn replay(
+    captured: &CapturedRequest,
+    overrides: &ReplayOverrides,
+    upstream_port: i32,
+) -> Result<ReplayResponse, String> {
+    let method = overrides.method.as_deref().unwrap_or(&captured.method);
+    let path = overrides.path.as_deref().unwrap_or(&captured.path);
+    let headers = overrides
+        .headers
+        .as_ref()
+        .unwrap_or(&captured.request_headers);
+
+    let body = match (&overrides.body, &captured.request_body) {
+        (Some(body), _) | (None, Some(body)) => Bytes::from(body.clone()),
+        (None, None) if has_body(&captured.request_headers) => {
+            return Err(
+                "Request body was too large to record; provide a body override to replay it"
+                    .to_string(),
+            );
+        }
+        (None, None) => Bytes::new(),
+    };
+
+    let mut builder = Request::builder()
+        .method(
+            hyper::Method::from_bytes(method.as_bytes())
+                .map_err(|_| format!("Invalid method: {}", method))?,
+        )
+        .uri(path);
+    for (name, value) in headers {
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+    let mut req = builder
+        .body(Full::new(body))
+        .map_err(|e| format!("Invalid replay request: {}", e))?;
+    strip_hop_by_hop(req.headers_mut());
+    // The body may have been overridden, so let hyper compute the length
+    req.headers_mut().remove(hyper::header::CONTENT_LENGTH);
+
+    let started = std::time::Instant::now();
+    let response = forward(req, upstream_port).await?;
+    let (parts, body) = response.into_parts();
+    let body = body
+        .collect()
+        .await
+        .map_err(|e| format!("Failed to read response from port {}: {}", upstream_port, e))?
+        .to_bytes();
+
+    Ok(ReplayResponse {
+        status: parts.status.as_u16(),
+        duration_ms: started.elapsed().as_millis() as u64,
+        headers: header_pairs(&parts.headers),
+        body: capture_body(&body),
+    })
+}
+
+async fn forward(
+    req: Request<Full<Bytes>>,
+    upstream_port: i32,
+) -> Result<Response<Incoming>, String> {
+    let stream = TcpStream::connect(format!("127.0.0.1:{}", upstream_port))
+        .await
+        .map_err(|e| format!("Failed to connect to app on port {}: {}", upstream_port, e))?;
+
+    let (mut sender, conn) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+        .await
+        .map_err(|e| format!("Failed to talk to app on port {}: {}", upstream_port, e))?;
+
+    tauri::async_runtime::spawn(async move {
+        let _ = conn.await;
+    });
+
+    sender
+        .send_request(req)
+        .await
+        .map_err(|e| format!("Request to app on port {} failed: {}", upstream_port, e))
+}
+
+async fn record(requests: &RequestLog, captured: CapturedRequest) {
+    let mut requests = requests.lock().await;
+    let app_requests = requests.entry(captured.app_id.clone()).or_default();
+    app_requests.push_back(captured);
+    if app_requests.len() > MAX_RECORDED_REQUESTS {
+        app_requests.pop_front();
+    }
+}
+
+pub(crate) fn full_body(bytes: Bytes) -> RelayBody {
+    Full::new(bytes).map_err(|never| match never {}).boxed()
+}
+
+fn capture_body(bytes: &Bytes) -> Option<String> {
+    if bytes.is_empty() || bytes.len() > MAX_RECORDED_BODY {
+        return None;
+    }
+    Some(String::from_utf8_lossy(bytes).into_owned())
+}
+
+fn header_pairs(headers: &hyper::HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.to_string(),
+                String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            )
+        })
+        .collect()
+}
+
+fn has_body(headers: &[(String, String)]) -> bool {
+    headers.iter().any(|(name, value)| {
+        (name.eq_ignore_ascii_case("content-length") && value.trim() != "0")
+            || name.eq_ignore_ascii_case("transfer-encoding")
+    })
+}
+
+pub(crate) fn strip_hop_by_hop(headers: &mut hyper::HeaderMap) {
+    for name in HOP_BY_HOP_HEADERS {
+        headers.remove(name);
+    }
+}
+
+fn unix_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
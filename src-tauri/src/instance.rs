@@ -0,0 +1,121 @@
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// Env var every app process is started with. Children inherit it, so a process can be
+/// traced back to its app even after the shell that started it is gone.
+pub const ENV: &str = "MY_LITTLE_APPS_INSTANCE";
+
+// One `<app id>.pid` file per running app, for scripts that want to find it
+const PID_DIR: &str = "pids";
+
+/// The value of `ENV` for an app: the same across restarts, but different for another
+/// data dir, so the debug and release builds never claim each other's processes.
+pub fn identity(data_dir: &Path, app_id: &str) -> String {
+    let digest = Sha256::new()
+        .chain_update(data_dir.to_string_lossy().as_bytes())
+        .chain_update([0])
+        .chain_update(app_id.as_bytes())
+        .finalize();
+    let hex: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+    format!("mla-{}", hex)
+}
+
+/// Whether a process was started with `identity`. None when its environment can't be
+/// read, e.g. it belongs to another user.
+pub fn started_as(environ: &[OsString], identity: &str) -> Option<bool> {
+    if environ.is_empty() {
+        return None;
+    }
+    let expected = OsString::from(format!("{}={}", ENV, identity));
+    Some(environ.contains(&expected))
+}
+
+fn pid_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(PID_DIR)
+}
+
+pub fn write_pid(data_dir: &Path, app_id: &str, pid: u32) {
+    let dir = pid_dir(data_dir);
+    let result = std::fs::create_dir_all(&dir)
+        .and_then(|_| std::fs::write(dir.join(format!("{}.pid", app_id)), format!("{}\n", pid)));
+    if let Err(e) = result {
+        log::warn!("Failed to write pid file for {}: {}", app_id, e);
+    }
+}
+
+pub fn remove_pid(data_dir: &Path, app_id: &str) {
+    let _ = std::fs::remove_file(pid_dir(data_dir).join(format!("{}.pid", app_id)));
+}
+
+/// Pids of the apps with a pid file, by app id.
+pub fn read_pids(data_dir: &Path) -> HashMap<String, u32> {
+    let Ok(entries) = std::fs::read_dir(pid_dir(data_dir)) else {
+        return HashMap::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let app_id = path
+                .file_name()?
+                .to_str()?
+                .strip_suffix(".pid")?
+                .to_string();
+            let pid = std::fs::read_to_string(&path).ok()?.trim().parse().ok()?;
+            Some((app_id, pid))
+        })
+        .collect()
+}
+
+/// Replaces all pid files with `pids`.
+pub fn replace_pids(data_dir: &Path, pids: &HashMap<String, u32>) {
+    for app_id in read_pids(data_dir).keys() {
+        if !pids.contains_key(app_id) {
+            remove_pid(data_dir, app_id);
+        }
+    }
+    for (app_id, pid) in pids {
+        write_pid(data_dir, app_id, *pid);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity() {
+        let release = Path::new("/Users/me/.my-little-apps");
+        let debug = Path::new("/Users/me/.my-little-apps-debug");
+        let id = identity(release, "app1");
+        assert_eq!(id, identity(release, "app1"));
+        assert_eq!(id.len(), "mla-".len() + 16);
+        assert_ne!(id, identity(release, "app2"));
+        assert_ne!(id, identity(debug, "app1"));
+
+        let environ = [
+            OsString::from("PATH=/usr/bin"),
+            OsString::from(format!("{}={}", ENV, id)),
+        ];
+        assert_eq!(started_as(&environ, &id), Some(true));
+        assert_eq!(started_as(&environ[..1], &id), Some(false));
+        assert_eq!(started_as(&[], &id), None);
+    }
+
+    #[test]
+    fn test_pid_files() {
+        let dir = std::env::temp_dir().join(format!("mla-instance-{}", std::process::id()));
+        write_pid(&dir, "app1", 100);
+        write_pid(&dir, "app2", 200);
+        remove_pid(&dir, "app2");
+        assert_eq!(read_pids(&dir), HashMap::from([("app1".to_string(), 100)]));
+
+        replace_pids(&dir, &HashMap::from([("app3".to_string(), 300)]));
+        assert_eq!(read_pids(&dir), HashMap::from([("app3".to_string(), 300)]));
+        replace_pids(&dir, &HashMap::new());
+        assert!(read_pids(&dir).is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
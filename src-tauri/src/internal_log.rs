@@ -0,0 +1,223 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::{LevelFilter, Targets};
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::layer::SubscriberExt;
+
+const DIR: &str = "internal-logs";
+const FILE_PREFIX: &str = "internal";
+// One file per day
+const MAX_FILES: usize = 7;
+pub const DEFAULT_LIMIT: usize = 2000;
+
+pub fn log_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(DIR)
+}
+
+/// Sends this crate's tracing spans and events to a daily JSON log under `internal-logs/`.
+/// Writes happen on a background thread for as long as the returned guard lives.
+pub fn init(data_dir: &Path) -> Option<WorkerGuard> {
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(FILE_PREFIX)
+        .filename_suffix("log")
+        .max_log_files(MAX_FILES)
+        .build(log_dir(data_dir));
+    let appender = match appender {
+        Ok(appender) => appender,
+        Err(e) => {
+            eprintln!("Failed to open the internal log: {}", e);
+            return None;
+        }
+    };
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    let layer = tracing_subscriber::fmt::layer()
+        .json()
+        .flatten_event(true)
+        .with_current_span(true)
+        .with_span_list(true)
+        // Closing a span logs how long it took
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(writer);
+    let filter = Targets::new()
+        .with_target(env!("CARGO_CRATE_NAME"), LevelFilter::DEBUG)
+        .with_default(LevelFilter::WARN);
+    let subscriber = tracing_subscriber::registry().with(layer).with(filter);
+    if let Err(e) = tracing::subscriber::set_global_default(subscriber) {
+        eprintln!("Failed to set up the internal log: {}", e);
+        return None;
+    }
+    Some(guard)
+}
+
+/// Copies a `log` record into the internal log, inside whatever span is current.
+pub fn forward(record: &log::Record) {
+    let source = record.target();
+    // Other crates' debug output would drown ours
+    if record.level() > log::Level::Info && !source.starts_with(env!("CARGO_CRATE_NAME")) {
+        return;
+    }
+    let message = record.args();
+    match record.level() {
+        log::Level::Error => tracing::error!(source, "{}", message),
+        log::Level::Warn => tracing::warn!(source, "{}", message),
+        log::Level::Info => tracing::info!(source, "{}", message),
+        log::Level::Debug => tracing::debug!(source, "{}", message),
+        log::Level::Trace => tracing::trace!(source, "{}", message),
+    }
+}
+
+/// One line of the internal log, as the debug window shows it.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct InternalLogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    // Outermost first, like "launch_app{app=api}"
+    pub spans: Vec<String>,
+    pub fields: Map<String, Value>,
+}
+
+fn format_span(span: &Map<String, Value>) -> String {
+    let name = span.get("name").and_then(Value::as_str).unwrap_or_default();
+    let fields: Vec<String> = span
+        .iter()
+        .filter(|(key, _)| *key != "name")
+        .map(|(key, value)| match value {
+            Value::String(s) => format!("{}={}", key, s),
+            other => format!("{}={}", key, other),
+        })
+        .collect();
+    if fields.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}{{{}}}", name, fields.join(" "))
+    }
+}
+
+pub fn parse_line(line: &str) -> Option<InternalLogEntry> {
+    let Value::Object(mut fields) = serde_json::from_str(line).ok()? else {
+        return None;
+    };
+    let mut take = |key: &str| match fields.remove(key) {
+        Some(Value::String(s)) => s,
+        _ => String::new(),
+    };
+    let timestamp = take("timestamp");
+    let level = take("level");
+    let target = take("target");
+    let message = take("message");
+    let mut spans: Vec<String> = match fields.remove("spans") {
+        Some(Value::Array(spans)) => spans
+            .iter()
+            .filter_map(Value::as_object)
+            .map(format_span)
+            .collect(),
+        _ => Vec::new(),
+    };
+    // Span close events carry the closed span here, outside the list
+    if let Some(Value::Object(span)) = fields.remove("span") {
+        let span = format_span(&span);
+        if spans.last() != Some(&span) {
+            spans.push(span);
+        }
+    }
+    Some(InternalLogEntry {
+        timestamp,
+        level,
+        target,
+        message,
+        spans,
+        fields,
+    })
+}
+
+fn severity(level: &str) -> u8 {
+    match level.to_ascii_uppercase().as_str() {
+        "ERROR" => 4,
+        "WARN" => 3,
+        "INFO" => 2,
+        "DEBUG" => 1,
+        _ => 0,
+    }
+}
+
+/// The last `limit` entries at `min_level` or above, oldest first.
+pub fn read(data_dir: &Path, limit: usize, min_level: Option<&str>) -> Vec<InternalLogEntry> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(log_dir(data_dir))
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    // The date sits in the file name, so name order is age order
+    files.sort();
+    let min = min_level.map(severity).unwrap_or(0);
+
+    let mut entries = Vec::new();
+    for file in files.iter().rev() {
+        let Ok(content) = std::fs::read_to_string(file) else {
+            continue;
+        };
+        let mut newer: Vec<InternalLogEntry> = content
+            .lines()
+            .filter_map(parse_line)
+            .filter(|entry| severity(&entry.level) >= min)
+            .collect();
+        newer.append(&mut entries);
+        entries = newer;
+        if entries.len() >= limit {
+            break;
+        }
+    }
+    let skip = entries.len().saturating_sub(limit);
+    entries.split_off(skip)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        let entry = parse_line(
+            r#"{"timestamp":"2025-01-02T10:00:00.000Z","level":"INFO","message":"close","time.busy":"12ms","target":"my_little_apps_lib","span":{"app":"api","port":3000,"name":"launch_app"},"spans":[{"name":"start_apps"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(entry.level, "INFO");
+        assert_eq!(entry.message, "close");
+        assert_eq!(entry.spans, ["start_apps", "launch_app{app=api port=3000}"]);
+        assert_eq!(entry.fields["time.busy"], "12ms");
+        assert!(!entry.fields.contains_key("target"));
+        assert!(parse_line("not json").is_none());
+    }
+
+    #[test]
+    fn test_read() {
+        let dir = std::env::temp_dir().join(format!("mla-internal-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(log_dir(&dir)).unwrap();
+        let line = |level: &str, message: &str| {
+            format!(r#"{{"level":"{}","message":"{}"}}"#, level, message)
+        };
+        std::fs::write(
+            log_dir(&dir).join("internal.2025-01-01.log"),
+            [line("INFO", "a"), line("DEBUG", "b")].join("\n"),
+        )
+        .unwrap();
+        std::fs::write(
+            log_dir(&dir).join("internal.2025-01-02.log"),
+            [line("WARN", "c"), line("INFO", "d")].join("\n"),
+        )
+        .unwrap();
+
+        let messages = |entries: Vec<InternalLogEntry>| -> Vec<String> {
+            entries.into_iter().map(|e| e.message).collect()
+        };
+        assert_eq!(messages(read(&dir, 10, None)), ["a", "b", "c", "d"]);
+        assert_eq!(messages(read(&dir, 3, None)), ["b", "c", "d"]);
+        assert_eq!(messages(read(&dir, 10, Some("info"))), ["a", "c", "d"]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,94 @@
+use std::net::IpAddr;
+
+/// Parses an allowlist entry: a single address ("192.168.1.23") or a CIDR range
+/// ("192.168.1.0/24"). Returns the network address and prefix length.
+fn parse_entry(entry: &str) -> Result<(IpAddr, u8), String> {
+    let (addr, prefix) = match entry.split_once('/') {
+        Some((addr, prefix)) => (addr, Some(prefix)),
+        None => (entry, None),
+    };
+    let addr: IpAddr = addr
+        .parse()
+        .map_err(|_| format!("\"{}\" is not an IP address or CIDR range", entry))?;
+    let max = if addr.is_ipv4() { 32 } else { 128 };
+    let prefix = match prefix {
+        Some(prefix) => prefix
+            .parse::<u8>()
+            .ok()
+            .filter(|p| *p <= max)
+            .ok_or_else(|| format!("\"{}\" has an invalid prefix length", entry))?,
+        None => max,
+    };
+    Ok((addr, prefix))
+}
+
+/// Trims and validates entries, dropping blanks and duplicates.
+pub fn normalize(entries: &[String]) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for entry in entries.iter().map(|e| e.trim()).filter(|e| !e.is_empty()) {
+        parse_entry(entry)?;
+        if !normalized.iter().any(|e| e == entry) {
+            normalized.push(entry.to_string());
+        }
+    }
+    Ok(normalized)
+}
+
+/// Whether `ip` may reach a route with this allowlist. An empty list allows everyone and
+/// this machine is always allowed, so the app keeps working locally.
+pub fn allows(entries: &[String], ip: IpAddr) -> bool {
+    let ip = ip.to_canonical();
+    if entries.is_empty() || ip.is_loopback() {
+        return true;
+    }
+    entries
+        .iter()
+        .filter_map(|entry| parse_entry(entry).ok())
+        .any(|(network, prefix)| contains(network, prefix, ip))
+}
+
+fn contains(network: IpAddr, prefix: u8, ip: IpAddr) -> bool {
+    match (network, ip) {
+        (IpAddr::V4(network), IpAddr::V4(ip)) => {
+            let mask = u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0);
+            u32::from(network) & mask == u32::from(ip) & mask
+        }
+        (IpAddr::V6(network), IpAddr::V6(ip)) => {
+            let mask = u128::MAX.checked_shl(128 - prefix as u32).unwrap_or(0);
+            u128::from(network) & mask == u128::from(ip) & mask
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allows() {
+        let entries = normalize(&[
+            " 192.168.1.23 ".to_string(),
+            "10.0.0.0/24".to_string(),
+            "".to_string(),
+            "192.168.1.23".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(entries, vec!["192.168.1.23", "10.0.0.0/24"]);
+
+        assert!(allows(&entries, "192.168.1.23".parse().unwrap()));
+        assert!(allows(&entries, "10.0.0.200".parse().unwrap()));
+        assert!(!allows(&entries, "10.0.1.1".parse().unwrap()));
+        assert!(!allows(&entries, "192.168.1.24".parse().unwrap()));
+        assert!(allows(&entries, "127.0.0.1".parse().unwrap()));
+        assert!(allows(&entries, "::ffff:10.0.0.5".parse().unwrap()));
+        assert!(allows(&[], "192.168.1.24".parse().unwrap()));
+        assert!(allows(
+            &["0.0.0.0/0".to_string()],
+            "8.8.8.8".parse().unwrap()
+        ));
+
+        assert!(normalize(&["phone".to_string()]).is_err());
+        assert!(normalize(&["10.0.0.0/33".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const KEPT_FILE: &str = "kept_apps.json";
+pub const LOG_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Runs the app with output going to `$MY_APP_LOG` instead of our pipes, which close when
+/// we quit; most servers die on their next write to a closed pipe.
+pub const DETACHED_EVAL: &str = r#"eval "$MY_APP_CMD" < /dev/null >> "$MY_APP_LOG" 2>&1"#;
+
+/// What happens to a running app when My Little Apps quits.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QuitBehavior {
+    #[default]
+    Stop,
+    KeepRunning,
+}
+
+impl QuitBehavior {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            QuitBehavior::Stop => "stop",
+            QuitBehavior::KeepRunning => "keep_running",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "stop" => Some(QuitBehavior::Stop),
+            "keep_running" => Some(QuitBehavior::KeepRunning),
+            _ => None,
+        }
+    }
+}
+
+/// An app left running at quit, with what's needed to track it again on the next launch.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct KeptApp {
+    pub pid: u32,
+    // Tells the app apart from a process that got its pid after it exited
+    pub started_at: u64,
+    pub port: i32,
+    pub requested_port: i32,
+    pub subdomain: Option<String>,
+    pub profile: Option<String>,
+    pub command: String,
+    pub env: HashMap<String, String>,
+    pub container: Option<String>,
+    pub log: PathBuf,
+}
+
+pub fn log_path(data_dir: &Path, app_id: &str) -> PathBuf {
+    data_dir.join("logs").join(format!("{}.log", app_id))
+}
+
+pub fn save(data_dir: &Path, kept: &HashMap<String, KeptApp>) {
+    let path = data_dir.join(KEPT_FILE);
+    if kept.is_empty() {
+        let _ = std::fs::remove_file(path);
+        return;
+    }
+    match serde_json::to_string(kept) {
+        Ok(content) => {
+            if let Err(e) = std::fs::write(path, content) {
+                log::error!("Failed to save apps left running: {}", e);
+            }
+        }
+        Err(e) => log::error!("Failed to serialize apps left running: {}", e),
+    }
+}
+
+/// Apps the previous session left running. The file is removed, so they're only offered once.
+pub fn take(data_dir: &Path) -> HashMap<String, KeptApp> {
+    let path = data_dir.join(KEPT_FILE);
+    let kept = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    let _ = std::fs::remove_file(path);
+    kept
+}
+
+/// Complete lines appended to the log since `offset`, which is moved past them. A line
+/// still being written is left for the next call.
+pub fn read_new_lines(path: &Path, offset: &mut u64) -> Vec<String> {
+    let Ok(mut file) = std::fs::File::open(path) else {
+        return Vec::new();
+    };
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    // Truncated, e.g. by a restart
+    if len < *offset {
+        *offset = 0;
+    }
+    let mut buf = Vec::new();
+    if file.seek(SeekFrom::Start(*offset)).is_err() || file.read_to_end(&mut buf).is_err() {
+        return Vec::new();
+    }
+    let Some(end) = buf.iter().rposition(|b| *b == b'\n') else {
+        return Vec::new();
+    };
+    *offset += end as u64 + 1;
+    String::from_utf8_lossy(&buf[..end])
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_read_new_lines() {
+        let dir = std::env::temp_dir().join(format!("mla-kept-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = log_path(&dir, "api");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+        let mut file = std::fs::File::create(&path).unwrap();
+        write!(file, "listening on 3000\nGET /").unwrap();
+        let mut offset = 0;
+        assert_eq!(read_new_lines(&path, &mut offset), ["listening on 3000"]);
+        writeln!(file, " 200").unwrap();
+        assert_eq!(read_new_lines(&path, &mut offset), ["GET / 200"]);
+        assert!(read_new_lines(&path, &mut offset).is_empty());
+
+        std::fs::write(&path, "restarted\n").unwrap();
+        assert_eq!(read_new_lines(&path, &mut offset), ["restarted"]);
+
+        let kept = HashMap::from([(
+            "api".to_string(),
+            KeptApp {
+                pid: 42,
+                started_at: 1_700_000_000,
+                port: 3000,
+                requested_port: 3000,
+                subdomain: Some("api".to_string()),
+                profile: None,
+                command: "bun dev".to_string(),
+                env: HashMap::new(),
+                container: None,
+                log: path,
+            },
+        )]);
+        save(&dir, &kept);
+        assert_eq!(take(&dir), kept);
+        assert!(take(&dir).is_empty());
+        assert_eq!(
+            QuitBehavior::parse("keep_running"),
+            Some(QuitBehavior::KeepRunning)
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,29 +1,133 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::net::TcpListener;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use sysinfo::{Pid, Signal, System};
 use tauri::{
     image::Image,
-    menu::{Menu, MenuItem, PredefinedMenuItem},
+    menu::{IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
     AppHandle, Emitter, Manager, State, WebviewWindowBuilder,
 };
 use tauri_plugin_autostart::MacosLauncher;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_opener::OpenerExt;
 use tauri_plugin_shell::process::{CommandChild, CommandEvent};
 use tauri_plugin_shell::ShellExt;
 use tauri_plugin_sql::{Migration, MigrationKind};
+use tauri_plugin_updater::UpdaterExt;
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
+mod access_log;
+mod app_config;
+mod app_lock;
+mod benchmark;
+mod browser;
+mod builtin_proxy;
+mod caddy_admin;
+mod caddy_import;
+mod config_changes;
+mod crash_report;
+mod db;
+mod deps;
+mod devcontainer;
+mod disk_usage;
 mod dns;
+mod dns_responder;
+mod doctor;
+mod editor;
+mod env_diff;
+mod error;
+mod freshness;
+mod git;
+mod health;
+mod i18n;
+mod inspector;
+mod instance;
+mod internal_log;
+mod ip_allowlist;
+mod keep_running;
+mod log_events;
+mod log_forwarding;
 mod mdns;
+mod notifications;
+mod otlp;
+mod output;
+mod package_meta;
+mod path_entries;
+mod preferences;
+mod prerequisites;
+mod process_info;
+mod project;
 mod proxy;
+mod python;
+mod rate_limit;
+mod readiness;
+mod remote;
+mod remote_control;
+mod sandbox;
+mod search;
+mod self_update;
+mod services;
+mod sessions;
+mod settings;
+mod ssh_tunnel;
+mod status_page;
+mod terminal;
+mod timeline;
+mod unified_log;
+mod watch_ignore;
+mod watchdog;
+mod workspace;
 
-use dns::ProxyServiceStatus;
+use access_log::AccessTracker;
+use app_config::ConfigIssue;
+use app_lock::{AppLock, AppLockStatus};
+use browser::{Browser, BrowserPreference};
+use caddy_admin::AdminEndpoint;
+use caddy_import::{CaddyImport, ImportResult, ImportedRoute};
+use config_changes::{ConfigChange, LaunchConfig};
+use crash_report::CrashReport;
+use db::Db;
+use disk_usage::{CleanReport, DiskUsage};
+use dns::{ProxyServiceStatus, UninstallReport};
+use dns_responder::DnsResponder;
+use doctor::{CheckResult, DoctorContext, DoctorFix};
+use editor::{Editor, EditorPreference, Location};
+use error::AppError;
+use git::{GitInfo, RepoBranches};
+use inspector::{CapturedRequest, InspectorState, ReplayOverrides, ReplayResponse};
+use internal_log::InternalLogEntry;
+use keep_running::{KeptApp, QuitBehavior};
+use log_events::LogEvents;
+use log_forwarding::{LogForwarder, LogForwardingSettings, LogForwardingStatus, LogRecord};
 use mdns::MdnsRegistry;
-use proxy::{ProxyRoute, ProxyState};
+use notifications::{Notification, NotificationChannel, NotificationKind, NotificationSettings};
+use otlp::TraceExportSettings;
+use preferences::PreferencesState;
+use proxy::{
+    ForwardHeaders, HostDrift, MockResponse, ProxyBackend, ProxyRoute, ProxySettings, ProxyState,
+    ProxyTimeouts, UpstreamProtocol,
+};
+use readiness::ReadyDetector;
+use remote_control::{
+    Call, PairedDevice, RemoteApp, RemoteControl, RemoteControlStatus, RemoteHandler, RemoteSession,
+};
+use search::AppMatch;
+use self_update::{SnapshotApp, UpdateInfo, UpdateProgress, UpdateStage};
+use sessions::{Session, SessionApp, Trigger};
+use settings::{PortRange, SettingsStore};
+use ssh_tunnel::{SshTunnel, TunnelManager, TunnelStatus};
+use status_page::{AppStatus, StatusPage};
+use terminal::Terminal;
+use timeline::{AppEvent, AppEventKind};
+use unified_log::UnifiedLog;
+use watch_ignore::WatchPlan;
+use watchdog::Watchdog;
+use workspace::WorkspacePackage;
 
 // App data structure matching our SQLite schema
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,14 +140,122 @@ pub struct App {
     pub run_on_startup: bool,
     pub created_at: String,
     pub subdomain: Option<String>,
+    // Path of the repo this app is a git worktree of
+    #[serde(default)]
+    pub worktree_of: Option<String>,
+    // Run before every start, e.g. `cargo build`
+    #[serde(default)]
+    pub build_command: Option<String>,
+    // Service preset id for database apps, see `services::PRESETS`
+    #[serde(default)]
+    pub service: Option<String>,
+    // Ports, URLs or "docker" that must be up before the app starts, one per line
+    #[serde(default)]
+    pub prerequisites: Option<String>,
+    // SSH destination of a remote app; its path is a directory on that host
+    #[serde(default)]
+    pub remote_host: Option<String>,
+    // Run the command inside the project's devcontainer instead of on the host
+    #[serde(default)]
+    pub devcontainer: bool,
+    // Directories put in front of PATH when the app and its build run, one per line
+    #[serde(default)]
+    pub path_entries: Option<String>,
+    // Shell the command runs in, one of `app_config::SHELLS`; None is the login shell
+    #[serde(default)]
+    pub shell: Option<String>,
+    // "process", "static" or "remote"
+    #[serde(default)]
+    pub kind: String,
+    // Static apps only: serve index.html for unknown paths
+    #[serde(default)]
+    pub spa: bool,
+}
+
+/// The process behind a running app. Apps adopted from a previous session were spawned
+/// by it, so all that's left of them is the pid.
+#[derive(Debug)]
+pub enum AppProcess {
+    Spawned(CommandChild),
+    Adopted(u32),
+}
+
+impl AppProcess {
+    pub fn pid(&self) -> u32 {
+        match self {
+            AppProcess::Spawned(child) => child.pid(),
+            AppProcess::Adopted(pid) => *pid,
+        }
+    }
+
+    pub fn kill(self) -> Result<(), String> {
+        match self {
+            AppProcess::Spawned(child) => child.kill().map_err(|e| e.to_string()),
+            AppProcess::Adopted(pid) => {
+                let pid = Pid::from_u32(pid);
+                let mut system = System::new();
+                system.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[pid]), true);
+                if let Some(process) = system.process(pid) {
+                    process.kill_with(Signal::Term);
+                }
+                Ok(())
+            }
+        }
+    }
 }
 
 // Running process info
 #[derive(Debug)]
 pub struct RunningProcess {
-    pub child: CommandChild,
+    pub child: AppProcess,
     pub port: i32,
+    // Port it was asked to start on; `port` differs when that one was taken
+    pub requested_port: i32,
     pub subdomain: Option<String>,
+    pub profile: Option<String>,
+    // What the app was launched with, so it can be restarted the same way
+    pub command: String,
+    pub env: HashMap<String, String>,
+    // Docker container the command runs, stopped together with the app
+    pub container: Option<String>,
+    // Set once the app's output or port says it accepts requests, see `readiness`
+    pub ready: bool,
+    // Set for apps started to outlive a quit; their output goes here instead of our pipes
+    pub log: Option<PathBuf>,
+}
+
+/// Named variant of an app's launch settings, e.g. "staging" pointing at another backend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchProfile {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    pub port: Option<i32>,
+}
+
+struct LaunchSpec {
+    id: String,
+    path: String,
+    command: String,
+    port: i32,
+    subdomain: Option<String>,
+    env: HashMap<String, String>,
+    profile: Option<String>,
+    build_command: Option<String>,
+    prerequisites: Option<String>,
+    remote_host: Option<String>,
+    devcontainer: bool,
+    path_entries: Option<String>,
+    shell: Option<String>,
+}
+
+// Payload of the `proxy-service-status` event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProxyServiceHealth {
+    #[serde(flatten)]
+    pub status: ProxyServiceStatus,
+    pub responsive: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,6 +268,16 @@ pub struct AppUsage {
 pub struct AppState {
     pub processes: Arc<Mutex<HashMap<String, RunningProcess>>>,
     pub logs: Arc<Mutex<HashMap<String, Vec<String>>>>,
+    // Apps as last read from the database, see `reload_apps`
+    pub apps: Arc<Mutex<HashMap<String, App>>>,
+    // Last computed disk usage per app; walking node_modules is too slow to redo on every view
+    pub disk_usage: Arc<Mutex<HashMap<String, DiskUsage>>>,
+    // Config changes running apps haven't picked up, as last sent in `app-restart-needed`
+    pub restart_needed: Arc<Mutex<HashMap<String, Vec<ConfigChange>>>>,
+    // Apps in their build step or printing build output, by when the build began
+    pub building: Arc<Mutex<HashMap<String, std::time::Instant>>>,
+    // Set by the first of the exit paths to reach `shutdown`
+    pub shut_down: AtomicBool,
 }
 
 impl Default for AppState {
@@ -63,21 +285,216 @@ impl Default for AppState {
         Self {
             processes: Arc::new(Mutex::new(HashMap::new())),
             logs: Arc::new(Mutex::new(HashMap::new())),
+            apps: Arc::new(Mutex::new(HashMap::new())),
+            disk_usage: Arc::new(Mutex::new(HashMap::new())),
+            restart_needed: Arc::new(Mutex::new(HashMap::new())),
+            building: Arc::new(Mutex::new(HashMap::new())),
+            shut_down: AtomicBool::new(false),
+        }
+    }
+}
+
+impl AppState {
+    pub async fn find_app(&self, id: &str) -> Result<App, AppError> {
+        self.apps
+            .lock()
+            .await
+            .get(id)
+            .cloned()
+            .ok_or_else(|| AppError::unknown_app(id))
+    }
+}
+
+// Re-reads the apps table into `AppState.apps`, noting edits in the apps' timelines
+async fn load_apps(app_handle: &AppHandle) -> Result<Vec<App>, String> {
+    let apps = app_handle.state::<Db>().apps().await?;
+    let state = app_handle.state::<AppState>();
+    let mut cached = state.apps.lock().await;
+    for app in &apps {
+        let changes = match cached.get(&app.id) {
+            Some(old) => timeline::app_changes(old, app),
+            None => continue,
+        };
+        if !changes.is_empty() {
+            let detail = changes.join(", ");
+            record_app_event(
+                app_handle,
+                &app.id,
+                AppEventKind::ConfigChanged,
+                Some(detail),
+            );
+        }
+    }
+    *cached = apps.iter().map(|a| (a.id.clone(), a.clone())).collect();
+    Ok(apps)
+}
+
+// Adds an entry to the app's activity timeline and sends it to the window as `app-event`
+fn record_app_event(
+    app_handle: &AppHandle,
+    app_id: &str,
+    kind: AppEventKind,
+    detail: Option<String>,
+) {
+    save_app_event(app_handle, AppEvent::new(app_id, kind, detail));
+}
+
+fn save_app_event(app_handle: &AppHandle, event: AppEvent) {
+    let handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = handle.state::<Db>().record_app_event(&event).await {
+            log::warn!("{}", e);
+        }
+        let _ = handle.emit("app-event", &event);
+    });
+}
+
+// Re-reads the apps table and rebuilds the tray menu from it
+async fn reload_apps(app_handle: &AppHandle) -> Result<(), String> {
+    let apps = load_apps(app_handle).await?;
+    let state = app_handle.state::<AppState>();
+    let running = running_apps(&state, &app_handle.state::<ProxyState>()).await;
+    let restart_needed = update_restart_needed(app_handle).await;
+    let building: HashSet<String> = state.building.lock().await.keys().cloned().collect();
+    update_tray_menu(app_handle, apps, &running, &restart_needed, &building);
+    Ok(())
+}
+
+// Config changes the running app `id` hasn't picked up; empty when it isn't running
+async fn pending_config_changes(
+    app_handle: &AppHandle,
+    id: &str,
+) -> Result<Vec<ConfigChange>, String> {
+    let state = app_handle.state::<AppState>();
+    let (profile, launched) = {
+        let processes = state.processes.lock().await;
+        let Some(process) = processes.get(id) else {
+            return Ok(Vec::new());
+        };
+        let launched = LaunchConfig {
+            command: process.command.clone(),
+            env: process.env.clone(),
+            port: Some(process.requested_port),
+            subdomain: process.subdomain.clone(),
+        };
+        (process.profile.clone(), launched)
+    };
+    let Some(mut app) = state.apps.lock().await.get(id).cloned() else {
+        return Ok(Vec::new());
+    };
+    // Compared with what the template gives now, not the template itself
+    if let Some(template) = &app.subdomain {
+        app.subdomain = Some(resolve_subdomain(app_handle, id, template, profile.as_deref()).await);
+    }
+    let profile = match &profile {
+        Some(name) => app_handle.state::<Db>().profile(id, name).await?,
+        None => None,
+    };
+    let saved = match profile {
+        Some(profile) => LaunchConfig {
+            command: profile.command,
+            env: profile.env,
+            port: profile.port,
+            subdomain: app.subdomain,
+        },
+        None => LaunchConfig {
+            command: app.command,
+            env: HashMap::new(),
+            port: app.port,
+            subdomain: app.subdomain,
+        },
+    };
+    Ok(config_changes::changes(&launched, &saved))
+}
+
+// Fills a subdomain template like `{name}-{branch}` for the app; plain subdomains come back
+// as they are
+async fn resolve_subdomain(
+    app_handle: &AppHandle,
+    id: &str,
+    subdomain: &str,
+    profile: Option<&str>,
+) -> String {
+    if !proxy::is_subdomain_template(subdomain) {
+        return subdomain.to_string();
+    }
+    let state = app_handle.state::<AppState>();
+    let app = state.apps.lock().await.get(id).cloned();
+    let Some(app) = app else {
+        return proxy::render_subdomain(subdomain, &HashMap::new());
+    };
+    let branch = match git::get_info(&app.path).await {
+        Ok(info) => info.and_then(|info| info.branch),
+        Err(e) => {
+            log::warn!("No branch for the subdomain of {}: {}", app.name, e);
+            None
+        }
+    };
+    let dir = std::path::Path::new(&app.path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string());
+    let values: HashMap<&str, String> = [
+        ("name", Some(app.name.clone())),
+        ("branch", branch),
+        ("profile", profile.map(String::from)),
+        ("dir", dir),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| Some((name, value?)))
+    .collect();
+    let rendered = proxy::render_subdomain(subdomain, &values);
+    if rendered.is_empty() {
+        proxy::slugify(&app.name)
+    } else {
+        rendered
+    }
+}
+
+// Recomputes which running apps need a restart and emits `app-restart-needed` for the
+// ones whose answer changed
+async fn update_restart_needed(app_handle: &AppHandle) -> HashMap<String, Vec<ConfigChange>> {
+    let state = app_handle.state::<AppState>();
+    let ids: Vec<String> = state.processes.lock().await.keys().cloned().collect();
+    let mut current = HashMap::new();
+    for id in ids {
+        match pending_config_changes(app_handle, &id).await {
+            Ok(changes) if !changes.is_empty() => {
+                current.insert(id, changes);
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("Could not compare config of {}: {}", id, e),
+        }
+    }
+
+    let mut previous = state.restart_needed.lock().await;
+    let ids: std::collections::HashSet<&String> = previous.keys().chain(current.keys()).collect();
+    for id in ids {
+        if previous.get(id) != current.get(id) {
+            let _ = app_handle.emit(
+                "app-restart-needed",
+                serde_json::json!({
+                    "id": id,
+                    "changes": current.get(id).cloned().unwrap_or_default()
+                }),
+            );
         }
     }
+    *previous = current.clone();
+    current
 }
 
 // Find a free port in the given range
-fn find_free_port(preferred: Option<i32>) -> Option<i32> {
+fn find_free_port(range: PortRange, preferred: Option<i32>) -> Option<i32> {
     if let Some(port) = preferred {
         if TcpListener::bind(format!("127.0.0.1:{}", port)).is_ok() {
             return Some(port);
         }
     }
 
-    // Try random ports in range 10000-60000
+    // Try random ports in the configured range
+    let span = u32::from(range.end.saturating_sub(range.start)).max(1);
     for _ in 0..100 {
-        let port = 10000 + (rand_port() % 50000) as i32;
+        let port = i32::from(range.start) + (rand_port() % span) as i32;
         if TcpListener::bind(format!("127.0.0.1:{}", port)).is_ok() {
             return Some(port);
         }
@@ -85,14 +502,18 @@ fn find_free_port(preferred: Option<i32>) -> Option<i32> {
     None
 }
 
+// Something (possibly not started by us) is still listening on the port
+fn is_port_in_use(port: i32) -> bool {
+    TcpListener::bind(format!("127.0.0.1:{}", port)).is_err()
+}
+
 // Simple random number for port selection
 fn rand_port() -> u32 {
     use std::time::{SystemTime, UNIX_EPOCH};
-    let nanos = SystemTime::now()
+    SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap()
-        .subsec_nanos();
-    nanos
+        .subsec_nanos()
 }
 
 fn kill_process_tree(pid: u32) {
@@ -153,7 +574,8 @@ fn get_pids_file_path() -> PathBuf {
     app_data_dir().join("my-little-apps-pids.json")
 }
 
-fn read_pids() -> HashMap<String, u32> {
+// Pids written by versions before per-app pid files; their apps carry no identity
+fn read_legacy_pids() -> HashMap<String, u32> {
     let path = get_pids_file_path();
     if !path.exists() {
         return HashMap::new();
@@ -164,52 +586,75 @@ fn read_pids() -> HashMap<String, u32> {
         .unwrap_or_default()
 }
 
+fn read_pids() -> HashMap<String, u32> {
+    instance::read_pids(&app_data_dir())
+}
+
 fn write_pids(pids: &HashMap<String, u32>) {
-    let path = get_pids_file_path();
-    if let Ok(content) = serde_json::to_string(pids) {
-        let _ = std::fs::write(&path, content);
-    }
+    instance::replace_pids(&app_data_dir(), pids);
+    let _ = std::fs::remove_file(get_pids_file_path());
 }
 
 fn save_pid(app_id: &str, pid: u32) {
-    let mut pids = read_pids();
-    pids.insert(app_id.to_string(), pid);
-    write_pids(&pids);
+    instance::write_pid(&app_data_dir(), app_id, pid);
 }
 
 fn remove_pid(app_id: &str) {
-    let mut pids = read_pids();
-    pids.remove(app_id);
-    write_pids(&pids);
+    instance::remove_pid(&app_data_dir(), app_id);
 }
 
-fn cleanup_orphaned_processes() {
+// Stops apps left running by a previous session that crashed and returns their ids. Apps
+// it chose to keep running are left for `adopt_kept_apps`.
+fn cleanup_orphaned_processes(kept: &HashMap<String, KeptApp>) -> HashSet<String> {
+    let data_dir = app_data_dir();
+    let legacy = read_legacy_pids();
     let pids = read_pids();
-    if pids.is_empty() {
-        return;
+    let mut stopped = HashSet::new();
+    if pids.is_empty() && legacy.is_empty() {
+        return stopped;
     }
 
     let mut system = System::new_all();
     system.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
 
-    for (app_id, pid) in &pids {
+    for (app_id, pid) in legacy.iter().chain(&pids) {
+        if kept.get(app_id).is_some_and(|app| app.pid == *pid) {
+            continue;
+        }
+        let identity = instance::identity(&data_dir, app_id);
         let sysinfo_pid = Pid::from_u32(*pid);
-        if system.process(sysinfo_pid).is_some() {
-            log::info!("Cleaned orphan process {} (app: {})", pid, app_id);
-            kill_process_tree(*pid);
-            if let Some(process) = system.process(sysinfo_pid) {
+        if let Some(process) = system.process(sysinfo_pid) {
+            // The pid may belong to something else by now
+            let reused = !legacy.contains_key(app_id)
+                && instance::started_as(process.environ(), &identity) == Some(false);
+            if reused {
+                log::info!("Pid {} of app {} was reused, leaving it", pid, app_id);
+            } else {
+                log::info!("Cleaned orphan process {} (app: {})", pid, app_id);
+                stopped.insert(app_id.clone());
+                kill_process_tree(*pid);
+                process.kill_with(Signal::Term);
+            }
+        }
+
+        // Servers whose shell died were handed to launchd, but still carry the identity
+        let launchd = Pid::from_u32(1);
+        for (orphan_pid, process) in system.processes() {
+            let orphaned = process.parent() == Some(launchd) && *orphan_pid != sysinfo_pid;
+            if orphaned && instance::started_as(process.environ(), &identity) == Some(true) {
+                log::info!("Cleaned orphan process {} (app: {})", orphan_pid, app_id);
+                stopped.insert(app_id.clone());
                 process.kill_with(Signal::Term);
             }
         }
     }
 
     write_pids(&HashMap::new());
-    if !pids.is_empty() {
-        log::info!("Orphaned processes cleanup completed");
-    }
+    log::info!("Orphaned processes cleanup completed");
+    stopped
 }
 
-async fn cleanup_and_sync(app_handle: &AppHandle) {
+async fn cleanup_and_sync(app_handle: &AppHandle, watchdog: &mut Watchdog) {
     let app_state = app_handle.state::<AppState>();
     let proxy_state = app_handle.state::<ProxyState>();
     let mdns_registry = app_handle.state::<MdnsRegistry>();
@@ -219,6 +664,7 @@ async fn cleanup_and_sync(app_handle: &AppHandle) {
 
     let mut dead_apps: Vec<String> = Vec::new();
     let mut live_apps: HashMap<String, (i32, Option<String>)> = HashMap::new();
+    let mut watched: Vec<(String, u32, i32)> = Vec::new();
 
     {
         let processes = app_state.processes.lock().await;
@@ -226,14 +672,50 @@ async fn cleanup_and_sync(app_handle: &AppHandle) {
             let pid = Pid::from_u32(process.child.pid());
             if system.process(pid).is_some() {
                 live_apps.insert(app_id.clone(), (process.port, process.subdomain.clone()));
+                if process.ready {
+                    watched.push((app_id.clone(), process.child.pid(), process.port));
+                }
             } else {
                 dead_apps.push(app_id.clone());
             }
         }
     }
 
-    if !dead_apps.is_empty() {
+    // The process we track can outlive the server it started, e.g. when the server was
+    // killed from Activity Monitor or by the OOM killer
+    let mut unresponsive: Vec<(String, u32, i32)> = Vec::new();
+    for (app_id, pid, port) in watched {
+        let answered = watchdog::port_answers(port).await;
+        if watchdog.record(&app_id, pid, answered) {
+            unresponsive.push((app_id, pid, port));
+        }
+    }
+
+    let mut stopped: Vec<(String, i32)> = Vec::new();
+    if !dead_apps.is_empty() || !unresponsive.is_empty() {
         let mut processes = app_state.processes.lock().await;
+        for (app_id, pid, port) in unresponsive {
+            // Skip apps restarted or stopped since the check
+            if processes.get(&app_id).is_none_or(|p| p.child.pid() != pid) {
+                continue;
+            }
+            let Some(process) = processes.remove(&app_id) else {
+                continue;
+            };
+            log::warn!(
+                "App {} stopped answering on port {}, stopping it",
+                app_id,
+                port
+            );
+            kill_process_tree(pid);
+            let _ = process.child.kill();
+            if let Some(container) = process.container {
+                std::thread::spawn(move || stop_container(&container));
+            }
+            live_apps.remove(&app_id);
+            dead_apps.push(app_id.clone());
+            stopped.push((app_id, port));
+        }
         for app_id in &dead_apps {
             processes.remove(app_id);
             remove_pid(app_id);
@@ -246,17 +728,29 @@ async fn cleanup_and_sync(app_handle: &AppHandle) {
             );
         }
     }
+    watchdog.retain(|app_id| live_apps.contains_key(app_id));
 
-    let mut expected_routes: HashMap<String, proxy::ProxyRoute> = HashMap::new();
-    for (app_id, (port, subdomain)) in &live_apps {
-        if let Some(sub) = subdomain {
-            expected_routes.insert(
-                app_id.clone(),
-                proxy::ProxyRoute {
-                    subdomain: sub.clone(),
-                    port: *port,
-                },
-            );
+    for (app_id, port) in stopped {
+        record_app_event(
+            app_handle,
+            &app_id,
+            AppEventKind::Crashed,
+            Some(format!("stopped answering on port {}", port)),
+        );
+        let name = app_name(app_handle, &app_id).await;
+        send_notification(
+            app_handle,
+            Notification::new(
+                NotificationKind::Crash,
+                i18n::tf("notification.unresponsive.title", &[("name", &name)]),
+                i18n::tf("notification.unresponsive.message", &[("port", &port)]),
+            ),
+        );
+    }
+    // The tray is otherwise only rebuilt when the window asks for it
+    if !dead_apps.is_empty() {
+        if let Err(e) = reload_apps(app_handle).await {
+            log::warn!("{}", e);
         }
     }
 
@@ -265,20 +759,30 @@ async fn cleanup_and_sync(app_handle: &AppHandle) {
         routes.clone()
     };
 
-    if expected_routes != current_routes {
-        {
-            let mut routes = proxy_state.routes.lock().await;
-            *routes = expected_routes.clone();
+    let expected_routes =
+        proxy::synced_routes(&current_routes, &live_apps, &dead_apps, is_port_in_use);
+
+    let inspector = app_handle.state::<InspectorState>();
+    for app_id in current_routes.keys() {
+        if !expected_routes.contains_key(app_id) {
+            inspector.stop_relay(app_id).await;
         }
-        
-        if let Err(e) = proxy::update_routes(&expected_routes).await {
+    }
+
+    if expected_routes != current_routes {
+        let mut routes = proxy_state.routes.lock().await;
+        *routes = expected_routes.clone();
+        if let Err(e) = proxy::apply_routes(&proxy_state, routes).await {
             log::error!("Failed to sync routes with Caddy: {}", e);
         }
     }
 
+    record_accesses(app_handle, &expected_routes);
+
     if let Some(lan_ip) = dns::get_lan_ip() {
         let expected_subdomains: std::collections::HashSet<String> = expected_routes
             .values()
+            .filter(|r| !r.disabled)
             .map(|r| r.subdomain.clone())
             .collect();
 
@@ -302,178 +806,1239 @@ async fn cleanup_and_sync(app_handle: &AppHandle) {
     }
 }
 
+// Notes the requests the proxy served since the last pass, from Caddy's access log or
+// the built-in proxy, and tells the window about apps that were just used
+fn record_accesses(app_handle: &AppHandle, routes: &HashMap<String, ProxyRoute>) {
+    let proxy_state = app_handle.state::<ProxyState>();
+    let tracker = app_handle.state::<AccessTracker>();
+    let settings = proxy_state.settings();
+    let accesses: Vec<(String, i64)> = match settings.backend {
+        ProxyBackend::Builtin => proxy_state
+            .builtin
+            .take_accessed()
+            .into_iter()
+            .filter_map(|(subdomain, at)| {
+                let (app_id, _) = routes.iter().find(|(_, r)| r.subdomain == subdomain)?;
+                Some((app_id.clone(), at))
+            })
+            .collect(),
+        ProxyBackend::Caddy => tracker
+            .read_caddy_log(std::path::Path::new(access_log::CADDY_ACCESS_LOG))
+            .into_iter()
+            .filter_map(|(host, at)| {
+                let app_id = proxy::app_for_host(routes, settings.dns_domain.as_deref(), &host)?;
+                Some((app_id, at))
+            })
+            .collect(),
+    };
+    for (app_id, at) in tracker.record(accesses) {
+        let _ = app_handle.emit(
+            "app-accessed",
+            serde_json::json!({
+                "id": app_id,
+                "at": at
+            }),
+        );
+    }
+}
+
+// Startup reconcile pass: brings the proxy back in line with the apps in the database and
+// the processes still running, whatever state the previous session left it in
+async fn restore_proxy_routes(
+    app_handle: &AppHandle,
+    stopped_apps: HashSet<String>,
+    kept: HashMap<String, KeptApp>,
+) {
+    let app_state = app_handle.state::<AppState>();
+    let proxy_state = app_handle.state::<ProxyState>();
+
+    if let Err(e) = proxy::start_configured_backend(&proxy_state).await {
+        crash_report::report_task_error(&app_data_dir(), "Proxy backend", &e.to_string());
+    }
+    if let Some(domain) = proxy_state.settings().dns_domain {
+        let responder = app_handle.state::<DnsResponder>();
+        if let Err(e) = responder.start(&domain).await {
+            crash_report::report_task_error(&app_data_dir(), "DNS responder", &e);
+        } else if dns::fallback_domain(&domain).is_some() {
+            // A macOS update can start shadowing the domain; the doctor offers the fix
+            tauri::async_runtime::spawn(async move {
+                if !dns::resolves_through_responder(&domain).await {
+                    log::warn!(
+                        "Lookups for *.{} don't reach the DNS responder; multicast DNS may be answering .local first",
+                        domain
+                    );
+                }
+            });
+        }
+    }
+
+    // Without the apps table (e.g. before the first migration) every saved route is kept
+    let app_ids: Option<HashSet<String>> = match load_apps(app_handle).await {
+        Ok(apps) => Some(apps.into_iter().map(|app| app.id).collect()),
+        Err(e) => {
+            log::warn!("Failed to load apps for startup reconcile: {}", e);
+            None
+        }
+    };
+    // Adopted apps count as running below, so their routes are restored
+    adopt_kept_apps(app_handle, kept, app_ids.as_ref()).await;
+
+    let saved = match app_handle.state::<Db>().proxy_routes().await {
+        Ok(saved) => saved,
+        Err(e) => {
+            log::warn!("{}", e);
+            HashMap::new()
+        }
+    };
+    let mut deleted_apps = Vec::new();
+    let mut restored: HashMap<String, ProxyRoute> = HashMap::new();
+    {
+        let processes = app_state.processes.lock().await;
+        for (app_id, mut route) in saved {
+            if !route.manual && app_ids.as_ref().is_some_and(|ids| !ids.contains(&app_id)) {
+                deleted_apps.push(route.subdomain);
+                continue;
+            }
+            // A process stopped above can hold its port for a moment after exiting
+            let running = processes.contains_key(&app_id)
+                || (!stopped_apps.contains(&app_id) && is_port_in_use(route.port));
+            if route.manual || running {
+                // Inspection relays don't survive a restart
+                route.relay_port = None;
+                restored.insert(app_id, route);
+            }
+        }
+    }
+
+    log::info!(
+        "Restored {} proxy route(s) from the previous session",
+        restored.len()
+    );
+    if !deleted_apps.is_empty() {
+        log::info!(
+            "Dropped proxy routes of deleted apps: {}",
+            deleted_apps.join(", ")
+        );
+    }
+
+    // Rate limits are enforced by a relay, so limited routes get a new one
+    let inspector = app_handle.state::<InspectorState>();
+    for (app_id, route) in restored.iter_mut() {
+        let Some(per_second) = route.rate_limit else {
+            continue;
+        };
+        match inspector.start_relay(app_id, route.port).await {
+            Ok(relay_port) => {
+                inspector.set_rate_limit(app_id, Some(per_second)).await;
+                route.relay_port = Some(relay_port);
+            }
+            Err(e) => log::error!("Failed to restore the rate limit for {}: {}", app_id, e),
+        }
+    }
+
+    let tunnels = app_handle.state::<TunnelManager>();
+    for (app_id, route) in &restored {
+        if let Some(tunnel) = &route.tunnel {
+            tunnels
+                .open(app_id, tunnel.clone(), route.port as u16)
+                .await;
+        }
+    }
+
+    let mut routes = proxy_state.routes.lock().await;
+    for (app_id, route) in restored {
+        routes.entry(app_id).or_insert(route);
+    }
+
+    // Caddy outlives a crash and keeps serving whatever it had loaded then
+    let dns_domain = proxy_state.settings().dns_domain;
+    let drift = match proxy::live_hosts(&proxy_state).await {
+        Ok(Some(live)) => proxy::host_drift(&routes, dns_domain.as_deref(), &live),
+        Ok(None) => HostDrift::default(),
+        Err(e) => {
+            log::warn!("Failed to compare routes with the running proxy: {}", e);
+            HostDrift::default()
+        }
+    };
+    if !drift.orphaned.is_empty() {
+        log::info!(
+            "Removing orphaned proxy hosts: {}",
+            drift.orphaned.join(", ")
+        );
+    }
+    if !drift.missing.is_empty() {
+        log::info!(
+            "Re-adding missing proxy hosts: {}",
+            drift.missing.join(", ")
+        );
+    }
+
+    // Nothing to restore and nothing out of sync; leave the proxy's placeholder alone
+    if routes.is_empty() && drift.is_empty() && deleted_apps.is_empty() {
+        return;
+    }
+    if let Err(e) = proxy::apply_routes(&proxy_state, routes).await {
+        log::error!("Failed to restore proxy routes: {}", e);
+    }
+}
+
+// Starts the apps that were running when an update was installed. Apps left running
+// through the restart were adopted by then and are skipped.
+async fn restore_update_snapshot(app_handle: &AppHandle) {
+    let snapshot = self_update::take_snapshot(&app_data_dir());
+    if snapshot.is_empty() {
+        return;
+    }
+    let _ = app_handle.emit(
+        "update-progress",
+        UpdateProgress::stage(UpdateStage::Restoring),
+    );
+    let running = running_apps(&app_handle.state(), &app_handle.state()).await;
+    for app in snapshot {
+        if running.contains_key(&app.id) {
+            continue;
+        }
+        let result =
+            start_saved_app_with_profile(app_handle.clone(), app.id.clone(), app.profile).await;
+        if let Err(e) = result {
+            log::error!("Failed to restart {} after the update: {}", app.id, e);
+        }
+    }
+    let _ = app_handle.emit("update-progress", UpdateProgress::stage(UpdateStage::Done));
+}
+
 #[tauri::command]
 fn generate_id() -> String {
     Uuid::new_v4().to_string()
 }
 
 #[tauri::command]
-fn get_free_port(preferred: Option<i32>) -> Result<i32, String> {
-    find_free_port(preferred).ok_or_else(|| "Could not find a free port".to_string())
+fn get_free_port(
+    settings: State<'_, SettingsStore>,
+    preferred: Option<i32>,
+) -> Result<i32, AppError> {
+    find_free_port(settings.get(&settings::PORT_RANGE), preferred).ok_or_else(|| {
+        AppError::port_unavailable(preferred.unwrap_or(0), i18n::t("error.no_free_port"))
+    })
 }
 
 #[tauri::command]
-async fn read_package_json(path: String) -> Result<serde_json::Value, String> {
+async fn read_package_json(path: String) -> Result<serde_json::Value, AppError> {
     let package_path = std::path::Path::new(&path).join("package.json");
     let content = std::fs::read_to_string(&package_path)
         .map_err(|e| format!("Failed to read package.json: {}", e))?;
-    serde_json::from_str(&content).map_err(|e| format!("Failed to parse package.json: {}", e))
-}
-
-fn shell_exists(name: &str) -> bool {
-    std::process::Command::new(name)
-        .arg("-c")
-        .arg("")
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false)
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse package.json: {}", e).into())
 }
 
-#[tauri::command]
-async fn start_app(
-    app_handle: AppHandle,
-    state: State<'_, AppState>,
-    id: String,
+// What `prepare_service_app` hands the frontend to insert as a new app
+#[derive(Debug, Clone, Serialize)]
+struct ServiceApp {
+    name: String,
     path: String,
     command: String,
     port: i32,
-    subdomain: Option<String>,
-) -> Result<i32, String> {
-    let mut processes = state.processes.lock().await;
-
-    if processes.contains_key(&id) {
-        let msg = "App is already running".to_string();
-        log::error!("{}", msg);
-        return Err(msg);
-    }
+    service: String,
+}
 
-    let actual_port =
-        find_free_port(Some(port)).ok_or_else(|| {
-            let msg = "Could not find a free port".to_string();
-            log::error!("{}", msg);
-            msg
-        })?;
+#[tauri::command]
+fn get_service_presets() -> Vec<services::ServicePreset> {
+    services::PRESETS.to_vec()
+}
 
-    if command.trim().is_empty() {
-        let msg = "Invalid command".to_string();
-        log::error!("{}", msg);
-        return Err(msg);
-    }
+/// Creates the folder a database service app runs in and builds its command.
+#[tauri::command]
+fn prepare_service_app(
+    settings: State<'_, SettingsStore>,
+    preset: String,
+    name: String,
+    local: bool,
+) -> Result<ServiceApp, AppError> {
+    let preset = services::preset(&preset)
+        .ok_or_else(|| AppError::not_found(format!("Unknown service preset {}", preset)))?;
+    let base = Some(proxy::slugify(&name))
+        .filter(|slug| !slug.is_empty())
+        .unwrap_or_else(|| preset.id.to_string());
 
-    let default_shell = if cfg!(target_os = "macos") {
-        "zsh"
-    } else {
-        "bash"
-    };
-    let preferred = std::env::var("SHELL")
-        .ok()
-        .and_then(|s| {
-            std::path::Path::new(&s)
-                .file_name()
-                .and_then(|s| s.to_str())
-                .map(String::from)
+    // A fresh folder per service, so containers and volumes of two apps never collide
+    let services_dir = ensure_app_data_dir()?.join("services");
+    let (slug, dir) = (1..)
+        .map(|n| match n {
+            1 => base.clone(),
+            n => format!("{}-{}", base, n),
         })
-        .filter(|s| s == "zsh" || s == "bash")
-        .unwrap_or_else(|| default_shell.into());
+        .map(|slug| (slug.clone(), services_dir.join(slug)))
+        .find(|(_, dir)| !dir.exists())
+        .ok_or_else(|| "Could not pick a service folder".to_string())?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
 
-    let shell_basename = if shell_exists(&preferred) {
-        preferred
-    } else if preferred == "zsh" && shell_exists("bash") {
-        "bash".into()
-    } else if preferred == "bash" && shell_exists("zsh") {
-        "zsh".into()
-    } else {
-        "sh".into()
-    };
+    Ok(ServiceApp {
+        name,
+        path: dir.to_string_lossy().to_string(),
+        command: services::start_command(preset, &slug, local)?,
+        port: find_free_port(settings.get(&settings::PORT_RANGE), Some(preset.port))
+            .unwrap_or(preset.port),
+        service: preset.id.to_string(),
+    })
+}
 
-    let c_string = r#"eval "$MY_APP_CMD""#;
-    let shell_args: Vec<&str> = if shell_basename == "zsh" || shell_basename == "bash" {
-        vec!["-i", "-l", "-c", c_string]
-    } else {
-        vec!["-c", c_string]
-    };
+// Long enough for `docker exec` on a busy machine
+const SERVICE_HEALTH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
 
-    let shell = app_handle.shell();
-    let cmd = shell
-        .command(&shell_basename)
-        .args(shell_args)
-        .current_dir(&path)
-        .env("PORT", actual_port.to_string())
-        .env("MY_APP_CMD", command.trim());
+#[tauri::command]
+async fn get_uptime(
+    db: State<'_, Db>,
+    id: String,
+    range: health::UptimeRange,
+) -> Result<health::Uptime, AppError> {
+    let checks = db.health_checks(&id, range.since(health::now_ms())).await?;
+    let paths = db.health_paths().await?;
+    let path = paths.get(&id).map_or(health::DEFAULT_PATH, String::as_str);
+    Ok(health::summarize(path, &checks))
+}
 
-    let (mut rx, child) = cmd.spawn().map_err(|e| {
-        let msg = format!("Failed to start app: {}", e);
-        log::error!("{}", msg);
-        msg
-    })?;
+/// What happened to the app since `since` (unix ms; the last week by default), newest first.
+#[tauri::command]
+async fn get_app_timeline(
+    db: State<'_, Db>,
+    id: String,
+    since: Option<i64>,
+) -> Result<Vec<AppEvent>, AppError> {
+    let since = since.unwrap_or_else(|| health::now_ms() - timeline::DEFAULT_RANGE_MS);
+    Ok(db.app_events(&id, since).await?)
+}
 
-    let child_pid = child.pid();
-    save_pid(&id, child_pid);
+#[tauri::command]
+async fn set_health_path(db: State<'_, Db>, id: String, path: String) -> Result<(), AppError> {
+    let path = health::normalize_path(&path)?;
+    db.set_health_path(&id, path.as_deref()).await
+}
+
+#[tauri::command]
+fn get_quit_behavior(preferences: State<'_, PreferencesState>) -> QuitBehavior {
+    preferences.get().apps_on_quit
+}
+
+/// What happens to apps without their own setting when My Little Apps quits.
+#[tauri::command]
+fn set_quit_behavior(preferences: State<'_, PreferencesState>, behavior: QuitBehavior) {
+    preferences.update(|p| p.apps_on_quit = behavior);
+}
+
+#[tauri::command]
+async fn get_app_quit_behavior(
+    db: State<'_, Db>,
+    id: String,
+) -> Result<Option<QuitBehavior>, AppError> {
+    Ok(db.quit_behaviors().await?.get(&id).copied())
+}
+
+/// Overrides the global quit behavior for one app; None follows it again. Takes effect
+/// the next time the app starts.
+#[tauri::command]
+async fn set_app_quit_behavior(
+    db: State<'_, Db>,
+    id: String,
+    behavior: Option<QuitBehavior>,
+) -> Result<(), AppError> {
+    db.set_quit_behavior(&id, behavior).await
+}
+
+#[tauri::command]
+async fn get_app_notify_ready(db: State<'_, Db>, id: String) -> Result<bool, AppError> {
+    Ok(db.notify_ready_apps().await?.contains(&id))
+}
+
+/// Sends a notification with the app's URL each time it becomes ready, for slow
+/// starters that get started and forgotten.
+#[tauri::command]
+async fn set_app_notify_ready(db: State<'_, Db>, id: String, notify: bool) -> Result<(), AppError> {
+    db.set_notify_ready(&id, notify).await
+}
+
+#[tauri::command]
+async fn get_app_sandbox(db: State<'_, Db>, id: String) -> Result<bool, AppError> {
+    Ok(db.sandboxed_apps().await?.contains(&id))
+}
+
+/// Runs the app under `sandbox-exec` from its next start, for trying untrusted code:
+/// no network beyond localhost, no writes outside the project and temporary files.
+#[tauri::command]
+async fn set_app_sandbox(db: State<'_, Db>, id: String, sandboxed: bool) -> Result<(), AppError> {
+    db.set_sandbox(&id, sandboxed).await
+}
+
+#[tauri::command]
+async fn get_app_rebuild_stale(db: State<'_, Db>, id: String) -> Result<bool, AppError> {
+    Ok(db.rebuild_stale_apps().await?.contains(&id))
+}
+
+/// Runs the package's build script before starting when the build the app serves is
+/// missing or older than its sources, instead of only warning.
+#[tauri::command]
+async fn set_app_rebuild_stale(
+    db: State<'_, Db>,
+    id: String,
+    rebuild: bool,
+) -> Result<(), AppError> {
+    db.set_rebuild_stale(&id, rebuild).await
+}
+
+#[tauri::command]
+async fn get_app_log_sampling(db: State<'_, Db>, id: String) -> Result<bool, AppError> {
+    Ok(db.log_sampled_apps().await?.contains(&id))
+}
+
+/// Has the window only show a sample of the app's output while it prints more than a
+/// few hundred lines a second. The log buffer and forwarding still get every line.
+#[tauri::command]
+async fn set_app_log_sampling(
+    db: State<'_, Db>,
+    log_events: State<'_, LogEvents>,
+    id: String,
+    sampled: bool,
+) -> Result<(), AppError> {
+    db.set_log_sampling(&id, sampled).await?;
+    log_events.set_app_sampled(&id, sampled);
+    Ok(())
+}
+
+/// The release the update endpoint offers, if newer than this one.
+#[tauri::command]
+async fn check_for_update(app_handle: AppHandle) -> Result<Option<UpdateInfo>, AppError> {
+    if self_update::pubkey().is_none() {
+        return Ok(None);
+    }
+    let updater = app_handle.updater().map_err(|e| e.to_string())?;
+    let update = updater
+        .check()
+        .await
+        .map_err(|e| format!("Failed to check for updates: {}", e))?;
+    Ok(update.map(|update| UpdateInfo {
+        version: update.version,
+        current_version: update.current_version,
+        notes: update.body,
+        date: update.date.map(|date| date.to_string()),
+    }))
+}
+
+/// Installs the available update and relaunches. Apps running now are started again by
+/// the new version, see `restore_update_snapshot`.
+#[tauri::command]
+async fn install_update(app_handle: AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
+    if self_update::pubkey().is_none() {
+        return Err("This build has no key to verify updates with".into());
+    }
+    let updater = app_handle.updater().map_err(|e| e.to_string())?;
+    let update = updater
+        .check()
+        .await
+        .map_err(|e| format!("Failed to check for updates: {}", e))?
+        .ok_or_else(|| AppError::not_found("No update available"))?;
+
+    let progress_handle = app_handle.clone();
+    let mut downloaded = 0u64;
+    let mut last_emit = std::time::Instant::now() - self_update::PROGRESS_INTERVAL;
+    let on_chunk = move |chunk: usize, total: Option<u64>| {
+        downloaded += chunk as u64;
+        if last_emit.elapsed() < self_update::PROGRESS_INTERVAL {
+            return;
+        }
+        last_emit = std::time::Instant::now();
+        let progress = UpdateProgress {
+            stage: UpdateStage::Downloading,
+            downloaded,
+            total,
+        };
+        let _ = progress_handle.emit("update-progress", progress);
+    };
+    let installing_handle = app_handle.clone();
+    let on_download_finish = move || {
+        let progress = UpdateProgress::stage(UpdateStage::Installing);
+        let _ = installing_handle.emit("update-progress", progress);
+    };
+    // Nothing is stopped until the new version is in place, so a failed download or a bad
+    // signature leaves everything running
+    update
+        .download_and_install(on_chunk, on_download_finish)
+        .await
+        .map_err(|e| format!("Failed to install update: {}", e))?;
+    log::info!("Installed version {}, restarting", update.version);
+
+    let proxy_state = app_handle.state::<ProxyState>();
+    let running = running_apps(&state, &proxy_state).await;
+    let snapshot: Vec<SnapshotApp> = {
+        let processes = state.processes.lock().await;
+        running
+            .into_keys()
+            .map(|id| SnapshotApp {
+                profile: processes.get(&id).and_then(|p| p.profile.clone()),
+                id,
+            })
+            .collect()
+    };
+    self_update::save_snapshot(&app_data_dir(), &snapshot)?;
+
+    let _ = app_handle.emit(
+        "update-progress",
+        UpdateProgress::stage(UpdateStage::Restarting),
+    );
+    // restart() skips the exit events that normally run the cleanup
+    let restart_handle = app_handle.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        shutdown(&restart_handle);
+        restart_handle.restart();
+    });
+    Ok(())
+}
+
+/// Whether a running service app accepts connections yet.
+#[tauri::command]
+async fn check_service_health(state: State<'_, AppState>, id: String) -> Result<bool, AppError> {
+    let app = state.find_app(&id).await?;
+    let preset = app
+        .service
+        .as_deref()
+        .and_then(services::preset)
+        .ok_or_else(|| format!("{} is not a service", app.name))?;
+    let port = state
+        .processes
+        .lock()
+        .await
+        .get(&id)
+        .map(|process| process.port)
+        .ok_or_else(|| AppError::not_found("Service is not running"))?;
+    let probe = services::health_command(preset, &app.command)
+        .ok_or_else(|| format!("No health check for {}", preset.name))?;
+
+    let shell = login_shell();
+    let mut cmd = tokio::process::Command::new(&shell);
+    cmd.args(login_shell_args(&shell))
+        .arg(probe)
+        .env("PORT", port.to_string())
+        .current_dir(&app.path)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .kill_on_drop(true);
+    let status = tokio::time::timeout(SERVICE_HEALTH_TIMEOUT, cmd.status()).await;
+    Ok(matches!(status, Ok(Ok(status)) if status.success()))
+}
+
+/// Load-tests a running app on its own port, bypassing the proxy. `duration` is in seconds.
+#[tauri::command]
+async fn benchmark_app(
+    state: State<'_, AppState>,
+    id: String,
+    duration: u64,
+    concurrency: usize,
+) -> Result<benchmark::BenchmarkReport, AppError> {
+    let port = state
+        .processes
+        .lock()
+        .await
+        .get(&id)
+        .map(|process| process.port)
+        .ok_or_else(|| AppError::not_found(i18n::t("error.app_not_running")))?;
+
+    let duration = duration.clamp(1, benchmark::MAX_DURATION_SECS);
+    let concurrency = concurrency.clamp(1, benchmark::MAX_CONCURRENCY);
+    let report =
+        benchmark::run(port, std::time::Duration::from_secs(duration), concurrency).await?;
+    log::info!(
+        "Benchmarked app {}: {} requests, {:.0} req/s, p99 {:.1}ms, {} errors",
+        id,
+        report.requests,
+        report.requests_per_sec,
+        report.latency.p99,
+        report.errors
+    );
+    Ok(report)
+}
+
+/// Config changes (command, env, port, subdomain) saved since the running app started;
+/// empty when it's up to date or not running.
+#[tauri::command]
+async fn needs_restart(app_handle: AppHandle, id: String) -> Result<Vec<ConfigChange>, AppError> {
+    Ok(pending_config_changes(&app_handle, &id).await?)
+}
+
+/// Processes, environment and listening sockets of a running app's whole process tree.
+#[tauri::command]
+async fn inspect_process(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<process_info::ProcessInspection, AppError> {
+    let pid = state
+        .processes
+        .lock()
+        .await
+        .get(&id)
+        .map(|process| process.child.pid())
+        .ok_or_else(|| AppError::not_found(i18n::t("error.app_not_running")))?;
+
+    let inspection = tauri::async_runtime::spawn_blocking(move || process_info::inspect(pid))
+        .await
+        .map_err(|e| format!("Failed to inspect process: {}", e))??;
+    Ok(inspection)
+}
+
+/// Compares the environment the running app should have by its current config (profile,
+/// command and .env) with the one its process has, to tell when a restart is needed.
+#[tauri::command]
+async fn diff_app_env(
+    state: State<'_, AppState>,
+    db: State<'_, Db>,
+    id: String,
+) -> Result<env_diff::EnvDiff, AppError> {
+    let (pid, port, profile, launched, container) = state
+        .processes
+        .lock()
+        .await
+        .get(&id)
+        .map(|p| {
+            (
+                p.child.pid(),
+                p.port,
+                p.profile.clone(),
+                p.env.clone(),
+                p.container.clone(),
+            )
+        })
+        .ok_or_else(|| AppError::not_found(i18n::t("error.app_not_running")))?;
+    let app = db
+        .apps()
+        .await?
+        .into_iter()
+        .find(|app| app.id == id)
+        .ok_or_else(|| AppError::unknown_app(&id))?;
+    let remote = app
+        .remote_host
+        .as_ref()
+        .is_some_and(|h| !h.trim().is_empty());
+    if remote || container.is_some() {
+        return Err("Only apps running on this Mac can be compared".into());
+    }
+
+    let (command, profile_env) = match &profile {
+        Some(name) => db
+            .profile(&id, name)
+            .await?
+            .map(|profile| (profile.command, profile.env))
+            .unwrap_or_default(),
+        None => (app.command.clone(), HashMap::new()),
+    };
+    let dotenv = tokio::fs::read_to_string(std::path::Path::new(&app.path).join(".env"))
+        .await
+        .map(|contents| env_diff::parse_dotenv(&contents))
+        .unwrap_or_default();
+    let expected = env_diff::expected_env(dotenv, &profile_env, &command);
+
+    let inspection = tauri::async_runtime::spawn_blocking(move || process_info::inspect(pid))
+        .await
+        .map_err(|e| format!("Failed to inspect process: {}", e))??;
+    // The login shell wrapping the command doesn't have its assignments, so compare the
+    // process listening on the app's port when there is one
+    let listener = inspection
+        .sockets
+        .iter()
+        .find(|socket| i32::from(socket.port) == port)
+        .map(|socket| socket.pid);
+    let process = inspection
+        .processes
+        .iter()
+        .find(|p| Some(p.pid) == listener)
+        .or_else(|| inspection.processes.last())
+        .ok_or_else(|| AppError::not_found(i18n::t("error.app_not_running")))?;
+    Ok(env_diff::diff(
+        process.pid,
+        &expected,
+        &launched,
+        &process.env,
+    ))
+}
+
+/// Every TCP listener on the machine, attributed to managed apps where they own it.
+#[tauri::command]
+async fn get_listening_ports(
+    state: State<'_, AppState>,
+) -> Result<Vec<process_info::ListeningPort>, AppError> {
+    let apps: Vec<(String, u32)> = state
+        .processes
+        .lock()
+        .await
+        .iter()
+        .map(|(id, process)| (id.clone(), process.child.pid()))
+        .collect();
+
+    let ports = tauri::async_runtime::spawn_blocking(move || process_info::listening_ports(&apps))
+        .await
+        .map_err(|e| format!("Failed to list listening ports: {}", e))??;
+    Ok(ports)
+}
+
+#[tauri::command]
+fn has_devcontainer(path: String) -> bool {
+    devcontainer::find(std::path::Path::new(&path)).is_some()
+}
+
+#[tauri::command]
+fn detect_project(path: String) -> Option<project::DetectedProject> {
+    project::detect(std::path::Path::new(&path))
+}
+
+// Large enough for real READMEs, small enough to render without stalling the webview
+const README_MAX_BYTES: u64 = 256 * 1024;
+const README_NAMES: [&str; 5] = [
+    "readme.md",
+    "readme.mdx",
+    "readme.markdown",
+    "readme.txt",
+    "readme",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectReadme {
+    pub file_name: String,
+    pub content: String,
+    pub truncated: bool,
+}
+
+fn find_readme(dir: &std::path::Path) -> Option<std::path::PathBuf> {
+    let entries: Vec<std::path::PathBuf> = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+
+    README_NAMES.iter().find_map(|name| {
+        entries
+            .iter()
+            .find(|path| {
+                path.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.eq_ignore_ascii_case(name))
+            })
+            .cloned()
+    })
+}
+
+#[tauri::command]
+async fn read_project_readme(path: String) -> Result<Option<ProjectReadme>, AppError> {
+    use std::io::Read;
+
+    let Some(readme_path) = find_readme(std::path::Path::new(&path)) else {
+        return Ok(None);
+    };
+
+    let file =
+        std::fs::File::open(&readme_path).map_err(|e| format!("Failed to open README: {}", e))?;
+    let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let mut bytes = Vec::new();
+    file.take(README_MAX_BYTES)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read README: {}", e))?;
+
+    Ok(Some(ProjectReadme {
+        file_name: readme_path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        content: String::from_utf8_lossy(&bytes).into_owned(),
+        truncated: size > README_MAX_BYTES,
+    }))
+}
+
+fn shell_exists(name: &str) -> bool {
+    std::process::Command::new(name)
+        .arg("-c")
+        .arg("")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+// Shell apps are started in: the user's zsh/bash when available, falling back to sh
+fn login_shell() -> String {
+    let default_shell = if cfg!(target_os = "macos") {
+        "zsh"
+    } else {
+        "bash"
+    };
+    let preferred = std::env::var("SHELL")
+        .ok()
+        .and_then(|s| {
+            std::path::Path::new(&s)
+                .file_name()
+                .and_then(|s| s.to_str())
+                .map(String::from)
+        })
+        .filter(|s| s == "zsh" || s == "bash")
+        .unwrap_or_else(|| default_shell.into());
+
+    if shell_exists(&preferred) {
+        preferred
+    } else if preferred == "zsh" && shell_exists("bash") {
+        "bash".into()
+    } else if preferred == "bash" && shell_exists("zsh") {
+        "zsh".into()
+    } else {
+        "sh".into()
+    }
+}
+
+// Arguments up to and including `-c`; the script to run goes last
+fn login_shell_args(shell_basename: &str) -> Vec<&'static str> {
+    if shell_basename == "zsh" || shell_basename == "bash" {
+        vec!["-i", "-l", "-c"]
+    } else {
+        vec!["-c"]
+    }
+}
+
+// The shell an app runs in and its arguments up to `-c`. A picked shell isn't interactive,
+// so rc files with prompts or slow plugins are skipped while the profile still sets PATH
+fn app_shell(choice: Option<&str>) -> (String, Vec<&'static str>) {
+    match choice.filter(|shell| app_config::SHELLS.contains(shell)) {
+        Some("sh") => ("sh".to_string(), vec!["-c"]),
+        Some(shell) => (shell.to_string(), vec!["-l", "-c"]),
+        None => {
+            let shell = login_shell();
+            let args = login_shell_args(&shell);
+            (shell, args)
+        }
+    }
+}
+
+#[tauri::command]
+async fn scan_workspace(path: String) -> Result<Vec<WorkspacePackage>, AppError> {
+    let packages =
+        tauri::async_runtime::spawn_blocking(move || workspace::scan(std::path::Path::new(&path)))
+            .await
+            .map_err(|e| format!("Failed to scan workspace: {}", e))??;
+    Ok(packages)
+}
+
+#[tauri::command]
+async fn validate_app_config(
+    path: String,
+    command: String,
+    build_command: Option<String>,
+    prerequisites: Option<String>,
+    path_entries: Option<String>,
+    devcontainer: Option<bool>,
+    shell: Option<String>,
+) -> Vec<ConfigIssue> {
+    let mut issues: Vec<ConfigIssue> = app_config::check_shell(shell.as_deref())
+        .into_iter()
+        .collect();
+    // Commands are looked up the way the app will run them
+    let (shell, shell_args) = app_shell(shell.as_deref());
+    issues.extend(
+        app_config::validate(
+            &path,
+            &command,
+            build_command.as_deref(),
+            prerequisites.as_deref(),
+            path_entries.as_deref(),
+            devcontainer.unwrap_or(false),
+            &shell,
+            &shell_args,
+        )
+        .await,
+    );
+    issues
+}
+
+#[tauri::command]
+async fn start_app(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<i32, AppError> {
+    let app = state.find_app(&id).await?;
+    let spec = launch_spec(&app_handle, app, None)?;
+    launch_app(app_handle, &state, spec).await
+}
+
+#[tauri::command]
+async fn start_app_with_profile(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+    profile: String,
+) -> Result<i32, AppError> {
+    let app = state.find_app(&id).await?;
+    let profile = app_handle
+        .state::<Db>()
+        .profile(&id, &profile)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("No launch profile named {}", profile)))?;
+    let spec = launch_spec(&app_handle, app, Some(profile))?;
+    launch_app(app_handle, &state, spec).await
+}
+
+// How `app` is launched, with `profile`'s command and env in place of its own. Without a
+// port from the profile or the app, a free one in the port range is picked.
+fn launch_spec(
+    app_handle: &AppHandle,
+    app: App,
+    profile: Option<LaunchProfile>,
+) -> Result<LaunchSpec, AppError> {
+    let (command, env, port, profile) = match profile {
+        Some(profile) => (
+            profile.command,
+            profile.env,
+            profile.port.or(app.port),
+            Some(profile.name),
+        ),
+        None => (app.command, HashMap::new(), app.port, None),
+    };
+    let range = app_handle
+        .state::<SettingsStore>()
+        .get(&settings::PORT_RANGE);
+    let port = port
+        .or_else(|| find_free_port(range, None))
+        .ok_or_else(|| AppError::port_unavailable(0, i18n::t("error.no_free_port")))?;
+    Ok(LaunchSpec {
+        id: app.id,
+        path: app.path,
+        command,
+        port,
+        subdomain: app.subdomain.filter(|s| !s.is_empty()),
+        env,
+        profile,
+        build_command: app.build_command,
+        prerequisites: app.prerequisites,
+        remote_host: app.remote_host,
+        devcontainer: app.devcontainer,
+        path_entries: app.path_entries,
+        shell: app.shell,
+    })
+}
+
+#[tracing::instrument(
+    skip_all,
+    fields(app = %spec.id, port = spec.port, profile = ?spec.profile),
+    err(Display)
+)]
+async fn launch_app(
+    app_handle: AppHandle,
+    state: &AppState,
+    spec: LaunchSpec,
+) -> Result<i32, AppError> {
+    let LaunchSpec {
+        id,
+        path,
+        command,
+        port,
+        subdomain,
+        env,
+        profile,
+        build_command,
+        prerequisites,
+        remote_host,
+        devcontainer,
+        path_entries,
+        shell,
+    } = spec;
+    // Resolved once per launch, so the app keeps its URL until it restarts
+    let subdomain = match subdomain {
+        Some(template) => {
+            Some(resolve_subdomain(&app_handle, &id, &template, profile.as_deref()).await)
+        }
+        None => None,
+    };
+    let remote_host = remote_host.filter(|host| !host.trim().is_empty());
+    // Remote and devcontainer apps run their build where the app runs, as part of its command
+    let on_host = remote_host.is_none() && !devcontainer;
+    // Directories on this Mac, so they don't apply where remote or devcontainer apps run
+    let path_entries = match &path_entries {
+        Some(spec) if on_host => path_entries::resolve(spec, std::path::Path::new(&path)),
+        _ => Vec::new(),
+    };
+
+    if let Some(prerequisites) = &prerequisites {
+        let missing = prerequisites::unmet(prerequisites).await;
+        if !missing.is_empty() {
+            let msg = format!("Prerequisite not met:\n- {}", missing.join("\n- "));
+            log::error!("App {} not started: {}", id, missing.join(", "));
+            return Err(msg.into());
+        }
+    }
+
+    // Build before taking the process lock, builds can take minutes
+    let build_command = build_command.filter(|c| !c.trim().is_empty());
+    let mut local_build = build_command.clone().filter(|_| on_host);
+    if local_build.is_none() && on_host {
+        local_build = stale_build_step(&app_handle, &id, &path, &command).await;
+    }
+    if let Some(build) = &local_build {
+        if state.processes.lock().await.contains_key(&id) {
+            return Err(i18n::t("error.app_already_running").into());
+        }
+        state.logs.lock().await.insert(id.clone(), Vec::new());
+        set_building(&app_handle, &id, true).await;
+        let built = run_logged_step(
+            &app_handle,
+            state,
+            &id,
+            &path,
+            build.trim(),
+            shell.as_deref(),
+            &path_entries,
+        )
+        .await;
+        set_building(&app_handle, &id, false).await;
+        built?;
+    }
+
+    // Whether the app may outlive a quit is settled here, its output can't be moved later
+    let detached_log = (quit_behavior(&app_handle, &id).await == QuitBehavior::KeepRunning)
+        .then(|| keep_running::log_path(&app_data_dir(), &id));
+    let port_range = app_handle
+        .state::<SettingsStore>()
+        .get(&settings::PORT_RANGE);
+    let sandboxed = app_handle
+        .state::<Db>()
+        .sandboxed_apps()
+        .await?
+        .contains(&id);
+    // Running untrusted code without the sandbox asked for is worse than not running it
+    if sandboxed && !on_host {
+        return Err("Sandboxing only works for apps that run on this Mac".into());
+    }
+
+    let mut processes = state.processes.lock().await;
+
+    if processes.contains_key(&id) {
+        let msg = i18n::t("error.app_already_running");
+        log::error!("{}", msg);
+        return Err(msg.into());
+    }
+
+    let actual_port = find_free_port(port_range, Some(port)).ok_or_else(|| {
+        let msg = i18n::t("error.no_free_port").to_string();
+        log::error!("{}", msg);
+        AppError::port_unavailable(port, msg)
+    })?;
+
+    if command.trim().is_empty() {
+        let msg = "Invalid command".to_string();
+        log::error!("{}", msg);
+        return Err(msg.into());
+    }
+
+    let full_command = match build_command.as_deref().filter(|_| !on_host) {
+        Some(build) => format!("{} && {}", build.trim(), command.trim()),
+        None => command.trim().to_string(),
+    };
+    // A remote app's path only exists on its host, so ssh starts from the app data dir
+    let (run_command, run_dir) = match &remote_host {
+        Some(host) => {
+            let ssh =
+                remote::ssh_command(host.trim(), &path, &full_command, &env, port, actual_port);
+            (ssh, app_data_dir().to_string_lossy().to_string())
+        }
+        None if devcontainer => {
+            let dir = std::path::Path::new(&path);
+            let config = devcontainer::load(dir)?;
+            let slug = proxy::slugify(&dir.file_name().unwrap_or_default().to_string_lossy());
+            let container_port = config.forward_port.unwrap_or(actual_port);
+            let docker = devcontainer::run_command(
+                &config,
+                &slug,
+                &full_command,
+                &env,
+                container_port,
+                actual_port,
+            );
+            (docker, path.clone())
+        }
+        None => (full_command, path.clone()),
+    };
+
+    let (shell_basename, shell_args) = app_shell(shell.as_deref());
+    let mut shell_args: Vec<String> = shell_args.into_iter().map(String::from).collect();
+    // The venv goes on PATH after the profile is sourced, since path_helper would reorder it
+    let venv = python::venv_dir(std::path::Path::new(&path)).filter(|_| on_host);
+    let mut script = String::new();
+    if venv.is_some() {
+        script.push_str(r#"export PATH="$MY_APP_VENV/bin:$PATH" VIRTUAL_ENV="$MY_APP_VENV"; "#);
+    }
+    let extra_path = path_entries::env_value(&path_entries);
+    if extra_path.is_some() {
+        script.push_str(path_entries::EXPORT);
+    }
+    script.push_str(match detached_log {
+        Some(_) => keep_running::DETACHED_EVAL,
+        None => r#"eval "$MY_APP_CMD""#,
+    });
+    shell_args.push(script);
+    // Looked at once the app is spawned, so finding its runtime doesn't hold up the start
+    let package_check = on_host.then(|| (path.clone(), shell.clone(), extra_path.clone()));
+    let (program, args) = if sandboxed {
+        let writable: Vec<std::path::PathBuf> = detached_log
+            .iter()
+            .filter_map(|log| log.parent().map(std::path::Path::to_path_buf))
+            .collect();
+        // Keeps it away from the database and other apps' logs
+        let private: Vec<std::path::PathBuf> = std::iter::once(app_data_dir())
+            .chain(app_handle.path().app_config_dir().ok())
+            .collect();
+        let profile = sandbox::profile(
+            std::path::Path::new(&path),
+            &writable,
+            &private,
+            actual_port,
+        );
+        let mut args = vec!["-p".to_string(), profile, shell_basename];
+        args.extend(shell_args);
+        (sandbox::SANDBOX_EXEC.to_string(), args)
+    } else {
+        (shell_basename, shell_args)
+    };
+
+    let shell = app_handle.shell();
+    let mut cmd = shell
+        .command(&program)
+        .args(args)
+        .current_dir(&run_dir)
+        .envs(env.clone())
+        .env("PORT", actual_port.to_string())
+        .env(instance::ENV, instance::identity(&app_data_dir(), &id))
+        .env("MY_APP_CMD", &run_command);
+    if let Some(venv) = venv {
+        cmd = cmd.env("MY_APP_VENV", venv.to_string_lossy().to_string());
+    }
+    if let Some(extra_path) = extra_path {
+        cmd = cmd.env("MY_APP_PATH", extra_path);
+    }
+    if let Some(log) = &detached_log {
+        if let Some(dir) = log.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create log dir: {}", e))?;
+        }
+        std::fs::File::create(log).map_err(|e| format!("Failed to create log file: {}", e))?;
+        cmd = cmd.env("MY_APP_LOG", log.to_string_lossy().to_string());
+    }
+
+    // Raw chunks, assembled into lines by `output::Lines`
+    let (mut rx, child) = cmd.set_raw_out(true).spawn().map_err(|e| {
+        let msg = format!("Failed to start app: {}", e);
+        log::error!("{}", msg);
+        AppError::spawn_failed(msg, e.to_string())
+    })?;
+
+    let child_pid = child.pid();
+    save_pid(&id, child_pid);
 
     processes.insert(
         id.clone(),
         RunningProcess {
-            child,
+            child: AppProcess::Spawned(child),
             port: actual_port,
+            requested_port: port,
             subdomain,
+            profile: profile.clone(),
+            container: services::docker_container(&run_command).filter(|_| remote_host.is_none()),
+            command: command.clone(),
+            env,
+            ready: false,
+            log: detached_log.clone(),
         },
     );
+    // A fresh process runs the saved config; the frontend clears its flag on app-started
+    state.restart_needed.lock().await.remove(&id);
+    // ssh -L and docker accept connections before the app behind them listens, so
+    // remote and devcontainer apps only go by their output
+    if on_host {
+        tauri::async_runtime::spawn(wait_for_port(
+            app_handle.clone(),
+            id.clone(),
+            child_pid,
+            actual_port,
+        ));
+    }
+    tauri::async_runtime::spawn(watch_startup(app_handle.clone(), id.clone(), child_pid));
+    if let Some((dir, shell, extra_path)) = package_check {
+        tauri::async_runtime::spawn(refresh_package_meta(
+            app_handle.clone(),
+            id.clone(),
+            dir,
+            shell,
+            extra_path,
+        ));
+    }
 
-    // Initialize logs for this app
-    {
+    // Initialize logs for this app, keeping the build output above it
+    if local_build.is_none() {
         let mut logs = state.logs.lock().await;
         logs.insert(id.clone(), Vec::new());
     }
+    if let Some(log) = detached_log {
+        tauri::async_runtime::spawn(follow_log(
+            app_handle.clone(),
+            id.clone(),
+            child_pid,
+            log,
+            0,
+        ));
+    }
 
     // Spawn a task to capture output
     let logs = state.logs.clone();
     let app_id = id.clone();
     let handle = app_handle.clone();
+    let ready_patterns = app_handle.state::<PreferencesState>().get().ready_patterns;
+    let detector = ReadyDetector::new(&ready_patterns);
 
     tauri::async_runtime::spawn(async move {
+        let mut ready = false;
+        let mut stdout = output::Lines::default();
+        let mut stderr = output::Lines::default();
         while let Some(event) = rx.recv().await {
-            match event {
-                CommandEvent::Stdout(bytes) => {
-                    if let Ok(line) = String::from_utf8(bytes) {
-                        let mut logs_guard = logs.lock().await;
-                        if let Some(app_logs) = logs_guard.get_mut(&app_id) {
-                            app_logs.push(format!("[stdout] {}", line.trim()));
-                            // Keep only last 500 lines
-                            if app_logs.len() > 500 {
-                                app_logs.remove(0);
-                            }
+            let (stream, lines) = match event {
+                CommandEvent::Stdout(bytes) => ("stdout", stdout.push(&bytes)),
+                CommandEvent::Stderr(bytes) => ("stderr", stderr.push(&bytes)),
+                CommandEvent::Terminated(payload) => {
+                    for (stream, lines) in [("stdout", &mut stdout), ("stderr", &mut stderr)] {
+                        if let Some(line) = lines.finish() {
+                            push_app_log(&handle, &logs, &app_id, stream, line.trim()).await;
                         }
-                        // Emit log event to frontend
-                        let _ = handle.emit(
-                            "app-log",
-                            serde_json::json!({
-                                "id": app_id,
-                                "type": "stdout",
-                                "message": line.trim()
-                            }),
-                        );
                     }
-                }
-                CommandEvent::Stderr(bytes) => {
-                    if let Ok(line) = String::from_utf8(bytes) {
-                        let mut logs_guard = logs.lock().await;
-                        if let Some(app_logs) = logs_guard.get_mut(&app_id) {
-                            app_logs.push(format!("[stderr] {}", line.trim()));
-                            if app_logs.len() > 500 {
-                                app_logs.remove(0);
+                    // Still registered means nobody asked it to stop
+                    let crashed = {
+                        let state = handle.state::<AppState>();
+                        let processes = state.processes.lock().await;
+                        processes
+                            .get(&app_id)
+                            .is_some_and(|p| p.child.pid() == child_pid)
+                    };
+                    if crashed {
+                        let detail = match payload.code {
+                            Some(code) => format!("exit code {}", code),
+                            None => "killed by a signal".to_string(),
+                        };
+                        record_app_event(&handle, &app_id, AppEventKind::Crashed, Some(detail));
+                        let name = app_name(&handle, &app_id).await;
+                        let message = match payload.code {
+                            Some(code) => {
+                                i18n::tf("notification.crashed.exit_code", &[("code", &code)])
                             }
-                        }
-                        let _ = handle.emit(
-                            "app-log",
-                            serde_json::json!({
-                                "id": app_id,
-                                "type": "stderr",
-                                "message": line.trim()
-                            }),
+                            None => i18n::t("notification.crashed.signal"),
+                        };
+                        send_notification(
+                            &handle,
+                            Notification::new(
+                                NotificationKind::Crash,
+                                i18n::tf("notification.crashed.title", &[("name", &name)]),
+                                message,
+                            ),
                         );
                     }
-                }
-                CommandEvent::Terminated(payload) => {
+                    set_building(&handle, &app_id, false).await;
                     let _ = handle.emit(
                         "app-stopped",
                         serde_json::json!({
@@ -483,194 +2048,3000 @@ async fn start_app(
                     );
                     break;
                 }
-                _ => {}
+                _ => continue,
+            };
+            for line in lines {
+                push_app_log(&handle, &logs, &app_id, stream, line.trim()).await;
+                if !ready {
+                    ready = detect_ready(&handle, &detector, &app_id, child_pid, line.trim()).await;
+                }
             }
         }
     });
 
-    log::info!(target: "success", "App started: id={} port={}", id, actual_port);
+    match &profile {
+        Some(profile) => log::info!(
+            target: "success",
+            "App started: id={} port={} profile={}",
+            id,
+            actual_port,
+            profile
+        ),
+        None => log::info!(target: "success", "App started: id={} port={}", id, actual_port),
+    }
+    let detail = match &profile {
+        Some(profile) => format!("port {}, profile {}", actual_port, profile),
+        None => format!("port {}", actual_port),
+    };
+    record_app_event(&app_handle, &id, AppEventKind::Started, Some(detail));
 
     let _ = app_handle.emit(
         "app-started",
         serde_json::json!({
             "id": id,
-            "port": actual_port
+            "port": actual_port,
+            "profile": profile
         }),
     );
 
     Ok(actual_port)
 }
 
+const READY_PROBE_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+// Whether the app has been marked ready (now or before), i.e. its output needs no more checks
+async fn detect_ready(
+    app_handle: &AppHandle,
+    detector: &ReadyDetector,
+    id: &str,
+    pid: u32,
+    line: &str,
+) -> bool {
+    if let Some(source) = detector.matches(line) {
+        mark_ready(app_handle, id, pid, &source).await;
+        return true;
+    }
+    if !is_starting(app_handle, id, pid).await {
+        return true;
+    }
+    if let Some(tool) = readiness::build_started(line) {
+        if set_building(app_handle, id, true).await {
+            log::info!("App building: id={} ({})", id, tool);
+        }
+    }
+    false
+}
+
+// Notes whether `id` is building and passes it on to the window and the tray. Returns
+// whether that changed.
+async fn set_building(app_handle: &AppHandle, id: &str, building: bool) -> bool {
+    let changed = {
+        let state = app_handle.state::<AppState>();
+        let mut apps = state.building.lock().await;
+        if apps.contains_key(id) == building {
+            false
+        } else if building {
+            apps.insert(id.to_string(), std::time::Instant::now());
+            true
+        } else {
+            apps.remove(id).is_some()
+        }
+    };
+    if changed {
+        let _ = app_handle.emit(
+            "app-building",
+            serde_json::json!({
+                "id": id,
+                "building": building
+            }),
+        );
+        if let Err(e) = reload_apps(app_handle).await {
+            log::warn!("Failed to update the tray: {}", e);
+        }
+    }
+    changed
+}
+
+const STARTUP_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+// Reports an app that is slow to become ready, once per phase: a build gets
+// `BUILDING_TIMEOUT` from when it began, anything else `STARTING_TIMEOUT` from the launch.
+// Nothing is stopped, a slow start is only worth a look at the logs.
+async fn watch_startup(app_handle: AppHandle, id: String, pid: u32) {
+    let launched = std::time::Instant::now();
+    let mut reported = None;
+    loop {
+        tokio::time::sleep(STARTUP_CHECK_INTERVAL).await;
+        if !is_starting(&app_handle, &id, pid).await {
+            return;
+        }
+        let build_began = {
+            let state = app_handle.state::<AppState>();
+            let building = state.building.lock().await;
+            building.get(&id).copied()
+        };
+        let (phase, elapsed, timeout) = match build_began {
+            Some(began) => ("building", began.elapsed(), readiness::BUILDING_TIMEOUT),
+            None => ("starting", launched.elapsed(), readiness::STARTING_TIMEOUT),
+        };
+        if elapsed < timeout || reported == Some(phase) {
+            continue;
+        }
+        reported = Some(phase);
+        log::warn!("App {} still {} after {}s", id, phase, elapsed.as_secs());
+        let _ = app_handle.emit(
+            "app-start-slow",
+            serde_json::json!({
+                "id": id,
+                "phase": phase,
+                "secs": elapsed.as_secs()
+            }),
+        );
+    }
+}
+
+async fn is_starting(app_handle: &AppHandle, id: &str, pid: u32) -> bool {
+    let state = app_handle.state::<AppState>();
+    let processes = state.processes.lock().await;
+    processes
+        .get(id)
+        .is_some_and(|p| p.child.pid() == pid && !p.ready)
+}
+
+async fn mark_ready(app_handle: &AppHandle, id: &str, pid: u32, source: &str) {
+    {
+        let state = app_handle.state::<AppState>();
+        let mut processes = state.processes.lock().await;
+        match processes.get_mut(id) {
+            Some(process) if process.child.pid() == pid && !process.ready => process.ready = true,
+            _ => return,
+        }
+    }
+    log::info!("App ready: id={} ({})", id, source);
+    set_building(app_handle, id, false).await;
+    notify_ready(app_handle, id).await;
+    let _ = app_handle.emit(
+        "app-ready",
+        serde_json::json!({
+            "id": id,
+            "source": source
+        }),
+    );
+}
+
+// Announces a ready app in Notification Center and to the notification channels, if it
+// asked for that. Clicking the notification opens the app in the browser.
+async fn notify_ready(app_handle: &AppHandle, id: &str) {
+    match app_handle.state::<Db>().notify_ready_apps().await {
+        Ok(apps) if apps.contains(id) => {}
+        Ok(_) => return,
+        Err(e) => {
+            log::warn!("{}", e);
+            return;
+        }
+    }
+    let url = match resolve_app_url(app_handle, id).await {
+        Ok(url) => url,
+        Err(e) => {
+            log::warn!("No URL to announce {} at: {}", id, e);
+            return;
+        }
+    };
+    let name = app_name(app_handle, id).await;
+    let notification = Notification::new(
+        NotificationKind::Ready,
+        i18n::tf("notification.ready.title", &[("name", &name)]),
+        i18n::tf("notification.ready.message", &[("url", &url)]),
+    )
+    .with_url(url.clone());
+    send_notification(app_handle, notification.clone());
+
+    let app_handle = app_handle.clone();
+    // Waiting for the click blocks for up to a few minutes, so it gets a thread
+    tauri::async_runtime::spawn_blocking(move || {
+        let bundle_id = app_handle.config().identifier.clone();
+        if notifications::show_native(&bundle_id, &notification) {
+            let preference = app_handle
+                .state::<SettingsStore>()
+                .get(&settings::DEFAULT_BROWSER);
+            if let Err(e) = browser::open_url(&url, preference) {
+                log::warn!("Failed to open {}: {}", url, e);
+            }
+        }
+    });
+}
+
+// Pings the health endpoint of every ready app and keeps the results for `get_uptime`
+async fn run_health_checks(app_handle: AppHandle) {
+    let client = match health::client() {
+        Ok(client) => client,
+        Err(e) => {
+            log::error!("{}", e);
+            return;
+        }
+    };
+    let mut pruned_at: Option<std::time::Instant> = None;
+    // Result of each app's last check, by the pid it was made against
+    let mut last_up: HashMap<String, (u32, bool)> = HashMap::new();
+    loop {
+        tokio::time::sleep(health::CHECK_INTERVAL).await;
+        let targets: Vec<(String, u32, u16)> = {
+            let state = app_handle.state::<AppState>();
+            // Databases don't speak HTTP; `check_service_health` covers them
+            let services: Vec<String> = {
+                let apps = state.apps.lock().await;
+                apps.values()
+                    .filter(|app| app.service.is_some())
+                    .map(|app| app.id.clone())
+                    .collect()
+            };
+            let processes = state.processes.lock().await;
+            processes
+                .iter()
+                .filter(|(id, p)| p.ready && !services.contains(id))
+                .filter_map(|(id, p)| {
+                    Some((id.clone(), p.child.pid(), u16::try_from(p.port).ok()?))
+                })
+                .filter(|(_, _, port)| *port > 0)
+                .collect()
+        };
+        if targets.is_empty() {
+            continue;
+        }
+
+        let db = app_handle.state::<Db>();
+        let paths = match db.health_paths().await {
+            Ok(paths) => paths,
+            Err(e) => {
+                log::warn!("Skipping health checks: {}", e);
+                continue;
+            }
+        };
+        for (id, pid, port) in targets {
+            let path = paths.get(&id).map_or(health::DEFAULT_PATH, String::as_str);
+            let check = health::ping(&client, port, path).await;
+            // An app stopped while it was being checked isn't an outage
+            if !check.up && !is_ready(&app_handle, &id, pid).await {
+                continue;
+            }
+            if let Err(e) = db.record_health_check(&id, &check).await {
+                log::warn!("{}", e);
+            }
+            let was_up = last_up
+                .get(&id)
+                .filter(|(checked_pid, _)| *checked_pid == pid)
+                .map(|(_, up)| *up);
+            if let Some(event) = timeline::health_transition(&id, was_up, &check) {
+                save_app_event(&app_handle, event);
+            }
+            last_up.insert(id, (pid, check.up));
+        }
+
+        if pruned_at.is_none_or(|at| at.elapsed() >= health::PRUNE_INTERVAL) {
+            let before = health::now_ms() - health::RETENTION_MS;
+            if let Err(e) = db.prune_health_checks(before).await {
+                log::warn!("{}", e);
+            }
+            let before = health::now_ms() - timeline::RETENTION_MS;
+            if let Err(e) = db.prune_app_events(before).await {
+                log::warn!("{}", e);
+            }
+            pruned_at = Some(std::time::Instant::now());
+        }
+    }
+}
+
+async fn is_ready(app_handle: &AppHandle, id: &str, pid: u32) -> bool {
+    let state = app_handle.state::<AppState>();
+    let processes = state.processes.lock().await;
+    processes
+        .get(id)
+        .is_some_and(|p| p.child.pid() == pid && p.ready)
+}
+
+// Feeds a detached app's log file into its output, the way the pipe reader does for
+// other apps. stdout and stderr share the file, so it all shows as stdout.
+async fn follow_log(app_handle: AppHandle, id: String, pid: u32, path: PathBuf, mut offset: u64) {
+    let state = app_handle.state::<AppState>();
+    let ready_patterns = app_handle.state::<PreferencesState>().get().ready_patterns;
+    let detector = ReadyDetector::new(&ready_patterns);
+    let mut ready = false;
+    loop {
+        tokio::time::sleep(keep_running::LOG_POLL_INTERVAL).await;
+        let running = {
+            let processes = state.processes.lock().await;
+            processes.get(&id).is_some_and(|p| p.child.pid() == pid)
+        };
+        // Read once more after it stops, for its last words
+        for line in keep_running::read_new_lines(&path, &mut offset) {
+            push_app_log(&app_handle, &state.logs, &id, "stdout", line.trim()).await;
+            if !ready {
+                ready = detect_ready(&app_handle, &detector, &id, pid, line.trim()).await;
+            }
+        }
+        if !running {
+            set_building(&app_handle, &id, false).await;
+            return;
+        }
+    }
+}
+
+async fn quit_behavior(app_handle: &AppHandle, id: &str) -> QuitBehavior {
+    let overrides = match app_handle.state::<Db>().quit_behaviors().await {
+        Ok(overrides) => overrides,
+        Err(e) => {
+            log::warn!("{}", e);
+            HashMap::new()
+        }
+    };
+    overrides
+        .get(id)
+        .copied()
+        .unwrap_or_else(|| app_handle.state::<PreferencesState>().get().apps_on_quit)
+}
+
+// How long `<runtime> --version` in the app's shell gets
+const RUNTIME_CHECK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+// Caches the app's package.json name, version and engines on it, with a warning for
+// engines the runtime found in the app's shell doesn't meet. The window reloads its
+// apps on `app-package-updated`.
+async fn refresh_package_meta(
+    app_handle: AppHandle,
+    id: String,
+    dir: String,
+    shell: Option<String>,
+    extra_path: Option<String>,
+) {
+    let meta = package_meta::read(std::path::Path::new(&dir));
+    let mut warnings = Vec::new();
+    let engines = meta.as_ref().map(|meta| &meta.engines);
+    let (program, args) = app_shell(shell.as_deref());
+    for runtime in package_meta::RUNTIMES {
+        let Some(range) = engines.and_then(|engines| engines.get(runtime)) else {
+            continue;
+        };
+        let mut script = String::new();
+        if extra_path.is_some() {
+            script.push_str(path_entries::EXPORT);
+        }
+        script.push_str(&format!("{} --version", runtime));
+        let mut cmd = tokio::process::Command::new(&program);
+        cmd.args(&args)
+            .arg(script)
+            .current_dir(&dir)
+            .stdin(std::process::Stdio::null())
+            .kill_on_drop(true);
+        if let Some(extra_path) = &extra_path {
+            cmd.env("MY_APP_PATH", extra_path);
+        }
+        let Ok(Ok(output)) = tokio::time::timeout(RUNTIME_CHECK_TIMEOUT, cmd.output()).await else {
+            continue;
+        };
+        // Not installed is for the doctor to report, not a version conflict
+        if !output.status.success() {
+            continue;
+        }
+        let found = String::from_utf8_lossy(&output.stdout);
+        warnings.extend(package_meta::conflict(runtime, range, &found));
+    }
+    let warning = (!warnings.is_empty()).then(|| warnings.join("; "));
+    if let Some(warning) = &warning {
+        log::warn!("App {}: {}", id, warning);
+    }
+    let db = app_handle.state::<Db>();
+    match db
+        .set_package_meta(&id, meta.as_ref(), warning.as_deref())
+        .await
+    {
+        Ok(()) => {
+            let _ = app_handle.emit("app-package-updated", &id);
+        }
+        Err(e) => log::warn!("{}", e),
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct BuildStale {
+    id: String,
+    #[serde(flatten)]
+    stale: freshness::StaleBuild,
+}
+
+// A build step to run first when `command` serves a build that is missing or older than
+// the sources, e.g. `next start` after editing pages. Without automatic rebuilds the
+// app starts anyway, with an `app-build-stale` warning.
+async fn stale_build_step(
+    app_handle: &AppHandle,
+    id: &str,
+    path: &str,
+    command: &str,
+) -> Option<String> {
+    let dir = std::path::PathBuf::from(path);
+    let (check_dir, command) = (dir.clone(), command.to_string());
+    let stale =
+        tauri::async_runtime::spawn_blocking(move || freshness::check(&check_dir, &command))
+            .await
+            .ok()
+            .flatten()?;
+    let rebuild = match app_handle.state::<Db>().rebuild_stale_apps().await {
+        Ok(apps) => apps.contains(id),
+        Err(e) => {
+            log::warn!("{}", e);
+            false
+        }
+    };
+    if let Some(build) = freshness::build_command(&dir).filter(|_| rebuild) {
+        log::info!("Build of {} is stale, running {} first", id, build);
+        return Some(build);
+    }
+    log::warn!("App {} serves a stale build from {}", id, stale.artifact);
+    let _ = app_handle.emit(
+        "app-build-stale",
+        BuildStale {
+            id: id.to_string(),
+            stale,
+        },
+    );
+    None
+}
+
+fn process_start_time(pid: u32) -> Option<u64> {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[pid]), true);
+    system.process(pid).map(|process| process.start_time())
+}
+
+// Tracks apps the previous session left running again, as if they had just been started
+async fn adopt_kept_apps(
+    app_handle: &AppHandle,
+    kept: HashMap<String, KeptApp>,
+    app_ids: Option<&HashSet<String>>,
+) {
+    let state = app_handle.state::<AppState>();
+    for (id, app) in kept {
+        // The pid may have been reused since
+        if process_start_time(app.pid) != Some(app.started_at) {
+            log::info!("App {} left running has exited since", id);
+            continue;
+        }
+        if app_ids.is_some_and(|ids| !ids.contains(&id)) {
+            kill_process_tree(app.pid);
+            let _ = AppProcess::Adopted(app.pid).kill();
+            continue;
+        }
+
+        let offset = std::fs::metadata(&app.log).map(|m| m.len()).unwrap_or(0);
+        save_pid(&id, app.pid);
+        state.logs.lock().await.insert(id.clone(), Vec::new());
+        state.processes.lock().await.insert(
+            id.clone(),
+            RunningProcess {
+                child: AppProcess::Adopted(app.pid),
+                port: app.port,
+                requested_port: app.requested_port,
+                subdomain: app.subdomain,
+                profile: app.profile.clone(),
+                command: app.command,
+                env: app.env,
+                container: app.container,
+                ready: true,
+                log: Some(app.log.clone()),
+            },
+        );
+        tauri::async_runtime::spawn(follow_log(
+            app_handle.clone(),
+            id.clone(),
+            app.pid,
+            app.log,
+            offset,
+        ));
+
+        log::info!(target: "success", "App adopted: id={} port={}", id, app.port);
+        let _ = app_handle.emit(
+            "app-started",
+            serde_json::json!({
+                "id": id,
+                "port": app.port,
+                "profile": app.profile
+            }),
+        );
+    }
+}
+
+// Fallback for apps whose output matches no readiness pattern
+async fn wait_for_port(app_handle: AppHandle, id: String, pid: u32, port: i32) {
+    let Ok(port) = u16::try_from(port) else {
+        return;
+    };
+    loop {
+        tokio::time::sleep(READY_PROBE_INTERVAL).await;
+        if !is_starting(&app_handle, &id, pid).await {
+            return;
+        }
+        let v4 = tokio::net::TcpStream::connect(("127.0.0.1", port)).await;
+        let listening = v4.is_ok() || tokio::net::TcpStream::connect(("::1", port)).await.is_ok();
+        if listening {
+            mark_ready(&app_handle, &id, pid, "port").await;
+            return;
+        }
+    }
+}
+
+// Appends a line to the app's log buffer and forwards it to the frontend
+async fn push_app_log(
+    app_handle: &AppHandle,
+    logs: &Mutex<HashMap<String, Vec<String>>>,
+    id: &str,
+    stream: &str,
+    line: &str,
+) {
+    {
+        let mut logs_guard = logs.lock().await;
+        if let Some(app_logs) = logs_guard.get_mut(id) {
+            app_logs.push(format!("[{}] {}", stream, line));
+            // Keep only last 500 lines
+            if app_logs.len() > 500 {
+                app_logs.remove(0);
+            }
+        }
+    }
+    app_handle
+        .state::<RemoteControl>()
+        .forward_log(id, stream, line);
+    let forwarder = app_handle.state::<LogForwarder>();
+    let unified_log = app_handle.state::<UnifiedLog>();
+    if forwarder.is_active() || unified_log.is_enabled() {
+        let app = app_name(app_handle, id).await;
+        unified_log.write(&app, stream, line);
+        forwarder.push(LogRecord::new(id, &app, stream, line));
+    }
+    // The rest goes out with the next `flush_log_events` tick
+    if let Some(batch) = app_handle.state::<LogEvents>().push(id, stream, line) {
+        let _ = app_handle.emit("app-logs", batch);
+    }
+}
+
+// Sends queued output to the window as `app-logs`, see `log_events`
+async fn flush_log_events(app_handle: AppHandle) {
+    match app_handle.state::<Db>().log_sampled_apps().await {
+        Ok(apps) => app_handle.state::<LogEvents>().set_sampled(apps),
+        Err(e) => log::warn!("{}", e),
+    }
+    loop {
+        tokio::time::sleep(log_events::FLUSH_INTERVAL).await;
+        let batch = app_handle.state::<LogEvents>().take();
+        if !batch.is_empty() {
+            let _ = app_handle.emit("app-logs", batch);
+        }
+    }
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(app = %id), err(Display))]
+async fn stop_app(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    proxy_state: State<'_, ProxyState>,
+    mdns_registry: State<'_, MdnsRegistry>,
+    id: String,
+) -> Result<(), AppError> {
+    let mut processes = state.processes.lock().await;
+
+    if let Some(process) = processes.remove(&id) {
+        stop_process(&app_handle, &id, process)?;
+        return Ok(());
+    }
+
+    // A static app runs for as long as its route exists
+    let static_subdomain = {
+        let routes = proxy_state.routes.lock().await;
+        routes
+            .get(&id)
+            .filter(|r| r.static_site.is_some())
+            .map(|r| r.subdomain.clone())
+    };
+    if let Some(subdomain) = static_subdomain {
+        proxy::remove_route(&proxy_state, &id).await?;
+        if let Err(e) = mdns_registry.unregister(&subdomain) {
+            log::error!("Failed to unregister mDNS for {}: {}", subdomain, e);
+        }
+        log::info!(target: "success", "Static app stopped: id={}", id);
+        record_app_event(&app_handle, &id, AppEventKind::Stopped, None);
+        let _ = app_handle.emit(
+            "app-stopped",
+            serde_json::json!({
+                "id": id,
+                "code": null
+            }),
+        );
+    }
+
+    Ok(())
+}
+
+/// "Starts" a static folder app by routing its subdomain to the folder; no process is spawned.
+/// Returns the subdomain it is served on, with any template variables filled in.
+#[tauri::command]
+async fn start_static_app(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    proxy_state: State<'_, ProxyState>,
+    id: String,
+    path: String,
+    subdomain: Option<String>,
+    spa: bool,
+) -> Result<String, AppError> {
+    let subdomain = subdomain
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| "Static apps need a subdomain to be served on".to_string())?;
+    let subdomain = resolve_subdomain(&app_handle, &id, &subdomain, None).await;
+    if !std::path::Path::new(&path).is_dir() {
+        return Err(AppError::not_found(format!("{} is not a directory", path)));
+    }
+    if state.processes.lock().await.contains_key(&id) {
+        return Err(i18n::t("error.app_already_running").into());
+    }
+
+    let site = proxy::StaticSite { root: path, spa };
+    proxy::add_static_route(&proxy_state, &id, &subdomain, site).await?;
+
+    if let Some(lan_ip) = dns::get_lan_ip() {
+        let mdns_registry = app_handle.state::<MdnsRegistry>();
+        if let Err(e) = mdns_registry.register(&subdomain, &lan_ip) {
+            log::error!("Failed to register mDNS for {}: {}", subdomain, e);
+        }
+    }
+
+    log::info!(target: "success", "Static app started: id={} subdomain={}", id, subdomain);
+    record_app_event(
+        &app_handle,
+        &id,
+        AppEventKind::Started,
+        Some("static".to_string()),
+    );
+    let _ = app_handle.emit(
+        "app-started",
+        serde_json::json!({
+            "id": id,
+            "port": 0,
+            "profile": null
+        }),
+    );
+    Ok(subdomain)
+}
+
+// How many apps start_apps / stop_apps work on at once; builds and dev servers are heavy
+const BULK_CONCURRENCY: usize = 4;
+
+/// Outcome for one app of start_apps / stop_apps; `port` is set for started process apps.
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkResult {
+    pub id: String,
+    pub port: Option<i32>,
+    pub error: Option<AppError>,
+}
+
+// Starts a saved app the way the frontend would: static apps get their route, process apps
+// are launched and then routed on their subdomain
+async fn start_saved_app(app_handle: AppHandle, id: String) -> Result<Option<i32>, AppError> {
+    start_saved_app_with_profile(app_handle, id, None).await
+}
+
+async fn start_saved_app_with_profile(
+    app_handle: AppHandle,
+    id: String,
+    profile: Option<String>,
+) -> Result<Option<i32>, AppError> {
+    let app = app_handle.state::<AppState>().find_app(&id).await?;
+    let subdomain = app.subdomain.clone().filter(|s| !s.is_empty());
+    if app.kind == "static" {
+        start_static_app(
+            app_handle.clone(),
+            app_handle.state(),
+            app_handle.state(),
+            id,
+            app.path,
+            subdomain,
+            app.spa,
+        )
+        .await?;
+        return Ok(None);
+    }
+
+    let saved_profile = match &profile {
+        Some(name) => app_handle.state::<Db>().profile(&id, name).await?,
+        None => None,
+    };
+    // A profile deleted since falls back to the app's own command
+    let spec = launch_spec(&app_handle, app, saved_profile)?;
+    let port = launch_app(app_handle.clone(), &app_handle.state::<AppState>(), spec).await?;
+    if let Some(subdomain) = subdomain {
+        add_proxy_route(
+            app_handle.clone(),
+            app_handle.state(),
+            app_handle.state(),
+            id,
+            subdomain,
+            port,
+            None,
+        )
+        .await?;
+    }
+    Ok(Some(port))
+}
+
+async fn stop_saved_app(app_handle: AppHandle, id: String) -> Result<Option<i32>, AppError> {
+    stop_app(
+        app_handle.clone(),
+        app_handle.state(),
+        app_handle.state(),
+        app_handle.state(),
+        id.clone(),
+    )
+    .await?;
+    remove_proxy_route(
+        app_handle.clone(),
+        app_handle.state(),
+        app_handle.state(),
+        app_handle.state(),
+        app_handle.state(),
+        id,
+    )
+    .await?;
+    Ok(None)
+}
+
+// Runs `action` for every id, at most BULK_CONCURRENCY at a time, and refreshes the tray
+// once at the end. Results keep the order of `ids`.
+async fn run_bulk<F, Fut>(app_handle: &AppHandle, ids: Vec<String>, action: F) -> Vec<BulkResult>
+where
+    F: Fn(AppHandle, String) -> Fut,
+    Fut: std::future::Future<Output = Result<Option<i32>, AppError>> + Send + 'static,
+{
+    let permits = Arc::new(tokio::sync::Semaphore::new(BULK_CONCURRENCY));
+    let tasks: Vec<_> = ids
+        .into_iter()
+        .map(|id| {
+            let permits = permits.clone();
+            let run = action(app_handle.clone(), id.clone());
+            let task = tauri::async_runtime::spawn(async move {
+                let _permit = permits.acquire_owned().await;
+                run.await
+            });
+            (id, task)
+        })
+        .collect();
+
+    let mut results = Vec::new();
+    for (id, task) in tasks {
+        let outcome = task
+            .await
+            .unwrap_or_else(|e| Err(format!("Task failed: {}", e).into()));
+        if let Err(e) = &outcome {
+            log::error!("App {}: {}", id, e);
+        }
+        results.push(BulkResult {
+            id,
+            port: outcome.as_ref().ok().copied().flatten(),
+            error: outcome.err(),
+        });
+    }
+
+    if let Err(e) = reload_apps(app_handle).await {
+        log::warn!("{}", e);
+    }
+    results
+}
+
+#[tauri::command]
+async fn start_apps(app_handle: AppHandle, ids: Vec<String>) -> Result<Vec<BulkResult>, AppError> {
+    // The cache may predate apps the frontend just saved
+    load_apps(&app_handle).await?;
+    Ok(run_bulk(&app_handle, ids, start_saved_app).await)
+}
+
+#[tauri::command]
+async fn stop_apps(
+    app_handle: AppHandle,
+    ids: Vec<String>,
+    passcode: Option<String>,
+) -> Result<Vec<BulkResult>, AppError> {
+    check_app_lock(&app_handle, "stop all apps", passcode.as_deref()).await?;
+    Ok(run_bulk(&app_handle, ids, stop_saved_app).await)
+}
+
+#[tauri::command]
+async fn list_sessions(db: State<'_, Db>) -> Result<Vec<Session>, AppError> {
+    Ok(db.sessions().await?)
+}
+
+/// Saves the running apps, with their profiles, as the session `name`. Saving under an
+/// existing name replaces it.
+#[tauri::command]
+async fn save_session(
+    state: State<'_, AppState>,
+    proxy_state: State<'_, ProxyState>,
+    db: State<'_, Db>,
+    name: String,
+) -> Result<Session, AppError> {
+    let name = sessions::check_name(&name)?;
+    let mut apps: Vec<SessionApp> = running_profiles(&state, &proxy_state)
+        .await
+        .into_iter()
+        .map(|(app_id, profile)| SessionApp { app_id, profile })
+        .collect();
+    if apps.is_empty() {
+        return Err("No apps are running to save".into());
+    }
+    apps.sort_by(|a, b| a.app_id.cmp(&b.app_id));
+    db.save_session(&name, &apps).await?;
+    log::info!("Session saved: {} ({} apps)", name, apps.len());
+    Ok(Session { name, apps })
+}
+
+/// Runs exactly the apps of the session `name`: others are stopped, missing ones
+/// started, and apps on another profile restarted on the saved one.
+#[tauri::command]
+async fn restore_session(
+    app_handle: AppHandle,
+    name: String,
+    passcode: Option<String>,
+) -> Result<Vec<BulkResult>, AppError> {
+    check_app_lock(&app_handle, "switch sessions", passcode.as_deref()).await?;
+    switch_session(&app_handle, Some(&name)).await
+}
+
+// Runs the apps of the session `name`, or none at all
+async fn switch_session(
+    app_handle: &AppHandle,
+    name: Option<&str>,
+) -> Result<Vec<BulkResult>, AppError> {
+    let apps = match name {
+        Some(name) => {
+            app_handle
+                .state::<Db>()
+                .sessions()
+                .await?
+                .into_iter()
+                .find(|session| session.name == name)
+                .ok_or_else(|| AppError::not_found(format!("No session named {}", name)))?
+                .apps
+        }
+        None => Vec::new(),
+    };
+    load_apps(app_handle).await?;
+    let running = running_profiles(&app_handle.state(), &app_handle.state()).await;
+    let plan = sessions::plan(&apps, &running);
+    log::info!(
+        "Switching to session {}: stopping {}, starting {}",
+        name.unwrap_or("(none)"),
+        plan.stop.len(),
+        plan.start.len()
+    );
+
+    let mut results = run_bulk(app_handle, plan.stop, stop_saved_app).await;
+    let profiles: HashMap<String, Option<String>> = plan
+        .start
+        .iter()
+        .map(|app| (app.app_id.clone(), app.profile.clone()))
+        .collect();
+    let ids = plan.start.into_iter().map(|app| app.app_id).collect();
+    let started = run_bulk(app_handle, ids, |handle, id| {
+        let profile = profiles.get(&id).cloned().flatten();
+        start_saved_app_with_profile(handle, id, profile)
+    })
+    .await;
+    results.extend(started);
+    Ok(results)
+}
+
+#[tauri::command]
+async fn delete_session(db: State<'_, Db>, name: String) -> Result<(), AppError> {
+    Ok(db.delete_session(&name).await?)
+}
+
+#[tauri::command]
+async fn list_session_triggers(db: State<'_, Db>) -> Result<Vec<Trigger>, AppError> {
+    Ok(db.session_triggers().await?)
+}
+
+/// Saves `trigger`. It switches sessions, and so stops apps, later without asking, so
+/// the app lock is checked now.
+#[tauri::command]
+async fn add_session_trigger(
+    app_handle: AppHandle,
+    db: State<'_, Db>,
+    mut trigger: Trigger,
+    passcode: Option<String>,
+) -> Result<Trigger, AppError> {
+    sessions::check_trigger(&trigger)?;
+    if let Some(name) = &trigger.session {
+        let sessions = db.sessions().await?;
+        if !sessions.iter().any(|session| &session.name == name) {
+            return Err(AppError::not_found(format!("No session named {}", name)));
+        }
+    }
+    check_app_lock(
+        &app_handle,
+        "schedule a session switch",
+        passcode.as_deref(),
+    )
+    .await?;
+    trigger.id = db.add_session_trigger(&trigger).await?;
+    Ok(trigger)
+}
+
+#[tauri::command]
+async fn delete_session_trigger(db: State<'_, Db>, id: i64) -> Result<(), AppError> {
+    Ok(db.delete_session_trigger(id).await?)
+}
+
+// Schedules go by the minute, so this catches each one
+const SESSION_TRIGGER_INTERVAL: std::time::Duration = std::time::Duration::from_secs(20);
+
+#[derive(Clone, Serialize)]
+struct SessionSwitched {
+    session: Option<String>,
+    results: Vec<BulkResult>,
+}
+
+// Switches sessions when a schedule comes due or a Focus mode turns on, see `sessions`
+async fn run_session_triggers(app_handle: AppHandle) {
+    let mut last_time = None;
+    let mut last_focus = None;
+    loop {
+        tokio::time::sleep(SESSION_TRIGGER_INTERVAL).await;
+        let triggers = match app_handle.state::<Db>().session_triggers().await {
+            Ok(triggers) => triggers,
+            Err(e) => {
+                log::warn!("{}", e);
+                continue;
+            }
+        };
+        let Ok(Some(now)) = tokio::task::spawn_blocking(sessions::local_now).await else {
+            continue;
+        };
+        let watches_focus = triggers
+            .iter()
+            .any(|trigger| matches!(trigger.when, sessions::When::Focus { .. }));
+        let focus = if watches_focus {
+            tokio::task::spawn_blocking(sessions::active_focus)
+                .await
+                .unwrap_or_default()
+        } else {
+            None
+        };
+        let turned_on = focus.as_deref().filter(|_| focus != last_focus);
+        let due = sessions::due(&triggers, now, last_time, turned_on).cloned();
+        last_time = Some(now);
+        last_focus = focus;
+
+        let Some(trigger) = due else { continue };
+        log::info!("Session trigger {} fired", trigger.id);
+        match switch_session(&app_handle, trigger.session.as_deref()).await {
+            Ok(results) => {
+                let _ = app_handle.emit(
+                    "session-switched",
+                    SessionSwitched {
+                        session: trigger.session,
+                        results,
+                    },
+                );
+            }
+            Err(e) => log::warn!("Session trigger {} failed: {}", trigger.id, e),
+        }
+    }
+}
+
+// Running apps with the profile they run, None for the app's own command
+async fn running_profiles(
+    state: &AppState,
+    proxy_state: &ProxyState,
+) -> HashMap<String, Option<String>> {
+    let running = running_apps(state, proxy_state).await;
+    let processes = state.processes.lock().await;
+    running
+        .into_keys()
+        .map(|id| {
+            let profile = processes.get(&id).and_then(|p| p.profile.clone());
+            (id, profile)
+        })
+        .collect()
+}
+
+// Running apps and their ports; static apps have no process and report port 0
+async fn running_apps(state: &AppState, proxy_state: &ProxyState) -> HashMap<String, i32> {
+    let mut running: HashMap<String, i32> = {
+        let processes = state.processes.lock().await;
+        processes.iter().map(|(k, v)| (k.clone(), v.port)).collect()
+    };
+    let routes = proxy_state.routes.lock().await;
+    for (app_id, route) in routes.iter() {
+        if route.static_site.is_some() {
+            running.insert(app_id.clone(), 0);
+        }
+    }
+    running
+}
+
+fn stop_process(app_handle: &AppHandle, id: &str, process: RunningProcess) -> Result<(), AppError> {
+    kill_process_tree(process.child.pid());
+
+    if let Err(e) = process.child.kill() {
+        let msg = format!("Failed to stop app: {}", e);
+        log::error!("{}", msg);
+        return Err(msg.into());
+    }
+    if let Some(container) = process.container {
+        std::thread::spawn(move || stop_container(&container));
+    }
+
+    remove_pid(id);
+    log::info!(target: "success", "App stopped: id={}", id);
+    record_app_event(app_handle, id, AppEventKind::Stopped, None);
+
+    let _ = app_handle.emit(
+        "app-stopped",
+        serde_json::json!({
+            "id": id,
+            "code": null
+        }),
+    );
+    Ok(())
+}
+
+// Killing the docker client leaves its container running, so it is stopped by name
+fn stop_container(container: &str) {
+    let shell = login_shell();
+    let result = std::process::Command::new(&shell)
+        .args(login_shell_args(&shell))
+        .arg(r#"docker stop -t 5 "$MY_APP_CONTAINER""#)
+        .env("MY_APP_CONTAINER", container)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status();
+    if let Err(e) = result {
+        log::warn!("Failed to stop container {}: {}", container, e);
+    }
+}
+
+/// Pulls the app's repo, reinstalls dependencies when a lockfile changed and restarts it
+/// if it was running. Returns the new port of a restarted app.
+#[tauri::command]
+async fn update_and_restart(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Option<i32>, AppError> {
+    let app = state.find_app(&id).await?;
+    let dir = std::path::Path::new(&app.path);
+
+    state.logs.lock().await.entry(id.clone()).or_default();
+
+    let lockfiles = deps::snapshot_lockfiles(dir);
+    let git_pull = "git pull --ff-only";
+    run_logged_step(&app_handle, &state, &id, &app.path, git_pull, None, &[]).await?;
+
+    // The install may need the app's own shell and toolchain, like its build does
+    let path_entries = app
+        .path_entries
+        .as_deref()
+        .map(|spec| path_entries::resolve(spec, dir))
+        .unwrap_or_default();
+    match deps::changed_install_command(&lockfiles, &deps::snapshot_lockfiles(dir)) {
+        Some(install) => {
+            run_logged_step(
+                &app_handle,
+                &state,
+                &id,
+                &app.path,
+                install,
+                app.shell.as_deref(),
+                &path_entries,
+            )
+            .await?
+        }
+        None => {
+            let line = "Lockfile unchanged, skipping install";
+            push_app_log(&app_handle, &state.logs, &id, "stdout", line).await;
+        }
+    }
+
+    let running = state.processes.lock().await.remove(&id);
+    let Some(process) = running else {
+        log::info!(target: "success", "App updated: id={}", id);
+        return Ok(None);
+    };
+
+    let spec = LaunchSpec {
+        id: id.clone(),
+        path: app.path.clone(),
+        command: process.command.clone(),
+        port: process.port,
+        subdomain: process.subdomain.clone(),
+        env: process.env.clone(),
+        profile: process.profile.clone(),
+        build_command: app.build_command.clone(),
+        prerequisites: app.prerequisites.clone(),
+        remote_host: app.remote_host.clone(),
+        devcontainer: app.devcontainer,
+        path_entries: app.path_entries.clone(),
+        shell: app.shell.clone(),
+    };
+    stop_process(&app_handle, &id, process)?;
+
+    let port = launch_app(app_handle, &state, spec).await?;
+    log::info!(target: "success", "App updated and restarted: id={}", id);
+    Ok(Some(port))
+}
+
+async fn app_name(app_handle: &AppHandle, id: &str) -> String {
+    let state = app_handle.state::<AppState>();
+    state
+        .find_app(id)
+        .await
+        .map(|app| app.name)
+        .unwrap_or_else(|_| id.to_string())
+}
+
+// Sends in the background so a slow push server never holds anything up
+fn send_notification(app_handle: &AppHandle, notification: Notification) {
+    let channels = app_handle
+        .state::<SettingsStore>()
+        .get(&settings::NOTIFICATIONS)
+        .channels;
+    if channels.is_empty() {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        notifications::notify(&channels, notification).await;
+    });
+}
+
+// Reports a build or update step that ran past the configured threshold
+async fn notify_long_task(
+    app_handle: &AppHandle,
+    id: &str,
+    command: &str,
+    elapsed: std::time::Duration,
+    success: bool,
+) {
+    let threshold = app_handle
+        .state::<SettingsStore>()
+        .get(&settings::NOTIFICATIONS)
+        .long_task_secs;
+    if elapsed.as_secs() < threshold {
+        return;
+    }
+    let name = app_name(app_handle, id).await;
+    let (kind, title) = if success {
+        (NotificationKind::TaskDone, "notification.task_finished")
+    } else {
+        (NotificationKind::TaskFailed, "notification.task_failed")
+    };
+    let minutes = elapsed.as_secs() / 60;
+    let seconds = elapsed.as_secs() % 60;
+    send_notification(
+        app_handle,
+        Notification::new(
+            kind,
+            i18n::tf(title, &[("name", &name), ("command", &command)]),
+            i18n::tf(
+                "notification.task.duration",
+                &[("minutes", &minutes), ("seconds", &seconds)],
+            ),
+        ),
+    );
+}
+
+// Lines of a failed step's stderr kept for its error
+const STEP_ERROR_TAIL_LINES: usize = 20;
+
+// Runs one update step through the app's shell in `dir`, streaming its output into the
+// app's log
+async fn run_logged_step(
+    app_handle: &AppHandle,
+    state: &AppState,
+    id: &str,
+    dir: &str,
+    command: &str,
+    shell: Option<&str>,
+    path_entries: &[String],
+) -> Result<(), AppError> {
+    use tokio::io::{AsyncRead, AsyncReadExt};
+
+    let header = format!("$ {}", command);
+    push_app_log(app_handle, &state.logs, id, "stdout", &header).await;
+    let started = std::time::Instant::now();
+
+    let (shell, shell_args) = app_shell(shell);
+    let mut step = tokio::process::Command::new(&shell);
+    step.args(shell_args);
+    match path_entries::env_value(path_entries) {
+        Some(extra_path) => step
+            .arg(format!("{}{}", path_entries::EXPORT, command))
+            .env("MY_APP_PATH", extra_path),
+        None => step.arg(command),
+    };
+    let mut child = step
+        .current_dir(dir)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| AppError::spawn_failed(format!("Failed to run {}: {}", command, e), ""))?;
+
+    // Returns the stream's last lines, which end up in the error when the step fails
+    async fn forward(
+        app_handle: &AppHandle,
+        state: &AppState,
+        id: &str,
+        stream: &str,
+        output: Option<impl AsyncRead + Unpin>,
+    ) -> Vec<String> {
+        let mut tail = Vec::new();
+        let Some(mut output) = output else {
+            return tail;
+        };
+        // Not `lines()`, which gives up on the first line that isn't UTF-8
+        let mut lines = output::Lines::default();
+        let mut chunk = vec![0; 8192];
+        loop {
+            let read = output.read(&mut chunk).await.unwrap_or(0);
+            let complete = match read {
+                0 => lines.finish().into_iter().collect(),
+                n => lines.push(&chunk[..n]),
+            };
+            for line in complete {
+                push_app_log(app_handle, &state.logs, id, stream, line.trim_end()).await;
+                if tail.len() == STEP_ERROR_TAIL_LINES {
+                    tail.remove(0);
+                }
+                tail.push(line.trim_end().to_string());
+            }
+            if read == 0 {
+                return tail;
+            }
+        }
+    }
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let (_, stderr_tail) = tokio::join!(
+        forward(app_handle, state, id, "stdout", stdout),
+        forward(app_handle, state, id, "stderr", stderr),
+    );
+
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| format!("Failed to run {}: {}", command, e))?;
+    notify_long_task(app_handle, id, command, started.elapsed(), status.success()).await;
+    if !status.success() {
+        let msg = format!("{} failed with {}", command, status);
+        push_app_log(app_handle, &state.logs, id, "stderr", &msg).await;
+        log::error!("Update failed for {}: {}", id, msg);
+        return Err(AppError::spawn_failed(msg, stderr_tail.join("\n")));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_app_status(
+    state: State<'_, AppState>,
+    proxy_state: State<'_, ProxyState>,
+    id: String,
+) -> Result<Option<i32>, AppError> {
+    Ok(running_apps(&state, &proxy_state).await.get(&id).copied())
+}
+
+#[tauri::command]
+async fn get_running_apps(
+    state: State<'_, AppState>,
+    proxy_state: State<'_, ProxyState>,
+) -> Result<HashMap<String, i32>, AppError> {
+    Ok(running_apps(&state, &proxy_state).await)
+}
+
+/// Running apps that accept requests, see `readiness`.
+#[tauri::command]
+async fn get_ready_apps(state: State<'_, AppState>) -> Result<Vec<String>, AppError> {
+    let processes = state.processes.lock().await;
+    Ok(processes
+        .iter()
+        .filter(|(_, p)| p.ready)
+        .map(|(id, _)| id.clone())
+        .collect())
+}
+
+/// Apps that are building, either in their build step or going by their output.
+#[tauri::command]
+async fn get_building_apps(state: State<'_, AppState>) -> Result<Vec<String>, AppError> {
+    Ok(state.building.lock().await.keys().cloned().collect())
+}
+
+/// Extra readiness patterns (regexes) tried before the built-in framework ones.
+#[tauri::command]
+fn get_ready_patterns(preferences: State<'_, PreferencesState>) -> Vec<String> {
+    preferences.get().ready_patterns
+}
+
+#[tauri::command]
+fn set_ready_patterns(
+    preferences: State<'_, PreferencesState>,
+    patterns: Vec<String>,
+) -> Result<(), AppError> {
+    let patterns: Vec<String> = patterns
+        .into_iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+    readiness::validate(&patterns)?;
+    preferences.update(|p| p.ready_patterns = patterns);
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_git_info(state: State<'_, AppState>, id: String) -> Result<Option<GitInfo>, AppError> {
+    let app = state.find_app(&id).await?;
+    git::get_info(&app.path).await.map_err(AppError::from)
+}
+
+/// What watching the app's project for changes would cover, with `.gitignore`,
+/// `.ignore` and `.git/info/exclude` applied.
+#[tauri::command]
+async fn preview_watched_paths(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<WatchPlan, AppError> {
+    let app = state.find_app(&id).await?;
+    let plan = tauri::async_runtime::spawn_blocking(move || {
+        watch_ignore::plan(std::path::Path::new(&app.path))
+    })
+    .await
+    .map_err(|e| format!("Failed to list watched paths: {}", e))??;
+    Ok(plan)
+}
+
+/// Size of the app's folder with a node_modules/build/cache breakdown. Served from cache
+/// unless `refresh` is set or it was never computed.
+#[tauri::command]
+async fn get_disk_usage(
+    state: State<'_, AppState>,
+    id: String,
+    refresh: Option<bool>,
+) -> Result<DiskUsage, AppError> {
+    if !refresh.unwrap_or(false) {
+        if let Some(usage) = state.disk_usage.lock().await.get(&id) {
+            return Ok(usage.clone());
+        }
+    }
+
+    let app = state.find_app(&id).await?;
+    let usage = tauri::async_runtime::spawn_blocking(move || {
+        disk_usage::compute(std::path::Path::new(&app.path))
+    })
+    .await
+    .map_err(|e| format!("Failed to compute disk usage: {}", e))??;
+
+    state.disk_usage.lock().await.insert(id, usage.clone());
+    Ok(usage)
+}
+
+/// Deletes artifact directories (node_modules, .next, dist, .turbo) of a stopped app.
+/// With `dry_run` it only reports what would be reclaimed.
+#[tauri::command]
+async fn clean_project(
+    state: State<'_, AppState>,
+    id: String,
+    targets: Vec<String>,
+    dry_run: bool,
+) -> Result<CleanReport, AppError> {
+    if state.processes.lock().await.contains_key(&id) {
+        return Err("Stop the app before cleaning it".into());
+    }
+
+    let path = state.find_app(&id).await?.path;
+    let report = tauri::async_runtime::spawn_blocking(move || {
+        disk_usage::clean(std::path::Path::new(&path), &targets, dry_run)
+    })
+    .await
+    .map_err(|e| format!("Failed to clean project: {}", e))??;
+
+    if !dry_run {
+        state.disk_usage.lock().await.remove(&id);
+        log::info!(
+            target: "success",
+            "Project cleaned: id={} reclaimed={} bytes",
+            id,
+            report.reclaimed
+        );
+    }
+    Ok(report)
+}
+
+#[tauri::command]
+async fn get_repo_branches(path: String) -> Result<Option<RepoBranches>, AppError> {
+    git::get_branches(&path).await.map_err(AppError::from)
+}
+
+/// A worktree checked out for a new app, for the frontend to register.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorktreeApp {
+    pub path: String,
+    pub branch: String,
+    pub subdomain: String,
+}
+
+#[tauri::command]
+async fn create_worktree_app(
+    state: State<'_, AppState>,
+    repo: String,
+    branch: String,
+) -> Result<WorktreeApp, AppError> {
+    let path = git::add_worktree(&repo, &branch).await?;
+
+    // Branch apps sit next to the repo's app, e.g. shop-feature-login beside shop
+    let base = {
+        let apps = state.apps.lock().await;
+        apps.values()
+            .find(|a| a.path == repo)
+            .and_then(|a| a.subdomain.clone())
+    }
+    .unwrap_or_else(|| {
+        std::path::Path::new(&repo)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    });
+
+    log::info!(target: "success", "Worktree created: branch={} path={}", branch, path);
+    Ok(WorktreeApp {
+        path,
+        subdomain: proxy::slugify(&format!("{}-{}", base, branch)),
+        branch,
+    })
+}
+
+#[tauri::command]
+async fn remove_app_worktree(state: State<'_, AppState>, id: String) -> Result<(), AppError> {
+    let app = state.find_app(&id).await?;
+    let repo = app
+        .worktree_of
+        .ok_or_else(|| format!("{} is not a worktree app", app.name))?;
+    git::remove_worktree(&repo, &app.path).await?;
+    log::info!(target: "success", "Worktree removed: path={}", app.path);
+    Ok(())
+}
+
+/// What `delete_app` cleans up besides the app, its route and its health history.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct DeleteOptions {
+    // Output kept in memory and requests captured by the inspector
+    pub logs: bool,
+    // Artifact directories from `disk_usage::CLEAN_TARGETS` to move to the Trash
+    pub trash: Vec<String>,
+    // Worktree apps only: `git worktree remove` the checkout
+    pub worktree: bool,
+}
+
+/// Stops the app, removes its proxy route and mDNS name, and deletes it from the database.
+/// Files go first, so a failure there leaves the app in place to retry.
+#[tauri::command]
+async fn delete_app(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    proxy_state: State<'_, ProxyState>,
+    inspector: State<'_, InspectorState>,
+    id: String,
+    options: DeleteOptions,
+    passcode: Option<String>,
+) -> Result<Option<CleanReport>, AppError> {
+    let app = state.find_app(&id).await?;
+    let reason = format!("remove {}", app.name);
+    check_app_lock(&app_handle, &reason, passcode.as_deref()).await?;
+
+    stop_app(
+        app_handle.clone(),
+        app_handle.state(),
+        app_handle.state(),
+        app_handle.state(),
+        id.clone(),
+    )
+    .await?;
+    // Routes restored on startup can outlive the process we knew about
+    if proxy_state.routes.lock().await.contains_key(&id) {
+        remove_proxy_route(
+            app_handle.clone(),
+            app_handle.state(),
+            app_handle.state(),
+            app_handle.state(),
+            app_handle.state(),
+            id.clone(),
+        )
+        .await?;
+    }
+
+    let trashed = if options.trash.is_empty() || options.worktree {
+        None
+    } else {
+        let path = app.path.clone();
+        let report = tauri::async_runtime::spawn_blocking(move || {
+            disk_usage::trash(std::path::Path::new(&path), &options.trash)
+        })
+        .await
+        .map_err(|e| format!("Failed to move artifacts to the Trash: {}", e))??;
+        Some(report)
+    };
+    if options.worktree {
+        let repo = app
+            .worktree_of
+            .as_deref()
+            .ok_or_else(|| format!("{} is not a worktree app", app.name))?;
+        git::remove_worktree(repo, &app.path).await?;
+    }
+
+    app_handle.state::<Db>().delete_app(&id).await?;
+    if options.logs {
+        state.logs.lock().await.remove(&id);
+        inspector.forget_requests(&id).await;
+    }
+    state.disk_usage.lock().await.remove(&id);
+    state.restart_needed.lock().await.remove(&id);
+    app_handle.state::<AccessTracker>().forget(&id);
+    if let Err(e) = reload_apps(&app_handle).await {
+        log::error!("Failed to reload apps: {}", e);
+    }
+
+    log::info!(
+        target: "success",
+        "App deleted: id={} reclaimed={} bytes",
+        id,
+        trashed.as_ref().map_or(0, |report| report.reclaimed)
+    );
+    Ok(trashed)
+}
+
+// Feeds the LAN status page; apps are listed by name with their LAN URL while routed
+async fn publish_status(app_handle: &AppHandle) {
+    let status_page = app_handle.state::<StatusPage>();
+    if status_page.running_port().await.is_none() {
+        return;
+    }
+    let state = app_handle.state::<AppState>();
+    let proxy_state = app_handle.state::<ProxyState>();
+    let running = running_apps(&state, &proxy_state).await;
+    let routes = proxy_state.routes.lock().await.clone();
+    let mut apps: Vec<App> = state.apps.lock().await.values().cloned().collect();
+    apps.sort_by_key(|a| a.name.to_lowercase());
+
+    // Phones need the CA before shared HTTPS URLs open without a warning
+    if !status_page.has_ca_certificate() && routes.values().any(|r| r.tls && !r.disabled) {
+        match proxy::local_ca_certificate(&proxy_state).await {
+            Ok(pem) => status_page.publish_ca_certificate(Some(pem)),
+            Err(e) => log::warn!("Failed to read the local CA certificate: {}", e),
+        }
+    }
+
+    let public_port = proxy_state.public_port();
+    status_page.publish(
+        apps.into_iter()
+            .map(|app| AppStatus {
+                running: running.contains_key(&app.id),
+                url: routes
+                    .get(&app.id)
+                    .filter(|_| running.contains_key(&app.id))
+                    .map(|route| proxy::route_url(route, public_port)),
+                name: app.name,
+            })
+            .collect(),
+    );
+}
+
+async fn status_page_url(status_page: &StatusPage) -> Option<String> {
+    let port = status_page.running_port().await?;
+    let host = dns::get_lan_ip().unwrap_or_else(|| "localhost".to_string());
+    Some(format!("http://{}:{}", host, port))
+}
+
+#[tauri::command]
+async fn get_status_page_url(
+    status_page: State<'_, StatusPage>,
+) -> Result<Option<String>, AppError> {
+    Ok(status_page_url(&status_page).await)
+}
+
+/// Starts or stops the read-only status page and remembers the choice; returns its URL.
+#[tauri::command]
+async fn set_status_page(
+    app_handle: AppHandle,
+    preferences: State<'_, PreferencesState>,
+    status_page: State<'_, StatusPage>,
+    enabled: bool,
+) -> Result<Option<String>, AppError> {
+    if enabled {
+        status_page
+            .start(status_page::STATUS_PORT)
+            .await
+            .map_err(|e| AppError::port_unavailable(status_page::STATUS_PORT.into(), e))?;
+        publish_status(&app_handle).await;
+    } else {
+        status_page.stop().await;
+    }
+    preferences.update(|p| p.status_page = enabled);
+    Ok(status_page_url(&status_page).await)
+}
+
+// Runs calls from paired machines, see `remote_control`
+#[derive(Clone)]
+struct RemoteApi(AppHandle);
+
+impl RemoteHandler for RemoteApi {
+    async fn call(&self, call: Call) -> Result<serde_json::Value, AppError> {
+        let app_handle = &self.0;
+        let state = app_handle.state::<AppState>();
+        let value = match call {
+            Call::ListApps => {
+                let running = running_apps(&state, &app_handle.state::<ProxyState>()).await;
+                let mut apps: Vec<RemoteApp> = state
+                    .apps
+                    .lock()
+                    .await
+                    .values()
+                    .map(|app| RemoteApp {
+                        id: app.id.clone(),
+                        name: app.name.clone(),
+                        port: running.get(&app.id).copied(),
+                    })
+                    .collect();
+                apps.sort_by_key(|a| a.name.to_lowercase());
+                serde_json::to_value(apps)
+            }
+            Call::StartApp { id, passcode } => {
+                load_apps(app_handle).await?;
+                let name = state.find_app(&id).await?.name;
+                let reason = format!("start {} from a paired device", name);
+                check_app_lock(app_handle, &reason, passcode.as_deref()).await?;
+                let port = start_saved_app(app_handle.clone(), id).await?;
+                if let Err(e) = reload_apps(app_handle).await {
+                    log::warn!("{}", e);
+                }
+                serde_json::to_value(port)
+            }
+            Call::StopApp { id, passcode } => {
+                let name = state.find_app(&id).await?.name;
+                let reason = format!("stop {} from a paired device", name);
+                check_app_lock(app_handle, &reason, passcode.as_deref()).await?;
+                stop_saved_app(app_handle.clone(), id).await?;
+                if let Err(e) = reload_apps(app_handle).await {
+                    log::warn!("{}", e);
+                }
+                Ok(serde_json::Value::Null)
+            }
+            Call::WatchLogs { id } => {
+                let logs = state.logs.lock().await.get(&id).cloned();
+                serde_json::to_value(logs.unwrap_or_default())
+            }
+            Call::UnwatchLogs { .. } => Ok(serde_json::Value::Null),
+        };
+        value.map_err(|e| format!("Failed to encode response: {}", e).into())
+    }
+
+    fn devices(&self) -> Vec<PairedDevice> {
+        self.0
+            .state::<PreferencesState>()
+            .get()
+            .remote_control
+            .devices
+    }
+
+    fn paired(&self, device: PairedDevice) {
+        self.0
+            .state::<PreferencesState>()
+            .update(|p| p.remote_control.devices.push(device));
+        let _ = self.0.emit("remote-control-changed", ());
+    }
+}
+
+// Open sessions to the hosts this machine paired with, by host id
+#[derive(Default)]
+struct RemoteSessions(Mutex<HashMap<String, Arc<RemoteSession>>>);
+
+// Reuses the open session to `host_id`, reconnecting if it was closed
+async fn remote_session(
+    app_handle: &AppHandle,
+    host_id: &str,
+) -> Result<Arc<RemoteSession>, AppError> {
+    let sessions = app_handle.state::<RemoteSessions>();
+    let mut sessions = sessions.0.lock().await;
+    if let Some(session) = sessions.get(host_id).filter(|s| !s.is_closed()) {
+        return Ok(session.clone());
+    }
+
+    let host = app_handle
+        .state::<PreferencesState>()
+        .get()
+        .remote_control
+        .hosts
+        .into_iter()
+        .find(|h| h.id == host_id)
+        .ok_or_else(|| AppError::not_found(format!("No paired host {}", host_id)))?;
+    let log_handle = app_handle.clone();
+    let log_host = host.id.clone();
+    let session = RemoteSession::connect(&host, move |line| {
+        let _ = log_handle.emit(
+            "remote-log",
+            serde_json::json!({
+                "host_id": log_host,
+                "id": line.app_id,
+                "type": line.stream,
+                "message": line.message
+            }),
+        );
+    })
+    .await?;
+    let session = Arc::new(session);
+    sessions.insert(host.id, session.clone());
+    Ok(session)
+}
+
+#[tauri::command]
+fn get_notification_settings(settings: State<'_, SettingsStore>) -> NotificationSettings {
+    settings.get(&settings::NOTIFICATIONS)
+}
+
+#[tauri::command]
+async fn set_notification_settings(
+    app_handle: AppHandle,
+    settings: serde_json::Value,
+) -> Result<NotificationSettings, AppError> {
+    let saved = save_setting(&app_handle, settings::NOTIFICATIONS.name, settings).await?;
+    Ok(settings::NOTIFICATIONS.check(saved)?)
+}
+
+/// Sends a test notification through `channel`, which doesn't need to be saved yet.
+#[tauri::command]
+async fn test_notification_channel(channel: NotificationChannel) -> Result<(), AppError> {
+    let notification = Notification::new(
+        NotificationKind::Test,
+        "My Little Apps",
+        i18n::tf("notification.test.message", &[("channel", &channel.name)]),
+    );
+    Ok(channel.send(&notification).await?)
+}
+
+#[tauri::command]
+fn get_log_forwarding(preferences: State<'_, PreferencesState>) -> LogForwardingSettings {
+    preferences.get().log_forwarding
+}
+
+/// Saves the sink and restarts forwarding with it.
+#[tauri::command]
+fn set_log_forwarding(
+    preferences: State<'_, PreferencesState>,
+    forwarder: State<'_, LogForwarder>,
+    unified_log: State<'_, UnifiedLog>,
+    settings: LogForwardingSettings,
+) -> Result<LogForwardingStatus, AppError> {
+    settings.validate()?;
+    forwarder.configure(&settings);
+    unified_log.set_enabled(settings.unified_log);
+    preferences.update(|p| p.log_forwarding = settings);
+    Ok(forwarder.status())
+}
+
+#[tauri::command]
+fn get_log_forwarding_status(forwarder: State<'_, LogForwarder>) -> LogForwardingStatus {
+    forwarder.status()
+}
+
+#[tauri::command]
+fn get_trace_export(preferences: State<'_, PreferencesState>) -> TraceExportSettings {
+    preferences.get().trace_export
+}
+
+/// Sends a span per request through the inspection relay to an OTLP collector.
+#[tauri::command]
+fn set_trace_export(
+    preferences: State<'_, PreferencesState>,
+    inspector: State<'_, InspectorState>,
+    settings: TraceExportSettings,
+) -> Result<TraceExportSettings, AppError> {
+    inspector.traces().configure(&settings)?;
+    preferences.update(|p| p.trace_export = settings.clone());
+    Ok(settings)
+}
+
+async fn remote_control_status(
+    preferences: &PreferencesState,
+    remote_control: &RemoteControl,
+) -> RemoteControlStatus {
+    let port = remote_control.running_port().await;
+    preferences.get().remote_control.status(port)
+}
+
+#[tauri::command]
+async fn get_remote_control(
+    preferences: State<'_, PreferencesState>,
+    remote_control: State<'_, RemoteControl>,
+) -> Result<RemoteControlStatus, AppError> {
+    Ok(remote_control_status(&preferences, &remote_control).await)
+}
+
+/// Lets paired machines start and stop apps here and read their logs.
+#[tauri::command]
+async fn set_remote_control(
+    app_handle: AppHandle,
+    preferences: State<'_, PreferencesState>,
+    remote_control: State<'_, RemoteControl>,
+    enabled: bool,
+) -> Result<RemoteControlStatus, AppError> {
+    if enabled {
+        remote_control
+            .start(remote_control::REMOTE_PORT, RemoteApi(app_handle.clone()))
+            .await
+            .map_err(|e| AppError::port_unavailable(remote_control::REMOTE_PORT.into(), e))?;
+    } else {
+        remote_control.stop().await;
+    }
+    preferences.update(|p| p.remote_control.enabled = enabled);
+    Ok(remote_control_status(&preferences, &remote_control).await)
+}
+
+/// A one-time code to enter on the machine being paired.
+#[tauri::command]
+async fn create_pairing_code(remote_control: State<'_, RemoteControl>) -> Result<String, AppError> {
+    if remote_control.running_port().await.is_none() {
+        let message = "Turn on remote control before pairing a device";
+        return Err(message.to_string().into());
+    }
+    remote_control.create_pairing_code()
+}
+
+#[tauri::command]
+fn revoke_remote_device(preferences: State<'_, PreferencesState>, id: String) {
+    preferences.update(|p| p.remote_control.devices.retain(|d| d.id != id));
+}
+
+/// Pairs with another machine running My Little Apps, using the code it shows.
+#[tauri::command]
+async fn pair_remote_host(
+    preferences: State<'_, PreferencesState>,
+    remote_control: State<'_, RemoteControl>,
+    address: String,
+    code: String,
+) -> Result<RemoteControlStatus, AppError> {
+    let host = remote_control::pair(&address, &code, &remote_control::host_name()).await?;
+    preferences.update(|p| {
+        p.remote_control.hosts.retain(|h| h.address != host.address);
+        p.remote_control.hosts.push(host);
+    });
+    Ok(remote_control_status(&preferences, &remote_control).await)
+}
+
+#[tauri::command]
+async fn remove_remote_host(
+    preferences: State<'_, PreferencesState>,
+    sessions: State<'_, RemoteSessions>,
+    id: String,
+) -> Result<(), AppError> {
+    sessions.0.lock().await.remove(&id);
+    preferences.update(|p| p.remote_control.hosts.retain(|h| h.id != id));
+    Ok(())
+}
+
+/// Runs `call` on a paired host; watched logs arrive as `remote-log` events.
+#[tauri::command]
+async fn remote_call(
+    app_handle: AppHandle,
+    host_id: String,
+    call: Call,
+) -> Result<serde_json::Value, AppError> {
+    let session = remote_session(&app_handle, &host_id).await?;
+    session.call(call).await
+}
+
+// Git state of every known app that is a repository, keyed by app id
+async fn collect_git_info(app_handle: &AppHandle) -> HashMap<String, GitInfo> {
+    let apps: Vec<App> = {
+        let state = app_handle.state::<AppState>();
+        let apps = state.apps.lock().await;
+        apps.values().cloned().collect()
+    };
+
+    let mut infos = HashMap::new();
+    for app in apps {
+        match git::get_info(&app.path).await {
+            Ok(Some(info)) => {
+                infos.insert(app.id, info);
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("Git status for {} failed: {}", app.name, e),
+        }
+    }
+    infos
+}
+
+async fn emit_app_usage(app_handle: &AppHandle) {
+    let pids: Vec<(String, u32)> = {
+        let state = app_handle.state::<AppState>();
+        let processes = state.processes.lock().await;
+        processes
+            .iter()
+            .map(|(id, p)| (id.clone(), p.child.pid()))
+            .collect()
+    };
+    if pids.is_empty() {
+        let _ = app_handle.emit("app-usage", HashMap::<String, AppUsage>::new());
+        return;
+    }
+    let mut system = System::new_all();
+    system.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+    let mut usage = HashMap::new();
+    for (app_id, pid) in pids {
+        if let Some(process) = system.process(Pid::from_u32(pid)) {
+            usage.insert(
+                app_id,
+                AppUsage {
+                    cpu: process.cpu_usage(),
+                    memory: process.memory(),
+                },
+            );
+        }
+    }
+    let _ = app_handle.emit("app-usage", usage);
+}
+
+#[tauri::command]
+async fn get_app_logs(state: State<'_, AppState>, id: String) -> Result<Vec<String>, AppError> {
+    let logs = state.logs.lock().await;
+    Ok(logs.get(&id).cloned().unwrap_or_default())
+}
+
+#[tauri::command]
+async fn open_in_browser(
+    proxy_state: State<'_, ProxyState>,
+    settings: State<'_, SettingsStore>,
+    port: i32,
+    subdomain: Option<String>,
+    browser: Option<Browser>,
+    private: Option<bool>,
+) -> Result<(), AppError> {
+    let url = if let Some(sub) = subdomain {
+        proxy::get_app_url(&sub, proxy_state.public_port())
+    } else {
+        format!("http://localhost:{}", port)
+    };
+    // Explicit choices override the saved preference for this one call
+    let saved = settings.get(&settings::DEFAULT_BROWSER);
+    let preference = BrowserPreference {
+        browser: browser.unwrap_or(saved.browser),
+        private: private.unwrap_or(saved.private),
+    };
+    browser::open_url(&url, preference).map_err(AppError::from)
+}
+
+// Proxy URL when the app has a live route, otherwise plain localhost
+async fn resolve_app_url(app_handle: &AppHandle, app_id: &str) -> Result<String, AppError> {
+    let proxy_state = app_handle.state::<ProxyState>();
+    let route = proxy_state.routes.lock().await.get(app_id).cloned();
+
+    // Static apps only exist behind the proxy
+    if let Some(route) = route.as_ref().filter(|r| r.static_site.is_some()) {
+        if !is_proxy_responsive(&proxy_state).await {
+            let message = i18n::t("error.proxy_not_running");
+            return Err(AppError::proxy_unavailable(message));
+        }
+        return Ok(proxy::get_app_url(
+            &route.subdomain,
+            proxy_state.public_port(),
+        ));
+    }
+
+    let port = {
+        let state = app_handle.state::<AppState>();
+        let processes = state.processes.lock().await;
+        processes
+            .get(app_id)
+            .map(|p| p.port)
+            .ok_or_else(|| AppError::not_found(i18n::t("error.app_not_running")))?
+    };
+
+    match route {
+        Some(route) if is_proxy_responsive(&proxy_state).await => Ok(proxy::get_app_url(
+            &route.subdomain,
+            proxy_state.public_port(),
+        )),
+        _ => Ok(format!("http://localhost:{}", port)),
+    }
+}
+
+async fn copy_url_to_clipboard(app_handle: &AppHandle, app_id: &str) -> Result<String, AppError> {
+    let url = resolve_app_url(app_handle, app_id).await?;
+    app_handle
+        .clipboard()
+        .write_text(url.clone())
+        .map_err(|e| format!("Failed to copy URL: {}", e))?;
+    Ok(url)
+}
+
+#[tauri::command]
+async fn copy_app_url(app_handle: AppHandle, app_id: String) -> Result<String, AppError> {
+    copy_url_to_clipboard(&app_handle, &app_id).await
+}
+
+async fn reveal_app(app_handle: &AppHandle, id: &str) -> Result<(), AppError> {
+    let app = app_handle.state::<AppState>().find_app(id).await?;
+    app_handle
+        .opener()
+        .reveal_item_in_dir(&app.path)
+        .map_err(|e| format!("Failed to reveal {} in Finder: {}", app.path, e).into())
+}
+
+#[tauri::command]
+async fn reveal_in_finder(app_handle: AppHandle, id: String) -> Result<(), AppError> {
+    reveal_app(&app_handle, &id).await
+}
+
+/// Opens the app's project in the preferred editor, at `location` when given, e.g. a
+/// file:line clicked in a stack trace. Relative files are in the project.
+#[tauri::command]
+async fn open_in_editor(
+    state: State<'_, AppState>,
+    preferences: State<'_, PreferencesState>,
+    id: String,
+    location: Option<Location>,
+) -> Result<(), AppError> {
+    let app = state.find_app(&id).await?;
+    let project = std::path::Path::new(&app.path);
+    let location = location.map(|location| Location {
+        file: project.join(&location.file),
+        ..location
+    });
+    if let Some(location) = &location {
+        if !location.file.is_file() {
+            return Err(format!("{} doesn't exist", location.file.display()).into());
+        }
+    }
+    let shell = login_shell();
+    let shell_args = login_shell_args(&shell);
+    let preference = preferences.get().editor;
+    editor::open(&preference, project, location.as_ref(), (shell, shell_args))
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+fn get_installed_editors() -> Vec<Editor> {
+    Editor::installed()
+}
+
+#[tauri::command]
+fn get_editor_preference(preferences: State<'_, PreferencesState>) -> EditorPreference {
+    preferences.get().editor
+}
+
+/// Saves the editor preference and returns it as saved, see `editor::check_preference`.
+#[tauri::command]
+fn set_editor_preference(
+    preferences: State<'_, PreferencesState>,
+    preference: EditorPreference,
+) -> Result<EditorPreference, AppError> {
+    let preference = editor::check_preference(preference)?;
+    preferences.update(|p| p.editor = preference.clone());
+    Ok(preference)
+}
+
+#[tauri::command]
+async fn open_terminal(
+    state: State<'_, AppState>,
+    preferences: State<'_, PreferencesState>,
+    id: String,
+    command: Option<String>,
+) -> Result<(), AppError> {
+    let app = state.find_app(&id).await?;
+    let terminal = preferences.get().terminal.unwrap_or_else(Terminal::detect);
+    terminal::open(terminal, &app.path, command.as_deref()).map_err(AppError::from)
+}
+
+#[tauri::command]
+fn get_installed_terminals() -> Vec<Terminal> {
+    Terminal::installed()
+}
+
+#[tauri::command]
+fn get_terminal_preference(preferences: State<'_, PreferencesState>) -> Option<Terminal> {
+    preferences.get().terminal
+}
+
+#[tauri::command]
+fn set_terminal_preference(preferences: State<'_, PreferencesState>, terminal: Option<Terminal>) {
+    preferences.update(|p| p.terminal = terminal);
+}
+
+#[tauri::command]
+fn get_app_lock(preferences: State<'_, PreferencesState>) -> AppLockStatus {
+    preferences.get().app_lock.status()
+}
+
+/// Turning the lock off or changing it needs the current lock's authentication first.
+#[tauri::command]
+async fn set_app_lock(
+    preferences: State<'_, PreferencesState>,
+    enabled: bool,
+    passcode: Option<String>,
+    current_passcode: Option<String>,
+) -> Result<(), AppError> {
+    preferences
+        .get()
+        .app_lock
+        .authenticate("change the app lock", current_passcode.as_deref())
+        .await?;
+    let lock = if enabled {
+        AppLock::new(passcode.as_deref())
+    } else {
+        AppLock::default()
+    };
+    preferences.update(|p| p.app_lock = lock);
+    Ok(())
+}
+
+// Destructive commands check the lock themselves, so skipping the frontend's prompt
+// doesn't get around it
+async fn check_app_lock(
+    app_handle: &AppHandle,
+    reason: &str,
+    passcode: Option<&str>,
+) -> Result<(), AppError> {
+    let lock = app_handle.state::<PreferencesState>().get().app_lock;
+    lock.authenticate(reason, passcode).await
+}
+
+/// Checks a passcode before a destructive action asks for it; succeeds right away when
+/// the lock is off.
+#[tauri::command]
+async fn unlock_app(
+    preferences: State<'_, PreferencesState>,
+    reason: String,
+    passcode: Option<String>,
+) -> Result<(), AppError> {
+    preferences
+        .get()
+        .app_lock
+        .authenticate(&reason, passcode.as_deref())
+        .await
+}
+
+#[tauri::command]
+fn get_browser_preference(settings: State<'_, SettingsStore>) -> BrowserPreference {
+    settings.get(&settings::DEFAULT_BROWSER)
+}
+
+#[tauri::command]
+async fn set_browser_preference(
+    app_handle: AppHandle,
+    preference: BrowserPreference,
+) -> Result<(), AppError> {
+    let value = serde_json::to_value(preference).map_err(|e| e.to_string())?;
+    save_setting(&app_handle, settings::DEFAULT_BROWSER.name, value).await?;
+    Ok(())
+}
+
+/// Every global setting by key, see `settings`.
+#[tauri::command]
+fn get_settings(
+    settings: State<'_, SettingsStore>,
+) -> std::collections::BTreeMap<&'static str, serde_json::Value> {
+    settings.all()
+}
+
+/// Saves a global setting and returns the value as saved.
+#[tauri::command]
+async fn set_setting(
+    app_handle: AppHandle,
+    key: String,
+    value: serde_json::Value,
+) -> Result<serde_json::Value, AppError> {
+    save_setting(&app_handle, &key, value).await
+}
+
+// Validates, saves and announces a setting with `settings-changed`
+async fn save_setting(
+    app_handle: &AppHandle,
+    key: &str,
+    value: serde_json::Value,
+) -> Result<serde_json::Value, AppError> {
+    let value = settings::check(key, value)?;
+    app_handle
+        .state::<Db>()
+        .set_setting(key, &value.to_string())
+        .await?;
+    app_handle
+        .state::<SettingsStore>()
+        .insert(key, value.clone());
+    let _ = app_handle.emit(
+        "settings-changed",
+        serde_json::json!({ "key": key, "value": value }),
+    );
+    Ok(value)
+}
+
+// Fills the settings cache, importing what older versions kept in preferences.json
+async fn load_settings(app_handle: &AppHandle) {
+    let db = app_handle.state::<Db>();
+    let mut values: HashMap<String, serde_json::Value> = match db.settings().await {
+        Ok(saved) => saved
+            .into_iter()
+            .filter_map(|(key, value)| Some((key, serde_json::from_str(&value).ok()?)))
+            .collect(),
+        Err(e) => {
+            log::error!("{}", e);
+            return;
+        }
+    };
+    let preferences = std::fs::read_to_string(app_data_dir().join(preferences::FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or(serde_json::Value::Null);
+    for (key, value) in settings::legacy(&preferences) {
+        if values.contains_key(key) {
+            continue;
+        }
+        match db.set_setting(key, &value.to_string()).await {
+            Ok(()) => {
+                log::info!("Moved {} from preferences.json to settings", key);
+                values.insert(key.to_string(), value);
+            }
+            Err(e) => log::warn!("{}", e),
+        }
+    }
+    app_handle.state::<SettingsStore>().load(values);
+}
+
+// ============ Proxy Commands ============
+
+#[tauri::command]
+fn get_lan_ip() -> Option<String> {
+    dns::get_lan_ip()
+}
+
+#[tauri::command]
+fn slugify_name(name: String) -> String {
+    let slug = proxy::slugify(&name);
+    // An app named "API" still gets a subdomain it can be routed on
+    match proxy::check_subdomain_reserved(&slug, dns::get_local_hostname().as_deref()) {
+        Ok(()) => slug,
+        Err(_) => format!("{}-app", slug),
+    }
+}
+
+#[tauri::command]
+async fn search_apps(state: State<'_, AppState>, query: String) -> Result<Vec<AppMatch>, AppError> {
+    let apps: Vec<App> = state.apps.lock().await.values().cloned().collect();
+    Ok(search::search(&apps, &query))
+}
+
+#[tauri::command]
+async fn set_app_subdomain(
+    db: State<'_, Db>,
+    id: String,
+    subdomain: Option<String>,
+) -> Result<(), AppError> {
+    let subdomain = subdomain.filter(|s| !s.is_empty());
+    if let Some(template) = &subdomain {
+        proxy::check_subdomain_template(template)?;
+        if !proxy::is_subdomain_template(template) {
+            proxy::check_subdomain_reserved(template, dns::get_local_hostname().as_deref())?;
+        }
+    }
+    db.set_subdomain(&id, subdomain.as_deref()).await
+}
+
+#[tauri::command]
+async fn add_proxy_route(
+    app_handle: AppHandle,
+    proxy_state: State<'_, ProxyState>,
+    mdns_registry: State<'_, MdnsRegistry>,
+    app_id: String,
+    subdomain: String,
+    port: i32,
+    // Seconds until the route is removed again; None keeps any expiry it already has
+    ttl_secs: Option<u64>,
+) -> Result<String, AppError> {
+    // A running app keeps what its template resolved to at launch
+    let subdomain = if proxy::is_subdomain_template(&subdomain) {
+        let state = app_handle.state::<AppState>();
+        let launched = {
+            let processes = state.processes.lock().await;
+            processes.get(&app_id).and_then(|p| p.subdomain.clone())
+        };
+        match launched {
+            Some(launched) => launched,
+            None => resolve_subdomain(&app_handle, &app_id, &subdomain, None).await,
+        }
+    } else {
+        subdomain
+    };
+    let old_subdomain = {
+        let routes = proxy_state.routes.lock().await;
+        routes.get(&app_id).map(|r| r.subdomain.clone())
+    };
+
+    if let Some(old_sub) = &old_subdomain {
+        if old_sub != &subdomain {
+            if let Err(e) = mdns_registry.unregister(old_sub) {
+                eprintln!("Failed to unregister old mDNS for {}: {}", old_sub, e);
+            }
+        }
+    }
+
+    app_handle
+        .state::<InspectorState>()
+        .set_upstream_port(&app_id, port)
+        .await;
+    proxy::add_route(&proxy_state, &app_id, &subdomain, port).await?;
+    // The sync routes running apps on this, and expiry clears it
+    let state = app_handle.state::<AppState>();
+    if let Some(process) = state.processes.lock().await.get_mut(&app_id) {
+        process.subdomain = Some(subdomain.clone());
+    }
+    if let Some(ttl) = ttl_secs {
+        proxy::set_route_expiry(&proxy_state, &app_id, Some(route_expiry(ttl))).await?;
+    }
+    if old_subdomain.as_ref() != Some(&subdomain) {
+        record_app_event(
+            &app_handle,
+            &app_id,
+            AppEventKind::RouteAdded,
+            Some(subdomain.clone()),
+        );
+    }
+    
+    if let Some(lan_ip) = dns::get_lan_ip() {
+        if let Err(e) = mdns_registry.register(&subdomain, &lan_ip) {
+            eprintln!("Failed to register mDNS for {}: {}", subdomain, e);
+        }
+    }
+    
+    Ok(subdomain)
+}
+
 #[tauri::command]
-async fn stop_app(
+async fn remove_proxy_route(
     app_handle: AppHandle,
-    state: State<'_, AppState>,
-    id: String,
-) -> Result<(), String> {
-    let mut processes = state.processes.lock().await;
-
-    if let Some(process) = processes.remove(&id) {
-        kill_process_tree(process.child.pid());
-
-        if let Err(e) = process.child.kill() {
-            let msg = format!("Failed to stop app: {}", e);
-            log::error!("{}", msg);
-            return Err(msg);
+    proxy_state: State<'_, ProxyState>,
+    mdns_registry: State<'_, MdnsRegistry>,
+    inspector: State<'_, InspectorState>,
+    tunnels: State<'_, TunnelManager>,
+    app_id: String,
+) -> Result<(), AppError> {
+    let subdomain = {
+        let routes = proxy_state.routes.lock().await;
+        routes.get(&app_id).map(|r| r.subdomain.clone())
+    };
+    
+    if let Some(subdomain) = &subdomain {
+        if let Err(e) = mdns_registry.unregister(subdomain) {
+            eprintln!("Failed to unregister mDNS for {}: {}", subdomain, e);
         }
+    }
 
-        remove_pid(&id);
-        log::info!(target: "success", "App stopped: id={}", id);
-
-        let _ = app_handle.emit(
-            "app-stopped",
-            serde_json::json!({
-                "id": id,
-                "code": null
-            }),
+    inspector.stop_relay(&app_id).await;
+    tunnels.close(&app_id).await;
+    proxy::remove_route(&proxy_state, &app_id).await?;
+    if let Some(subdomain) = subdomain {
+        record_app_event(
+            &app_handle,
+            &app_id,
+            AppEventKind::RouteRemoved,
+            Some(subdomain),
         );
     }
-
     Ok(())
 }
 
-#[tauri::command]
-async fn get_app_status(state: State<'_, AppState>, id: String) -> Result<Option<i32>, String> {
-    let processes = state.processes.lock().await;
-    Ok(processes.get(&id).map(|p| p.port))
+fn route_expiry(ttl_secs: u64) -> i64 {
+    health::now_ms().saturating_add(
+        i64::try_from(ttl_secs)
+            .unwrap_or(i64::MAX)
+            .saturating_mul(1000),
+    )
 }
 
+/// Gives a route a new lifetime from now, or keeps it until removed with None.
 #[tauri::command]
-async fn get_running_apps(state: State<'_, AppState>) -> Result<HashMap<String, i32>, String> {
-    let processes = state.processes.lock().await;
-    Ok(processes.iter().map(|(k, v)| (k.clone(), v.port)).collect())
+async fn set_route_ttl(
+    proxy_state: State<'_, ProxyState>,
+    app_id: String,
+    ttl_secs: Option<u64>,
+) -> Result<(), AppError> {
+    proxy::set_route_expiry(&proxy_state, &app_id, ttl_secs.map(route_expiry)).await
 }
 
-async fn emit_app_usage(app_handle: &AppHandle) {
-    let pids: Vec<(String, u32)> = {
-        let state = app_handle.state::<AppState>();
-        let processes = state.processes.lock().await;
-        processes
-            .iter()
-            .map(|(id, p)| (id.clone(), p.child.pid()))
-            .collect()
+// Takes down temporary routes whose time is up; the app itself keeps running
+async fn remove_expired_routes(app_handle: &AppHandle) {
+    let expired = {
+        let proxy_state = app_handle.state::<ProxyState>();
+        let routes = proxy_state.routes.lock().await;
+        proxy::expired_routes(&routes, health::now_ms())
     };
-    if pids.is_empty() {
-        let _ = app_handle.emit("app-usage", HashMap::<String, AppUsage>::new());
-        return;
-    }
-    let mut system = System::new_all();
-    system.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
-    let mut usage = HashMap::new();
-    for (app_id, pid) in pids {
-        if let Some(process) = system.process(Pid::from_u32(pid)) {
-            usage.insert(
-                app_id,
-                AppUsage {
-                    cpu: process.cpu_usage(),
-                    memory: process.memory(),
-                },
-            );
+    for app_id in expired {
+        // The sync re-adds routes for running apps with a subdomain, so the app loses it
+        // until a route is added again
+        let state = app_handle.state::<AppState>();
+        if let Some(process) = state.processes.lock().await.get_mut(&app_id) {
+            process.subdomain = None;
+        }
+        let removed = remove_proxy_route(
+            app_handle.clone(),
+            app_handle.state(),
+            app_handle.state(),
+            app_handle.state(),
+            app_handle.state(),
+            app_id.clone(),
+        )
+        .await;
+        match removed {
+            Ok(()) => {
+                log::info!("Temporary route for {} expired", app_id);
+                let _ = app_handle.emit("proxy-route-expired", &app_id);
+            }
+            Err(e) => log::error!("Failed to remove expired route for {}: {}", app_id, e),
         }
     }
-    let _ = app_handle.emit("app-usage", usage);
 }
 
 #[tauri::command]
-async fn get_app_logs(state: State<'_, AppState>, id: String) -> Result<Vec<String>, String> {
-    let logs = state.logs.lock().await;
-    Ok(logs.get(&id).cloned().unwrap_or_default())
+async fn get_proxy_routes(
+    proxy_state: State<'_, ProxyState>,
+) -> Result<HashMap<String, ProxyRoute>, AppError> {
+    let routes = proxy_state.routes.lock().await;
+    Ok(routes.clone())
 }
 
 #[tauri::command]
-async fn open_in_browser(port: i32, subdomain: Option<String>) -> Result<(), String> {
-    let url = if let Some(sub) = subdomain {
-        format!("http://{}.local", sub)
-    } else {
-        format!("http://localhost:{}", port)
+async fn set_route_inspection(
+    proxy_state: State<'_, ProxyState>,
+    inspector: State<'_, InspectorState>,
+    app_id: String,
+    enabled: bool,
+) -> Result<(), AppError> {
+    let (port, rate_limited) = {
+        let routes = proxy_state.routes.lock().await;
+        routes
+            .get(&app_id)
+            .map(|r| (r.port, r.rate_limit.is_some()))
+            .ok_or_else(|| AppError::not_found(format!("No proxy route for app {}", app_id)))?
     };
-    open::that(&url).map_err(|e| format!("Failed to open browser: {}", e))
+
+    if !enabled && rate_limited {
+        return Err("Remove the route's rate limit first; it runs in the inspection relay".into());
+    }
+    if enabled {
+        let relay_port = inspector.start_relay(&app_id, port).await?;
+        proxy::set_route_relay(&proxy_state, &app_id, Some(relay_port)).await
+    } else {
+        let result = proxy::set_route_relay(&proxy_state, &app_id, None).await;
+        inspector.stop_relay(&app_id).await;
+        result
+    }
 }
 
-// ============ Proxy Commands ============
+#[tauri::command]
+async fn set_route_mocks(
+    proxy_state: State<'_, ProxyState>,
+    app_id: String,
+    mocks: Vec<MockResponse>,
+) -> Result<(), AppError> {
+    proxy::set_route_mocks(&proxy_state, &app_id, mocks).await
+}
 
 #[tauri::command]
-fn get_lan_ip() -> Option<String> {
-    dns::get_lan_ip()
+async fn set_route_mocks_enabled(
+    proxy_state: State<'_, ProxyState>,
+    app_id: String,
+    enabled: bool,
+) -> Result<(), AppError> {
+    proxy::set_route_mocks_enabled(&proxy_state, &app_id, enabled).await
 }
 
+/// Lets an app's route answer on `*.<subdomain>.<hostname>.local` too, for apps that route
+/// tenants by subdomain. Needs the DNS responder to resolve those names.
 #[tauri::command]
-fn slugify_name(name: String) -> String {
-    proxy::slugify(&name)
+async fn set_route_wildcard(
+    proxy_state: State<'_, ProxyState>,
+    app_id: String,
+    wildcard: bool,
+) -> Result<(), AppError> {
+    if wildcard && proxy_state.settings().dns_domain.is_none() {
+        return Err("Wildcard routes need the DNS responder; enable it first".into());
+    }
+    proxy::set_route_wildcard(&proxy_state, &app_id, wildcard).await
 }
 
+/// Changes which Host and X-Forwarded-* headers an app's route sends the app.
 #[tauri::command]
-async fn add_proxy_route(
+async fn set_route_headers(
     proxy_state: State<'_, ProxyState>,
-    mdns_registry: State<'_, MdnsRegistry>,
     app_id: String,
-    subdomain: String,
-    port: i32,
-) -> Result<(), String> {
-    let old_subdomain = {
+    headers: ForwardHeaders,
+) -> Result<(), AppError> {
+    proxy::set_route_headers(&proxy_state, &app_id, headers).await
+}
+
+/// Sets how long the proxy waits on an app, e.g. to keep an SSE stream open for hours.
+#[tauri::command]
+async fn set_route_timeouts(
+    proxy_state: State<'_, ProxyState>,
+    app_id: String,
+    timeouts: ProxyTimeouts,
+) -> Result<(), AppError> {
+    proxy::set_route_timeouts(&proxy_state, &app_id, timeouts).await
+}
+
+/// Switches what the proxy speaks to an app: plain HTTP, h2c for gRPC, or HTTPS.
+#[tauri::command]
+async fn set_route_upstream(
+    proxy_state: State<'_, ProxyState>,
+    app_id: String,
+    upstream: UpstreamProtocol,
+) -> Result<(), AppError> {
+    proxy::set_route_upstream(&proxy_state, &app_id, upstream).await
+}
+
+/// Turns response compression on or off for a route; None goes back to the default,
+/// which compresses static sites only.
+#[tauri::command]
+async fn set_route_compression(
+    proxy_state: State<'_, ProxyState>,
+    app_id: String,
+    compress: Option<bool>,
+) -> Result<(), AppError> {
+    proxy::set_route_compression(&proxy_state, &app_id, compress).await
+}
+
+/// Caps the request body size a route accepts, in bytes; None removes the cap.
+#[tauri::command]
+async fn set_route_body_limit(
+    proxy_state: State<'_, ProxyState>,
+    app_id: String,
+    max_bytes: Option<u64>,
+) -> Result<(), AppError> {
+    proxy::set_route_body_limit(&proxy_state, &app_id, max_bytes).await
+}
+
+/// Answers 429 once a route gets more than `per_second` requests a second, to exercise a
+/// client's retry and backoff. The limit is enforced by the inspection relay, so a limited
+/// route is inspected too.
+#[tauri::command]
+async fn set_route_rate_limit(
+    proxy_state: State<'_, ProxyState>,
+    inspector: State<'_, InspectorState>,
+    app_id: String,
+    per_second: Option<u32>,
+) -> Result<(), AppError> {
+    let route = {
         let routes = proxy_state.routes.lock().await;
-        routes.get(&app_id).map(|r| r.subdomain.clone())
+        routes
+            .get(&app_id)
+            .cloned()
+            .ok_or_else(|| AppError::not_found(format!("No proxy route for app {}", app_id)))?
     };
-
-    if let Some(old_sub) = &old_subdomain {
-        if old_sub != &subdomain {
-            if let Err(e) = mdns_registry.unregister(old_sub) {
-                eprintln!("Failed to unregister old mDNS for {}: {}", old_sub, e);
-            }
-        }
+    if per_second.is_some()
+        && (route.static_site.is_some() || route.upstream != UpstreamProtocol::Http)
+    {
+        return Err("Only routes proxied over plain HTTP can be rate limited".into());
     }
 
-    proxy::add_route(&proxy_state, &app_id, &subdomain, port).await?;
-    
-    if let Some(lan_ip) = dns::get_lan_ip() {
-        if let Err(e) = mdns_registry.register(&subdomain, &lan_ip) {
-            eprintln!("Failed to register mDNS for {}: {}", subdomain, e);
+    // The relay goes first, so a limit is never saved without one enforcing it
+    let relay_port = match per_second {
+        Some(_) if route.relay_port.is_none() => {
+            Some(inspector.start_relay(&app_id, route.port).await?)
         }
+        _ => None,
+    };
+    let saved = proxy::set_route_rate_limit(&proxy_state, &app_id, per_second, relay_port).await;
+    if saved.is_err() && relay_port.is_some() {
+        inspector.stop_relay(&app_id).await;
     }
-    
+    saved?;
+    inspector.set_rate_limit(&app_id, per_second).await;
     Ok(())
 }
 
+/// Temporarily unpublishes an app's route without losing its subdomain and settings.
 #[tauri::command]
-async fn remove_proxy_route(
+async fn set_route_enabled(
     proxy_state: State<'_, ProxyState>,
     mdns_registry: State<'_, MdnsRegistry>,
     app_id: String,
-) -> Result<(), String> {
+    enabled: bool,
+) -> Result<(), AppError> {
+    proxy::set_route_enabled(&proxy_state, &app_id, enabled).await?;
     let subdomain = {
         let routes = proxy_state.routes.lock().await;
         routes.get(&app_id).map(|r| r.subdomain.clone())
     };
-    
     if let Some(subdomain) = subdomain {
-        if let Err(e) = mdns_registry.unregister(&subdomain) {
-            eprintln!("Failed to unregister mDNS for {}: {}", subdomain, e);
+        let result = if !enabled {
+            mdns_registry.unregister(&subdomain)
+        } else if let Some(lan_ip) = dns::get_lan_ip() {
+            mdns_registry.register(&subdomain, &lan_ip)
+        } else {
+            Ok(())
+        };
+        if let Err(e) = result {
+            log::error!("Failed to update mDNS for {}: {}", subdomain, e);
+        }
+    }
+    Ok(())
+}
+
+/// Serves an app's route over HTTPS as well, signed by Caddy's local CA. Other devices
+/// can install the CA from the status page.
+#[tauri::command]
+async fn set_route_tls(
+    app_handle: AppHandle,
+    proxy_state: State<'_, ProxyState>,
+    app_id: String,
+    enabled: bool,
+) -> Result<(), AppError> {
+    proxy::set_route_tls(&proxy_state, &app_id, enabled).await?;
+    publish_status(&app_handle).await;
+    Ok(())
+}
+
+/// Limits who on the LAN can reach an app's route; an empty list opens it to everyone.
+#[tauri::command]
+async fn set_route_allowed_ips(
+    proxy_state: State<'_, ProxyState>,
+    app_id: String,
+    allowed_ips: Vec<String>,
+) -> Result<(), AppError> {
+    proxy::set_route_allowed_ips(&proxy_state, &app_id, &allowed_ips).await
+}
+
+#[tauri::command]
+async fn get_recent_requests(
+    inspector: State<'_, InspectorState>,
+    app_id: String,
+) -> Result<Vec<CapturedRequest>, AppError> {
+    Ok(inspector.recent_requests(&app_id).await)
+}
+
+#[tauri::command]
+async fn replay_request(
+    state: State<'_, AppState>,
+    inspector: State<'_, InspectorState>,
+    request_id: String,
+    overrides: Option<ReplayOverrides>,
+) -> Result<ReplayResponse, AppError> {
+    let captured = inspector
+        .find_request(&request_id)
+        .await
+        .ok_or_else(|| AppError::not_found(format!("No recorded request {}", request_id)))?;
+    let overrides = overrides.unwrap_or_default();
+
+    let port = match overrides.port {
+        Some(port) => port,
+        None => {
+            let app_id = overrides.app_id.as_ref().unwrap_or(&captured.app_id);
+            let processes = state.processes.lock().await;
+            processes
+                .get(app_id)
+                .map(|p| p.port)
+                .ok_or_else(|| AppError::not_found(format!("App {} is not running", app_id)))?
+        }
+    };
+
+    inspector::replay(&captured, &overrides, port)
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn preview_caddy_import(path: String) -> Result<CaddyImport, AppError> {
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read Caddy config: {}", e))?;
+    Ok(caddy_import::parse_config(&content))
+}
+
+#[tauri::command]
+async fn import_manual_routes(
+    proxy_state: State<'_, ProxyState>,
+    routes: Vec<ImportedRoute>,
+) -> Result<ImportResult, AppError> {
+    let (routes, skipped) = caddy_import::route_subdomains(routes);
+    let ids = proxy::add_manual_routes(&proxy_state, &routes).await?;
+    log::info!(target: "success", "Imported {} manual route(s)", ids.len());
+    Ok(ImportResult { ids, skipped })
+}
+
+/// Routes `subdomain` to a service only `tunnel.ssh_host` can reach, over an ssh forward the
+/// app keeps open. Returns the route id; `remove_proxy_route` closes the tunnel.
+#[tauri::command]
+async fn add_tunnel_route(
+    proxy_state: State<'_, ProxyState>,
+    tunnels: State<'_, TunnelManager>,
+    settings: State<'_, SettingsStore>,
+    subdomain: String,
+    tunnel: SshTunnel,
+) -> Result<String, AppError> {
+    tunnel.validate()?;
+    let port = find_free_port(settings.get(&settings::PORT_RANGE), None)
+        .ok_or_else(|| AppError::port_unavailable(0, i18n::t("error.no_free_port")))?;
+    let id = proxy::add_tunnel_route(
+        &proxy_state,
+        &proxy::slugify(&subdomain),
+        port,
+        tunnel.clone(),
+    )
+    .await?;
+    tunnels.open(&id, tunnel, port as u16).await;
+    Ok(id)
+}
+
+#[tauri::command]
+async fn get_tunnel_statuses(
+    tunnels: State<'_, TunnelManager>,
+) -> Result<HashMap<String, TunnelStatus>, AppError> {
+    Ok(tunnels.statuses().await)
+}
+
+#[tauri::command]
+fn get_app_url(proxy_state: State<'_, ProxyState>, subdomain: String) -> String {
+    proxy::get_app_url(&subdomain, proxy_state.public_port())
+}
+
+async fn is_proxy_responsive(proxy_state: &ProxyState) -> bool {
+    if proxy_state.settings().backend == ProxyBackend::Builtin {
+        return proxy_state.builtin.running_port().await.is_some();
+    }
+    proxy::is_caddy_responsive(&proxy_state.admin_endpoint()).await
+}
+
+#[tauri::command]
+async fn is_proxy_service_running(proxy_state: State<'_, ProxyState>) -> Result<bool, AppError> {
+    Ok(is_proxy_responsive(&proxy_state).await)
+}
+
+#[tauri::command]
+fn get_proxy_settings(proxy_state: State<'_, ProxyState>) -> ProxySettings {
+    proxy_state.settings()
+}
+
+#[tauri::command]
+async fn set_proxy_backend(
+    proxy_state: State<'_, ProxyState>,
+    backend: ProxyBackend,
+    builtin_port: Option<u16>,
+) -> Result<(), AppError> {
+    proxy::set_backend(&proxy_state, backend, builtin_port).await?;
+    log::info!(target: "success", "Proxy backend set to {:?}", backend);
+    Ok(())
+}
+
+#[tauri::command]
+fn get_caddy_admin_endpoint(proxy_state: State<'_, ProxyState>) -> AdminEndpoint {
+    proxy_state.admin_endpoint()
+}
+
+#[tauri::command]
+async fn set_caddy_admin_endpoint(
+    proxy_state: State<'_, ProxyState>,
+    endpoint: Option<AdminEndpoint>,
+) -> Result<(), AppError> {
+    proxy::set_admin_endpoint(&proxy_state, endpoint).await
+}
+
+/// Unix ms of each app's last request through the proxy, for apps that have had one.
+#[tauri::command]
+fn get_last_accessed(tracker: State<'_, AccessTracker>) -> HashMap<String, i64> {
+    tracker.last_accessed()
+}
+
+#[tauri::command]
+async fn get_effective_caddy_config(
+    proxy_state: State<'_, ProxyState>,
+) -> Result<serde_json::Value, AppError> {
+    proxy::effective_config(&proxy_state).await
+}
+
+/// Loads a hand-edited Caddy JSON config for debugging. Lasts until the next route change.
+#[tauri::command]
+async fn apply_custom_caddy_config(
+    proxy_state: State<'_, ProxyState>,
+    config: serde_json::Value,
+) -> Result<(), AppError> {
+    proxy::apply_custom_config(&proxy_state, config).await?;
+    log::info!(target: "success", "Applied a custom Caddy config");
+    Ok(())
+}
+
+// Undoes a half-finished enable, e.g. when the admin prompt is cancelled
+async fn remove_dns_responder(responder: &DnsResponder, domain: &str) {
+    if let Err(e) = dns::remove_resolver(domain).await {
+        log::error!("Failed to remove the resolver for {}: {}", domain, e);
+    }
+    responder.stop().await;
+}
+
+#[tauri::command]
+async fn enable_dns_responder(
+    proxy_state: State<'_, ProxyState>,
+    responder: State<'_, DnsResponder>,
+) -> Result<String, AppError> {
+    let hostname = dns::get_local_hostname()
+        .ok_or_else(|| "Could not determine the local host name".to_string())?;
+    let mut domain = format!("{}.local", hostname);
+
+    responder
+        .start(&domain)
+        .await
+        .map_err(|e| AppError::port_unavailable(dns_responder::RESPONDER_PORT.into(), e))?;
+    if let Err(e) = dns::install_resolver(&domain, dns_responder::RESPONDER_PORT).await {
+        responder.stop().await;
+        return Err(e);
+    }
+
+    // Some macOS versions resolve every .local name over multicast DNS and never ask
+    // /etc/resolver, so app addresses move to a suffix mDNS leaves alone
+    if !dns::resolves_through_responder(&domain).await {
+        if let Some(fallback) = dns::fallback_domain(&domain) {
+            log::warn!(
+                "Lookups for *.{} don't reach the DNS responder, switching to *.{}",
+                domain,
+                fallback
+            );
+            if let Err(e) =
+                dns::move_resolver(&domain, &fallback, dns_responder::RESPONDER_PORT).await
+            {
+                remove_dns_responder(&responder, &domain).await;
+                return Err(e);
+            }
+            // The running responder swaps its domain without rebinding the port
+            responder
+                .start(&fallback)
+                .await
+                .map_err(|e| AppError::port_unavailable(dns_responder::RESPONDER_PORT.into(), e))?;
+            domain = fallback;
+        }
+    }
+    if let Err(e) = proxy::set_dns_domain(&proxy_state, Some(domain.clone())).await {
+        remove_dns_responder(&responder, &domain).await;
+        return Err(e);
+    }
+
+    log::info!(target: "success", "DNS responder enabled for *.{}", domain);
+    Ok(domain)
+}
+
+#[tauri::command]
+async fn disable_dns_responder(
+    proxy_state: State<'_, ProxyState>,
+    responder: State<'_, DnsResponder>,
+) -> Result<(), AppError> {
+    if let Some(domain) = proxy_state.settings().dns_domain {
+        dns::remove_resolver(&domain).await?;
+    }
+    responder.stop().await;
+    proxy::set_dns_domain(&proxy_state, None).await?;
+
+    log::info!(target: "success", "DNS responder disabled");
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_dns_responder_domain(
+    responder: State<'_, DnsResponder>,
+) -> Result<Option<String>, AppError> {
+    Ok(responder.domain().await)
+}
+
+/// Checks the machine is ready to run apps behind the proxy, for the setup wizard.
+#[tauri::command]
+async fn run_doctor(proxy_state: State<'_, ProxyState>) -> Result<Vec<CheckResult>, AppError> {
+    let shell = login_shell();
+    let settings = proxy_state.settings();
+    let context = DoctorContext {
+        shell_args: login_shell_args(&shell),
+        shell,
+        backend: settings.backend,
+        proxy_installed: dns::is_service_installed(),
+        proxy_responsive: is_proxy_responsive(&proxy_state).await,
+        proxy_port: proxy_state.public_port(),
+        dns_domain: settings.dns_domain,
+    };
+    Ok(doctor::run(&context).await)
+}
+
+#[tauri::command]
+async fn run_doctor_fix(app_handle: AppHandle, fix: DoctorFix) -> Result<(), AppError> {
+    match fix {
+        DoctorFix::InstallProxy => install_proxy_service(app_handle).await,
+        DoctorFix::StartProxy => start_proxy_service().await,
+        DoctorFix::EnableDnsResponder => {
+            enable_dns_responder(app_handle.state(), app_handle.state()).await?;
+            Ok(())
+        }
+        DoctorFix::NetworkWorkarounds => {
+            let domain = app_handle.state::<ProxyState>().settings().dns_domain;
+            let issues = tauri::async_runtime::spawn_blocking(move || {
+                dns::network_issues(domain.as_deref())
+            })
+            .await
+            .map_err(|e| format!("Failed to check the network: {}", e))?;
+            let applied = dns::apply_network_workarounds(&issues).await?;
+            log::info!(target: "success", "Network workarounds: {}", applied.join(", "));
+            Ok(())
         }
+        DoctorFix::StartDocker => Ok(doctor::start_docker().await?),
+        DoctorFix::OpenUrl { url } => app_handle
+            .opener()
+            .open_url(&url, None::<&str>)
+            .map_err(|e| format!("Failed to open {}: {}", url, e).into()),
     }
-    
-    proxy::remove_route(&proxy_state, &app_id).await
 }
 
+/// Crash reports the user hasn't reported or dismissed yet, newest first.
 #[tauri::command]
-async fn get_proxy_routes(
-    proxy_state: State<'_, ProxyState>,
-) -> Result<HashMap<String, ProxyRoute>, String> {
-    let routes = proxy_state.routes.lock().await;
-    Ok(routes.clone())
+fn get_crash_reports() -> Vec<CrashReport> {
+    crash_report::load_all(&app_data_dir())
+        .into_iter()
+        .filter(|report| !report.dismissed)
+        .collect()
+}
+
+/// Opens a prefilled GitHub issue for a crash report.
+#[tauri::command]
+fn report_crash(app_handle: AppHandle, id: String) -> Result<(), AppError> {
+    let report = crash_report::load(&app_data_dir(), &id)?;
+    app_handle
+        .opener()
+        .open_url(report.issue_url(), None::<&str>)
+        .map_err(|e| format!("Failed to open the issue page: {}", e))?;
+    Ok(crash_report::dismiss(&app_data_dir(), &id)?)
+}
+
+#[tauri::command]
+fn dismiss_crash_report(id: String) -> Result<(), AppError> {
+    Ok(crash_report::dismiss(&app_data_dir(), &id)?)
 }
 
+/// The newest internal log entries, for the debug window.
 #[tauri::command]
-fn get_app_url(subdomain: String) -> String {
-    proxy::get_app_url(&subdomain)
+async fn get_internal_logs(
+    limit: Option<usize>,
+    level: Option<String>,
+) -> Result<Vec<InternalLogEntry>, AppError> {
+    let limit = limit.unwrap_or(internal_log::DEFAULT_LIMIT);
+    tauri::async_runtime::spawn_blocking(move || {
+        internal_log::read(&app_data_dir(), limit, level.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Failed to read the internal log: {}", e).into())
 }
 
 #[tauri::command]
-async fn is_proxy_service_running() -> Result<bool, String> {
-    Ok(proxy::is_caddy_responsive().await)
+fn open_debug_window(app_handle: AppHandle) -> Result<(), AppError> {
+    if let Some(window) = app_handle.get_webview_window("debug") {
+        let _ = window.show();
+        let _ = window.set_focus();
+        return Ok(());
+    }
+    WebviewWindowBuilder::new(
+        &app_handle,
+        "debug",
+        tauri::WebviewUrl::App("index.html".into()),
+    )
+    .title("My Little Apps – Internal Log")
+    .inner_size(1000.0, 600.0)
+    .build()
+    .map_err(|e| format!("Failed to open the debug window: {}", e))?;
+    Ok(())
 }
 
 // ============ Proxy Service (LaunchDaemon) Commands ============
@@ -681,7 +5052,8 @@ fn get_proxy_service_status() -> ProxyServiceStatus {
 }
 
 #[tauri::command]
-async fn install_proxy_service(app_handle: AppHandle) -> Result<(), String> {
+#[tracing::instrument(skip_all, err(Display))]
+async fn install_proxy_service(app_handle: AppHandle) -> Result<(), AppError> {
     match dns::install_service(&app_handle).await {
         Ok(()) => {
             log::info!(target: "success", "Proxy service installed");
@@ -695,11 +5067,36 @@ async fn install_proxy_service(app_handle: AppHandle) -> Result<(), String> {
 }
 
 #[tauri::command]
-async fn uninstall_proxy_service(app_handle: AppHandle) -> Result<(), String> {
-    match dns::uninstall_service(&app_handle).await {
-        Ok(()) => {
-            log::info!(target: "success", "Proxy service uninstalled");
-            Ok(())
+#[tracing::instrument(skip_all, err(Display))]
+async fn uninstall_proxy_service(
+    app_handle: AppHandle,
+    proxy_state: State<'_, ProxyState>,
+    responder: State<'_, DnsResponder>,
+    passcode: Option<String>,
+) -> Result<UninstallReport, AppError> {
+    check_app_lock(
+        &app_handle,
+        "uninstall the proxy service",
+        passcode.as_deref(),
+    )
+    .await?;
+    let resolver_domain = proxy_state.settings().dns_domain;
+    match dns::uninstall_service(&app_handle, resolver_domain.as_deref()).await {
+        Ok(report) => {
+            // The resolver file went with the service; Caddy is gone so there is nothing to reload
+            if resolver_domain.is_some() {
+                responder.stop().await;
+                proxy_state.update_settings(|settings| settings.dns_domain = None);
+            }
+            if report.is_clean() {
+                log::info!(target: "success", "Proxy service uninstalled");
+            } else {
+                log::warn!(
+                    "Proxy service uninstalled with leftovers: {}",
+                    report.remaining.join(", ")
+                );
+            }
+            Ok(report)
         }
         Err(e) => {
             log::error!("Proxy service uninstall failed: {}", e);
@@ -709,7 +5106,8 @@ async fn uninstall_proxy_service(app_handle: AppHandle) -> Result<(), String> {
 }
 
 #[tauri::command]
-async fn start_proxy_service() -> Result<(), String> {
+#[tracing::instrument(skip_all, err(Display))]
+async fn start_proxy_service() -> Result<(), AppError> {
     match dns::start_service().await {
         Ok(()) => {
             log::info!(target: "success", "Proxy service started");
@@ -723,7 +5121,8 @@ async fn start_proxy_service() -> Result<(), String> {
 }
 
 #[tauri::command]
-async fn stop_proxy_service() -> Result<(), String> {
+#[tracing::instrument(skip_all, err(Display))]
+async fn stop_proxy_service() -> Result<(), AppError> {
     match dns::stop_service().await {
         Ok(()) => {
             log::info!(target: "success", "Proxy service stopped");
@@ -736,7 +5135,16 @@ async fn stop_proxy_service() -> Result<(), String> {
     }
 }
 
-fn update_tray_menu(app: &AppHandle, apps: Vec<App>, running: &HashMap<String, i32>) {
+const COPY_URL_MENU_PREFIX: &str = "copy-url:";
+const REVEAL_MENU_PREFIX: &str = "reveal:";
+
+fn update_tray_menu(
+    app: &AppHandle,
+    apps: Vec<App>,
+    running: &HashMap<String, i32>,
+    restart_needed: &HashMap<String, Vec<ConfigChange>>,
+    building: &HashSet<String>,
+) {
     let tray = app.tray_by_id("main-tray");
     if tray.is_none() {
         return;
@@ -749,14 +5157,44 @@ fn update_tray_menu(app: &AppHandle, apps: Vec<App>, running: &HashMap<String, i
     if let Ok(menu) = &mut menu_builder {
         // Add app items
         for app_data in &apps {
-            let status = if let Some(port) = running.get(&app_data.id) {
-                format!("{} (:{}) - Running", app_data.name, port)
-            } else {
-                format!("{} - Stopped", app_data.name)
+            let port = running.get(&app_data.id);
+            let status = match port {
+                _ if building.contains(&app_data.id) => {
+                    i18n::tf("tray.app_building", &[("name", &app_data.name)])
+                }
+                Some(port) if restart_needed.contains_key(&app_data.id) => i18n::tf(
+                    "tray.app_restart_needed",
+                    &[("name", &app_data.name), ("port", port)],
+                ),
+                Some(port) => i18n::tf(
+                    "tray.app_running",
+                    &[("name", &app_data.name), ("port", port)],
+                ),
+                None => i18n::tf("tray.app_stopped", &[("name", &app_data.name)]),
             };
 
-            if let Ok(item) = MenuItem::with_id(app, &app_data.id, &status, true, None::<&str>) {
-                let _ = menu.append(&item);
+            // The "open" item keeps the app id as its menu id
+            let mut entries = Vec::new();
+            if port.is_some() {
+                entries.push((app_data.id.clone(), i18n::t("tray.open_in_browser")));
+                let copy_id = format!("{}{}", COPY_URL_MENU_PREFIX, app_data.id);
+                entries.push((copy_id, i18n::t("tray.copy_url")));
+            }
+            let reveal_id = format!("{}{}", REVEAL_MENU_PREFIX, app_data.id);
+            entries.push((reveal_id, i18n::t("tray.reveal_in_finder")));
+
+            let items: Vec<MenuItem<tauri::Wry>> = entries
+                .into_iter()
+                .filter_map(|(id, label)| {
+                    MenuItem::with_id(app, id, label, true, None::<&str>).ok()
+                })
+                .collect();
+            let item_refs: Vec<&dyn IsMenuItem<tauri::Wry>> = items
+                .iter()
+                .map(|item| item as &dyn IsMenuItem<tauri::Wry>)
+                .collect();
+            if let Ok(submenu) = Submenu::with_items(app, &status, true, &item_refs) {
+                let _ = menu.append(&submenu);
             }
         }
 
@@ -767,13 +5205,27 @@ fn update_tray_menu(app: &AppHandle, apps: Vec<App>, running: &HashMap<String, i
             }
         }
 
+        if !apps.is_empty() {
+            if let Ok(switch) = MenuItem::with_id(
+                app,
+                "quick-switch",
+                i18n::t("tray.find_app"),
+                true,
+                Some("CmdOrCtrl+K"),
+            ) {
+                let _ = menu.append(&switch);
+            }
+        }
+
         // Add settings item
-        if let Ok(settings) = MenuItem::with_id(app, "settings", "Settings...", true, None::<&str>) {
+        if let Ok(settings) =
+            MenuItem::with_id(app, "settings", i18n::t("tray.settings"), true, None::<&str>)
+        {
             let _ = menu.append(&settings);
         }
 
         // Add quit item
-        if let Ok(quit) = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>) {
+        if let Ok(quit) = MenuItem::with_id(app, "quit", i18n::t("tray.quit"), true, None::<&str>) {
             let _ = menu.append(&quit);
         }
 
@@ -782,20 +5234,102 @@ fn update_tray_menu(app: &AppHandle, apps: Vec<App>, running: &HashMap<String, i
 }
 
 #[tauri::command]
-async fn refresh_tray(
-    app_handle: AppHandle,
-    state: State<'_, AppState>,
-    apps: Vec<App>,
-) -> Result<(), String> {
-    let processes = state.processes.lock().await;
-    let running: HashMap<String, i32> = processes.iter().map(|(k, v)| (k.clone(), v.port)).collect();
-    update_tray_menu(&app_handle, apps, &running);
-    Ok(())
+async fn refresh_tray(app_handle: AppHandle) -> Result<(), AppError> {
+    reload_apps(&app_handle).await.map_err(AppError::from)
+}
+
+// Stops every app and clears the proxy. The tray's Quit, Cmd+Q, logout and SIGTERM all end
+// up here, some more than once, so only the first call does anything
+fn shutdown(app_handle: &AppHandle) {
+    let app_state = app_handle.state::<AppState>();
+    if app_state.shut_down.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    log::info!("Application shutting down");
+    let proxy_state = app_handle.state::<ProxyState>();
+    let mdns_registry = app_handle.state::<MdnsRegistry>();
+    // Exit can't wait on a lock held by a stuck task
+    let timeout = std::time::Duration::from_secs(2);
+
+    let default_behavior = app_handle.state::<PreferencesState>().get().apps_on_quit;
+    let overrides = tauri::async_runtime::block_on(async {
+        tokio::time::timeout(timeout, app_handle.state::<Db>().quit_behaviors()).await
+    });
+    let overrides = match overrides {
+        Ok(Ok(overrides)) => overrides,
+        Ok(Err(e)) => {
+            log::warn!("{}", e);
+            HashMap::new()
+        }
+        Err(_) => HashMap::new(),
+    };
+
+    let processes = tauri::async_runtime::block_on(async {
+        tokio::time::timeout(timeout, app_state.processes.lock())
+            .await
+            .map(|mut processes| processes.drain().collect::<Vec<_>>())
+    });
+    let mut kept: HashMap<String, KeptApp> = HashMap::new();
+    match processes {
+        Ok(processes) => {
+            for (id, process) in processes {
+                let pid = process.child.pid();
+                let behavior = overrides.get(&id).copied().unwrap_or(default_behavior);
+                match (behavior, &process.log, process_start_time(pid)) {
+                    (QuitBehavior::KeepRunning, Some(log), Some(started_at)) => {
+                        log::info!("Leaving app {} running", id);
+                        kept.insert(
+                            id,
+                            KeptApp {
+                                pid,
+                                started_at,
+                                port: process.port,
+                                requested_port: process.requested_port,
+                                subdomain: process.subdomain,
+                                profile: process.profile,
+                                command: process.command,
+                                env: process.env,
+                                container: process.container,
+                                log: log.clone(),
+                            },
+                        );
+                        continue;
+                    }
+                    (QuitBehavior::KeepRunning, None, _) => {
+                        log::info!("App {} was started before it was set to keep running", id)
+                    }
+                    _ => {}
+                }
+                kill_process_tree(pid);
+                let _ = process.child.kill();
+                if let Some(container) = process.container {
+                    stop_container(&container);
+                }
+            }
+            keep_running::save(&app_data_dir(), &kept);
+            write_pids(&kept.iter().map(|(id, app)| (id.clone(), app.pid)).collect());
+        }
+        Err(_) => log::warn!("Apps are busy, leaving them to the next launch's cleanup"),
+    }
+
+    let _ = mdns_registry.unregister_all();
+
+    tauri::async_runtime::block_on(async {
+        let Ok(mut routes) = tokio::time::timeout(timeout, proxy_state.routes.lock()).await else {
+            return;
+        };
+        // Caddy keeps serving apps left running while we're gone
+        routes.retain(|id, _| kept.contains_key(id));
+        let _ = proxy::update_routes(&proxy_state, &routes).await;
+    });
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let _ = ensure_app_data_dir();
+    crash_report::install_panic_hook(app_data_dir());
+    // Flushes the internal log when run() returns
+    let _trace_guard = internal_log::init(&app_data_dir());
     let log_dir = app_data_dir();
     let log_plugin = tauri_plugin_log::Builder::new()
         .clear_targets()
@@ -817,7 +5351,10 @@ pub fn run() {
             } else {
                 "[info]"
             };
-            out.finish(format_args!("{} {} {}", ts, prefix, message))
+            let line = format!("{} {} {}", ts, prefix, message);
+            crash_report::record(&line);
+            internal_log::forward(record);
+            out.finish(format_args!("{}", line))
         })
         .build();
 
@@ -846,8 +5383,304 @@ pub fn run() {
             "#,
             kind: MigrationKind::Up,
         },
+        Migration {
+            version: 3,
+            description: "add_description_and_notes_columns",
+            sql: r#"
+                ALTER TABLE apps ADD COLUMN description TEXT;
+                ALTER TABLE apps ADD COLUMN notes TEXT;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 4,
+            description: "create_app_profiles_table",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS app_profiles (
+                    id TEXT PRIMARY KEY NOT NULL,
+                    app_id TEXT NOT NULL REFERENCES apps(id) ON DELETE CASCADE,
+                    name TEXT NOT NULL,
+                    command TEXT NOT NULL,
+                    env TEXT NOT NULL DEFAULT '{}',
+                    port INTEGER,
+                    UNIQUE (app_id, name)
+                );
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 5,
+            description: "add_worktree_of_column",
+            sql: r#"
+                ALTER TABLE apps ADD COLUMN worktree_of TEXT;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 6,
+            description: "add_app_kind_columns",
+            sql: r#"
+                ALTER TABLE apps ADD COLUMN kind TEXT NOT NULL DEFAULT 'process';
+                ALTER TABLE apps ADD COLUMN spa INTEGER NOT NULL DEFAULT 0;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 7,
+            description: "add_build_command_column",
+            sql: r#"
+                ALTER TABLE apps ADD COLUMN build_command TEXT;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 8,
+            description: "add_service_column",
+            sql: r#"
+                ALTER TABLE apps ADD COLUMN service TEXT;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 9,
+            description: "add_prerequisites_column",
+            sql: r#"
+                ALTER TABLE apps ADD COLUMN prerequisites TEXT;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 10,
+            description: "add_remote_host_column",
+            sql: r#"
+                ALTER TABLE apps ADD COLUMN remote_host TEXT;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 11,
+            description: "add_devcontainer_column",
+            sql: r#"
+                ALTER TABLE apps ADD COLUMN devcontainer INTEGER NOT NULL DEFAULT 0;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        // SQLite can't drop a column constraint, so the table is rebuilt. Dropping the old
+        // table cascades into app_profiles even inside the migration, hence the copy
+        Migration {
+            version: 12,
+            description: "unique_subdomain_and_non_unique_path",
+            sql: r#"
+                CREATE TABLE app_profiles_backup AS SELECT * FROM app_profiles;
+                CREATE TABLE apps_new (
+                    id TEXT PRIMARY KEY NOT NULL,
+                    name TEXT NOT NULL,
+                    path TEXT NOT NULL,
+                    command TEXT NOT NULL DEFAULT 'bun start',
+                    port INTEGER,
+                    run_on_startup INTEGER NOT NULL DEFAULT 0,
+                    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                    subdomain TEXT,
+                    description TEXT,
+                    notes TEXT,
+                    worktree_of TEXT,
+                    kind TEXT NOT NULL DEFAULT 'process',
+                    spa INTEGER NOT NULL DEFAULT 0,
+                    build_command TEXT,
+                    service TEXT,
+                    prerequisites TEXT,
+                    remote_host TEXT,
+                    devcontainer INTEGER NOT NULL DEFAULT 0
+                );
+                INSERT INTO apps_new (id, name, path, command, port, run_on_startup, created_at,
+                    subdomain, description, notes, worktree_of, kind, spa, build_command, service,
+                    prerequisites, remote_host, devcontainer)
+                SELECT id, name, path, command, port, run_on_startup, created_at,
+                    NULLIF(subdomain, ''), description, notes, worktree_of, kind, spa,
+                    build_command, service, prerequisites, remote_host, devcontainer
+                FROM apps;
+                DROP TABLE apps;
+                ALTER TABLE apps_new RENAME TO apps;
+                INSERT INTO app_profiles SELECT * FROM app_profiles_backup;
+                DROP TABLE app_profiles_backup;
+                -- Existing duplicates keep the subdomain on the first app added
+                UPDATE apps SET subdomain = NULL
+                WHERE subdomain IS NOT NULL AND rowid NOT IN (
+                    SELECT MIN(rowid) FROM apps WHERE subdomain IS NOT NULL GROUP BY subdomain
+                );
+                CREATE UNIQUE INDEX apps_subdomain ON apps (subdomain);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 13,
+            description: "create_health_checks_table",
+            sql: r#"
+                ALTER TABLE apps ADD COLUMN health_path TEXT;
+                CREATE TABLE IF NOT EXISTS health_checks (
+                    id INTEGER PRIMARY KEY,
+                    app_id TEXT NOT NULL REFERENCES apps(id) ON DELETE CASCADE,
+                    checked_at INTEGER NOT NULL,
+                    up INTEGER NOT NULL,
+                    status INTEGER,
+                    response_ms INTEGER,
+                    error TEXT
+                );
+                CREATE INDEX health_checks_app ON health_checks (app_id, checked_at);
+                CREATE INDEX health_checks_checked_at ON health_checks (checked_at);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 14,
+            description: "add_app_on_quit",
+            // "stop" or "keep_running"; NULL follows the global setting
+            sql: "ALTER TABLE apps ADD COLUMN on_quit TEXT;",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 15,
+            description: "create_settings_table",
+            // Values are JSON, see `settings`
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS settings (
+                    key TEXT PRIMARY KEY,
+                    value TEXT NOT NULL
+                );
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 16,
+            description: "create_app_events_table",
+            // Kinds are `timeline::AppEventKind`
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS app_events (
+                    id INTEGER PRIMARY KEY,
+                    app_id TEXT NOT NULL REFERENCES apps(id) ON DELETE CASCADE,
+                    at INTEGER NOT NULL,
+                    kind TEXT NOT NULL,
+                    detail TEXT
+                );
+                CREATE INDEX app_events_app ON app_events (app_id, at);
+                CREATE INDEX app_events_at ON app_events (at);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 17,
+            description: "add_path_entries_column",
+            sql: r#"
+                ALTER TABLE apps ADD COLUMN path_entries TEXT;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 18,
+            description: "add_shell_column",
+            sql: r#"
+                ALTER TABLE apps ADD COLUMN shell TEXT;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 19,
+            description: "add_sample_logs_column",
+            sql: r#"
+                ALTER TABLE apps ADD COLUMN sample_logs INTEGER NOT NULL DEFAULT 0;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 20,
+            description: "add_notify_ready_column",
+            sql: r#"
+                ALTER TABLE apps ADD COLUMN notify_ready INTEGER NOT NULL DEFAULT 0;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 21,
+            description: "create_sessions",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS sessions (
+                    name TEXT NOT NULL,
+                    app_id TEXT NOT NULL REFERENCES apps(id) ON DELETE CASCADE,
+                    profile TEXT,
+                    PRIMARY KEY (name, app_id)
+                );
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 22,
+            description: "create_session_triggers",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS session_triggers (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    session TEXT,
+                    kind TEXT NOT NULL,
+                    days INTEGER NOT NULL DEFAULT 0,
+                    at TEXT,
+                    focus TEXT
+                );
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 23,
+            description: "add_sandbox_column",
+            sql: r#"
+                ALTER TABLE apps ADD COLUMN sandbox INTEGER NOT NULL DEFAULT 0;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 24,
+            description: "add_rebuild_stale_column",
+            sql: r#"
+                ALTER TABLE apps ADD COLUMN rebuild_stale INTEGER NOT NULL DEFAULT 0;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 25,
+            description: "add_package_meta_columns",
+            sql: r#"
+                ALTER TABLE apps ADD COLUMN package_name TEXT;
+                ALTER TABLE apps ADD COLUMN package_version TEXT;
+                ALTER TABLE apps ADD COLUMN package_engines TEXT;
+                ALTER TABLE apps ADD COLUMN engine_warning TEXT;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 26,
+            description: "create_proxy_routes",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS proxy_routes (
+                    app_id TEXT PRIMARY KEY,
+                    route TEXT NOT NULL
+                );
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 27,
+            description: "delete_orphaned_session_triggers",
+            sql: r#"
+                DELETE FROM session_triggers
+                WHERE session IS NOT NULL AND session NOT IN (SELECT name FROM sessions);
+            "#,
+            kind: MigrationKind::Up,
+        },
     ];
 
+    let mut updater = tauri_plugin_updater::Builder::new();
+    if let Some(pubkey) = self_update::pubkey() {
+        updater = updater.pubkey(pubkey);
+    }
+
     let app = tauri::Builder::default()
         .plugin(log_plugin)
         .plugin(
@@ -862,9 +5695,23 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(updater.build())
         .manage(AppState::default())
-        .manage(ProxyState::default())
+        .manage(ProxyState::with_data_dir(app_data_dir()))
+        .manage(PreferencesState::with_data_dir(app_data_dir()))
+        .manage(AccessTracker::with_data_dir(app_data_dir()))
+        .manage(SettingsStore::default())
+        .manage(InspectorState::default())
+        .manage(DnsResponder::new())
         .manage(MdnsRegistry::new())
+        .manage(StatusPage::new())
+        .manage(RemoteControl::new())
+        .manage(RemoteSessions::default())
+        .manage(TunnelManager::new())
+        .manage(LogForwarder::new())
+        .manage(LogEvents::new())
+        .manage(UnifiedLog::new())
         .on_window_event(|window, event| {
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 if window.label() == "main" {
@@ -882,13 +5729,116 @@ pub fn run() {
                     eprintln!("{}", e);
                 }
             }
-            cleanup_orphaned_processes();
+            let kept = keep_running::take(&app_data_dir());
+            let stopped_apps = cleanup_orphaned_processes(&kept);
+            app.manage(Db::new(app.path().app_config_dir()?.join(db::DB_FILE)));
+            // Before anything reads a setting; the sql plugin has run the migrations by now
+            tauri::async_runtime::block_on(load_settings(app.handle()));
+            app.state::<ProxyState>()
+                .set_app_handle(app.handle().clone());
+            let crash_handle = app.handle().clone();
+            crash_report::set_listener(move |report| {
+                let _ = crash_handle.emit("crash-report", report);
+            });
+
+            let restore_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                restore_proxy_routes(&restore_handle, stopped_apps, kept).await;
+                restore_update_snapshot(&restore_handle).await;
+            });
+
+            // launchd sends SIGTERM to whatever still runs at logout and shutdown
+            #[cfg(unix)]
+            {
+                let signal_handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    use tokio::signal::unix::{signal, SignalKind};
+                    let Ok(mut terminate) = signal(SignalKind::terminate()) else {
+                        return;
+                    };
+                    if terminate.recv().await.is_some() {
+                        signal_handle.exit(0);
+                    }
+                });
+            }
 
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
+                let mut watchdog = Watchdog::default();
                 loop {
                     tokio::time::sleep(std::time::Duration::from_secs(10)).await;
-                    cleanup_and_sync(&app_handle).await;
+                    cleanup_and_sync(&app_handle, &mut watchdog).await;
+                    remove_expired_routes(&app_handle).await;
+                }
+            });
+
+            let status_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let mut last_health: Option<ProxyServiceHealth> = None;
+                loop {
+                    let proxy_state = status_handle.state::<ProxyState>();
+                    let health = ProxyServiceHealth {
+                        status: dns::get_service_status(),
+                        responsive: is_proxy_responsive(&proxy_state).await,
+                    };
+                    if last_health.as_ref() != Some(&health) {
+                        let _ = status_handle.emit("proxy-service-status", &health);
+                        last_health = Some(health);
+                    }
+                    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                }
+            });
+
+            tauri::async_runtime::spawn(run_health_checks(app.handle().clone()));
+            tauri::async_runtime::spawn(flush_log_events(app.handle().clone()));
+            tauri::async_runtime::spawn(run_session_triggers(app.handle().clone()));
+
+            let git_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let mut last_infos: Option<HashMap<String, GitInfo>> = None;
+                loop {
+                    let infos = collect_git_info(&git_handle).await;
+                    if last_infos.as_ref() != Some(&infos) {
+                        let _ = git_handle.emit("app-git-info", &infos);
+                        last_infos = Some(infos);
+                    }
+                    tokio::time::sleep(std::time::Duration::from_secs(15)).await;
+                }
+            });
+
+            if app.state::<PreferencesState>().get().status_page {
+                let status_handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    let status_page = status_handle.state::<StatusPage>();
+                    if let Err(e) = status_page.start(status_page::STATUS_PORT).await {
+                        crash_report::report_task_error(&app_data_dir(), "Status page", &e);
+                    }
+                });
+            }
+            let log_forwarding = app.state::<PreferencesState>().get().log_forwarding;
+            app.state::<LogForwarder>().configure(&log_forwarding);
+            app.state::<UnifiedLog>()
+                .set_enabled(log_forwarding.unified_log);
+            let trace_export = app.state::<PreferencesState>().get().trace_export;
+            let inspector = app.state::<InspectorState>();
+            if let Err(e) = inspector.traces().configure(&trace_export) {
+                log::error!("{}", e);
+            }
+            if app.state::<PreferencesState>().get().remote_control.enabled {
+                let remote_handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    let remote_control = remote_handle.state::<RemoteControl>();
+                    let api = RemoteApi(remote_handle.clone());
+                    if let Err(e) = remote_control.start(remote_control::REMOTE_PORT, api).await {
+                        crash_report::report_task_error(&app_data_dir(), "Remote control", &e);
+                    }
+                });
+            }
+            let publish_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    publish_status(&publish_handle).await;
+                    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
                 }
             });
 
@@ -908,8 +5858,9 @@ pub fn run() {
 
             // Create initial menu
             let menu = Menu::with_id(app, "tray-menu")?;
-            let settings = MenuItem::with_id(app, "settings", "Settings...", true, None::<&str>)?;
-            let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+            let settings =
+                MenuItem::with_id(app, "settings", i18n::t("tray.settings"), true, None::<&str>)?;
+            let quit = MenuItem::with_id(app, "quit", i18n::t("tray.quit"), true, None::<&str>)?;
             menu.append(&settings)?;
             menu.append(&quit)?;
 
@@ -940,6 +5891,31 @@ pub fn run() {
                         "quit" => {
                             app.exit(0);
                         }
+                        "quick-switch" => {
+                            if let Some(window) = app.get_webview_window("main") {
+                                let _ = window.show();
+                                let _ = window.set_focus();
+                                let _ = app.emit("open-quick-switcher", ());
+                            }
+                        }
+                        _ if id.starts_with(COPY_URL_MENU_PREFIX) => {
+                            let app_id = id.trim_start_matches(COPY_URL_MENU_PREFIX).to_string();
+                            let handle = app.clone();
+                            tauri::async_runtime::spawn(async move {
+                                if let Err(e) = copy_url_to_clipboard(&handle, &app_id).await {
+                                    log::error!("{}", e);
+                                }
+                            });
+                        }
+                        _ if id.starts_with(REVEAL_MENU_PREFIX) => {
+                            let app_id = id.trim_start_matches(REVEAL_MENU_PREFIX).to_string();
+                            let handle = app.clone();
+                            tauri::async_runtime::spawn(async move {
+                                if let Err(e) = reveal_app(&handle, &app_id).await {
+                                    log::error!("{}", e);
+                                }
+                            });
+                        }
                         _ => {
                             // App item clicked - emit event to open in browser
                             let _ = app.emit("open-app", id);
@@ -962,6 +5938,15 @@ pub fn run() {
                 })
                 .build(app)?;
 
+            // Fill the tray without waiting for the window; on first launch the database
+            // only exists once the frontend has opened it, and its refresh_tray catches up
+            let apps_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = reload_apps(&apps_handle).await {
+                    log::warn!("{}", e);
+                }
+            });
+
             // Hide the main window on startup if launched with --minimized
             if std::env::args().any(|arg| arg == "--minimized") {
                 if let Some(window) = app.get_webview_window("main") {
@@ -975,21 +5960,148 @@ pub fn run() {
             generate_id,
             get_free_port,
             read_package_json,
+            detect_project,
+            has_devcontainer,
+            benchmark_app,
+            inspect_process,
+            diff_app_env,
+            needs_restart,
+            get_listening_ports,
+            get_service_presets,
+            prepare_service_app,
+            check_service_health,
+            get_uptime,
+            get_app_timeline,
+            set_health_path,
+            get_quit_behavior,
+            set_quit_behavior,
+            get_app_quit_behavior,
+            set_app_quit_behavior,
+            list_sessions,
+            save_session,
+            restore_session,
+            delete_session,
+            list_session_triggers,
+            add_session_trigger,
+            delete_session_trigger,
+            get_app_notify_ready,
+            set_app_notify_ready,
+            get_app_sandbox,
+            set_app_sandbox,
+            get_app_rebuild_stale,
+            set_app_rebuild_stale,
+            get_app_log_sampling,
+            set_app_log_sampling,
+            check_for_update,
+            install_update,
+            read_project_readme,
+            validate_app_config,
+            scan_workspace,
             start_app,
+            start_app_with_profile,
             stop_app,
+            start_static_app,
+            start_apps,
+            stop_apps,
             get_app_status,
             get_running_apps,
+            get_ready_apps,
+            get_building_apps,
+            get_ready_patterns,
+            set_ready_patterns,
             get_app_logs,
+            update_and_restart,
             open_in_browser,
+            copy_app_url,
+            open_in_editor,
+            get_installed_editors,
+            get_editor_preference,
+            set_editor_preference,
+            open_terminal,
+            reveal_in_finder,
+            get_git_info,
+            get_repo_branches,
+            get_disk_usage,
+            preview_watched_paths,
+            clean_project,
+            create_worktree_app,
+            remove_app_worktree,
+            delete_app,
+            get_installed_terminals,
+            get_terminal_preference,
+            set_terminal_preference,
+            get_browser_preference,
+            set_browser_preference,
+            get_settings,
+            set_setting,
+            get_app_lock,
+            get_status_page_url,
+            set_status_page,
+            get_notification_settings,
+            set_notification_settings,
+            test_notification_channel,
+            get_log_forwarding,
+            set_log_forwarding,
+            get_log_forwarding_status,
+            get_trace_export,
+            set_trace_export,
+            get_remote_control,
+            set_remote_control,
+            create_pairing_code,
+            revoke_remote_device,
+            pair_remote_host,
+            remove_remote_host,
+            remote_call,
+            set_app_lock,
+            unlock_app,
             refresh_tray,
             // Proxy commands
             get_lan_ip,
             slugify_name,
+            set_app_subdomain,
+            search_apps,
             add_proxy_route,
             remove_proxy_route,
             get_proxy_routes,
+            set_route_ttl,
+            set_route_inspection,
+            set_route_mocks,
+            set_route_mocks_enabled,
+            set_route_wildcard,
+            set_route_headers,
+            set_route_timeouts,
+            set_route_upstream,
+            set_route_compression,
+            set_route_body_limit,
+            set_route_rate_limit,
+            set_route_enabled,
+            set_route_tls,
+            set_route_allowed_ips,
+            get_recent_requests,
+            replay_request,
+            preview_caddy_import,
+            import_manual_routes,
+            add_tunnel_route,
+            get_tunnel_statuses,
             get_app_url,
             is_proxy_service_running,
+            get_caddy_admin_endpoint,
+            get_proxy_settings,
+            set_proxy_backend,
+            enable_dns_responder,
+            disable_dns_responder,
+            get_dns_responder_domain,
+            run_doctor,
+            run_doctor_fix,
+            get_crash_reports,
+            report_crash,
+            dismiss_crash_report,
+            get_internal_logs,
+            open_debug_window,
+            set_caddy_admin_endpoint,
+            get_last_accessed,
+            get_effective_caddy_config,
+            apply_custom_caddy_config,
             // Proxy service (LaunchDaemon) commands
             get_proxy_service_status,
             install_proxy_service,
@@ -1000,35 +6112,10 @@ pub fn run() {
         .build(tauri::generate_context!())
         .expect("error while building tauri application");
 
-    app.run(|app_handle, event| {
-        if let tauri::RunEvent::Exit = event {
-            log::info!("Application shutting down");
-            let app_state = app_handle.state::<AppState>();
-            let proxy_state = app_handle.state::<ProxyState>();
-            let mdns_registry = app_handle.state::<MdnsRegistry>();
-            let processes = app_state.processes.clone();
-            let routes = proxy_state.routes.clone();
-
-            if let Ok(mut procs) = processes.try_lock() {
-                for (_, process) in procs.drain() {
-                    kill_process_tree(process.child.pid());
-                    let _ = process.child.kill();
-                }
-            }
-
-            let _ = mdns_registry.unregister_all();
-
-            let should_update = if let Ok(mut routes_guard) = routes.try_lock() {
-                routes_guard.clear();
-                true
-            } else {
-                false
-            };
-
-            if should_update {
-                let empty_routes = std::collections::HashMap::new();
-                let _ = tauri::async_runtime::block_on(proxy::update_routes(&empty_routes));
-            }
-        }
+    app.run(|app_handle, event| match event {
+        // Cmd+Q and logout don't go through the tray's Quit, and the process can be gone
+        // before a final Exit, so cleanup starts as soon as exiting is requested
+        tauri::RunEvent::ExitRequested { .. } | tauri::RunEvent::Exit => shutdown(app_handle),
+        _ => {}
     });
 }
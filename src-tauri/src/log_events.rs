@@ -0,0 +1,187 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// How often queued lines are sent to the window.
+pub const FLUSH_INTERVAL: Duration = Duration::from_millis(50);
+// Sent right away once this many lines are queued, without waiting for the timer
+const BATCH_SIZE: usize = 100;
+// Past this many lines in a second, an app with sampling on only shows every
+// `SAMPLE_EVERY`th line until the second is over
+const SAMPLE_THRESHOLD: u32 = 200;
+const SAMPLE_EVERY: u32 = 20;
+const SAMPLE_WINDOW: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct LogLine {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub stream: String,
+    pub message: String,
+}
+
+impl LogLine {
+    fn new(id: &str, stream: &str, message: impl Into<String>) -> Self {
+        Self {
+            id: id.to_string(),
+            stream: stream.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    started: Instant,
+    lines: u32,
+    skipped: u32,
+}
+
+#[derive(Debug, Default)]
+struct Queue {
+    pending: Vec<LogLine>,
+    // Apps whose output is sampled under load
+    sampled: HashSet<String>,
+    windows: HashMap<String, Window>,
+}
+
+impl Queue {
+    // Starts a new window for apps whose last one is over, noting what it skipped
+    fn close_windows(&mut self, now: Instant) {
+        let pending = &mut self.pending;
+        self.windows.retain(|id, window| {
+            if now.duration_since(window.started) < SAMPLE_WINDOW {
+                return true;
+            }
+            if window.skipped > 0 {
+                let note = format!(
+                    "[my-little-apps] {} lines not shown, output was sampled",
+                    window.skipped
+                );
+                pending.push(LogLine::new(id, "stdout", note));
+            }
+            false
+        });
+    }
+}
+
+/// Batches app output for the window: one event per line freezes the webview once an
+/// app prints thousands of lines a second. Lines go out as `app-logs` every
+/// `FLUSH_INTERVAL`, or sooner when a batch fills up. Only what the window shows is
+/// sampled; the log buffer and forwarding still get every line.
+#[derive(Debug, Default)]
+pub struct LogEvents {
+    queue: std::sync::Mutex<Queue>,
+}
+
+impl LogEvents {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_sampled(&self, apps: HashSet<String>) {
+        self.queue.lock().unwrap().sampled = apps;
+    }
+
+    pub fn set_app_sampled(&self, id: &str, sampled: bool) {
+        let mut queue = self.queue.lock().unwrap();
+        if sampled {
+            queue.sampled.insert(id.to_string());
+        } else {
+            queue.sampled.remove(id);
+            queue.windows.remove(id);
+        }
+    }
+
+    /// Queues a line, returning a full batch that should be sent now.
+    pub fn push(&self, id: &str, stream: &str, message: &str) -> Option<Vec<LogLine>> {
+        self.push_at(id, stream, message, Instant::now())
+    }
+
+    fn push_at(&self, id: &str, stream: &str, message: &str, now: Instant) -> Option<Vec<LogLine>> {
+        let mut queue = self.queue.lock().unwrap();
+        if queue.sampled.contains(id) {
+            queue.close_windows(now);
+            let window = queue.windows.entry(id.to_string()).or_insert(Window {
+                started: now,
+                lines: 0,
+                skipped: 0,
+            });
+            window.lines += 1;
+            let over = window.lines.saturating_sub(SAMPLE_THRESHOLD);
+            if over > 0 && !over.is_multiple_of(SAMPLE_EVERY) {
+                window.skipped += 1;
+                return None;
+            }
+        }
+        queue.pending.push(LogLine::new(id, stream, message));
+        (queue.pending.len() >= BATCH_SIZE).then(|| std::mem::take(&mut queue.pending))
+    }
+
+    /// Everything queued, for the flush timer.
+    pub fn take(&self) -> Vec<LogLine> {
+        self.take_at(Instant::now())
+    }
+
+    fn take_at(&self, now: Instant) -> Vec<LogLine> {
+        let mut queue = self.queue.lock().unwrap();
+        queue.close_windows(now);
+        std::mem::take(&mut queue.pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batches() {
+        let events = LogEvents::new();
+        for i in 1..BATCH_SIZE {
+            assert_eq!(events.push("app1", "stdout", &i.to_string()), None);
+        }
+        let batch = events.push("app1", "stderr", "last").unwrap();
+        assert_eq!(batch.len(), BATCH_SIZE);
+        assert_eq!(
+            batch[BATCH_SIZE - 1],
+            LogLine::new("app1", "stderr", "last")
+        );
+        assert!(events.take().is_empty());
+    }
+
+    #[test]
+    fn test_sampling() {
+        let events = LogEvents::new();
+        events.set_app_sampled("noisy", true);
+        let start = Instant::now();
+        let total = SAMPLE_THRESHOLD + 10 * SAMPLE_EVERY;
+        let mut lines = Vec::new();
+        for i in 0..total {
+            for id in ["noisy", "quiet"] {
+                lines.extend(
+                    events
+                        .push_at(id, "stdout", &i.to_string(), start)
+                        .unwrap_or_default(),
+                );
+            }
+        }
+        lines.extend(events.take_at(start));
+        lines.extend(events.take_at(start + SAMPLE_WINDOW));
+        let mut shown = 0;
+        let mut quiet = 0;
+        for line in &lines {
+            match line.id.as_str() {
+                "noisy" => shown += 1,
+                _ => quiet += 1,
+            }
+        }
+        // The skipped count arrives once the second is over
+        assert_eq!(shown, SAMPLE_THRESHOLD + 10 + 1);
+        assert_eq!(quiet, total);
+        assert!(lines
+            .last()
+            .unwrap()
+            .message
+            .contains("190 lines not shown"));
+    }
+}
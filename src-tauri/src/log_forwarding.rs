@@ -0,0 +1,327 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+
+// Lines waiting to be sent; past this, new lines are dropped rather than slowing apps down
+const QUEUE_CAPACITY: usize = 10_000;
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_RETRIES: u32 = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LogSink {
+    // Loki's push API, e.g. http://nas.local:3100/loki/api/v1/push
+    Loki { url: String },
+    // One JSON object per line, POSTed as a batch
+    Ndjson { url: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogForwardingSettings {
+    pub enabled: bool,
+    pub sink: Option<LogSink>,
+    // Sent as `Authorization`, e.g. "Bearer ..." or "Basic ..."
+    pub authorization: Option<String>,
+    pub batch_size: usize,
+    pub flush_ms: u64,
+    // Also mirror output into macOS unified logging, see `unified_log`
+    pub unified_log: bool,
+}
+
+impl Default for LogForwardingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sink: None,
+            authorization: None,
+            batch_size: 500,
+            flush_ms: 2000,
+            unified_log: false,
+        }
+    }
+}
+
+impl LogForwardingSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        let url = match &self.sink {
+            Some(LogSink::Loki { url }) | Some(LogSink::Ndjson { url }) => url.trim(),
+            None if self.enabled => return Err("Pick where to forward logs to".to_string()),
+            None => return Ok(()),
+        };
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(format!("{} isn't an http(s) URL", url));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogRecord {
+    pub app_id: String,
+    pub app: String,
+    pub stream: String,
+    pub message: String,
+    // Unix time in nanoseconds, as Loki wants it
+    pub timestamp_ns: u128,
+}
+
+impl LogRecord {
+    pub fn new(app_id: &str, app: &str, stream: &str, message: &str) -> Self {
+        Self {
+            app_id: app_id.to_string(),
+            app: app.to_string(),
+            stream: stream.to_string(),
+            message: message.to_string(),
+            timestamp_ns: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogForwardingStatus {
+    pub active: bool,
+    // Lines lost to a full queue or a sink that kept failing, since it was turned on
+    pub dropped: u64,
+}
+
+struct Pump {
+    tx: mpsc::Sender<LogRecord>,
+    dropped: Arc<AtomicU64>,
+}
+
+/// Ships app output to an external collector alongside the in-memory buffer. Lines are
+/// batched by size and time, and dropped (and counted) instead of blocking when the sink
+/// can't keep up.
+#[derive(Default)]
+pub struct LogForwarder {
+    pump: std::sync::Mutex<Option<Pump>>,
+}
+
+impl LogForwarder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces any running pump; disabled or incomplete settings just stop it.
+    pub fn configure(&self, settings: &LogForwardingSettings) {
+        let pump = settings
+            .sink
+            .clone()
+            .filter(|_| settings.enabled)
+            .map(|sink| {
+                let (tx, rx) = mpsc::channel(QUEUE_CAPACITY);
+                let dropped = Arc::new(AtomicU64::new(0));
+                tauri::async_runtime::spawn(run_pump(rx, sink, settings.clone(), dropped.clone()));
+                Pump { tx, dropped }
+            });
+        if let Ok(mut current) = self.pump.lock() {
+            // Dropping the old sender lets its pump flush and exit
+            *current = pump;
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.pump.lock().map(|p| p.is_some()).unwrap_or(false)
+    }
+
+    pub fn push(&self, record: LogRecord) {
+        if let Ok(pump) = self.pump.lock() {
+            if let Some(pump) = pump.as_ref() {
+                if pump.tx.try_send(record).is_err() {
+                    pump.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+    }
+
+    pub fn status(&self) -> LogForwardingStatus {
+        let pump = self.pump.lock().ok();
+        let pump = pump.as_ref().and_then(|p| p.as_ref());
+        LogForwardingStatus {
+            active: pump.is_some(),
+            dropped: pump.map(|p| p.dropped.load(Ordering::Relaxed)).unwrap_or(0),
+        }
+    }
+}
+
+async fn run_pump(
+    mut rx: mpsc::Receiver<LogRecord>,
+    sink: LogSink,
+    settings: LogForwardingSettings,
+    dropped: Arc<AtomicU64>,
+) {
+    let client = match reqwest::Client::builder().timeout(SEND_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            log::error!("Log forwarding disabled: {}", e);
+            return;
+        }
+    };
+    let batch_size = settings.batch_size.max(1);
+    let flush_every = Duration::from_millis(settings.flush_ms.max(100));
+
+    loop {
+        // Wait for the first line, then give the batch until the flush interval to fill
+        let Some(first) = rx.recv().await else {
+            break;
+        };
+        let mut batch = vec![first];
+        let deadline = tokio::time::Instant::now() + flush_every;
+        while batch.len() < batch_size {
+            match tokio::time::timeout_at(deadline, rx.recv()).await {
+                Ok(Some(record)) => batch.push(record),
+                Ok(None) | Err(_) => break,
+            }
+        }
+
+        if let Err(e) = send_with_retry(&client, &sink, &settings, &batch).await {
+            dropped.fetch_add(batch.len() as u64, Ordering::Relaxed);
+            log::warn!("Dropped {} log lines: {}", batch.len(), e);
+        }
+    }
+}
+
+// Retries with backoff; lines arriving meanwhile queue up to QUEUE_CAPACITY
+async fn send_with_retry(
+    client: &reqwest::Client,
+    sink: &LogSink,
+    settings: &LogForwardingSettings,
+    batch: &[LogRecord],
+) -> Result<(), String> {
+    let (url, content_type, body) = match sink {
+        LogSink::Loki { url } => (url, "application/json", loki_payload(batch)),
+        LogSink::Ndjson { url } => (url, "application/x-ndjson", ndjson_payload(batch)),
+    };
+    let mut attempt = 0;
+    loop {
+        let mut request = client
+            .post(url.trim())
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .body(body.clone());
+        if let Some(authorization) = settings.authorization.as_deref().filter(|a| !a.is_empty()) {
+            request = request.header(reqwest::header::AUTHORIZATION, authorization);
+        }
+        let error = match request.send().await {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            // The collector rejected the batch itself, so sending it again won't help
+            Ok(response) if response.status().is_client_error() => {
+                return Err(format!("{} answered {}", url, response.status()));
+            }
+            Ok(response) => format!("{} answered {}", url, response.status()),
+            Err(e) => e.to_string(),
+        };
+        attempt += 1;
+        if attempt > MAX_RETRIES {
+            return Err(error);
+        }
+        tokio::time::sleep(Duration::from_millis(500 * 2u64.pow(attempt))).await;
+    }
+}
+
+// One Loki stream per app and output stream
+fn loki_payload(batch: &[LogRecord]) -> String {
+    let mut streams: BTreeMap<(&str, &str, &str), Vec<[String; 2]>> = BTreeMap::new();
+    for record in batch {
+        streams
+            .entry((&record.app_id, &record.app, &record.stream))
+            .or_default()
+            .push([record.timestamp_ns.to_string(), record.message.clone()]);
+    }
+    let streams: Vec<_> = streams
+        .into_iter()
+        .map(|((app_id, app, stream), values)| {
+            serde_json::json!({
+                "stream": {
+                    "job": "my-little-apps",
+                    "app": app,
+                    "app_id": app_id,
+                    "stream": stream,
+                },
+                "values": values,
+            })
+        })
+        .collect();
+    serde_json::json!({ "streams": streams }).to_string()
+}
+
+fn ndjson_payload(batch: &[LogRecord]) -> String {
+    batch
+        .iter()
+        .filter_map(|record| serde_json::to_string(record).ok())
+        .map(|line| line + "\n")
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(app: &str, stream: &str, message: &str, timestamp_ns: u128) -> LogRecord {
+        LogRecord {
+            app_id: format!("{}-id", app),
+            app: app.to_string(),
+            stream: stream.to_string(),
+            message: message.to_string(),
+            timestamp_ns,
+        }
+    }
+
+    #[test]
+    fn test_loki_payload() {
+        let batch = vec![
+            record("shop", "stdout", "listening", 1),
+            record("api", "stderr", "oops", 2),
+            record("shop", "stdout", "GET /", 3),
+        ];
+        let payload: serde_json::Value = serde_json::from_str(&loki_payload(&batch)).unwrap();
+        let streams = payload["streams"].as_array().unwrap();
+        assert_eq!(streams.len(), 2);
+        let shop = streams
+            .iter()
+            .find(|s| s["stream"]["app"] == "shop")
+            .unwrap();
+        assert_eq!(shop["stream"]["stream"], "stdout");
+        assert_eq!(
+            shop["values"],
+            serde_json::json!([["1", "listening"], ["3", "GET /"]])
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut settings = LogForwardingSettings {
+            enabled: true,
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+        settings.sink = Some(LogSink::Loki {
+            url: "nas.local:3100".to_string(),
+        });
+        assert!(settings.validate().is_err());
+        settings.sink = Some(LogSink::Loki {
+            url: "http://nas.local:3100/loki/api/v1/push".to_string(),
+        });
+        assert!(settings.validate().is_ok());
+    }
+
+    #[test]
+    fn test_ndjson_payload() {
+        let payload = ndjson_payload(&[
+            record("shop", "stdout", "a", 1),
+            record("shop", "stderr", "b", 2),
+        ]);
+        let lines: Vec<&str> = payload.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(payload.ends_with('\n'));
+        let first: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first["app"], "shop");
+        assert_eq!(first["message"], "a");
+    }
+}
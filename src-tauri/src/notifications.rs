@@ -0,0 +1,273 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_NTFY_SERVER: &str = "https://ntfy.sh";
+// Unclicked native notifications are taken down after this, which ends the wait for a click
+#[cfg(target_os = "macos")]
+const CLICK_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Where a channel delivers to.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChannelTarget {
+    // Self-hosted servers work too; an empty server means ntfy.sh
+    Ntfy {
+        #[serde(default)]
+        server: String,
+        topic: String,
+        // Access token for protected topics
+        #[serde(default)]
+        token: Option<String>,
+    },
+    // Receives the notification as JSON
+    Webhook {
+        url: String,
+    },
+}
+
+/// One place notifications go, with the events it wants.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NotificationChannel {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub target: ChannelTarget,
+    #[serde(default)]
+    pub crashes: bool,
+    #[serde(default)]
+    pub long_tasks: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub channels: Vec<NotificationChannel>,
+    // Builds and update steps running at least this long report when they finish
+    pub long_task_secs: u64,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            channels: Vec::new(),
+            long_task_secs: 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    Crash,
+    TaskDone,
+    TaskFailed,
+    // An app that asked to be announced is ready
+    Ready,
+    Test,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    pub kind: NotificationKind,
+    pub title: String,
+    pub message: String,
+    // Opened when the notification is clicked, where the channel supports it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl Notification {
+    pub fn new(
+        kind: NotificationKind,
+        title: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            kind,
+            title: title.into(),
+            message: message.into(),
+            url: None,
+        }
+    }
+
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    // ntfy priority (1-5) and emoji tags
+    fn ntfy_style(&self) -> (&'static str, &'static str) {
+        match self.kind {
+            NotificationKind::Crash => ("4", "rotating_light"),
+            NotificationKind::TaskFailed => ("4", "x"),
+            NotificationKind::TaskDone => ("3", "white_check_mark"),
+            NotificationKind::Ready => ("3", "rocket"),
+            NotificationKind::Test => ("3", "bell"),
+        }
+    }
+}
+
+/// Shows `notification` in Notification Center and waits until it's clicked, dismissed
+/// or `CLICK_TIMEOUT` passes; true when it was clicked.
+#[cfg(target_os = "macos")]
+pub fn show_native(bundle_id: &str, notification: &Notification) -> bool {
+    // Only the first call can set it, which is the one that matters
+    let _ = mac_notification_sys::set_application(bundle_id);
+    // The wait can't be cancelled, but taking the notification down counts as a dismissal
+    let (answered, answer) = std::sync::mpsc::channel::<()>();
+    let (title, message) = (notification.title.clone(), notification.message.clone());
+    std::thread::spawn(move || {
+        if answer.recv_timeout(CLICK_TIMEOUT) == Err(std::sync::mpsc::RecvTimeoutError::Timeout) {
+            take_down(&title, &message);
+        }
+    });
+    let response = mac_notification_sys::Notification::new()
+        .title(&notification.title)
+        .message(&notification.message)
+        .wait_for_click(true)
+        .send();
+    drop(answered);
+    match response {
+        Ok(response) => response == mac_notification_sys::NotificationResponse::Click,
+        Err(e) => {
+            log::warn!("Failed to show notification: {}", e);
+            false
+        }
+    }
+}
+
+// Removes delivered notifications with this text, the crate keeps their ids to itself
+#[cfg(target_os = "macos")]
+#[allow(deprecated)]
+fn take_down(title: &str, message: &str) {
+    use objc2_foundation::NSUserNotificationCenter;
+    let center = NSUserNotificationCenter::defaultUserNotificationCenter();
+    for delivered in center.deliveredNotifications().iter() {
+        let matches = delivered.title().is_some_and(|t| t.to_string() == title)
+            && delivered
+                .informativeText()
+                .is_some_and(|t| t.to_string() == message);
+        if matches {
+            center.removeDeliveredNotification(&delivered);
+        }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn show_native(_bundle_id: &str, _notification: &Notification) -> bool {
+    false
+}
+
+impl NotificationChannel {
+    pub fn wants(&self, kind: NotificationKind) -> bool {
+        match kind {
+            NotificationKind::Crash => self.crashes,
+            NotificationKind::TaskDone | NotificationKind::TaskFailed => self.long_tasks,
+            // Apps opt into these one by one, so every channel gets them
+            NotificationKind::Ready | NotificationKind::Test => true,
+        }
+    }
+
+    pub async fn send(&self, notification: &Notification) -> Result<(), String> {
+        let client = reqwest::Client::builder()
+            .timeout(SEND_TIMEOUT)
+            .build()
+            .map_err(|e| e.to_string())?;
+        let request = match &self.target {
+            ChannelTarget::Ntfy {
+                server,
+                topic,
+                token,
+            } => {
+                let (priority, tags) = notification.ntfy_style();
+                let mut request = client
+                    .post(ntfy_url(server, topic)?)
+                    .header("Title", &notification.title)
+                    .header("Priority", priority)
+                    .header("Tags", tags)
+                    .body(notification.message.clone());
+                if let Some(token) = token.as_deref().filter(|t| !t.is_empty()) {
+                    request = request.bearer_auth(token);
+                }
+                if let Some(url) = &notification.url {
+                    request = request.header("Click", url);
+                }
+                request
+            }
+            ChannelTarget::Webhook { url } => client.post(url.trim()).json(notification),
+        };
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("{}: {}", self.name, e))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "{}: server answered {}",
+                self.name,
+                response.status()
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn ntfy_url(server: &str, topic: &str) -> Result<String, String> {
+    let topic = topic.trim().trim_matches('/');
+    if topic.is_empty() {
+        return Err("An ntfy channel needs a topic".to_string());
+    }
+    let server = match server.trim().trim_end_matches('/') {
+        "" => DEFAULT_NTFY_SERVER,
+        server => server,
+    };
+    Ok(format!("{}/{}", server, topic))
+}
+
+/// Sends to every channel that wants this kind of notification, logging failures.
+pub async fn notify(channels: &[NotificationChannel], notification: Notification) {
+    for channel in channels.iter().filter(|c| c.wants(notification.kind)) {
+        if let Err(e) = channel.send(&notification).await {
+            log::warn!("Notification not sent: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ntfy_url() {
+        assert_eq!(ntfy_url("", "my-apps").unwrap(), "https://ntfy.sh/my-apps");
+        assert_eq!(
+            ntfy_url("https://ntfy.home.lan/", "/my-apps").unwrap(),
+            "https://ntfy.home.lan/my-apps"
+        );
+        assert!(ntfy_url("", " ").is_err());
+    }
+
+    #[test]
+    fn test_channel_events() {
+        let channel: NotificationChannel = serde_json::from_str(
+            r#"{"name":"phone","target":{"type":"ntfy","topic":"my-apps"},"crashes":true}"#,
+        )
+        .unwrap();
+        assert!(channel.wants(NotificationKind::Crash));
+        assert!(!channel.wants(NotificationKind::TaskDone));
+        assert!(channel.wants(NotificationKind::Test));
+        assert!(channel.wants(NotificationKind::Ready));
+    }
+
+    #[test]
+    fn test_notification_url() {
+        let plain = Notification::new(NotificationKind::Crash, "shop", "exited");
+        assert!(serde_json::to_value(&plain).unwrap().get("url").is_none());
+        let ready = Notification::new(NotificationKind::Ready, "shop is ready", "")
+            .with_url("http://shop.local");
+        let json = serde_json::to_value(&ready).unwrap();
+        assert_eq!(json["url"], "http://shop.local");
+        assert_eq!(json["kind"], "ready");
+    }
+}
@@ -0,0 +1,345 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+
+use crate::inspector::CapturedRequest;
+
+const QUEUE_CAPACITY: usize = 2048;
+const BATCH_SIZE: usize = 256;
+const FLUSH_EVERY: Duration = Duration::from_secs(1);
+const SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Where spans for inspected requests go. Any OTLP/HTTP collector works, including
+/// Jaeger's built-in one on port 4318.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TraceExportSettings {
+    pub enabled: bool,
+    pub endpoint: String,
+    pub service_name: String,
+}
+
+impl Default for TraceExportSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: "http://localhost:4318".to_string(),
+            service_name: "my-little-apps".to_string(),
+        }
+    }
+}
+
+impl TraceExportSettings {
+    /// The collector's traces URL; a bare `http://host:4318` gets the standard path.
+    pub fn traces_url(&self) -> Result<String, String> {
+        let endpoint = self.endpoint.trim().trim_end_matches('/');
+        if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+            return Err(format!("{} isn't an http(s) URL", endpoint));
+        }
+        if endpoint.ends_with("/v1/traces") {
+            return Ok(endpoint.to_string());
+        }
+        Ok(format!("{}/v1/traces", endpoint))
+    }
+}
+
+/// W3C trace context for one relayed request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    pub trace_id: String,
+    pub span_id: String,
+    pub parent_span_id: Option<String>,
+}
+
+impl TraceContext {
+    /// Joins the caller's trace when the request carries a valid `traceparent`, so
+    /// spans from the browser or another service line up; otherwise starts a new one.
+    pub fn continue_from(traceparent: Option<&str>) -> Self {
+        let parent = traceparent.and_then(parse_traceparent);
+        let span_id = uuid::Uuid::new_v4().simple().to_string()[..16].to_string();
+        match parent {
+            Some((trace_id, parent_span_id)) => Self {
+                trace_id,
+                span_id,
+                parent_span_id: Some(parent_span_id),
+            },
+            None => Self {
+                trace_id: uuid::Uuid::new_v4().simple().to_string(),
+                span_id,
+                parent_span_id: None,
+            },
+        }
+    }
+
+    /// Header for the upstream request, making the app's own spans children of ours.
+    pub fn traceparent(&self) -> String {
+        format!("00-{}-{}-01", self.trace_id, self.span_id)
+    }
+}
+
+fn parse_traceparent(header: &str) -> Option<(String, String)> {
+    let mut parts = header.trim().split('-');
+    let (version, trace_id, span_id, _flags) =
+        (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+    let is_hex = |s: &str, len: usize| {
+        s.len() == len && s.bytes().all(|b| b.is_ascii_hexdigit()) && s.bytes().any(|b| b != b'0')
+    };
+    if version == "ff" || !is_hex(trace_id, 32) || !is_hex(span_id, 16) {
+        return None;
+    }
+    Some((trace_id.to_ascii_lowercase(), span_id.to_ascii_lowercase()))
+}
+
+pub fn unix_nanos() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default()
+}
+
+/// A finished request through the inspection relay.
+#[derive(Debug, Clone)]
+pub struct RequestSpan {
+    context: TraceContext,
+    app_id: String,
+    method: String,
+    host: String,
+    path: String,
+    status: Option<u16>,
+    error: Option<String>,
+    start_ns: u128,
+    end_ns: u128,
+}
+
+impl RequestSpan {
+    pub fn new(context: TraceContext, captured: &CapturedRequest, start_ns: u128) -> Self {
+        let host = captured
+            .request_headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("host"))
+            .map(|(_, value)| value.split(':').next().unwrap_or_default().to_string())
+            .unwrap_or_default();
+        let path = captured.path.split('?').next().unwrap_or("/").to_string();
+        Self {
+            context,
+            app_id: captured.app_id.clone(),
+            method: captured.method.clone(),
+            host,
+            path,
+            status: captured.status,
+            error: captured.error.clone(),
+            start_ns,
+            end_ns: unix_nanos(),
+        }
+    }
+
+    fn to_otlp(&self) -> Value {
+        let mut attributes = vec![
+            string_attribute("http.request.method", &self.method),
+            string_attribute("server.address", &self.host),
+            string_attribute("url.path", &self.path),
+            string_attribute("my_little_apps.app_id", &self.app_id),
+        ];
+        if let Some(status) = self.status {
+            attributes.push(json!({
+                "key": "http.response.status_code",
+                "value": { "intValue": status.to_string() },
+            }));
+        }
+        // Server spans only count 5xx and relay failures as errors
+        let status = match (&self.error, self.status) {
+            (Some(error), _) => json!({ "code": 2, "message": error }),
+            (None, Some(status)) if status >= 500 => json!({ "code": 2 }),
+            _ => json!({ "code": 0 }),
+        };
+        let mut span = json!({
+            "traceId": self.context.trace_id,
+            "spanId": self.context.span_id,
+            "name": format!("{} {}", self.method, self.host),
+            // SPAN_KIND_SERVER
+            "kind": 2,
+            "startTimeUnixNano": self.start_ns.to_string(),
+            "endTimeUnixNano": self.end_ns.to_string(),
+            "attributes": attributes,
+            "status": status,
+        });
+        if let Some(parent) = &self.context.parent_span_id {
+            span["parentSpanId"] = json!(parent);
+        }
+        span
+    }
+}
+
+fn string_attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+fn traces_payload(service_name: &str, spans: &[RequestSpan]) -> String {
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [string_attribute("service.name", service_name)],
+            },
+            "scopeSpans": [{
+                "scope": { "name": "my-little-apps.inspector" },
+                "spans": spans.iter().map(RequestSpan::to_otlp).collect::<Vec<_>>(),
+            }],
+        }],
+    })
+    .to_string()
+}
+
+/// Batches spans in the background and posts them as OTLP/HTTP JSON. Cheap to clone;
+/// every relay holds one. Spans are dropped when the collector can't keep up.
+#[derive(Clone, Default)]
+pub struct TraceExporter {
+    tx: Arc<std::sync::Mutex<Option<mpsc::Sender<RequestSpan>>>>,
+}
+
+impl TraceExporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn configure(&self, settings: &TraceExportSettings) -> Result<(), String> {
+        let tx = if settings.enabled {
+            let url = settings.traces_url()?;
+            let (tx, rx) = mpsc::channel(QUEUE_CAPACITY);
+            tauri::async_runtime::spawn(run_exporter(rx, url, settings.service_name.clone()));
+            Some(tx)
+        } else {
+            None
+        };
+        if let Ok(mut current) = self.tx.lock() {
+            *current = tx;
+        }
+        Ok(())
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.tx.lock().map(|tx| tx.is_some()).unwrap_or(false)
+    }
+
+    pub fn export(&self, span: RequestSpan) {
+        if let Ok(tx) = self.tx.lock() {
+            if let Some(tx) = tx.as_ref() {
+                let _ = tx.try_send(span);
+            }
+        }
+    }
+}
+
+async fn run_exporter(mut rx: mpsc::Receiver<RequestSpan>, url: String, service_name: String) {
+    let client = match reqwest::Client::builder().timeout(SEND_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            log::error!("Trace export disabled: {}", e);
+            return;
+        }
+    };
+
+    while let Some(first) = rx.recv().await {
+        let mut batch = vec![first];
+        let deadline = tokio::time::Instant::now() + FLUSH_EVERY;
+        while batch.len() < BATCH_SIZE {
+            match tokio::time::timeout_at(deadline, rx.recv()).await {
+                Ok(Some(span)) => batch.push(span),
+                Ok(None) | Err(_) => break,
+            }
+        }
+
+        let sent = client
+            .post(&url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(traces_payload(&service_name, &batch))
+            .send()
+            .await;
+        match sent {
+            Ok(response) if response.status().is_success() => {}
+            Ok(response) => log::warn!(
+                "Dropped {} spans: {} answered {}",
+                batch.len(),
+                url,
+                response.status()
+            ),
+            Err(e) => log::warn!("Dropped {} spans: {}", batch.len(), e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_context() {
+        let parent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let context = TraceContext::continue_from(Some(parent));
+        assert_eq!(context.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(context.parent_span_id.as_deref(), Some("00f067aa0ba902b7"));
+        assert_eq!(context.span_id.len(), 16);
+        assert!(context
+            .traceparent()
+            .starts_with("00-4bf92f3577b34da6a3ce929d0e0e4736-"));
+
+        let fresh = TraceContext::continue_from(Some("00-0000-garbage"));
+        assert_eq!(fresh.trace_id.len(), 32);
+        assert_eq!(fresh.parent_span_id, None);
+        assert!(parse_traceparent(&fresh.traceparent()).is_some());
+    }
+
+    #[test]
+    fn test_traces_url() {
+        let mut settings = TraceExportSettings::default();
+        assert_eq!(
+            settings.traces_url().unwrap(),
+            "http://localhost:4318/v1/traces"
+        );
+        settings.endpoint = "http://jaeger.lan:4318/v1/traces/".to_string();
+        assert_eq!(
+            settings.traces_url().unwrap(),
+            "http://jaeger.lan:4318/v1/traces"
+        );
+        settings.endpoint = "localhost:4318".to_string();
+        assert!(settings.traces_url().is_err());
+    }
+
+    #[test]
+    fn test_span_payload() {
+        let captured = CapturedRequest {
+            id: "1".to_string(),
+            app_id: "shop-id".to_string(),
+            method: "POST".to_string(),
+            path: "/cart?item=2".to_string(),
+            status: Some(503),
+            duration_ms: 12,
+            started_at: 0,
+            request_headers: vec![("host".to_string(), "shop.localhost:443".to_string())],
+            request_body: None,
+            response_headers: Vec::new(),
+            response_body: None,
+            error: None,
+        };
+        let span = RequestSpan::new(TraceContext::continue_from(None), &captured, 1);
+        let payload: Value = serde_json::from_str(&traces_payload("stack", &[span])).unwrap();
+        let resource = &payload["resourceSpans"][0];
+        assert_eq!(
+            resource["resource"]["attributes"][0]["value"]["stringValue"],
+            "stack"
+        );
+        let span = &resource["scopeSpans"][0]["spans"][0];
+        assert_eq!(span["name"], "POST shop.localhost");
+        assert_eq!(span["status"]["code"], 2);
+        assert_eq!(span["startTimeUnixNano"], "1");
+        assert!(span.get("parentSpanId").is_none());
+        let path = span["attributes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|a| a["key"] == "url.path")
+            .unwrap();
+        assert_eq!(path["value"]["stringValue"], "/cart");
+    }
+}
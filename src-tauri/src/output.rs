@@ -0,0 +1,204 @@
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    // Only ASCII so far
+    #[default]
+    Unknown,
+    Utf8,
+    Latin1,
+}
+
+/// Turns an app's output bytes into text. Invalid UTF-8 is replaced instead of losing the
+/// whole chunk, and a stream whose first non-ASCII bytes aren't UTF-8 is taken to be
+/// Latin-1 from then on, as printed by older JVMs and Windows-built tools.
+#[derive(Debug, Default)]
+pub struct Decoder {
+    // Start of a character cut off at the end of the last chunk
+    pending: Vec<u8>,
+    encoding: Encoding,
+}
+
+impl Decoder {
+    pub fn decode(&mut self, chunk: &[u8]) -> String {
+        let mut bytes = std::mem::take(&mut self.pending);
+        bytes.extend_from_slice(chunk);
+        if self.encoding == Encoding::Latin1 {
+            return latin1(&bytes);
+        }
+
+        let end = complete_len(&bytes);
+        let rest = bytes.split_off(end);
+        let text = match String::from_utf8(bytes) {
+            Ok(text) => {
+                if !text.is_ascii() {
+                    self.encoding = Encoding::Utf8;
+                }
+                text
+            }
+            Err(e) => {
+                let mut bytes = e.into_bytes();
+                if self.encoding == Encoding::Unknown && !has_utf8(&bytes) {
+                    self.encoding = Encoding::Latin1;
+                    bytes.extend_from_slice(&rest);
+                    return latin1(&bytes);
+                }
+                self.encoding = Encoding::Utf8;
+                String::from_utf8_lossy(&bytes).into_owned()
+            }
+        };
+        self.pending = rest;
+        text
+    }
+
+    /// Whatever was held back when the stream ends.
+    pub fn finish(&mut self) -> String {
+        let bytes = std::mem::take(&mut self.pending);
+        if self.encoding == Encoding::Latin1 {
+            latin1(&bytes)
+        } else {
+            String::from_utf8_lossy(&bytes).into_owned()
+        }
+    }
+}
+
+/// Longest line kept whole; anything longer is cut into lines of this size, so a
+/// process that never prints a newline can't grow the buffer without bound.
+pub const MAX_LINE_LEN: usize = 64 * 1024;
+
+/// Assembles a stream's chunks into lines. Output arrives in pieces of any size, so a
+/// line is held until its newline comes in. A lone `\r`, as progress bars print, ends a
+/// line too.
+#[derive(Debug, Default)]
+pub struct Lines {
+    decoder: Decoder,
+    partial: String,
+    // The last chunk ended in `\r`, so a `\n` starting this one belongs to it
+    after_cr: bool,
+}
+
+impl Lines {
+    /// The lines `chunk` completes, without their line endings.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        let text = self.decoder.decode(chunk);
+        let mut lines = Vec::new();
+        let mut rest = text.as_str();
+        if self.after_cr {
+            rest = rest.strip_prefix('\n').unwrap_or(rest);
+        }
+        while let Some(end) = rest.find(['\n', '\r']) {
+            self.partial.push_str(&rest[..end]);
+            lines.push(std::mem::take(&mut self.partial));
+            let newline = if rest[end..].starts_with("\r\n") {
+                2
+            } else {
+                1
+            };
+            rest = &rest[end + newline..];
+        }
+        if !text.is_empty() {
+            self.after_cr = text.ends_with('\r');
+        }
+        self.partial.push_str(rest);
+
+        while self.partial.len() >= MAX_LINE_LEN {
+            let mut cut = MAX_LINE_LEN;
+            while !self.partial.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            let rest = self.partial.split_off(cut);
+            lines.push(std::mem::replace(&mut self.partial, rest));
+        }
+        lines
+    }
+
+    /// The unfinished last line, once the stream has ended.
+    pub fn finish(&mut self) -> Option<String> {
+        self.partial.push_str(&self.decoder.finish());
+        let line = std::mem::take(&mut self.partial);
+        (!line.is_empty()).then_some(line)
+    }
+}
+
+// Length of `bytes` without a multibyte character still missing its last bytes
+fn complete_len(bytes: &[u8]) -> usize {
+    let len = bytes.len();
+    // A character is at most 4 bytes, so only the last 3 can start an unfinished one
+    for start in (len.saturating_sub(3)..len).rev() {
+        let byte = bytes[start];
+        if byte & 0xC0 == 0x80 {
+            continue;
+        }
+        let width = match byte {
+            0xF0.. => 4,
+            0xE0.. => 3,
+            0xC0.. => 2,
+            _ => 1,
+        };
+        return if len - start < width { start } else { len };
+    }
+    len
+}
+
+// Whether some of the non-ASCII bytes form UTF-8 characters
+fn has_utf8(bytes: &[u8]) -> bool {
+    bytes.utf8_chunks().any(|chunk| !chunk.valid().is_ascii())
+}
+
+fn latin1(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| char::from(b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_character() {
+        let mut decoder = Decoder::default();
+        let text = "größe: 3 €".as_bytes();
+        // Cut inside the euro sign
+        let (head, tail) = text.split_at(text.len() - 2);
+        assert_eq!(decoder.decode(head), "größe: 3 ");
+        assert_eq!(decoder.decode(tail), "€");
+        assert_eq!(decoder.finish(), "");
+    }
+
+    #[test]
+    fn test_lines() {
+        let mut lines = Lines::default();
+        assert!(lines.push(b"GET /api/products?page=").is_empty());
+        assert_eq!(
+            lines.push(b"2 200\nGET / 200\r"),
+            ["GET /api/products?page=2 200", "GET / 200"]
+        );
+        // The \n of a \r\n split across chunks doesn't make an empty line
+        assert_eq!(
+            lines.push(b"\n\nbuilding 10%\rbuilding 20%"),
+            ["", "building 10%"]
+        );
+        assert_eq!(lines.finish().as_deref(), Some("building 20%"));
+        assert_eq!(lines.finish(), None);
+    }
+
+    #[test]
+    fn test_max_line_len() {
+        let mut lines = Lines::default();
+        let long = "é".repeat(MAX_LINE_LEN);
+        let pieces = lines.push(long.as_bytes());
+        assert_eq!(pieces.len(), 2);
+        assert!(pieces.iter().all(|line| line.len() == MAX_LINE_LEN));
+        assert_eq!(lines.finish(), None);
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let mut decoder = Decoder::default();
+        assert_eq!(decoder.decode(b"caf\xe9 ok\n"), "café ok\n");
+        // Stays Latin-1 once detected
+        assert_eq!(decoder.decode(b"\xfc"), "ü");
+
+        let mut decoder = Decoder::default();
+        // Real UTF-8 next to the bad byte means it's just a bad byte
+        assert_eq!(decoder.decode(b"\xc3\xbc \xff\n"), "ü \u{FFFD}\n");
+        assert_eq!(decoder.decode(b"\xfc\n"), "\u{FFFD}\n");
+    }
+}
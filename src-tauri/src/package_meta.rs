@@ -0,0 +1,215 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Runtimes whose `engines` ranges are checked against the one the app would run on.
+pub const RUNTIMES: [&str; 2] = ["node", "bun"];
+
+/// What an app's package.json says about it, cached on the app at each start.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackageMeta {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    // Runtime name to version range, e.g. "node" to ">=20"
+    pub engines: BTreeMap<String, String>,
+}
+
+pub fn read(dir: &Path) -> Option<PackageMeta> {
+    let content = std::fs::read_to_string(dir.join("package.json")).ok()?;
+    let package: serde_json::Value = serde_json::from_str(&content).ok()?;
+    let text = |key: &str| package[key].as_str().map(String::from);
+    let engines = package["engines"]
+        .as_object()
+        .map(|engines| {
+            engines
+                .iter()
+                .filter_map(|(name, range)| Some((name.clone(), range.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(PackageMeta {
+        name: text("name"),
+        version: text("version"),
+        engines,
+    })
+}
+
+/// Why `runtime` at `found`, as printed by `<runtime> --version`, doesn't meet the
+/// `engines` range. None when it does, or when the range is beyond what's understood.
+pub fn conflict(runtime: &str, range: &str, found: &str) -> Option<String> {
+    let found = found.trim().trim_start_matches('v');
+    match satisfies(range, parse_version(found)?)? {
+        true => None,
+        false => Some(format!(
+            "engines.{} wants {}, {} is {}",
+            runtime, range, runtime, found
+        )),
+    }
+}
+
+type Version = (u64, u64, u64);
+
+// "18.19.0", ignoring pre-release and build suffixes
+fn parse_version(text: &str) -> Option<Version> {
+    let core = text.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+    let major = parts.next()??;
+    let minor = parts.next().unwrap_or(Some(0))?;
+    let patch = parts.next().unwrap_or(Some(0))?;
+    Some((major, minor, patch))
+}
+
+// A version missing its lower parts, like the "18" of "^18" or "18.x"
+fn parse_partial(text: &str) -> Option<Vec<u64>> {
+    let text = text.trim_start_matches(['v', '=']);
+    let mut parts = Vec::new();
+    for part in text.split(['-', '+']).next()?.split('.') {
+        if matches!(part, "x" | "X" | "*") {
+            break;
+        }
+        parts.push(part.parse().ok()?);
+    }
+    (parts.len() <= 3).then_some(parts)
+}
+
+fn filled(parts: &[u64]) -> Version {
+    let at = |i: usize| parts.get(i).copied().unwrap_or(0);
+    (at(0), at(1), at(2))
+}
+
+// The first version past everything `parts` covers: 18 -> 19.0.0, 18.2 -> 18.3.0
+fn next_after(parts: &[u64]) -> Option<Version> {
+    match parts {
+        [] => None,
+        [major] => Some((major + 1, 0, 0)),
+        [major, minor] => Some((*major, minor + 1, 0)),
+        [major, minor, patch, ..] => Some((*major, *minor, patch + 1)),
+    }
+}
+
+/// Whether `version` is in an npm-style `range`: `||` alternatives of comparators like
+/// `>=18`, `^20.1`, `~1.2`, `18.x` or `1.2 - 2.0`. None for anything else.
+fn satisfies(range: &str, version: Version) -> Option<bool> {
+    let mut any = false;
+    for alternative in range.split("||") {
+        let words: Vec<&str> = alternative.split_whitespace().collect();
+        let mut bounds = Vec::new();
+        if let [from, "-", to] = words[..] {
+            bounds.push((">=", filled(&parse_partial(from)?)));
+            let to = parse_partial(to)?;
+            match next_after(&to) {
+                Some(end) if to.len() < 3 => bounds.push(("<", end)),
+                _ => bounds.push(("<=", filled(&to))),
+            }
+        } else {
+            for word in words {
+                comparator(word, &mut bounds)?;
+            }
+        }
+        any |= bounds.iter().all(|(op, bound)| match *op {
+            ">=" => version >= *bound,
+            ">" => version > *bound,
+            "<" => version < *bound,
+            _ => version <= *bound,
+        });
+    }
+    Some(any)
+}
+
+// Adds the bounds of one comparator, e.g. `^18.2` to >=18.2.0 <19.0.0
+fn comparator(word: &str, bounds: &mut Vec<(&'static str, Version)>) -> Option<()> {
+    let op_len = word
+        .find(|c: char| c.is_ascii_digit() || matches!(c, 'x' | 'X' | '*'))
+        .unwrap_or(word.len());
+    let (op, rest) = word.split_at(op_len);
+    let parts = parse_partial(rest)?;
+    let start = filled(&parts);
+    match op {
+        "" | "=" | "v" => {
+            if let Some(end) = next_after(&parts) {
+                bounds.push((">=", start));
+                if parts.len() < 3 {
+                    bounds.push(("<", end));
+                } else {
+                    bounds.push(("<=", start));
+                }
+            }
+        }
+        "^" => {
+            let end = match parts.as_slice() {
+                [0, 0, _] | [0, 0] => next_after(&parts)?,
+                [0, minor, ..] => (0, minor + 1, 0),
+                _ => (start.0 + 1, 0, 0),
+            };
+            bounds.extend([(">=", start), ("<", end)]);
+        }
+        "~" => {
+            let end = next_after(&parts[..parts.len().min(2)])?;
+            bounds.extend([(">=", start), ("<", end)]);
+        }
+        ">=" => bounds.push((">=", start)),
+        "<" => bounds.push(("<", start)),
+        // `>18` means past all of 18, `<=18` up to the end of it
+        ">" if parts.len() < 3 => bounds.push((">=", next_after(&parts)?)),
+        ">" => bounds.push((">", start)),
+        "<=" if parts.len() < 3 => bounds.push(("<", next_after(&parts)?)),
+        "<=" => bounds.push(("<=", start)),
+        _ => return None,
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_satisfies() {
+        let ok = |range: &str, version: &str| satisfies(range, parse_version(version).unwrap());
+        assert_eq!(ok(">=18", "18.0.0"), Some(true));
+        assert_eq!(ok(">=18.17.0", "18.16.1"), Some(false));
+        assert_eq!(ok("^20.1", "20.9.0"), Some(true));
+        assert_eq!(ok("^20.1", "21.0.0"), Some(false));
+        assert_eq!(ok("^0.3.1", "0.4.0"), Some(false));
+        assert_eq!(ok("~18.2", "18.2.9"), Some(true));
+        assert_eq!(ok("~18.2", "18.3.0"), Some(false));
+        assert_eq!(ok("18.x", "18.20.4"), Some(true));
+        assert_eq!(ok("20", "21.0.0"), Some(false));
+        assert_eq!(ok(">18", "18.9.0"), Some(false));
+        assert_eq!(ok("<=18", "18.9.0"), Some(true));
+        assert_eq!(ok(">=16 <19", "20.0.0"), Some(false));
+        assert_eq!(ok("^16 || ^18 || >=20", "20.11.1"), Some(true));
+        assert_eq!(ok("16 - 18", "18.5.0"), Some(true));
+        assert_eq!(ok("16 - 18", "19.0.0"), Some(false));
+        assert_eq!(ok("*", "22.0.0"), Some(true));
+        assert_eq!(ok("lts/*", "22.0.0"), None);
+    }
+
+    #[test]
+    fn test_conflict() {
+        assert_eq!(
+            conflict("node", ">=20", "v18.19.0\n").as_deref(),
+            Some("engines.node wants >=20, node is 18.19.0")
+        );
+        assert_eq!(conflict("bun", ">=1.1", "1.1.34"), None);
+        assert_eq!(conflict("node", ">=20", "not a version"), None);
+    }
+
+    #[test]
+    fn test_read() {
+        let dir = std::env::temp_dir().join(format!("mla-package-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("package.json"),
+            r#"{"name": "shop", "version": "1.4.0", "engines": {"node": ">=20", "npm": 10}}"#,
+        )
+        .unwrap();
+        let meta = read(&dir).unwrap();
+        assert_eq!(meta.name.as_deref(), Some("shop"));
+        assert_eq!(meta.version.as_deref(), Some("1.4.0"));
+        assert_eq!(
+            meta.engines,
+            BTreeMap::from([("node".to_string(), ">=20".to_string())])
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
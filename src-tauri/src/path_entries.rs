@@ -0,0 +1,74 @@
+use std::path::Path;
+
+// Shell snippet that puts `$MY_APP_PATH` in front of PATH; runs after the profile is
+// sourced, since path_helper would reorder it
+pub const EXPORT: &str = r#"export PATH="$MY_APP_PATH:$PATH"; "#;
+
+/// Lines of an app's PATH entries field, blank ones skipped.
+fn lines(spec: &str) -> impl Iterator<Item = &str> {
+    spec.lines().map(str::trim).filter(|line| !line.is_empty())
+}
+
+/// Entries that can't go on PATH, for validating the field before it is saved.
+pub fn invalid(spec: &str) -> Vec<String> {
+    lines(spec)
+        .filter(|line| line.contains(':'))
+        .map(|line| format!("\"{}\" contains ':', which separates PATH entries", line))
+        .collect()
+}
+
+/// Directories to put in front of PATH, in order. `~` is the home directory and relative
+/// entries are inside the app's directory, e.g. "node_modules/.bin".
+pub fn resolve(spec: &str, app_dir: &Path) -> Vec<String> {
+    let home = std::env::var("HOME").unwrap_or_default();
+    lines(spec)
+        .filter(|line| !line.contains(':'))
+        .map(|line| {
+            if line == "~" {
+                home.clone()
+            } else if let Some(rest) = line.strip_prefix("~/") {
+                Path::new(&home).join(rest).to_string_lossy().to_string()
+            } else {
+                app_dir.join(line).to_string_lossy().to_string()
+            }
+        })
+        .collect()
+}
+
+/// The `MY_APP_PATH` value for `EXPORT`; None when there's nothing to add.
+pub fn env_value(entries: &[String]) -> Option<String> {
+    (!entries.is_empty()).then(|| entries.join(":"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let home = std::env::var("HOME").unwrap_or_default();
+        let entries = resolve(
+            "~/.cargo/bin\n\n  .toolchain/bin \n/opt/tools/bin\nbad:entry",
+            Path::new("/work/shop"),
+        );
+        assert_eq!(
+            entries,
+            [
+                format!("{}/.cargo/bin", home),
+                "/work/shop/.toolchain/bin".to_string(),
+                "/opt/tools/bin".to_string(),
+            ]
+        );
+        assert_eq!(
+            env_value(&entries[1..]).as_deref(),
+            Some("/work/shop/.toolchain/bin:/opt/tools/bin")
+        );
+        assert_eq!(env_value(&[]), None);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(invalid("~/.cargo/bin\nnode_modules/.bin").is_empty());
+        assert_eq!(invalid("/a:/b").len(), 1);
+    }
+}
@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::app_lock::AppLock;
+use crate::editor::EditorPreference;
+use crate::keep_running::QuitBehavior;
+use crate::log_forwarding::LogForwardingSettings;
+use crate::otlp::TraceExportSettings;
+use crate::remote_control::RemoteControlSettings;
+use crate::terminal::Terminal;
+
+pub const FILE: &str = "preferences.json";
+
+/// App-wide preferences that aren't tied to the proxy or a single app. New options go in
+/// `settings` instead.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    pub editor: EditorPreference,
+    // None picks the first installed terminal
+    pub terminal: Option<Terminal>,
+    pub app_lock: AppLock,
+    // Serve the read-only status page to the LAN, see `status_page`
+    pub status_page: bool,
+    // Regexes marking an app ready, on top of the built-in framework ones
+    pub ready_patterns: Vec<String>,
+    pub remote_control: RemoteControlSettings,
+    pub log_forwarding: LogForwardingSettings,
+    // Spans for requests through the inspection relay
+    pub trace_export: TraceExportSettings,
+    // Apps can override it, see `keep_running`
+    pub apps_on_quit: QuitBehavior,
+}
+
+pub struct PreferencesState {
+    path: Option<PathBuf>,
+    preferences: Mutex<Preferences>,
+}
+
+impl PreferencesState {
+    pub fn with_data_dir(dir: PathBuf) -> Self {
+        let path = dir.join(FILE);
+        let preferences = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            path: Some(path),
+            preferences: Mutex::new(preferences),
+        }
+    }
+
+    pub fn get(&self) -> Preferences {
+        self.preferences
+            .lock()
+            .map(|preferences| preferences.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn update(&self, update: impl FnOnce(&mut Preferences)) {
+        let preferences = match self.preferences.lock() {
+            Ok(mut preferences) => {
+                update(&mut preferences);
+                preferences.clone()
+            }
+            Err(_) => return,
+        };
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Ok(content) = serde_json::to_string(&preferences) {
+            if let Err(e) = std::fs::write(path, content) {
+                log::error!("Failed to save preferences: {}", e);
+            }
+        }
+    }
+}
+
+impl Default for PreferencesState {
+    fn default() -> Self {
+        Self {
+            path: None,
+            preferences: Mutex::new(Preferences::default()),
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use std::time::Duration;
+
+// Per check, so a dead host fails the start quickly instead of hanging it
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Docker Desktop's per-user socket, then the classic system one
+const DOCKER_SOCKETS: [&str; 2] = ["~/.docker/run/docker.sock", "/var/run/docker.sock"];
+
+/// Something outside the app that has to be up before it starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Prerequisite {
+    Port { host: String, port: u16 },
+    Url(String),
+    Docker,
+}
+
+/// Entries of an app's prerequisites field, one per line or comma separated.
+fn entries(spec: &str) -> impl Iterator<Item = &str> {
+    spec.split(['\n', ','])
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+}
+
+fn parse(entry: &str) -> Result<Prerequisite, String> {
+    if entry.eq_ignore_ascii_case("docker") {
+        return Ok(Prerequisite::Docker);
+    }
+    if entry.starts_with("http://") || entry.starts_with("https://") {
+        return Ok(Prerequisite::Url(entry.to_string()));
+    }
+
+    let (host, port) = entry.rsplit_once(':').unwrap_or(("localhost", entry));
+    match port.parse::<u16>() {
+        Ok(port) if port > 0 && !host.is_empty() => Ok(Prerequisite::Port {
+            host: host.to_string(),
+            port,
+        }),
+        _ => Err(format!(
+            "\"{}\" is not a port, host:port, URL or \"docker\"",
+            entry
+        )),
+    }
+}
+
+/// Entries that can't be checked, for validating the field before it is saved.
+pub fn invalid(spec: &str) -> Vec<String> {
+    entries(spec)
+        .filter_map(|entry| parse(entry).err())
+        .collect()
+}
+
+/// Checks every prerequisite and describes the ones that aren't met.
+pub async fn unmet(spec: &str) -> Vec<String> {
+    let mut missing = Vec::new();
+    for entry in entries(spec) {
+        let result = match parse(entry) {
+            Ok(prerequisite) => check(&prerequisite).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            missing.push(e);
+        }
+    }
+    missing
+}
+
+async fn check(prerequisite: &Prerequisite) -> Result<(), String> {
+    match prerequisite {
+        Prerequisite::Port { host, port } => {
+            let connect = tokio::net::TcpStream::connect((host.as_str(), *port));
+            match tokio::time::timeout(CHECK_TIMEOUT, connect).await {
+                Ok(Ok(_)) => Ok(()),
+                _ => Err(format!("nothing is listening on {}:{}", host, port)),
+            }
+        }
+        Prerequisite::Url(url) => {
+            let client = reqwest::Client::builder()
+                .timeout(CHECK_TIMEOUT)
+                .build()
+                .map_err(|e| format!("Failed to check {}: {}", url, e))?;
+            match client.get(url).send().await {
+                Ok(response) if !response.status().is_server_error() => Ok(()),
+                Ok(response) => Err(format!("{} responded {}", url, response.status())),
+                Err(_) => Err(format!("{} is unreachable", url)),
+            }
+        }
+        Prerequisite::Docker => {
+            if docker_running().await {
+                Ok(())
+            } else {
+                Err("the Docker daemon is not running".to_string())
+            }
+        }
+    }
+}
+
+pub async fn docker_running() -> bool {
+    let home = std::env::var("HOME").unwrap_or_default();
+    for socket in DOCKER_SOCKETS {
+        let path = socket.replacen('~', &home, 1);
+        let connect = tokio::net::UnixStream::connect(path);
+        if let Ok(Ok(_)) = tokio::time::timeout(CHECK_TIMEOUT, connect).await {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("5432"),
+            Ok(Prerequisite::Port {
+                host: "localhost".to_string(),
+                port: 5432
+            })
+        );
+        assert_eq!(
+            parse("db.local:6379"),
+            Ok(Prerequisite::Port {
+                host: "db.local".to_string(),
+                port: 6379
+            })
+        );
+        assert_eq!(parse("Docker"), Ok(Prerequisite::Docker));
+        assert!(matches!(
+            parse("http://localhost:8080/health"),
+            Ok(Prerequisite::Url(_))
+        ));
+        assert_eq!(
+            invalid("5432, postgres\n\n:80"),
+            vec![
+                "\"postgres\" is not a port, host:port, URL or \"docker\"".to_string(),
+                "\":80\" is not a port, host:port, URL or \"docker\"".to_string(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use sysinfo::{Pid, System};
+
+/// One process of an app's tree as the OS sees it, not as the app was configured.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub parent: Option<u32>,
+    pub name: String,
+    pub command: Vec<String>,
+    pub cwd: Option<String>,
+    pub env: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ListeningSocket {
+    pub pid: u32,
+    /// Short process name as lsof reports it, e.g. "node"
+    pub process: String,
+    pub address: String,
+    pub port: u16,
+}
+
+/// A TCP listener anywhere on the machine, with who owns it.
+#[derive(Debug, Clone, Serialize)]
+pub struct ListeningPort {
+    #[serde(flatten)]
+    pub socket: ListeningSocket,
+    /// Working directory of the owning process, usually its project
+    pub cwd: Option<String>,
+    /// The managed app whose process tree owns the listener
+    pub app_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessInspection {
+    pub processes: Vec<ProcessInfo>,
+    pub sockets: Vec<ListeningSocket>,
+}
+
+/// Snapshot of the process `root` and all of its descendants, with their listening sockets.
+pub fn inspect(root: u32) -> Result<ProcessInspection, String> {
+    let mut system = System::new_all();
+    system.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+
+    let root = Pid::from_u32(root);
+    if system.process(root).is_none() {
+        return Err(format!("Process {} is not running", root));
+    }
+
+    let processes: Vec<ProcessInfo> = process_tree(&system, root)
+        .iter()
+        .filter_map(|pid| system.process(*pid))
+        .map(|process| ProcessInfo {
+            pid: process.pid().as_u32(),
+            parent: process.parent().map(|p| p.as_u32()),
+            name: process.name().to_string_lossy().to_string(),
+            command: process
+                .cmd()
+                .iter()
+                .map(|arg| arg.to_string_lossy().to_string())
+                .collect(),
+            cwd: process.cwd().map(|cwd| cwd.display().to_string()),
+            env: process
+                .environ()
+                .iter()
+                .filter_map(|var| {
+                    let var = var.to_string_lossy();
+                    var.split_once('=')
+                        .map(|(key, value)| (key.to_string(), value.to_string()))
+                })
+                .collect(),
+        })
+        .collect();
+
+    let pids: Vec<u32> = processes.iter().map(|p| p.pid).collect();
+    Ok(ProcessInspection {
+        processes,
+        sockets: listening_sockets(Some(&pids))?,
+    })
+}
+
+/// Every TCP listener on the machine; `apps` maps app ids to their root pids so listeners
+/// inside an app's tree are attributed to it.
+pub fn listening_ports(apps: &[(String, u32)]) -> Result<Vec<ListeningPort>, String> {
+    let mut system = System::new_all();
+    system.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+
+    let mut owners: HashMap<u32, &str> = HashMap::new();
+    for (app_id, root) in apps {
+        for pid in process_tree(&system, Pid::from_u32(*root)) {
+            owners.insert(pid.as_u32(), app_id);
+        }
+    }
+
+    let mut ports: Vec<ListeningPort> = listening_sockets(None)?
+        .into_iter()
+        .map(|socket| ListeningPort {
+            cwd: system
+                .process(Pid::from_u32(socket.pid))
+                .and_then(|process| process.cwd())
+                .map(|cwd| cwd.display().to_string()),
+            app_id: owners.get(&socket.pid).map(|id| id.to_string()),
+            socket,
+        })
+        .collect();
+    ports.sort_by_key(|port| (port.socket.port, port.socket.pid));
+    Ok(ports)
+}
+
+// `root` and its descendants, breadth-first so the tree reads top-down, siblings in pid order
+fn process_tree(system: &System, root: Pid) -> Vec<Pid> {
+    let mut pids = vec![root];
+    let mut next = 0;
+    while next < pids.len() {
+        let parent = pids[next];
+        let mut children: Vec<Pid> = system
+            .processes()
+            .iter()
+            .filter(|(_, process)| process.parent() == Some(parent))
+            .map(|(pid, _)| *pid)
+            .collect();
+        children.sort();
+        pids.extend(children);
+        next += 1;
+    }
+    pids
+}
+
+// Listeners of the given processes, or of every process lsof can see
+fn listening_sockets(pids: Option<&[u32]>) -> Result<Vec<ListeningSocket>, String> {
+    let mut command = std::process::Command::new("lsof");
+    command.args(["-nP", "-iTCP", "-sTCP:LISTEN", "-F", "pcn"]);
+    if let Some(pids) = pids {
+        let pid_list = pids
+            .iter()
+            .map(|pid| pid.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        command.args(["-a", "-p", &pid_list]);
+    }
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run lsof: {}", e))?;
+    // lsof exits 1 when nothing matched, which is not an error here
+    Ok(parse_lsof(&String::from_utf8_lossy(&output.stdout)))
+}
+
+// Parses `lsof -F pcn` output: `p<pid>` and `c<name>` lines start each process,
+// `n<address>:<port>` lines follow for its sockets
+fn parse_lsof(output: &str) -> Vec<ListeningSocket> {
+    let mut sockets = Vec::new();
+    let mut pid = None;
+    let mut process = String::new();
+    for line in output.lines() {
+        if let Some(value) = line.strip_prefix('p') {
+            pid = value.parse::<u32>().ok();
+            process.clear();
+        } else if let Some(value) = line.strip_prefix('c') {
+            process = value.to_string();
+        } else if let (Some(value), Some(pid)) = (line.strip_prefix('n'), pid) {
+            let Some((address, port)) = value.rsplit_once(':') else {
+                continue;
+            };
+            let Ok(port) = port.parse::<u16>() else {
+                continue;
+            };
+            let socket = ListeningSocket {
+                pid,
+                process: process.clone(),
+                address: address.to_string(),
+                port,
+            };
+            // A socket shared by several descriptors is listed once per descriptor
+            if !sockets.contains(&socket) {
+                sockets.push(socket);
+            }
+        }
+    }
+    sockets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lsof() {
+        let output =
+            "p4120\ncnode\nf23\nn*:3000\nf24\nn[::1]:24678\np4188\ncpostgres\nf12\nn127.0.0.1:5432\n";
+        assert_eq!(
+            parse_lsof(output),
+            vec![
+                ListeningSocket {
+                    pid: 4120,
+                    process: "node".to_string(),
+                    address: "*".to_string(),
+                    port: 3000
+                },
+                ListeningSocket {
+                    pid: 4120,
+                    process: "node".to_string(),
+                    address: "[::1]".to_string(),
+                    port: 24678
+                },
+                ListeningSocket {
+                    pid: 4188,
+                    process: "postgres".to_string(),
+                    address: "127.0.0.1".to_string(),
+                    port: 5432
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Runtime {
+    Node,
+    Rust,
+    Go,
+    Python,
+}
+
+/// What a freshly added folder looks like: a name and how to run (and optionally build) it.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct DetectedProject {
+    pub runtime: Runtime,
+    pub name: Option<String>,
+    pub command: Option<String>,
+    pub build_command: Option<String>,
+}
+
+/// Detects the project at `dir` from its manifest; `None` when there is nothing recognizable,
+/// e.g. a folder holding a prebuilt binary.
+pub fn detect(dir: &Path) -> Option<DetectedProject> {
+    detect_node(dir)
+        .or_else(|| detect_rust(dir))
+        .or_else(|| detect_go(dir))
+        .or_else(|| {
+            crate::python::detect(dir).map(|project| DetectedProject {
+                runtime: Runtime::Python,
+                name: project.name,
+                command: project.command,
+                build_command: None,
+            })
+        })
+}
+
+fn detect_node(dir: &Path) -> Option<DetectedProject> {
+    let content = std::fs::read_to_string(dir.join("package.json")).ok()?;
+    let package: serde_json::Value = serde_json::from_str(&content).ok()?;
+    let runner = crate::workspace::package_runner(dir);
+    Some(DetectedProject {
+        runtime: Runtime::Node,
+        name: package["name"].as_str().map(String::from),
+        command: crate::workspace::pick_script(&package, None)
+            .map(|script| crate::workspace::run_command(runner, &script)),
+        build_command: None,
+    })
+}
+
+fn detect_rust(dir: &Path) -> Option<DetectedProject> {
+    let manifest = std::fs::read_to_string(dir.join("Cargo.toml")).ok()?;
+    Some(DetectedProject {
+        runtime: Runtime::Rust,
+        name: toml_name(&manifest, "[package]"),
+        command: Some("cargo run".to_string()),
+        build_command: Some("cargo build".to_string()),
+    })
+}
+
+fn detect_go(dir: &Path) -> Option<DetectedProject> {
+    let module = std::fs::read_to_string(dir.join("go.mod")).ok()?;
+    // "module github.com/acme/api" -> "api"
+    let name = module
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("module "))
+        .and_then(|path| path.trim().rsplit('/').next())
+        .map(String::from);
+    Some(DetectedProject {
+        runtime: Runtime::Go,
+        name,
+        command: Some("go run .".to_string()),
+        build_command: Some("go build ./...".to_string()),
+    })
+}
+
+/// `name = "..."` inside the given TOML section, without a full TOML parser.
+pub fn toml_name(content: &str, section: &str) -> Option<String> {
+    let mut in_section = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_section = line == section;
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=').filter(|_| in_section) {
+            if key.trim() == "name" {
+                return Some(value.trim().trim_matches(['"', '\'']).to_string());
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let root = std::env::temp_dir().join(format!("mla-project-{}", std::process::id()));
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write(
+            "rust/Cargo.toml",
+            "[package]\nname = \"server\"\n\n[dependencies]\nname = \"x\"\n",
+        );
+        write("go/go.mod", "module github.com/acme/api\n\ngo 1.22\n");
+        write(
+            "web/package.json",
+            r#"{"name": "web", "scripts": {"dev": "vite"}}"#,
+        );
+        write("web/pnpm-lock.yaml", "");
+        write("bin/server", "");
+
+        let rust = detect(&root.join("rust")).unwrap();
+        assert_eq!(rust.runtime, Runtime::Rust);
+        assert_eq!(rust.name.as_deref(), Some("server"));
+        assert_eq!(rust.build_command.as_deref(), Some("cargo build"));
+
+        let go = detect(&root.join("go")).unwrap();
+        assert_eq!(go.name.as_deref(), Some("api"));
+        assert_eq!(go.command.as_deref(), Some("go run ."));
+
+        let web = detect(&root.join("web")).unwrap();
+        assert_eq!(web.command.as_deref(), Some("pnpm run dev"));
+
+        assert_eq!(detect(&root.join("bin")), None);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
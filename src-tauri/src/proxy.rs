@@ -1,22 +1,395 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::access_log::CADDY_ACCESS_LOG;
+use crate::builtin_proxy::BuiltinProxy;
+use crate::caddy_admin::{self, AdminEndpoint};
+use crate::caddy_import;
+use crate::db::Db;
+use crate::error::AppError;
+use crate::ip_allowlist;
+use crate::ssh_tunnel::SshTunnel;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ProxyRoute {
     pub subdomain: String,
     pub port: i32,
+    // Local inspection relay port; when set, traffic goes through the relay instead of the app
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relay_port: Option<u16>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mocks: Vec<MockResponse>,
+    // Mocks are kept while disabled so they can be toggled back on
+    #[serde(default)]
+    pub mocks_enabled: bool,
+    // Manual routes point at a port we don't manage and are kept until removed explicitly
+    #[serde(default)]
+    pub manual: bool,
+    // Set for static folder apps, which are served from disk and have no port
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub static_site: Option<StaticSite>,
+    // Client IPs or CIDR ranges allowed in from the LAN; empty allows everyone
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_ips: Vec<String>,
+    // Manual routes to a remote service; `port` is the local end of the ssh forward
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tunnel: Option<SshTunnel>,
+    // Unix ms after which the route is removed, for temporary shares
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+    // Also answers on any `<tenant>.<subdomain>` host, passing that Host header through
+    #[serde(default)]
+    pub wildcard: bool,
+    #[serde(default, skip_serializing_if = "ForwardHeaders::is_default")]
+    pub headers: ForwardHeaders,
+    #[serde(default, skip_serializing_if = "ProxyTimeouts::is_default")]
+    pub timeouts: ProxyTimeouts,
+    #[serde(default)]
+    pub upstream: UpstreamProtocol,
+    // zstd/gzip responses; None compresses static sites only. Caddy only, the built-in
+    // proxy always sends responses as they are
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compress: Option<bool>,
+    // Larger request bodies get 413, like an upload limit in production
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_body_bytes: Option<u64>,
+    // Requests per second the relay lets through before answering 429
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<u32>,
+    // Unpublished: kept with its subdomain and settings but left out of the proxy config
+    #[serde(default)]
+    pub disabled: bool,
+    // Also served over HTTPS with a certificate from Caddy's local CA. Caddy only
+    #[serde(default)]
+    pub tls: bool,
+}
+
+/// What the proxy speaks to a route's app.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UpstreamProtocol {
+    #[default]
+    Http,
+    // HTTP/2 without TLS, what gRPC dev servers speak
+    H2c,
+    // For apps that only serve HTTPS, usually with a self-signed certificate
+    HttpsInsecure,
+}
+
+impl UpstreamProtocol {
+    fn scheme(self) -> &'static str {
+        match self {
+            UpstreamProtocol::Http => "",
+            UpstreamProtocol::H2c => "h2c://",
+            UpstreamProtocol::HttpsInsecure => "https://",
+        }
+    }
+}
+
+/// How long Caddy waits on a route's app, for long-lived WebSocket and SSE connections.
+/// Unset values keep Caddy's defaults; the built-in proxy has no timeouts and never buffers.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct ProxyTimeouts {
+    // Seconds to wait for the app's response headers
+    pub read_secs: Option<u64>,
+    // Seconds to wait while sending the request to the app
+    pub write_secs: Option<u64>,
+    // Seconds an unused connection to the app stays open
+    pub idle_secs: Option<u64>,
+    // Pass each chunk on right away instead of buffering, for server-sent events
+    pub streaming: bool,
+}
+
+impl ProxyTimeouts {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// The Host and X-Forwarded-* headers a route sends its app. The defaults are Caddy's;
+/// some frameworks want otherwise, e.g. Django's CSRF check compares Origin against Host
+/// and Next.js' image optimizer fetches from whatever host it is told.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct ForwardHeaders {
+    // Send `localhost:<port>` as Host instead of the name the browser asked for
+    pub rewrite_host: bool,
+    pub forwarded_for: bool,
+    pub forwarded_proto: bool,
+    pub forwarded_host: bool,
+}
+
+impl ForwardHeaders {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    // (header, sent) for each X-Forwarded-* header
+    pub fn forwarded(&self) -> [(&'static str, bool); 3] {
+        [
+            ("X-Forwarded-For", self.forwarded_for),
+            ("X-Forwarded-Proto", self.forwarded_proto),
+            ("X-Forwarded-Host", self.forwarded_host),
+        ]
+    }
+}
+
+impl Default for ForwardHeaders {
+    fn default() -> Self {
+        Self {
+            rewrite_host: false,
+            forwarded_for: true,
+            forwarded_proto: true,
+            forwarded_host: true,
+        }
+    }
+}
+
+/// A built directory served as files instead of proxied to a running app.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StaticSite {
+    pub root: String,
+    // Paths without a matching file get index.html, for client-side routing
+    #[serde(default)]
+    pub spa: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MockResponse {
+    // Caddy path matcher (e.g. "/api/*"); matches every request when empty
+    #[serde(default)]
+    pub path: Option<String>,
+    pub status: u16,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    #[serde(default)]
+    pub body: String,
+}
+
+impl ProxyRoute {
+    pub fn new(subdomain: &str, port: i32) -> Self {
+        Self {
+            subdomain: subdomain.to_string(),
+            port,
+            relay_port: None,
+            mocks: Vec::new(),
+            mocks_enabled: false,
+            manual: false,
+            static_site: None,
+            allowed_ips: Vec::new(),
+            tunnel: None,
+            expires_at: None,
+            wildcard: false,
+            headers: ForwardHeaders::default(),
+            timeouts: ProxyTimeouts::default(),
+            upstream: UpstreamProtocol::Http,
+            compress: None,
+            max_body_bytes: None,
+            rate_limit: None,
+            disabled: false,
+            tls: false,
+        }
+    }
+
+    pub fn compresses(&self) -> bool {
+        self.compress.unwrap_or(self.static_site.is_some())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProxyBackend {
+    #[default]
+    Caddy,
+    Builtin,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxySettings {
+    // None follows whatever the install script set up
+    #[serde(default)]
+    pub admin_endpoint: Option<AdminEndpoint>,
+    #[serde(default)]
+    pub backend: ProxyBackend,
+    #[serde(default = "default_builtin_port")]
+    pub builtin_port: u16,
+    // Set while the built-in DNS responder serves `*.<dns_domain>` (e.g. "macbook.local")
+    #[serde(default)]
+    pub dns_domain: Option<String>,
+}
+
+impl Default for ProxySettings {
+    fn default() -> Self {
+        Self {
+            admin_endpoint: None,
+            backend: ProxyBackend::default(),
+            builtin_port: default_builtin_port(),
+            dns_domain: None,
+        }
+    }
+}
+
+fn default_builtin_port() -> u16 {
+    80
 }
 
 pub struct ProxyState {
     pub routes: std::sync::Arc<tokio::sync::Mutex<HashMap<String, ProxyRoute>>>,
+    // Last Caddyfile Caddy accepted and stayed healthy with, restored when a change breaks it
+    last_good_config: tokio::sync::Mutex<Option<String>>,
+    // Routes the proxy last took, put back when a change to them is rolled back
+    applied_routes: std::sync::Mutex<Option<HashMap<String, ProxyRoute>>>,
+    // Held while a config is pushed, so changes reach the proxy in the order they were made
+    applying: tokio::sync::Mutex<()>,
+    backup_path: Option<PathBuf>,
+    settings_path: Option<PathBuf>,
+    settings: std::sync::Mutex<ProxySettings>,
+    pub builtin: BuiltinProxy,
+    app_handle: std::sync::Mutex<Option<tauri::AppHandle>>,
 }
 
 impl ProxyState {
     pub fn new() -> Self {
         Self {
             routes: std::sync::Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+            last_good_config: tokio::sync::Mutex::new(None),
+            applied_routes: std::sync::Mutex::new(None),
+            applying: tokio::sync::Mutex::new(()),
+            backup_path: None,
+            settings_path: None,
+            settings: std::sync::Mutex::new(ProxySettings::default()),
+            builtin: BuiltinProxy::new(),
+            app_handle: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// Persists routes and the last-known-good config under `dir` so they survive restarts.
+    pub fn with_data_dir(dir: PathBuf) -> Self {
+        let backup_path = dir.join("caddy-last-good.caddyfile");
+        let last_good = std::fs::read_to_string(&backup_path).ok();
+        let settings_path = dir.join("proxy-settings.json");
+        let settings: ProxySettings = std::fs::read_to_string(&settings_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            last_good_config: tokio::sync::Mutex::new(last_good),
+            applied_routes: std::sync::Mutex::new(None),
+            applying: tokio::sync::Mutex::new(()),
+            backup_path: Some(backup_path),
+            settings_path: Some(settings_path),
+            settings: std::sync::Mutex::new(settings),
+            ..Self::new()
+        }
+    }
+
+    pub fn settings(&self) -> ProxySettings {
+        self.settings
+            .lock()
+            .map(|settings| settings.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn admin_endpoint(&self) -> AdminEndpoint {
+        self.settings()
+            .admin_endpoint
+            .unwrap_or_else(AdminEndpoint::detect)
+    }
+
+    /// Port apps are reachable on through the proxy.
+    pub fn public_port(&self) -> u16 {
+        let settings = self.settings();
+        match settings.backend {
+            ProxyBackend::Caddy => 80,
+            ProxyBackend::Builtin => settings.builtin_port,
         }
     }
+
+    pub fn update_settings(&self, update: impl FnOnce(&mut ProxySettings)) {
+        let settings = match self.settings.lock() {
+            Ok(mut settings) => {
+                update(&mut settings);
+                settings.clone()
+            }
+            Err(_) => return,
+        };
+        let Some(path) = &self.settings_path else {
+            return;
+        };
+        if let Ok(content) = serde_json::to_string(&settings) {
+            if let Err(e) = std::fs::write(path, content) {
+                log::error!("Failed to save proxy settings: {}", e);
+            }
+        }
+    }
+
+    // Kept in the database so the next launch can reconcile them with what's running
+    async fn save_routes(&self, routes: &HashMap<String, ProxyRoute>) {
+        let Some(handle) = self
+            .app_handle
+            .lock()
+            .ok()
+            .and_then(|handle| handle.clone())
+        else {
+            return;
+        };
+        if let Err(e) = handle.state::<Db>().save_proxy_routes(routes).await {
+            log::error!("{}", e);
+        }
+    }
+
+    pub fn set_app_handle(&self, app_handle: tauri::AppHandle) {
+        if let Ok(mut handle) = self.app_handle.lock() {
+            *handle = Some(app_handle);
+        }
+    }
+
+    fn remember_applied_routes(&self, routes: &HashMap<String, ProxyRoute>) {
+        if let Ok(mut applied) = self.applied_routes.lock() {
+            *applied = Some(routes.clone());
+        }
+    }
+
+    async fn remember_good_config(&self, content: &str) {
+        let mut last_good = self.last_good_config.lock().await;
+        *last_good = Some(content.to_string());
+        if let Some(path) = &self.backup_path {
+            if let Err(e) = std::fs::write(path, content) {
+                log::error!("Failed to save last-known-good proxy config: {}", e);
+            }
+        }
+    }
+
+    async fn rollback(&self, failed_content: &str, reason: &str) -> Result<(), String> {
+        let previous = self.last_good_config.lock().await.clone();
+        let previous = match previous {
+            Some(previous) if previous != failed_content => previous,
+            _ => return Err("No previous proxy config to roll back to".to_string()),
+        };
+
+        load_caddyfile_via_api(&self.admin_endpoint(), &previous)
+            .await
+            .map_err(|e| e.to_string())?;
+        log::info!("Rolled back proxy config: {}", reason);
+
+        if let Ok(handle) = self.app_handle.lock() {
+            if let Some(handle) = handle.as_ref() {
+                let _ = handle.emit(
+                    "proxy-rollback",
+                    serde_json::json!({
+                        "reason": reason
+                    }),
+                );
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Default for ProxyState {
@@ -25,12 +398,55 @@ impl Default for ProxyState {
     }
 }
 
-fn generate_caddyfile(routes: &HashMap<String, ProxyRoute>) -> String {
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CaddyConfigError {
+    // Site address of the route the error points at, when it can be located
+    pub site: Option<String>,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl std::fmt::Display for CaddyConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.site, self.line) {
+            (Some(site), Some(line)) => write!(
+                f,
+                "Caddy rejected the config for {} (line {}): {}",
+                site, line, self.message
+            ),
+            (None, Some(line)) => {
+                write!(
+                    f,
+                    "Caddy rejected the config (line {}): {}",
+                    line, self.message
+                )
+            }
+            _ => write!(f, "Caddy rejected the config: {}", self.message),
+        }
+    }
+}
+
+fn generate_caddyfile(
+    routes: &HashMap<String, ProxyRoute>,
+    admin: &str,
+    dns_domain: Option<&str>,
+) -> String {
+    let routes: Vec<&ProxyRoute> = routes.values().filter(|r| !r.disabled).collect();
     let mut content = String::new();
 
     content.push_str("{\n");
-    content.push_str("\tauto_https off\n");
-    content.push_str("\tadmin localhost:2019\n");
+    // Certificates are only issued while some route asks for HTTPS; plain HTTP sites are
+    // never redirected either way
+    if routes.iter().any(|r| r.tls) {
+        content.push_str("\tauto_https disable_redirects\n");
+    } else {
+        content.push_str("\tauto_https off\n");
+    }
+    content.push_str(&format!("\tadmin {}\n", admin));
+    // gRPC clients talk HTTP/2 in cleartext to the proxy as well
+    if routes.iter().any(|r| r.upstream == UpstreamProtocol::H2c) {
+        content.push_str("\tservers {\n\t\tprotocols h1 h2 h2c\n\t}\n");
+    }
     content.push_str("}\n\n");
 
     if routes.is_empty() {
@@ -40,9 +456,59 @@ fn generate_caddyfile(routes: &HashMap<String, ProxyRoute>) -> String {
         );
         content.push_str("}\n");
     } else {
-        for route in routes.values() {
-            content.push_str(&format!("http://{}.local {{\n", route.subdomain));
-            content.push_str(&format!("\treverse_proxy localhost:{}\n", route.port));
+        for route in routes {
+            let hosts = site_hosts(route, dns_domain);
+            let mut addresses: Vec<String> = hosts
+                .iter()
+                .map(|host| format!("http://{}", host))
+                .collect();
+            if route.tls {
+                addresses.extend(hosts.iter().map(|host| format!("https://{}", host)));
+            }
+            content.push_str(&format!("{} {{\n", addresses.join(", ")));
+            if route.tls {
+                content.push_str("\ttls internal\n");
+            }
+            // Read back for each app's last access; readable so the app needn't run as root
+            content.push_str(&format!(
+                "\tlog {{\n\t\toutput file {} {{\n\t\t\tmode 644\n\t\t}}\n\t}}\n",
+                CADDY_ACCESS_LOG
+            ));
+            if !route.allowed_ips.is_empty() {
+                // Loopback stays allowed so the app keeps working on this machine
+                content.push_str(&format!(
+                    "\t@blocked not remote_ip 127.0.0.1/8 ::1 {}\n",
+                    route.allowed_ips.join(" ")
+                ));
+                content.push_str("\trespond @blocked \"Forbidden\" 403\n");
+            }
+            if let Some(max) = route.max_body_bytes {
+                content.push_str(&format!("\trequest_body {{\n\t\tmax_size {}\n\t}}\n", max));
+            }
+            if route.compresses() {
+                content.push_str("\tencode zstd gzip\n");
+            }
+            if route.mocks_enabled {
+                write_mocks(&mut content, &route.mocks);
+            }
+            if let Some(site) = &route.static_site {
+                content.push_str(&format!("\troot * {}\n", quote_caddy(&site.root)));
+                if site.spa {
+                    content.push_str("\ttry_files {path} {path}/ /index.html\n");
+                }
+                content.push_str("\tfile_server\n");
+            } else if let Some(relay_port) = route
+                .relay_port
+                // The inspection relay only understands plain HTTP/1.1
+                .filter(|_| route.upstream == UpstreamProtocol::Http)
+            {
+                // WebSocket upgrades bypass the relay and go straight to the app
+                content.push_str("\t@websockets header Connection *Upgrade*\n");
+                write_reverse_proxy(&mut content, "@websockets ", route.port, route);
+                write_reverse_proxy(&mut content, "", relay_port.into(), route);
+            } else {
+                write_reverse_proxy(&mut content, "", route.port, route);
+            }
             content.push_str("}\n\n");
         }
     }
@@ -50,36 +516,607 @@ fn generate_caddyfile(routes: &HashMap<String, ProxyRoute>) -> String {
     content
 }
 
-pub async fn load_caddyfile_via_api(content: &str) -> Result<(), String> {
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(5))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+// The hostnames a route's site block answers on. Wildcards need the DNS responder, since
+// mDNS only resolves names that are registered one by one.
+fn site_hosts(route: &ProxyRoute, dns_domain: Option<&str>) -> Vec<String> {
+    let subdomain = &route.subdomain;
+    let mut hosts = vec![format!("{}.local", subdomain)];
+    if let Some(domain) = dns_domain {
+        hosts.push(format!("{}.{}", subdomain, domain));
+        if route.wildcard {
+            hosts.push(format!("*.{}.{}", subdomain, domain));
+        }
+    }
+    hosts
+}
+
+// A reverse_proxy directive, with a block for the route's header and timeout settings
+// when it has any
+fn write_reverse_proxy(content: &mut String, matcher: &str, port: i32, route: &ProxyRoute) {
+    let (headers, timeouts) = (&route.headers, &route.timeouts);
+    let address = format!("{}{}localhost:{}", matcher, route.upstream.scheme(), port);
+    let mut changes = Vec::new();
+    if headers.rewrite_host {
+        // The app's own port, not a relay's in front of it
+        changes.push(format!("header_up Host localhost:{}", route.port));
+    }
+    for (name, sent) in headers.forwarded() {
+        if !sent {
+            changes.push(format!("header_up -{}", name));
+        }
+    }
+    if timeouts.streaming {
+        changes.push("flush_interval -1".to_string());
+    }
+    let mut transport: Vec<String> = [
+        ("read_timeout", timeouts.read_secs),
+        ("write_timeout", timeouts.write_secs),
+        ("keepalive", timeouts.idle_secs),
+    ]
+    .into_iter()
+    .filter_map(|(name, secs)| Some(format!("\t\t\t{} {}s\n", name, secs?)))
+    .collect();
+    if route.upstream == UpstreamProtocol::HttpsInsecure {
+        transport.push("\t\t\ttls_insecure_skip_verify\n".to_string());
+    }
+    if !transport.is_empty() {
+        changes.push(format!("transport http {{\n{}\t\t}}", transport.concat()));
+    }
+    if changes.is_empty() {
+        content.push_str(&format!("\treverse_proxy {}\n", address));
+        return;
+    }
+    content.push_str(&format!("\treverse_proxy {} {{\n", address));
+    for change in changes {
+        content.push_str(&format!("\t\t{}\n", change));
+    }
+    content.push_str("\t}\n");
+}
+
+fn write_mocks(content: &mut String, mocks: &[MockResponse]) {
+    for (i, mock) in mocks.iter().enumerate() {
+        let matcher = match &mock.path {
+            Some(path) if !path.is_empty() => {
+                content.push_str(&format!("\t@mock{} path {}\n", i, path));
+                format!("@mock{} ", i)
+            }
+            _ => String::new(),
+        };
+        for (name, value) in &mock.headers {
+            content.push_str(&format!(
+                "\theader {}{} {}\n",
+                matcher,
+                name,
+                quote_caddy(value)
+            ));
+        }
+        content.push_str(&format!(
+            "\trespond {}{} {}\n",
+            matcher,
+            quote_caddy(&mock.body),
+            mock.status
+        ));
+    }
+}
+
+// Caddy only unescapes `\"` inside double quotes and keeps any other backslash, so
+// values with backslashes go in raw backtick quotes where they round-trip unchanged.
+// With a backtick in the value too, doubling the backslashes at least stops one from
+// eating the closing quote.
+fn quote_caddy(value: &str) -> String {
+    if !value.contains('\\') {
+        format!("\"{}\"", value.replace('"', "\\\""))
+    } else if !value.contains('`') {
+        format!("`{}`", value)
+    } else {
+        let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+        format!("\"{}\"", escaped)
+    }
+}
+
+// Tabs and line breaks are fine in a quoted body but nowhere else
+fn check_caddy_value(what: &str, value: &str, multiline: bool) -> Result<(), AppError> {
+    let allowed = |c: char| multiline && matches!(c, '\n' | '\r' | '\t');
+    if value.chars().any(|c| c.is_control() && !allowed(c)) {
+        return Err(format!("{} can't contain control characters", what).into());
+    }
+    Ok(())
+}
+
+// Mock paths and header names go into the Caddyfile unquoted, so they're limited to
+// characters that can't end the token or open a block
+fn check_mocks(mocks: &[MockResponse]) -> Result<(), AppError> {
+    for mock in mocks {
+        if let Some(path) = &mock.path {
+            let bad = |c: char| c.is_whitespace() || c.is_control() || "\"`{}".contains(c);
+            if path.chars().any(bad) {
+                return Err(format!("Invalid mock path {:?}", path).into());
+            }
+        }
+        for (name, value) in &mock.headers {
+            let token = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+            if name.is_empty() || !name.chars().all(token) {
+                return Err(format!("Invalid header name {:?}", name).into());
+            }
+            check_caddy_value("Header values", value, false)?;
+        }
+        check_caddy_value("Mock bodies", &mock.body, true)?;
+    }
+    Ok(())
+}
+
+pub async fn load_caddyfile_via_api(admin: &AdminEndpoint, content: &str) -> Result<(), AppError> {
+    let response = caddy_admin::request(
+        admin,
+        hyper::Method::POST,
+        "/load",
+        Some("text/caddyfile"),
+        content.to_string(),
+        Duration::from_secs(5),
+    )
+    .await
+    .map_err(AppError::proxy_unavailable)?;
+
+    if response.is_success() {
+        Ok(())
+    } else {
+        Err(format!("Caddy config load failed: {}", response.body).into())
+    }
+}
+
+/// Runs the Caddyfile through the admin API's adapter without applying it.
+pub async fn validate_caddyfile(
+    admin: &AdminEndpoint,
+    content: &str,
+) -> Result<(), CaddyConfigError> {
+    let response = caddy_admin::request(
+        admin,
+        hyper::Method::POST,
+        "/adapt",
+        Some("text/caddyfile"),
+        content.to_string(),
+        Duration::from_secs(5),
+    )
+    .await
+    .map_err(config_error)?;
+
+    if response.is_success() {
+        return Ok(());
+    }
+
+    let message = serde_json::from_str::<serde_json::Value>(&response.body)
+        .ok()
+        .and_then(|v| v.get("error").and_then(|e| e.as_str()).map(String::from))
+        .unwrap_or(response.body);
+    Err(locate_config_error(content, &message))
+}
+
+fn config_error(message: String) -> CaddyConfigError {
+    CaddyConfigError {
+        site: None,
+        line: None,
+        message,
+    }
+}
+
+// Caddy reports adapt errors as "... Caddyfile:<line> - <reason>"
+fn locate_config_error(content: &str, message: &str) -> CaddyConfigError {
+    let line = message.split("Caddyfile:").nth(1).and_then(|rest| {
+        rest.chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect::<String>()
+            .parse::<usize>()
+            .ok()
+    });
+
+    let site = line.and_then(|line| {
+        content
+            .lines()
+            .take(line)
+            .filter(|l| !l.starts_with(char::is_whitespace) && l.ends_with(" {"))
+            .last()
+            .map(|l| l.trim_end_matches(" {").to_string())
+            .filter(|site| site.starts_with("http://"))
+    });
+
+    CaddyConfigError {
+        site,
+        line,
+        message: message.to_string(),
+    }
+}
+
+#[tracing::instrument(skip_all, fields(routes = routes.len()), err(Display))]
+pub async fn update_routes(
+    proxy_state: &ProxyState,
+    routes: &HashMap<String, ProxyRoute>,
+) -> Result<(), AppError> {
+    let _applying = proxy_state.applying.lock().await;
+    push_routes(proxy_state, routes).await.map_err(|(e, _)| e)
+}
+
+// Needs `applying` held. The flag on errors says the proxy kept, or went back to, the
+// routes it had before.
+async fn push_routes(
+    proxy_state: &ProxyState,
+    routes: &HashMap<String, ProxyRoute>,
+) -> Result<(), (AppError, bool)> {
+    if proxy_state.settings().backend == ProxyBackend::Builtin {
+        proxy_state.builtin.set_routes(routes);
+        proxy_state.remember_applied_routes(routes);
+        return Ok(());
+    }
+
+    let admin = proxy_state.admin_endpoint();
+    let dns_domain = proxy_state.settings().dns_domain;
+    let caddyfile_content = generate_caddyfile(routes, &admin.directive(), dns_domain.as_deref());
+    // A rejected config never reaches /load, so the running config stays in place
+    if let Err(e) = validate_caddyfile(&admin, &caddyfile_content).await {
+        if !is_caddy_responsive(&admin).await {
+            return Err((AppError::proxy_unavailable(e.message), false));
+        }
+        return Err((e.to_string().into(), true));
+    }
+
+    let failure = match load_caddyfile_via_api(&admin, &caddyfile_content).await {
+        Ok(()) => {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            if is_caddy_responsive(&admin).await {
+                proxy_state.remember_good_config(&caddyfile_content).await;
+                proxy_state.remember_applied_routes(routes);
+                return Ok(());
+            }
+            "Caddy stopped responding after loading the new config".to_string()
+        }
+        Err(e) => e.to_string(),
+    };
+
+    let (message, rolled_back) = match proxy_state.rollback(&caddyfile_content, &failure).await {
+        Ok(()) => (
+            format!("{} (restored the last working proxy config)", failure),
+            true,
+        ),
+        Err(e) => (format!("{} (rollback failed: {})", failure, e), false),
+    };
+    Err((AppError::proxy_unavailable(message), rolled_back))
+}
+
+/// Hosts Caddy serves that no route asks for, and route hosts it isn't serving.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct HostDrift {
+    pub orphaned: Vec<String>,
+    pub missing: Vec<String>,
+}
+
+impl HostDrift {
+    pub fn is_empty(&self) -> bool {
+        self.orphaned.is_empty() && self.missing.is_empty()
+    }
+}
+
+pub fn host_drift(
+    routes: &HashMap<String, ProxyRoute>,
+    dns_domain: Option<&str>,
+    live: &BTreeSet<String>,
+) -> HostDrift {
+    let expected: BTreeSet<String> = routes
+        .values()
+        .filter(|route| !route.disabled)
+        .flat_map(|route| site_hosts(route, dns_domain))
+        .map(|host| host.to_ascii_lowercase())
+        .collect();
+    HostDrift {
+        orphaned: live.difference(&expected).cloned().collect(),
+        missing: expected.difference(live).cloned().collect(),
+    }
+}
+
+/// The app whose route serves `host`, matched the way Caddy matches its site addresses.
+pub fn app_for_host(
+    routes: &HashMap<String, ProxyRoute>,
+    dns_domain: Option<&str>,
+    host: &str,
+) -> Option<String> {
+    let host = host
+        .split(':')
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('.')
+        .to_ascii_lowercase();
+    let parent = host.split_once('.').map(|(_, parent)| parent);
+    routes
+        .iter()
+        .filter(|(_, route)| !route.disabled)
+        .find(|(_, route)| {
+            site_hosts(route, dns_domain).iter().any(|site| {
+                *site == host || site.strip_prefix("*.").is_some_and(|s| Some(s) == parent)
+            })
+        })
+        .map(|(app_id, _)| app_id.clone())
+}
+
+/// Hosts in Caddy's running config. None with the builtin proxy, whose routes live in
+/// this process and can't outlast it.
+pub async fn live_hosts(proxy_state: &ProxyState) -> Result<Option<BTreeSet<String>>, String> {
+    if proxy_state.settings().backend == ProxyBackend::Builtin {
+        return Ok(None);
+    }
+    let config = fetch_config(&proxy_state.admin_endpoint()).await?;
+    Ok(Some(caddy_import::hosts(&config)))
+}
+
+async fn fetch_config(admin: &AdminEndpoint) -> Result<serde_json::Value, String> {
+    let response = caddy_admin::request(
+        admin,
+        hyper::Method::GET,
+        "/config/",
+        None,
+        String::new(),
+        Duration::from_secs(5),
+    )
+    .await?;
+    if !response.is_success() {
+        return Err(format!("Failed to read Caddy's config: {}", response.body));
+    }
+    serde_json::from_str(&response.body)
+        .map_err(|e| format!("Failed to parse Caddy's config: {}", e))
+}
+
+/// PEM root certificate of the local CA Caddy signs HTTPS routes with, for other devices
+/// to trust. Caddy creates the CA the first time a route asks for HTTPS.
+pub async fn local_ca_certificate(proxy_state: &ProxyState) -> Result<String, AppError> {
+    if proxy_state.settings().backend == ProxyBackend::Builtin {
+        return Err("The built-in proxy has no certificate authority".into());
+    }
+    let response = caddy_admin::request(
+        &proxy_state.admin_endpoint(),
+        hyper::Method::GET,
+        "/pki/ca/local",
+        None,
+        String::new(),
+        Duration::from_secs(5),
+    )
+    .await
+    .map_err(AppError::proxy_unavailable)?;
+    if !response.is_success() {
+        return Err(AppError::not_found(format!(
+            "Caddy has no local CA yet: {}",
+            response.body
+        )));
+    }
+    root_certificate(&response.body).ok_or_else(|| "Caddy returned no root certificate".into())
+}
+
+// The root_certificate field of Caddy's /pki/ca/<id> response
+fn root_certificate(body: &str) -> Option<String> {
+    let ca: serde_json::Value = serde_json::from_str(body).ok()?;
+    let pem = ca.get("root_certificate")?.as_str()?;
+    pem.starts_with("-----BEGIN CERTIFICATE-----")
+        .then(|| pem.to_string())
+}
+
+/// The JSON config Caddy is running, as adapted from the generated Caddyfile plus any
+/// custom edits applied since.
+pub async fn effective_config(proxy_state: &ProxyState) -> Result<serde_json::Value, AppError> {
+    if proxy_state.settings().backend == ProxyBackend::Builtin {
+        return Err("The built-in proxy has no Caddy config".into());
+    }
+    fetch_config(&proxy_state.admin_endpoint())
+        .await
+        .map_err(AppError::proxy_unavailable)
+}
+
+/// Refuses a hand-edited config that would cut the app off from Caddy: the admin API must
+/// stay where the app talks to it, and every route's hosts must still be served.
+pub fn check_custom_config(
+    config: &serde_json::Value,
+    routes: &HashMap<String, ProxyRoute>,
+    dns_domain: Option<&str>,
+    admin: &AdminEndpoint,
+) -> Result<(), String> {
+    if !config.is_object() {
+        return Err("Caddy config must be a JSON object".to_string());
+    }
+    let listen = config.pointer("/admin/listen").and_then(|l| l.as_str());
+    if listen != Some(admin.directive().as_str()) {
+        return Err(format!(
+            "admin.listen must stay \"{}\" so the app can keep managing Caddy",
+            admin.directive()
+        ));
+    }
+    let drift = host_drift(routes, dns_domain, &caddy_import::hosts(config));
+    if !drift.missing.is_empty() {
+        return Err(format!(
+            "Config drops hosts managed by My Little Apps: {}",
+            drift.missing.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Loads a hand-edited JSON config into Caddy, restoring the previous one if Caddy rejects
+/// it or stops responding. The next route change regenerates the config from the routes
+/// and discards these edits.
+pub async fn apply_custom_config(
+    proxy_state: &ProxyState,
+    config: serde_json::Value,
+) -> Result<(), AppError> {
+    if proxy_state.settings().backend == ProxyBackend::Builtin {
+        return Err("The built-in proxy has no Caddy config".into());
+    }
+    let routes = proxy_state.routes.lock().await;
+    // Taken in the same order as `apply_routes`, and held through the rollback so no push
+    // lands in between; the routes are free again during the wait
+    let _applying = proxy_state.applying.lock().await;
+    let admin = proxy_state.admin_endpoint();
+    let dns_domain = proxy_state.settings().dns_domain;
+    check_custom_config(&config, &routes, dns_domain.as_deref(), &admin)?;
+    drop(routes);
 
-    let response = client
-        .post("http://localhost:2019/load")
-        .header("Content-Type", "text/caddyfile")
-        .body(content.to_string())
-        .send()
+    let previous = fetch_config(&admin)
         .await
-        .map_err(|e| {
-            format!(
-                "Failed to connect to Caddy admin API (is the proxy service running?): {}",
-                e
-            )
-        })?;
-
-    if response.status().is_success() {
+        .map_err(AppError::proxy_unavailable)?;
+    let failure = match load_json_config(&admin, &config).await {
+        Ok(()) => {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            if is_caddy_responsive(&admin).await {
+                return Ok(());
+            }
+            "Caddy stopped responding after loading the custom config".to_string()
+        }
+        // Caddy leaves the running config alone when it rejects a load
+        Err(e) => return Err(e.into()),
+    };
+
+    log::warn!("{}; restoring the previous config", failure);
+    let message = match load_json_config(&admin, &previous).await {
+        Ok(()) => format!("{} (restored the previous proxy config)", failure),
+        Err(e) => format!("{} (rollback failed: {})", failure, e),
+    };
+    Err(AppError::proxy_unavailable(message))
+}
+
+async fn load_json_config(admin: &AdminEndpoint, config: &serde_json::Value) -> Result<(), String> {
+    let response = caddy_admin::request(
+        admin,
+        hyper::Method::POST,
+        "/load",
+        Some("application/json"),
+        config.to_string(),
+        Duration::from_secs(5),
+    )
+    .await?;
+    if response.is_success() {
         Ok(())
     } else {
-        let error_text = response.text().await.unwrap_or_default();
-        Err(format!("Caddy config load failed: {}", error_text))
+        Err(format!("Caddy config load failed: {}", response.body))
+    }
+}
+
+/// Saves the changed `routes` and pushes them to the proxy. The lock is released before
+/// waiting on the proxy, and if it rejects the change the routes go back to the ones it
+/// kept, unless they were changed again in the meantime.
+pub async fn apply_routes(
+    proxy_state: &ProxyState,
+    routes: tokio::sync::MutexGuard<'_, HashMap<String, ProxyRoute>>,
+) -> Result<(), AppError> {
+    let next = routes.clone();
+    proxy_state.save_routes(&next).await;
+    let applying = proxy_state.applying.lock().await;
+    drop(routes);
+    let result = push_routes(proxy_state, &next).await;
+    drop(applying);
+
+    let Err((e, kept_previous)) = result else {
+        return Ok(());
+    };
+    let previous = proxy_state
+        .applied_routes
+        .lock()
+        .ok()
+        .and_then(|applied| applied.clone());
+    if let Some(previous) = previous.filter(|_| kept_previous) {
+        let mut routes = proxy_state.routes.lock().await;
+        if *routes == next {
+            proxy_state.save_routes(&previous).await;
+            *routes = previous;
+        }
+    }
+    Err(e)
+}
+
+/// Moves Caddy's admin API to `endpoint` (None restores the installed default).
+pub async fn set_admin_endpoint(
+    proxy_state: &ProxyState,
+    endpoint: Option<AdminEndpoint>,
+) -> Result<(), AppError> {
+    let guard = proxy_state.routes.lock().await;
+    let applying = proxy_state.applying.lock().await;
+    let routes = guard.clone();
+    drop(guard);
+    let current = proxy_state.admin_endpoint();
+    let next = endpoint.clone().unwrap_or_else(AdminEndpoint::detect);
+
+    if next == current {
+        proxy_state.update_settings(|settings| settings.admin_endpoint = endpoint);
+        return Ok(());
+    }
+
+    // Caddy switches its admin listener as part of loading the new config
+    let dns_domain = proxy_state.settings().dns_domain;
+    let content = generate_caddyfile(&routes, &next.directive(), dns_domain.as_deref());
+    validate_caddyfile(&current, &content)
+        .await
+        .map_err(|e| e.to_string())?;
+    let last_good = proxy_state.last_good_config.lock().await.clone();
+    load_caddyfile_via_api(&current, &content).await?;
+    // Caddy has moved by now, so the setting follows it even if it can't be reached there
+    proxy_state.update_settings(|settings| settings.admin_endpoint = endpoint);
+    drop(applying);
+
+    // Route changes go on during the probe, which can take a while
+    if !is_caddy_responsive(&next).await {
+        return Err(AppError::proxy_unavailable(format!(
+            "Caddy is not reachable at the new admin endpoint {}",
+            next.directive()
+        )));
+    }
+    let _applying = proxy_state.applying.lock().await;
+    // A push since has a newer config to fall back to
+    let pushed_since = *proxy_state.last_good_config.lock().await != last_good;
+    if !pushed_since {
+        proxy_state.remember_good_config(&content).await;
+    }
+    Ok(())
+}
+
+/// Starts the built-in proxy when it is the configured backend.
+pub async fn start_configured_backend(proxy_state: &ProxyState) -> Result<(), AppError> {
+    let settings = proxy_state.settings();
+    if settings.backend == ProxyBackend::Builtin {
+        let port = settings.builtin_port;
+        proxy_state
+            .builtin
+            .start(port)
+            .await
+            .map_err(|e| AppError::port_unavailable(port.into(), e))?;
+    }
+    Ok(())
+}
+
+/// Switches between Caddy and the built-in proxy, moving the current routes over.
+pub async fn set_backend(
+    proxy_state: &ProxyState,
+    backend: ProxyBackend,
+    builtin_port: Option<u16>,
+) -> Result<(), AppError> {
+    let port = builtin_port.unwrap_or(proxy_state.settings().builtin_port);
+    match backend {
+        ProxyBackend::Builtin => proxy_state
+            .builtin
+            .start(port)
+            .await
+            .map_err(|e| AppError::port_unavailable(port.into(), e))?,
+        ProxyBackend::Caddy => proxy_state.builtin.stop().await,
     }
+
+    proxy_state.update_settings(|settings| {
+        settings.backend = backend;
+        settings.builtin_port = port;
+    });
+
+    let routes = proxy_state.routes.lock().await.clone();
+    update_routes(proxy_state, &routes).await
 }
 
-pub async fn update_routes(routes: &HashMap<String, ProxyRoute>) -> Result<(), String> {
-    let caddyfile_content = generate_caddyfile(routes);
-    load_caddyfile_via_api(&caddyfile_content).await
+/// Serves routes under `*.<domain>` too (None stops), for the built-in DNS responder.
+pub async fn set_dns_domain(
+    proxy_state: &ProxyState,
+    domain: Option<String>,
+) -> Result<(), AppError> {
+    proxy_state.update_settings(|settings| settings.dns_domain = domain);
+    let routes = proxy_state.routes.lock().await.clone();
+    update_routes(proxy_state, &routes).await
 }
 
 pub async fn add_route(
@@ -87,87 +1124,1061 @@ pub async fn add_route(
     app_id: &str,
     subdomain: &str,
     port: i32,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
+    check_subdomain_reserved(subdomain, crate::dns::get_local_hostname().as_deref())?;
     let mut routes = proxy_state.routes.lock().await;
-    routes.insert(
-        app_id.to_string(),
-        ProxyRoute {
+    // Re-adding a route (e.g. after a restart) keeps its per-route options
+    let route = match routes.get(app_id) {
+        Some(existing) => ProxyRoute {
             subdomain: subdomain.to_string(),
             port,
+            static_site: None,
+            ..existing.clone()
         },
-    );
+        None => ProxyRoute::new(subdomain, port),
+    };
+    routes.insert(app_id.to_string(), route);
 
-    update_routes(&routes).await
+    apply_routes(proxy_state, routes).await
 }
 
-pub async fn remove_route(proxy_state: &ProxyState, app_id: &str) -> Result<(), String> {
+/// Serves `site` under `subdomain`; this route is all a running static app consists of.
+pub async fn add_static_route(
+    proxy_state: &ProxyState,
+    app_id: &str,
+    subdomain: &str,
+    site: StaticSite,
+) -> Result<(), AppError> {
+    check_subdomain_reserved(subdomain, crate::dns::get_local_hostname().as_deref())?;
+    check_caddy_value("Static site paths", &site.root, false)?;
     let mut routes = proxy_state.routes.lock().await;
-    routes.remove(app_id);
+    if routes
+        .iter()
+        .any(|(id, r)| id != app_id && r.subdomain == subdomain)
+    {
+        return Err(format!("Subdomain {} is already in use", subdomain).into());
+    }
 
-    update_routes(&routes).await
-}
+    let route = match routes.get(app_id) {
+        Some(existing) => ProxyRoute {
+            subdomain: subdomain.to_string(),
+            static_site: Some(site),
+            ..existing.clone()
+        },
+        None => ProxyRoute {
+            static_site: Some(site),
+            ..ProxyRoute::new(subdomain, 0)
+        },
+    };
+    routes.insert(app_id.to_string(), route);
 
-pub fn get_app_url(subdomain: &str) -> String {
-    format!("http://{}.local", subdomain)
+    apply_routes(proxy_state, routes).await
 }
 
-pub fn slugify(name: &str) -> String {
-    name.to_lowercase()
-        .chars()
-        .map(|c| if c.is_alphanumeric() { c } else { '-' })
-        .collect::<String>()
-        .split('-')
-        .filter(|s| !s.is_empty())
-        .collect::<Vec<&str>>()
-        .join("-")
-}
+/// Adds routes for ports outside our control and returns their generated ids.
+pub async fn add_manual_routes(
+    proxy_state: &ProxyState,
+    new_routes: &[(String, i32)],
+) -> Result<Vec<String>, AppError> {
+    let hostname = crate::dns::get_local_hostname();
+    for (subdomain, _) in new_routes {
+        check_subdomain_reserved(subdomain, hostname.as_deref())?;
+    }
+    let mut routes = proxy_state.routes.lock().await;
+    let mut ids = Vec::new();
 
-pub async fn is_caddy_responsive() -> bool {
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(2))
-        .build()
-        .unwrap_or_default();
+    for (subdomain, port) in new_routes {
+        if routes.values().any(|r| &r.subdomain == subdomain) {
+            return Err(format!("Subdomain {} is already in use", subdomain).into());
+        }
+        let id = format!("manual-{}", uuid::Uuid::new_v4());
+        routes.insert(
+            id.clone(),
+            ProxyRoute {
+                manual: true,
+                ..ProxyRoute::new(subdomain, *port)
+            },
+        );
+        ids.push(id);
+    }
 
-    client
-        .get("http://localhost:2019/config/")
-        .send()
-        .await
-        .map(|r| r.status().is_success())
-        .unwrap_or(false)
+    apply_routes(proxy_state, routes).await?;
+    Ok(ids)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_slugify() {
-        assert_eq!(slugify("My App"), "my-app");
+/// Adds a manual route to `port`, where the app keeps `tunnel` forwarded, and returns its id.
+pub async fn add_tunnel_route(
+    proxy_state: &ProxyState,
+    subdomain: &str,
+    port: i32,
+    tunnel: SshTunnel,
+) -> Result<String, AppError> {
+    // An empty one would route the bare domain
+    if subdomain.is_empty() {
+        return Err("The subdomain needs at least one letter or digit".into());
+    }
+    check_subdomain_reserved(subdomain, crate::dns::get_local_hostname().as_deref())?;
+    let mut routes = proxy_state.routes.lock().await;
+    if routes.values().any(|r| r.subdomain == subdomain) {
+        return Err(format!("Subdomain {} is already in use", subdomain).into());
+    }
+    let id = format!("tunnel-{}", uuid::Uuid::new_v4());
+    routes.insert(
+        id.clone(),
+        ProxyRoute {
+            manual: true,
+            tunnel: Some(tunnel),
+            ..ProxyRoute::new(subdomain, port)
+        },
+    );
+
+    apply_routes(proxy_state, routes).await?;
+    Ok(id)
+}
+
+pub async fn set_route_relay(
+    proxy_state: &ProxyState,
+    app_id: &str,
+    relay_port: Option<u16>,
+) -> Result<(), AppError> {
+    let mut routes = proxy_state.routes.lock().await;
+    let route = routes
+        .get_mut(app_id)
+        .ok_or_else(|| AppError::not_found(format!("No proxy route for app {}", app_id)))?;
+    route.relay_port = relay_port;
+
+    apply_routes(proxy_state, routes).await
+}
+
+pub async fn set_route_mocks(
+    proxy_state: &ProxyState,
+    app_id: &str,
+    mocks: Vec<MockResponse>,
+) -> Result<(), AppError> {
+    check_mocks(&mocks)?;
+    let mut routes = proxy_state.routes.lock().await;
+    let route = routes
+        .get_mut(app_id)
+        .ok_or_else(|| AppError::not_found(format!("No proxy route for app {}", app_id)))?;
+    route.mocks = mocks;
+
+    apply_routes(proxy_state, routes).await
+}
+
+pub async fn set_route_mocks_enabled(
+    proxy_state: &ProxyState,
+    app_id: &str,
+    enabled: bool,
+) -> Result<(), AppError> {
+    let mut routes = proxy_state.routes.lock().await;
+    let route = routes
+        .get_mut(app_id)
+        .ok_or_else(|| AppError::not_found(format!("No proxy route for app {}", app_id)))?;
+    route.mocks_enabled = enabled;
+
+    apply_routes(proxy_state, routes).await
+}
+
+pub async fn set_route_wildcard(
+    proxy_state: &ProxyState,
+    app_id: &str,
+    wildcard: bool,
+) -> Result<(), AppError> {
+    let mut routes = proxy_state.routes.lock().await;
+    let route = routes
+        .get_mut(app_id)
+        .ok_or_else(|| AppError::not_found(format!("No proxy route for app {}", app_id)))?;
+    route.wildcard = wildcard;
+
+    apply_routes(proxy_state, routes).await
+}
+
+pub async fn set_route_headers(
+    proxy_state: &ProxyState,
+    app_id: &str,
+    headers: ForwardHeaders,
+) -> Result<(), AppError> {
+    let mut routes = proxy_state.routes.lock().await;
+    let route = routes
+        .get_mut(app_id)
+        .ok_or_else(|| AppError::not_found(format!("No proxy route for app {}", app_id)))?;
+    route.headers = headers;
+
+    apply_routes(proxy_state, routes).await
+}
+
+pub async fn set_route_timeouts(
+    proxy_state: &ProxyState,
+    app_id: &str,
+    timeouts: ProxyTimeouts,
+) -> Result<(), AppError> {
+    let mut routes = proxy_state.routes.lock().await;
+    let route = routes
+        .get_mut(app_id)
+        .ok_or_else(|| AppError::not_found(format!("No proxy route for app {}", app_id)))?;
+    route.timeouts = timeouts;
+
+    apply_routes(proxy_state, routes).await
+}
+
+pub async fn set_route_upstream(
+    proxy_state: &ProxyState,
+    app_id: &str,
+    upstream: UpstreamProtocol,
+) -> Result<(), AppError> {
+    let mut routes = proxy_state.routes.lock().await;
+    let route = routes
+        .get_mut(app_id)
+        .ok_or_else(|| AppError::not_found(format!("No proxy route for app {}", app_id)))?;
+    route.upstream = upstream;
+
+    apply_routes(proxy_state, routes).await
+}
+
+pub async fn set_route_compression(
+    proxy_state: &ProxyState,
+    app_id: &str,
+    compress: Option<bool>,
+) -> Result<(), AppError> {
+    let mut routes = proxy_state.routes.lock().await;
+    let route = routes
+        .get_mut(app_id)
+        .ok_or_else(|| AppError::not_found(format!("No proxy route for app {}", app_id)))?;
+    route.compress = compress;
+
+    apply_routes(proxy_state, routes).await
+}
+
+pub async fn set_route_body_limit(
+    proxy_state: &ProxyState,
+    app_id: &str,
+    max_bytes: Option<u64>,
+) -> Result<(), AppError> {
+    let mut routes = proxy_state.routes.lock().await;
+    let route = routes
+        .get_mut(app_id)
+        .ok_or_else(|| AppError::not_found(format!("No proxy route for app {}", app_id)))?;
+    route.max_body_bytes = max_bytes;
+
+    apply_routes(proxy_state, routes).await
+}
+
+pub async fn set_route_allowed_ips(
+    proxy_state: &ProxyState,
+    app_id: &str,
+    allowed_ips: &[String],
+) -> Result<(), AppError> {
+    let allowed_ips = ip_allowlist::normalize(allowed_ips)?;
+    let mut routes = proxy_state.routes.lock().await;
+    let route = routes
+        .get_mut(app_id)
+        .ok_or_else(|| AppError::not_found(format!("No proxy route for app {}", app_id)))?;
+    route.allowed_ips = allowed_ips;
+
+    apply_routes(proxy_state, routes).await
+}
+
+/// Publishes or unpublishes a route. A disabled route keeps its subdomain and settings
+/// but is left out of the proxy config until enabled again.
+pub async fn set_route_enabled(
+    proxy_state: &ProxyState,
+    app_id: &str,
+    enabled: bool,
+) -> Result<(), AppError> {
+    let mut routes = proxy_state.routes.lock().await;
+    let route = routes
+        .get_mut(app_id)
+        .ok_or_else(|| AppError::not_found(format!("No proxy route for app {}", app_id)))?;
+    route.disabled = !enabled;
+
+    apply_routes(proxy_state, routes).await
+}
+
+/// Serves a route over HTTPS as well, with a certificate from Caddy's local CA.
+pub async fn set_route_tls(
+    proxy_state: &ProxyState,
+    app_id: &str,
+    enabled: bool,
+) -> Result<(), AppError> {
+    if enabled && proxy_state.settings().backend == ProxyBackend::Builtin {
+        return Err("HTTPS needs the Caddy proxy".into());
+    }
+    let mut routes = proxy_state.routes.lock().await;
+    let route = routes
+        .get_mut(app_id)
+        .ok_or_else(|| AppError::not_found(format!("No proxy route for app {}", app_id)))?;
+    route.tls = enabled;
+
+    apply_routes(proxy_state, routes).await
+}
+
+/// Makes a route temporary until `expires_at` (unix ms), or permanent again with None.
+pub async fn set_route_expiry(
+    proxy_state: &ProxyState,
+    app_id: &str,
+    expires_at: Option<i64>,
+) -> Result<(), AppError> {
+    let mut routes = proxy_state.routes.lock().await;
+    let route = routes
+        .get_mut(app_id)
+        .ok_or_else(|| AppError::not_found(format!("No proxy route for app {}", app_id)))?;
+    route.expires_at = expires_at;
+    // Caddy's config doesn't change, but the expiry has to survive a restart
+    proxy_state.save_routes(&routes).await;
+    Ok(())
+}
+
+/// Records a route's rate limit; the inspection relay in front of the app enforces it.
+/// `relay_port` is a relay just started for the limit, which the route then goes through.
+pub async fn set_route_rate_limit(
+    proxy_state: &ProxyState,
+    app_id: &str,
+    per_second: Option<u32>,
+    relay_port: Option<u16>,
+) -> Result<(), AppError> {
+    let mut routes = proxy_state.routes.lock().await;
+    let route = routes
+        .get_mut(app_id)
+        .ok_or_else(|| AppError::not_found(format!("No proxy route for app {}", app_id)))?;
+    route.rate_limit = per_second;
+    if relay_port.is_some() {
+        route.relay_port = relay_port;
+        return apply_routes(proxy_state, routes).await;
+    }
+    proxy_state.save_routes(&routes).await;
+    Ok(())
+}
+
+/// The routes the periodic sync keeps: one for each live app with a subdomain, keeping
+/// the options of its current route, plus manual and static routes and restored routes of
+/// apps we didn't spawn while their port answers.
+pub fn synced_routes(
+    current: &HashMap<String, ProxyRoute>,
+    live_apps: &HashMap<String, (i32, Option<String>)>,
+    dead_apps: &[String],
+    port_in_use: impl Fn(i32) -> bool,
+) -> HashMap<String, ProxyRoute> {
+    let mut routes = HashMap::new();
+    for (app_id, (port, subdomain)) in live_apps {
+        if let Some(subdomain) = subdomain {
+            let mut route = current
+                .get(app_id)
+                .cloned()
+                .unwrap_or_else(|| ProxyRoute::new(subdomain, *port));
+            route.subdomain = subdomain.clone();
+            route.port = *port;
+            routes.insert(app_id.clone(), route);
+        }
+    }
+    for (app_id, route) in current {
+        if route.manual
+            || route.static_site.is_some()
+            || (!live_apps.contains_key(app_id)
+                && !dead_apps.contains(app_id)
+                && port_in_use(route.port))
+        {
+            routes.insert(app_id.clone(), route.clone());
+        }
+    }
+    routes
+}
+
+/// Ids of the routes whose expiry has passed by `now` (unix ms).
+pub fn expired_routes(routes: &HashMap<String, ProxyRoute>, now: i64) -> Vec<String> {
+    routes
+        .iter()
+        .filter(|(_, route)| route.expires_at.is_some_and(|at| at <= now))
+        .map(|(id, _)| id.clone())
+        .collect()
+}
+
+pub async fn remove_route(proxy_state: &ProxyState, app_id: &str) -> Result<(), AppError> {
+    let mut routes = proxy_state.routes.lock().await;
+    routes.remove(app_id);
+
+    apply_routes(proxy_state, routes).await
+}
+
+/// The URL a route is opened at from other devices: HTTPS when it's on, since
+/// that's the address worth trusting the CA for.
+pub fn route_url(route: &ProxyRoute, proxy_port: u16) -> String {
+    if route.tls {
+        format!("https://{}.local", route.subdomain)
+    } else {
+        get_app_url(&route.subdomain, proxy_port)
+    }
+}
+
+pub fn get_app_url(subdomain: &str, proxy_port: u16) -> String {
+    if proxy_port == 80 {
+        format!("http://{}.local", subdomain)
+    } else {
+        format!("http://{}.local:{}", subdomain, proxy_port)
+    }
+}
+
+// DNS labels stop at 63 characters
+const MAX_LABEL_LEN: usize = 63;
+
+// Latin letters that don't decompose into an ASCII letter and an accent
+fn transliterate(c: char) -> Option<&'static str> {
+    Some(match c {
+        'ß' => "ss",
+        'æ' => "ae",
+        'œ' => "oe",
+        'ø' => "o",
+        'đ' | 'ð' => "d",
+        'ł' => "l",
+        'þ' => "th",
+        'ı' => "i",
+        _ => return None,
+    })
+}
+
+// The longest prefix of a non-ASCII slug whose punycode form fits in one label
+fn punycode_label(slug: &str) -> String {
+    let mut chars: Vec<char> = slug.chars().collect();
+    loop {
+        while chars.last() == Some(&'-') {
+            chars.pop();
+        }
+        if chars.is_empty() {
+            return String::new();
+        }
+        match idna::punycode::encode(&chars) {
+            Some(encoded) if encoded.len() + 4 <= MAX_LABEL_LEN => {
+                return format!("xn--{}", encoded)
+            }
+            Some(_) => {
+                chars.pop();
+            }
+            None => return String::new(),
+        }
+    }
+}
+
+/// Turns a name into a subdomain label. Accents and compatibility forms become plain ASCII
+/// ("Café" gives "cafe"), anything that isn't a letter or digit becomes a dash, and letters
+/// with no ASCII spelling, like Cyrillic or kana, are kept and punycode-encoded.
+pub fn slugify(name: &str) -> String {
+    let mut spelled = String::new();
+    for c in name.to_lowercase().chars() {
+        if let Some(ascii) = transliterate(c) {
+            spelled.push_str(ascii);
+            continue;
+        }
+        let base: String = std::iter::once(c)
+            .nfkd()
+            .filter(|c| !is_combining_mark(*c))
+            .collect();
+        if !base.is_empty() && base.chars().all(|c| c.is_ascii_alphanumeric()) {
+            spelled.push_str(&base);
+        } else if c.is_alphanumeric() {
+            spelled.push(c);
+        } else {
+            spelled.push('-');
+        }
+    }
+    let slug = spelled
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-");
+    if !slug.is_ascii() {
+        return punycode_label(&slug);
+    }
+    let slug: String = slug.chars().take(MAX_LABEL_LEN).collect();
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Variables a subdomain template like `{name}-{branch}` can use.
+pub const SUBDOMAIN_VARIABLES: [&str; 4] = ["name", "branch", "profile", "dir"];
+
+pub fn is_subdomain_template(subdomain: &str) -> bool {
+    subdomain.contains('{')
+}
+
+// Names of the `{variable}`s in a template, in order
+fn template_variables(template: &str) -> Vec<&str> {
+    template
+        .split('{')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+        .collect()
+}
+
+/// Subdomains no app can take: the landing page's `www` and `api`, names that pass for the
+/// proxy's own tooling, and `localhost`, which never leaves the machine.
+pub const RESERVED_SUBDOMAINS: [&str; 5] = ["www", "api", "caddy", "admin", "localhost"];
+
+/// Rejects reserved subdomains and this Mac's Bonjour name, since `<hostname>.local`
+/// already resolves to the machine itself.
+pub fn check_subdomain_reserved(subdomain: &str, hostname: Option<&str>) -> Result<(), String> {
+    let reserved = RESERVED_SUBDOMAINS
+        .iter()
+        .chain(hostname.as_slice())
+        .any(|name| name.eq_ignore_ascii_case(subdomain));
+    if reserved {
+        Err(crate::i18n::tf(
+            "error.subdomain_reserved",
+            &[("subdomain", &subdomain)],
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Rejects templates with variables `render_subdomain` doesn't know.
+pub fn check_subdomain_template(template: &str) -> Result<(), String> {
+    match template_variables(template)
+        .into_iter()
+        .find(|name| !SUBDOMAIN_VARIABLES.contains(name))
+    {
+        Some(name) => Err(format!(
+            "Unknown subdomain variable {{{}}}; use {}",
+            name,
+            SUBDOMAIN_VARIABLES.map(|v| format!("{{{}}}", v)).join(", ")
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Fills the `{variable}`s of a subdomain template and slugifies the result. Variables
+/// without a value, e.g. `{profile}` for an app started without one, drop out with their
+/// separator, so `{name}-{profile}` gives `shop` or `shop-staging`.
+pub fn render_subdomain(template: &str, values: &HashMap<&str, String>) -> String {
+    let mut rendered = template.to_string();
+    for name in template_variables(template) {
+        let value = values.get(name).map_or("", String::as_str);
+        rendered = rendered.replace(&format!("{{{}}}", name), value);
+    }
+    slugify(&rendered)
+}
+
+pub async fn is_caddy_responsive(admin: &AdminEndpoint) -> bool {
+    caddy_admin::request(
+        admin,
+        hyper::Method::GET,
+        "/config/",
+        None,
+        String::new(),
+        Duration::from_secs(2),
+    )
+    .await
+    .map(|r| r.is_success())
+    .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("My App"), "my-app");
         assert_eq!(slugify("My  App"), "my-app");
         assert_eq!(slugify("My_App_123"), "my-app-123");
         assert_eq!(slugify("  My App  "), "my-app");
+        assert_eq!(slugify("My Café ❤️"), "my-cafe");
+        assert_eq!(slugify("Straße Ærø Łódź"), "strasse-aero-lodz");
+        assert_eq!(slugify("Ｆｕｌｌ ﬁle ①"), "full-file-1");
+        assert_eq!(slugify("❤️"), "");
+
+        let cyrillic = slugify("Моё приложение");
+        assert!(cyrillic.starts_with("xn--"));
+        assert_eq!(
+            idna::punycode::decode_to_string(&cyrillic[4..]).unwrap(),
+            "моё-приложение"
+        );
+        // Kana keep their voicing marks
+        assert_eq!(
+            idna::punycode::decode_to_string(&slugify("ガイド")[4..]).unwrap(),
+            "ガイド"
+        );
+
+        assert_eq!(slugify(&"a".repeat(80)).len(), 63);
+        assert_eq!(slugify(&format!("{}-b", "a".repeat(62))), "a".repeat(62));
+        let long = slugify(&"я".repeat(80));
+        assert!(long.starts_with("xn--") && long.len() <= 63);
+    }
+
+    #[test]
+    fn test_render_subdomain() {
+        let values = HashMap::from([
+            ("name", "Shop".to_string()),
+            ("branch", "feature/Login".to_string()),
+        ]);
+        assert_eq!(
+            render_subdomain("{name}-{branch}", &values),
+            "shop-feature-login"
+        );
+        assert_eq!(render_subdomain("{name}-{profile}", &values), "shop");
+        assert_eq!(render_subdomain("api.{name}", &values), "api-shop");
+        assert_eq!(render_subdomain(&"{name}".repeat(20), &values).len(), 63);
+
+        assert!(is_subdomain_template("{name}-{branch}"));
+        assert!(!is_subdomain_template("shop"));
+        assert!(check_subdomain_template("{name}-{dir}").is_ok());
+        assert!(check_subdomain_reserved("shop", Some("studio")).is_ok());
+        assert!(check_subdomain_reserved("www", None).is_err());
+        assert!(check_subdomain_reserved("Admin", None).is_err());
+        assert!(check_subdomain_reserved("studio", Some("studio")).is_err());
+        assert!(check_subdomain_reserved("api-v2", None).is_ok());
+        assert!(check_subdomain_template("{name}-{user}").is_err());
     }
 
     #[test]
     fn test_generate_caddyfile_empty() {
         let routes = HashMap::new();
-        let content = generate_caddyfile(&routes);
+        let content = generate_caddyfile(&routes, "unix//tmp/admin.sock|0666", None);
         assert!(content.contains("auto_https off"));
+        assert!(content.contains("admin unix//tmp/admin.sock|0666"));
         assert!(content.contains("No apps configured"));
     }
 
     #[test]
     fn test_generate_caddyfile_with_routes() {
+        let mut routes = HashMap::new();
+        routes.insert("app1".to_string(), ProxyRoute::new("my-app", 3000));
+        let content = generate_caddyfile(&routes, "localhost:2019", None);
+        assert!(content.contains("http://my-app.local {"));
+        assert!(content.contains("reverse_proxy localhost:3000"));
+        assert!(content.contains(&format!("output file {} {{", CADDY_ACCESS_LOG)));
+
+        let content = generate_caddyfile(&routes, "localhost:2019", Some("macbook.local"));
+        assert!(content.contains("http://my-app.local, http://my-app.macbook.local {"));
+    }
+
+    #[test]
+    fn test_generate_caddyfile_with_headers() {
+        let mut routes = HashMap::new();
+        routes.insert(
+            "app1".to_string(),
+            ProxyRoute {
+                relay_port: Some(45000),
+                headers: ForwardHeaders {
+                    rewrite_host: true,
+                    forwarded_for: false,
+                    ..ForwardHeaders::default()
+                },
+                ..ProxyRoute::new("my-app", 3000)
+            },
+        );
+        let content = generate_caddyfile(&routes, "localhost:2019", None);
+        assert!(content.contains(
+            "\treverse_proxy localhost:45000 {\n\t\theader_up Host localhost:3000\n\t\theader_up -X-Forwarded-For\n\t}\n"
+        ));
+        assert!(content.contains("reverse_proxy @websockets localhost:3000 {"));
+        assert!(!content.contains("-X-Forwarded-Proto"));
+
+        let route: ProxyRoute = serde_json::from_str(r#"{"subdomain":"a","port":1}"#).unwrap();
+        assert!(route.headers.is_default());
+        assert!(route.headers.forwarded_host);
+    }
+
+    #[test]
+    fn test_generate_caddyfile_with_timeouts() {
+        let mut routes = HashMap::new();
+        routes.insert(
+            "app1".to_string(),
+            ProxyRoute {
+                timeouts: ProxyTimeouts {
+                    read_secs: Some(3600),
+                    idle_secs: Some(600),
+                    streaming: true,
+                    ..ProxyTimeouts::default()
+                },
+                ..ProxyRoute::new("events", 3000)
+            },
+        );
+        let content = generate_caddyfile(&routes, "localhost:2019", None);
+        assert!(content.contains(
+            "\treverse_proxy localhost:3000 {\n\t\tflush_interval -1\n\t\ttransport http {\n\t\t\tread_timeout 3600s\n\t\t\tkeepalive 600s\n\t\t}\n\t}\n"
+        ));
+        assert!(!content.contains("write_timeout"));
+    }
+
+    #[test]
+    fn test_generate_caddyfile_with_upstream() {
+        let mut routes = HashMap::new();
+        routes.insert(
+            "app1".to_string(),
+            ProxyRoute {
+                upstream: UpstreamProtocol::H2c,
+                relay_port: Some(45000),
+                ..ProxyRoute::new("grpc", 50051)
+            },
+        );
+        routes.insert(
+            "app2".to_string(),
+            ProxyRoute {
+                upstream: UpstreamProtocol::HttpsInsecure,
+                ..ProxyRoute::new("secure", 8443)
+            },
+        );
+        let content = generate_caddyfile(&routes, "localhost:2019", None);
+        assert!(content.contains("\t\tprotocols h1 h2 h2c\n"));
+        assert!(content.contains("\treverse_proxy h2c://localhost:50051\n"));
+        assert!(!content.contains("45000"));
+        assert!(content.contains(
+            "\treverse_proxy https://localhost:8443 {\n\t\ttransport http {\n\t\t\ttls_insecure_skip_verify\n\t\t}\n\t}\n"
+        ));
+
+        routes.remove("app1");
+        let content = generate_caddyfile(&routes, "localhost:2019", None);
+        assert!(!content.contains("h2c"));
+    }
+
+    #[test]
+    fn test_generate_caddyfile_with_wildcard() {
+        let mut routes = HashMap::new();
+        routes.insert(
+            "app1".to_string(),
+            ProxyRoute {
+                wildcard: true,
+                ..ProxyRoute::new("my-app", 3000)
+            },
+        );
+        let content = generate_caddyfile(&routes, "localhost:2019", Some("macbook.local"));
+        assert!(content.contains(
+            "http://my-app.local, http://my-app.macbook.local, http://*.my-app.macbook.local {"
+        ));
+        // Without the DNS responder nothing would resolve the tenant names
+        let content = generate_caddyfile(&routes, "localhost:2019", None);
+        assert!(content.contains("http://my-app.local {"));
+    }
+
+    #[test]
+    fn test_generate_caddyfile_with_tls() {
+        let mut routes = HashMap::new();
+        routes.insert("app1".to_string(), ProxyRoute::new("my-app", 3000));
+        let content = generate_caddyfile(&routes, "localhost:2019", None);
+        assert!(content.contains("\tauto_https off\n"));
+
+        routes.get_mut("app1").unwrap().tls = true;
+        let content = generate_caddyfile(&routes, "localhost:2019", Some("macbook.local"));
+        assert!(content.contains("\tauto_https disable_redirects\n"));
+        assert!(content.contains(
+            "http://my-app.local, http://my-app.macbook.local, https://my-app.local, https://my-app.macbook.local {\n\ttls internal\n"
+        ));
+    }
+
+    #[test]
+    fn test_root_certificate() {
+        let body = r#"{"id":"local","root_certificate":"-----BEGIN CERTIFICATE-----\nMIIB\n-----END CERTIFICATE-----\n"}"#;
+        assert!(root_certificate(body)
+            .unwrap()
+            .ends_with("-----END CERTIFICATE-----\n"));
+        assert_eq!(root_certificate(r#"{"id":"local"}"#), None);
+        assert_eq!(root_certificate("not found"), None);
+    }
+
+    #[test]
+    fn test_generate_caddyfile_with_relay() {
+        let mut routes = HashMap::new();
+        routes.insert(
+            "app1".to_string(),
+            ProxyRoute {
+                relay_port: Some(45000),
+                ..ProxyRoute::new("my-app", 3000)
+            },
+        );
+        let content = generate_caddyfile(&routes, "localhost:2019", None);
+        assert!(content.contains("reverse_proxy @websockets localhost:3000"));
+        assert!(content.contains("reverse_proxy localhost:45000"));
+    }
+
+    #[test]
+    fn test_expired_routes() {
+        let mut routes = HashMap::new();
+        routes.insert("app1".to_string(), ProxyRoute::new("shop", 3000));
+        routes.insert(
+            "app2".to_string(),
+            ProxyRoute {
+                expires_at: Some(1_000),
+                ..ProxyRoute::new("blog", 4000)
+            },
+        );
+        routes.insert(
+            "app3".to_string(),
+            ProxyRoute {
+                expires_at: Some(5_000),
+                ..ProxyRoute::new("docs", 5000)
+            },
+        );
+        assert!(expired_routes(&routes, 999).is_empty());
+        assert_eq!(expired_routes(&routes, 1_000), ["app2"]);
+        let mut both = expired_routes(&routes, 10_000);
+        both.sort();
+        assert_eq!(both, ["app2", "app3"]);
+    }
+
+    #[test]
+    fn test_synced_routes_after_expiry() {
+        let mut routes = HashMap::new();
+        routes.insert(
+            "app1".to_string(),
+            ProxyRoute {
+                expires_at: Some(1_000),
+                tls: true,
+                ..ProxyRoute::new("shop", 3000)
+            },
+        );
+        let mut live_apps = HashMap::new();
+        live_apps.insert("app1".to_string(), (3000, Some("shop".to_string())));
+        live_apps.insert("app2".to_string(), (4000, None));
+        assert_eq!(synced_routes(&routes, &live_apps, &[], |_| true), routes);
+
+        // Expiry removes the route and the subdomain the app was launched with
+        for app_id in expired_routes(&routes, 1_000) {
+            routes.remove(&app_id);
+            live_apps.insert(app_id, (3000, None));
+        }
+        assert!(synced_routes(&routes, &live_apps, &[], |_| true).is_empty());
+    }
+
+    #[test]
+    fn test_host_drift() {
+        let mut routes = HashMap::new();
+        routes.insert("app1".to_string(), ProxyRoute::new("shop", 3000));
+        routes.insert("app2".to_string(), ProxyRoute::new("blog", 4000));
+        let live = BTreeSet::from([
+            "shop.local".to_string(),
+            "shop.macbook.local".to_string(),
+            "deleted.local".to_string(),
+        ]);
+
+        let drift = host_drift(&routes, Some("macbook.local"), &live);
+        assert_eq!(drift.orphaned, ["deleted.local"]);
+        assert_eq!(drift.missing, ["blog.local", "blog.macbook.local"]);
+        assert!(host_drift(&HashMap::new(), None, &BTreeSet::new()).is_empty());
+    }
+
+    #[test]
+    fn test_disabled_route_is_left_out() {
+        let mut routes = HashMap::new();
+        routes.insert("app1".to_string(), ProxyRoute::new("shop", 3000));
+        routes.insert(
+            "app2".to_string(),
+            ProxyRoute {
+                disabled: true,
+                ..ProxyRoute::new("blog", 4000)
+            },
+        );
+        let content = generate_caddyfile(&routes, "localhost:2019", None);
+        assert!(content.contains("http://shop.local"));
+        assert!(!content.contains("blog.local"));
+
+        let live = BTreeSet::from(["shop.local".to_string()]);
+        assert!(host_drift(&routes, None, &live).is_empty());
+
+        routes.remove("app1");
+        let content = generate_caddyfile(&routes, "localhost:2019", None);
+        assert!(content.contains("No apps configured yet"));
+    }
+
+    #[test]
+    fn test_app_for_host() {
+        let mut routes = HashMap::new();
+        routes.insert("app1".to_string(), ProxyRoute::new("shop", 3000));
+        routes.insert(
+            "app2".to_string(),
+            ProxyRoute {
+                wildcard: true,
+                ..ProxyRoute::new("tenants", 4000)
+            },
+        );
+        let domain = Some("macbook.local");
+        assert_eq!(
+            app_for_host(&routes, None, "Shop.local:80"),
+            Some("app1".to_string())
+        );
+        assert_eq!(
+            app_for_host(&routes, domain, "shop.macbook.local"),
+            Some("app1".to_string())
+        );
+        assert_eq!(
+            app_for_host(&routes, domain, "acme.tenants.macbook.local"),
+            Some("app2".to_string())
+        );
+        assert_eq!(app_for_host(&routes, None, "acme.tenants.local"), None);
+        assert_eq!(app_for_host(&routes, domain, "blog.local"), None);
+    }
+
+    #[test]
+    fn test_check_custom_config() {
+        let mut routes = HashMap::new();
+        routes.insert("app1".to_string(), ProxyRoute::new("shop", 3000));
+        let admin = AdminEndpoint::Tcp("localhost:2019".to_string());
+        let config = |listen: &str, hosts: &[&str]| {
+            serde_json::json!({
+                "admin": { "listen": listen },
+                "apps": { "http": { "servers": { "srv0": { "routes": [
+                    { "match": [{ "host": hosts }] }
+                ] } } } }
+            })
+        };
+
+        let ok = config("localhost:2019", &["shop.local", "extra.local"]);
+        assert!(check_custom_config(&ok, &routes, None, &admin).is_ok());
+        let moved = config("localhost:2020", &["shop.local"]);
+        assert!(check_custom_config(&moved, &routes, None, &admin)
+            .unwrap_err()
+            .contains("admin.listen"));
+        let dropped = config("localhost:2019", &["other.local"]);
+        assert!(check_custom_config(&dropped, &routes, None, &admin)
+            .unwrap_err()
+            .contains("shop.local"));
+        assert!(check_custom_config(&serde_json::json!([]), &routes, None, &admin).is_err());
+    }
+
+    #[test]
+    fn test_generate_caddyfile_with_static_site() {
         let mut routes = HashMap::new();
         routes.insert(
             "app1".to_string(),
             ProxyRoute {
-                subdomain: "my-app".to_string(),
-                port: 3000,
+                static_site: Some(StaticSite {
+                    root: "/Users/me/site/dist".to_string(),
+                    spa: true,
+                }),
+                ..ProxyRoute::new("docs", 0)
             },
         );
-        let content = generate_caddyfile(&routes);
-        assert!(content.contains("my-app.local"));
+        let content = generate_caddyfile(&routes, "localhost:2019", None);
+        assert!(content.contains("root * \"/Users/me/site/dist\""));
+        assert!(content.contains("try_files {path} {path}/ /index.html"));
+        assert!(content.contains("file_server"));
+        assert!(!content.contains("reverse_proxy"));
+    }
+
+    #[test]
+    fn test_generate_caddyfile_with_body_limit() {
+        let mut routes = HashMap::new();
+        routes.insert(
+            "app1".to_string(),
+            ProxyRoute {
+                max_body_bytes: Some(10 * 1024 * 1024),
+                ..ProxyRoute::new("uploads", 3000)
+            },
+        );
+        let content = generate_caddyfile(&routes, "localhost:2019", None);
+        assert!(content.contains("\trequest_body {\n\t\tmax_size 10485760\n\t}\n"));
+
+        routes.insert("app1".to_string(), ProxyRoute::new("uploads", 3000));
+        assert!(!generate_caddyfile(&routes, "localhost:2019", None).contains("request_body"));
+    }
+
+    #[test]
+    fn test_generate_caddyfile_with_compression() {
+        let site = StaticSite {
+            root: "/Users/me/site/dist".to_string(),
+            spa: false,
+        };
+        let mut routes = HashMap::new();
+        routes.insert(
+            "docs".to_string(),
+            ProxyRoute {
+                static_site: Some(site.clone()),
+                ..ProxyRoute::new("docs", 0)
+            },
+        );
+        routes.insert("api".to_string(), ProxyRoute::new("shop", 3000));
+        let content = generate_caddyfile(&routes, "localhost:2019", None);
+        assert_eq!(content.matches("\tencode zstd gzip\n").count(), 1);
+
+        routes.get_mut("docs").unwrap().compress = Some(false);
+        routes.get_mut("api").unwrap().compress = Some(true);
+        let content = generate_caddyfile(&routes, "localhost:2019", None);
+        let shop = content.find("http://shop.local").unwrap();
+        assert!(content[shop..].contains("encode zstd gzip"));
+        assert_eq!(content.matches("encode").count(), 1);
+    }
+
+    #[test]
+    fn test_generate_caddyfile_with_mocks() {
+        let mut route = ProxyRoute::new("my-app", 3000);
+        route.mocks = vec![MockResponse {
+            path: Some("/api/*".to_string()),
+            status: 503,
+            headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+            body: r#"{"error":"down"}"#.to_string(),
+        }];
+
+        let mut routes = HashMap::new();
+        routes.insert("app1".to_string(), route.clone());
+        assert!(!generate_caddyfile(&routes, "localhost:2019", None).contains("respond"));
+
+        route.mocks_enabled = true;
+        routes.insert("app1".to_string(), route);
+        let content = generate_caddyfile(&routes, "localhost:2019", None);
+        assert!(content.contains("@mock0 path /api/*"));
+        assert!(content.contains("header @mock0 Content-Type \"application/json\""));
+        assert!(content.contains(r#"respond @mock0 "{\"error\":\"down\"}" 503"#));
         assert!(content.contains("reverse_proxy localhost:3000"));
     }
+
+    #[test]
+    fn test_quote_caddy() {
+        assert_eq!(quote_caddy(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(quote_caddy(r#"{"a":"\"}"#), r#"`{"a":"\"}`"#);
+        assert_eq!(quote_caddy(r#"`a\"#), r#""`a\\""#);
+
+        let mock = |path: &str, header: &str, body: &str| MockResponse {
+            path: Some(path.to_string()),
+            status: 200,
+            headers: vec![(header.to_string(), "text/plain".to_string())],
+            body: body.to_string(),
+        };
+        assert!(check_mocks(&[mock("/api/*", "Content-Type", "a\nb")]).is_ok());
+        assert!(check_mocks(&[mock("/api/* {", "Content-Type", "")]).is_err());
+        assert!(check_mocks(&[mock("/api/*", "X-A\nrespond", "")]).is_err());
+        assert!(check_mocks(&[mock("/api/*", "Content-Type", "a\u{0}")]).is_err());
+    }
+
+    #[test]
+    fn test_generate_caddyfile_with_allowed_ips() {
+        let mut routes = HashMap::new();
+        routes.insert(
+            "app1".to_string(),
+            ProxyRoute {
+                allowed_ips: vec!["192.168.1.23".to_string(), "10.0.0.0/24".to_string()],
+                ..ProxyRoute::new("my-app", 3000)
+            },
+        );
+        let content = generate_caddyfile(&routes, "localhost:2019", None);
+        assert!(content.contains("@blocked not remote_ip 127.0.0.1/8 ::1 192.168.1.23 10.0.0.0/24"));
+        assert!(content.contains("respond @blocked \"Forbidden\" 403"));
+
+        routes.insert("app1".to_string(), ProxyRoute::new("my-app", 3000));
+        assert!(!generate_caddyfile(&routes, "localhost:2019", None).contains("remote_ip"));
+    }
+
+    #[test]
+    fn test_locate_config_error() {
+        let mut routes = HashMap::new();
+        routes.insert("app1".to_string(), ProxyRoute::new("my-app", 3000));
+        let content = generate_caddyfile(&routes, "localhost:2019", None);
+
+        let error = locate_config_error(
+            &content,
+            "adapting config using caddyfile: Caddyfile:7 - Error during parsing: unrecognized directive: oops",
+        );
+        assert_eq!(error.line, Some(7));
+        assert_eq!(error.site.as_deref(), Some("http://my-app.local"));
+
+        let error = locate_config_error(&content, "Caddyfile:2 - bad global option");
+        assert_eq!(error.line, Some(2));
+        assert_eq!(error.site, None);
+
+        let error = locate_config_error(&content, "connection refused");
+        assert_eq!(error.line, None);
+        assert_eq!(error.site, None);
+    }
+
+    #[test]
+    fn test_get_app_url() {
+        assert_eq!(get_app_url("my-app", 80), "http://my-app.local");
+        assert_eq!(get_app_url("my-app", 8080), "http://my-app.local:8080");
+    }
 }
@@ -0,0 +1,153 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+// Virtualenv folder names checked in the project, in the order tools default to them
+const VENV_DIRS: [&str; 3] = [".venv", "venv", "env"];
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PythonTool {
+    Uv,
+    Poetry,
+    Pip,
+}
+
+impl PythonTool {
+    // Prefix that runs a command inside the tool's environment; plain venvs go through PATH instead
+    fn run_prefix(self) -> &'static str {
+        match self {
+            PythonTool::Uv => "uv run ",
+            PythonTool::Poetry => "poetry run ",
+            PythonTool::Pip => "",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct PythonProject {
+    pub name: Option<String>,
+    pub tool: PythonTool,
+    pub venv: Option<String>,
+    /// Suggested dev server command, reading the port from `$PORT`
+    pub command: Option<String>,
+}
+
+pub fn is_python_project(dir: &Path) -> bool {
+    dir.join("pyproject.toml").is_file() || dir.join("requirements.txt").is_file()
+}
+
+/// The project's own virtualenv, whose `bin` goes first on PATH when the app starts.
+pub fn venv_dir(dir: &Path) -> Option<PathBuf> {
+    VENV_DIRS
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|venv| venv.join("bin").join("python").exists())
+}
+
+pub fn detect(dir: &Path) -> Option<PythonProject> {
+    if !is_python_project(dir) {
+        return None;
+    }
+
+    let pyproject = std::fs::read_to_string(dir.join("pyproject.toml")).unwrap_or_default();
+    let requirements = std::fs::read_to_string(dir.join("requirements.txt")).unwrap_or_default();
+
+    let tool = if dir.join("uv.lock").exists() {
+        PythonTool::Uv
+    } else if dir.join("poetry.lock").exists() || pyproject.contains("[tool.poetry]") {
+        PythonTool::Poetry
+    } else {
+        PythonTool::Pip
+    };
+
+    let dependencies = format!("{}\n{}", pyproject, requirements).to_lowercase();
+    Some(PythonProject {
+        name: project_name(&pyproject),
+        tool,
+        venv: venv_dir(dir).map(|v| v.to_string_lossy().to_string()),
+        command: suggest_command(dir, &dependencies)
+            .map(|command| format!("{}{}", tool.run_prefix(), command)),
+    })
+}
+
+// `name = "..."` under [project] (PEP 621) or [tool.poetry]
+fn project_name(pyproject: &str) -> Option<String> {
+    crate::project::toml_name(pyproject, "[project]")
+        .or_else(|| crate::project::toml_name(pyproject, "[tool.poetry]"))
+}
+
+fn suggest_command(dir: &Path, dependencies: &str) -> Option<String> {
+    if dir.join("manage.py").is_file() {
+        return Some("python manage.py runserver $PORT".to_string());
+    }
+
+    if dependencies.contains("fastapi") || dependencies.contains("uvicorn") {
+        let module = [
+            ("main.py", "main:app"),
+            ("app/main.py", "app.main:app"),
+            ("src/main.py", "src.main:app"),
+            ("app.py", "app:app"),
+        ]
+        .into_iter()
+        .find(|(file, _)| dir.join(file).is_file())
+        .map(|(_, module)| module)?;
+        return Some(format!("uvicorn {} --reload --port $PORT", module));
+    }
+
+    if dependencies.contains("flask") {
+        return Some("flask run --port $PORT".to_string());
+    }
+
+    ["main.py", "app.py"]
+        .into_iter()
+        .find(|file| dir.join(file).is_file())
+        .map(|file| format!("python {}", file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_name() {
+        let pyproject = r#"
+[build-system]
+name = "not-this"
+
+[project]
+name = "billing-api"
+version = "0.1.0"
+"#;
+        assert_eq!(project_name(pyproject), Some("billing-api".to_string()));
+        assert_eq!(
+            project_name("[tool.poetry]\nname = 'legacy'\n"),
+            Some("legacy".to_string())
+        );
+        assert_eq!(project_name(""), None);
+    }
+
+    #[test]
+    fn test_detect() {
+        let dir = std::env::temp_dir().join(format!("mla-python-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join(".venv/bin")).unwrap();
+        std::fs::write(dir.join(".venv/bin/python"), "").unwrap();
+        std::fs::write(
+            dir.join("pyproject.toml"),
+            "[project]\nname = \"api\"\ndependencies = [\"fastapi\", \"uvicorn\"]\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("uv.lock"), "").unwrap();
+        std::fs::write(dir.join("main.py"), "").unwrap();
+
+        let project = detect(&dir).unwrap();
+        assert_eq!(project.name.as_deref(), Some("api"));
+        assert_eq!(project.tool, PythonTool::Uv);
+        assert_eq!(
+            project.command.as_deref(),
+            Some("uv run uvicorn main:app --reload --port $PORT")
+        );
+        assert_eq!(venv_dir(&dir), Some(dir.join(".venv")));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
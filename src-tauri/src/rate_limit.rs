@@ -0,0 +1,70 @@
+use std::time::{Duration, Instant};
+
+/// Token bucket letting `per_second` requests a second through on average, in bursts of
+/// up to as many.
+#[derive(Debug)]
+pub struct RateLimiter {
+    per_second: u32,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(per_second: u32) -> Self {
+        let per_second = per_second.max(1);
+        Self {
+            per_second,
+            tokens: per_second as f64,
+            refilled_at: Instant::now(),
+        }
+    }
+
+    pub fn per_second(&self) -> u32 {
+        self.per_second
+    }
+
+    /// Takes a token for a request arriving at `now`, or returns how long until one is free.
+    pub fn acquire(&mut self, now: Instant) -> Result<(), Duration> {
+        let rate = self.per_second as f64;
+        let elapsed = now.saturating_duration_since(self.refilled_at);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * rate).min(rate);
+        self.refilled_at = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / rate))
+        }
+    }
+}
+
+/// The Retry-After value for a wait, in whole seconds rounded up.
+pub fn retry_after_secs(wait: Duration) -> u64 {
+    wait.as_secs_f64().ceil().max(1.0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter() {
+        let mut limiter = RateLimiter::new(2);
+        let start = limiter.refilled_at;
+        assert!(limiter.acquire(start).is_ok());
+        assert!(limiter.acquire(start).is_ok());
+        let wait = limiter.acquire(start).unwrap_err();
+        assert_eq!(wait, Duration::from_millis(500));
+        assert_eq!(retry_after_secs(wait), 1);
+
+        // Half a second refills one token, and the bucket never holds more than a second's worth
+        assert!(limiter.acquire(start + Duration::from_millis(500)).is_ok());
+        assert!(limiter.acquire(start + Duration::from_millis(500)).is_err());
+        let later = start + Duration::from_secs(10);
+        assert!(limiter.acquire(later).is_ok());
+        assert!(limiter.acquire(later).is_ok());
+        assert!(limiter.acquire(later).is_err());
+
+        assert_eq!(RateLimiter::new(0).per_second(), 1);
+    }
+}
@@ -0,0 +1,218 @@
+use regex::Regex;
+use std::borrow::Cow;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// How long an app may go without becoming ready before it is reported as slow to start.
+pub const STARTING_TIMEOUT: Duration = Duration::from_secs(120);
+/// The allowance for an app that is building, counted from when the build began.
+/// Production builds and first compiles routinely take minutes.
+pub const BUILDING_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+/// Lines dev servers print once they accept requests, by framework. Checked against
+/// app output with color codes stripped.
+const BUILTIN_PATTERNS: [(&str, &str); 13] = [
+    (
+        "next",
+        r"✓ Ready in \d|ready - started server on|Ready on https?://",
+    ),
+    ("vite", r"(?i)\bvite v\S+\s+ready in \d"),
+    ("astro", r"(?i)\bastro\s+v\S+\s+ready in \d"),
+    ("remix", r"\[remix-serve\] https?://"),
+    ("webpack", r"(?i)\bcompiled successfully\b"),
+    ("rails", r"\* Listening on (?:tcp|http|https|ssl|unix)://"),
+    (
+        "django",
+        r"Starting (?:development|ASGI/\S+) server at https?://",
+    ),
+    ("flask", r"\* Running on https?://"),
+    (
+        "uvicorn",
+        r"Uvicorn running on https?://|Application startup complete",
+    ),
+    ("phoenix", r"Running \S+\.Endpoint with \S+ at"),
+    ("spring", r"Started \S+ in [\d.]+ seconds"),
+    ("hugo", r"Web Server is available at"),
+    (
+        "server",
+        r"(?i)\b(?:server (?:is )?(?:listening|running|started)|listening (?:on|at) (?:port )?(?:https?://|:?\d))",
+    ),
+];
+
+/// Lines build tools print when they begin a build, by tool. An app printing one is
+/// building rather than stuck, e.g. `next build && next start`.
+const BUILD_PATTERNS: [(&str, &str); 7] = [
+    ("next", r"Creating an optimized production build"),
+    (
+        "vite",
+        r"(?i)\bvite v\S+\s+building (?:for|SSR bundle for) ",
+    ),
+    (
+        "astro",
+        r"(?i)\bbuilding (?:client|server|static entrypoints)\b",
+    ),
+    (
+        "webpack",
+        r"(?i)\bwebpack\b.*\bcompiling\b|\[webpack\.Progress\]",
+    ),
+    ("cargo", r"^\s*Compiling \S+ v\d"),
+    ("gradle", r"^> Task :\S*(?:compile|build)"),
+    (
+        "dotnet",
+        r"^\s*Determining projects to restore|^Building\.\.\.$",
+    ),
+];
+
+fn build_patterns() -> &'static [(&'static str, Regex)] {
+    static BUILD: OnceLock<Vec<(&'static str, Regex)>> = OnceLock::new();
+    BUILD.get_or_init(|| {
+        BUILD_PATTERNS
+            .iter()
+            .filter_map(|(name, pattern)| Some((*name, Regex::new(pattern).ok()?)))
+            .collect()
+    })
+}
+
+/// The tool whose build `line` says has begun, if any.
+pub fn build_started(line: &str) -> Option<&'static str> {
+    let line = strip_ansi(line);
+    build_patterns()
+        .iter()
+        .find(|(_, re)| re.is_match(&line))
+        .map(|(name, _)| *name)
+}
+
+fn builtin() -> &'static [(String, Regex)] {
+    static BUILTIN: OnceLock<Vec<(String, Regex)>> = OnceLock::new();
+    BUILTIN.get_or_init(|| {
+        BUILTIN_PATTERNS
+            .iter()
+            .filter_map(|(name, pattern)| Some((name.to_string(), Regex::new(pattern).ok()?)))
+            .collect()
+    })
+}
+
+/// Rejects user patterns that don't compile, naming the first bad one.
+pub fn validate(patterns: &[String]) -> Result<(), String> {
+    for pattern in patterns {
+        Regex::new(pattern).map_err(|e| format!("Invalid pattern {}: {}", pattern, e))?;
+    }
+    Ok(())
+}
+
+/// Watches an app's output for the line that says it's ready.
+pub struct ReadyDetector {
+    custom: Vec<Regex>,
+}
+
+impl ReadyDetector {
+    pub fn new(custom_patterns: &[String]) -> Self {
+        Self {
+            custom: custom_patterns
+                .iter()
+                .filter(|p| !p.trim().is_empty())
+                .filter_map(|p| Regex::new(p).ok())
+                .collect(),
+        }
+    }
+
+    /// What recognized the line: "custom" for user patterns, otherwise the framework.
+    pub fn matches(&self, line: &str) -> Option<String> {
+        let line = strip_ansi(line);
+        if self.custom.iter().any(|re| re.is_match(&line)) {
+            return Some("custom".to_string());
+        }
+        builtin()
+            .iter()
+            .find(|(_, re)| re.is_match(&line))
+            .map(|(name, _)| name.clone())
+    }
+}
+
+// Vite and friends color the interesting parts, e.g. "ready in \x1b[1m312\x1b[22m ms"
+fn strip_ansi(line: &str) -> Cow<'_, str> {
+    if !line.contains('\x1b') {
+        return Cow::Borrowed(line);
+    }
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+            continue;
+        }
+        if chars.next() == Some('[') {
+            // Skip parameters up to the final byte of the CSI sequence
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+    Cow::Owned(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_patterns() {
+        let detector = ReadyDetector::new(&[]);
+        let cases = [
+            (" ✓ Ready in 1234ms", "next"),
+            (
+                "  \x1b[32m\x1b[1mVITE\x1b[22m v5.2.0\x1b[39m  \x1b[2mready in \x1b[0m\x1b[1m312\x1b[22m\x1b[2m ms\x1b[22m",
+                "vite",
+            ),
+            ("* Listening on http://127.0.0.1:3000", "rails"),
+            (
+                "Starting development server at http://127.0.0.1:8000/",
+                "django",
+            ),
+            (
+                "INFO:     Uvicorn running on http://127.0.0.1:8000 (Press CTRL+C to quit)",
+                "uvicorn",
+            ),
+            ("Server listening on port 4000", "server"),
+            ("listening on :8080", "server"),
+        ];
+        for (line, framework) in cases {
+            assert_eq!(
+                detector.matches(line).as_deref(),
+                Some(framework),
+                "{}",
+                line
+            );
+        }
+        assert_eq!(detector.matches("Compiling app v0.1.0"), None);
+        assert_eq!(detector.matches("ready - compiling..."), None);
+    }
+
+    #[test]
+    fn test_build_started() {
+        let cases = [
+            ("   Creating an optimized production build ...", "next"),
+            (
+                "\x1b[36mvite v5.2.0 \x1b[32mbuilding for production...\x1b[39m",
+                "vite",
+            ),
+            ("   Compiling serde v1.0.200", "cargo"),
+            ("> Task :app:compileKotlin", "gradle"),
+        ];
+        for (line, tool) in cases {
+            assert_eq!(build_started(line), Some(tool), "{}", line);
+        }
+        assert_eq!(build_started(" ✓ Ready in 1234ms"), None);
+        assert_eq!(build_started("Compiled successfully"), None);
+    }
+
+    #[test]
+    fn test_custom_patterns() {
+        let detector = ReadyDetector::new(&["^worker booted$".to_string()]);
+        assert_eq!(detector.matches("worker booted").as_deref(), Some("custom"));
+        assert!(validate(&["worker (booted".to_string()]).is_err());
+        assert!(validate(&["worker booted".to_string()]).is_ok());
+    }
+}
@@ -0,0 +1,72 @@
+use crate::terminal::shell_quote;
+use std::collections::HashMap;
+
+/// Local command that runs `command` in `dir` on `host` over the system ssh, forwarding
+/// `local_port` to the port the remote app listens on.
+///
+/// `-tt` gives the remote command a terminal, so it gets SIGHUP and exits when the local
+/// ssh is killed instead of lingering on the other machine.
+pub fn ssh_command(
+    host: &str,
+    dir: &str,
+    command: &str,
+    env: &HashMap<String, String>,
+    remote_port: i32,
+    local_port: i32,
+) -> String {
+    let mut assignments: Vec<String> = env
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, shell_quote(value)))
+        .collect();
+    assignments.sort();
+    assignments.push(format!("PORT={}", remote_port));
+
+    // The remote login shell runs the command, so its profile's PATH applies like it does locally
+    let remote_script = format!(
+        "cd {} && {} exec \"$SHELL\" -lc {}",
+        quote_dir(dir),
+        assignments.join(" "),
+        shell_quote(command)
+    );
+    format!(
+        "exec ssh -tt -o ExitOnForwardFailure=yes -o ServerAliveInterval=15 \
+         -L {}:127.0.0.1:{} {} {}",
+        local_port,
+        remote_port,
+        shell_quote(host),
+        shell_quote(&remote_script)
+    )
+}
+
+// Leaves a leading `~` outside the quotes so the remote shell still expands it
+fn quote_dir(dir: &str) -> String {
+    match dir.strip_prefix("~/") {
+        Some(rest) => format!("~/{}", shell_quote(rest)),
+        None if dir == "~" => dir.to_string(),
+        None => shell_quote(dir),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ssh_command() {
+        let env = HashMap::from([("NODE_ENV".to_string(), "development".to_string())]);
+        assert_eq!(
+            ssh_command(
+                "me@desktop",
+                "~/code/shop",
+                "bun run dev",
+                &env,
+                3000,
+                41000
+            ),
+            "exec ssh -tt -o ExitOnForwardFailure=yes -o ServerAliveInterval=15 \
+             -L 41000:127.0.0.1:3000 'me@desktop' \
+             'cd ~/'\\''code/shop'\\'' && NODE_ENV='\\''development'\\'' PORT=3000 \
+             exec \"$SHELL\" -lc '\\''bun run dev'\\'''"
+        );
+    }
+}
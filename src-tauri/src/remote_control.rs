@@ -0,0 +1,869 @@
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, mpsc, oneshot, watch, Mutex};
+use uuid::Uuid;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::error::AppError;
+
+pub const REMOTE_PORT: u16 = 4041;
+
+// A pairing code is good for one attempt within this window
+const PAIRING_TTL: Duration = Duration::from_secs(300);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+// Starting an app may include its build step
+const CALL_TIMEOUT: Duration = Duration::from_secs(180);
+const MAX_FRAME: usize = 1 << 20;
+
+// 32 symbols without look-alikes (no I, O, 0 or 1), so each character is 5 bits
+const CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const CODE_LENGTH: usize = 10;
+
+/// A machine allowed to control this one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairedDevice {
+    pub id: String,
+    pub name: String,
+    // Hex of the 32-byte secret both sides derive session keys from
+    pub secret: String,
+    pub paired_at: u64,
+}
+
+/// A machine this one can control, as handed out when pairing with it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteHost {
+    // Our device id on that host
+    pub id: String,
+    pub name: String,
+    pub address: String,
+    pub secret: String,
+}
+
+/// Pairings in both directions, kept with the other preferences.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RemoteControlSettings {
+    pub enabled: bool,
+    pub devices: Vec<PairedDevice>,
+    pub hosts: Vec<RemoteHost>,
+}
+
+/// What the frontend sees of the pairings; secrets stay in the backend.
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteControlStatus {
+    pub port: Option<u16>,
+    pub devices: Vec<PairingInfo>,
+    pub hosts: Vec<PairingInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PairingInfo {
+    pub id: String,
+    pub name: String,
+    pub address: Option<String>,
+    pub paired_at: Option<u64>,
+}
+
+impl RemoteControlSettings {
+    pub fn status(&self, port: Option<u16>) -> RemoteControlStatus {
+        RemoteControlStatus {
+            port,
+            devices: self
+                .devices
+                .iter()
+                .map(|d| PairingInfo {
+                    id: d.id.clone(),
+                    name: d.name.clone(),
+                    address: None,
+                    paired_at: Some(d.paired_at),
+                })
+                .collect(),
+            hosts: self
+                .hosts
+                .iter()
+                .map(|h| PairingInfo {
+                    id: h.id.clone(),
+                    name: h.name.clone(),
+                    address: Some(h.address.clone()),
+                    paired_at: None,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// One app as listed to a paired device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteApp {
+    pub id: String,
+    pub name: String,
+    pub port: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogLine {
+    pub app_id: String,
+    pub stream: String,
+    pub message: String,
+}
+
+/// What a paired device can ask for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "call", rename_all = "snake_case")]
+pub enum Call {
+    ListApps,
+    // `passcode` answers the host's app lock when it uses one
+    StartApp {
+        id: String,
+        #[serde(default)]
+        passcode: Option<String>,
+    },
+    StopApp {
+        id: String,
+        #[serde(default)]
+        passcode: Option<String>,
+    },
+    // Returns the buffered lines and streams new ones until `unwatch_logs`
+    WatchLogs {
+        id: String,
+    },
+    UnwatchLogs {
+        id: String,
+    },
+}
+
+/// The host side of remote control: runs calls and keeps track of paired devices.
+pub trait RemoteHandler: Clone + Send + Sync + 'static {
+    fn call(&self, call: Call) -> impl Future<Output = Result<serde_json::Value, AppError>> + Send;
+    fn devices(&self) -> Vec<PairedDevice>;
+    fn paired(&self, device: PairedDevice);
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Mode {
+    Pair,
+    Session,
+}
+
+// Sent in the clear; everything after it is encrypted
+#[derive(Serialize, Deserialize)]
+struct Hello {
+    mode: Mode,
+    device_id: Option<String>,
+    public_key: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Message {
+    Pair {
+        device_name: String,
+    },
+    Paired {
+        device_id: String,
+        secret: String,
+        host_name: String,
+    },
+    Request {
+        id: u64,
+        call: Call,
+    },
+    Response {
+        id: u64,
+        value: Option<serde_json::Value>,
+        error: Option<String>,
+    },
+    Log(LogLine),
+}
+
+struct Server {
+    port: u16,
+    // Dropping it closes the listener and every open session
+    _shutdown: watch::Sender<()>,
+}
+
+/// Opt-in management of this machine from paired machines on the network. Pairing takes a
+/// one-time code shown here; each session after that is keyed from an X25519 exchange and
+/// the secret handed out at pairing, and encrypted with ChaCha20-Poly1305.
+pub struct RemoteControl {
+    server: Mutex<Option<Server>>,
+    pairing: Arc<std::sync::Mutex<Option<(String, Instant)>>>,
+    logs: broadcast::Sender<LogLine>,
+}
+
+impl RemoteControl {
+    pub fn new() -> Self {
+        Self {
+            server: Mutex::new(None),
+            pairing: Arc::new(std::sync::Mutex::new(None)),
+            logs: broadcast::Sender::new(256),
+        }
+    }
+
+    pub async fn start<H: RemoteHandler>(&self, port: u16, handler: H) -> Result<(), String> {
+        let mut server = self.server.lock().await;
+        if server.as_ref().is_some_and(|s| s.port == port) {
+            return Ok(());
+        }
+        server.take();
+
+        let listener = TcpListener::bind(("0.0.0.0", port))
+            .await
+            .map_err(|e| format!("Failed to start remote control on port {}: {}", port, e))?;
+        let (shutdown_tx, shutdown_rx) = watch::channel(());
+        let host = Host {
+            handler,
+            pairing: self.pairing.clone(),
+            logs: self.logs.clone(),
+            shutdown: shutdown_rx,
+        };
+        tauri::async_runtime::spawn(run_server(listener, host));
+
+        *server = Some(Server {
+            port,
+            _shutdown: shutdown_tx,
+        });
+        log::info!("Remote control listening on port {}", port);
+        Ok(())
+    }
+
+    pub async fn stop(&self) {
+        self.server.lock().await.take();
+        if let Ok(mut pairing) = self.pairing.lock() {
+            pairing.take();
+        }
+    }
+
+    pub async fn running_port(&self) -> Option<u16> {
+        self.server.lock().await.as_ref().map(|s| s.port)
+    }
+
+    /// A fresh code for pairing one device, replacing any earlier one.
+    pub fn create_pairing_code(&self) -> Result<String, AppError> {
+        let bytes: [u8; CODE_LENGTH] = random_bytes()?;
+        let code: String = bytes
+            .iter()
+            .map(|b| CODE_ALPHABET[(*b as usize) % CODE_ALPHABET.len()] as char)
+            .collect();
+        if let Ok(mut pairing) = self.pairing.lock() {
+            *pairing = Some((code.clone(), Instant::now()));
+        }
+        Ok(format!("{}-{}", &code[..5], &code[5..]))
+    }
+
+    /// Passes an app's log line on to sessions watching that app.
+    pub fn forward_log(&self, app_id: &str, stream: &str, message: &str) {
+        if self.logs.receiver_count() == 0 {
+            return;
+        }
+        let _ = self.logs.send(LogLine {
+            app_id: app_id.to_string(),
+            stream: stream.to_string(),
+            message: message.to_string(),
+        });
+    }
+}
+
+impl Default for RemoteControl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone)]
+struct Host<H> {
+    handler: H,
+    pairing: Arc<std::sync::Mutex<Option<(String, Instant)>>>,
+    logs: broadcast::Sender<LogLine>,
+    shutdown: watch::Receiver<()>,
+}
+
+async fn run_server<H: RemoteHandler>(listener: TcpListener, host: Host<H>) {
+    let mut shutdown = host.shutdown.clone();
+    loop {
+        let (stream, remote) = tokio::select! {
+            _ = shutdown.changed() => break,
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    log::error!("Remote control accept failed: {}", e);
+                    continue;
+                }
+            },
+        };
+
+        let host = host.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = serve_connection(stream, host).await {
+                log::warn!("Remote control connection from {}: {}", remote, e);
+            }
+        });
+    }
+}
+
+async fn serve_connection<H: RemoteHandler>(
+    stream: TcpStream,
+    host: Host<H>,
+) -> Result<(), String> {
+    let (mut reader, mut writer) = stream.into_split();
+    let hello: Hello = serde_json::from_slice(&read_frame(&mut reader).await?)
+        .map_err(|e| format!("Invalid hello: {}", e))?;
+    let client_public = PublicKey::from(parse_key(&hello.public_key)?);
+
+    let secret = StaticSecret::from(random_bytes::<32>().map_err(|e| e.to_string())?);
+    let public = PublicKey::from(&secret);
+    let reply = serde_json::json!({ "public_key": to_hex(public.as_bytes()) });
+    write_frame(&mut writer, reply.to_string().as_bytes()).await?;
+    let shared = secret.diffie_hellman(&client_public);
+
+    match hello.mode {
+        Mode::Pair => {
+            // Taken whether or not the attempt succeeds, so a code can't be guessed at
+            let code = host
+                .pairing
+                .lock()
+                .ok()
+                .and_then(|mut pairing| pairing.take())
+                .filter(|(_, created)| created.elapsed() < PAIRING_TTL)
+                .map(|(code, _)| code)
+                .ok_or("No pairing code is active")?;
+            let keys = derive_keys(code.as_bytes(), shared.as_bytes(), &client_public, &public);
+            let mut incoming = FrameReader::new(reader, keys.client_to_host);
+            let mut outgoing = FrameWriter::new(writer, keys.host_to_client);
+
+            let Message::Pair { device_name } = incoming.recv().await? else {
+                return Err("Expected a pairing request".to_string());
+            };
+            let device = PairedDevice {
+                id: Uuid::new_v4().to_string(),
+                name: device_name,
+                secret: to_hex(&random_bytes::<32>().map_err(|e| e.to_string())?),
+                paired_at: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or_default(),
+            };
+            host.handler.paired(device.clone());
+            log::info!("Paired remote device {}", device.name);
+            outgoing
+                .send(&Message::Paired {
+                    device_id: device.id,
+                    secret: device.secret,
+                    host_name: host_name(),
+                })
+                .await
+        }
+        Mode::Session => {
+            let device_id = hello.device_id.ok_or("Missing device id")?;
+            let device = host
+                .handler
+                .devices()
+                .into_iter()
+                .find(|d| d.id == device_id)
+                .ok_or("Unknown device")?;
+            let keys = derive_keys(
+                &parse_key(&device.secret)?,
+                shared.as_bytes(),
+                &client_public,
+                &public,
+            );
+            let incoming = FrameReader::new(reader, keys.client_to_host);
+            let outgoing = FrameWriter::new(writer, keys.host_to_client);
+            run_session(device, incoming, outgoing, host).await
+        }
+    }
+}
+
+async fn run_session<H: RemoteHandler>(
+    device: PairedDevice,
+    mut incoming: FrameReader,
+    mut outgoing: FrameWriter,
+    mut host: Host<H>,
+) -> Result<(), String> {
+    let (out_tx, mut out_rx) = mpsc::channel::<Message>(64);
+    let writer = tauri::async_runtime::spawn(async move {
+        while let Some(message) = out_rx.recv().await {
+            if outgoing.send(&message).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let watched = Arc::new(std::sync::Mutex::new(HashSet::<String>::new()));
+    let mut logs = host.logs.subscribe();
+    let forwarder = {
+        let watched = watched.clone();
+        let out_tx = out_tx.clone();
+        tauri::async_runtime::spawn(async move {
+            loop {
+                let line = match logs.recv().await {
+                    Ok(line) => line,
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                let wanted = watched
+                    .lock()
+                    .map(|w| w.contains(&line.app_id))
+                    .unwrap_or(false);
+                if wanted && out_tx.send(Message::Log(line)).await.is_err() {
+                    break;
+                }
+            }
+        })
+    };
+
+    let result = loop {
+        let message = tokio::select! {
+            _ = host.shutdown.changed() => break Ok(()),
+            message = incoming.recv() => match message {
+                Ok(message) => message,
+                Err(e) => break Err(e),
+            },
+        };
+        let Message::Request { id, call } = message else {
+            continue;
+        };
+        // A revoked device loses its open sessions too
+        if !host.handler.devices().iter().any(|d| d.id == device.id) {
+            break Err(format!("{} is no longer paired", device.name));
+        }
+
+        if let Ok(mut watched) = watched.lock() {
+            match &call {
+                Call::WatchLogs { id } => {
+                    watched.insert(id.clone());
+                }
+                Call::UnwatchLogs { id } => {
+                    watched.remove(id);
+                }
+                _ => {}
+            }
+        }
+        let handler = host.handler.clone();
+        let out_tx = out_tx.clone();
+        tauri::async_runtime::spawn(async move {
+            let result = handler.call(call).await;
+            let _ = out_tx.send(response(id, result)).await;
+        });
+    };
+
+    forwarder.abort();
+    writer.abort();
+    result
+}
+
+fn response(id: u64, result: Result<serde_json::Value, AppError>) -> Message {
+    match result {
+        Ok(value) => Message::Response {
+            id,
+            value: Some(value),
+            error: None,
+        },
+        Err(e) => Message::Response {
+            id,
+            value: None,
+            error: Some(e.message().to_string()),
+        },
+    }
+}
+
+/// Pairs with the host at `address` (`host` or `host:port`) using the code it shows.
+pub async fn pair(address: &str, code: &str, device_name: &str) -> Result<RemoteHost, AppError> {
+    let address = with_default_port(address);
+    let code = normalize_code(code);
+    let (mut incoming, mut outgoing) =
+        handshake(&address, Mode::Pair, None, code.as_bytes()).await?;
+    outgoing
+        .send(&Message::Pair {
+            device_name: device_name.to_string(),
+        })
+        .await?;
+    match incoming.recv().await {
+        Ok(Message::Paired {
+            device_id,
+            secret,
+            host_name,
+        }) => Ok(RemoteHost {
+            id: device_id,
+            name: host_name,
+            address,
+            secret,
+        }),
+        // The host drops the connection when the code doesn't decrypt our request
+        _ => Err(AppError::permission_denied(
+            "Pairing failed; check the code and generate a new one on the host",
+        )),
+    }
+}
+
+type Pending =
+    Arc<std::sync::Mutex<HashMap<u64, oneshot::Sender<Result<serde_json::Value, String>>>>>;
+
+/// An open, authenticated connection to a paired host.
+pub struct RemoteSession {
+    requests: mpsc::Sender<Message>,
+    pending: Pending,
+    next_id: AtomicU64,
+    closed: Arc<AtomicBool>,
+}
+
+impl RemoteSession {
+    /// Log lines for watched apps go to `on_log` as they arrive.
+    pub async fn connect(
+        host: &RemoteHost,
+        on_log: impl Fn(LogLine) + Send + 'static,
+    ) -> Result<Self, AppError> {
+        let (mut incoming, mut outgoing) = handshake(
+            &host.address,
+            Mode::Session,
+            Some(&host.id),
+            &parse_key(&host.secret)?,
+        )
+        .await?;
+
+        let (requests, mut out_rx) = mpsc::channel::<Message>(16);
+        tauri::async_runtime::spawn(async move {
+            while let Some(message) = out_rx.recv().await {
+                if outgoing.send(&message).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let pending: Pending = Arc::default();
+        let reader_pending = pending.clone();
+        let closed = Arc::new(AtomicBool::new(false));
+        let reader_closed = closed.clone();
+        tauri::async_runtime::spawn(async move {
+            while let Ok(message) = incoming.recv().await {
+                match message {
+                    Message::Response { id, value, error } => {
+                        let waiter = reader_pending.lock().ok().and_then(|mut p| p.remove(&id));
+                        if let Some(waiter) = waiter {
+                            let _ = waiter.send(match error {
+                                Some(error) => Err(error),
+                                None => Ok(value.unwrap_or_default()),
+                            });
+                        }
+                    }
+                    Message::Log(line) => on_log(line),
+                    _ => {}
+                }
+            }
+            // Waiters see the connection close instead of timing out
+            reader_closed.store(true, Ordering::Relaxed);
+            if let Ok(mut pending) = reader_pending.lock() {
+                pending.clear();
+            }
+        });
+
+        Ok(Self {
+            requests,
+            pending,
+            next_id: AtomicU64::new(1),
+            closed,
+        })
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Relaxed) || self.requests.is_closed()
+    }
+
+    pub async fn call(&self, call: Call) -> Result<serde_json::Value, AppError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.insert(id, tx);
+        }
+        self.requests
+            .send(Message::Request { id, call })
+            .await
+            .map_err(|_| "Connection to the remote host is closed".to_string())?;
+        match tokio::time::timeout(CALL_TIMEOUT, rx).await {
+            Ok(Ok(result)) => result.map_err(AppError::from),
+            Ok(Err(_)) => Err("Connection to the remote host was lost".to_string().into()),
+            Err(_) => {
+                if let Ok(mut pending) = self.pending.lock() {
+                    pending.remove(&id);
+                }
+                Err("The remote host didn't answer in time".to_string().into())
+            }
+        }
+    }
+}
+
+async fn handshake(
+    address: &str,
+    mode: Mode,
+    device_id: Option<&str>,
+    material: &[u8],
+) -> Result<(FrameReader, FrameWriter), String> {
+    let stream = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(address))
+        .await
+        .map_err(|_| format!("Timed out connecting to {}", address))?
+        .map_err(|e| format!("Failed to connect to {}: {}", address, e))?;
+    let (mut reader, mut writer) = stream.into_split();
+
+    let secret = StaticSecret::from(random_bytes::<32>().map_err(|e| e.to_string())?);
+    let public = PublicKey::from(&secret);
+    let hello = Hello {
+        mode,
+        device_id: device_id.map(str::to_string),
+        public_key: to_hex(public.as_bytes()),
+    };
+    let hello = serde_json::to_vec(&hello).map_err(|e| e.to_string())?;
+    write_frame(&mut writer, &hello).await?;
+
+    let reply: serde_json::Value = serde_json::from_slice(&read_frame(&mut reader).await?)
+        .map_err(|e| format!("Invalid reply from {}: {}", address, e))?;
+    let host_public = PublicKey::from(parse_key(reply["public_key"].as_str().unwrap_or_default())?);
+    let shared = secret.diffie_hellman(&host_public);
+
+    let keys = derive_keys(material, shared.as_bytes(), &public, &host_public);
+    Ok((
+        FrameReader::new(reader, keys.host_to_client),
+        FrameWriter::new(writer, keys.client_to_host),
+    ))
+}
+
+struct SessionKeys {
+    client_to_host: [u8; 32],
+    host_to_client: [u8; 32],
+}
+
+// Both directions get their own key, bound to the exchange and to the pairing code or
+// device secret, so a peer without that secret can't produce a frame we accept
+fn derive_keys(
+    material: &[u8],
+    shared: &[u8; 32],
+    client: &PublicKey,
+    host: &PublicKey,
+) -> SessionKeys {
+    let derive = |direction: &[u8]| -> [u8; 32] {
+        Sha256::new()
+            .chain_update(b"my-little-apps remote ")
+            .chain_update(direction)
+            .chain_update(material)
+            .chain_update(shared)
+            .chain_update(client.as_bytes())
+            .chain_update(host.as_bytes())
+            .finalize()
+            .into()
+    };
+    SessionKeys {
+        client_to_host: derive(b"client-to-host"),
+        host_to_client: derive(b"host-to-client"),
+    }
+}
+
+// Nonces are a per-direction counter, which also rejects replayed or reordered frames
+struct Cipher {
+    aead: ChaCha20Poly1305,
+    counter: u64,
+}
+
+impl Cipher {
+    fn new(key: [u8; 32]) -> Self {
+        Self {
+            aead: ChaCha20Poly1305::new(Key::from_slice(&key)),
+            counter: 0,
+        }
+    }
+
+    fn next_nonce(&mut self) -> Nonce {
+        let mut nonce = [0u8; 12];
+        nonce[4..].copy_from_slice(&self.counter.to_be_bytes());
+        self.counter += 1;
+        *Nonce::from_slice(&nonce)
+    }
+
+    fn seal(&mut self, plaintext: &[u8]) -> Vec<u8> {
+        let nonce = self.next_nonce();
+        self.aead
+            .encrypt(&nonce, plaintext)
+            .expect("encrypting in memory can't fail")
+    }
+
+    fn open(&mut self, ciphertext: &[u8]) -> Result<Vec<u8>, String> {
+        let nonce = self.next_nonce();
+        self.aead
+            .decrypt(&nonce, ciphertext)
+            .map_err(|_| "Message failed authentication".to_string())
+    }
+}
+
+struct FrameReader {
+    reader: OwnedReadHalf,
+    cipher: Cipher,
+}
+
+impl FrameReader {
+    fn new(reader: OwnedReadHalf, key: [u8; 32]) -> Self {
+        Self {
+            reader,
+            cipher: Cipher::new(key),
+        }
+    }
+
+    async fn recv(&mut self) -> Result<Message, String> {
+        let frame = read_frame(&mut self.reader).await?;
+        let plaintext = self.cipher.open(&frame)?;
+        serde_json::from_slice(&plaintext).map_err(|e| format!("Invalid message: {}", e))
+    }
+}
+
+struct FrameWriter {
+    writer: OwnedWriteHalf,
+    cipher: Cipher,
+}
+
+impl FrameWriter {
+    fn new(writer: OwnedWriteHalf, key: [u8; 32]) -> Self {
+        Self {
+            writer,
+            cipher: Cipher::new(key),
+        }
+    }
+
+    async fn send(&mut self, message: &Message) -> Result<(), String> {
+        let plaintext = serde_json::to_vec(message).map_err(|e| e.to_string())?;
+        let frame = self.cipher.seal(&plaintext);
+        write_frame(&mut self.writer, &frame).await
+    }
+}
+
+async fn read_frame(reader: &mut (impl AsyncRead + Unpin)) -> Result<Vec<u8>, String> {
+    let len = reader
+        .read_u32()
+        .await
+        .map_err(|e| format!("Connection closed: {}", e))? as usize;
+    if len > MAX_FRAME {
+        return Err(format!("Frame of {} bytes is too large", len));
+    }
+    let mut frame = vec![0; len];
+    reader
+        .read_exact(&mut frame)
+        .await
+        .map_err(|e| format!("Connection closed: {}", e))?;
+    Ok(frame)
+}
+
+async fn write_frame(writer: &mut (impl AsyncWrite + Unpin), frame: &[u8]) -> Result<(), String> {
+    let sent = match writer.write_u32(frame.len() as u32).await {
+        Ok(()) => writer.write_all(frame).await,
+        Err(e) => Err(e),
+    };
+    sent.map_err(|e| format!("Failed to send: {}", e))
+}
+
+fn random_bytes<const N: usize>() -> Result<[u8; N], AppError> {
+    let mut bytes = [0u8; N];
+    getrandom::getrandom(&mut bytes).map_err(|e| format!("No randomness available: {}", e))?;
+    Ok(bytes)
+}
+
+// Codes are shown as "ABCDE-FGHJK" but typed any which way
+fn normalize_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+fn with_default_port(address: &str) -> String {
+    let address = address.trim();
+    if address
+        .rsplit_once(':')
+        .is_some_and(|(_, port)| port.parse::<u16>().is_ok())
+    {
+        address.to_string()
+    } else {
+        format!("{}:{}", address, REMOTE_PORT)
+    }
+}
+
+pub fn host_name() -> String {
+    sysinfo::System::host_name().unwrap_or_else(|| "Mac".to_string())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn parse_key(hex: &str) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    if hex.len() != 64 || !hex.is_ascii() {
+        return Err("Invalid key".to_string());
+    }
+    for (i, byte) in key.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).map_err(|_| "Invalid key")?;
+    }
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_keys() {
+        let client = StaticSecret::from([1u8; 32]);
+        let host = StaticSecret::from([2u8; 32]);
+        let (client_public, host_public) = (PublicKey::from(&client), PublicKey::from(&host));
+        let client_shared = client.diffie_hellman(&host_public);
+        let host_shared = host.diffie_hellman(&client_public);
+
+        let code = normalize_code("abcde-fghjk");
+        assert_eq!(code, "ABCDEFGHJK");
+        let client_keys = derive_keys(
+            code.as_bytes(),
+            client_shared.as_bytes(),
+            &client_public,
+            &host_public,
+        );
+        let host_keys = derive_keys(
+            code.as_bytes(),
+            host_shared.as_bytes(),
+            &client_public,
+            &host_public,
+        );
+        assert_eq!(client_keys.client_to_host, host_keys.client_to_host);
+        assert_ne!(client_keys.client_to_host, client_keys.host_to_client);
+
+        let mut sender = Cipher::new(client_keys.client_to_host);
+        let mut receiver = Cipher::new(host_keys.client_to_host);
+        let first = sender.seal(b"list apps");
+        let second = sender.seal(b"stop app");
+        assert_eq!(receiver.open(&first).unwrap(), b"list apps");
+        // Replaying the first frame fails, since the counter has moved on
+        assert!(receiver.open(&first).is_err());
+
+        // A wrong code derives different keys
+        let guessed = derive_keys(
+            b"ABCDEFGHJX",
+            host_shared.as_bytes(),
+            &client_public,
+            &host_public,
+        );
+        assert!(Cipher::new(guessed.client_to_host).open(&second).is_err());
+    }
+
+    #[test]
+    fn test_address_and_hex() {
+        assert_eq!(with_default_port("studio.local"), "studio.local:4041");
+        assert_eq!(with_default_port("192.168.1.5:5000"), "192.168.1.5:5000");
+        assert_eq!(parse_key(&to_hex(&[7u8; 32])).unwrap(), [7u8; 32]);
+        assert!(parse_key("abc").is_err());
+    }
+}
@@ -0,0 +1,80 @@
+use std::path::{Path, PathBuf};
+
+/// Runs a sandboxed app's shell under a profile from `profile`.
+pub const SANDBOX_EXEC: &str = "/usr/bin/sandbox-exec";
+
+/// Directories every sandboxed app may write to: compilers and package managers keep
+/// temporary files there.
+const SCRATCH_DIRS: [&str; 3] = ["/private/tmp", "/private/var/folders", "/dev"];
+
+/// A sandbox profile for running untrusted code, e.g. an example repo from the internet.
+/// The app can serve on `port` and reach itself there, but has no other network, can't
+/// read `private`, e.g. our own data, and can only write inside `project`, temporary
+/// directories and `writable`.
+pub fn profile(project: &Path, writable: &[PathBuf], private: &[PathBuf], port: i32) -> String {
+    let mut dirs: Vec<String> = SCRATCH_DIRS.iter().map(|dir| quote(dir)).collect();
+    dirs.extend(
+        std::iter::once(project)
+            .chain(writable.iter().map(PathBuf::as_path))
+            .map(real_path),
+    );
+    let private: Vec<String> = private.iter().map(|dir| real_path(dir)).collect();
+    // With no paths the rule would cover every file
+    let deny_read = if private.is_empty() {
+        String::new()
+    } else {
+        format!("(deny file-read*{})\n", subpaths(&private))
+    };
+    format!(
+        r#"(version 1)
+(allow default)
+(deny network-outbound)
+(allow network-outbound (remote ip "localhost:{}"))
+{}(deny file-write*)
+(allow file-read* file-write*{})
+"#,
+        port,
+        deny_read,
+        subpaths(&dirs)
+    )
+}
+
+// Sandbox rules match real paths, so /tmp has to be /private/tmp
+fn real_path(dir: &Path) -> String {
+    let dir = std::fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+    quote(&dir.to_string_lossy())
+}
+
+fn subpaths(dirs: &[String]) -> String {
+    dirs.iter()
+        .map(|dir| format!("\n    (subpath {})", dir))
+        .collect()
+}
+
+// A string literal in the profile language
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile() {
+        let profile = profile(
+            Path::new("/Users/me/Downloads/some \"demo\""),
+            &[PathBuf::from("/Users/me/.my-little-apps/logs")],
+            &[PathBuf::from("/Users/me/.my-little-apps")],
+            3000,
+        );
+        assert!(profile.contains("(deny network-outbound)"));
+        assert!(profile.contains(r#"(allow network-outbound (remote ip "localhost:3000"))"#));
+        assert!(profile.contains("(deny file-read*\n    (subpath \"/Users/me/.my-little-apps\"))"));
+        assert!(profile.contains(r#"(subpath "/Users/me/Downloads/some \"demo\"")"#));
+        // Its own log can still be written, since the later rule wins
+        assert!(profile.contains(r#"(subpath "/Users/me/.my-little-apps/logs")"#));
+        assert!(profile.contains(r#"(subpath "/private/tmp")"#));
+        assert!(!super::profile(Path::new("/p"), &[], &[], 3000).contains("(deny file-read*"));
+    }
+}
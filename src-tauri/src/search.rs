@@ -0,0 +1,227 @@
+use crate::App;
+use serde::Serialize;
+
+/// An app matching a quick-switcher query. `positions` are char indices of the matched
+/// characters in the `field` it matched on, for highlighting.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct AppMatch {
+    pub id: String,
+    pub score: i32,
+    pub field: &'static str,
+    pub positions: Vec<usize>,
+}
+
+// A hit on the name beats the same hit on the subdomain, and so on down the path
+const NAME_BONUS: i32 = 30;
+const SUBDOMAIN_BONUS: i32 = 20;
+const TAG_BONUS: i32 = 10;
+const FOLDER_BONUS: i32 = 10;
+
+/// Apps matching `query`, best first. An empty query lists every app by name.
+pub fn search(apps: &[App], query: &str) -> Vec<AppMatch> {
+    let query = query.trim().to_lowercase();
+    let mut matches: Vec<(AppMatch, &str)> = apps
+        .iter()
+        .filter_map(|app| {
+            let found = if query.is_empty() {
+                Some(AppMatch {
+                    id: app.id.clone(),
+                    score: 0,
+                    field: "name",
+                    positions: Vec::new(),
+                })
+            } else {
+                best_match(app, &query)
+            };
+            found.map(|m| (m, app.name.as_str()))
+        })
+        .collect();
+    matches.sort_by(|(a, a_name), (b, b_name)| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a_name.to_lowercase().cmp(&b_name.to_lowercase()))
+    });
+    matches.into_iter().map(|(m, _)| m).collect()
+}
+
+fn best_match(app: &App, query: &str) -> Option<AppMatch> {
+    let folder = app.path.rsplit('/').find(|s| !s.is_empty()).unwrap_or("");
+    // The path matches on its last folder first, so "/Users/me/shop" ranks "shop" highly
+    let folder_offset = app.path.rfind(folder).unwrap_or(0);
+    let folder_offset = app.path[..folder_offset].chars().count();
+
+    let mut best: Option<AppMatch> = None;
+    let mut consider = |field: &'static str, bonus: i32, found: Option<(i32, Vec<usize>)>| {
+        let Some((score, positions)) = found else {
+            return;
+        };
+        if best.as_ref().is_some_and(|b| b.score >= score + bonus) {
+            return;
+        }
+        best = Some(AppMatch {
+            id: app.id.clone(),
+            score: score + bonus,
+            field,
+            positions,
+        });
+    };
+
+    consider("name", NAME_BONUS, fuzzy_match(query, &app.name));
+    if let Some(subdomain) = &app.subdomain {
+        consider("subdomain", SUBDOMAIN_BONUS, fuzzy_match(query, subdomain));
+    }
+    // Tags aren't shown as one string, so there's nothing to highlight
+    for tag in tags(app) {
+        let found = fuzzy_match(query, &tag).map(|(score, _)| (score, Vec::new()));
+        consider("tags", TAG_BONUS, found);
+    }
+    let folder_match = fuzzy_match(query, folder)
+        .map(|(score, positions)| (score, positions.iter().map(|p| p + folder_offset).collect()));
+    consider("path", FOLDER_BONUS, folder_match);
+    consider("path", 0, fuzzy_match(query, &app.path));
+    best
+}
+
+// Words an app can be found by besides its name, e.g. "postgres" or "worktree"
+fn tags(app: &App) -> Vec<String> {
+    let mut tags = Vec::new();
+    if let Some(service) = &app.service {
+        tags.push(service.clone());
+    }
+    if !app.kind.is_empty() && app.kind != "process" {
+        tags.push(app.kind.clone());
+    }
+    if let Some(host) = &app.remote_host {
+        tags.push(host.clone());
+    }
+    if app.worktree_of.is_some() {
+        tags.push("worktree".to_string());
+    }
+    if app.devcontainer {
+        tags.push("devcontainer".to_string());
+    }
+    tags
+}
+
+// Scores `text` against an already lowercased `query` whose chars must all appear in
+// order. Contiguous runs, word starts and matches near the front score higher.
+fn fuzzy_match(query: &str, text: &str) -> Option<(i32, Vec<usize>)> {
+    let query: Vec<char> = query.chars().collect();
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() || query.len() > text.len() {
+        return None;
+    }
+
+    // A contiguous hit is preferred over scattered characters, at a word start if possible
+    let substring = (0..=text.len() - query.len())
+        .filter(|&start| text[start..start + query.len()] == query[..])
+        .max_by_key(|&start| (is_word_start(&text, start), std::cmp::Reverse(start)));
+    let positions: Vec<usize> = match substring {
+        Some(start) => (start..start + query.len()).collect(),
+        None => {
+            let mut positions = Vec::with_capacity(query.len());
+            let mut from = 0;
+            for c in &query {
+                let found = text[from..].iter().position(|t| t == c)? + from;
+                positions.push(found);
+                from = found + 1;
+            }
+            positions
+        }
+    };
+
+    let mut score = 0;
+    for (i, &pos) in positions.iter().enumerate() {
+        score += 10;
+        if i > 0 && positions[i - 1] + 1 == pos {
+            score += 8;
+        }
+        if is_word_start(&text, pos) {
+            score += 6;
+        }
+    }
+    // Leading characters skipped before the first match cost a little each
+    score -= positions[0].min(15) as i32;
+    if positions.len() == text.len() {
+        score += 40;
+    } else if positions[0] == 0 && substring.is_some() {
+        score += 20;
+    }
+    Some((score, positions))
+}
+
+fn is_word_start(text: &[char], index: usize) -> bool {
+    index == 0 || matches!(text[index - 1], ' ' | '-' | '_' | '/' | '.' | '(')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(id: &str, name: &str, path: &str, subdomain: Option<&str>) -> App {
+        App {
+            id: id.to_string(),
+            name: name.to_string(),
+            path: path.to_string(),
+            command: "bun start".to_string(),
+            port: None,
+            run_on_startup: false,
+            created_at: String::new(),
+            subdomain: subdomain.map(str::to_string),
+            worktree_of: None,
+            build_command: None,
+            service: None,
+            prerequisites: None,
+            remote_host: None,
+            devcontainer: false,
+            path_entries: None,
+            shell: None,
+            kind: "process".to_string(),
+            spa: false,
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_match() {
+        assert_eq!(fuzzy_match("shp", "Shop").map(|m| m.1), Some(vec![0, 1, 3]));
+        assert_eq!(
+            fuzzy_match("api", "my-api").map(|m| m.1),
+            Some(vec![3, 4, 5])
+        );
+        assert_eq!(fuzzy_match("xyz", "shop"), None);
+        // Exact beats prefix beats a match further in
+        let exact = fuzzy_match("shop", "shop").unwrap().0;
+        let prefix = fuzzy_match("shop", "shop-admin").unwrap().0;
+        let inner = fuzzy_match("shop", "my-shop").unwrap().0;
+        let scattered = fuzzy_match("shop", "s-h-o-p").unwrap().0;
+        assert!(exact > prefix && prefix > inner && inner > scattered);
+    }
+
+    #[test]
+    fn test_search() {
+        let mut db = app("4", "Database", "/Users/me/services/pg", None);
+        db.service = Some("postgres".to_string());
+        let apps = vec![
+            app("1", "Admin", "/Users/me/shop-admin", Some("admin")),
+            app("2", "Shop", "/Users/me/shop", Some("shop")),
+            app("3", "Blog", "/Users/me/blog", Some("my-blog")),
+            db,
+        ];
+
+        let ids = |query: &str| -> Vec<String> {
+            search(&apps, query).into_iter().map(|m| m.id).collect()
+        };
+        assert_eq!(ids("shop"), vec!["2", "1"]);
+        assert_eq!(ids("postgres"), vec!["4"]);
+        assert_eq!(ids(""), vec!["1", "3", "4", "2"]);
+
+        let blog = &search(&apps, "blog")[0];
+        assert_eq!(
+            (blog.field, blog.positions.clone()),
+            ("name", vec![0, 1, 2, 3])
+        );
+        let admin = &search(&apps, "shop-ad")[0];
+        assert_eq!(admin.field, "path");
+        assert_eq!(admin.positions, (10..17).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+
+const SNAPSHOT_FILE: &str = "update_snapshot.json";
+// Public half of the release signing key, given when building a release
+const PUBKEY: Option<&str> = option_env!("UPDATER_PUBKEY");
+// Download progress is reported per chunk of a few KB, far more often than the UI needs
+pub const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// The key updates are verified with. Builds without one can't verify an update, so
+/// they don't offer any.
+pub fn pubkey() -> Option<&'static str> {
+    PUBKEY.filter(|key| !key.trim().is_empty())
+}
+
+/// A newer release the update endpoint offers.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    // Release notes
+    pub notes: Option<String>,
+    pub date: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateStage {
+    Downloading,
+    Installing,
+    Restarting,
+    // After the relaunch, while the apps that were running start again
+    Restoring,
+    Done,
+}
+
+/// Payload of `update-progress` events.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct UpdateProgress {
+    pub stage: UpdateStage,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+}
+
+impl UpdateProgress {
+    pub fn stage(stage: UpdateStage) -> Self {
+        UpdateProgress {
+            stage,
+            downloaded: 0,
+            total: None,
+        }
+    }
+}
+
+/// An app running when an update was installed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SnapshotApp {
+    pub id: String,
+    pub profile: Option<String>,
+}
+
+/// Saves the apps to start again once the updated version is up.
+pub fn save_snapshot(data_dir: &Path, apps: &[SnapshotApp]) -> Result<(), String> {
+    let content = serde_json::to_string(apps)
+        .map_err(|e| format!("Failed to serialize running apps: {}", e))?;
+    std::fs::write(data_dir.join(SNAPSHOT_FILE), content)
+        .map_err(|e| format!("Failed to save running apps: {}", e))
+}
+
+/// Apps running before the update. The file is removed, so a later launch doesn't start
+/// them again.
+pub fn take_snapshot(data_dir: &Path) -> Vec<SnapshotApp> {
+    let path = data_dir.join(SNAPSHOT_FILE);
+    let apps = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    let _ = std::fs::remove_file(path);
+    apps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot() {
+        let dir = std::env::temp_dir().join(format!("mla-update-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(take_snapshot(&dir).is_empty());
+
+        let apps = vec![
+            SnapshotApp {
+                id: "api".to_string(),
+                profile: Some("staging".to_string()),
+            },
+            SnapshotApp {
+                id: "docs".to_string(),
+                profile: None,
+            },
+        ];
+        save_snapshot(&dir, &apps).unwrap();
+        assert_eq!(take_snapshot(&dir), apps);
+        assert!(take_snapshot(&dir).is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
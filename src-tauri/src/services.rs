@@ -0,0 +1,163 @@
+use serde::Serialize;
+
+/// A database that can be added as a managed app, run in Docker or from a local install.
+#[derive(Debug, Clone, Serialize)]
+pub struct ServicePreset {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub image: &'static str,
+    /// Port the service listens on inside the container, also the suggested host port
+    pub port: i32,
+    // Where the image keeps its data; backed by a named volume so it survives restarts
+    #[serde(skip)]
+    volume: &'static str,
+    #[serde(skip)]
+    env: &'static [(&'static str, &'static str)],
+    // Readiness probe run inside the container
+    #[serde(skip)]
+    health: &'static str,
+    // Same service from binaries on PATH, storing data in the app folder; `$PORT` is the host port
+    #[serde(skip)]
+    local_command: Option<&'static str>,
+    #[serde(skip)]
+    local_health: Option<&'static str>,
+    pub supports_local: bool,
+}
+
+pub const PRESETS: [ServicePreset; 3] = [
+    ServicePreset {
+        id: "postgres",
+        name: "PostgreSQL",
+        image: "postgres:16",
+        port: 5432,
+        volume: "/var/lib/postgresql/data",
+        env: &[("POSTGRES_PASSWORD", "postgres")],
+        health: "pg_isready -U postgres",
+        local_command: Some(
+            "[ -f data/PG_VERSION ] || initdb -D data -U postgres --auth=trust; \
+             postgres -D data -p $PORT -k /tmp",
+        ),
+        local_health: Some("pg_isready -h 127.0.0.1 -p $PORT"),
+        supports_local: true,
+    },
+    ServicePreset {
+        id: "redis",
+        name: "Redis",
+        image: "redis:7",
+        port: 6379,
+        volume: "/data",
+        env: &[],
+        health: "redis-cli ping",
+        local_command: Some("redis-server --port $PORT --dir ."),
+        local_health: Some("redis-cli -p $PORT ping"),
+        supports_local: true,
+    },
+    ServicePreset {
+        id: "mysql",
+        name: "MySQL",
+        image: "mysql:8",
+        port: 3306,
+        volume: "/var/lib/mysql",
+        env: &[("MYSQL_ALLOW_EMPTY_PASSWORD", "yes")],
+        health: "mysqladmin ping -h 127.0.0.1",
+        local_command: None,
+        local_health: None,
+        supports_local: false,
+    },
+];
+
+pub fn preset(id: &str) -> Option<&'static ServicePreset> {
+    PRESETS.iter().find(|p| p.id == id)
+}
+
+/// Container name of a Docker service app, unique per service folder name.
+pub fn container_name(slug: &str) -> String {
+    format!("mla-{}", slug)
+}
+
+/// Command that runs the service in the foreground on `$PORT`. The Docker variant first
+/// removes a container left behind by a crash, since a stale one would hold the name.
+pub fn start_command(preset: &ServicePreset, slug: &str, local: bool) -> Result<String, String> {
+    if local {
+        return preset
+            .local_command
+            .map(String::from)
+            .ok_or_else(|| format!("{} can only run in Docker", preset.name));
+    }
+
+    let container = container_name(slug);
+    let mut command = format!(
+        "docker rm -f {container} >/dev/null 2>&1; docker run --rm --name {container} \
+         -p $PORT:{} -v {container}-data:{}",
+        preset.port, preset.volume
+    );
+    for (key, value) in preset.env {
+        command.push_str(&format!(" -e {}={}", key, value));
+    }
+    command.push(' ');
+    command.push_str(preset.image);
+    Ok(command)
+}
+
+/// Probe that exits 0 once the service accepts connections, for an app started with `command`.
+pub fn health_command(preset: &ServicePreset, command: &str) -> Option<String> {
+    match docker_container(command) {
+        Some(container) => Some(format!("docker exec {} {}", container, preset.health)),
+        None => preset.local_health.map(String::from),
+    }
+}
+
+/// Name given with `--name` to a `docker run` in `command`, so the container can be stopped
+/// along with the app; killing the docker client alone leaves it running.
+pub fn docker_container(command: &str) -> Option<String> {
+    let run = command.find("docker run")?;
+    let mut tokens = command[run..].split_whitespace();
+    while let Some(token) = tokens.next() {
+        if token == "--name" {
+            return tokens.next().map(String::from);
+        }
+        if let Some(name) = token.strip_prefix("--name=") {
+            return Some(name.to_string());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_command() {
+        let postgres = preset("postgres").unwrap();
+        let command = start_command(postgres, "billing-db", false).unwrap();
+        assert_eq!(
+            command,
+            "docker rm -f mla-billing-db >/dev/null 2>&1; docker run --rm --name mla-billing-db \
+             -p $PORT:5432 -v mla-billing-db-data:/var/lib/postgresql/data \
+             -e POSTGRES_PASSWORD=postgres postgres:16"
+        );
+        assert_eq!(
+            docker_container(&command),
+            Some("mla-billing-db".to_string())
+        );
+        assert_eq!(
+            health_command(postgres, &command),
+            Some("docker exec mla-billing-db pg_isready -U postgres".to_string())
+        );
+
+        let redis = preset("redis").unwrap();
+        let local = start_command(redis, "cache", true).unwrap();
+        assert_eq!(docker_container(&local), None);
+        assert_eq!(
+            health_command(redis, &local),
+            Some("redis-cli -p $PORT ping".to_string())
+        );
+
+        assert!(start_command(preset("mysql").unwrap(), "db", true).is_err());
+        assert_eq!(
+            docker_container("docker run --name=web nginx"),
+            Some("web".to_string())
+        );
+    }
+}
@@ -0,0 +1,348 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const MAX_NAME_LEN: usize = 64;
+const MINUTES_PER_DAY: u32 = 24 * 60;
+const MINUTES_PER_WEEK: u32 = 7 * MINUTES_PER_DAY;
+
+/// An app in a saved session, with the profile it was running.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SessionApp {
+    pub app_id: String,
+    pub profile: Option<String>,
+}
+
+/// A named set of apps to run together, e.g. everything for one client.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct Session {
+    pub name: String,
+    pub apps: Vec<SessionApp>,
+}
+
+pub fn check_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Give the session a name".to_string());
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(format!(
+            "Session names are at most {} characters",
+            MAX_NAME_LEN
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// What restoring a session takes.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Plan {
+    pub stop: Vec<String>,
+    pub start: Vec<SessionApp>,
+}
+
+/// Turns the running apps, with their profiles, into exactly the session's apps. Apps
+/// running another profile than the saved one are restarted on it.
+pub fn plan(session: &[SessionApp], running: &HashMap<String, Option<String>>) -> Plan {
+    let mut stop: Vec<String> = running
+        .iter()
+        .filter(|(id, profile)| {
+            !session
+                .iter()
+                .any(|app| &app.app_id == *id && &app.profile == *profile)
+        })
+        .map(|(id, _)| id.clone())
+        .collect();
+    stop.sort();
+    let start = session
+        .iter()
+        .filter(|app| running.get(&app.app_id) != Some(&app.profile))
+        .cloned()
+        .collect();
+    Plan { stop, start }
+}
+
+/// When a trigger switches sessions.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum When {
+    /// At `at`, "HH:MM" local time, on `days`: a bit per weekday, Monday first.
+    Schedule { days: u8, at: String },
+    /// When the macOS Focus mode named `mode` turns on.
+    Focus { mode: String },
+}
+
+/// Switches to a session on its own, e.g. stopping the work stack at 11pm.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Trigger {
+    #[serde(default)]
+    pub id: i64,
+    /// None stops every app.
+    pub session: Option<String>,
+    #[serde(flatten)]
+    pub when: When,
+}
+
+pub fn check_trigger(trigger: &Trigger) -> Result<(), String> {
+    match &trigger.when {
+        When::Schedule { days, at } => {
+            if *days == 0 || *days >= 1 << 7 {
+                return Err("Pick at least one day".to_string());
+            }
+            if parse_time(at).is_none() {
+                return Err(format!("{} is not a time, use HH:MM", at));
+            }
+        }
+        When::Focus { mode } => {
+            if mode.trim().is_empty() {
+                return Err("Name the Focus mode".to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+// Minutes since midnight of "HH:MM"
+fn parse_time(at: &str) -> Option<u16> {
+    let (hours, minutes) = at.split_once(':')?;
+    let hours: u16 = hours.parse().ok()?;
+    let minutes: u16 = minutes.parse().ok()?;
+    (hours < 24 && minutes < 60 && at.len() == 5).then_some(hours * 60 + minutes)
+}
+
+/// The local weekday and minute, the granularity schedules work at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalTime {
+    // 0 is Monday
+    pub weekday: u8,
+    pub minute: u16,
+}
+
+impl LocalTime {
+    // Minutes since Monday midnight
+    fn of_week(self) -> u32 {
+        u32::from(self.weekday) * MINUTES_PER_DAY + u32::from(self.minute)
+    }
+}
+
+// Parses `date "+%u %H:%M"`, which prints the weekday from 1 for Monday
+fn parse_local_time(output: &str) -> Option<LocalTime> {
+    let (weekday, at) = output.trim().split_once(' ')?;
+    let weekday: u8 = weekday.parse().ok()?;
+    if !(1..=7).contains(&weekday) {
+        return None;
+    }
+    Some(LocalTime {
+        weekday: weekday - 1,
+        minute: parse_time(at)?,
+    })
+}
+
+/// The time where the user is. There is no time zone database here, so `date` does
+/// the conversion.
+pub fn local_now() -> Option<LocalTime> {
+    let output = std::process::Command::new("date")
+        .arg("+%u %H:%M")
+        .output()
+        .ok()?;
+    parse_local_time(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// The trigger to act on at a check. `last` is the time at the previous check, and
+/// schedules fire when their time falls after it and up to `now`, so a check that comes
+/// late or across midnight misses none; of several, the latest wins. `focus` is the
+/// Focus mode that has turned on since, which counts as now. Nothing is due at the
+/// first check: opening the app shouldn't switch sessions.
+pub fn due<'a>(
+    triggers: &'a [Trigger],
+    now: LocalTime,
+    last: Option<LocalTime>,
+    focus: Option<&str>,
+) -> Option<&'a Trigger> {
+    let last = last?.of_week();
+    let elapsed = (now.of_week() + MINUTES_PER_WEEK - last) % MINUTES_PER_WEEK;
+    // Minutes between each due trigger and now
+    let ago = |trigger: &Trigger| match &trigger.when {
+        When::Schedule { days, at } => {
+            let minute = u32::from(parse_time(at)?);
+            (0..7)
+                .filter(|weekday| days & (1 << weekday) != 0)
+                .map(|weekday| {
+                    let scheduled = weekday * MINUTES_PER_DAY + minute;
+                    (scheduled + MINUTES_PER_WEEK - last) % MINUTES_PER_WEEK
+                })
+                .filter(|&after_last| after_last > 0 && after_last <= elapsed)
+                .map(|after_last| elapsed - after_last)
+                .min()
+        }
+        When::Focus { mode } => focus
+            .is_some_and(|focus| focus.eq_ignore_ascii_case(mode.trim()))
+            .then_some(0),
+    };
+    triggers
+        .iter()
+        .filter_map(|trigger| Some((ago(trigger)?, trigger)))
+        .min_by_key(|(ago, _)| *ago)
+        .map(|(_, trigger)| trigger)
+}
+
+/// The Focus mode that is on, by name. macOS has no API for it, so this reads the Do Not
+/// Disturb store, which needs Full Disk Access; without it Focus triggers never fire.
+pub fn active_focus() -> Option<String> {
+    let home = std::env::var("HOME").ok()?;
+    let dir = std::path::Path::new(&home).join("Library/DoNotDisturb/DB");
+    let read = |file: &str| -> Option<serde_json::Value> {
+        serde_json::from_slice(&std::fs::read(dir.join(file)).ok()?).ok()
+    };
+    focus_name(&read("Assertions.json")?, &read("ModeConfigurations.json")?)
+}
+
+// The name of the mode the newest assertion turned on
+fn focus_name(
+    assertions: &serde_json::Value,
+    configurations: &serde_json::Value,
+) -> Option<String> {
+    let identifier = assertions["data"][0]["storeAssertionRecords"]
+        .as_array()?
+        .last()?["assertionDetails"]["assertionDetailsModeIdentifier"]
+        .as_str()?;
+    let mode = &configurations["data"][0]["modeConfigurations"][identifier]["mode"];
+    mode["name"].as_str().map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(id: &str, profile: Option<&str>) -> SessionApp {
+        SessionApp {
+            app_id: id.to_string(),
+            profile: profile.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_plan() {
+        let session = [
+            app("api", Some("staging")),
+            app("web", None),
+            app("docs", None),
+        ];
+        let running = HashMap::from([
+            ("api".to_string(), None),
+            ("web".to_string(), None),
+            ("blog".to_string(), None),
+        ]);
+        assert_eq!(
+            plan(&session, &running),
+            Plan {
+                stop: vec!["api".to_string(), "blog".to_string()],
+                start: vec![app("api", Some("staging")), app("docs", None)],
+            }
+        );
+        assert_eq!(plan(&[], &HashMap::new()), Plan::default());
+    }
+
+    fn at(weekday: u8, minute: u16) -> LocalTime {
+        LocalTime { weekday, minute }
+    }
+
+    #[test]
+    fn test_due() {
+        let triggers = [
+            Trigger {
+                id: 1,
+                session: None,
+                // Monday to Friday
+                when: When::Schedule {
+                    days: 0b11111,
+                    at: "23:00".to_string(),
+                },
+            },
+            Trigger {
+                id: 2,
+                session: Some("work".to_string()),
+                when: When::Focus {
+                    mode: "Work".to_string(),
+                },
+            },
+        ];
+        let eleven = 23 * 60;
+        let due_id = |now, last, focus| due(&triggers, now, last, focus).map(|t| t.id);
+        assert_eq!(
+            due_id(at(0, eleven), Some(at(0, eleven - 1)), None),
+            Some(1)
+        );
+        // Once per minute, on the chosen days, not at startup
+        assert_eq!(due_id(at(0, eleven), Some(at(0, eleven)), None), None);
+        assert_eq!(due_id(at(5, eleven), Some(at(5, eleven - 1)), None), None);
+        assert_eq!(due_id(at(0, eleven), None, None), None);
+        assert_eq!(due_id(at(2, 540), Some(at(2, 540)), Some("work")), Some(2));
+        assert_eq!(due_id(at(2, 540), Some(at(2, 540)), Some("Personal")), None);
+
+        // A late check still catches the minute it skipped, but only once
+        assert_eq!(
+            due_id(at(0, eleven + 1), Some(at(0, eleven - 1)), None),
+            Some(1)
+        );
+        assert_eq!(
+            due_id(at(0, eleven + 2), Some(at(0, eleven + 1)), None),
+            None
+        );
+        // Across midnight, and from Sunday into Monday
+        let midnight = Trigger {
+            id: 3,
+            session: None,
+            when: When::Schedule {
+                days: 0b1,
+                at: "00:00".to_string(),
+            },
+        };
+        let fired =
+            |now, last| due(std::slice::from_ref(&midnight), now, Some(last), None).is_some();
+        assert!(fired(at(0, 0), at(6, 1439)));
+        assert!(fired(at(0, 1), at(6, 1438)));
+        assert!(!fired(at(6, 1439), at(6, 1438)));
+        assert!(!fired(at(1, 0), at(0, 1439)));
+    }
+
+    #[test]
+    fn test_check_trigger() {
+        let schedule = |days, at: &str| Trigger {
+            id: 0,
+            session: None,
+            when: When::Schedule {
+                days,
+                at: at.to_string(),
+            },
+        };
+        assert!(check_trigger(&schedule(1, "09:30")).is_ok());
+        assert!(check_trigger(&schedule(0, "09:30")).is_err());
+        assert!(check_trigger(&schedule(1, "24:00")).is_err());
+        assert!(check_trigger(&schedule(1, "9:30")).is_err());
+        assert_eq!(parse_local_time("7 08:05\n"), Some(at(6, 8 * 60 + 5)));
+        assert_eq!(parse_local_time("0 08:05"), None);
+    }
+
+    #[test]
+    fn test_focus_name() {
+        let assertions = serde_json::json!({"data": [{"storeAssertionRecords": [
+            {"assertionDetails": {"assertionDetailsModeIdentifier": "com.apple.focus.work"}}
+        ]}]});
+        let configurations = serde_json::json!({"data": [{"modeConfigurations": {
+            "com.apple.focus.work": {"mode": {"name": "Work"}}
+        }}]});
+        assert_eq!(
+            focus_name(&assertions, &configurations).as_deref(),
+            Some("Work")
+        );
+        let off = serde_json::json!({"data": [{"storeAssertionRecords": []}]});
+        assert_eq!(focus_name(&off, &configurations), None);
+    }
+
+    #[test]
+    fn test_check_name() {
+        assert_eq!(check_name("  client-acme ").unwrap(), "client-acme");
+        assert!(check_name(" ").is_err());
+        assert!(check_name(&"x".repeat(MAX_NAME_LEN + 1)).is_err());
+    }
+}
@@ -0,0 +1,243 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use crate::browser::BrowserPreference;
+use crate::notifications::NotificationSettings;
+
+/// A global setting, stored as JSON in the `settings` table under `name`.
+pub struct Key<T> {
+    pub name: &'static str,
+    default: fn() -> T,
+    // Rejects or normalizes a new value before it's saved
+    check: fn(T) -> Result<T, String>,
+}
+
+impl<T: Serialize + DeserializeOwned> Key<T> {
+    pub fn default_value(&self) -> T {
+        (self.default)()
+    }
+
+    pub fn check(&self, value: Value) -> Result<T, String> {
+        let value =
+            serde_json::from_value(value).map_err(|e| format!("Invalid {}: {}", self.name, e))?;
+        (self.check)(value)
+    }
+}
+
+// Lets settings of different types sit in one list
+trait AnyKey {
+    fn name(&self) -> &'static str;
+    fn default_json(&self) -> Value;
+    fn check_json(&self, value: Value) -> Result<Value, String>;
+}
+
+impl<T: Serialize + DeserializeOwned> AnyKey for Key<T> {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn default_json(&self) -> Value {
+        serde_json::to_value(self.default_value()).unwrap_or(Value::Null)
+    }
+
+    fn check_json(&self, value: Value) -> Result<Value, String> {
+        let value = self.check(value)?;
+        serde_json::to_value(value).map_err(|e| format!("Invalid {}: {}", self.name, e))
+    }
+}
+
+/// Ports picked for apps that don't ask for one, `end` excluded.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PortRange {
+    pub start: u16,
+    pub end: u16,
+}
+
+impl Default for PortRange {
+    fn default() -> Self {
+        Self {
+            start: 10000,
+            end: 60000,
+        }
+    }
+}
+
+fn check_port_range(range: PortRange) -> Result<PortRange, String> {
+    if range.start < 1024 {
+        return Err("Ports below 1024 need root".to_string());
+    }
+    // Enough room to find a free one by chance
+    if range.end <= range.start || range.end - range.start < 100 {
+        return Err("The port range needs at least 100 ports".to_string());
+    }
+    Ok(range)
+}
+
+fn default_log_retention() -> u32 {
+    200
+}
+
+fn check_log_retention(lines: u32) -> Result<u32, String> {
+    if !(50..=10_000).contains(&lines) {
+        return Err("Keep between 50 and 10000 lines of output".to_string());
+    }
+    Ok(lines)
+}
+
+fn check_notifications(mut settings: NotificationSettings) -> Result<NotificationSettings, String> {
+    for channel in settings.channels.iter_mut().filter(|c| c.id.is_empty()) {
+        channel.id = uuid::Uuid::new_v4().to_string();
+    }
+    Ok(settings)
+}
+
+pub const PORT_RANGE: Key<PortRange> = Key {
+    name: "port_range",
+    default: PortRange::default,
+    check: check_port_range,
+};
+
+/// Lines of output kept per app in the window.
+pub const LOG_RETENTION: Key<u32> = Key {
+    name: "log_retention",
+    default: default_log_retention,
+    check: check_log_retention,
+};
+
+pub const DEFAULT_BROWSER: Key<BrowserPreference> = Key {
+    name: "default_browser",
+    default: BrowserPreference::default,
+    check: Ok,
+};
+
+pub const NOTIFICATIONS: Key<NotificationSettings> = Key {
+    name: "notifications",
+    default: NotificationSettings::default,
+    check: check_notifications,
+};
+
+const KEYS: &[&dyn AnyKey] = &[
+    &PORT_RANGE,
+    &LOG_RETENTION,
+    &DEFAULT_BROWSER,
+    &NOTIFICATIONS,
+];
+
+/// Validates `value` for the setting called `name`, returning it as it should be saved.
+pub fn check(name: &str, value: Value) -> Result<Value, String> {
+    KEYS.iter()
+        .find(|key| key.name() == name)
+        .ok_or_else(|| format!("Unknown setting: {}", name))?
+        .check_json(value)
+}
+
+/// Settings earlier versions kept in preferences.json, by the key they now live under.
+pub fn legacy(preferences: &Value) -> Vec<(&'static str, Value)> {
+    [
+        ("browser", DEFAULT_BROWSER.name),
+        ("notifications", NOTIFICATIONS.name),
+    ]
+    .into_iter()
+    .filter_map(|(old, name)| Some((name, preferences.get(old)?.clone())))
+    .collect()
+}
+
+/// In-memory copy of the `settings` table, so settings can be read without a query.
+#[derive(Default)]
+pub struct SettingsStore {
+    values: Mutex<HashMap<String, Value>>,
+}
+
+impl SettingsStore {
+    pub fn load(&self, values: HashMap<String, Value>) {
+        if let Ok(mut current) = self.values.lock() {
+            *current = values;
+        }
+    }
+
+    pub fn get<T: Serialize + DeserializeOwned>(&self, key: &Key<T>) -> T {
+        let stored = self
+            .values
+            .lock()
+            .ok()
+            .and_then(|values| values.get(key.name).cloned());
+        // A value this version can't read falls back to the default
+        stored
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_else(|| key.default_value())
+    }
+
+    pub fn insert(&self, name: &str, value: Value) {
+        if let Ok(mut values) = self.values.lock() {
+            values.insert(name.to_string(), value);
+        }
+    }
+
+    /// Every known setting, with defaults for the ones never changed.
+    pub fn all(&self) -> BTreeMap<&'static str, Value> {
+        let values = match self.values.lock() {
+            Ok(values) => values.clone(),
+            Err(_) => HashMap::new(),
+        };
+        KEYS.iter()
+            .map(|key| {
+                let value = values
+                    .get(key.name())
+                    .and_then(|value| key.check_json(value.clone()).ok())
+                    .unwrap_or_else(|| key.default_json());
+                (key.name(), value)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_settings() {
+        let store = SettingsStore::default();
+        assert_eq!(store.get(&PORT_RANGE), PortRange::default());
+        assert_eq!(store.all()["log_retention"], json!(200));
+
+        let range = check("port_range", json!({"start": 20000, "end": 21000})).unwrap();
+        store.insert(PORT_RANGE.name, range);
+        assert_eq!(
+            store.get(&PORT_RANGE),
+            PortRange {
+                start: 20000,
+                end: 21000
+            }
+        );
+        assert!(check("port_range", json!({"start": 80, "end": 9000})).is_err());
+        assert!(check("port_range", json!({"start": 20000, "end": 20010})).is_err());
+        assert!(check("log_retention", json!("lots")).is_err());
+        assert!(check("theme", json!("dark")).is_err());
+
+        // Unreadable values fall back to the default
+        store.insert(LOG_RETENTION.name, json!("lots"));
+        assert_eq!(store.get(&LOG_RETENTION), 200);
+
+        let notifications = check(
+            "notifications",
+            json!({"channels": [{"id": "", "name": "phone", "target": {"type": "webhook", "url": "http://nas"}}]}),
+        )
+        .unwrap();
+        assert!(!notifications["channels"][0]["id"]
+            .as_str()
+            .unwrap()
+            .is_empty());
+
+        let preferences =
+            json!({"browser": {"browser": "arc", "private": false}, "status_page": true});
+        assert_eq!(
+            legacy(&preferences),
+            [("default_browser", preferences["browser"].clone())]
+        );
+    }
+}
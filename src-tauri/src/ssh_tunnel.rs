@@ -0,0 +1,275 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::{oneshot, Mutex};
+
+// Give up on the first connect after this and let the backoff retry it
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(20);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+// A tunnel that stayed up this long starts the backoff over when it drops
+const STABLE_AFTER: Duration = Duration::from_secs(30);
+
+/// A service on another network, reached by forwarding a local port through `ssh_host`.
+/// `target_host` is resolved on the ssh host, so it can be a name only it knows.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SshTunnel {
+    // Anything ssh accepts, including Host aliases from ~/.ssh/config
+    pub ssh_host: String,
+    pub target_host: String,
+    pub target_port: u16,
+}
+
+impl SshTunnel {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.ssh_host.trim().is_empty() || self.ssh_host.trim().starts_with('-') {
+            return Err("Enter the ssh host to tunnel through".to_string());
+        }
+        if self.target_host.trim().is_empty() || self.target_host.contains(char::is_whitespace) {
+            return Err("Enter the host the service runs on".to_string());
+        }
+        if self.target_port == 0 {
+            return Err("Enter the port the service listens on".to_string());
+        }
+        Ok(())
+    }
+
+    /// Arguments for a forward-only ssh. BatchMode makes missing keys fail fast instead
+    /// of waiting on a password prompt nobody sees.
+    pub fn ssh_args(&self, local_port: u16) -> Vec<String> {
+        let forward = format!(
+            "127.0.0.1:{}:{}:{}",
+            local_port,
+            self.target_host.trim(),
+            self.target_port
+        );
+        [
+            "-N",
+            "-o",
+            "BatchMode=yes",
+            "-o",
+            "ExitOnForwardFailure=yes",
+            "-o",
+            "ServerAliveInterval=15",
+            "-o",
+            "ServerAliveCountMax=3",
+            "-L",
+            &forward,
+            self.ssh_host.trim(),
+        ]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum TunnelStatus {
+    Connecting,
+    Up,
+    // ssh exited; `error` is the last thing it printed
+    Down { error: String },
+}
+
+struct Tunnel {
+    status: Arc<std::sync::Mutex<TunnelStatus>>,
+    stop: oneshot::Sender<()>,
+}
+
+/// Keeps one ssh process per tunnel route alive, reconnecting with backoff.
+#[derive(Default)]
+pub struct TunnelManager {
+    tunnels: Mutex<HashMap<String, Tunnel>>,
+}
+
+impl TunnelManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts supervising the tunnel for route `id`, replacing any previous one.
+    pub async fn open(&self, id: &str, tunnel: SshTunnel, local_port: u16) {
+        let status = Arc::new(std::sync::Mutex::new(TunnelStatus::Connecting));
+        let (stop_tx, stop_rx) = oneshot::channel();
+        tauri::async_runtime::spawn(supervise(
+            id.to_string(),
+            tunnel,
+            local_port,
+            status.clone(),
+            stop_rx,
+        ));
+        let previous = self.tunnels.lock().await.insert(
+            id.to_string(),
+            Tunnel {
+                status,
+                stop: stop_tx,
+            },
+        );
+        if let Some(previous) = previous {
+            let _ = previous.stop.send(());
+        }
+    }
+
+    pub async fn close(&self, id: &str) {
+        if let Some(tunnel) = self.tunnels.lock().await.remove(id) {
+            let _ = tunnel.stop.send(());
+        }
+    }
+
+    pub async fn statuses(&self) -> HashMap<String, TunnelStatus> {
+        self.tunnels
+            .lock()
+            .await
+            .iter()
+            .filter_map(|(id, t)| Some((id.clone(), t.status.lock().ok()?.clone())))
+            .collect()
+    }
+}
+
+fn set_status(status: &std::sync::Mutex<TunnelStatus>, id: &str, next: TunnelStatus) {
+    if let Ok(mut current) = status.lock() {
+        if *current != next {
+            match &next {
+                TunnelStatus::Up => log::info!(target: "success", "Tunnel {} is up", id),
+                TunnelStatus::Down { error } => log::warn!("Tunnel {} is down: {}", id, error),
+                TunnelStatus::Connecting => {}
+            }
+            *current = next;
+        }
+    }
+}
+
+async fn supervise(
+    id: String,
+    tunnel: SshTunnel,
+    local_port: u16,
+    status: Arc<std::sync::Mutex<TunnelStatus>>,
+    mut stop: oneshot::Receiver<()>,
+) {
+    let mut backoff = Duration::from_secs(1);
+    loop {
+        set_status(&status, &id, TunnelStatus::Connecting);
+        let started = std::time::Instant::now();
+        let error = tokio::select! {
+            _ = &mut stop => return,
+            error = run_once(&id, &tunnel, local_port, &status) => error,
+        };
+        set_status(&status, &id, TunnelStatus::Down { error });
+
+        if started.elapsed() >= STABLE_AFTER {
+            backoff = Duration::from_secs(1);
+        }
+        tokio::select! {
+            _ = &mut stop => return,
+            _ = tokio::time::sleep(backoff) => {}
+        }
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+// Runs ssh until it exits (it's killed on drop when stopped) and returns why it did
+async fn run_once(
+    id: &str,
+    tunnel: &SshTunnel,
+    local_port: u16,
+    status: &std::sync::Mutex<TunnelStatus>,
+) -> String {
+    let child = tokio::process::Command::new("ssh")
+        .args(tunnel.ssh_args(local_port))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => return format!("Failed to start ssh: {}", e),
+    };
+
+    let last_line = Arc::new(std::sync::Mutex::new(String::new()));
+    if let Some(stderr) = child.stderr.take() {
+        let last_line = last_line.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if let Ok(mut last) = last_line.lock() {
+                    *last = line;
+                }
+            }
+        });
+    }
+
+    let connected = async {
+        let deadline = tokio::time::Instant::now() + CONNECT_TIMEOUT;
+        while tokio::time::Instant::now() < deadline {
+            if tokio::net::TcpStream::connect(("127.0.0.1", local_port))
+                .await
+                .is_ok()
+            {
+                return true;
+            }
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        }
+        false
+    };
+    tokio::select! {
+        exit = child.wait() => return exit_reason(exit, &last_line),
+        up = connected => {
+            if !up {
+                return format!("No forward on port {} after {}s", local_port, CONNECT_TIMEOUT.as_secs());
+            }
+        }
+    }
+    set_status(status, id, TunnelStatus::Up);
+    let exit = child.wait().await;
+    exit_reason(exit, &last_line)
+}
+
+fn exit_reason(
+    exit: std::io::Result<std::process::ExitStatus>,
+    last_line: &std::sync::Mutex<String>,
+) -> String {
+    let line = last_line
+        .lock()
+        .map(|l| l.trim().to_string())
+        .unwrap_or_default();
+    match exit {
+        Ok(_) if !line.is_empty() => line,
+        Ok(exit) => format!("ssh exited ({})", exit),
+        Err(e) => format!("ssh failed: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ssh_args() {
+        let tunnel = SshTunnel {
+            ssh_host: "me@jump.example.com".to_string(),
+            target_host: "staging-api.internal".to_string(),
+            target_port: 8080,
+        };
+        assert!(tunnel.validate().is_ok());
+        let args = tunnel.ssh_args(41000);
+        assert_eq!(args[0], "-N");
+        assert!(args.contains(&"BatchMode=yes".to_string()));
+        assert_eq!(
+            args[args.len() - 2..],
+            [
+                "127.0.0.1:41000:staging-api.internal:8080".to_string(),
+                "me@jump.example.com".to_string()
+            ]
+        );
+
+        let option = SshTunnel {
+            ssh_host: "-oProxyCommand=evil".to_string(),
+            ..tunnel
+        };
+        assert!(option.validate().is_err());
+    }
+}
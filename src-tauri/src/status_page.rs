@@ -0,0 +1,350 @@
+use bytes::Bytes;
+use http_body_util::BodyExt;
+use hyper::body::{Frame, Incoming};
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde::Serialize;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, oneshot, watch, Mutex};
+
+use crate::inspector::{full_body, RelayBody};
+
+pub const STATUS_PORT: u16 = 4040;
+
+// Comment lines keep idle connections from being dropped by phones and proxies
+const KEEPALIVE: Duration = Duration::from_secs(15);
+
+const PAGE: &str = r#"<!doctype html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>my-little-apps</title>
+<style>
+  body { font: 14px ui-monospace, Menlo, monospace; margin: 2rem auto; max-width: 40rem; padding: 0 1rem; background: #111; color: #ddd; }
+  h1 { font-size: 14px; } h1 span, .muted { color: #777; }
+  li { list-style: none; padding: .4rem 0; border-bottom: 1px solid #222; display: flex; gap: .6rem; align-items: baseline; }
+  ul { padding: 0; } a { color: #7ab7ff; } .up { color: #5fd068; } .down { color: #777; }
+</style>
+</head>
+<body>
+<h1><span>&gt;</span> my-little-apps</h1>
+<p class="muted" id="state">connecting...</p>
+<ul id="apps"></ul>
+<p class="muted"><a href="/ca">https apps warn about the certificate?</a></p>
+<script>
+  const list = document.getElementById("apps");
+  const state = document.getElementById("state");
+  const events = new EventSource("/events");
+  events.onopen = () => { state.textContent = "live"; };
+  events.onerror = () => { state.textContent = "reconnecting..."; };
+  events.onmessage = (event) => {
+    list.replaceChildren(...JSON.parse(event.data).map((app) => {
+      const item = document.createElement("li");
+      const dot = document.createElement("span");
+      dot.className = app.running ? "up" : "down";
+      dot.textContent = app.running ? "●" : "○";
+      const name = document.createElement("span");
+      name.textContent = app.name;
+      item.append(dot, name);
+      if (app.url) {
+        const link = document.createElement("a");
+        link.href = app.url;
+        link.textContent = app.url;
+        item.append(link);
+      }
+      return item;
+    }));
+  };
+</script>
+</body>
+</html>
+"#;
+
+const CA_PAGE: &str = r#"<!doctype html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>my-little-apps certificate</title>
+<style>
+  body { font: 14px ui-monospace, Menlo, monospace; margin: 2rem auto; max-width: 40rem; padding: 0 1rem; background: #111; color: #ddd; }
+  h1, h2 { font-size: 14px; } h1 span, .muted { color: #777; }
+  li { padding: .2rem 0; } a { color: #7ab7ff; }
+</style>
+</head>
+<body>
+<h1><span>&gt;</span> trust https apps</h1>
+<p class="muted">https apps on this network use certificates from my-little-apps' own
+certificate authority. install it once per device and they open without warnings.</p>
+<p><a href="/ca.crt">download the certificate</a></p>
+<h2>iphone / ipad</h2>
+<ol>
+  <li>open this page in Safari and download the certificate, then tap "Allow"</li>
+  <li>Settings &rsaquo; General &rsaquo; VPN &amp; Device Management: install the downloaded profile</li>
+  <li>Settings &rsaquo; General &rsaquo; About &rsaquo; Certificate Trust Settings: turn on full trust for it</li>
+</ol>
+<h2>android</h2>
+<ol>
+  <li>download the certificate</li>
+  <li>Settings &rsaquo; Security &rsaquo; Encryption &amp; credentials &rsaquo; Install a certificate &rsaquo; CA certificate</li>
+  <li>pick the downloaded file; Chrome trusts it after a restart</li>
+</ol>
+<p class="muted">remove it the same way once you no longer need it.</p>
+</body>
+</html>
+"#;
+
+/// One row of the status page; `url` is set while the app is routed on the LAN.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct AppStatus {
+    pub name: String,
+    pub running: bool,
+    pub url: Option<String>,
+}
+
+struct Server {
+    port: u16,
+    shutdown: oneshot::Sender<()>,
+}
+
+/// Read-only page for other devices on the LAN showing which apps are up, kept live over
+/// server-sent events. It has no routes that change anything.
+pub struct StatusPage {
+    apps: watch::Sender<Vec<AppStatus>>,
+    // PEM root of the CA HTTPS routes are signed with, offered to devices as /ca.crt
+    ca: watch::Sender<Option<String>>,
+    server: Mutex<Option<Server>>,
+}
+
+impl StatusPage {
+    pub fn new() -> Self {
+        Self {
+            apps: watch::Sender::new(Vec::new()),
+            ca: watch::Sender::new(None),
+            server: Mutex::new(None),
+        }
+    }
+
+    pub async fn start(&self, port: u16) -> Result<(), String> {
+        let mut server = self.server.lock().await;
+        if server.as_ref().is_some_and(|s| s.port == port) {
+            return Ok(());
+        }
+        if let Some(running) = server.take() {
+            let _ = running.shutdown.send(());
+        }
+
+        let listener = TcpListener::bind(("0.0.0.0", port))
+            .await
+            .map_err(|e| format!("Failed to start status page on port {}: {}", port, e))?;
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let state = PageState {
+            apps: self.apps.subscribe(),
+            ca: self.ca.subscribe(),
+        };
+        tauri::async_runtime::spawn(run_server(listener, state, shutdown_rx));
+
+        *server = Some(Server {
+            port,
+            shutdown: shutdown_tx,
+        });
+        log::info!("Status page listening on port {}", port);
+        Ok(())
+    }
+
+    pub async fn stop(&self) {
+        if let Some(server) = self.server.lock().await.take() {
+            let _ = server.shutdown.send(());
+        }
+    }
+
+    pub async fn running_port(&self) -> Option<u16> {
+        self.server.lock().await.as_ref().map(|s| s.port)
+    }
+
+    /// Pushes to connected pages, but only when something actually changed.
+    pub fn publish(&self, apps: Vec<AppStatus>) {
+        self.apps.send_if_modified(|current| {
+            if *current == apps {
+                return false;
+            }
+            *current = apps;
+            true
+        });
+    }
+
+    pub fn has_ca_certificate(&self) -> bool {
+        self.ca.borrow().is_some()
+    }
+
+    pub fn publish_ca_certificate(&self, pem: Option<String>) {
+        self.ca.send_replace(pem);
+    }
+}
+
+impl Default for StatusPage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// What a running server reads from
+#[derive(Clone)]
+struct PageState {
+    apps: watch::Receiver<Vec<AppStatus>>,
+    ca: watch::Receiver<Option<String>>,
+}
+
+async fn run_server(listener: TcpListener, state: PageState, mut shutdown: oneshot::Receiver<()>) {
+    loop {
+        let stream = tokio::select! {
+            _ = &mut shutdown => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    log::error!("Status page accept failed: {}", e);
+                    continue;
+                }
+            },
+        };
+
+        let state = state.clone();
+        tauri::async_runtime::spawn(async move {
+            let service = hyper::service::service_fn(move |req| handle(req, state.clone()));
+            let _ = hyper::server::conn::http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await;
+        });
+    }
+}
+
+async fn handle(
+    req: Request<Incoming>,
+    state: PageState,
+) -> Result<Response<RelayBody>, hyper::Error> {
+    if req.method() != Method::GET {
+        return Ok(text_response(StatusCode::METHOD_NOT_ALLOWED, "read-only"));
+    }
+    let response = match req.uri().path() {
+        "/" => Response::builder()
+            .header(hyper::header::CONTENT_TYPE, "text/html; charset=utf-8")
+            .body(full_body(Bytes::from_static(PAGE.as_bytes()))),
+        "/status.json" => Response::builder()
+            .header(hyper::header::CONTENT_TYPE, "application/json")
+            .body(full_body(Bytes::from(snapshot_json(&state.apps.borrow())))),
+        "/events" => Response::builder()
+            .header(hyper::header::CONTENT_TYPE, "text/event-stream")
+            .header(hyper::header::CACHE_CONTROL, "no-cache")
+            .body(event_stream(state.apps)),
+        "/ca" | "/ca.crt" if state.ca.borrow().is_none() => {
+            return Ok(text_response(
+                StatusCode::NOT_FOUND,
+                "no app is served over https yet",
+            ))
+        }
+        "/ca" => Response::builder()
+            .header(hyper::header::CONTENT_TYPE, "text/html; charset=utf-8")
+            .body(full_body(Bytes::from_static(CA_PAGE.as_bytes()))),
+        // The content type iOS and Android offer to install as a certificate
+        "/ca.crt" => Response::builder()
+            .header(hyper::header::CONTENT_TYPE, "application/x-x509-ca-cert")
+            .header(
+                hyper::header::CONTENT_DISPOSITION,
+                "attachment; filename=\"my-little-apps.crt\"",
+            )
+            .body(full_body(Bytes::from(
+                state.ca.borrow().clone().unwrap_or_default(),
+            ))),
+        _ => return Ok(text_response(StatusCode::NOT_FOUND, "not found")),
+    };
+    Ok(response.expect("static response parts are valid"))
+}
+
+fn snapshot_json(apps: &[AppStatus]) -> String {
+    serde_json::to_string(apps).unwrap_or_else(|_| "[]".to_string())
+}
+
+// Sends the current apps right away and again on every change, until the client goes away
+fn event_stream(mut apps: watch::Receiver<Vec<AppStatus>>) -> RelayBody {
+    let (tx, rx) = mpsc::channel(4);
+    tauri::async_runtime::spawn(async move {
+        apps.mark_changed();
+        loop {
+            let chunk = tokio::select! {
+                changed = apps.changed() => {
+                    if changed.is_err() {
+                        break;
+                    }
+                    format!("data: {}\n\n", snapshot_json(&apps.borrow_and_update()))
+                }
+                _ = tokio::time::sleep(KEEPALIVE) => ": keepalive\n\n".to_string(),
+            };
+            if tx.send(Bytes::from(chunk)).await.is_err() {
+                break;
+            }
+        }
+    });
+    EventStream(rx).boxed()
+}
+
+struct EventStream(mpsc::Receiver<Bytes>);
+
+impl hyper::body::Body for EventStream {
+    type Data = Bytes;
+    type Error = hyper::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, hyper::Error>>> {
+        self.0
+            .poll_recv(cx)
+            .map(|chunk| chunk.map(|chunk| Ok(Frame::data(chunk))))
+    }
+}
+
+fn text_response(status: StatusCode, message: &'static str) -> Response<RelayBody> {
+    let mut response = Response::new(full_body(Bytes::from_static(message.as_bytes())));
+    *response.status_mut() = status;
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_publish_only_on_change() {
+        let page = StatusPage::new();
+        let mut apps = page.apps.subscribe();
+        let status = vec![AppStatus {
+            name: "shop".to_string(),
+            running: true,
+            url: Some("http://shop.local".to_string()),
+        }];
+
+        page.publish(status.clone());
+        assert!(apps.has_changed().unwrap());
+        apps.mark_unchanged();
+        page.publish(status);
+        assert!(!apps.has_changed().unwrap());
+        assert_eq!(
+            snapshot_json(&apps.borrow()),
+            r#"[{"name":"shop","running":true,"url":"http://shop.local"}]"#
+        );
+    }
+
+    #[test]
+    fn test_publish_ca_certificate() {
+        let page = StatusPage::new();
+        assert!(!page.has_ca_certificate());
+        page.publish_ca_certificate(Some("-----BEGIN CERTIFICATE-----".to_string()));
+        assert!(page.has_ca_certificate());
+        page.publish_ca_certificate(None);
+        assert!(!page.has_ca_certificate());
+    }
+}
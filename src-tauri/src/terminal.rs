@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Terminal {
+    // Terminal.app
+    #[serde(rename = "terminal")]
+    System,
+    Iterm2,
+    Wezterm,
+    Ghostty,
+}
+
+// Third-party terminals win over Terminal.app when detecting: having one installed is a strong hint
+const DETECTION_ORDER: [Terminal; 4] = [
+    Terminal::Ghostty,
+    Terminal::Iterm2,
+    Terminal::Wezterm,
+    Terminal::System,
+];
+
+impl Terminal {
+    fn bundle_path(self) -> &'static str {
+        match self {
+            Terminal::System => "/System/Applications/Utilities/Terminal.app",
+            Terminal::Iterm2 => "/Applications/iTerm.app",
+            Terminal::Wezterm => "/Applications/WezTerm.app",
+            Terminal::Ghostty => "/Applications/Ghostty.app",
+        }
+    }
+
+    pub fn is_installed(self) -> bool {
+        Path::new(self.bundle_path()).exists()
+    }
+
+    pub fn installed() -> Vec<Terminal> {
+        DETECTION_ORDER
+            .into_iter()
+            .filter(|t| t.is_installed())
+            .collect()
+    }
+
+    pub fn detect() -> Terminal {
+        Self::installed()
+            .into_iter()
+            .next()
+            .unwrap_or(Terminal::System)
+    }
+}
+
+/// Opens `terminal` in `dir`, optionally typing `command` into the new shell first.
+pub fn open(terminal: Terminal, dir: &str, command: Option<&str>) -> Result<(), String> {
+    let command = command.map(str::trim).filter(|c| !c.is_empty());
+    let script = match command {
+        Some(command) => format!("cd {} && {}", shell_quote(dir), command),
+        None => format!("cd {}", shell_quote(dir)),
+    };
+
+    match terminal {
+        Terminal::System => run(
+            "osascript",
+            &[
+                "-e".to_string(),
+                format!(
+                    "tell application \"Terminal\"\n    do script {}\n    activate\nend tell",
+                    applescript_string(&script)
+                ),
+            ],
+        ),
+        Terminal::Iterm2 => run(
+            "osascript",
+            &[
+                "-e".to_string(),
+                format!(
+                    "tell application \"iTerm\"\n    create window with default profile\n    tell current session of current window to write text {}\n    activate\nend tell",
+                    applescript_string(&script)
+                ),
+            ],
+        ),
+        Terminal::Wezterm | Terminal::Ghostty => run("open", &open_args(terminal, dir, command)),
+    }
+}
+
+// WezTerm and Ghostty take the directory as a flag; a command runs before handing over
+// to an interactive login shell so the window stays open
+fn open_args(terminal: Terminal, dir: &str, command: Option<&str>) -> Vec<String> {
+    let (app, mut args) = match terminal {
+        Terminal::Wezterm => (
+            "WezTerm",
+            vec!["start".to_string(), "--cwd".to_string(), dir.to_string()],
+        ),
+        _ => ("Ghostty", vec![format!("--working-directory={}", dir)]),
+    };
+
+    if let Some(command) = command {
+        let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string());
+        let wrapped = format!("{}; exec {} -l", command, shell_quote(&shell));
+        let exec_flag = if terminal == Terminal::Wezterm {
+            "--"
+        } else {
+            "-e"
+        };
+        args.push(exec_flag.to_string());
+        args.extend([shell, "-l".to_string(), "-c".to_string(), wrapped]);
+    }
+
+    let mut full = vec!["-na".to_string(), app.to_string(), "--args".to_string()];
+    full.extend(args);
+    full
+}
+
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn applescript_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn run(program: &str, args: &[String]) -> Result<(), String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to open terminal: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to open terminal: {}", stderr.trim()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quoting() {
+        assert_eq!(
+            shell_quote("/Users/me/it's here"),
+            r"'/Users/me/it'\''s here'"
+        );
+        assert_eq!(
+            applescript_string(r#"cd '/tmp' && echo "hi\n""#),
+            r#""cd '/tmp' && echo \"hi\\n\"""#
+        );
+    }
+
+    #[test]
+    fn test_open_args() {
+        assert_eq!(
+            open_args(Terminal::Ghostty, "/tmp/app", None),
+            vec!["-na", "Ghostty", "--args", "--working-directory=/tmp/app"]
+        );
+
+        let args = open_args(Terminal::Wezterm, "/tmp/app", Some("source .env"));
+        assert_eq!(
+            &args[..7],
+            &["-na", "WezTerm", "--args", "start", "--cwd", "/tmp/app", "--"]
+        );
+        assert!(args.last().unwrap().starts_with("source .env; exec "));
+    }
+}
@@ -0,0 +1,183 @@
+use serde::{Deserialize, Serialize};
+
+use crate::health::{self, HealthCheck};
+use crate::App;
+
+// As long as health history, so the timeline and the uptime view cover the same time
+pub const RETENTION_MS: i64 = health::RETENTION_MS;
+pub const DEFAULT_RANGE_MS: i64 = 7 * 24 * 60 * 60 * 1000;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AppEventKind {
+    Started,
+    Stopped,
+    // Exited on its own or was stopped by the watchdog
+    Crashed,
+    ConfigChanged,
+    RouteAdded,
+    RouteRemoved,
+    HealthDown,
+    HealthUp,
+}
+
+impl AppEventKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AppEventKind::Started => "started",
+            AppEventKind::Stopped => "stopped",
+            AppEventKind::Crashed => "crashed",
+            AppEventKind::ConfigChanged => "config_changed",
+            AppEventKind::RouteAdded => "route_added",
+            AppEventKind::RouteRemoved => "route_removed",
+            AppEventKind::HealthDown => "health_down",
+            AppEventKind::HealthUp => "health_up",
+        }
+    }
+
+    pub fn parse(kind: &str) -> Option<Self> {
+        serde_json::from_value(serde_json::Value::String(kind.to_string())).ok()
+    }
+}
+
+/// One entry of an app's activity timeline, as stored in `app_events`.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct AppEvent {
+    pub app_id: String,
+    // Unix ms
+    pub at: i64,
+    pub kind: AppEventKind,
+    // e.g. the port it started on or the exit code
+    pub detail: Option<String>,
+}
+
+impl AppEvent {
+    pub fn new(app_id: &str, kind: AppEventKind, detail: Option<String>) -> Self {
+        AppEvent {
+            app_id: app_id.to_string(),
+            at: health::now_ms(),
+            kind,
+            detail,
+        }
+    }
+}
+
+/// The saved settings that differ between two versions of an app, by column name.
+pub fn app_changes(old: &App, new: &App) -> Vec<&'static str> {
+    [
+        ("name", old.name != new.name),
+        ("path", old.path != new.path),
+        ("command", old.command != new.command),
+        ("port", old.port != new.port),
+        ("subdomain", old.subdomain != new.subdomain),
+        ("build_command", old.build_command != new.build_command),
+        ("prerequisites", old.prerequisites != new.prerequisites),
+        ("remote_host", old.remote_host != new.remote_host),
+        ("devcontainer", old.devcontainer != new.devcontainer),
+        ("path_entries", old.path_entries != new.path_entries),
+        ("shell", old.shell != new.shell),
+        ("spa", old.spa != new.spa),
+    ]
+    .into_iter()
+    .filter(|(_, changed)| *changed)
+    .map(|(column, _)| column)
+    .collect()
+}
+
+/// The timeline entry for a health check, if it flips the app between up and down.
+/// `was_up` is None before the app's first check; a first check that passes isn't news.
+pub fn health_transition(
+    app_id: &str,
+    was_up: Option<bool>,
+    check: &HealthCheck,
+) -> Option<AppEvent> {
+    match (was_up, check.up) {
+        (Some(true) | None, false) => Some(AppEventKind::HealthDown),
+        (Some(false), true) => Some(AppEventKind::HealthUp),
+        _ => None,
+    }
+    .map(|kind| {
+        let detail = match kind {
+            AppEventKind::HealthDown => check.error.clone(),
+            _ => check.response_ms.map(|ms| format!("{} ms", ms)),
+        };
+        AppEvent {
+            app_id: app_id.to_string(),
+            at: check.checked_at,
+            kind,
+            detail,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_transition() {
+        let check = |up: bool| HealthCheck {
+            checked_at: 1000,
+            up,
+            status: up.then_some(200),
+            response_ms: up.then_some(12),
+            error: (!up).then(|| "connection refused".to_string()),
+        };
+        assert_eq!(health_transition("api", None, &check(true)), None);
+        assert_eq!(health_transition("api", Some(true), &check(true)), None);
+        assert_eq!(health_transition("api", Some(false), &check(false)), None);
+
+        let down = health_transition("api", Some(true), &check(false)).unwrap();
+        assert_eq!(down.kind, AppEventKind::HealthDown);
+        assert_eq!(down.at, 1000);
+        assert_eq!(down.detail.as_deref(), Some("connection refused"));
+        assert!(health_transition("api", None, &check(false)).is_some());
+
+        let up = health_transition("api", Some(false), &check(true)).unwrap();
+        assert_eq!(up.kind, AppEventKind::HealthUp);
+        assert_eq!(up.detail.as_deref(), Some("12 ms"));
+        assert_eq!(up.app_id, "api");
+
+        assert_eq!(
+            AppEventKind::parse("route_added"),
+            Some(AppEventKind::RouteAdded)
+        );
+        assert_eq!(
+            AppEventKind::parse(AppEventKind::HealthUp.as_str()),
+            Some(AppEventKind::HealthUp)
+        );
+        assert_eq!(AppEventKind::parse("exploded"), None);
+    }
+
+    #[test]
+    fn test_app_changes() {
+        let app = App {
+            id: "api".to_string(),
+            name: "API".to_string(),
+            path: "/code/api".to_string(),
+            command: "bun dev".to_string(),
+            port: Some(3000),
+            run_on_startup: false,
+            created_at: String::new(),
+            subdomain: None,
+            worktree_of: None,
+            build_command: None,
+            service: None,
+            prerequisites: None,
+            remote_host: None,
+            devcontainer: false,
+            path_entries: None,
+            shell: None,
+            kind: "process".to_string(),
+            spa: false,
+        };
+        assert!(app_changes(&app, &app.clone()).is_empty());
+        let changed = App {
+            command: "bun start".to_string(),
+            subdomain: Some("api".to_string()),
+            run_on_startup: true,
+            ..app.clone()
+        };
+        assert_eq!(app_changes(&app, &changed), ["command", "subdomain"]);
+    }
+}
@@ -0,0 +1,91 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(target_os = "macos")]
+use std::{collections::HashMap, sync::Mutex};
+
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+const SUBSYSTEM_PREFIX: &str = "com.artsiomshaitar.my-little-apps";
+
+/// Each app logs under its own subsystem, with stdout and stderr as categories, so
+/// Console.app and `log stream` can filter to one app.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub fn subsystem(app_name: &str) -> String {
+    let mut slug = String::new();
+    for c in app_name.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        return format!("{}.app", SUBSYSTEM_PREFIX);
+    }
+    format!("{}.{}", SUBSYSTEM_PREFIX, slug)
+}
+
+/// Mirrors app output into macOS unified logging. A no-op on other platforms.
+#[derive(Default)]
+pub struct UnifiedLog {
+    enabled: AtomicBool,
+    // One os_log handle per app and stream, created on its first line
+    #[cfg(target_os = "macos")]
+    loggers: Mutex<HashMap<(String, String), oslog::OsLog>>,
+}
+
+impl UnifiedLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+        #[cfg(target_os = "macos")]
+        if !enabled {
+            if let Ok(mut loggers) = self.loggers.lock() {
+                loggers.clear();
+            }
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    #[cfg(target_os = "macos")]
+    pub fn write(&self, app_name: &str, stream: &str, line: &str) {
+        if !self.is_enabled() {
+            return;
+        }
+        let Ok(mut loggers) = self.loggers.lock() else {
+            return;
+        };
+        let logger = loggers
+            .entry((app_name.to_string(), stream.to_string()))
+            .or_insert_with(|| oslog::OsLog::new(&subsystem(app_name), stream));
+        // stderr shows up highlighted as errors in Console.app
+        let level = if stream == "stderr" {
+            oslog::Level::Error
+        } else {
+            oslog::Level::Default
+        };
+        logger.with_level(level, line);
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    pub fn write(&self, _app_name: &str, _stream: &str, _line: &str) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subsystem() {
+        assert_eq!(
+            subsystem("My Shop (dev)"),
+            "com.artsiomshaitar.my-little-apps.my-shop-dev"
+        );
+        assert_eq!(subsystem("  "), "com.artsiomshaitar.my-little-apps.app");
+    }
+}
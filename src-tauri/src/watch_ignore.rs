@@ -0,0 +1,165 @@
+use ignore::{DirEntry, WalkBuilder};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+// A preview this long is already past reading
+const MAX_LISTED: usize = 2000;
+
+/// What a watcher on the project would and wouldn't look at.
+#[derive(Debug, Default, Serialize)]
+pub struct WatchPlan {
+    /// Watched directories, relative to the project; "" is the project itself.
+    pub dirs: Vec<String>,
+    /// Ignored directories, whose contents are skipped entirely.
+    pub ignored: Vec<String>,
+    /// Watched files.
+    pub files: usize,
+    /// Whether the lists stop at `MAX_LISTED`.
+    pub truncated: bool,
+}
+
+/// What `walk` found at a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visit {
+    Dir,
+    /// An ignored directory, whose contents are skipped entirely.
+    IgnoredDir,
+    File,
+}
+
+/// Walks `project` the way a watcher should: skipping `.git`, the artifact directories
+/// from `disk_usage::CLEAN_TARGETS`, and whatever `.gitignore`, `.ignore` and
+/// `.git/info/exclude` list, so installs and builds don't set off restarts. `visit` gets
+/// paths relative to the project, directories in order before their contents and the
+/// ignored ones last.
+pub fn walk(project: &Path, mut visit: impl FnMut(&Path, Visit)) -> Result<(), String> {
+    if !project.is_dir() {
+        return Err(format!("{} is not a directory", project.display()));
+    }
+    let walker = WalkBuilder::new(project)
+        .standard_filters(false)
+        .git_ignore(true)
+        .git_exclude(true)
+        .ignore(true)
+        // Ignore files count in projects that aren't repositories too
+        .require_git(false)
+        // Symlinks aren't followed, they can point anywhere or loop
+        .follow_links(false)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .filter_entry(|entry| !is_artifact_dir(entry))
+        .build();
+
+    // The walker skips ignored entries without a word, so they're what's left over
+    let mut dirs = Vec::new();
+    for entry in walker.flatten() {
+        let Ok(path) = entry.path().strip_prefix(project) else {
+            continue;
+        };
+        if entry.file_type().is_some_and(|t| t.is_dir()) {
+            visit(path, Visit::Dir);
+            dirs.push(path.to_path_buf());
+        } else {
+            visit(path, Visit::File);
+        }
+    }
+    let walked: HashSet<&Path> = dirs.iter().map(PathBuf::as_path).collect();
+    for dir in &dirs {
+        let Ok(entries) = std::fs::read_dir(project.join(dir)) else {
+            continue;
+        };
+        let mut ignored: Vec<PathBuf> = entries
+            .flatten()
+            .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+            .map(|entry| dir.join(entry.file_name()))
+            .filter(|path| !walked.contains(path.as_path()))
+            .collect();
+        ignored.sort();
+        for path in ignored {
+            visit(&path, Visit::IgnoredDir);
+        }
+    }
+    Ok(())
+}
+
+fn is_artifact_dir(entry: &DirEntry) -> bool {
+    let name = entry.file_name().to_string_lossy();
+    entry.file_type().is_some_and(|t| t.is_dir())
+        && (name == ".git" || crate::disk_usage::CLEAN_TARGETS.contains(&name.as_ref()))
+}
+
+/// What a watcher on `project` would cover, see `walk`.
+pub fn plan(project: &Path) -> Result<WatchPlan, String> {
+    let mut plan = WatchPlan::default();
+    walk(project, |path, visit| match visit {
+        Visit::Dir => list(&mut plan.dirs, &mut plan.truncated, path),
+        Visit::IgnoredDir => list(&mut plan.ignored, &mut plan.truncated, path),
+        Visit::File => plan.files += 1,
+    })?;
+    plan.ignored.sort();
+    Ok(plan)
+}
+
+fn list(paths: &mut Vec<String>, truncated: &mut bool, path: &Path) {
+    if paths.len() < MAX_LISTED {
+        paths.push(path.to_string_lossy().into_owned());
+    } else {
+        *truncated = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan() {
+        let dir = std::env::temp_dir().join(format!("mla-watch-{}", std::process::id()));
+        for sub in [
+            "src/components",
+            "src/dist",
+            "node_modules/react",
+            "coverage",
+            ".git/objects",
+            "packages/web/generated",
+            "packages/api/generated",
+        ] {
+            std::fs::create_dir_all(dir.join(sub)).unwrap();
+        }
+        std::fs::write(dir.join(".gitignore"), "coverage\n*.log\n!keep.log\n").unwrap();
+        std::fs::write(dir.join("packages/web/.ignore"), "generated/\n").unwrap();
+        std::fs::write(dir.join("src/index.ts"), "").unwrap();
+        std::fs::write(dir.join("src/debug.log"), "").unwrap();
+        std::fs::write(dir.join("keep.log"), "").unwrap();
+        std::fs::write(dir.join("node_modules/react/index.js"), "").unwrap();
+
+        let plan = plan(&dir).unwrap();
+        assert_eq!(
+            plan.dirs,
+            [
+                "",
+                "packages",
+                "packages/api",
+                "packages/api/generated",
+                "packages/web",
+                "src",
+                "src/components"
+            ]
+        );
+        assert_eq!(
+            plan.ignored,
+            [
+                ".git",
+                "coverage",
+                "node_modules",
+                "packages/web/generated",
+                "src/dist"
+            ]
+        );
+        // .gitignore, keep.log, packages/web/.ignore and src/index.ts
+        assert_eq!(plan.files, 4);
+        assert!(!plan.truncated);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+// Checks run every 10s, so an app is given up on after about 30s without an answer
+const MAX_MISSES: u32 = 3;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PortState {
+    pid: u32,
+    answered: bool,
+    misses: u32,
+}
+
+/// Follows whether running apps keep answering on their port. A wrapper like `npm run dev`
+/// or a file watcher can outlive the server it started, which would otherwise show as
+/// running forever. Ports never seen answering don't count: some apps ignore the port
+/// they were given.
+#[derive(Debug, Default)]
+pub struct Watchdog {
+    ports: HashMap<String, PortState>,
+}
+
+impl Watchdog {
+    /// Records one check of `app_id` running as `pid` and returns true once its port has
+    /// stopped answering.
+    pub fn record(&mut self, app_id: &str, pid: u32, answered: bool) -> bool {
+        let fresh = PortState {
+            pid,
+            answered: false,
+            misses: 0,
+        };
+        let state = self.ports.entry(app_id.to_string()).or_insert(fresh);
+        // A restarted app starts over
+        if state.pid != pid {
+            *state = fresh;
+        }
+        if answered {
+            state.answered = true;
+            state.misses = 0;
+            return false;
+        }
+        if !state.answered {
+            return false;
+        }
+        state.misses += 1;
+        state.misses >= MAX_MISSES
+    }
+
+    /// Forgets apps `running` says are gone.
+    pub fn retain(&mut self, running: impl Fn(&str) -> bool) {
+        self.ports.retain(|app_id, _| running(app_id));
+    }
+}
+
+pub async fn port_answers(port: i32) -> bool {
+    let Ok(port) = u16::try_from(port) else {
+        return false;
+    };
+    for host in ["127.0.0.1", "::1"] {
+        let connect = tokio::net::TcpStream::connect((host, port));
+        if let Ok(Ok(_)) = tokio::time::timeout(CONNECT_TIMEOUT, connect).await {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let mut watchdog = Watchdog::default();
+        // Never answered, e.g. the app listens somewhere else
+        for _ in 0..5 {
+            assert!(!watchdog.record("api", 100, false));
+        }
+
+        assert!(!watchdog.record("api", 100, true));
+        assert!(!watchdog.record("api", 100, false));
+        assert!(!watchdog.record("api", 100, false));
+        assert!(watchdog.record("api", 100, false));
+
+        // Restarted under a new pid
+        assert!(!watchdog.record("api", 200, false));
+        watchdog.retain(|_| false);
+        assert!(watchdog.ports.is_empty());
+    }
+}
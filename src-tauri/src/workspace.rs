@@ -0,0 +1,299 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+// Scripts that start a long-running server, in order of preference
+const RUN_SCRIPTS: [&str; 3] = ["dev", "start", "serve"];
+
+// `**` patterns don't descend further than this, keeping a stray pattern from walking node_modules
+const MAX_DEPTH: usize = 4;
+
+/// A workspace package that can be added as an app.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct WorkspacePackage {
+    pub name: String,
+    pub path: String,
+    pub command: String,
+    pub subdomain: String,
+}
+
+/// Runnable packages of the monorepo rooted at `root`, from pnpm-workspace.yaml or the
+/// `workspaces` field of package.json. Empty when `root` isn't a workspace.
+pub fn scan(root: &Path) -> Result<Vec<WorkspacePackage>, String> {
+    if !root.is_dir() {
+        return Err(format!("{} is not a directory", root.display()));
+    }
+
+    let mut patterns = read_pnpm_patterns(root);
+    if patterns.is_empty() {
+        patterns = read_package_patterns(root);
+    }
+
+    let runner = package_runner(root);
+    let preferred_task = turbo_dev_task(root);
+
+    let mut packages = Vec::new();
+    for dir in expand_patterns(root, &patterns) {
+        let Some(package) = read_json(&dir.join("package.json")) else {
+            continue;
+        };
+        let Some(script) = pick_script(&package, preferred_task.as_deref()) else {
+            continue;
+        };
+
+        let folder = dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let name = package["name"]
+            .as_str()
+            .map(String::from)
+            .unwrap_or_else(|| folder.clone());
+        // "@acme/web" gets the subdomain "web"
+        let short_name = name.rsplit('/').next().unwrap_or(&name);
+
+        packages.push(WorkspacePackage {
+            subdomain: crate::proxy::slugify(short_name),
+            command: run_command(runner, &script),
+            path: dir.to_string_lossy().to_string(),
+            name,
+        });
+    }
+    packages.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(packages)
+}
+
+fn read_json(path: &Path) -> Option<serde_json::Value> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn read_pnpm_patterns(root: &Path) -> Vec<String> {
+    std::fs::read_to_string(root.join("pnpm-workspace.yaml"))
+        .map(|content| parse_pnpm_workspace(&content))
+        .unwrap_or_default()
+}
+
+// Just enough YAML for the `packages:` list; other keys (catalogs etc.) are ignored
+fn parse_pnpm_workspace(content: &str) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with([' ', '\t', '-']) {
+            in_packages = trimmed.starts_with("packages:");
+            continue;
+        }
+        if let Some(item) = trimmed.strip_prefix('-').filter(|_| in_packages) {
+            let item = item.split(" #").next().unwrap_or(item).trim();
+            patterns.push(item.trim_matches(['"', '\'']).to_string());
+        }
+    }
+    patterns
+}
+
+fn read_package_patterns(root: &Path) -> Vec<String> {
+    let Some(package) = read_json(&root.join("package.json")) else {
+        return Vec::new();
+    };
+    // Either ["apps/*"] or { "packages": ["apps/*"] } (yarn classic)
+    let workspaces = match &package["workspaces"] {
+        serde_json::Value::Object(obj) => obj.get("packages").cloned().unwrap_or_default(),
+        other => other.clone(),
+    };
+    workspaces
+        .as_array()
+        .map(|patterns| {
+            patterns
+                .iter()
+                .filter_map(|p| p.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// turbo.json marks dev servers as persistent tasks; that task's script is preferred when present
+fn turbo_dev_task(root: &Path) -> Option<String> {
+    let turbo = read_json(&root.join("turbo.json"))?;
+    let tasks = turbo
+        .get("tasks")
+        .or_else(|| turbo.get("pipeline"))?
+        .as_object()?;
+    tasks
+        .iter()
+        .find(|(_, task)| task["persistent"].as_bool() == Some(true))
+        .map(|(name, _)| name.rsplit('#').next().unwrap_or(name).to_string())
+}
+
+pub fn pick_script(package: &serde_json::Value, preferred: Option<&str>) -> Option<String> {
+    let scripts = package["scripts"].as_object()?;
+    preferred
+        .into_iter()
+        .chain(RUN_SCRIPTS)
+        .find(|s| scripts.contains_key(*s))
+        .map(String::from)
+}
+
+pub fn package_runner(root: &Path) -> &'static str {
+    if root.join("bun.lock").exists() || root.join("bun.lockb").exists() {
+        "bun"
+    } else if root.join("pnpm-lock.yaml").exists() || root.join("pnpm-workspace.yaml").exists() {
+        "pnpm"
+    } else if root.join("yarn.lock").exists() {
+        "yarn"
+    } else {
+        "npm"
+    }
+}
+
+pub fn run_command(runner: &str, script: &str) -> String {
+    match (runner, script) {
+        ("npm", "start") => "npm start".to_string(),
+        ("npm", _) => format!("npm run {}", script),
+        _ => format!("{} run {}", runner, script),
+    }
+}
+
+// Expands workspace globs: literal paths, `dir/*` (direct children) and `dir/**` (any depth).
+// `!` patterns exclude matching directories.
+fn expand_patterns(root: &Path, patterns: &[String]) -> Vec<PathBuf> {
+    let (excludes, includes): (Vec<&String>, Vec<&String>) =
+        patterns.iter().partition(|p| p.starts_with('!'));
+    let excluded: Vec<PathBuf> = excludes
+        .iter()
+        .flat_map(|p| expand_pattern(root, p.trim_start_matches('!')))
+        .collect();
+
+    let mut dirs = Vec::new();
+    for pattern in includes {
+        for dir in expand_pattern(root, pattern) {
+            if !excluded.contains(&dir) && !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+    }
+    dirs
+}
+
+fn expand_pattern(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+    if let Some(base) = pattern.strip_suffix("/**") {
+        let mut dirs = Vec::new();
+        collect_dirs(&root.join(base), MAX_DEPTH, &mut dirs);
+        dirs
+    } else if let Some(base) = pattern.strip_suffix("/*") {
+        let mut dirs = Vec::new();
+        collect_dirs(&root.join(base), 1, &mut dirs);
+        dirs
+    } else {
+        let dir = root.join(pattern);
+        if dir.is_dir() {
+            vec![dir]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+fn collect_dirs(dir: &Path, depth: usize, dirs: &mut Vec<PathBuf>) {
+    if depth == 0 {
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut children: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .filter(|p| {
+            p.file_name()
+                .is_some_and(|n| n != "node_modules" && !n.to_string_lossy().starts_with('.'))
+        })
+        .collect();
+    children.sort();
+    for child in children {
+        dirs.push(child.clone());
+        collect_dirs(&child, depth - 1, dirs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pnpm_workspace() {
+        let yaml = r#"
+packages:
+  - "apps/*"
+  - 'packages/**'
+  - tooling/eslint # shared config
+  - "!**/test"
+catalog:
+  - react
+"#;
+        assert_eq!(
+            parse_pnpm_workspace(yaml),
+            vec!["apps/*", "packages/**", "tooling/eslint", "!**/test"]
+        );
+    }
+
+    #[test]
+    fn test_pick_script() {
+        let package = serde_json::json!({
+            "scripts": { "build": "next build", "start": "next start", "dev": "next dev" }
+        });
+        assert_eq!(pick_script(&package, None), Some("dev".to_string()));
+        assert_eq!(
+            pick_script(&package, Some("start")),
+            Some("start".to_string())
+        );
+        assert_eq!(pick_script(&serde_json::json!({}), None), None);
+        assert_eq!(run_command("npm", "dev"), "npm run dev");
+        assert_eq!(run_command("pnpm", "start"), "pnpm run start");
+    }
+
+    #[test]
+    fn test_scan() {
+        let root = std::env::temp_dir().join(format!("mla-workspace-{}", std::process::id()));
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write(
+            "package.json",
+            r#"{"workspaces": {"packages": ["apps/*", "packages/ui"]}}"#,
+        );
+        write("bun.lock", "");
+        write(
+            "apps/web/package.json",
+            r#"{"name": "@acme/web", "scripts": {"dev": "vite"}}"#,
+        );
+        write(
+            "apps/api/package.json",
+            r#"{"name": "api", "scripts": {"start": "node server.js"}}"#,
+        );
+        write(
+            "packages/ui/package.json",
+            r#"{"name": "@acme/ui", "scripts": {"build": "tsc"}}"#,
+        );
+
+        let packages = scan(&root).unwrap();
+        assert_eq!(
+            packages
+                .iter()
+                .map(|p| (p.name.as_str(), p.command.as_str(), p.subdomain.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("api", "bun run start", "api"),
+                ("@acme/web", "bun run dev", "web"),
+            ]
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,7 +1,10 @@
 import { useState, useCallback, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { enable, disable, isEnabled } from "@tauri-apps/plugin-autostart";
+import { open } from "@tauri-apps/plugin-dialog";
 import { TooltipProvider } from "@/components/ui/tooltip";
-import { confirm } from "@/components/confirm-dialog";
+import { confirm, prompt } from "@/components/confirm-dialog";
 
 import { useProxy, useApps } from "@/hooks";
 import { AppHeader } from "@/components/app-header";
@@ -11,14 +14,78 @@ import {
   EditAppDialog,
   SetupWizardDialog,
   LanInfoDialog,
+  RemoteControlDialog,
+  NotificationsDialog,
+  LogForwardingDialog,
+  SessionsDialog,
+  WorktreeDialog,
+  ImportWorkspaceDialog,
+  ServiceDialog,
+  RemoteAppDialog,
+  QuickSwitcherDialog,
 } from "@/components/dialogs";
-import type { App } from "@/types";
+import type {
+  App,
+  AppLockStatus,
+  ConfigIssue,
+  CrashReport,
+  LaunchProfile,
+  QuitBehavior,
+  RepoBranches,
+  UpdateInfo,
+  UpdateProgress,
+} from "@/types";
+import { describeError } from "@/lib/errors";
+import { unlock } from "@/lib/app-lock";
+
+async function offerCrashReport(report: CrashReport, others: CrashReport[]) {
+  const what =
+    report.kind === "panic"
+      ? "My Little Apps ran into an internal error"
+      : `${report.location ?? "A background task"} stopped with an error`;
+  const earlier = others.length > 0 ? ` (and ${others.length} earlier)` : "";
+  const shouldReport = await confirm(
+    `${what}${earlier}: ${report.message}. Open a GitHub issue with the report? You can review it before submitting; passwords, tokens and your home folder are left out.`,
+    { title: "crash report", confirm: "Open issue", cancel: "Dismiss" }
+  );
+  try {
+    if (shouldReport) {
+      await invoke("report_crash", { id: report.id });
+    } else {
+      await invoke("dismiss_crash_report", { id: report.id });
+    }
+    for (const other of others) {
+      await invoke("dismiss_crash_report", { id: other.id });
+    }
+  } catch (e) {
+    alert(`Failed to handle the crash report: ${describeError(e)}`);
+  }
+}
+
+// Regenerated by an install or build, so they're offered for the Trash on removal
+const ARTIFACT_DIRS = ["node_modules", ".next", "dist", ".turbo"];
 
 function AppComponent() {
   const [selectedAppId, setSelectedAppId] = useState<string | null>(null);
   const [editingApp, setEditingApp] = useState<App | null>(null);
+  const [editingProfiles, setEditingProfiles] = useState<LaunchProfile[]>([]);
   const [autoStartEnabled, setAutoStartEnabled] = useState(false);
+  const [appLock, setAppLock] = useState<AppLockStatus | null>(null);
+  const [quitBehavior, setQuitBehavior] = useState<QuitBehavior>("stop");
+  const [update, setUpdate] = useState<UpdateInfo | null>(null);
+  const [updateProgress, setUpdateProgress] = useState<UpdateProgress | null>(
+    null
+  );
   const [showLanInfo, setShowLanInfo] = useState(false);
+  const [showRemoteControl, setShowRemoteControl] = useState(false);
+  const [showNotifications, setShowNotifications] = useState(false);
+  const [showLogForwarding, setShowLogForwarding] = useState(false);
+  const [showSessions, setShowSessions] = useState(false);
+  const [worktreeApp, setWorktreeApp] = useState<App | null>(null);
+  const [workspaceRoot, setWorkspaceRoot] = useState<string | null>(null);
+  const [showServiceDialog, setShowServiceDialog] = useState(false);
+  const [showRemoteDialog, setShowRemoteDialog] = useState(false);
+  const [showQuickSwitcher, setShowQuickSwitcher] = useState(false);
 
   const {
     lanIp,
@@ -39,13 +106,32 @@ function AppComponent() {
   const {
     apps,
     runningApps,
+    readyApps,
+    buildingApps,
+    slowStarts,
+    staleBuilds,
+    restartNeeded,
     appsUsage,
+    gitInfo,
+    lastAccessed,
     logs,
+    profiles,
+    runningProfiles,
     addApp,
+    addWorkspaceApps,
+    addServiceApp,
+    addRemoteApp,
+    setDevcontainer,
+    createWorktreeApp,
     removeApp,
     startApp,
+    startAppWithProfile,
+    saveProfiles,
     stopApp,
+    stopAllApps,
+    restoreSession,
     restartApp,
+    updateAndRestart,
     clearLogs,
     updateApp,
     handleOpenInBrowser,
@@ -60,6 +146,57 @@ function AppComponent() {
 
   useEffect(() => {
     isEnabled().then(setAutoStartEnabled);
+    invoke<AppLockStatus>("get_app_lock").then(setAppLock);
+    invoke<QuitBehavior>("get_quit_behavior").then(setQuitBehavior);
+    // Offline is fine, the next launch checks again
+    invoke<UpdateInfo | null>("check_for_update")
+      .then(setUpdate)
+      .catch(() => {});
+  }, []);
+
+  // Reports left from a crash last time, then any from this session
+  useEffect(() => {
+    invoke<CrashReport[]>("get_crash_reports").then(([latest, ...others]) => {
+      if (latest) offerCrashReport(latest, others);
+    });
+    const unlisten = listen<CrashReport>("crash-report", (event) =>
+      offerCrashReport(event.payload, [])
+    );
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  useEffect(() => {
+    const unlisten = listen<UpdateProgress>("update-progress", (event) =>
+      setUpdateProgress(event.payload.stage === "done" ? null : event.payload)
+    );
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Cmd+K in the window, or "Find App..." in the tray menu;
+  // Cmd+Option+Shift+D opens the internal log
+  useEffect(() => {
+    const handleKeyDown = (e: KeyboardEvent) => {
+      if ((e.metaKey || e.ctrlKey) && e.key.toLowerCase() === "k") {
+        e.preventDefault();
+        setShowQuickSwitcher(true);
+      }
+      if (e.metaKey && e.altKey && e.shiftKey && e.code === "KeyD") {
+        e.preventDefault();
+        invoke("open_debug_window");
+      }
+    };
+    window.addEventListener("keydown", handleKeyDown);
+    const unlisten = listen("open-quick-switcher", () =>
+      setShowQuickSwitcher(true)
+    );
+    return () => {
+      window.removeEventListener("keydown", handleKeyDown);
+      unlisten.then((fn) => fn());
+    };
   }, []);
 
   const handleToggleAutostart = useCallback(async () => {
@@ -71,15 +208,87 @@ function AppComponent() {
     setAutoStartEnabled((prev) => !prev);
   }, [autoStartEnabled]);
 
+  const handleToggleKeepAppsOnQuit = useCallback(async () => {
+    const behavior = quitBehavior === "stop" ? "keep_running" : "stop";
+    await invoke("set_quit_behavior", { behavior });
+    setQuitBehavior(behavior);
+  }, [quitBehavior]);
+
+  const handleInstallUpdate = useCallback(async () => {
+    if (!update) return;
+    const shouldInstall = await confirm(
+      `Update to ${update.version}? My Little Apps restarts, and the apps running now start again afterwards.`
+    );
+    if (!shouldInstall) return;
+    try {
+      await invoke("install_update");
+    } catch (e) {
+      setUpdateProgress(null);
+      alert(`Update failed: ${describeError(e)}`);
+    }
+  }, [update]);
+
+  // Turning the lock off goes through the lock itself
+  const handleToggleAppLock = useCallback(async () => {
+    try {
+      if (appLock?.enabled) {
+        const currentPasscode =
+          appLock.method === "passcode"
+            ? await prompt("Enter the passcode to turn off the app lock.", {
+                title: "app lock",
+                confirm: "Unlock",
+              })
+            : null;
+        if (appLock.method === "passcode" && currentPasscode === null) return;
+        await invoke("set_app_lock", {
+          enabled: false,
+          passcode: null,
+          currentPasscode,
+        });
+      } else {
+        const passcode = await prompt(
+          "Stopping all apps, uninstalling the proxy and removing apps will need authentication. Set a passcode, or leave it empty to use Touch ID or your login password.",
+          { title: "app lock", confirm: "Lock" }
+        );
+        if (passcode === null) return;
+        await invoke("set_app_lock", {
+          enabled: true,
+          passcode,
+          currentPasscode: null,
+        });
+      }
+    } catch (e) {
+      alert(describeError(e));
+    }
+    setAppLock(await invoke<AppLockStatus>("get_app_lock"));
+  }, [appLock]);
+
+  const handleStopAll = useCallback(async () => {
+    const unlocked = await unlock("stop all apps");
+    if (!unlocked) return;
+    await stopAllApps(unlocked.passcode);
+  }, [stopAllApps]);
+
+  const handleRestoreSession = useCallback(
+    async (name: string) => {
+      const unlocked = await unlock("switch sessions");
+      if (!unlocked) return;
+      await restoreSession(name, unlocked.passcode);
+    },
+    [restoreSession]
+  );
+
   const handleInstallService = useCallback(async () => {
     try {
       await installService();
     } catch (e) {
-      alert(`Installation failed: ${e}`);
+      alert(`Installation failed: ${describeError(e)}`);
     }
   }, [installService]);
 
   const handleUninstallService = useCallback(async () => {
+    const unlocked = await unlock("uninstall the proxy service");
+    if (!unlocked) return;
     const shouldUninstall = await confirm(
       "Are you sure you want to uninstall the proxy service? Your apps will only be accessible via localhost:port.",
       { destructive: true }
@@ -87,9 +296,16 @@ function AppComponent() {
     if (!shouldUninstall) return;
 
     try {
-      await uninstallService();
+      const report = await uninstallService(unlocked.passcode);
+      const leftovers = [
+        ...report.remaining,
+        ...(report.caddy_running ? ["Caddy process is still running"] : []),
+      ];
+      if (leftovers.length > 0) {
+        alert(`Uninstalled, but some items need manual removal:\n\n${leftovers.join("\n")}`);
+      }
     } catch (e) {
-      alert(`Uninstallation failed: ${e}`);
+      alert(`Uninstallation failed: ${describeError(e)}`);
     }
   }, [uninstallService]);
 
@@ -97,26 +313,140 @@ function AppComponent() {
     try {
       await startProxyService();
     } catch (e) {
-      alert(`Failed to start proxy service: ${e}`);
+      alert(`Failed to start proxy service: ${describeError(e)}`);
     }
   }, [startProxyService]);
 
   const handleSaveApp = useCallback(async () => {
     if (!editingApp) return;
 
+    // Static apps have no command to check, remote ones only run on their host
+    const issues =
+      editingApp.kind !== "process"
+        ? []
+        : await invoke<ConfigIssue[]>("validate_app_config", {
+            path: editingApp.path,
+            command: editingApp.command,
+            buildCommand: editingApp.build_command,
+            prerequisites: editingApp.prerequisites,
+            pathEntries: editingApp.path_entries,
+            devcontainer: !!editingApp.devcontainer,
+            shell: editingApp.shell,
+          });
+    if (issues.length > 0) {
+      const shouldSave = await confirm(
+        `${issues.map((issue) => issue.message).join("\n")}\n\nSave anyway?`,
+        { confirm: "Save anyway" }
+      );
+      if (!shouldSave) return;
+    }
+
     const originalApp = apps.find((a) => a.id === editingApp.id);
     const success = await updateApp(editingApp, originalApp);
 
     if (success) {
+      await saveProfiles(editingApp.id, editingProfiles);
       setEditingApp(null);
     }
-  }, [editingApp, apps, updateApp]);
+  }, [editingApp, editingProfiles, apps, updateApp, saveProfiles]);
+
+  const handleEditApp = useCallback(
+    (app: App) => {
+      setEditingApp({ ...app });
+      setEditingProfiles(profiles[app.id] || []);
+    },
+    [profiles]
+  );
+
+  const handleAddApp = useCallback(async () => {
+    const path = await addApp();
+    if (!path) return;
+
+    const hasDevcontainer = await invoke<boolean>("has_devcontainer", {
+      path,
+    }).catch(() => false);
+    if (hasDevcontainer) {
+      const useContainer = await confirm(
+        "This project has a devcontainer.\n\nRun it inside the container?",
+        { confirm: "Use devcontainer", cancel: "Run on host" }
+      );
+      if (useContainer) {
+        await setDevcontainer(path, true);
+      }
+    }
+
+    // Offer to add the repo's other worktrees alongside it
+    const repo = await invoke<RepoBranches | null>("get_repo_branches", {
+      path,
+    }).catch(() => null);
+    const others = (repo?.worktrees ?? []).filter(
+      (w) => w.path !== path && w.branch
+    );
+    if (others.length === 0) return;
+
+    const shouldAdd = await confirm(
+      `This repo has ${others.length} other worktree${others.length === 1 ? "" : "s"}: ${others.map((w) => w.branch).join(", ")}.\n\nAdd them as apps too?`,
+      { confirm: "Add worktrees", cancel: "Skip" }
+    );
+    if (!shouldAdd) return;
+
+    for (const worktree of others) {
+      try {
+        await createWorktreeApp(path, worktree.branch as string);
+      } catch (e) {
+        alert(`Failed to add worktree ${worktree.branch}: ${describeError(e)}`);
+      }
+    }
+  }, [addApp, setDevcontainer, createWorktreeApp]);
+
+  const handleImportWorkspace = useCallback(async () => {
+    const selected = await open({
+      directory: true,
+      multiple: false,
+      title: "Select your workspace root",
+    });
+    if (selected) {
+      setWorkspaceRoot(selected as string);
+    }
+  }, []);
 
   const handleRemoveApp = useCallback(async () => {
     if (!selectedAppId) return;
-    await removeApp(selectedAppId);
+    const app = apps.find((a) => a.id === selectedAppId);
+    const unlocked = await unlock(`remove ${app?.name ?? "the app"}`);
+    if (!unlocked) return;
+
+    let deleteWorktree = false;
+    if (app?.worktree_of) {
+      deleteWorktree = await confirm(
+        `Also delete the worktree at ${app.path}?`,
+        { confirm: "Delete worktree", cancel: "Keep files", destructive: true }
+      );
+    }
+    const trashArtifacts =
+      !deleteWorktree &&
+      app?.kind === "process" &&
+      (await confirm(
+        "Also move node_modules and build output (.next, dist, .turbo) to the Trash?",
+        { confirm: "Move to Trash", cancel: "Keep files" }
+      ));
+
+    try {
+      await removeApp(
+        selectedAppId,
+        {
+          logs: true,
+          trash: trashArtifacts ? ARTIFACT_DIRS : [],
+          worktree: deleteWorktree,
+        },
+        unlocked.passcode
+      );
+    } catch (e) {
+      alert(`Failed to remove app: ${describeError(e)}`);
+      return;
+    }
     setSelectedAppId(null);
-  }, [selectedAppId, removeApp]);
+  }, [selectedAppId, apps, removeApp]);
 
   const selectedApp = apps.find((a) => a.id === selectedAppId);
 
@@ -129,11 +459,27 @@ function AppComponent() {
           setupLoading={setupLoading}
           autoStartEnabled={autoStartEnabled}
           onToggleAutostart={handleToggleAutostart}
+          appLockEnabled={!!appLock?.enabled}
+          onToggleAppLock={handleToggleAppLock}
+          keepAppsOnQuit={quitBehavior === "keep_running"}
+          onToggleKeepAppsOnQuit={handleToggleKeepAppsOnQuit}
+          update={update}
+          updateProgress={updateProgress}
+          onInstallUpdate={handleInstallUpdate}
+          runningCount={Object.keys(runningApps).length}
+          onStopAll={handleStopAll}
           onSetupClick={() => setShowSetupWizard(true)}
           onStartProxy={handleStartProxyService}
           onUninstallProxy={handleUninstallService}
           onLanInfoClick={() => setShowLanInfo(true)}
-          onAddApp={addApp}
+          onRemoteControlClick={() => setShowRemoteControl(true)}
+          onNotificationsClick={() => setShowNotifications(true)}
+          onLogForwardingClick={() => setShowLogForwarding(true)}
+          onSessionsClick={() => setShowSessions(true)}
+          onAddApp={handleAddApp}
+          onImportWorkspace={handleImportWorkspace}
+          onAddService={() => setShowServiceDialog(true)}
+          onAddRemote={() => setShowRemoteDialog(true)}
           showLanButton={!!isProxyOperational && !!lanIp}
           isDbReady={isDbReady}
         />
@@ -142,12 +488,16 @@ function AppComponent() {
           <AppSidebar
             apps={apps}
             runningApps={runningApps}
+            restartNeeded={restartNeeded}
             appsUsage={appsUsage}
+            gitInfo={gitInfo}
+            profiles={profiles}
             selectedAppId={selectedAppId}
             serviceStatus={serviceStatus}
             isProxyOperational={isProxyOperational}
             onSelectApp={setSelectedAppId}
             onStartApp={startApp}
+            onStartAppWithProfile={startAppWithProfile}
             onStopApp={stopApp}
             onOpenApp={handleOpenInBrowser}
           />
@@ -156,13 +506,27 @@ function AppComponent() {
             <AppDetails
               app={selectedApp}
               isRunning={runningApps[selectedApp.id] !== undefined}
+              // Static sites have no process to wait for
+              isReady={
+                readyApps[selectedApp.id] === true ||
+                runningApps[selectedApp.id] === 0
+              }
+              isBuilding={buildingApps[selectedApp.id] === true}
+              slowStart={slowStarts[selectedApp.id]}
+              staleBuild={staleBuilds[selectedApp.id]}
               port={runningApps[selectedApp.id]}
+              route={proxyRoutes[selectedApp.id]}
+              lastAccessed={lastAccessed[selectedApp.id]}
+              pendingChanges={restartNeeded[selectedApp.id]}
               logs={logs[selectedApp.id] || []}
+              profile={runningProfiles[selectedApp.id]}
               serviceStatus={serviceStatus}
               isProxyOperational={isProxyOperational}
-              onEdit={() => setEditingApp({ ...selectedApp })}
+              onEdit={() => handleEditApp(selectedApp)}
               onRemove={handleRemoveApp}
               onRestart={() => restartApp(selectedApp)}
+              onUpdate={() => updateAndRestart(selectedApp)}
+              onNewWorktree={() => setWorktreeApp(selectedApp)}
               onClearLogs={() => clearLogs(selectedApp.id)}
             />
           ) : (
@@ -173,9 +537,37 @@ function AppComponent() {
         <EditAppDialog
           editingApp={editingApp}
           apps={apps}
+          profiles={editingProfiles}
           onClose={() => setEditingApp(null)}
           onSave={handleSaveApp}
           onUpdate={setEditingApp}
+          onProfilesChange={setEditingProfiles}
+        />
+
+        <ImportWorkspaceDialog
+          root={workspaceRoot}
+          apps={apps}
+          onClose={() => setWorkspaceRoot(null)}
+          onImport={addWorkspaceApps}
+        />
+
+        <ServiceDialog
+          open={showServiceDialog}
+          onClose={() => setShowServiceDialog(false)}
+          onAdd={addServiceApp}
+        />
+
+        <RemoteAppDialog
+          open={showRemoteDialog}
+          onClose={() => setShowRemoteDialog(false)}
+          onAdd={addRemoteApp}
+        />
+
+        <WorktreeDialog
+          app={worktreeApp}
+          apps={apps}
+          onClose={() => setWorktreeApp(null)}
+          onCreate={createWorktreeApp}
         />
 
         <SetupWizardDialog
@@ -191,6 +583,37 @@ function AppComponent() {
           lanIp={lanIp}
           onClose={() => setShowLanInfo(false)}
         />
+
+        <RemoteControlDialog
+          open={showRemoteControl}
+          onClose={() => setShowRemoteControl(false)}
+        />
+
+        <NotificationsDialog
+          open={showNotifications}
+          onClose={() => setShowNotifications(false)}
+        />
+
+        <LogForwardingDialog
+          open={showLogForwarding}
+          onClose={() => setShowLogForwarding(false)}
+        />
+
+        <SessionsDialog
+          open={showSessions}
+          apps={apps}
+          runningCount={Object.keys(runningApps).length}
+          onClose={() => setShowSessions(false)}
+          onRestore={handleRestoreSession}
+        />
+
+        <QuickSwitcherDialog
+          open={showQuickSwitcher}
+          apps={apps}
+          runningApps={runningApps}
+          onClose={() => setShowQuickSwitcher(false)}
+          onSelect={setSelectedAppId}
+        />
       </div>
     </TooltipProvider>
   );
@@ -1,11 +1,39 @@
 import { memo, useRef, useEffect, useState, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { Button } from "@/components/ui/button";
 import { Badge } from "@/components/ui/badge";
 import { ScrollArea } from "@/components/ui/scroll-area";
 import { Separator } from "@/components/ui/separator";
+import { Checkbox } from "@/components/ui/checkbox";
+import { Input } from "@/components/ui/input";
 import { cn } from "@/lib/utils";
+import { describeError } from "@/lib/errors";
 import { confirm } from "@/components/confirm-dialog";
-import type { App, LogEntry, ProxyServiceStatus } from "@/types";
+import { parseSourceLocation } from "@/lib/source-location";
+import type {
+  App,
+  AppEvent,
+  AppEventKind,
+  BenchmarkReport,
+  ConfigChange,
+  CleanReport,
+  DiskUsage,
+  DriftKind,
+  EnvDiff,
+  LogEntry,
+  ProcessInspection,
+  WatchPlan,
+  ProjectReadme,
+  ProxyRoute,
+  ProxyServiceStatus,
+  QuitBehavior,
+  SourceLocation,
+  StartPhase,
+  StaleBuild,
+  Uptime,
+  UptimeRange,
+} from "@/types";
 
 const emptyLogsMessage = (
   <p className="text-muted-foreground italic">
@@ -15,11 +43,27 @@ const emptyLogsMessage = (
 
 interface LogLineProps {
   log: LogEntry;
+  onOpenLocation?: (location: SourceLocation) => void;
 }
 
-const LogLine = memo(function LogLine({ log }: LogLineProps) {
+const LogLine = memo(function LogLine({ log, onOpenLocation }: LogLineProps) {
+  const location = onOpenLocation ? parseSourceLocation(log.message) : null;
   return (
-    <div className={cn("py-0.5", log.type === "stderr" && "text-destructive")}>
+    <div
+      className={cn(
+        "py-0.5",
+        log.type === "stderr" && "text-destructive",
+        location && "cursor-pointer hover:underline"
+      )}
+      title={
+        location
+          ? `open ${location.file}:${location.line} in editor`
+          : undefined
+      }
+      onClick={
+        location && onOpenLocation ? () => onOpenLocation(location) : undefined
+      }
+    >
       {log.message}
     </div>
   );
@@ -28,9 +72,15 @@ const LogLine = memo(function LogLine({ log }: LogLineProps) {
 interface AppLogsProps {
   logs: LogEntry[];
   onClear?: () => void;
+  // Lines with a file:line, like stack trace frames, open there when clicked
+  onOpenLocation?: (location: SourceLocation) => void;
 }
 
-const AppLogs = memo(function AppLogs({ logs, onClear }: AppLogsProps) {
+const AppLogs = memo(function AppLogs({
+  logs,
+  onClear,
+  onOpenLocation,
+}: AppLogsProps) {
   const logsEndRef = useRef<HTMLDivElement>(null);
 
   useEffect(() => {
@@ -63,7 +113,7 @@ const AppLogs = memo(function AppLogs({ logs, onClear }: AppLogsProps) {
           ) : (
             <>
               {logs.map((log, i) => (
-                <LogLine key={i} log={log} />
+                <LogLine key={i} log={log} onOpenLocation={onOpenLocation} />
               ))}
               <div ref={logsEndRef} />
             </>
@@ -76,6 +126,8 @@ const AppLogs = memo(function AppLogs({ logs, onClear }: AppLogsProps) {
 
 interface AppUrlsProps {
   subdomain: string | null;
+  // Unix ms when a temporary proxy route goes away
+  expiresAt: number | undefined;
   port: number;
   serviceInstalled: boolean;
   isProxyOperational: boolean | undefined;
@@ -83,6 +135,7 @@ interface AppUrlsProps {
 
 const AppUrls = memo(function AppUrls({
   subdomain,
+  expiresAt,
   port,
   serviceInstalled,
   isProxyOperational,
@@ -137,27 +190,1169 @@ const AppUrls = memo(function AppUrls({
               {!isProxyOperational ? (
                 <span className="text-xs text-warning">(proxy stopped)</span>
               ) : null}
+              {expiresAt ? (
+                <span className="text-xs text-muted-foreground">
+                  until {new Date(expiresAt).toLocaleTimeString()}
+                </span>
+              ) : null}
+            </div>
+          ) : null}
+          {port > 0 ? (
+            <div className="flex items-center gap-2">
+              <a
+                href={localhostUrl}
+                target="_blank"
+                rel="noopener noreferrer"
+                className="text-xs text-muted-foreground underline-offset-2 hover:underline cursor-pointer"
+              >
+                {localhostUrl}
+              </a>
+              <Button
+                variant="ghost"
+                size="sm"
+                className="h-5 text-xs px-2"
+                onClick={() => copyToClipboard(localhostUrl)}
+              >
+                {copiedUrl === localhostUrl ? "copied!" : "copy"}
+              </Button>
             </div>
           ) : null}
-          <div className="flex items-center gap-2">
-            <a
-              href={localhostUrl}
-              target="_blank"
-              rel="noopener noreferrer"
-              className="text-xs text-muted-foreground underline-offset-2 hover:underline cursor-pointer"
+        </div>
+      </div>
+    </>
+  );
+});
+
+function formatSize(bytes: number): string {
+  const units = ["B", "KB", "MB", "GB", "TB"];
+  let value = bytes;
+  let unit = 0;
+  while (value >= 1024 && unit < units.length - 1) {
+    value /= 1024;
+    unit++;
+  }
+  return `${value.toFixed(unit === 0 ? 0 : 1)} ${units[unit]}`;
+}
+
+// Hover text of the version badge: the package name and its engines
+function packageTitle(app: App): string {
+  let engines: Record<string, string> = {};
+  try {
+    engines = JSON.parse(app.package_engines ?? "{}");
+  } catch {}
+  return [
+    app.package_name,
+    ...Object.entries(engines).map(([name, range]) => `${name} ${range}`),
+  ]
+    .filter(Boolean)
+    .join(" · ");
+}
+
+// Apps without a request through the proxy for this long get a cleanup hint
+const STALE_AFTER_DAYS = 21;
+const DAY_MS = 24 * 60 * 60 * 1000;
+
+function LastAccessedLabel({ at }: { at: number | undefined }) {
+  if (at === undefined) {
+    return <span className="text-xs text-muted-foreground">never</span>;
+  }
+  const days = Math.floor((Date.now() - at) / DAY_MS);
+  if (days < 1) {
+    return <span className="text-xs">today</span>;
+  }
+  if (days < STALE_AFTER_DAYS) {
+    return (
+      <span className="text-xs">
+        {days === 1 ? "yesterday" : `${days} days ago`}
+      </span>
+    );
+  }
+  const weeks = Math.floor(days / 7);
+  return (
+    <span className="text-xs text-warning">
+      {weeks} weeks ago · unused, consider removing it
+    </span>
+  );
+}
+
+const cleanTargets = ["node_modules", ".next", "dist", ".turbo"];
+
+// How often a running database is probed until it accepts connections, and after
+const SERVICE_HEALTH_INTERVAL_MS = 3000;
+
+const ServiceHealthBadge = memo(function ServiceHealthBadge({
+  appId,
+}: {
+  appId: string;
+}) {
+  const [healthy, setHealthy] = useState<boolean | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    setHealthy(null);
+    const check = () =>
+      invoke<boolean>("check_service_health", { id: appId })
+        .then((result) => {
+          if (!cancelled) setHealthy(result);
+        })
+        .catch(() => {
+          if (!cancelled) setHealthy(false);
+        });
+    check();
+    const interval = setInterval(check, SERVICE_HEALTH_INTERVAL_MS);
+    return () => {
+      cancelled = true;
+      clearInterval(interval);
+    };
+  }, [appId]);
+
+  return (
+    <Badge
+      variant="outline"
+      className={cn(
+        "text-xs",
+        healthy && "bg-success/10 text-success border-success/30"
+      )}
+    >
+      {healthy ? "accepting connections" : "starting..."}
+    </Badge>
+  );
+});
+
+const AppBenchmark = memo(function AppBenchmark({
+  appId,
+}: {
+  appId: string;
+}) {
+  const [duration, setDuration] = useState(10);
+  const [concurrency, setConcurrency] = useState(10);
+  const [running, setRunning] = useState(false);
+  const [report, setReport] = useState<BenchmarkReport | null>(null);
+
+  useEffect(() => {
+    setReport(null);
+  }, [appId]);
+
+  const handleRun = useCallback(async () => {
+    setRunning(true);
+    try {
+      setReport(
+        await invoke<BenchmarkReport>("benchmark_app", {
+          id: appId,
+          duration,
+          concurrency,
+        })
+      );
+    } catch (e) {
+      alert(`Benchmark failed: ${describeError(e)}`);
+    } finally {
+      setRunning(false);
+    }
+  }, [appId, duration, concurrency]);
+
+  return (
+    <>
+      <Separator className="my-3" />
+      <div className="flex items-center gap-2 text-xs">
+        <span className="text-muted-foreground uppercase tracking-wider">
+          benchmark
+        </span>
+        <Input
+          type="number"
+          min={1}
+          max={60}
+          value={duration}
+          onChange={(e) => setDuration(parseInt(e.target.value) || 1)}
+          className="h-6 w-14 text-xs"
+          aria-label="duration in seconds"
+        />
+        <span className="text-muted-foreground">s ×</span>
+        <Input
+          type="number"
+          min={1}
+          max={256}
+          value={concurrency}
+          onChange={(e) => setConcurrency(parseInt(e.target.value) || 1)}
+          className="h-6 w-14 text-xs"
+          aria-label="concurrent connections"
+        />
+        <span className="text-muted-foreground">connections</span>
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-5 text-xs px-2"
+          onClick={handleRun}
+          disabled={running}
+        >
+          {running ? "running..." : "run"}
+        </Button>
+      </div>
+      {report ? (
+        <div className="mt-2 text-xs text-muted-foreground space-y-1">
+          <div>
+            <span className="text-foreground">
+              {report.requests_per_sec.toFixed(0)} req/s
+            </span>
+            {" · "}
+            {report.requests} requests
+            {" · "}
+            <span className={cn(report.errors > 0 && "text-destructive")}>
+              {report.errors} errors
+            </span>
+          </div>
+          <div>
+            p50 {report.latency.p50.toFixed(1)}ms · p90{" "}
+            {report.latency.p90.toFixed(1)}ms · p99{" "}
+            {report.latency.p99.toFixed(1)}ms · max{" "}
+            {report.latency.max.toFixed(1)}ms
+          </div>
+          <div>
+            {Object.entries(report.status_codes)
+              .map(([status, count]) => `${status}: ${count}`)
+              .join(" · ")}
+          </div>
+        </div>
+      ) : null}
+    </>
+  );
+});
+
+const UPTIME_RANGES: UptimeRange[] = ["24h", "7d", "30d"];
+
+const formatIncidentTime = (ms: number) =>
+  new Date(ms).toLocaleString(undefined, {
+    month: "short",
+    day: "numeric",
+    hour: "2-digit",
+    minute: "2-digit",
+  });
+
+const AppUptime = memo(function AppUptime({ appId }: { appId: string }) {
+  const [range, setRange] = useState<UptimeRange>("24h");
+  const [uptime, setUptime] = useState<Uptime | null>(null);
+  const [path, setPath] = useState("");
+
+  const load = useCallback(async () => {
+    try {
+      const result = await invoke<Uptime>("get_uptime", { id: appId, range });
+      setUptime(result);
+      setPath(result.health_path);
+    } catch (e) {
+      console.error("Failed to load uptime:", e);
+    }
+  }, [appId, range]);
+
+  useEffect(() => {
+    setUptime(null);
+    load();
+  }, [load]);
+
+  const handleSavePath = useCallback(async () => {
+    try {
+      await invoke("set_health_path", { id: appId, path });
+      await load();
+    } catch (e) {
+      alert(`Failed to set health path: ${describeError(e)}`);
+    }
+  }, [appId, path, load]);
+
+  return (
+    <>
+      <Separator className="my-3" />
+      <div className="flex items-center gap-2 text-xs">
+        <span className="text-muted-foreground uppercase tracking-wider">
+          uptime
+        </span>
+        {UPTIME_RANGES.map((r) => (
+          <Button
+            key={r}
+            variant={r === range ? "secondary" : "ghost"}
+            size="sm"
+            className="h-5 text-xs px-2"
+            onClick={() => setRange(r)}
+          >
+            {r}
+          </Button>
+        ))}
+        <Input
+          value={path}
+          onChange={(e) => setPath(e.target.value)}
+          onBlur={handleSavePath}
+          onKeyDown={(e) => e.key === "Enter" && handleSavePath()}
+          className="h-6 w-32 text-xs ml-auto"
+          aria-label="health check path"
+        />
+      </div>
+      {uptime ? (
+        <div className="mt-2 text-xs text-muted-foreground space-y-1">
+          {uptime.uptime_percent === null ? (
+            <div>no checks yet; running apps are pinged every 30s</div>
+          ) : (
+            <div>
+              <span
+                className={cn(
+                  "text-foreground",
+                  uptime.uptime_percent < 99 && "text-destructive"
+                )}
+              >
+                {uptime.uptime_percent.toFixed(2)}%
+              </span>
+              {" · "}
+              {uptime.checks} checks
+              {uptime.avg_response_ms !== null
+                ? ` · avg ${uptime.avg_response_ms.toFixed(0)}ms`
+                : null}
+            </div>
+          )}
+          {uptime.incidents.slice(0, 5).map((incident) => (
+            <div key={incident.started_at}>
+              <span className="text-destructive">down</span>{" "}
+              {formatIncidentTime(incident.started_at)}
+              {incident.ended_at
+                ? ` → ${formatIncidentTime(incident.ended_at)}`
+                : " → now"}
+              {" · "}
+              {incident.error}
+            </div>
+          ))}
+        </div>
+      ) : null}
+    </>
+  );
+});
+
+const APP_EVENT_LABELS: Record<AppEventKind, string> = {
+  started: "started",
+  stopped: "stopped",
+  crashed: "crashed",
+  config_changed: "config changed",
+  route_added: "route added",
+  route_removed: "route removed",
+  health_down: "health check failing",
+  health_up: "health check passing",
+};
+
+const PROBLEM_EVENTS: AppEventKind[] = ["crashed", "health_down"];
+
+// Entries shown before "show all"
+const TIMELINE_PREVIEW = 10;
+
+const AppTimeline = memo(function AppTimeline({ appId }: { appId: string }) {
+  const [events, setEvents] = useState<AppEvent[] | null>(null);
+  const [showAll, setShowAll] = useState(false);
+
+  useEffect(() => {
+    setEvents(null);
+    setShowAll(false);
+    invoke<AppEvent[]>("get_app_timeline", { id: appId })
+      .then(setEvents)
+      .catch((e) => console.error("Failed to load timeline:", e));
+    const unlisten = listen<AppEvent>("app-event", (event) => {
+      if (event.payload.app_id !== appId) return;
+      setEvents((prev) => (prev ? [event.payload, ...prev] : prev));
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [appId]);
+
+  if (!events) return null;
+  const shown = showAll ? events : events.slice(0, TIMELINE_PREVIEW);
+
+  return (
+    <>
+      <Separator className="my-3" />
+      <div className="flex items-center gap-2 text-xs">
+        <span className="text-muted-foreground uppercase tracking-wider">
+          activity this week
+        </span>
+        {events.length > TIMELINE_PREVIEW ? (
+          <Button
+            variant="ghost"
+            size="sm"
+            className="h-5 text-xs px-2 ml-auto"
+            onClick={() => setShowAll((prev) => !prev)}
+          >
+            {showAll ? "show less" : `show all ${events.length}`}
+          </Button>
+        ) : null}
+      </div>
+      <div className="mt-2 text-xs text-muted-foreground space-y-1">
+        {events.length === 0 ? <div>nothing happened yet</div> : null}
+        {shown.map((event, i) => (
+          <div key={`${event.at}-${i}`}>
+            {formatIncidentTime(event.at)}{" "}
+            <span
+              className={cn(
+                "text-foreground",
+                PROBLEM_EVENTS.includes(event.kind) && "text-destructive"
+              )}
             >
-              {localhostUrl}
-            </a>
+              {APP_EVENT_LABELS[event.kind]}
+            </span>
+            {event.detail ? ` · ${event.detail}` : null}
+          </div>
+        ))}
+      </div>
+    </>
+  );
+});
+
+// null follows the global setting
+const QUIT_BEHAVIORS: { value: QuitBehavior | null; label: string }[] = [
+  { value: null, label: "default" },
+  { value: "stop", label: "stop" },
+  { value: "keep_running", label: "keep running" },
+];
+
+const AppQuitBehavior = memo(function AppQuitBehavior({
+  appId,
+  isRunning,
+}: {
+  appId: string;
+  isRunning: boolean;
+}) {
+  const [behavior, setBehavior] = useState<QuitBehavior | null>(null);
+  const [changed, setChanged] = useState(false);
+
+  useEffect(() => {
+    setChanged(false);
+    invoke<QuitBehavior | null>("get_app_quit_behavior", { id: appId })
+      .then(setBehavior)
+      .catch((e) => console.error("Failed to load quit behavior:", e));
+  }, [appId]);
+
+  const handleChange = useCallback(
+    async (next: QuitBehavior | null) => {
+      try {
+        await invoke("set_app_quit_behavior", { id: appId, behavior: next });
+        setBehavior(next);
+        setChanged(true);
+      } catch (e) {
+        alert(`Failed to set quit behavior: ${describeError(e)}`);
+      }
+    },
+    [appId]
+  );
+
+  return (
+    <>
+      <Separator className="my-3" />
+      <div className="flex items-center gap-2 text-xs">
+        <span className="text-muted-foreground uppercase tracking-wider">
+          on quit
+        </span>
+        {QUIT_BEHAVIORS.map(({ value, label }) => (
+          <Button
+            key={label}
+            variant={value === behavior ? "secondary" : "ghost"}
+            size="sm"
+            className="h-5 text-xs px-2"
+            onClick={() => handleChange(value)}
+          >
+            {label}
+          </Button>
+        ))}
+      </div>
+      {changed && isRunning ? (
+        <p className="mt-2 text-xs text-muted-foreground">
+          takes effect the next time the app starts
+        </p>
+      ) : null}
+    </>
+  );
+});
+
+const AppLogSampling = memo(function AppLogSampling({
+  appId,
+}: {
+  appId: string;
+}) {
+  const [sampled, setSampled] = useState(false);
+
+  useEffect(() => {
+    invoke<boolean>("get_app_log_sampling", { id: appId })
+      .then(setSampled)
+      .catch((e) => console.error("Failed to load log sampling:", e));
+  }, [appId]);
+
+  const handleChange = useCallback(
+    async (next: boolean) => {
+      try {
+        await invoke("set_app_log_sampling", { id: appId, sampled: next });
+        setSampled(next);
+      } catch (e) {
+        alert(`Failed to set log sampling: ${describeError(e)}`);
+      }
+    },
+    [appId]
+  );
+
+  return (
+    <div className="mt-2 flex items-center gap-2 text-xs">
+      <span className="text-muted-foreground uppercase tracking-wider">
+        noisy output
+      </span>
+      {[
+        { value: false, label: "show all" },
+        { value: true, label: "sample" },
+      ].map(({ value, label }) => (
+        <Button
+          key={label}
+          variant={value === sampled ? "secondary" : "ghost"}
+          size="sm"
+          className="h-5 text-xs px-2"
+          onClick={() => handleChange(value)}
+        >
+          {label}
+        </Button>
+      ))}
+    </div>
+  );
+});
+
+const AppNotifyReady = memo(function AppNotifyReady({
+  appId,
+}: {
+  appId: string;
+}) {
+  const [notify, setNotify] = useState(false);
+
+  useEffect(() => {
+    invoke<boolean>("get_app_notify_ready", { id: appId })
+      .then(setNotify)
+      .catch((e) => console.error("Failed to load ready notification:", e));
+  }, [appId]);
+
+  const handleChange = useCallback(
+    async (next: boolean) => {
+      try {
+        await invoke("set_app_notify_ready", { id: appId, notify: next });
+        setNotify(next);
+      } catch (e) {
+        alert(`Failed to set ready notification: ${describeError(e)}`);
+      }
+    },
+    [appId]
+  );
+
+  return (
+    <div className="mt-2 flex items-center gap-2 text-xs">
+      <span className="text-muted-foreground uppercase tracking-wider">
+        when ready
+      </span>
+      {[
+        { value: false, label: "nothing" },
+        { value: true, label: "notify" },
+      ].map(({ value, label }) => (
+        <Button
+          key={label}
+          variant={value === notify ? "secondary" : "ghost"}
+          size="sm"
+          className="h-5 text-xs px-2"
+          onClick={() => handleChange(value)}
+        >
+          {label}
+        </Button>
+      ))}
+      {notify ? (
+        <span className="text-muted-foreground">
+          in Notification Center and your channels
+        </span>
+      ) : null}
+    </div>
+  );
+});
+
+const AppSandbox = memo(function AppSandbox({
+  appId,
+  isRunning,
+}: {
+  appId: string;
+  isRunning: boolean;
+}) {
+  const [sandboxed, setSandboxed] = useState(false);
+
+  useEffect(() => {
+    invoke<boolean>("get_app_sandbox", { id: appId })
+      .then(setSandboxed)
+      .catch((e) => console.error("Failed to load sandbox:", e));
+  }, [appId]);
+
+  const handleChange = useCallback(
+    async (next: boolean) => {
+      try {
+        await invoke("set_app_sandbox", { id: appId, sandboxed: next });
+        setSandboxed(next);
+      } catch (e) {
+        alert(`Failed to set sandbox: ${describeError(e)}`);
+      }
+    },
+    [appId]
+  );
+
+  return (
+    <div className="mt-2 flex items-center gap-2 text-xs">
+      <span className="text-muted-foreground uppercase tracking-wider">
+        sandbox
+      </span>
+      {[
+        { value: false, label: "off" },
+        { value: true, label: "on" },
+      ].map(({ value, label }) => (
+        <Button
+          key={label}
+          variant={value === sandboxed ? "secondary" : "ghost"}
+          size="sm"
+          className="h-5 text-xs px-2"
+          onClick={() => handleChange(value)}
+        >
+          {label}
+        </Button>
+      ))}
+      {sandboxed ? (
+        <span className="text-muted-foreground">
+          {isRunning
+            ? "applies from the next start"
+            : "no network, writes only inside the project"}
+        </span>
+      ) : null}
+    </div>
+  );
+});
+
+const AppStaleBuilds = memo(function AppStaleBuilds({
+  appId,
+}: {
+  appId: string;
+}) {
+  const [rebuild, setRebuild] = useState(false);
+
+  useEffect(() => {
+    invoke<boolean>("get_app_rebuild_stale", { id: appId })
+      .then(setRebuild)
+      .catch((e) => console.error("Failed to load stale build setting:", e));
+  }, [appId]);
+
+  const handleChange = useCallback(
+    async (next: boolean) => {
+      try {
+        await invoke("set_app_rebuild_stale", { id: appId, rebuild: next });
+        setRebuild(next);
+      } catch (e) {
+        alert(`Failed to set stale build setting: ${describeError(e)}`);
+      }
+    },
+    [appId]
+  );
+
+  return (
+    <div className="mt-2 flex items-center gap-2 text-xs">
+      <span className="text-muted-foreground uppercase tracking-wider">
+        stale build
+      </span>
+      {[
+        { value: false, label: "warn" },
+        { value: true, label: "rebuild" },
+      ].map(({ value, label }) => (
+        <Button
+          key={label}
+          variant={value === rebuild ? "secondary" : "ghost"}
+          size="sm"
+          className="h-5 text-xs px-2"
+          onClick={() => handleChange(value)}
+        >
+          {label}
+        </Button>
+      ))}
+      <span className="text-muted-foreground">
+        when a command like next start serves an old build
+      </span>
+    </div>
+  );
+});
+
+const AppProcesses = memo(function AppProcesses({
+  appId,
+}: {
+  appId: string;
+}) {
+  const [inspection, setInspection] = useState<ProcessInspection | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [envFilter, setEnvFilter] = useState("");
+
+  useEffect(() => {
+    setInspection(null);
+    setEnvFilter("");
+  }, [appId]);
+
+  const handleInspect = useCallback(async () => {
+    setLoading(true);
+    try {
+      setInspection(
+        await invoke<ProcessInspection>("inspect_process", { id: appId })
+      );
+    } catch (e) {
+      alert(`Failed to inspect process: ${describeError(e)}`);
+    } finally {
+      setLoading(false);
+    }
+  }, [appId]);
+
+  const filter = envFilter.trim().toLowerCase();
+
+  return (
+    <>
+      <Separator className="my-3" />
+      <div className="flex items-center gap-2 text-xs">
+        <span className="text-muted-foreground uppercase tracking-wider">
+          processes
+        </span>
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-5 text-xs px-2"
+          onClick={handleInspect}
+          disabled={loading}
+        >
+          {loading ? "inspecting..." : inspection ? "refresh" : "inspect"}
+        </Button>
+        {inspection ? (
+          <Input
+            value={envFilter}
+            onChange={(e) => setEnvFilter(e.target.value)}
+            placeholder="filter env, e.g. DATABASE"
+            className="h-6 w-48 text-xs ml-auto"
+          />
+        ) : null}
+      </div>
+      {inspection ? (
+        <div className="mt-2 space-y-3 text-xs">
+          {inspection.processes.map((process) => {
+            const sockets = inspection.sockets.filter(
+              (socket) => socket.pid === process.pid
+            );
+            const env = Object.entries(process.env).filter(
+              ([key, value]) =>
+                !filter ||
+                key.toLowerCase().includes(filter) ||
+                value.toLowerCase().includes(filter)
+            );
+            return (
+              <div key={process.pid} className="space-y-1">
+                <div>
+                  <span className="text-foreground">{process.name}</span>
+                  <span className="text-muted-foreground">
+                    {" "}
+                    · pid {process.pid} · {Object.keys(process.env).length}{" "}
+                    env vars
+                    {sockets.length > 0
+                      ? ` · listening on ${sockets
+                          .map((socket) => `${socket.address}:${socket.port}`)
+                          .join(", ")}`
+                      : ""}
+                  </span>
+                </div>
+                <p className="font-mono text-muted-foreground break-all">
+                  {process.command.join(" ")}
+                </p>
+                {process.cwd ? (
+                  <p className="text-muted-foreground">cwd {process.cwd}</p>
+                ) : null}
+                {filter && env.length > 0 ? (
+                  <div className="font-mono break-all">
+                    {env.map(([key, value]) => (
+                      <div key={key}>
+                        <span className="text-foreground">{key}</span>
+                        <span className="text-muted-foreground">={value}</span>
+                      </div>
+                    ))}
+                  </div>
+                ) : null}
+              </div>
+            );
+          })}
+        </div>
+      ) : null}
+    </>
+  );
+});
+
+const DRIFT_LABELS: Record<DriftKind, string> = {
+  missing: "missing",
+  changed: "changed",
+  removed: "removed from config",
+  not_exported: "not exported",
+};
+
+const AppEnvDrift = memo(function AppEnvDrift({
+  appId,
+  onRestart,
+}: {
+  appId: string;
+  onRestart: () => void | Promise<void>;
+}) {
+  const [diff, setDiff] = useState<EnvDiff | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  useEffect(() => {
+    setDiff(null);
+  }, [appId]);
+
+  const handleCompare = useCallback(async () => {
+    setLoading(true);
+    try {
+      setDiff(await invoke<EnvDiff>("diff_app_env", { id: appId }));
+    } catch (e) {
+      alert(`Failed to compare env: ${describeError(e)}`);
+    } finally {
+      setLoading(false);
+    }
+  }, [appId]);
+
+  return (
+    <>
+      <Separator className="my-3" />
+      <div className="flex items-center gap-2 text-xs">
+        <span className="text-muted-foreground uppercase tracking-wider">
+          env drift
+        </span>
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-5 text-xs px-2"
+          onClick={handleCompare}
+          disabled={loading}
+        >
+          {loading ? "comparing..." : diff ? "refresh" : "compare"}
+        </Button>
+        {diff?.restart_needed ? (
+          <Button
+            variant="ghost"
+            size="sm"
+            className="h-5 text-xs px-2 ml-auto text-destructive"
+            onClick={onRestart}
+          >
+            restart to apply
+          </Button>
+        ) : null}
+      </div>
+      {diff ? (
+        <div className="mt-2 text-xs space-y-1">
+          <div className="text-muted-foreground">
+            pid {diff.pid} · {diff.matched} matching
+            {diff.restart_needed ? "" : " · up to date with config"}
+          </div>
+          <div className="font-mono break-all">
+            {diff.drift.map((entry) => (
+              <div key={entry.key}>
+                <span
+                  className={cn(
+                    "text-foreground",
+                    entry.kind !== "not_exported" && "text-destructive"
+                  )}
+                >
+                  {entry.key}
+                </span>
+                <span className="text-muted-foreground">
+                  {" "}
+                  {DRIFT_LABELS[entry.kind]}
+                  {entry.source ? ` (${entry.source})` : ""}
+                  {entry.actual !== null ? ` · running ${entry.actual}` : ""}
+                  {entry.expected !== null ? ` · config ${entry.expected}` : ""}
+                </span>
+              </div>
+            ))}
+          </div>
+        </div>
+      ) : null}
+    </>
+  );
+});
+
+interface AppDiskUsageProps {
+  appId: string;
+  isRunning: boolean;
+}
+
+const AppWatchedPaths = memo(function AppWatchedPaths({
+  appId,
+}: {
+  appId: string;
+}) {
+  const [plan, setPlan] = useState<WatchPlan | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  useEffect(() => {
+    setPlan(null);
+  }, [appId]);
+
+  const handlePreview = useCallback(async () => {
+    setLoading(true);
+    try {
+      setPlan(await invoke<WatchPlan>("preview_watched_paths", { id: appId }));
+    } catch (e) {
+      alert(`Failed to list watched paths: ${describeError(e)}`);
+    } finally {
+      setLoading(false);
+    }
+  }, [appId]);
+
+  return (
+    <>
+      <Separator className="my-3" />
+      <div className="flex items-center gap-2 text-xs">
+        <span className="text-muted-foreground uppercase tracking-wider">
+          watched paths
+        </span>
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-5 text-xs px-2"
+          onClick={handlePreview}
+          disabled={loading}
+        >
+          {loading ? "scanning..." : plan ? "refresh" : "preview"}
+        </Button>
+        {plan ? (
+          <span className="text-muted-foreground">
+            {plan.files} files in {plan.dirs.length} directories
+            {plan.truncated ? " (list cut short)" : ""}
+          </span>
+        ) : null}
+      </div>
+      {plan ? (
+        <div className="mt-2 grid grid-cols-2 gap-3 text-xs">
+          {[
+            { label: "watched", paths: plan.dirs },
+            { label: "ignored", paths: plan.ignored },
+          ].map(({ label, paths }) => (
+            <div key={label}>
+              <div className="text-muted-foreground mb-1">{label}</div>
+              <div className="max-h-40 overflow-auto space-y-0.5">
+                {paths.map((path) => (
+                  <div key={path} className="truncate">
+                    {path || "."}
+                  </div>
+                ))}
+              </div>
+            </div>
+          ))}
+        </div>
+      ) : null}
+    </>
+  );
+});
+
+const AppDiskUsage = memo(function AppDiskUsage({
+  appId,
+  isRunning,
+}: AppDiskUsageProps) {
+  const [usage, setUsage] = useState<DiskUsage | null>(null);
+  const [loading, setLoading] = useState(false);
+  // Dry run result the user picks targets from before anything is deleted
+  const [cleanup, setCleanup] = useState<CleanReport | null>(null);
+  const [selected, setSelected] = useState<string[]>([]);
+
+  const load = useCallback(
+    async (refresh: boolean, isCancelled: () => boolean = () => false) => {
+      setLoading(true);
+      try {
+        const result = await invoke<DiskUsage>("get_disk_usage", {
+          id: appId,
+          refresh,
+        });
+        if (!isCancelled()) setUsage(result);
+      } catch (e) {
+        console.error("Failed to compute disk usage:", e);
+      } finally {
+        if (!isCancelled()) setLoading(false);
+      }
+    },
+    [appId]
+  );
+
+  useEffect(() => {
+    let cancelled = false;
+    setUsage(null);
+    setCleanup(null);
+    load(false, () => cancelled);
+    return () => {
+      cancelled = true;
+    };
+  }, [load]);
+
+  const handlePreviewClean = useCallback(async () => {
+    try {
+      const report = await invoke<CleanReport>("clean_project", {
+        id: appId,
+        targets: cleanTargets,
+        dryRun: true,
+      });
+      if (report.targets.length === 0) {
+        alert("Nothing to clean");
+        return;
+      }
+      setCleanup(report);
+      setSelected(report.targets.map((t) => t.target));
+    } catch (e) {
+      alert(`Failed to check project: ${describeError(e)}`);
+    }
+  }, [appId]);
+
+  const handleClean = useCallback(async () => {
+    try {
+      await invoke<CleanReport>("clean_project", {
+        id: appId,
+        targets: selected,
+        dryRun: false,
+      });
+      setCleanup(null);
+      await load(true);
+    } catch (e) {
+      alert(`Failed to clean project: ${describeError(e)}`);
+    }
+  }, [appId, selected, load]);
+
+  const toggleTarget = useCallback((target: string, checked: boolean) => {
+    setSelected((prev) =>
+      checked ? [...prev, target] : prev.filter((t) => t !== target)
+    );
+  }, []);
+
+  const selectedBytes = (cleanup?.targets ?? [])
+    .filter((t) => selected.includes(t.target))
+    .reduce((sum, t) => sum + t.bytes, 0);
+
+  return (
+    <>
+      <Separator className="my-3" />
+      <div className="flex items-center gap-2 text-xs">
+        <span className="text-muted-foreground uppercase tracking-wider">
+          disk
+        </span>
+        {usage ? (
+          <span className="text-muted-foreground">
+            <span className="text-foreground">{formatSize(usage.total)}</span>
+            {" · "}node_modules {formatSize(usage.node_modules)}
+            {" · "}build {formatSize(usage.build)}
+            {" · "}cache {formatSize(usage.cache)}
+          </span>
+        ) : (
+          <span className="text-muted-foreground italic">
+            {loading ? "calculating..." : "—"}
+          </span>
+        )}
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-5 text-xs px-2"
+          onClick={() => load(true)}
+          disabled={loading}
+        >
+          refresh
+        </Button>
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-5 text-xs px-2"
+          onClick={handlePreviewClean}
+          disabled={isRunning || cleanup !== null}
+          title={isRunning ? "stop the app to clean it" : undefined}
+        >
+          clean
+        </Button>
+      </div>
+      {cleanup ? (
+        <div className="mt-2 space-y-1 text-xs">
+          {cleanup.targets.map(({ target, bytes }) => (
+            <label key={target} className="flex items-center gap-2">
+              <Checkbox
+                checked={selected.includes(target)}
+                onCheckedChange={(checked) =>
+                  toggleTarget(target, checked === true)
+                }
+              />
+              <span>{target}</span>
+              <span className="text-muted-foreground">
+                {formatSize(bytes)}
+              </span>
+            </label>
+          ))}
+          <div className="flex items-center gap-2 pt-1">
+            <Button
+              variant="destructive"
+              size="sm"
+              className="h-6 text-xs"
+              onClick={handleClean}
+              disabled={selected.length === 0 || isRunning}
+            >
+              delete ({formatSize(selectedBytes)})
+            </Button>
             <Button
               variant="ghost"
               size="sm"
-              className="h-5 text-xs px-2"
-              onClick={() => copyToClipboard(localhostUrl)}
+              className="h-6 text-xs"
+              onClick={() => setCleanup(null)}
             >
-              {copiedUrl === localhostUrl ? "copied!" : "copy"}
+              cancel
             </Button>
           </div>
         </div>
+      ) : null}
+    </>
+  );
+});
+
+interface AppReadmeProps {
+  path: string;
+}
+
+const AppReadme = memo(function AppReadme({ path }: AppReadmeProps) {
+  const [readme, setReadme] = useState<ProjectReadme | null>(null);
+  const [expanded, setExpanded] = useState(false);
+
+  useEffect(() => {
+    let cancelled = false;
+    setReadme(null);
+    setExpanded(false);
+    invoke<ProjectReadme | null>("read_project_readme", { path })
+      .then((result) => {
+        if (!cancelled) setReadme(result);
+      })
+      .catch((e) => console.error("Failed to read README:", e));
+    return () => {
+      cancelled = true;
+    };
+  }, [path]);
+
+  if (!readme) return null;
+
+  return (
+    <>
+      <Separator className="my-3" />
+      <div className="space-y-2">
+        <div className="flex items-center gap-2">
+          <span className="text-xs text-muted-foreground uppercase tracking-wider">
+            {readme.file_name}
+          </span>
+          <Button
+            variant="ghost"
+            size="sm"
+            className="h-5 text-xs px-2"
+            onClick={() => setExpanded((prev) => !prev)}
+          >
+            {expanded ? "hide" : "show"}
+          </Button>
+        </div>
+        {expanded ? (
+          <ScrollArea className="max-h-64">
+            <pre className="text-xs whitespace-pre-wrap break-words">
+              {readme.content}
+              {readme.truncated ? "\n\n… (truncated)" : null}
+            </pre>
+          </ScrollArea>
+        ) : null}
       </div>
     </>
   );
@@ -166,28 +1361,56 @@ const AppUrls = memo(function AppUrls({
 interface AppDetailsProps {
   app: App;
   isRunning: boolean;
+  isReady: boolean;
+  // In its build step, or printing build output before it is ready
+  isBuilding: boolean;
+  // Set once it has taken longer than allowed to become ready
+  slowStart: StartPhase | undefined;
+  // Set when it was started on a build older than its sources
+  staleBuild: StaleBuild | undefined;
   port: number | undefined;
+  // What the proxy serves it on, with subdomain template variables filled in
+  route: ProxyRoute | undefined;
+  // Unix ms of its last request through the proxy
+  lastAccessed: number | undefined;
+  // Saved config the running app hasn't picked up yet
+  pendingChanges: ConfigChange[] | undefined;
   logs: LogEntry[];
+  profile: string | undefined;
   serviceStatus: ProxyServiceStatus | null;
   isProxyOperational: boolean | undefined;
   onEdit: () => void;
   onRemove: () => void;
   onRestart: () => void | Promise<void>;
+  onUpdate: () => Promise<void>;
+  onNewWorktree: () => void;
   onClearLogs: () => void;
 }
 
 export const AppDetails = memo(function AppDetails({
   app,
   isRunning,
+  isReady,
+  isBuilding,
+  slowStart,
+  staleBuild,
   port,
+  route,
+  lastAccessed,
+  pendingChanges,
   logs,
+  profile,
   serviceStatus,
   isProxyOperational,
   onEdit,
   onRemove,
   onRestart,
+  onUpdate,
+  onNewWorktree,
   onClearLogs,
 }: AppDetailsProps) {
+  const [updating, setUpdating] = useState(false);
+
   const handleRemove = useCallback(async () => {
     const shouldRemove = await confirm("Remove this app?", {
       confirm: "Remove",
@@ -198,20 +1421,137 @@ export const AppDetails = memo(function AppDetails({
     }
   }, [onRemove]);
 
+  const handleOpenTerminal = useCallback(async () => {
+    try {
+      await invoke("open_terminal", { id: app.id });
+    } catch (e) {
+      alert(`Failed to open terminal: ${describeError(e)}`);
+    }
+  }, [app.id]);
+
+  const handleUpdate = useCallback(async () => {
+    setUpdating(true);
+    try {
+      await onUpdate();
+    } finally {
+      setUpdating(false);
+    }
+  }, [onUpdate]);
+
+  const handleReveal = useCallback(async () => {
+    try {
+      await invoke("reveal_in_finder", { id: app.id });
+    } catch (e) {
+      alert(`Failed to reveal in Finder: ${describeError(e)}`);
+    }
+  }, [app.id]);
+
+  const handleOpenInEditor = useCallback(
+    async (location?: SourceLocation) => {
+      try {
+        await invoke("open_in_editor", { id: app.id, location });
+      } catch (e) {
+        alert(`Failed to open editor: ${describeError(e)}`);
+      }
+    },
+    [app.id]
+  );
+
+  const startState = isBuilding ? "building..." : "starting...";
+
   return (
     <section className="flex-1 flex flex-col overflow-hidden">
       <div className="px-4 py-3 border-b border-border flex items-center justify-between">
         <div className="flex items-center gap-2">
           <span className="text-muted-foreground">&gt;</span>
           <h2 className="text-sm font-semibold">{app.name}</h2>
-          {isRunning ? (
+          {app.kind === "static" ? (
+            <Badge variant="outline" className="text-xs">
+              static{app.spa ? " · spa" : ""}
+            </Badge>
+          ) : null}
+          {app.kind === "remote" && app.remote_host ? (
+            <Badge variant="outline" className="text-xs">
+              ssh · {app.remote_host}
+            </Badge>
+          ) : null}
+          {app.devcontainer ? (
+            <Badge variant="outline" className="text-xs">
+              devcontainer
+            </Badge>
+          ) : null}
+          {app.package_version ? (
+            <Badge
+              variant="outline"
+              className="text-xs"
+              title={packageTitle(app)}
+            >
+              v{app.package_version}
+            </Badge>
+          ) : null}
+          {app.engine_warning ? (
+            <Badge
+              variant="outline"
+              className="text-xs text-warning border-warning/30"
+            >
+              {app.engine_warning}
+            </Badge>
+          ) : null}
+          {app.service ? (
+            <Badge variant="outline" className="text-xs">
+              {app.service}
+            </Badge>
+          ) : null}
+          {app.description ? (
+            <span className="text-xs text-muted-foreground truncate">
+              {app.description}
+            </span>
+          ) : null}
+          {isRunning || isBuilding ? (
             <>
               <Badge
                 variant="outline"
-                className="text-xs bg-success/10 text-success border-success/30"
+                className={cn(
+                  "text-xs",
+                  isReady && "bg-success/10 text-success border-success/30"
+                )}
               >
-                running
+                {isReady ? "running" : startState}
               </Badge>
+              {slowStart && !isReady ? (
+                <Badge
+                  variant="outline"
+                  className="text-xs text-warning border-warning/30"
+                >
+                  {slowStart === "building"
+                    ? "build is taking long · check the logs"
+                    : "slow to start · check the logs"}
+                </Badge>
+              ) : null}
+              {staleBuild ? (
+                <Badge
+                  variant="outline"
+                  className="text-xs text-warning border-warning/30"
+                >
+                  {staleBuild.changed
+                    ? `old build · ${staleBuild.changed} changed since`
+                    : `not built · ${staleBuild.artifact} is missing`}
+                </Badge>
+              ) : null}
+              {app.service ? <ServiceHealthBadge appId={app.id} /> : null}
+              {pendingChanges ? (
+                <Badge
+                  variant="outline"
+                  className="text-xs text-warning border-warning/30"
+                >
+                  restart needed · {pendingChanges.join(", ")} changed
+                </Badge>
+              ) : null}
+              {profile ? (
+                <Badge variant="outline" className="text-xs">
+                  {profile}
+                </Badge>
+              ) : null}
               <Button
                 variant="ghost"
                 size="sm"
@@ -224,6 +1564,47 @@ export const AppDetails = memo(function AppDetails({
           ) : null}
         </div>
         <div className="flex gap-2">
+          <Button
+            variant="ghost"
+            size="sm"
+            className="h-7 text-xs"
+            onClick={handleUpdate}
+            disabled={updating}
+          >
+            {updating ? "updating..." : "update"}
+          </Button>
+          <Button
+            variant="ghost"
+            size="sm"
+            className="h-7 text-xs"
+            onClick={onNewWorktree}
+          >
+            worktree
+          </Button>
+          <Button
+            variant="ghost"
+            size="sm"
+            className="h-7 text-xs"
+            onClick={handleOpenTerminal}
+          >
+            terminal
+          </Button>
+          <Button
+            variant="ghost"
+            size="sm"
+            className="h-7 text-xs"
+            onClick={() => handleOpenInEditor()}
+          >
+            editor
+          </Button>
+          <Button
+            variant="ghost"
+            size="sm"
+            className="h-7 text-xs"
+            onClick={handleReveal}
+          >
+            finder
+          </Button>
           <Button
             variant="ghost"
             size="sm"
@@ -261,19 +1642,67 @@ export const AppDetails = memo(function AppDetails({
             <span className="text-muted-foreground">autostart:</span>
             <span className="text-xs">{app.run_on_startup ? "yes" : "no"}</span>
           </div>
+          <div className="flex items-center gap-4">
+            <span className="text-muted-foreground">last request:</span>
+            <LastAccessedLabel at={lastAccessed} />
+          </div>
         </div>
 
-        {isRunning && port ? (
+        {app.notes ? (
+          <p className="mt-3 text-xs text-muted-foreground whitespace-pre-wrap">
+            {app.notes}
+          </p>
+        ) : null}
+
+        {isRunning && port !== undefined ? (
           <AppUrls
-            subdomain={app.subdomain}
+            subdomain={
+              route?.subdomain ??
+              (app.subdomain?.includes("{") ? null : app.subdomain)
+            }
+            expiresAt={route?.expires_at}
             port={port}
             serviceInstalled={serviceStatus?.installed ?? false}
             isProxyOperational={isProxyOperational}
           />
         ) : null}
+
+        {isRunning && port > 0 ? <AppBenchmark appId={app.id} /> : null}
+
+        {isRunning && port > 0 ? <AppProcesses appId={app.id} /> : null}
+
+        {isRunning && app.kind === "process" && !app.devcontainer ? (
+          <AppEnvDrift appId={app.id} onRestart={onRestart} />
+        ) : null}
+
+        {app.kind !== "static" && !app.service ? (
+          <AppUptime appId={app.id} />
+        ) : null}
+
+        <AppTimeline appId={app.id} />
+
+        {app.kind !== "static" ? (
+          <>
+            <AppQuitBehavior appId={app.id} isRunning={isRunning} />
+            <AppNotifyReady appId={app.id} />
+            <AppSandbox appId={app.id} isRunning={isRunning} />
+            <AppStaleBuilds appId={app.id} />
+            <AppLogSampling appId={app.id} />
+          </>
+        ) : null}
+
+        <AppDiskUsage appId={app.id} isRunning={isRunning} />
+
+        {app.kind !== "static" ? <AppWatchedPaths appId={app.id} /> : null}
+
+        <AppReadme path={app.path} />
       </div>
 
-      <AppLogs logs={logs} onClear={onClearLogs} />
+      <AppLogs
+        logs={logs}
+        onClear={onClearLogs}
+        onOpenLocation={handleOpenInEditor}
+      />
     </section>
   );
 });
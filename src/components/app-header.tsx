@@ -3,8 +3,14 @@ import { Button } from "@/components/ui/button";
 import { Badge } from "@/components/ui/badge";
 import { Label } from "@/components/ui/label";
 import { Switch } from "@/components/ui/switch";
+import { BrowserMenu } from "@/components/browser-menu";
+import { EditorMenu } from "@/components/editor-menu";
 import { cn } from "@/lib/utils";
-import type { ProxyServiceStatus } from "@/types";
+import type {
+  ProxyServiceStatus,
+  UpdateInfo,
+  UpdateProgress,
+} from "@/types";
 
 interface AppHeaderProps {
   serviceStatus: ProxyServiceStatus | null;
@@ -12,26 +18,65 @@ interface AppHeaderProps {
   setupLoading: boolean;
   autoStartEnabled: boolean;
   onToggleAutostart: () => void;
+  appLockEnabled: boolean;
+  onToggleAppLock: () => void;
+  keepAppsOnQuit: boolean;
+  onToggleKeepAppsOnQuit: () => void;
+  update: UpdateInfo | null;
+  updateProgress: UpdateProgress | null;
+  onInstallUpdate: () => void;
+  runningCount: number;
+  onStopAll: () => void;
   onSetupClick: () => void;
   onStartProxy: () => void;
   onUninstallProxy: () => void;
   onLanInfoClick: () => void;
+  onRemoteControlClick: () => void;
+  onNotificationsClick: () => void;
+  onLogForwardingClick: () => void;
+  onSessionsClick: () => void;
   onAddApp: () => void;
+  onImportWorkspace: () => void;
+  onAddService: () => void;
+  onAddRemote: () => void;
   showLanButton: boolean;
   isDbReady?: boolean;
 }
 
+function updateStatusText({ stage, downloaded, total }: UpdateProgress) {
+  if (stage === "downloading" && total) {
+    return `downloading ${Math.round((downloaded / total) * 100)}%`;
+  }
+  return stage === "restoring" ? "restarting apps" : stage;
+}
+
 export const AppHeader = memo(function AppHeader({
   serviceStatus,
   isProxyOperational,
   setupLoading,
   autoStartEnabled,
   onToggleAutostart,
+  appLockEnabled,
+  onToggleAppLock,
+  keepAppsOnQuit,
+  onToggleKeepAppsOnQuit,
+  update,
+  updateProgress,
+  onInstallUpdate,
+  runningCount,
+  onStopAll,
   onSetupClick,
   onStartProxy,
   onUninstallProxy,
   onLanInfoClick,
+  onRemoteControlClick,
+  onNotificationsClick,
+  onLogForwardingClick,
+  onSessionsClick,
   onAddApp,
+  onImportWorkspace,
+  onAddService,
+  onAddRemote,
   showLanButton,
   isDbReady = true,
 }: AppHeaderProps) {
@@ -102,6 +147,18 @@ export const AppHeader = memo(function AppHeader({
             </Button>
           ) : null}
         </div>
+        {runningCount > 0 ? (
+          <Button
+            variant="ghost"
+            size="sm"
+            className="h-7 text-xs text-destructive"
+            onClick={onStopAll}
+          >
+            stop all ({runningCount})
+          </Button>
+        ) : null}
+        <BrowserMenu />
+        <EditorMenu />
         <div className="flex items-center gap-2">
           <Switch
             id="autostart"
@@ -116,6 +173,112 @@ export const AppHeader = memo(function AppHeader({
             autostart
           </Label>
         </div>
+        <div className="flex items-center gap-2">
+          <Switch
+            id="app-lock"
+            checked={appLockEnabled}
+            onCheckedChange={onToggleAppLock}
+            className="scale-75"
+          />
+          <Label
+            htmlFor="app-lock"
+            className="text-xs text-muted-foreground cursor-pointer"
+          >
+            lock
+          </Label>
+        </div>
+        <div className="flex items-center gap-2">
+          <Switch
+            id="keep-apps-on-quit"
+            checked={keepAppsOnQuit}
+            onCheckedChange={onToggleKeepAppsOnQuit}
+            className="scale-75"
+          />
+          <Label
+            htmlFor="keep-apps-on-quit"
+            className="text-xs text-muted-foreground cursor-pointer"
+            title="applies to apps started from now on"
+          >
+            keep on quit
+          </Label>
+        </div>
+        {updateProgress ? (
+          <span className="text-xs text-muted-foreground">
+            {updateStatusText(updateProgress)}
+          </span>
+        ) : (
+          update && (
+            <Button
+              variant="outline"
+              size="sm"
+              className="h-7 text-xs"
+              onClick={onInstallUpdate}
+              title={update.notes ?? undefined}
+            >
+              update to {update.version}
+            </Button>
+          )
+        )}
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-7 text-xs"
+          onClick={onRemoteControlClick}
+        >
+          control
+        </Button>
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-7 text-xs"
+          onClick={onNotificationsClick}
+        >
+          notify
+        </Button>
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-7 text-xs"
+          onClick={onLogForwardingClick}
+        >
+          ship logs
+        </Button>
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-7 text-xs"
+          onClick={onSessionsClick}
+          disabled={!isDbReady}
+        >
+          sessions
+        </Button>
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-7 text-xs"
+          onClick={onImportWorkspace}
+          disabled={!isDbReady}
+        >
+          + workspace
+        </Button>
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-7 text-xs"
+          onClick={onAddService}
+          disabled={!isDbReady}
+        >
+          + database
+        </Button>
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-7 text-xs"
+          onClick={onAddRemote}
+          disabled={!isDbReady}
+        >
+          + remote
+        </Button>
         <Button
           variant="default"
           size="sm"
@@ -1,11 +1,25 @@
 import { memo } from "react";
 import { Button } from "@/components/ui/button";
 import { ScrollArea } from "@/components/ui/scroll-area";
+import {
+  DropdownMenu,
+  DropdownMenuContent,
+  DropdownMenuItem,
+  DropdownMenuLabel,
+  DropdownMenuSeparator,
+  DropdownMenuTrigger,
+} from "@/components/ui/dropdown-menu";
 import { cn } from "@/lib/utils";
 import type {
   App,
+  AppProfiles,
   AppUsage,
   AppsUsage,
+  AppsGitInfo,
+  ConfigChange,
+  GitInfo,
+  LaunchProfile,
+  RestartNeeded,
   RunningApps,
   ProxyServiceStatus,
 } from "@/types";
@@ -27,9 +41,13 @@ interface AppListItemProps {
   isSelected: boolean;
   isRunning: boolean;
   port: number | undefined;
+  pendingChanges: ConfigChange[] | undefined;
   usage: AppUsage | undefined;
+  git: GitInfo | undefined;
+  profiles: LaunchProfile[] | undefined;
   onSelect: (id: string) => void;
   onStart: (app: App) => void;
+  onStartWithProfile: (app: App, profile: LaunchProfile) => void;
   onStop: (id: string) => void;
   onOpen: (app: App, port: number) => void;
 }
@@ -39,9 +57,13 @@ const AppListItem = memo(function AppListItem({
   isSelected,
   isRunning,
   port,
+  pendingChanges,
   usage,
+  git,
+  profiles,
   onSelect,
   onStart,
+  onStartWithProfile,
   onStop,
   onOpen,
 }: AppListItemProps) {
@@ -68,7 +90,26 @@ const AppListItem = memo(function AppListItem({
     >
       <div className="flex items-center justify-between gap-2">
         <div className="min-w-0 flex-1">
-          <div className="text-sm font-medium truncate">{app.name}</div>
+          <div className="text-sm font-medium truncate">
+            {app.name}
+            {pendingChanges ? (
+              <span
+                className="text-xs font-normal text-warning"
+                title={`changed: ${pendingChanges.join(", ")}`}
+              >
+                {" "}
+                · restart needed
+              </span>
+            ) : null}
+          </div>
+          {git ? (
+            <div className="text-xs mt-0.5 text-muted-foreground truncate">
+              {git.branch ?? "detached"}
+              {git.dirty ? <span className="text-warning"> *</span> : null}
+              {git.ahead > 0 ? ` ↑${git.ahead}` : null}
+              {git.behind > 0 ? ` ↓${git.behind}` : null}
+            </div>
+          ) : null}
           {usageLine ? (
             <div className="text-xs mt-0.5 text-muted-foreground">
               {usageLine}
@@ -79,7 +120,7 @@ const AppListItem = memo(function AppListItem({
           className="flex gap-1 opacity-0 group-hover:opacity-100 transition-opacity"
           onClick={(e) => e.stopPropagation()}
         >
-          {isRunning && port ? (
+          {isRunning && port !== undefined ? (
             <>
               <Button
                 variant="ghost"
@@ -98,6 +139,33 @@ const AppListItem = memo(function AppListItem({
                 stop
               </Button>
             </>
+          ) : profiles && profiles.length > 0 ? (
+            <DropdownMenu>
+              <DropdownMenuTrigger asChild>
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  className="h-6 text-xs px-2 text-success hover:text-success"
+                >
+                  start ▾
+                </Button>
+              </DropdownMenuTrigger>
+              <DropdownMenuContent align="end">
+                <DropdownMenuLabel>profile</DropdownMenuLabel>
+                <DropdownMenuItem onSelect={() => onStart(app)}>
+                  default
+                </DropdownMenuItem>
+                <DropdownMenuSeparator />
+                {profiles.map((profile) => (
+                  <DropdownMenuItem
+                    key={profile.id}
+                    onSelect={() => onStartWithProfile(app, profile)}
+                  >
+                    {profile.name}
+                  </DropdownMenuItem>
+                ))}
+              </DropdownMenuContent>
+            </DropdownMenu>
           ) : (
             <Button
               variant="ghost"
@@ -117,12 +185,16 @@ const AppListItem = memo(function AppListItem({
 interface AppSidebarProps {
   apps: App[];
   runningApps: RunningApps;
+  restartNeeded: RestartNeeded;
   appsUsage: AppsUsage;
+  gitInfo: AppsGitInfo;
+  profiles: AppProfiles;
   selectedAppId: string | null;
   serviceStatus: ProxyServiceStatus | null;
   isProxyOperational: boolean | undefined;
   onSelectApp: (id: string) => void;
   onStartApp: (app: App) => void;
+  onStartAppWithProfile: (app: App, profile: LaunchProfile) => void;
   onStopApp: (id: string) => void;
   onOpenApp: (app: App, port: number) => void;
 }
@@ -130,12 +202,16 @@ interface AppSidebarProps {
 export const AppSidebar = memo(function AppSidebar({
   apps,
   runningApps,
+  restartNeeded,
   appsUsage,
+  gitInfo,
+  profiles,
   selectedAppId,
   serviceStatus: _serviceStatus,
   isProxyOperational: _isProxyOperational,
   onSelectApp,
   onStartApp,
+  onStartAppWithProfile,
   onStopApp,
   onOpenApp,
 }: AppSidebarProps) {
@@ -158,9 +234,13 @@ export const AppSidebar = memo(function AppSidebar({
                 isSelected={selectedAppId === app.id}
                 isRunning={runningApps[app.id] !== undefined}
                 port={runningApps[app.id]}
+                pendingChanges={restartNeeded[app.id]}
                 usage={appsUsage[app.id]}
+                git={gitInfo[app.id]}
+                profiles={profiles[app.id]}
                 onSelect={onSelectApp}
                 onStart={onStartApp}
+                onStartWithProfile={onStartAppWithProfile}
                 onStop={onStopApp}
                 onOpen={onOpenApp}
               />
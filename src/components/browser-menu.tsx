@@ -0,0 +1,80 @@
+import { memo, useCallback, useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Button } from "@/components/ui/button";
+import {
+  DropdownMenu,
+  DropdownMenuCheckboxItem,
+  DropdownMenuContent,
+  DropdownMenuLabel,
+  DropdownMenuRadioGroup,
+  DropdownMenuRadioItem,
+  DropdownMenuSeparator,
+  DropdownMenuTrigger,
+} from "@/components/ui/dropdown-menu";
+import type { Browser, BrowserPreference } from "@/types";
+
+const browsers: Browser[] = ["default", "chrome", "firefox", "safari", "arc"];
+
+export const BrowserMenu = memo(function BrowserMenu() {
+  const [preference, setPreference] = useState<BrowserPreference>({
+    browser: "default",
+    private: false,
+  });
+
+  useEffect(() => {
+    invoke<BrowserPreference>("get_browser_preference")
+      .then(setPreference)
+      .catch((e) => console.error("Failed to load browser preference:", e));
+  }, []);
+
+  const updatePreference = useCallback(async (next: BrowserPreference) => {
+    setPreference(next);
+    try {
+      await invoke("set_browser_preference", { preference: next });
+    } catch (e) {
+      console.error("Failed to save browser preference:", e);
+    }
+  }, []);
+
+  return (
+    <DropdownMenu>
+      <DropdownMenuTrigger asChild>
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-6 text-xs text-muted-foreground"
+        >
+          browser: {preference.browser}
+          {preference.private ? " (private)" : ""}
+        </Button>
+      </DropdownMenuTrigger>
+      <DropdownMenuContent align="end">
+        <DropdownMenuLabel>open apps in</DropdownMenuLabel>
+        <DropdownMenuRadioGroup
+          value={preference.browser}
+          onValueChange={(value) =>
+            updatePreference({ ...preference, browser: value as Browser })
+          }
+        >
+          {browsers.map((browser) => (
+            <DropdownMenuRadioItem key={browser} value={browser}>
+              {browser}
+            </DropdownMenuRadioItem>
+          ))}
+        </DropdownMenuRadioGroup>
+        <DropdownMenuSeparator />
+        <DropdownMenuCheckboxItem
+          checked={preference.private}
+          disabled={
+            preference.browser === "default" || preference.browser === "safari"
+          }
+          onCheckedChange={(checked) =>
+            updatePreference({ ...preference, private: checked === true })
+          }
+        >
+          private window
+        </DropdownMenuCheckboxItem>
+      </DropdownMenuContent>
+    </DropdownMenu>
+  );
+});
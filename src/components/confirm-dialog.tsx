@@ -9,6 +9,7 @@ import {
   AlertDialogHeader,
   AlertDialogTitle,
 } from "@/components/ui/alert-dialog";
+import { Input } from "@/components/ui/input";
 
 interface ConfirmOptions {
   title?: string;
@@ -21,7 +22,9 @@ interface ConfirmState {
   open: boolean;
   message: string;
   options: ConfirmOptions;
-  resolve: ((value: boolean) => void) | null;
+  // Text typed into a prompt; null for a plain confirm
+  input: string | null;
+  resolve: ((value: boolean, input: string | null) => void) | null;
 }
 
 type ShowConfirmFn = (
@@ -29,9 +32,15 @@ type ShowConfirmFn = (
   options?: ConfirmOptions
 ) => Promise<boolean>;
 
+type ShowPromptFn = (
+  message: string,
+  options?: ConfirmOptions
+) => Promise<string | null>;
+
 const ConfirmContext = createContext<ShowConfirmFn | null>(null);
 
 let globalShowConfirm: ShowConfirmFn | null = null;
+let globalShowPrompt: ShowPromptFn | null = null;
 
 export function confirm(
   message: string,
@@ -44,6 +53,18 @@ export function confirm(
   return globalShowConfirm(message, options);
 }
 
+// Asks for a secret such as a passcode; resolves to null when cancelled
+export function prompt(
+  message: string,
+  options?: ConfirmOptions
+): Promise<string | null> {
+  if (!globalShowPrompt) {
+    console.error("ConfirmDialogProvider not mounted");
+    return Promise.resolve(null);
+  }
+  return globalShowPrompt(message, options);
+}
+
 export function ConfirmDialogProvider({
   children,
 }: {
@@ -53,6 +74,7 @@ export function ConfirmDialogProvider({
     open: false,
     message: "",
     options: {},
+    input: null,
     resolve: null,
   });
 
@@ -62,15 +84,29 @@ export function ConfirmDialogProvider({
         open: true,
         message,
         options,
+        input: null,
         resolve,
       });
     });
   }, []);
 
+  const showPrompt: ShowPromptFn = useCallback((message, options = {}) => {
+    return new Promise<string | null>((resolve) => {
+      setState({
+        open: true,
+        message,
+        options,
+        input: "",
+        resolve: (confirmed, input) => resolve(confirmed ? input : null),
+      });
+    });
+  }, []);
+
   globalShowConfirm = showConfirm;
+  globalShowPrompt = showPrompt;
 
   const handleResponse = (value: boolean) => {
-    state.resolve?.(value);
+    state.resolve?.(value, state.input);
     setState((prev) => ({ ...prev, open: false, resolve: null }));
   };
 
@@ -89,6 +125,19 @@ export function ConfirmDialogProvider({
             </AlertDialogTitle>
             <AlertDialogDescription>{state.message}</AlertDialogDescription>
           </AlertDialogHeader>
+          {state.input !== null ? (
+            <Input
+              type="password"
+              autoFocus
+              value={state.input}
+              onChange={(e) => {
+                const input = e.target.value;
+                setState((prev) => ({ ...prev, input }));
+              }}
+              onKeyDown={(e) => e.key === "Enter" && handleResponse(true)}
+              className="h-8 text-sm"
+            />
+          ) : null}
           <AlertDialogFooter>
             <AlertDialogCancel size="sm" onClick={() => handleResponse(false)}>
               {state.options.cancel || "Cancel"}
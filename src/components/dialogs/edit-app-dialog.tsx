@@ -1,7 +1,9 @@
-import { memo, useMemo, useCallback } from "react";
+import { memo, useMemo, useCallback, useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
+import { Textarea } from "@/components/ui/textarea";
 import { Checkbox } from "@/components/ui/checkbox";
 import {
   Dialog,
@@ -11,31 +13,181 @@ import {
   DialogFooter,
 } from "@/components/ui/dialog";
 import { cn } from "@/lib/utils";
-import type { App } from "@/types";
+import type { App, AppShell, LaunchProfile, ListeningPort } from "@/types";
+
+// null is the login shell
+const SHELLS: { value: AppShell | null; label: string }[] = [
+  { value: null, label: "login" },
+  { value: "zsh", label: "zsh" },
+  { value: "bash", label: "bash" },
+  { value: "sh", label: "sh" },
+];
+
+function formatEnv(env: Record<string, string>): string {
+  return Object.entries(env)
+    .map(([key, value]) => `${key}=${value}`)
+    .join("\n");
+}
+
+function parseEnv(text: string): Record<string, string> {
+  const env: Record<string, string> = {};
+  for (const line of text.split("\n")) {
+    const index = line.indexOf("=");
+    if (index > 0) {
+      env[line.slice(0, index).trim()] = line.slice(index + 1).trim();
+    }
+  }
+  return env;
+}
+
+interface ProfileEditorProps {
+  profile: LaunchProfile;
+  onChange: (profile: LaunchProfile) => void;
+  onRemove: (id: string) => void;
+}
+
+const ProfileEditor = memo(function ProfileEditor({
+  profile,
+  onChange,
+  onRemove,
+}: ProfileEditorProps) {
+  // Env is edited as text and parsed on blur so partial lines aren't dropped while typing
+  const [envText, setEnvText] = useState(() => formatEnv(profile.env));
+
+  return (
+    <div className="space-y-2 border border-border p-2">
+      <div className="flex items-center gap-2">
+        <Input
+          value={profile.name}
+          onChange={(e) => onChange({ ...profile, name: e.target.value })}
+          placeholder="staging"
+          className="h-7 text-xs"
+        />
+        <Input
+          type="number"
+          value={profile.port || ""}
+          onChange={(e) =>
+            onChange({
+              ...profile,
+              port: e.target.value ? parseInt(e.target.value) : null,
+            })
+          }
+          placeholder="port"
+          className="h-7 text-xs w-24"
+        />
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-7 text-xs px-2"
+          onClick={() => onRemove(profile.id)}
+        >
+          remove
+        </Button>
+      </div>
+      <Input
+        value={profile.command}
+        onChange={(e) => onChange({ ...profile, command: e.target.value })}
+        placeholder="bun run dev"
+        className="h-7 text-xs"
+      />
+      <Textarea
+        value={envText}
+        onChange={(e) => setEnvText(e.target.value)}
+        onBlur={() => onChange({ ...profile, env: parseEnv(envText) })}
+        placeholder="API_URL=https://staging.example.com"
+        className="text-xs min-h-12"
+      />
+    </div>
+  );
+});
 
 interface EditAppDialogProps {
   editingApp: App | null;
   apps: App[];
+  profiles: LaunchProfile[];
   onClose: () => void;
   onSave: () => void;
   onUpdate: (app: App) => void;
+  onProfilesChange: (profiles: LaunchProfile[]) => void;
 }
 
 export const EditAppDialog = memo(function EditAppDialog({
   editingApp,
   apps,
+  profiles,
   onClose,
   onSave,
   onUpdate,
+  onProfilesChange,
 }: EditAppDialogProps) {
+  const duplicateProfileName = useMemo(() => {
+    const names = profiles.map((p) => p.name.trim());
+    return names.some((name, i) => !name || names.indexOf(name) !== i);
+  }, [profiles]);
+
+  const handleAddProfile = useCallback(async () => {
+    if (!editingApp) return;
+    const id = await invoke<string>("generate_id");
+    onProfilesChange([
+      ...profiles,
+      {
+        id,
+        app_id: editingApp.id,
+        name: `profile-${profiles.length + 1}`,
+        command: editingApp.command,
+        env: {},
+        port: null,
+      },
+    ]);
+  }, [editingApp, profiles, onProfilesChange]);
+
+  const handleProfileChange = useCallback(
+    (profile: LaunchProfile) => {
+      onProfilesChange(profiles.map((p) => (p.id === profile.id ? profile : p)));
+    },
+    [profiles, onProfilesChange]
+  );
+
+  const handleProfileRemove = useCallback(
+    (id: string) => {
+      onProfilesChange(profiles.filter((p) => p.id !== id));
+    },
+    [profiles, onProfilesChange]
+  );
+
   const subdomainConflict = useMemo(() => {
     if (!editingApp?.subdomain) return null;
+    // Templates only clash once filled in, which happens at launch
+    if (editingApp.subdomain.includes("{")) return null;
     const conflict = apps.find(
       (a) => a.id !== editingApp.id && a.subdomain === editingApp.subdomain
     );
     return conflict ? conflict.name : null;
   }, [editingApp?.subdomain, editingApp?.id, apps]);
 
+  const [listeningPorts, setListeningPorts] = useState<ListeningPort[]>([]);
+
+  const editingAppId = editingApp?.id;
+  useEffect(() => {
+    if (!editingAppId) return;
+    invoke<ListeningPort[]>("get_listening_ports")
+      .then(setListeningPorts)
+      .catch(() => setListeningPorts([]));
+  }, [editingAppId]);
+
+  // Who else listens on the chosen port; the app's own running process doesn't count
+  const portOwner = useMemo(() => {
+    if (!editingApp?.port) return null;
+    const listener = listeningPorts.find(
+      (l) => l.port === editingApp.port && l.app_id !== editingApp.id
+    );
+    if (!listener) return null;
+    const app = apps.find((a) => a.id === listener.app_id);
+    if (app) return app.name;
+    const project = listener.cwd?.split("/").pop();
+    return project ? `${listener.process} in ${project}` : listener.process;
+  }, [editingApp?.port, editingApp?.id, listeningPorts, apps]);
+
   const handleNameChange = useCallback(
     (e: React.ChangeEvent<HTMLInputElement>) => {
       if (editingApp) {
@@ -45,6 +197,42 @@ export const EditAppDialog = memo(function EditAppDialog({
     [editingApp, onUpdate]
   );
 
+  const handleDescriptionChange = useCallback(
+    (e: React.ChangeEvent<HTMLInputElement>) => {
+      if (editingApp) {
+        onUpdate({ ...editingApp, description: e.target.value || null });
+      }
+    },
+    [editingApp, onUpdate]
+  );
+
+  const handleNotesChange = useCallback(
+    (e: React.ChangeEvent<HTMLTextAreaElement>) => {
+      if (editingApp) {
+        onUpdate({ ...editingApp, notes: e.target.value || null });
+      }
+    },
+    [editingApp, onUpdate]
+  );
+
+  const handlePrerequisitesChange = useCallback(
+    (e: React.ChangeEvent<HTMLTextAreaElement>) => {
+      if (editingApp) {
+        onUpdate({ ...editingApp, prerequisites: e.target.value || null });
+      }
+    },
+    [editingApp, onUpdate]
+  );
+
+  const handlePathEntriesChange = useCallback(
+    (e: React.ChangeEvent<HTMLTextAreaElement>) => {
+      if (editingApp) {
+        onUpdate({ ...editingApp, path_entries: e.target.value || null });
+      }
+    },
+    [editingApp, onUpdate]
+  );
+
   const handleSubdomainChange = useCallback(
     (e: React.ChangeEvent<HTMLInputElement>) => {
       if (editingApp) {
@@ -63,6 +251,15 @@ export const EditAppDialog = memo(function EditAppDialog({
     [editingApp, onUpdate]
   );
 
+  const handleBuildCommandChange = useCallback(
+    (e: React.ChangeEvent<HTMLInputElement>) => {
+      if (editingApp) {
+        onUpdate({ ...editingApp, build_command: e.target.value || null });
+      }
+    },
+    [editingApp, onUpdate]
+  );
+
   const handlePortChange = useCallback(
     (e: React.ChangeEvent<HTMLInputElement>) => {
       if (editingApp) {
@@ -75,6 +272,51 @@ export const EditAppDialog = memo(function EditAppDialog({
     [editingApp, onUpdate]
   );
 
+  const handleStaticChange = useCallback(
+    (checked: boolean | "indeterminate") => {
+      if (editingApp && checked !== "indeterminate") {
+        onUpdate({ ...editingApp, kind: checked ? "static" : "process" });
+      }
+    },
+    [editingApp, onUpdate]
+  );
+
+  const handleRemoteHostChange = useCallback(
+    (e: React.ChangeEvent<HTMLInputElement>) => {
+      if (editingApp) {
+        onUpdate({ ...editingApp, remote_host: e.target.value || null });
+      }
+    },
+    [editingApp, onUpdate]
+  );
+
+  const handleDevcontainerChange = useCallback(
+    (checked: boolean | "indeterminate") => {
+      if (editingApp && checked !== "indeterminate") {
+        onUpdate({ ...editingApp, devcontainer: checked });
+      }
+    },
+    [editingApp, onUpdate]
+  );
+
+  const handleShellChange = useCallback(
+    (shell: AppShell | null) => {
+      if (editingApp) {
+        onUpdate({ ...editingApp, shell });
+      }
+    },
+    [editingApp, onUpdate]
+  );
+
+  const handleSpaChange = useCallback(
+    (checked: boolean | "indeterminate") => {
+      if (editingApp && checked !== "indeterminate") {
+        onUpdate({ ...editingApp, spa: checked });
+      }
+    },
+    [editingApp, onUpdate]
+  );
+
   const handleRunOnStartupChange = useCallback(
     (checked: boolean | "indeterminate") => {
       if (editingApp && checked !== "indeterminate") {
@@ -86,7 +328,7 @@ export const EditAppDialog = memo(function EditAppDialog({
 
   return (
     <Dialog open={!!editingApp} onOpenChange={() => onClose()}>
-      <DialogContent className="max-w-md">
+      <DialogContent className="max-w-md max-h-[90vh] overflow-y-auto">
         <DialogHeader>
           <DialogTitle className="text-sm font-semibold">
             <span className="text-muted-foreground">&gt;</span> edit app
@@ -105,6 +347,18 @@ export const EditAppDialog = memo(function EditAppDialog({
                 className="h-8 text-sm"
               />
             </div>
+            <div className="space-y-2">
+              <Label htmlFor="description" className="text-xs">
+                description
+              </Label>
+              <Input
+                id="description"
+                value={editingApp.description || ""}
+                onChange={handleDescriptionChange}
+                placeholder="what is this app?"
+                className="h-8 text-sm"
+              />
+            </div>
             <div className="space-y-2">
               <Label htmlFor="subdomain" className="text-xs">
                 subdomain
@@ -129,30 +383,167 @@ export const EditAppDialog = memo(function EditAppDialog({
                 <p className="text-xs text-destructive">
                   already used by "{subdomainConflict}"
                 </p>
-              ) : null}
-            </div>
-            <div className="space-y-2">
-              <Label htmlFor="command" className="text-xs">
-                command
-              </Label>
-              <Input
-                id="command"
-                value={editingApp.command}
-                onChange={handleCommandChange}
-                className="h-8 text-sm"
-              />
+              ) : (
+                <p className="text-xs text-muted-foreground">
+                  can use {"{name}"}, {"{branch}"}, {"{profile}"} and{" "}
+                  {"{dir}"}, e.g. {"{branch}-{name}"}
+                </p>
+              )}
             </div>
+            {editingApp.kind === "remote" ? (
+              <div className="space-y-2">
+                <Label htmlFor="remote-host" className="text-xs">
+                  ssh host (path is on that machine)
+                </Label>
+                <Input
+                  id="remote-host"
+                  value={editingApp.remote_host ?? ""}
+                  onChange={handleRemoteHostChange}
+                  placeholder="user@desktop"
+                  className="h-8 text-sm"
+                />
+              </div>
+            ) : (
+              <div className="flex items-center gap-2">
+                <Checkbox
+                  id="static"
+                  checked={editingApp.kind === "static"}
+                  onCheckedChange={handleStaticChange}
+                />
+                <Label htmlFor="static" className="text-xs cursor-pointer">
+                  static folder (serve files, no process)
+                </Label>
+              </div>
+            )}
+            {editingApp.kind === "static" ? (
+              <div className="flex items-center gap-2">
+                <Checkbox
+                  id="spa"
+                  checked={!!editingApp.spa}
+                  onCheckedChange={handleSpaChange}
+                />
+                <Label htmlFor="spa" className="text-xs cursor-pointer">
+                  single-page app (unknown paths serve index.html)
+                </Label>
+              </div>
+            ) : (
+              <>
+                <div className="space-y-2">
+                  <Label htmlFor="command" className="text-xs">
+                    command
+                  </Label>
+                  <Input
+                    id="command"
+                    value={editingApp.command}
+                    onChange={handleCommandChange}
+                    className="h-8 text-sm"
+                  />
+                </div>
+                {editingApp.kind === "process" ? (
+                  <div className="flex items-center gap-2">
+                    <Checkbox
+                      id="devcontainer"
+                      checked={!!editingApp.devcontainer}
+                      onCheckedChange={handleDevcontainerChange}
+                    />
+                    <Label
+                      htmlFor="devcontainer"
+                      className="text-xs cursor-pointer"
+                    >
+                      run inside the devcontainer (.devcontainer/)
+                    </Label>
+                  </div>
+                ) : null}
+                {editingApp.kind === "process" && !editingApp.devcontainer ? (
+                  <div className="flex items-center gap-2 text-xs">
+                    <span className="text-muted-foreground">shell</span>
+                    {SHELLS.map(({ value, label }) => (
+                      <Button
+                        key={label}
+                        variant={
+                          value === (editingApp.shell ?? null)
+                            ? "secondary"
+                            : "ghost"
+                        }
+                        size="sm"
+                        className="h-5 text-xs px-2"
+                        onClick={() => handleShellChange(value)}
+                      >
+                        {label}
+                      </Button>
+                    ))}
+                  </div>
+                ) : null}
+                <div className="space-y-2">
+                  <Label htmlFor="build-command" className="text-xs">
+                    build command (runs before each start)
+                  </Label>
+                  <Input
+                    id="build-command"
+                    value={editingApp.build_command ?? ""}
+                    onChange={handleBuildCommandChange}
+                    placeholder="none"
+                    className="h-8 text-sm"
+                  />
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="port" className="text-xs">
+                    port (empty for auto)
+                  </Label>
+                  <Input
+                    id="port"
+                    type="number"
+                    value={editingApp.port || ""}
+                    onChange={handlePortChange}
+                    placeholder="auto"
+                    className={cn(
+                      "h-8 text-sm",
+                      portOwner &&
+                        "border-destructive focus-visible:ring-destructive"
+                    )}
+                  />
+                  {portOwner ? (
+                    <p className="text-xs text-destructive">
+                      {editingApp.port} is taken by {portOwner}
+                    </p>
+                  ) : null}
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="prerequisites" className="text-xs">
+                    prerequisites (checked before start, one per line)
+                  </Label>
+                  <Textarea
+                    id="prerequisites"
+                    value={editingApp.prerequisites ?? ""}
+                    onChange={handlePrerequisitesChange}
+                    placeholder={"5432\nhttp://localhost:8080/health\ndocker"}
+                    className="text-sm max-h-32"
+                  />
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="path-entries" className="text-xs">
+                    path entries (put in front of PATH, one per line)
+                  </Label>
+                  <Textarea
+                    id="path-entries"
+                    value={editingApp.path_entries ?? ""}
+                    onChange={handlePathEntriesChange}
+                    placeholder={"~/.cargo/bin\n.toolchain/bin"}
+                    className="text-sm max-h-32"
+                  />
+                </div>
+              </>
+            )}
             <div className="space-y-2">
-              <Label htmlFor="port" className="text-xs">
-                port (empty for auto)
+              <Label htmlFor="notes" className="text-xs">
+                notes
               </Label>
-              <Input
-                id="port"
-                type="number"
-                value={editingApp.port || ""}
-                onChange={handlePortChange}
-                placeholder="auto"
-                className="h-8 text-sm"
+              <Textarea
+                id="notes"
+                value={editingApp.notes || ""}
+                onChange={handleNotesChange}
+                placeholder="client, credentials location, setup steps..."
+                className="text-sm max-h-40"
               />
             </div>
             <div className="flex items-center gap-2">
@@ -165,13 +556,43 @@ export const EditAppDialog = memo(function EditAppDialog({
                 run on startup
               </Label>
             </div>
+            <div className="space-y-2">
+              <div className="flex items-center justify-between">
+                <Label className="text-xs">launch profiles</Label>
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  className="h-6 text-xs px-2"
+                  onClick={handleAddProfile}
+                >
+                  + add profile
+                </Button>
+              </div>
+              {profiles.map((profile) => (
+                <ProfileEditor
+                  key={profile.id}
+                  profile={profile}
+                  onChange={handleProfileChange}
+                  onRemove={handleProfileRemove}
+                />
+              ))}
+              {duplicateProfileName ? (
+                <p className="text-xs text-destructive">
+                  profile names must be unique and non-empty
+                </p>
+              ) : null}
+            </div>
           </div>
         ) : null}
         <DialogFooter>
           <Button variant="ghost" size="sm" onClick={onClose}>
             cancel
           </Button>
-          <Button size="sm" onClick={onSave} disabled={!!subdomainConflict}>
+          <Button
+            size="sm"
+            onClick={onSave}
+            disabled={!!subdomainConflict || duplicateProfileName}
+          >
             save
           </Button>
         </DialogFooter>
@@ -0,0 +1,158 @@
+import { memo, useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Button } from "@/components/ui/button";
+import { Checkbox } from "@/components/ui/checkbox";
+import { ScrollArea } from "@/components/ui/scroll-area";
+import {
+  Dialog,
+  DialogContent,
+  DialogHeader,
+  DialogTitle,
+  DialogFooter,
+} from "@/components/ui/dialog";
+import type { App, WorkspacePackage } from "@/types";
+import { formatError } from "@/lib/errors";
+
+interface ImportWorkspaceDialogProps {
+  root: string | null;
+  apps: App[];
+  onClose: () => void;
+  onImport: (packages: WorkspacePackage[]) => Promise<void>;
+}
+
+export const ImportWorkspaceDialog = memo(function ImportWorkspaceDialog({
+  root,
+  apps,
+  onClose,
+  onImport,
+}: ImportWorkspaceDialogProps) {
+  const [packages, setPackages] = useState<WorkspacePackage[] | null>(null);
+  const [selected, setSelected] = useState<string[]>([]);
+  const [importing, setImporting] = useState(false);
+  const [errorMessage, setErrorMessage] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!root) return;
+    let cancelled = false;
+    setPackages(null);
+    setErrorMessage(null);
+    invoke<WorkspacePackage[]>("scan_workspace", { path: root })
+      .then((result) => {
+        if (cancelled) return;
+        setPackages(result);
+        const registered = new Set(apps.map((a) => a.path));
+        setSelected(
+          result.filter((p) => !registered.has(p.path)).map((p) => p.path)
+        );
+      })
+      .catch((e) => {
+        if (!cancelled) setErrorMessage(formatError(e));
+      });
+    return () => {
+      cancelled = true;
+    };
+    // Only rescan when a different folder is picked, not whenever apps reload
+  }, [root]);
+
+  const toggle = useCallback((path: string, checked: boolean) => {
+    setSelected((prev) =>
+      checked ? [...prev, path] : prev.filter((p) => p !== path)
+    );
+  }, []);
+
+  const handleImport = useCallback(async () => {
+    if (!packages) return;
+    setImporting(true);
+    try {
+      await onImport(packages.filter((p) => selected.includes(p.path)));
+      onClose();
+    } catch (e) {
+      setErrorMessage(formatError(e));
+    } finally {
+      setImporting(false);
+    }
+  }, [packages, selected, onImport, onClose]);
+
+  const isRegistered = (path: string) => apps.some((a) => a.path === path);
+
+  return (
+    <Dialog open={root !== null} onOpenChange={onClose}>
+      <DialogContent className="max-w-lg">
+        <DialogHeader>
+          <DialogTitle className="text-sm font-semibold">
+            <span className="text-muted-foreground">&gt;</span> import
+            workspace
+          </DialogTitle>
+        </DialogHeader>
+        <div className="space-y-3 py-2">
+          <p className="text-muted-foreground text-xs">
+            runnable packages found in <code className="text-primary">{root}</code>
+          </p>
+          {errorMessage ? (
+            <p className="text-xs text-destructive">{errorMessage}</p>
+          ) : null}
+          {packages === null ? (
+            errorMessage ? null : (
+              <p className="text-xs text-muted-foreground italic">
+                scanning...
+              </p>
+            )
+          ) : packages.length === 0 ? (
+            <p className="text-xs text-muted-foreground italic">
+              no workspace packages with a dev or start script
+            </p>
+          ) : (
+            <ScrollArea className="max-h-72">
+              <div className="space-y-2">
+                {packages.map((pkg) => {
+                  const registered = isRegistered(pkg.path);
+                  return (
+                    <label
+                      key={pkg.path}
+                      className="flex items-start gap-2 text-xs"
+                    >
+                      <Checkbox
+                        className="mt-0.5"
+                        checked={selected.includes(pkg.path)}
+                        disabled={registered}
+                        onCheckedChange={(checked) =>
+                          toggle(pkg.path, checked === true)
+                        }
+                      />
+                      <div className="min-w-0">
+                        <div>
+                          {pkg.name}
+                          {registered ? (
+                            <span className="text-muted-foreground">
+                              {" "}
+                              (added)
+                            </span>
+                          ) : null}
+                        </div>
+                        <div className="text-muted-foreground truncate">
+                          {pkg.subdomain}.local · {pkg.command}
+                        </div>
+                      </div>
+                    </label>
+                  );
+                })}
+              </div>
+            </ScrollArea>
+          )}
+        </div>
+        <DialogFooter>
+          <Button variant="ghost" size="sm" onClick={onClose}>
+            cancel
+          </Button>
+          <Button
+            size="sm"
+            onClick={handleImport}
+            disabled={selected.length === 0 || importing}
+          >
+            {importing ? "adding..." : `add ${selected.length} apps`}
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+});
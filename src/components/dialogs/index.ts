@@ -1,3 +1,12 @@
 export { EditAppDialog } from "./edit-app-dialog";
 export { SetupWizardDialog } from "./setup-wizard-dialog";
 export { LanInfoDialog } from "./lan-info-dialog";
+export { WorktreeDialog } from "./worktree-dialog";
+export { ImportWorkspaceDialog } from "./import-workspace-dialog";
+export { ServiceDialog } from "./service-dialog";
+export { RemoteAppDialog } from "./remote-app-dialog";
+export { QuickSwitcherDialog } from "./quick-switcher-dialog";
+export { RemoteControlDialog } from "./remote-control-dialog";
+export { NotificationsDialog } from "./notifications-dialog";
+export { LogForwardingDialog } from "./log-forwarding-dialog";
+export { SessionsDialog } from "./sessions-dialog";
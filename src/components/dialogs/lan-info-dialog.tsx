@@ -1,5 +1,8 @@
-import { memo, useState, useCallback } from "react";
+import { memo, useState, useCallback, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { Button } from "@/components/ui/button";
+import { Label } from "@/components/ui/label";
+import { Switch } from "@/components/ui/switch";
 import {
   Dialog,
   DialogContent,
@@ -7,6 +10,7 @@ import {
   DialogTitle,
   DialogFooter,
 } from "@/components/ui/dialog";
+import { describeError } from "@/lib/errors";
 
 interface LanInfoDialogProps {
   open: boolean;
@@ -20,6 +24,22 @@ export const LanInfoDialog = memo(function LanInfoDialog({
   onClose,
 }: LanInfoDialogProps) {
   const [copiedUrl, setCopiedUrl] = useState<string | null>(null);
+  const [statusPageUrl, setStatusPageUrl] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!open) return;
+    invoke<string | null>("get_status_page_url").then(setStatusPageUrl);
+  }, [open]);
+
+  const toggleStatusPage = useCallback(async (enabled: boolean) => {
+    try {
+      setStatusPageUrl(
+        await invoke<string | null>("set_status_page", { enabled })
+      );
+    } catch (e) {
+      alert(describeError(e));
+    }
+  }, []);
 
   const copyToClipboard = useCallback(async (text: string) => {
     try {
@@ -71,6 +91,43 @@ export const LanInfoDialog = memo(function LanInfoDialog({
               </Button>
             </div>
           </div>
+          <div className="space-y-2">
+            <div className="flex items-center gap-2">
+              <Switch
+                id="status-page"
+                checked={statusPageUrl !== null}
+                onCheckedChange={toggleStatusPage}
+                className="scale-75"
+              />
+              <Label htmlFor="status-page" className="text-xs cursor-pointer">
+                status page
+              </Label>
+            </div>
+            <p className="text-xs text-muted-foreground">
+              a read-only page showing which apps are up, for teammates on
+              the same network. it can't start or stop anything.
+            </p>
+            {statusPageUrl ? (
+              <>
+                <div className="flex items-center gap-2">
+                  <code className="text-xs text-success">{statusPageUrl}</code>
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    onClick={() => copyToClipboard(statusPageUrl)}
+                  >
+                    {copiedUrl === statusPageUrl ? "copied!" : "copy"}
+                  </Button>
+                </div>
+                <p className="text-xs text-muted-foreground">
+                  phones opening https apps can install the certificate
+                  from{" "}
+                  <code className="text-primary">{statusPageUrl}/ca</code> to
+                  skip the warning.
+                </p>
+              </>
+            ) : null}
+          </div>
         </div>
         <DialogFooter>
           <Button size="sm" onClick={onClose}>
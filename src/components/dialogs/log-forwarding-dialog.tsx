@@ -0,0 +1,202 @@
+import { memo, useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { Switch } from "@/components/ui/switch";
+import {
+  Dialog,
+  DialogContent,
+  DialogHeader,
+  DialogTitle,
+  DialogFooter,
+} from "@/components/ui/dialog";
+import { formatError } from "@/lib/errors";
+import type {
+  LogForwardingSettings,
+  LogForwardingStatus,
+  LogSink,
+} from "@/types";
+
+const UNIFIED_LOG_COMMAND =
+  "log stream --predicate 'subsystem BEGINSWITH \"com.artsiomshaitar.my-little-apps.\"'";
+
+interface LogForwardingDialogProps {
+  open: boolean;
+  onClose: () => void;
+}
+
+const sinkPlaceholders: Record<LogSink["type"], string> = {
+  loki: "http://nas.local:3100/loki/api/v1/push",
+  ndjson: "https://logs.home.lan/ingest",
+};
+
+export const LogForwardingDialog = memo(function LogForwardingDialog({
+  open,
+  onClose,
+}: LogForwardingDialogProps) {
+  const [settings, setSettings] = useState<LogForwardingSettings | null>(
+    null
+  );
+  const [status, setStatus] = useState<LogForwardingStatus | null>(null);
+  const [saving, setSaving] = useState(false);
+  const [errorMessage, setErrorMessage] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!open) return;
+    setErrorMessage(null);
+    invoke<LogForwardingSettings>("get_log_forwarding").then(setSettings);
+    invoke<LogForwardingStatus>("get_log_forwarding_status").then(setStatus);
+  }, [open]);
+
+  const update = useCallback((change: Partial<LogForwardingSettings>) => {
+    setSettings((prev) => (prev ? { ...prev, ...change } : prev));
+  }, []);
+
+  const handleSave = useCallback(async () => {
+    if (!settings) return;
+    setSaving(true);
+    setErrorMessage(null);
+    try {
+      await invoke("set_log_forwarding", { settings });
+      onClose();
+    } catch (e) {
+      setErrorMessage(formatError(e));
+    } finally {
+      setSaving(false);
+    }
+  }, [settings, onClose]);
+
+  const sink = settings?.sink ?? null;
+
+  return (
+    <Dialog open={open} onOpenChange={onClose}>
+      <DialogContent className="max-w-lg">
+        <DialogHeader>
+          <DialogTitle className="text-sm font-semibold">
+            <span className="text-muted-foreground">&gt;</span> ship logs
+          </DialogTitle>
+        </DialogHeader>
+        {settings ? (
+          <div className="space-y-4 py-2">
+            <div className="flex items-center gap-2">
+              <Switch
+                id="log-forwarding"
+                checked={settings.enabled}
+                onCheckedChange={(enabled) => update({ enabled })}
+                className="scale-75"
+              />
+              <Label
+                htmlFor="log-forwarding"
+                className="text-xs cursor-pointer"
+              >
+                forward app output to a log collector
+              </Label>
+            </div>
+            <div className="flex items-center gap-2">
+              {(["loki", "ndjson"] as const).map((type) => (
+                <Button
+                  key={type}
+                  variant={sink?.type === type ? "secondary" : "ghost"}
+                  size="sm"
+                  className="h-7 text-xs"
+                  onClick={() =>
+                    update({ sink: { type, url: sink?.url ?? "" } })
+                  }
+                >
+                  {type}
+                </Button>
+              ))}
+            </div>
+            {sink ? (
+              <div className="space-y-2">
+                <Input
+                  value={sink.url}
+                  onChange={(e) =>
+                    update({ sink: { ...sink, url: e.target.value } })
+                  }
+                  placeholder={sinkPlaceholders[sink.type]}
+                  className="h-8 text-sm"
+                />
+                <Input
+                  type="password"
+                  value={settings.authorization ?? ""}
+                  onChange={(e) =>
+                    update({ authorization: e.target.value || null })
+                  }
+                  placeholder="authorization header (optional)"
+                  className="h-8 text-sm"
+                />
+              </div>
+            ) : null}
+            <div className="flex items-center gap-2">
+              <Label htmlFor="batch-size" className="text-xs">
+                send up to
+              </Label>
+              <Input
+                id="batch-size"
+                type="number"
+                min={1}
+                value={settings.batch_size}
+                onChange={(e) =>
+                  update({ batch_size: parseInt(e.target.value) || 1 })
+                }
+                className="h-8 w-20 text-sm"
+              />
+              <Label htmlFor="flush-ms" className="text-xs">
+                lines every
+              </Label>
+              <Input
+                id="flush-ms"
+                type="number"
+                min={100}
+                value={settings.flush_ms}
+                onChange={(e) =>
+                  update({ flush_ms: parseInt(e.target.value) || 0 })
+                }
+                className="h-8 w-20 text-sm"
+              />
+              <span className="text-xs text-muted-foreground">ms</span>
+            </div>
+            <p className="text-xs text-muted-foreground">
+              lines that can't be sent in time are dropped rather than slowing
+              your apps down.
+              {status?.active ? ` ${status.dropped} dropped so far.` : null}
+            </p>
+            <div className="space-y-2 border-t border-border pt-3">
+              <div className="flex items-center gap-2">
+                <Switch
+                  id="unified-log"
+                  checked={settings.unified_log}
+                  onCheckedChange={(unified_log) => update({ unified_log })}
+                  className="scale-75"
+                />
+                <Label htmlFor="unified-log" className="text-xs cursor-pointer">
+                  also write to macOS unified logging
+                </Label>
+              </div>
+              <p className="text-xs text-muted-foreground">
+                each app gets its own subsystem in Console.app. from a
+                terminal:
+              </p>
+              <code className="block bg-muted px-3 py-2 text-xs break-all">
+                {UNIFIED_LOG_COMMAND}
+              </code>
+            </div>
+            {errorMessage ? (
+              <p className="text-xs text-destructive">{errorMessage}</p>
+            ) : null}
+          </div>
+        ) : null}
+        <DialogFooter>
+          <Button variant="ghost" size="sm" onClick={onClose}>
+            cancel
+          </Button>
+          <Button size="sm" onClick={handleSave} disabled={saving}>
+            {saving ? "saving..." : "save"}
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+});
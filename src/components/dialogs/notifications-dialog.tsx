@@ -0,0 +1,301 @@
+import { memo, useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Button } from "@/components/ui/button";
+import { Checkbox } from "@/components/ui/checkbox";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import {
+  Dialog,
+  DialogContent,
+  DialogHeader,
+  DialogTitle,
+  DialogFooter,
+} from "@/components/ui/dialog";
+import { formatError } from "@/lib/errors";
+import type {
+  ChannelTarget,
+  NotificationChannel,
+  NotificationSettings,
+} from "@/types";
+
+interface NotificationsDialogProps {
+  open: boolean;
+  onClose: () => void;
+}
+
+const ntfyTarget: ChannelTarget = {
+  type: "ntfy",
+  server: "",
+  topic: "",
+  token: null,
+};
+
+const newChannel = (): NotificationChannel => ({
+  id: "",
+  name: "phone",
+  target: ntfyTarget,
+  crashes: true,
+  long_tasks: true,
+});
+
+export const NotificationsDialog = memo(function NotificationsDialog({
+  open,
+  onClose,
+}: NotificationsDialogProps) {
+  const [settings, setSettings] = useState<NotificationSettings | null>(null);
+  const [saving, setSaving] = useState(false);
+  const [testResult, setTestResult] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!open) return;
+    setTestResult(null);
+    invoke<NotificationSettings>("get_notification_settings").then(setSettings);
+  }, [open]);
+
+  const updateChannel = useCallback(
+    (index: number, change: Partial<NotificationChannel>) => {
+      setSettings((prev) =>
+        prev
+          ? {
+              ...prev,
+              channels: prev.channels.map((channel, i) =>
+                i === index ? { ...channel, ...change } : channel
+              ),
+            }
+          : prev
+      );
+    },
+    []
+  );
+
+  const updateTarget = useCallback(
+    (index: number, target: ChannelTarget) => updateChannel(index, { target }),
+    [updateChannel]
+  );
+
+  const addChannel = useCallback(() => {
+    setSettings((prev) =>
+      prev ? { ...prev, channels: [...prev.channels, newChannel()] } : prev
+    );
+  }, []);
+
+  const removeChannel = useCallback((index: number) => {
+    setSettings((prev) =>
+      prev
+        ? { ...prev, channels: prev.channels.filter((_, i) => i !== index) }
+        : prev
+    );
+  }, []);
+
+  const testChannel = useCallback(async (channel: NotificationChannel) => {
+    setTestResult(null);
+    try {
+      await invoke("test_notification_channel", { channel });
+      setTestResult(`sent a test to ${channel.name}`);
+    } catch (e) {
+      setTestResult(formatError(e));
+    }
+  }, []);
+
+  const handleSave = useCallback(async () => {
+    if (!settings) return;
+    setSaving(true);
+    try {
+      await invoke("set_notification_settings", { settings });
+      onClose();
+    } finally {
+      setSaving(false);
+    }
+  }, [settings, onClose]);
+
+  return (
+    <Dialog open={open} onOpenChange={onClose}>
+      <DialogContent className="max-w-lg">
+        <DialogHeader>
+          <DialogTitle className="text-sm font-semibold">
+            <span className="text-muted-foreground">&gt;</span> notifications
+          </DialogTitle>
+        </DialogHeader>
+        <div className="space-y-4 py-2">
+          <p className="text-muted-foreground text-xs">
+            push crashes and finished builds to your phone through ntfy, or
+            post them as JSON to a webhook.
+          </p>
+          {settings?.channels.map((channel, index) => (
+            <div key={index} className="space-y-2 border-b border-border pb-3">
+              <div className="flex items-center gap-2">
+                <Input
+                  value={channel.name}
+                  onChange={(e) =>
+                    updateChannel(index, { name: e.target.value })
+                  }
+                  placeholder="name"
+                  className="h-8 text-sm"
+                />
+                <Button
+                  variant={
+                    channel.target.type === "ntfy" ? "secondary" : "ghost"
+                  }
+                  size="sm"
+                  className="h-7 text-xs"
+                  onClick={() => updateTarget(index, ntfyTarget)}
+                >
+                  ntfy
+                </Button>
+                <Button
+                  variant={
+                    channel.target.type === "webhook" ? "secondary" : "ghost"
+                  }
+                  size="sm"
+                  className="h-7 text-xs"
+                  onClick={() =>
+                    updateTarget(index, { type: "webhook", url: "" })
+                  }
+                >
+                  webhook
+                </Button>
+              </div>
+              {channel.target.type === "ntfy" ? (
+                <div className="grid grid-cols-3 gap-2">
+                  <Input
+                    value={channel.target.server}
+                    onChange={(e) =>
+                      channel.target.type === "ntfy" &&
+                      updateTarget(index, {
+                        ...channel.target,
+                        server: e.target.value,
+                      })
+                    }
+                    placeholder="https://ntfy.sh"
+                    className="h-8 text-sm"
+                  />
+                  <Input
+                    value={channel.target.topic}
+                    onChange={(e) =>
+                      channel.target.type === "ntfy" &&
+                      updateTarget(index, {
+                        ...channel.target,
+                        topic: e.target.value,
+                      })
+                    }
+                    placeholder="topic"
+                    className="h-8 text-sm"
+                  />
+                  <Input
+                    type="password"
+                    value={channel.target.token ?? ""}
+                    onChange={(e) =>
+                      channel.target.type === "ntfy" &&
+                      updateTarget(index, {
+                        ...channel.target,
+                        token: e.target.value || null,
+                      })
+                    }
+                    placeholder="token (optional)"
+                    className="h-8 text-sm"
+                  />
+                </div>
+              ) : (
+                <Input
+                  value={channel.target.url}
+                  onChange={(e) =>
+                    updateTarget(index, {
+                      type: "webhook",
+                      url: e.target.value,
+                    })
+                  }
+                  placeholder="https://example.com/hooks/my-apps"
+                  className="h-8 text-sm"
+                />
+              )}
+              <div className="flex items-center gap-2">
+                <Checkbox
+                  id={`crashes-${index}`}
+                  checked={channel.crashes}
+                  onCheckedChange={(checked) =>
+                    updateChannel(index, { crashes: checked === true })
+                  }
+                />
+                <Label
+                  htmlFor={`crashes-${index}`}
+                  className="text-xs cursor-pointer"
+                >
+                  crashes
+                </Label>
+                <Checkbox
+                  id={`long-tasks-${index}`}
+                  checked={channel.long_tasks}
+                  onCheckedChange={(checked) =>
+                    updateChannel(index, { long_tasks: checked === true })
+                  }
+                />
+                <Label
+                  htmlFor={`long-tasks-${index}`}
+                  className="text-xs cursor-pointer"
+                >
+                  long builds and updates
+                </Label>
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  className="h-6 text-xs ml-auto"
+                  onClick={() => testChannel(channel)}
+                >
+                  test
+                </Button>
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  className="h-6 text-xs text-destructive"
+                  onClick={() => removeChannel(index)}
+                >
+                  remove
+                </Button>
+              </div>
+            </div>
+          ))}
+          <Button
+            variant="ghost"
+            size="sm"
+            className="h-7 text-xs"
+            onClick={addChannel}
+          >
+            + channel
+          </Button>
+          {settings ? (
+            <div className="flex items-center gap-2">
+              <Label htmlFor="long-task-secs" className="text-xs">
+                a build or update is long after
+              </Label>
+              <Input
+                id="long-task-secs"
+                type="number"
+                min={0}
+                value={settings.long_task_secs}
+                onChange={(e) =>
+                  setSettings({
+                    ...settings,
+                    long_task_secs: parseInt(e.target.value) || 0,
+                  })
+                }
+                className="h-8 w-20 text-sm"
+              />
+              <span className="text-xs text-muted-foreground">seconds</span>
+            </div>
+          ) : null}
+          {testResult ? (
+            <p className="text-xs text-muted-foreground">{testResult}</p>
+          ) : null}
+        </div>
+        <DialogFooter>
+          <Button variant="ghost" size="sm" onClick={onClose}>
+            cancel
+          </Button>
+          <Button size="sm" onClick={handleSave} disabled={saving}>
+            {saving ? "saving..." : "save"}
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+});
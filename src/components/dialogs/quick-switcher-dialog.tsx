@@ -0,0 +1,183 @@
+import { memo, useState, useEffect, useCallback, useMemo } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Input } from "@/components/ui/input";
+import {
+  Dialog,
+  DialogContent,
+  DialogHeader,
+  DialogTitle,
+} from "@/components/ui/dialog";
+import { cn } from "@/lib/utils";
+import type { App, AppMatch, RunningApps } from "@/types";
+
+const MAX_RESULTS = 8;
+
+interface QuickSwitcherDialogProps {
+  open: boolean;
+  apps: App[];
+  runningApps: RunningApps;
+  onClose: () => void;
+  onSelect: (id: string) => void;
+}
+
+// `text` with the matched characters emphasized; positions are char indices
+function Highlighted({
+  text,
+  positions,
+}: {
+  text: string;
+  positions: number[];
+}) {
+  const matched = new Set(positions);
+  return (
+    <>
+      {Array.from(text).map((char, i) =>
+        matched.has(i) ? (
+          <span key={i} className="text-primary">
+            {char}
+          </span>
+        ) : (
+          char
+        )
+      )}
+    </>
+  );
+}
+
+export const QuickSwitcherDialog = memo(function QuickSwitcherDialog({
+  open,
+  apps,
+  runningApps,
+  onClose,
+  onSelect,
+}: QuickSwitcherDialogProps) {
+  const [query, setQuery] = useState("");
+  const [matches, setMatches] = useState<AppMatch[]>([]);
+  const [active, setActive] = useState(0);
+
+  useEffect(() => {
+    if (!open) return;
+    setQuery("");
+  }, [open]);
+
+  useEffect(() => {
+    if (!open) return;
+    let cancelled = false;
+    invoke<AppMatch[]>("search_apps", { query })
+      .then((found) => {
+        if (cancelled) return;
+        setMatches(found.slice(0, MAX_RESULTS));
+        setActive(0);
+      })
+      .catch((e) => console.error("Search failed:", e));
+    return () => {
+      cancelled = true;
+    };
+  }, [open, query]);
+
+  const appsById = useMemo(
+    () => Object.fromEntries(apps.map((a) => [a.id, a])),
+    [apps]
+  );
+
+  const choose = useCallback(
+    (id: string) => {
+      onSelect(id);
+      onClose();
+    },
+    [onSelect, onClose]
+  );
+
+  const handleKeyDown = useCallback(
+    (e: React.KeyboardEvent<HTMLInputElement>) => {
+      if (e.key === "ArrowDown") {
+        e.preventDefault();
+        setActive((i) => Math.min(i + 1, matches.length - 1));
+      } else if (e.key === "ArrowUp") {
+        e.preventDefault();
+        setActive((i) => Math.max(i - 1, 0));
+      } else if (e.key === "Enter" && matches[active]) {
+        e.preventDefault();
+        choose(matches[active].id);
+      }
+    },
+    [matches, active, choose]
+  );
+
+  return (
+    <Dialog open={open} onOpenChange={onClose}>
+      <DialogContent className="max-w-md">
+        <DialogHeader>
+          <DialogTitle className="text-sm font-semibold">
+            <span className="text-muted-foreground">&gt;</span> find app
+          </DialogTitle>
+        </DialogHeader>
+        <Input
+          autoFocus
+          value={query}
+          onChange={(e) => setQuery(e.target.value)}
+          onKeyDown={handleKeyDown}
+          placeholder="name, subdomain, folder or tag"
+          className="h-8 text-sm"
+        />
+        <div className="space-y-0.5">
+          {matches.length === 0 ? (
+            <p className="text-muted-foreground text-xs py-2">no matches</p>
+          ) : (
+            matches.map((match, i) => {
+              const app = appsById[match.id];
+              if (!app) return null;
+              const isRunning = runningApps[app.id] !== undefined;
+              return (
+                <button
+                  key={match.id}
+                  type="button"
+                  onClick={() => choose(match.id)}
+                  onMouseEnter={() => setActive(i)}
+                  className={cn(
+                    "w-full text-left px-2 py-1.5 flex items-center gap-2",
+                    i === active && "bg-muted"
+                  )}
+                >
+                  <span
+                    className={cn(
+                      "size-1.5 shrink-0 rounded-full",
+                      isRunning ? "bg-success" : "bg-muted-foreground/40"
+                    )}
+                  />
+                  <span className="text-sm truncate">
+                    {match.field === "name" ? (
+                      <Highlighted
+                        text={app.name}
+                        positions={match.positions}
+                      />
+                    ) : (
+                      app.name
+                    )}
+                  </span>
+                  <span
+                    className="text-xs text-muted-foreground truncate ml-auto"
+                  >
+                    {match.field === "path" ? (
+                      <Highlighted
+                        text={app.path}
+                        positions={match.positions}
+                      />
+                    ) : match.field === "subdomain" && app.subdomain ? (
+                      <Highlighted
+                        text={app.subdomain}
+                        positions={match.positions}
+                      />
+                    ) : (
+                      app.subdomain ?? app.path
+                    )}
+                  </span>
+                </button>
+              );
+            })
+          )}
+        </div>
+      </DialogContent>
+    </Dialog>
+  );
+});
@@ -0,0 +1,172 @@
+import { memo, useState, useEffect, useCallback } from "react";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import {
+  Dialog,
+  DialogContent,
+  DialogHeader,
+  DialogTitle,
+  DialogFooter,
+} from "@/components/ui/dialog";
+import type { RemoteAppInput } from "@/types";
+import { formatError } from "@/lib/errors";
+
+interface RemoteAppDialogProps {
+  open: boolean;
+  onClose: () => void;
+  onAdd: (remote: RemoteAppInput) => Promise<void>;
+}
+
+const emptyRemote: RemoteAppInput = {
+  name: "",
+  host: "",
+  path: "",
+  command: "",
+  port: null,
+};
+
+export const RemoteAppDialog = memo(function RemoteAppDialog({
+  open,
+  onClose,
+  onAdd,
+}: RemoteAppDialogProps) {
+  const [remote, setRemote] = useState<RemoteAppInput>(emptyRemote);
+  const [adding, setAdding] = useState(false);
+  const [errorMessage, setErrorMessage] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!open) return;
+    setRemote(emptyRemote);
+    setErrorMessage(null);
+  }, [open]);
+
+  const update = useCallback(
+    (field: keyof RemoteAppInput) =>
+      (e: React.ChangeEvent<HTMLInputElement>) => {
+        const value = e.target.value;
+        setRemote((prev) => ({
+          ...prev,
+          [field]: field === "port" ? (value ? parseInt(value) : null) : value,
+        }));
+      },
+    []
+  );
+
+  const handleAdd = useCallback(async () => {
+    setAdding(true);
+    setErrorMessage(null);
+    try {
+      const path = remote.path.trim();
+      await onAdd({
+        ...remote,
+        name: remote.name.trim() || path.split("/").pop() || remote.host,
+        host: remote.host.trim(),
+        path,
+        command: remote.command.trim(),
+      });
+      onClose();
+    } catch (e) {
+      setErrorMessage(formatError(e));
+    } finally {
+      setAdding(false);
+    }
+  }, [remote, onAdd, onClose]);
+
+  const canAdd =
+    remote.host.trim() !== "" &&
+    remote.path.trim() !== "" &&
+    remote.command.trim() !== "";
+
+  return (
+    <Dialog open={open} onOpenChange={onClose}>
+      <DialogContent className="max-w-md">
+        <DialogHeader>
+          <DialogTitle className="text-sm font-semibold">
+            <span className="text-muted-foreground">&gt;</span> add remote app
+          </DialogTitle>
+        </DialogHeader>
+        <div className="space-y-4 py-2">
+          <p className="text-muted-foreground text-xs">
+            runs the command over ssh and forwards its port here. the host
+            needs key-based login, e.g. an entry in ~/.ssh/config.
+          </p>
+          <div className="space-y-2">
+            <Label htmlFor="remote-app-host" className="text-xs">
+              ssh host
+            </Label>
+            <Input
+              id="remote-app-host"
+              value={remote.host}
+              onChange={update("host")}
+              placeholder="user@desktop"
+              className="h-8 text-sm"
+            />
+          </div>
+          <div className="space-y-2">
+            <Label htmlFor="remote-app-path" className="text-xs">
+              path on the host
+            </Label>
+            <Input
+              id="remote-app-path"
+              value={remote.path}
+              onChange={update("path")}
+              placeholder="~/code/my-app"
+              className="h-8 text-sm"
+            />
+          </div>
+          <div className="space-y-2">
+            <Label htmlFor="remote-app-command" className="text-xs">
+              command
+            </Label>
+            <Input
+              id="remote-app-command"
+              value={remote.command}
+              onChange={update("command")}
+              placeholder="bun run dev"
+              className="h-8 text-sm"
+            />
+          </div>
+          <div className="grid grid-cols-2 gap-2">
+            <div className="space-y-2">
+              <Label htmlFor="remote-app-name" className="text-xs">
+                name
+              </Label>
+              <Input
+                id="remote-app-name"
+                value={remote.name}
+                onChange={update("name")}
+                placeholder="folder name"
+                className="h-8 text-sm"
+              />
+            </div>
+            <div className="space-y-2">
+              <Label htmlFor="remote-app-port" className="text-xs">
+                remote port
+              </Label>
+              <Input
+                id="remote-app-port"
+                type="number"
+                value={remote.port ?? ""}
+                onChange={update("port")}
+                placeholder="auto"
+                className="h-8 text-sm"
+              />
+            </div>
+          </div>
+          {errorMessage ? (
+            <p className="text-xs text-destructive">{errorMessage}</p>
+          ) : null}
+        </div>
+        <DialogFooter>
+          <Button variant="ghost" size="sm" onClick={onClose}>
+            cancel
+          </Button>
+          <Button size="sm" onClick={handleAdd} disabled={!canAdd || adding}>
+            {adding ? "adding..." : "add"}
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+});
@@ -0,0 +1,363 @@
+import { memo, useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { Switch } from "@/components/ui/switch";
+import {
+  Dialog,
+  DialogContent,
+  DialogHeader,
+  DialogTitle,
+  DialogFooter,
+} from "@/components/ui/dialog";
+import { prompt } from "@/components/confirm-dialog";
+import { formatError } from "@/lib/errors";
+import { cn } from "@/lib/utils";
+import type { RemoteApp, RemoteControlStatus } from "@/types";
+
+const MAX_LOG_LINES = 200;
+
+interface RemoteControlDialogProps {
+  open: boolean;
+  onClose: () => void;
+}
+
+export const RemoteControlDialog = memo(function RemoteControlDialog({
+  open,
+  onClose,
+}: RemoteControlDialogProps) {
+  const [status, setStatus] = useState<RemoteControlStatus | null>(null);
+  const [pairingCode, setPairingCode] = useState<string | null>(null);
+  const [address, setAddress] = useState("");
+  const [code, setCode] = useState("");
+  const [pairing, setPairing] = useState(false);
+  const [hostId, setHostId] = useState<string | null>(null);
+  const [remoteApps, setRemoteApps] = useState<RemoteApp[]>([]);
+  const [busyApp, setBusyApp] = useState<string | null>(null);
+  const [watchedApp, setWatchedApp] = useState<string | null>(null);
+  const [logLines, setLogLines] = useState<string[]>([]);
+  const [errorMessage, setErrorMessage] = useState<string | null>(null);
+
+  const loadStatus = useCallback(async () => {
+    setStatus(await invoke<RemoteControlStatus>("get_remote_control"));
+  }, []);
+
+  useEffect(() => {
+    if (!open) return;
+    setPairingCode(null);
+    setHostId(null);
+    setErrorMessage(null);
+    loadStatus();
+    const unlisten = listen("remote-control-changed", () => {
+      setPairingCode(null);
+      loadStatus();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [open, loadStatus]);
+
+  const remoteCall = useCallback(
+    (call: Record<string, string>) =>
+      invoke<unknown>("remote_call", { hostId, call }),
+    [hostId]
+  );
+
+  const loadRemoteApps = useCallback(async () => {
+    if (!hostId) return;
+    try {
+      setRemoteApps((await remoteCall({ call: "list_apps" })) as RemoteApp[]);
+      setErrorMessage(null);
+    } catch (e) {
+      setRemoteApps([]);
+      setErrorMessage(formatError(e));
+    }
+  }, [hostId, remoteCall]);
+
+  useEffect(() => {
+    setRemoteApps([]);
+    setWatchedApp(null);
+    loadRemoteApps();
+  }, [loadRemoteApps]);
+
+  useEffect(() => {
+    if (!hostId || !watchedApp) return;
+    const unlisten = listen<{
+      host_id: string;
+      id: string;
+      type: string;
+      message: string;
+    }>("remote-log", (event) => {
+      const line = event.payload;
+      if (line.host_id !== hostId || line.id !== watchedApp) return;
+      setLogLines((prev) =>
+        [...prev, `[${line.type}] ${line.message}`].slice(-MAX_LOG_LINES)
+      );
+    });
+    remoteCall({ call: "watch_logs", id: watchedApp })
+      .then((lines) => setLogLines(lines as string[]))
+      .catch((e) => setErrorMessage(formatError(e)));
+    return () => {
+      unlisten.then((fn) => fn());
+      remoteCall({ call: "unwatch_logs", id: watchedApp }).catch(() => {});
+    };
+  }, [hostId, watchedApp, remoteCall]);
+
+  const toggleRemoteControl = useCallback(async (enabled: boolean) => {
+    try {
+      setStatus(
+        await invoke<RemoteControlStatus>("set_remote_control", { enabled })
+      );
+      setPairingCode(null);
+    } catch (e) {
+      setErrorMessage(formatError(e));
+    }
+  }, []);
+
+  const createPairingCode = useCallback(async () => {
+    try {
+      setPairingCode(await invoke<string>("create_pairing_code"));
+    } catch (e) {
+      setErrorMessage(formatError(e));
+    }
+  }, []);
+
+  const revokeDevice = useCallback(
+    async (id: string) => {
+      await invoke("revoke_remote_device", { id });
+      loadStatus();
+    },
+    [loadStatus]
+  );
+
+  const pairHost = useCallback(async () => {
+    setPairing(true);
+    setErrorMessage(null);
+    try {
+      setStatus(
+        await invoke<RemoteControlStatus>("pair_remote_host", {
+          address: address.trim(),
+          code: code.trim(),
+        })
+      );
+      setAddress("");
+      setCode("");
+    } catch (e) {
+      setErrorMessage(formatError(e));
+    } finally {
+      setPairing(false);
+    }
+  }, [address, code]);
+
+  const removeHost = useCallback(
+    async (id: string) => {
+      await invoke("remove_remote_host", { id });
+      if (hostId === id) setHostId(null);
+      loadStatus();
+    },
+    [hostId, loadStatus]
+  );
+
+  const toggleApp = useCallback(
+    async (app: RemoteApp) => {
+      setBusyApp(app.id);
+      const call = app.port === null ? "start_app" : "stop_app";
+      try {
+        try {
+          await remoteCall({ call, id: app.id });
+        } catch (e) {
+          // The host's app lock uses a passcode; Touch ID is answered there
+          if (formatError(e) !== "Wrong passcode") throw e;
+          const action = app.port === null ? "start" : "stop";
+          const passcode = await prompt(
+            `Enter the host's passcode to ${action} ${app.name}.`,
+            { title: "app lock", confirm: "Unlock" }
+          );
+          if (passcode === null) return;
+          await remoteCall({ call, id: app.id, passcode });
+        }
+      } catch (e) {
+        setErrorMessage(formatError(e));
+      } finally {
+        setBusyApp(null);
+        loadRemoteApps();
+      }
+    },
+    [remoteCall, loadRemoteApps]
+  );
+
+  const hosting = status?.port != null;
+
+  return (
+    <Dialog open={open} onOpenChange={onClose}>
+      <DialogContent className="max-w-lg">
+        <DialogHeader>
+          <DialogTitle className="text-sm font-semibold">
+            <span className="text-muted-foreground">&gt;</span> remote control
+          </DialogTitle>
+        </DialogHeader>
+        <div className="space-y-4 py-2">
+          <div className="space-y-2">
+            <div className="flex items-center gap-2">
+              <Switch
+                id="remote-control"
+                checked={hosting}
+                onCheckedChange={toggleRemoteControl}
+                className="scale-75"
+              />
+              <Label
+                htmlFor="remote-control"
+                className="text-xs cursor-pointer"
+              >
+                let paired machines control this one
+              </Label>
+            </div>
+            <p className="text-xs text-muted-foreground">
+              paired machines can start and stop apps here and read their
+              logs. traffic is encrypted with a key set up when pairing.
+            </p>
+            {hosting ? (
+              <div className="flex items-center gap-2">
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  className="h-6 text-xs"
+                  onClick={createPairingCode}
+                >
+                  pair a machine
+                </Button>
+                {pairingCode ? (
+                  <span className="text-xs text-muted-foreground">
+                    enter{" "}
+                    <code className="text-success text-sm">
+                      {pairingCode}
+                    </code>{" "}
+                    on the other machine within 5 minutes
+                  </span>
+                ) : null}
+              </div>
+            ) : null}
+            {status?.devices.map((device) => (
+              <div key={device.id} className="flex items-center gap-2">
+                <span className="text-xs">{device.name}</span>
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  className="h-6 text-xs text-destructive ml-auto"
+                  onClick={() => revokeDevice(device.id)}
+                >
+                  revoke
+                </Button>
+              </div>
+            ))}
+          </div>
+
+          <div className="space-y-2">
+            <span className="text-xs text-muted-foreground">
+              other machines
+            </span>
+            {status?.hosts.map((host) => (
+              <div key={host.id} className="flex items-center gap-2">
+                <button
+                  type="button"
+                  onClick={() => setHostId(host.id)}
+                  className={cn(
+                    "text-xs text-left",
+                    hostId === host.id && "text-primary"
+                  )}
+                >
+                  {host.name}{" "}
+                  <span className="text-muted-foreground">{host.address}</span>
+                </button>
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  className="h-6 text-xs text-destructive ml-auto"
+                  onClick={() => removeHost(host.id)}
+                >
+                  remove
+                </Button>
+              </div>
+            ))}
+            <div className="flex items-center gap-2">
+              <Input
+                value={address}
+                onChange={(e) => setAddress(e.target.value)}
+                placeholder="studio.local"
+                className="h-8 text-sm"
+              />
+              <Input
+                value={code}
+                onChange={(e) => setCode(e.target.value)}
+                placeholder="pairing code"
+                className="h-8 text-sm"
+              />
+              <Button
+                size="sm"
+                onClick={pairHost}
+                disabled={!address.trim() || !code.trim() || pairing}
+              >
+                {pairing ? "pairing..." : "pair"}
+              </Button>
+            </div>
+          </div>
+
+          {hostId ? (
+            <div className="space-y-1">
+              {remoteApps.map((app) => (
+                <div key={app.id} className="flex items-center gap-2">
+                  <span
+                    className={cn(
+                      "size-1.5 shrink-0 rounded-full",
+                      app.port !== null
+                        ? "bg-success"
+                        : "bg-muted-foreground/40"
+                    )}
+                  />
+                  <span className="text-xs truncate">{app.name}</span>
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    className="h-6 text-xs ml-auto"
+                    onClick={() =>
+                      setWatchedApp(watchedApp === app.id ? null : app.id)
+                    }
+                  >
+                    {watchedApp === app.id ? "hide logs" : "logs"}
+                  </Button>
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    className="h-6 text-xs"
+                    onClick={() => toggleApp(app)}
+                    disabled={busyApp === app.id}
+                  >
+                    {app.port === null ? "start" : "stop"}
+                  </Button>
+                </div>
+              ))}
+              {watchedApp ? (
+                <pre
+                  className="bg-muted px-3 py-2 text-xs max-h-48 overflow-auto whitespace-pre-wrap"
+                >
+                  {logLines.join("\n") || "no output yet"}
+                </pre>
+              ) : null}
+            </div>
+          ) : null}
+
+          {errorMessage ? (
+            <p className="text-xs text-destructive">{errorMessage}</p>
+          ) : null}
+        </div>
+        <DialogFooter>
+          <Button size="sm" onClick={onClose}>
+            done
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+});
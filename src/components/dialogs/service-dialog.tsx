@@ -0,0 +1,145 @@
+import { memo, useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Button } from "@/components/ui/button";
+import { Checkbox } from "@/components/ui/checkbox";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import {
+  Dialog,
+  DialogContent,
+  DialogHeader,
+  DialogTitle,
+  DialogFooter,
+} from "@/components/ui/dialog";
+import { cn } from "@/lib/utils";
+import { formatError } from "@/lib/errors";
+import type { ServicePreset } from "@/types";
+
+interface ServiceDialogProps {
+  open: boolean;
+  onClose: () => void;
+  onAdd: (preset: string, name: string, local: boolean) => Promise<void>;
+}
+
+export const ServiceDialog = memo(function ServiceDialog({
+  open,
+  onClose,
+  onAdd,
+}: ServiceDialogProps) {
+  const [presets, setPresets] = useState<ServicePreset[]>([]);
+  const [selected, setSelected] = useState<ServicePreset | null>(null);
+  const [name, setName] = useState("");
+  const [local, setLocal] = useState(false);
+  const [adding, setAdding] = useState(false);
+  const [errorMessage, setErrorMessage] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!open) return;
+    setSelected(null);
+    setName("");
+    setLocal(false);
+    setErrorMessage(null);
+    invoke<ServicePreset[]>("get_service_presets")
+      .then(setPresets)
+      .catch((e) => setErrorMessage(formatError(e)));
+  }, [open]);
+
+  const handleSelect = useCallback((preset: ServicePreset) => {
+    setSelected(preset);
+    setName(preset.id);
+    setLocal((prev) => prev && preset.supports_local);
+  }, []);
+
+  const handleAdd = useCallback(async () => {
+    if (!selected) return;
+    setAdding(true);
+    setErrorMessage(null);
+    try {
+      await onAdd(selected.id, name.trim() || selected.id, local);
+      onClose();
+    } catch (e) {
+      setErrorMessage(formatError(e));
+    } finally {
+      setAdding(false);
+    }
+  }, [selected, name, local, onAdd, onClose]);
+
+  return (
+    <Dialog open={open} onOpenChange={onClose}>
+      <DialogContent className="max-w-md">
+        <DialogHeader>
+          <DialogTitle className="text-sm font-semibold">
+            <span className="text-muted-foreground">&gt;</span> add database
+          </DialogTitle>
+        </DialogHeader>
+        <div className="space-y-4 py-2">
+          <div className="grid grid-cols-3 gap-2">
+            {presets.map((preset) => (
+              <button
+                key={preset.id}
+                type="button"
+                onClick={() => handleSelect(preset)}
+                className={cn(
+                  "border border-border p-2 text-left text-xs hover:bg-muted",
+                  selected?.id === preset.id && "border-primary bg-muted"
+                )}
+              >
+                <div>{preset.name}</div>
+                <div className="text-muted-foreground">:{preset.port}</div>
+              </button>
+            ))}
+          </div>
+          {selected ? (
+            <>
+              <div className="space-y-2">
+                <Label htmlFor="service-name" className="text-xs">
+                  name
+                </Label>
+                <Input
+                  id="service-name"
+                  value={name}
+                  onChange={(e) => setName(e.target.value)}
+                  className="h-8 text-sm"
+                />
+              </div>
+              <div className="flex items-center gap-2">
+                <Checkbox
+                  id="service-local"
+                  checked={local}
+                  disabled={!selected.supports_local}
+                  onCheckedChange={(checked) => setLocal(checked === true)}
+                />
+                <Label
+                  htmlFor="service-local"
+                  className="text-xs cursor-pointer"
+                >
+                  use local binaries instead of docker
+                </Label>
+              </div>
+              <p className="text-muted-foreground text-xs">
+                {local
+                  ? "data is stored in the app folder."
+                  : `runs ${selected.image} with its data in a named docker volume.`}
+              </p>
+            </>
+          ) : null}
+          {errorMessage ? (
+            <p className="text-xs text-destructive">{errorMessage}</p>
+          ) : null}
+        </div>
+        <DialogFooter>
+          <Button variant="ghost" size="sm" onClick={onClose}>
+            cancel
+          </Button>
+          <Button
+            size="sm"
+            onClick={handleAdd}
+            disabled={!selected || adding}
+          >
+            {adding ? "adding..." : "add"}
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+});
@@ -0,0 +1,348 @@
+import { memo, useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { ScrollArea } from "@/components/ui/scroll-area";
+import {
+  Dialog,
+  DialogContent,
+  DialogHeader,
+  DialogTitle,
+  DialogFooter,
+} from "@/components/ui/dialog";
+import { unlock } from "@/lib/app-lock";
+import { formatError } from "@/lib/errors";
+import type { App, Session, SessionTrigger } from "@/types";
+
+const DAYS = ["mo", "tu", "we", "th", "fr", "sa", "su"];
+const WEEKDAYS = 0b0011111;
+
+function describeTrigger(trigger: SessionTrigger) {
+  const target = trigger.session ?? "stop all apps";
+  if (trigger.kind === "focus") return `focus "${trigger.mode}" → ${target}`;
+  const days =
+    trigger.days === WEEKDAYS
+      ? "weekdays"
+      : trigger.days === 0b1111111
+        ? "every day"
+        : DAYS.filter((_, i) => trigger.days & (1 << i)).join(" ");
+  return `${days} ${trigger.at} → ${target}`;
+}
+
+interface SessionTriggersProps {
+  sessions: Session[];
+}
+
+// Schedules and Focus modes that switch sessions on their own
+const SessionTriggers = memo(function SessionTriggers({
+  sessions,
+}: SessionTriggersProps) {
+  const [triggers, setTriggers] = useState<SessionTrigger[]>([]);
+  const [kind, setKind] = useState<"schedule" | "focus">("schedule");
+  const [days, setDays] = useState(WEEKDAYS);
+  const [at, setAt] = useState("23:00");
+  const [mode, setMode] = useState("");
+  const [session, setSession] = useState<string | null>(null);
+  const [errorMessage, setErrorMessage] = useState<string | null>(null);
+
+  const loadTriggers = useCallback(async () => {
+    try {
+      setTriggers(await invoke<SessionTrigger[]>("list_session_triggers"));
+    } catch (e) {
+      setErrorMessage(formatError(e));
+    }
+  }, []);
+
+  // Deleting a session deletes its triggers too
+  useEffect(() => {
+    loadTriggers();
+  }, [sessions, loadTriggers]);
+
+  const handleAdd = useCallback(async () => {
+    setErrorMessage(null);
+    const trigger =
+      kind === "schedule"
+        ? { id: 0, session, kind, days, at }
+        : { id: 0, session, kind, mode };
+    const unlocked = await unlock("schedule a session switch");
+    if (!unlocked) return;
+    try {
+      await invoke<SessionTrigger>("add_session_trigger", {
+        trigger,
+        passcode: unlocked.passcode,
+      });
+      setMode("");
+      await loadTriggers();
+    } catch (e) {
+      setErrorMessage(formatError(e));
+    }
+  }, [kind, session, days, at, mode, loadTriggers]);
+
+  const handleDelete = useCallback(
+    async (id: number) => {
+      try {
+        await invoke("delete_session_trigger", { id });
+        await loadTriggers();
+      } catch (e) {
+        setErrorMessage(formatError(e));
+      }
+    },
+    [loadTriggers]
+  );
+
+  return (
+    <div className="space-y-2 border-t pt-3">
+      <div className="text-xs font-semibold">switch automatically</div>
+      {triggers.map((trigger) => (
+        <div key={trigger.id} className="flex items-center justify-between">
+          <span className="text-xs">{describeTrigger(trigger)}</span>
+          <Button
+            variant="ghost"
+            size="sm"
+            className="h-6 text-xs text-destructive"
+            onClick={() => handleDelete(trigger.id)}
+          >
+            delete
+          </Button>
+        </div>
+      ))}
+      <div className="flex items-center gap-1">
+        {(["schedule", "focus"] as const).map((option) => (
+          <Button
+            key={option}
+            variant={kind === option ? "secondary" : "ghost"}
+            size="sm"
+            className="h-6 text-xs"
+            onClick={() => setKind(option)}
+          >
+            {option === "schedule" ? "at a time" : "on a focus mode"}
+          </Button>
+        ))}
+      </div>
+      {kind === "schedule" ? (
+        <div className="flex items-center gap-1">
+          {DAYS.map((day, i) => (
+            <Button
+              key={day}
+              variant={days & (1 << i) ? "secondary" : "ghost"}
+              size="sm"
+              className="h-6 w-7 px-0 text-xs"
+              onClick={() => setDays(days ^ (1 << i))}
+            >
+              {day}
+            </Button>
+          ))}
+          <Input
+            type="time"
+            value={at}
+            onChange={(e) => setAt(e.target.value)}
+            className="h-7 w-28 text-xs"
+          />
+        </div>
+      ) : (
+        <div className="space-y-1">
+          <Input
+            value={mode}
+            onChange={(e) => setMode(e.target.value)}
+            placeholder="Work"
+            className="h-7 text-xs"
+          />
+          <p className="text-muted-foreground text-xs">
+            reading the focus mode needs full disk access for my little apps
+          </p>
+        </div>
+      )}
+      <div className="flex flex-wrap items-center gap-1">
+        <span className="text-muted-foreground text-xs">switch to</span>
+        {[null, ...sessions.map((s) => s.name)].map((name) => (
+          <Button
+            key={name ?? ""}
+            variant={session === name ? "secondary" : "ghost"}
+            size="sm"
+            className="h-6 text-xs"
+            onClick={() => setSession(name)}
+          >
+            {name ?? "stop all apps"}
+          </Button>
+        ))}
+      </div>
+      <Button size="sm" className="h-7 text-xs" onClick={handleAdd}>
+        add trigger
+      </Button>
+      {errorMessage ? (
+        <p className="text-xs text-destructive">{errorMessage}</p>
+      ) : null}
+    </div>
+  );
+});
+
+interface SessionsDialogProps {
+  open: boolean;
+  apps: App[];
+  runningCount: number;
+  onClose: () => void;
+  onRestore: (name: string) => Promise<void>;
+}
+
+export const SessionsDialog = memo(function SessionsDialog({
+  open,
+  apps,
+  runningCount,
+  onClose,
+  onRestore,
+}: SessionsDialogProps) {
+  const [sessions, setSessions] = useState<Session[]>([]);
+  const [name, setName] = useState("");
+  const [busy, setBusy] = useState<string | null>(null);
+  const [errorMessage, setErrorMessage] = useState<string | null>(null);
+
+  const loadSessions = useCallback(async () => {
+    try {
+      setSessions(await invoke<Session[]>("list_sessions"));
+    } catch (e) {
+      setErrorMessage(formatError(e));
+    }
+  }, []);
+
+  useEffect(() => {
+    if (!open) return;
+    setErrorMessage(null);
+    loadSessions();
+  }, [open, loadSessions]);
+
+  const handleSave = useCallback(async () => {
+    setBusy("save");
+    setErrorMessage(null);
+    try {
+      await invoke<Session>("save_session", { name });
+      setName("");
+      await loadSessions();
+    } catch (e) {
+      setErrorMessage(formatError(e));
+    } finally {
+      setBusy(null);
+    }
+  }, [name, loadSessions]);
+
+  const handleRestore = useCallback(
+    async (session: string) => {
+      setBusy(session);
+      setErrorMessage(null);
+      try {
+        await onRestore(session);
+        onClose();
+      } catch (e) {
+        setErrorMessage(formatError(e));
+      } finally {
+        setBusy(null);
+      }
+    },
+    [onRestore, onClose]
+  );
+
+  const handleDelete = useCallback(
+    async (session: string) => {
+      try {
+        await invoke("delete_session", { name: session });
+        await loadSessions();
+      } catch (e) {
+        setErrorMessage(formatError(e));
+      }
+    },
+    [loadSessions]
+  );
+
+  const appNames = (session: Session) =>
+    session.apps
+      .map(({ app_id, profile }) => {
+        const appName = apps.find((a) => a.id === app_id)?.name ?? app_id;
+        return profile ? `${appName} (${profile})` : appName;
+      })
+      .join(", ");
+
+  return (
+    <Dialog open={open} onOpenChange={onClose}>
+      <DialogContent className="max-w-lg">
+        <DialogHeader>
+          <DialogTitle className="text-sm font-semibold">
+            <span className="text-muted-foreground">&gt;</span> sessions
+          </DialogTitle>
+        </DialogHeader>
+        <div className="space-y-3 py-2">
+          <p className="text-muted-foreground text-xs">
+            a session is a set of running apps. restoring one stops everything
+            else and starts exactly its apps, on the profiles they ran with.
+          </p>
+          <div className="flex items-center gap-2">
+            <Input
+              value={name}
+              onChange={(e) => setName(e.target.value)}
+              placeholder="client-acme"
+              className="h-8 text-sm"
+            />
+            <Button
+              size="sm"
+              className="h-8 text-xs"
+              disabled={!name.trim() || runningCount === 0 || busy !== null}
+              onClick={handleSave}
+            >
+              {busy === "save" ? "saving..." : `save ${runningCount} running`}
+            </Button>
+          </div>
+          {errorMessage ? (
+            <p className="text-xs text-destructive">{errorMessage}</p>
+          ) : null}
+          {sessions.length > 0 ? (
+            <ScrollArea className="max-h-64">
+              <div className="space-y-1">
+                {sessions.map((session) => (
+                  <div
+                    key={session.name}
+                    className="flex items-center justify-between gap-2"
+                  >
+                    <div className="min-w-0">
+                      <div className="text-xs">{session.name}</div>
+                      <div className="text-xs text-muted-foreground truncate">
+                        {appNames(session)}
+                      </div>
+                    </div>
+                    <div className="flex items-center gap-1">
+                      <Button
+                        variant="ghost"
+                        size="sm"
+                        className="h-6 text-xs"
+                        disabled={busy !== null}
+                        onClick={() => handleRestore(session.name)}
+                      >
+                        {busy === session.name ? "switching..." : "restore"}
+                      </Button>
+                      <Button
+                        variant="ghost"
+                        size="sm"
+                        className="h-6 text-xs text-destructive"
+                        disabled={busy !== null}
+                        onClick={() => handleDelete(session.name)}
+                      >
+                        delete
+                      </Button>
+                    </div>
+                  </div>
+                ))}
+              </div>
+            </ScrollArea>
+          ) : (
+            <p className="text-xs text-muted-foreground italic">
+              no sessions saved yet
+            </p>
+          )}
+          <SessionTriggers sessions={sessions} />
+        </div>
+        <DialogFooter>
+          <Button variant="ghost" size="sm" onClick={onClose}>
+            close
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+});
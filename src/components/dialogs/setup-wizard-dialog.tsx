@@ -1,4 +1,5 @@
-import { memo } from "react";
+import { memo, useCallback, useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { Button } from "@/components/ui/button";
 import {
   Dialog,
@@ -8,7 +9,23 @@ import {
   DialogFooter,
 } from "@/components/ui/dialog";
 import { cn } from "@/lib/utils";
-import type { ProxyServiceStatus } from "@/types";
+import { describeError } from "@/lib/errors";
+import type { DoctorCheck, DoctorFix, ProxyServiceStatus } from "@/types";
+
+const fixLabels: Record<DoctorFix["type"], string> = {
+  install_proxy: "install",
+  start_proxy: "start",
+  enable_dns_responder: "repair",
+  network_workarounds: "work around",
+  start_docker: "start",
+  open_url: "get",
+};
+
+const statusColors: Record<DoctorCheck["status"], string> = {
+  ok: "bg-success",
+  warning: "bg-warning",
+  error: "bg-destructive",
+};
 
 interface SetupWizardDialogProps {
   open: boolean;
@@ -25,6 +42,34 @@ export const SetupWizardDialog = memo(function SetupWizardDialog({
   onClose,
   onInstall,
 }: SetupWizardDialogProps) {
+  const [checks, setChecks] = useState<DoctorCheck[] | null>(null);
+  const [fixing, setFixing] = useState<string | null>(null);
+
+  const runDoctor = useCallback(() => {
+    invoke<DoctorCheck[]>("run_doctor").then(setChecks);
+  }, []);
+
+  // Again once an install finishes
+  useEffect(() => {
+    if (open && !setupLoading) runDoctor();
+  }, [open, setupLoading, runDoctor]);
+
+  const handleFix = useCallback(
+    async (check: DoctorCheck) => {
+      if (!check.fix) return;
+      setFixing(check.check);
+      try {
+        await invoke("run_doctor_fix", { fix: check.fix });
+      } catch (e) {
+        alert(describeError(e));
+      } finally {
+        setFixing(null);
+        runDoctor();
+      }
+    },
+    [runDoctor]
+  );
+
   return (
     <Dialog open={open} onOpenChange={onClose}>
       <DialogContent className="max-w-lg">
@@ -69,6 +114,58 @@ export const SetupWizardDialog = memo(function SetupWizardDialog({
             </div>
           </div>
 
+          <div className="bg-muted/50 p-3 space-y-2">
+            <div className="flex items-center justify-between">
+              <span className="text-xs text-muted-foreground uppercase tracking-wider">
+                checks
+              </span>
+              <Button
+                variant="ghost"
+                size="sm"
+                className="h-6 text-xs"
+                onClick={runDoctor}
+              >
+                re-run
+              </Button>
+            </div>
+            {checks === null ? (
+              <p className="text-xs text-muted-foreground">checking...</p>
+            ) : (
+              <div className="space-y-1 text-xs">
+                {checks.map((check) => (
+                  <div key={check.check} className="flex items-center gap-2">
+                    <span
+                      className={cn(
+                        "w-2 h-2 rounded-full shrink-0",
+                        statusColors[check.status]
+                      )}
+                    />
+                    <span className="shrink-0">{check.label}:</span>
+                    <span
+                      className="text-muted-foreground truncate"
+                      title={check.message}
+                    >
+                      {check.message}
+                    </span>
+                    {check.fix && check.status !== "ok" && (
+                      <Button
+                        variant="outline"
+                        size="sm"
+                        className="h-6 text-xs ml-auto shrink-0"
+                        disabled={fixing !== null}
+                        onClick={() => handleFix(check)}
+                      >
+                        {fixing === check.check
+                          ? "..."
+                          : fixLabels[check.fix.type]}
+                      </Button>
+                    )}
+                  </div>
+                ))}
+              </div>
+            )}
+          </div>
+
           <div className="bg-muted/50 p-3 space-y-2">
             <span className="text-xs text-muted-foreground uppercase tracking-wider">
               what will be installed
@@ -0,0 +1,138 @@
+import { memo, useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Button } from "@/components/ui/button";
+import { ScrollArea } from "@/components/ui/scroll-area";
+import {
+  Dialog,
+  DialogContent,
+  DialogHeader,
+  DialogTitle,
+  DialogFooter,
+} from "@/components/ui/dialog";
+import type { App, RepoBranches } from "@/types";
+import { formatError } from "@/lib/errors";
+
+interface WorktreeDialogProps {
+  app: App | null;
+  apps: App[];
+  onClose: () => void;
+  onCreate: (repo: string, branch: string) => Promise<void>;
+}
+
+export const WorktreeDialog = memo(function WorktreeDialog({
+  app,
+  apps,
+  onClose,
+  onCreate,
+}: WorktreeDialogProps) {
+  const [branches, setBranches] = useState<RepoBranches | null>(null);
+  const [creating, setCreating] = useState<string | null>(null);
+  const [errorMessage, setErrorMessage] = useState<string | null>(null);
+
+  const repo = app ? app.worktree_of ?? app.path : null;
+
+  useEffect(() => {
+    if (!repo) return;
+    let cancelled = false;
+    setBranches(null);
+    setErrorMessage(null);
+    invoke<RepoBranches | null>("get_repo_branches", { path: repo })
+      .then((result) => {
+        if (cancelled) return;
+        if (result) {
+          setBranches(result);
+        } else {
+          setErrorMessage("not a git repository");
+        }
+      })
+      .catch((e) => {
+        if (!cancelled) setErrorMessage(formatError(e));
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [repo]);
+
+  const handleCreate = useCallback(
+    async (branch: string) => {
+      if (!repo) return;
+      setCreating(branch);
+      setErrorMessage(null);
+      try {
+        await onCreate(repo, branch);
+        onClose();
+      } catch (e) {
+        setErrorMessage(formatError(e));
+      } finally {
+        setCreating(null);
+      }
+    },
+    [repo, onCreate, onClose]
+  );
+
+  const registeredBranch = (branch: string) => {
+    const worktree = branches?.worktrees.find((w) => w.branch === branch);
+    return worktree && apps.some((a) => a.path === worktree.path);
+  };
+
+  return (
+    <Dialog open={app !== null} onOpenChange={onClose}>
+      <DialogContent className="max-w-lg">
+        <DialogHeader>
+          <DialogTitle className="text-sm font-semibold">
+            <span className="text-muted-foreground">&gt;</span> new worktree
+            app
+          </DialogTitle>
+        </DialogHeader>
+        <div className="space-y-3 py-2">
+          <p className="text-muted-foreground text-xs">
+            checks out a branch into its own folder next to{" "}
+            <code className="text-primary">{repo}</code> and adds it as an app.
+          </p>
+          {errorMessage ? (
+            <p className="text-xs text-destructive">{errorMessage}</p>
+          ) : null}
+          {branches ? (
+            <ScrollArea className="max-h-64">
+              <div className="space-y-1">
+                {branches.branches.map((branch) => {
+                  const added = registeredBranch(branch);
+                  return (
+                    <div
+                      key={branch}
+                      className="flex items-center justify-between gap-2"
+                    >
+                      <span className="text-xs truncate">{branch}</span>
+                      <Button
+                        variant="ghost"
+                        size="sm"
+                        className="h-6 text-xs"
+                        disabled={added || creating !== null}
+                        onClick={() => handleCreate(branch)}
+                      >
+                        {added
+                          ? "added"
+                          : creating === branch
+                            ? "creating..."
+                            : "add"}
+                      </Button>
+                    </div>
+                  );
+                })}
+              </div>
+            </ScrollArea>
+          ) : errorMessage ? null : (
+            <p className="text-xs text-muted-foreground italic">
+              loading branches...
+            </p>
+          )}
+        </div>
+        <DialogFooter>
+          <Button variant="ghost" size="sm" onClick={onClose}>
+            cancel
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+});
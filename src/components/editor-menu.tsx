@@ -0,0 +1,126 @@
+import { memo, useCallback, useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import {
+  DropdownMenu,
+  DropdownMenuContent,
+  DropdownMenuLabel,
+  DropdownMenuRadioGroup,
+  DropdownMenuRadioItem,
+  DropdownMenuSeparator,
+  DropdownMenuTrigger,
+} from "@/components/ui/dropdown-menu";
+import { formatError } from "@/lib/errors";
+import type { Editor, EditorPreference } from "@/types";
+
+const editorNames: Record<Editor, string> = {
+  vscode: "vs code",
+  cursor: "cursor",
+  zed: "zed",
+  webstorm: "webstorm",
+};
+
+export const EditorMenu = memo(function EditorMenu() {
+  const [preference, setPreference] = useState<EditorPreference>({
+    editor: null,
+    command: null,
+  });
+  const [installed, setInstalled] = useState<Editor[]>([]);
+  const [command, setCommand] = useState("");
+  const [errorMessage, setErrorMessage] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<EditorPreference>("get_editor_preference")
+      .then((saved) => {
+        setPreference(saved);
+        setCommand(saved.command ?? "");
+      })
+      .catch((e) => console.error("Failed to load editor preference:", e));
+    invoke<Editor[]>("get_installed_editors")
+      .then(setInstalled)
+      .catch((e) => console.error("Failed to find editors:", e));
+  }, []);
+
+  const updatePreference = useCallback(async (next: EditorPreference) => {
+    setErrorMessage(null);
+    try {
+      const saved = await invoke<EditorPreference>("set_editor_preference", {
+        preference: next,
+      });
+      setPreference(saved);
+      setCommand(saved.command ?? "");
+    } catch (e) {
+      setErrorMessage(formatError(e));
+    }
+  }, []);
+
+  const label = preference.command
+    ? "command"
+    : preference.editor
+      ? editorNames[preference.editor]
+      : "auto";
+
+  return (
+    <DropdownMenu>
+      <DropdownMenuTrigger asChild>
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-6 text-xs text-muted-foreground"
+        >
+          editor: {label}
+        </Button>
+      </DropdownMenuTrigger>
+      <DropdownMenuContent align="end" className="w-64">
+        <DropdownMenuLabel>open projects in</DropdownMenuLabel>
+        <DropdownMenuRadioGroup
+          value={preference.editor ?? "auto"}
+          onValueChange={(value) =>
+            updatePreference({
+              ...preference,
+              editor: value === "auto" ? null : (value as Editor),
+            })
+          }
+        >
+          <DropdownMenuRadioItem value="auto">
+            first installed
+          </DropdownMenuRadioItem>
+          {installed.map((editor) => (
+            <DropdownMenuRadioItem key={editor} value={editor}>
+              {editorNames[editor]}
+            </DropdownMenuRadioItem>
+          ))}
+        </DropdownMenuRadioGroup>
+        <DropdownMenuSeparator />
+        <DropdownMenuLabel>or run a command</DropdownMenuLabel>
+        <div className="space-y-1 px-2 pb-2">
+          <Input
+            value={command}
+            onChange={(e) => setCommand(e.target.value)}
+            // Keep typing out of the menu's keyboard navigation
+            onKeyDown={(e) => {
+              e.stopPropagation();
+              if (e.key === "Enter") {
+                updatePreference({ ...preference, command });
+              }
+            }}
+            onBlur={() => {
+              if (command !== (preference.command ?? "")) {
+                updatePreference({ ...preference, command });
+              }
+            }}
+            placeholder="subl {file}:{line}"
+            className="h-7 text-xs"
+          />
+          <p className="text-muted-foreground text-xs">
+            {"{path} {file} {line} {column}"}, runs in your login shell
+          </p>
+          {errorMessage ? (
+            <p className="text-xs text-destructive">{errorMessage}</p>
+          ) : null}
+        </div>
+      </DropdownMenuContent>
+    </DropdownMenu>
+  );
+});
@@ -0,0 +1,139 @@
+import { memo, useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { ScrollArea } from "@/components/ui/scroll-area";
+import { cn } from "@/lib/utils";
+import { describeError } from "@/lib/errors";
+import type { InternalLogEntry } from "@/types";
+
+const LEVELS = ["debug", "info", "warn", "error"] as const;
+type Level = (typeof LEVELS)[number];
+
+const REFRESH_INTERVAL_MS = 2000;
+
+function formatFields(fields: Record<string, unknown>) {
+  return Object.entries(fields)
+    .map(([key, value]) =>
+      typeof value === "string"
+        ? `${key}=${value}`
+        : `${key}=${JSON.stringify(value)}`
+    )
+    .join(" ");
+}
+
+const InternalLogLine = memo(function InternalLogLine({
+  entry,
+}: {
+  entry: InternalLogEntry;
+}) {
+  return (
+    <div
+      className={cn(
+        "py-0.5 break-all",
+        entry.level === "ERROR" && "text-destructive",
+        entry.level === "WARN" && "text-yellow-500"
+      )}
+    >
+      <span className="text-muted-foreground">
+        {entry.timestamp.slice(11, 23)}
+      </span>{" "}
+      <span className="inline-block w-12">{entry.level.toLowerCase()}</span>
+      {entry.spans.length > 0 ? (
+        <span className="text-muted-foreground">
+          {entry.spans.join(" › ")}:{" "}
+        </span>
+      ) : null}
+      {entry.message}{" "}
+      <span className="text-muted-foreground">
+        {formatFields(entry.fields)}
+      </span>
+    </div>
+  );
+});
+
+// The hidden debug window: the backend's internal log, refreshed while open
+export const InternalLogViewer = memo(function InternalLogViewer() {
+  const [entries, setEntries] = useState<InternalLogEntry[]>([]);
+  const [level, setLevel] = useState<Level>("info");
+  const [filter, setFilter] = useState("");
+  const [paused, setPaused] = useState(false);
+  const [errorMessage, setErrorMessage] = useState<string | null>(null);
+
+  const refresh = useCallback(async () => {
+    try {
+      setEntries(
+        await invoke<InternalLogEntry[]>("get_internal_logs", { level })
+      );
+      setErrorMessage(null);
+    } catch (e) {
+      setErrorMessage(describeError(e));
+    }
+  }, [level]);
+
+  useEffect(() => {
+    if (paused) return;
+    refresh();
+    const interval = setInterval(refresh, REFRESH_INTERVAL_MS);
+    return () => clearInterval(interval);
+  }, [paused, refresh]);
+
+  const query = filter.trim().toLowerCase();
+  const shown = query
+    ? entries.filter((entry) =>
+        [entry.message, ...entry.spans, formatFields(entry.fields)].some(
+          (text) => text.toLowerCase().includes(query)
+        )
+      )
+    : entries;
+
+  return (
+    <div className="flex flex-col h-screen overflow-hidden">
+      <div className="px-4 py-2 border-b border-border flex items-center gap-2">
+        <span className="text-xs text-muted-foreground uppercase tracking-wider mr-2">
+          internal log
+        </span>
+        {LEVELS.map((l) => (
+          <Button
+            key={l}
+            variant={l === level ? "secondary" : "ghost"}
+            size="sm"
+            className="h-7 text-xs"
+            onClick={() => setLevel(l)}
+          >
+            {l}
+          </Button>
+        ))}
+        <Input
+          value={filter}
+          onChange={(e) => setFilter(e.target.value)}
+          placeholder="filter, e.g. launch_app or app=api"
+          className="h-7 w-64 text-xs ml-auto"
+        />
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-7 text-xs"
+          onClick={() => setPaused((p) => !p)}
+        >
+          {paused ? "resume" : "pause"}
+        </Button>
+      </div>
+      {errorMessage ? (
+        <p className="px-4 py-2 text-xs text-destructive">{errorMessage}</p>
+      ) : null}
+      <ScrollArea className="flex-1 min-h-0 bg-[oklch(0.1_0.005_285.823)]">
+        <div className="p-4 font-mono text-xs leading-relaxed">
+          {shown.length === 0 ? (
+            <p className="text-muted-foreground italic">nothing logged yet.</p>
+          ) : (
+            // Newest first, so fresh entries don't need scrolling to
+            [...shown]
+              .reverse()
+              .map((entry, i) => <InternalLogLine key={i} entry={entry} />)
+          )}
+        </div>
+      </ScrollArea>
+    </div>
+  );
+});
@@ -4,7 +4,34 @@ import { listen } from "@tauri-apps/api/event";
 import { open } from "@tauri-apps/plugin-dialog";
 import { info, error } from "@tauri-apps/plugin-log";
 import Database from "@tauri-apps/plugin-sql";
-import type { App, RunningApps, AppLogs, AppsUsage, ProxyRoute } from "@/types";
+import type {
+  App,
+  RunningApps,
+  AppLogs,
+  AppsUsage,
+  LastAccessed,
+  AppsGitInfo,
+  AppProfiles,
+  LaunchProfile,
+  ProxyRoute,
+  WorktreeApp,
+  WorkspacePackage,
+  DetectedProject,
+  ServiceApp,
+  RemoteAppInput,
+  BulkResult,
+  CleanReport,
+  ConfigChange,
+  DeleteOptions,
+  RestartNeeded,
+  SettingChange,
+  StartPhase,
+  StaleBuild,
+  Settings,
+} from "@/types";
+import { describeError, formatError } from "@/lib/errors";
+
+type ProfileRow = Omit<LaunchProfile, "env"> & { env: string };
 
 interface UseAppsOptions {
   addProxyRoute: (
@@ -29,22 +56,64 @@ export function useApps({
 }: UseAppsOptions) {
   const [apps, setApps] = useState<App[]>([]);
   const [runningApps, setRunningApps] = useState<RunningApps>({});
+  // Running apps whose output or port says they accept requests
+  const [readyApps, setReadyApps] = useState<{ [id: string]: boolean }>({});
+  // Apps in their build step or printing build output
+  const [buildingApps, setBuildingApps] = useState<{ [id: string]: boolean }>(
+    {}
+  );
+  // Apps still not ready after their startup allowance
+  const [slowStarts, setSlowStarts] = useState<{ [id: string]: StartPhase }>(
+    {}
+  );
+  // Apps started on a build older than their sources
+  const [staleBuilds, setStaleBuilds] = useState<{
+    [id: string]: StaleBuild;
+  }>({});
+  const [restartNeeded, setRestartNeeded] = useState<RestartNeeded>({});
   const [appsUsage, setAppsUsage] = useState<AppsUsage>({});
   const [logs, setLogs] = useState<AppLogs>({});
+  const [profiles, setProfiles] = useState<AppProfiles>({});
+  const [gitInfo, setGitInfo] = useState<AppsGitInfo>({});
+  // Unix ms of each app's last request through the proxy
+  const [lastAccessed, setLastAccessed] = useState<LastAccessed>({});
+  const [runningProfiles, setRunningProfiles] = useState<{
+    [id: string]: string;
+  }>({});
   const [db, setDb] = useState<Database | null>(null);
   const appsRef = useRef<App[]>([]);
+  const proxyRoutesRef = useRef(proxyRoutes);
+  const logRetention = useRef(200);
 
   useEffect(() => {
     appsRef.current = apps;
   }, [apps]);
 
+  useEffect(() => {
+    proxyRoutesRef.current = proxyRoutes;
+  }, [proxyRoutes]);
+
+  useEffect(() => {
+    invoke<Settings>("get_settings").then((settings) => {
+      logRetention.current = settings.log_retention;
+    });
+    const unlisten = listen<SettingChange>("settings-changed", (event) => {
+      if (event.payload.key === "log_retention") {
+        logRetention.current = event.payload.value;
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   useEffect(() => {
     const initDb = async () => {
       try {
         const database = await Database.load("sqlite:my-little-apps.db");
         setDb(database);
       } catch (e) {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = formatError(e);
         try {
           await error(`Database load failed: ${message}`);
         } catch {}
@@ -57,40 +126,63 @@ export function useApps({
     if (!db) return;
     const result = await db.select<App[]>("SELECT * FROM apps ORDER BY name");
     setApps(result);
-    invoke("refresh_tray", { apps: result });
+
+    const profileRows = await db.select<ProfileRow[]>(
+      "SELECT * FROM app_profiles ORDER BY name"
+    );
+    const grouped: AppProfiles = {};
+    for (const row of profileRows) {
+      let env: Record<string, string> = {};
+      try {
+        env = JSON.parse(row.env);
+      } catch {}
+      grouped[row.app_id] = [...(grouped[row.app_id] || []), { ...row, env }];
+    }
+    setProfiles(grouped);
+
+    invoke("refresh_tray").catch((e) =>
+      console.error("Failed to refresh tray:", e)
+    );
     return result;
   }, [db]);
 
+  // Static apps have no process: starting one only routes its subdomain to the folder
+  const startStaticApp = useCallback(
+    async (app: App) => {
+      const subdomain = await invoke<string>("start_static_app", {
+        id: app.id,
+        path: app.path,
+        subdomain: app.subdomain,
+        spa: !!app.spa,
+      });
+      setProxyRoutes((prev) => ({
+        ...prev,
+        [app.id]: { subdomain, port: 0 },
+      }));
+    },
+    [setProxyRoutes]
+  );
+
+  // One backend call starts them all, a few at a time, and refreshes the tray once
   const autoStartApps = useCallback(
     async (appsToStart: App[], currentRunning: RunningApps) => {
-      for (const app of appsToStart) {
-        if (app.run_on_startup && !currentRunning[app.id]) {
-          try {
-            const port =
-              app.port ||
-              (await invoke<number>("get_free_port", { preferred: null }));
-            const actualPort = await invoke<number>("start_app", {
-              id: app.id,
-              path: app.path,
-              command: app.command,
-              port,
-              subdomain: app.subdomain,
-            });
-
-            if (app.subdomain) {
-              try {
-                await addProxyRoute(app.id, app.subdomain, actualPort);
-              } catch (e) {
-                console.error(`Failed to add proxy route for ${app.name}:`, e);
-              }
-            }
-          } catch (e) {
-            console.error(`Failed to auto-start ${app.name}:`, e);
-          }
+      const ids = appsToStart
+        .filter((a) => a.run_on_startup && currentRunning[a.id] === undefined)
+        .map((a) => a.id);
+      if (ids.length === 0) return;
+
+      const results = await invoke<BulkResult[]>("start_apps", { ids });
+      for (const result of results) {
+        if (result.error) {
+          const name = appsToStart.find((a) => a.id === result.id)?.name;
+          console.error(`Failed to auto-start ${name}:`, result.error);
         }
       }
+      setProxyRoutes(
+        await invoke<{ [id: string]: ProxyRoute }>("get_proxy_routes")
+      );
     },
-    [addProxyRoute]
+    [setProxyRoutes]
   );
 
   useEffect(() => {
@@ -100,6 +192,10 @@ export function useApps({
       const loadedApps = await loadApps();
       const running = await invoke<RunningApps>("get_running_apps");
       setRunningApps(running);
+      const ready = await invoke<string[]>("get_ready_apps");
+      setReadyApps(Object.fromEntries(ready.map((id) => [id, true])));
+      const building = await invoke<string[]>("get_building_apps");
+      setBuildingApps(Object.fromEntries(building.map((id) => [id, true])));
 
       const newProxyRoutes: { [id: string]: ProxyRoute } = {};
       if (loadedApps) {
@@ -107,12 +203,12 @@ export function useApps({
           const port = running[app.id];
           if (port && app.subdomain) {
             try {
-              await invoke("add_proxy_route", {
+              const subdomain = await invoke<string>("add_proxy_route", {
                 appId: app.id,
                 subdomain: app.subdomain,
                 port,
               });
-              newProxyRoutes[app.id] = { subdomain: app.subdomain, port };
+              newProxyRoutes[app.id] = { subdomain, port };
             } catch (e) {
               console.error(
                 `Failed to re-register proxy route for ${app.name}:`,
@@ -133,23 +229,52 @@ export function useApps({
 
   const handleOpenInBrowser = useCallback(
     (app: App, port: number) => {
-      const sub = app.subdomain && isProxyOperational ? app.subdomain : null;
+      // A live route has the template in app.subdomain already filled in
+      const routed = proxyRoutesRef.current[app.id]?.subdomain;
+      const saved = app.subdomain?.includes("{") ? null : app.subdomain;
+      const subdomain = routed ?? saved;
+      const sub = subdomain && isProxyOperational ? subdomain : null;
       invoke("open_in_browser", { port, subdomain: sub });
     },
     [isProxyOperational]
   );
 
   useEffect(() => {
-    const unlistenStarted = listen<{ id: string; port: number }>(
-      "app-started",
-      (event) => {
-        setRunningApps((prev) => ({
-          ...prev,
-          [event.payload.id]: event.payload.port,
-        }));
-        loadApps();
-      }
-    );
+    const unlistenStarted = listen<{
+      id: string;
+      port: number;
+      profile: string | null;
+    }>("app-started", (event) => {
+      setRunningApps((prev) => ({
+        ...prev,
+        [event.payload.id]: event.payload.port,
+      }));
+      setReadyApps((prev) => {
+        const next = { ...prev };
+        delete next[event.payload.id];
+        return next;
+      });
+      setSlowStarts((prev) => {
+        const next = { ...prev };
+        delete next[event.payload.id];
+        return next;
+      });
+      setRestartNeeded((prev) => {
+        const next = { ...prev };
+        delete next[event.payload.id];
+        return next;
+      });
+      setRunningProfiles((prev) => {
+        const next = { ...prev };
+        if (event.payload.profile) {
+          next[event.payload.id] = event.payload.profile;
+        } else {
+          delete next[event.payload.id];
+        }
+        return next;
+      });
+      loadApps();
+    });
 
     const unlistenStopped = listen<{ id: string; code: number | null }>(
       "app-stopped",
@@ -161,6 +286,26 @@ export function useApps({
           delete next[appId];
           return next;
         });
+        setReadyApps((prev) => {
+          const next = { ...prev };
+          delete next[appId];
+          return next;
+        });
+        setSlowStarts((prev) => {
+          const next = { ...prev };
+          delete next[appId];
+          return next;
+        });
+        setStaleBuilds((prev) => {
+          const next = { ...prev };
+          delete next[appId];
+          return next;
+        });
+        setRestartNeeded((prev) => {
+          const next = { ...prev };
+          delete next[appId];
+          return next;
+        });
 
         try {
           await invoke("remove_proxy_route", { appId });
@@ -175,27 +320,92 @@ export function useApps({
       }
     );
 
-    const unlistenLog = listen<{ id: string; type: string; message: string }>(
-      "app-log",
+    // Output arrives in batches that can span several apps
+    const unlistenLog = listen<
+      { id: string; type: "stdout" | "stderr"; message: string }[]
+    >("app-logs", (event) => {
+      setLogs((prev) => {
+        const next = { ...prev };
+        for (const { id, type, message } of event.payload) {
+          next[id] = [...(next[id] || []), { type, message }];
+        }
+        for (const id of new Set(event.payload.map((line) => line.id))) {
+          next[id] = next[id].slice(-logRetention.current);
+        }
+        return next;
+      });
+    });
+
+    const unlistenReady = listen<{ id: string; source: string }>(
+      "app-ready",
+      (event) => {
+        setReadyApps((prev) => ({ ...prev, [event.payload.id]: true }));
+        setSlowStarts((prev) => {
+          const next = { ...prev };
+          delete next[event.payload.id];
+          return next;
+        });
+      }
+    );
+
+    const unlistenBuilding = listen<{ id: string; building: boolean }>(
+      "app-building",
+      (event) => {
+        const { id, building } = event.payload;
+        setBuildingApps((prev) => {
+          const next = { ...prev };
+          if (building) {
+            next[id] = true;
+          } else {
+            delete next[id];
+          }
+          return next;
+        });
+      }
+    );
+
+    const unlistenSlowStart = listen<{
+      id: string;
+      phase: StartPhase;
+      secs: number;
+    }>("app-start-slow", (event) => {
+      const { id, phase } = event.payload;
+      setSlowStarts((prev) => ({ ...prev, [id]: phase }));
+    });
+
+    const unlistenBuildStale = listen<{ id: string } & StaleBuild>(
+      "app-build-stale",
       (event) => {
-        setLogs((prev) => ({
-          ...prev,
-          [event.payload.id]: [
-            ...(prev[event.payload.id] || []),
-            {
-              type: event.payload.type as "stdout" | "stderr",
-              message: event.payload.message,
-            },
-          ].slice(-200),
-        }));
+        const { id, artifact, changed } = event.payload;
+        setStaleBuilds((prev) => ({ ...prev, [id]: { artifact, changed } }));
       }
     );
 
+    const unlistenRestartNeeded = listen<{
+      id: string;
+      changes: ConfigChange[];
+    }>("app-restart-needed", (event) => {
+      const { id, changes } = event.payload;
+      setRestartNeeded((prev) => {
+        const next = { ...prev };
+        if (changes.length > 0) {
+          next[id] = changes;
+        } else {
+          delete next[id];
+        }
+        return next;
+      });
+    });
+
+    const unlistenPackage = listen<string>("app-package-updated", () => {
+      loadApps();
+    });
+
     const unlistenOpenApp = listen<string>("open-app", async (event) => {
       const appId = event.payload;
       const currentRunning = await invoke<RunningApps>("get_running_apps");
       const port = currentRunning[appId];
-      if (port) {
+      if (port !== undefined) {
         const app = appsRef.current.find((a) => a.id === appId);
         if (app) {
           handleOpenInBrowser(app, port);
@@ -209,6 +419,12 @@ export function useApps({
       unlistenStarted.then((fn) => fn());
       unlistenStopped.then((fn) => fn());
       unlistenLog.then((fn) => fn());
+      unlistenReady.then((fn) => fn());
+      unlistenBuilding.then((fn) => fn());
+      unlistenSlowStart.then((fn) => fn());
+      unlistenBuildStale.then((fn) => fn());
+      unlistenRestartNeeded.then((fn) => fn());
+      unlistenPackage.then((fn) => fn());
       unlistenOpenApp.then((fn) => fn());
     };
   }, [loadApps, handleOpenInBrowser, setProxyRoutes]);
@@ -222,6 +438,41 @@ export function useApps({
     };
   }, []);
 
+  useEffect(() => {
+    const unlisten = listen<AppsGitInfo>("app-git-info", (event) => {
+      setGitInfo(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  useEffect(() => {
+    invoke<LastAccessed>("get_last_accessed").then(setLastAccessed);
+    const unlisten = listen<{ id: string; at: number }>(
+      "app-accessed",
+      (event) => {
+        const { id, at } = event.payload;
+        setLastAccessed((prev) => ({ ...prev, [id]: at }));
+      }
+    );
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // A session trigger switched apps in the background
+  useEffect(() => {
+    const unlisten = listen("session-switched", async () => {
+      setProxyRoutes(
+        await invoke<{ [id: string]: ProxyRoute }>("get_proxy_routes")
+      );
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [setProxyRoutes]);
+
   const addApp = useCallback(async () => {
     try {
       await info("Add app: opening folder dialog");
@@ -257,30 +508,44 @@ export function useApps({
     }
 
     let name = path.split("/").pop() || "Unknown App";
+    let command = "bun start";
+    let buildCommand: string | null = null;
     try {
-      const pkg = await invoke<{ name?: string }>("read_package_json", {
+      const project = await invoke<DetectedProject | null>("detect_project", {
         path,
       });
-      if (pkg.name) {
-        name = pkg.name;
+      if (project) {
+        name = project.name ?? name;
+        command = project.command ?? command;
+        buildCommand = project.build_command;
       }
     } catch {}
 
     try {
       const id = await invoke<string>("generate_id");
       const subdomain = await invoke<string>("slugify_name", { name });
+      const taken = appsRef.current.some((a) => a.subdomain === subdomain);
 
       await db.execute(
-        "INSERT INTO apps (id, name, path, command, run_on_startup, subdomain) VALUES ($1, $2, $3, $4, $5, $6)",
-        [id, name, path, "bun start", false, subdomain]
+        "INSERT INTO apps (id, name, path, command, run_on_startup, subdomain, build_command) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        [
+          id,
+          name,
+          path,
+          command,
+          false,
+          taken ? null : subdomain,
+          buildCommand,
+        ]
       );
 
       try {
         await info(`Add app: added project ${name} at ${path}`);
       } catch {}
       loadApps();
+      return path;
     } catch (e) {
-      const reason = e instanceof Error ? e.message : String(e);
+      const reason = formatError(e);
       try {
         await error(`Add app failed: ${reason}`);
       } catch {}
@@ -288,35 +553,207 @@ export function useApps({
     }
   }, [db, loadApps]);
 
-  const removeApp = useCallback(
-    async (id: string) => {
+  const addWorkspaceApps = useCallback(
+    async (packages: WorkspacePackage[]) => {
       if (!db) return;
 
-      const running = await invoke<RunningApps>("get_running_apps");
-      if (running[id]) {
-        await invoke("stop_app", { id });
-        await removeProxyRoute(id);
+      const takenPaths = new Set(appsRef.current.map((a) => a.path));
+      const takenSubdomains = new Set(
+        appsRef.current.map((a) => a.subdomain).filter(Boolean)
+      );
+
+      for (const pkg of packages) {
+        if (takenPaths.has(pkg.path)) continue;
+
+        const subdomain = takenSubdomains.has(pkg.subdomain)
+          ? null
+          : pkg.subdomain;
+        const id = await invoke<string>("generate_id");
+        await db.execute(
+          "INSERT INTO apps (id, name, path, command, run_on_startup, subdomain) VALUES ($1, $2, $3, $4, $5, $6)",
+          [id, pkg.name, pkg.path, pkg.command, false, subdomain]
+        );
+        takenPaths.add(pkg.path);
+        if (subdomain) takenSubdomains.add(subdomain);
       }
 
-      await db.execute("DELETE FROM apps WHERE id = $1", [id]);
+      try {
+        await info(`Add app: imported ${packages.length} workspace packages`);
+      } catch {}
+      loadApps();
+    },
+    [db, loadApps]
+  );
+
+  const addServiceApp = useCallback(
+    async (preset: string, name: string, local: boolean) => {
+      if (!db) return;
+
+      const service = await invoke<ServiceApp>("prepare_service_app", {
+        preset,
+        name,
+        local,
+      });
+      const id = await invoke<string>("generate_id");
+      await db.execute(
+        "INSERT INTO apps (id, name, path, command, port, run_on_startup, service) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        [
+          id,
+          service.name,
+          service.path,
+          service.command,
+          service.port,
+          false,
+          service.service,
+        ]
+      );
+
+      try {
+        await info(`Add app: added ${service.service} service ${service.name}`);
+      } catch {}
+      loadApps();
+    },
+    [db, loadApps]
+  );
+
+  const setDevcontainer = useCallback(
+    async (path: string, enabled: boolean) => {
+      if (!db) return;
+      await db.execute("UPDATE apps SET devcontainer = $1 WHERE path = $2", [
+        enabled ? 1 : 0,
+        path,
+      ]);
+      loadApps();
+    },
+    [db, loadApps]
+  );
+
+  const addRemoteApp = useCallback(
+    async (remote: RemoteAppInput) => {
+      if (!db) return;
+
+      const id = await invoke<string>("generate_id");
+      const subdomain = await invoke<string>("slugify_name", {
+        name: remote.name,
+      });
+      const taken = appsRef.current.some((a) => a.subdomain === subdomain);
+      await db.execute(
+        "INSERT INTO apps (id, name, path, command, port, run_on_startup, subdomain, kind, remote_host) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+        [
+          id,
+          remote.name,
+          remote.path,
+          remote.command,
+          remote.port,
+          false,
+          taken ? null : subdomain,
+          "remote",
+          remote.host,
+        ]
+      );
+
+      try {
+        await info(`Add app: added remote app ${remote.name} on ${remote.host}`);
+      } catch {}
+      loadApps();
+    },
+    [db, loadApps]
+  );
+
+  const createWorktreeApp = useCallback(
+    async (repo: string, branch: string) => {
+      if (!db) return;
+
+      const worktree = await invoke<WorktreeApp>("create_worktree_app", {
+        repo,
+        branch,
+      });
+
+      const existing = await db.select<App[]>(
+        "SELECT * FROM apps WHERE path = $1",
+        [worktree.path]
+      );
+      if (existing.length > 0) return;
+
+      const repoApp = appsRef.current.find((a) => a.path === repo);
+      const subdomainTaken = appsRef.current.some(
+        (a) => a.subdomain === worktree.subdomain
+      );
+      const baseName = repoApp?.name ?? repo.split("/").pop() ?? "Unknown App";
+      const id = await invoke<string>("generate_id");
+
+      await db.execute(
+        "INSERT INTO apps (id, name, path, command, run_on_startup, subdomain, worktree_of) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        [
+          id,
+          `${baseName} (${branch})`,
+          worktree.path,
+          repoApp?.command ?? "bun start",
+          false,
+          subdomainTaken ? null : worktree.subdomain,
+          repo,
+        ]
+      );
+
+      try {
+        await info(`Add app: added worktree ${branch} at ${worktree.path}`);
+      } catch {}
+      loadApps();
+    },
+    [db, loadApps]
+  );
+
+  // The backend stops the app and removes its route, mDNS name and history
+  const removeApp = useCallback(
+    async (id: string, options: DeleteOptions, passcode: string | null) => {
+      await invoke<CleanReport | null>("delete_app", { id, options, passcode });
+      setProxyRoutes((prev) => {
+        const next = { ...prev };
+        delete next[id];
+        return next;
+      });
+      setLogs((prev) => {
+        const next = { ...prev };
+        delete next[id];
+        return next;
+      });
       loadApps();
     },
-    [db, loadApps, removeProxyRoute]
+    [loadApps, setProxyRoutes]
   );
 
   const startApp = useCallback(
     async (app: App) => {
-      const port =
-        app.port ||
-        (await invoke<number>("get_free_port", { preferred: null }));
+      if (app.kind === "static") {
+        try {
+          await startStaticApp(app);
+        } catch (e) {
+          console.error("Failed to start app:", e);
+          alert(`Failed to start app: ${describeError(e)}`);
+        }
+        return;
+      }
+
+      try {
+        const actualPort = await invoke<number>("start_app", { id: app.id });
+
+        if (app.subdomain) {
+          await addProxyRoute(app.id, app.subdomain, actualPort);
+        }
+      } catch (e) {
+        console.error("Failed to start app:", e);
+        alert(`Failed to start app: ${describeError(e)}`);
+      }
+    },
+    [addProxyRoute, startStaticApp]
+  );
 
+  const startAppWithProfile = useCallback(
+    async (app: App, profile: LaunchProfile) => {
       try {
-        const actualPort = await invoke<number>("start_app", {
+        const actualPort = await invoke<number>("start_app_with_profile", {
           id: app.id,
-          path: app.path,
-          command: app.command,
-          port,
-          subdomain: app.subdomain,
+          profile: profile.name,
         });
 
         if (app.subdomain) {
@@ -324,7 +761,7 @@ export function useApps({
         }
       } catch (e) {
         console.error("Failed to start app:", e);
-        alert(`Failed to start app: ${e}`);
+        alert(`Failed to start app with profile "${profile.name}": ${describeError(e)}`);
       }
     },
     [addProxyRoute]
@@ -338,18 +775,100 @@ export function useApps({
     [removeProxyRoute]
   );
 
+  const stopAllApps = useCallback(async (passcode: string | null) => {
+    const ids = Object.keys(runningApps);
+    if (ids.length === 0) return;
+    const results = await invoke<BulkResult[]>("stop_apps", { ids, passcode });
+    const failed = results.filter((r) => r.error);
+    if (failed.length > 0) {
+      alert(
+        `Failed to stop ${failed.length} app${failed.length === 1 ? "" : "s"}:\n\n${failed.map((r) => `${apps.find((a) => a.id === r.id)?.name ?? r.id}: ${formatError(r.error)}`).join("\n")}`
+      );
+    }
+    setProxyRoutes(
+      await invoke<{ [id: string]: ProxyRoute }>("get_proxy_routes")
+    );
+  }, [apps, runningApps, setProxyRoutes]);
+
+  // Stops everything outside the session and starts its apps
+  const restoreSession = useCallback(
+    async (name: string, passcode: string | null) => {
+      const results = await invoke<BulkResult[]>("restore_session", {
+        name,
+        passcode,
+      });
+      const failed = results.filter((r) => r.error);
+      if (failed.length > 0) {
+        alert(
+          `Failed to switch ${failed.length} app${failed.length === 1 ? "" : "s"}:\n\n${failed.map((r) => `${apps.find((a) => a.id === r.id)?.name ?? r.id}: ${formatError(r.error)}`).join("\n")}`
+        );
+      }
+      setProxyRoutes(
+        await invoke<{ [id: string]: ProxyRoute }>("get_proxy_routes")
+      );
+    },
+    [apps, setProxyRoutes]
+  );
+
   const restartApp = useCallback(
     async (app: App) => {
+      const profileName = runningProfiles[app.id];
+      const profile = profiles[app.id]?.find((p) => p.name === profileName);
       await stopApp(app.id);
-      await startApp(app);
+      if (profile) {
+        await startAppWithProfile(app, profile);
+      } else {
+        await startApp(app);
+      }
     },
-    [stopApp, startApp]
+    [stopApp, startApp, startAppWithProfile, runningProfiles, profiles]
+  );
+
+  const updateAndRestart = useCallback(
+    async (app: App) => {
+      try {
+        const port = await invoke<number | null>("update_and_restart", {
+          id: app.id,
+        });
+
+        if (port !== null && app.subdomain) {
+          await addProxyRoute(app.id, app.subdomain, port);
+        }
+      } catch (e) {
+        console.error("Failed to update app:", e);
+        alert(`Failed to update app: ${describeError(e)}`);
+      }
+    },
+    [addProxyRoute]
   );
 
   const clearLogs = useCallback((appId: string) => {
     setLogs((prev) => ({ ...prev, [appId]: [] }));
   }, []);
 
+  const saveProfiles = useCallback(
+    async (appId: string, appProfiles: LaunchProfile[]) => {
+      if (!db) return;
+
+      await db.execute("DELETE FROM app_profiles WHERE app_id = $1", [appId]);
+      for (const profile of appProfiles) {
+        await db.execute(
+          "INSERT INTO app_profiles (id, app_id, name, command, env, port) VALUES ($1, $2, $3, $4, $5, $6)",
+          [
+            profile.id,
+            appId,
+            profile.name,
+            profile.command,
+            JSON.stringify(profile.env),
+            profile.port,
+          ]
+        );
+      }
+      loadApps();
+    },
+    [db, loadApps]
+  );
+
   const updateApp = useCallback(
     async (editingApp: App, originalApp: App | undefined) => {
       if (!db) return;
@@ -357,32 +876,51 @@ export function useApps({
       const oldSubdomain = originalApp?.subdomain;
       const newSubdomain = editingApp.subdomain;
 
-      if (newSubdomain) {
-        const conflictingApp = apps.find(
-          (a) => a.id !== editingApp.id && a.subdomain === newSubdomain
-        );
-        if (conflictingApp) {
-          alert(
-            `Subdomain "${newSubdomain}" is already used by "${conflictingApp.name}"`
-          );
-          return false;
-        }
+      // The backend owns subdomain uniqueness and names the app holding it
+      try {
+        await invoke("set_app_subdomain", {
+          id: editingApp.id,
+          subdomain: newSubdomain,
+        });
+      } catch (e) {
+        alert(describeError(e));
+        return false;
       }
 
       await db.execute(
-        "UPDATE apps SET name = $1, command = $2, port = $3, run_on_startup = $4, subdomain = $5 WHERE id = $6",
+        "UPDATE apps SET name = $1, command = $2, port = $3, run_on_startup = $4, description = $5, notes = $6, kind = $7, spa = $8, build_command = $9, prerequisites = $10, remote_host = $11, devcontainer = $12, path_entries = $13, shell = $14 WHERE id = $15",
         [
           editingApp.name,
           editingApp.command,
           editingApp.port,
           editingApp.run_on_startup ? 1 : 0,
-          editingApp.subdomain,
+          editingApp.description,
+          editingApp.notes,
+          editingApp.kind,
+          editingApp.spa ? 1 : 0,
+          editingApp.build_command || null,
+          editingApp.prerequisites?.trim() || null,
+          editingApp.remote_host?.trim() || null,
+          editingApp.devcontainer ? 1 : 0,
+          editingApp.path_entries?.trim() || null,
+          editingApp.shell,
           editingApp.id,
         ]
       );
 
       const running = await invoke<RunningApps>("get_running_apps");
-      if (running[editingApp.id]) {
+      if (
+        editingApp.kind === "static" &&
+        running[editingApp.id] !== undefined
+      ) {
+        // Restarting re-routes the folder under the new subdomain and SPA setting
+        try {
+          await invoke("stop_app", { id: editingApp.id });
+          await startStaticApp(editingApp);
+        } catch (e) {
+          console.error("Failed to update static app:", e);
+        }
+      } else if (running[editingApp.id]) {
         const port = running[editingApp.id];
         const subdomainChanged = oldSubdomain !== newSubdomain;
 
@@ -397,14 +935,14 @@ export function useApps({
           }
 
           if (newSubdomain) {
-            await invoke("add_proxy_route", {
+            const subdomain = await invoke<string>("add_proxy_route", {
               appId: editingApp.id,
               subdomain: newSubdomain,
               port,
             });
             setProxyRoutes((prev) => ({
               ...prev,
-              [editingApp.id]: { subdomain: newSubdomain, port },
+              [editingApp.id]: { subdomain, port },
             }));
           }
         } catch (e) {
@@ -415,19 +953,38 @@ export function useApps({
       loadApps();
       return true;
     },
-    [db, apps, loadApps, proxyRoutes, setProxyRoutes]
+    [db, loadApps, proxyRoutes, setProxyRoutes, startStaticApp]
   );
 
   return {
     apps,
     runningApps,
+    readyApps,
+    buildingApps,
+    slowStarts,
+    staleBuilds,
+    restartNeeded,
     appsUsage,
+    gitInfo,
+    lastAccessed,
     logs,
+    profiles,
+    runningProfiles,
     addApp,
+    addWorkspaceApps,
+    addServiceApp,
+    addRemoteApp,
+    setDevcontainer,
+    createWorktreeApp,
     removeApp,
     startApp,
+    startAppWithProfile,
+    saveProfiles,
     stopApp,
+    stopAllApps,
+    restoreSession,
     restartApp,
+    updateAndRestart,
     clearLogs,
     updateApp,
     handleOpenInBrowser,
@@ -1,6 +1,7 @@
 import { useState, useCallback, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import type { ProxyServiceStatus, ProxyRoute } from "@/types";
+import { listen } from "@tauri-apps/api/event";
+import type { ProxyServiceStatus, ProxyRoute, UninstallReport } from "@/types";
 
 export function useProxy() {
   const [lanIp, setLanIp] = useState<string | null>(null);
@@ -34,12 +35,38 @@ export function useProxy() {
     initProxy();
   }, []);
 
-  const addProxyRoute = useCallback(async (appId: string, subdomain: string, port: number) => {
+  useEffect(() => {
+    const unlisten = listen<string>("proxy-route-expired", (event) => {
+      setProxyRoutes((prev) => {
+        const next = { ...prev };
+        delete next[event.payload];
+        return next;
+      });
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // A `ttlSecs` route is removed again by the backend once that time is up
+  const addProxyRoute = useCallback(async (appId: string, subdomain: string, port: number, ttlSecs?: number) => {
     try {
-      await invoke("add_proxy_route", { appId, subdomain, port });
+      // The backend fills in template variables like {branch}
+      const resolved = await invoke<string>("add_proxy_route", {
+        appId,
+        subdomain,
+        port,
+        ttlSecs: ttlSecs ?? null,
+      });
       setProxyRoutes((prev) => ({
         ...prev,
-        [appId]: { subdomain, port },
+        [appId]: {
+          subdomain: resolved,
+          port,
+          expires_at: ttlSecs
+            ? Date.now() + ttlSecs * 1000
+            : prev[appId]?.expires_at,
+        },
       }));
     } catch (e) {
       console.error("Failed to add proxy route:", e);
@@ -83,11 +110,14 @@ export function useProxy() {
     }
   }, []);
 
-  const uninstallService = useCallback(async () => {
+  const uninstallService = useCallback(async (passcode: string | null) => {
     try {
-      await invoke("uninstall_proxy_service");
+      const report = await invoke<UninstallReport>("uninstall_proxy_service", {
+        passcode,
+      });
       const status = await invoke<ProxyServiceStatus>("get_proxy_service_status");
       setServiceStatus(status);
+      return report;
     } catch (e) {
       console.error("Service uninstallation failed:", e);
       throw e;
@@ -0,0 +1,30 @@
+import { invoke } from "@tauri-apps/api/core";
+import { prompt } from "@/components/confirm-dialog";
+import { describeError } from "@/lib/errors";
+import type { AppLockStatus } from "@/types";
+
+export interface Unlocked {
+  passcode: string | null;
+}
+
+// Asks for the passcode when the app lock uses one; null when cancelled. The
+// passcode goes along with the action, which the backend checks again, and
+// Touch ID is asked for by the backend when the action runs
+export async function unlock(reason: string): Promise<Unlocked | null> {
+  const lock = await invoke<AppLockStatus>("get_app_lock");
+  if (!lock.enabled || lock.method !== "passcode") return { passcode: null };
+
+  const passcode = await prompt(`Enter the passcode to ${reason}.`, {
+    title: "app lock",
+    confirm: "Unlock",
+  });
+  if (passcode === null) return null;
+
+  try {
+    await invoke("unlock_app", { reason, passcode });
+    return { passcode };
+  } catch (e) {
+    alert(describeError(e));
+    return null;
+  }
+}
@@ -0,0 +1,32 @@
+import type { AppError } from "@/types";
+
+function isAppError(e: unknown): e is AppError {
+  return typeof e === "object" && e !== null && "kind" in e && "message" in e;
+}
+
+// Commands reject with an AppError; anything else is shown as is
+export function formatError(e: unknown): string {
+  if (isAppError(e)) return e.message;
+  return e instanceof Error ? e.message : String(e);
+}
+
+// The message plus what to do about it, for errors the user can fix
+export function describeError(e: unknown): string {
+  if (!isAppError(e)) return formatError(e);
+  switch (e.kind) {
+    case "port_unavailable":
+      return e.port > 0
+        ? `${e.message}\n\nSomething else is using port ${e.port}. Stop it or pick another port in the app's settings.`
+        : e.message;
+    case "subdomain_taken":
+      return `${e.message}\n\nPick another subdomain, or clear it on "${e.app_name}" first.`;
+    case "proxy_unavailable":
+      return `${e.message}\n\nStart the proxy service from the header, or switch to the built-in proxy.`;
+    case "permission_denied":
+      return `${e.message}\n\nThis needs administrator access. Try again and approve the prompt.`;
+    case "spawn_failed":
+      return e.stderr ? `${e.message}\n\n${e.stderr}` : e.message;
+    default:
+      return e.message;
+  }
+}
@@ -0,0 +1,33 @@
+import type { SourceLocation } from "@/types";
+
+// Python: File "/app/main.py", line 12
+const PYTHON = /File "([^"]+)", line (\d+)/;
+// tsc: src/app.ts(12,5)
+const TSC = /((?:[\w.@~-]+\/)+[\w.@~-]+\.[A-Za-z]\w*)\((\d+),(\d+)\)/;
+// Node, Rust, Go and most others: /app/src/app.ts:12:5 or src/main.rs:12
+const PATH_LINE =
+  /(?:file:\/\/)?((?:\/|\.{1,2}\/)?(?:[\w.@~-]+\/)+[\w.@~-]+\.[A-Za-z]\w*):(\d+)(?::(\d+))?/g;
+
+// The first file:line in a log line, e.g. a frame of a stack trace. Paths need
+// a slash so hosts like api.example.com:443 aren't taken for files.
+export function parseSourceLocation(message: string): SourceLocation | null {
+  const python = PYTHON.exec(message);
+  if (python) {
+    return { file: python[1], line: Number(python[2]), column: null };
+  }
+  const tsc = TSC.exec(message);
+  if (tsc) {
+    return { file: tsc[1], line: Number(tsc[2]), column: Number(tsc[3]) };
+  }
+  for (const match of message.matchAll(PATH_LINE)) {
+    // Skip URLs like http://localhost:5173/src/app.ts:12
+    const before = message.slice(0, match.index);
+    if (!match[0].startsWith("file:") && /:\/\/\S*$/.test(before)) continue;
+    return {
+      file: match[1],
+      line: Number(match[2]),
+      column: match[3] ? Number(match[3]) : null,
+    };
+  }
+  return null;
+}
@@ -1,13 +1,18 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
+import { getCurrentWindow } from "@tauri-apps/api/window";
 import App from "./App";
 import { ConfirmDialogProvider } from "./components/confirm-dialog";
+import { InternalLogViewer } from "./components/internal-log-viewer";
 import "./index.css";
 
+// The debug window loads the same page
+const isDebugWindow = getCurrentWindow().label === "debug";
+
 ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
   <React.StrictMode>
     <ConfirmDialogProvider>
-      <App />
+      {isDebugWindow ? <InternalLogViewer /> : <App />}
     </ConfirmDialogProvider>
   </React.StrictMode>,
 );
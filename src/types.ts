@@ -7,6 +7,87 @@ export interface App {
   run_on_startup: boolean;
   created_at: string;
   subdomain: string | null;
+  description: string | null;
+  notes: string | null;
+  worktree_of: string | null;
+  // Run before every start, e.g. "cargo build"
+  build_command: string | null;
+  // Database preset id ("postgres", "redis", "mysql") for service apps
+  service: string | null;
+  // Ports, host:port pairs, URLs or "docker", one per line, checked before start
+  prerequisites: string | null;
+  // Remote apps only: ssh destination the command runs on
+  remote_host: string | null;
+  // Run the command inside .devcontainer's container instead of on the host
+  devcontainer: boolean;
+  // Directories put in front of PATH at start, one per line
+  path_entries: string | null;
+  // null runs the command in the login shell
+  shell: AppShell | null;
+  kind: AppKind;
+  // Static apps only: serve index.html for unknown paths
+  spa: boolean;
+  // From package.json, refreshed each time the app starts
+  package_name: string | null;
+  package_version: string | null;
+  // JSON object of runtime name to version range
+  package_engines: string | null;
+  // Engines the runtime found in the app's shell doesn't meet
+  engine_warning: string | null;
+}
+
+export type AppKind = "process" | "static" | "remote";
+
+// Picked shells run with -l -c: the profile is sourced, rc files aren't
+export type AppShell = "zsh" | "bash" | "sh";
+
+export type Browser = "default" | "chrome" | "firefox" | "safari" | "arc";
+
+export interface BrowserPreference {
+  browser: Browser;
+  private: boolean;
+}
+
+export type Terminal = "terminal" | "iterm2" | "wezterm" | "ghostty";
+
+export type Editor = "vscode" | "cursor" | "zed" | "webstorm";
+
+export interface EditorPreference {
+  // null picks the first installed editor
+  editor: Editor | null;
+  // e.g. "subl {file}:{line}"; wins over editor when set
+  command: string | null;
+}
+
+// A place in a file, e.g. from a stack trace; relative files are in the project
+export interface SourceLocation {
+  file: string;
+  line: number;
+  column: number | null;
+}
+
+export interface LaunchProfile {
+  id: string;
+  app_id: string;
+  name: string;
+  command: string;
+  env: Record<string, string>;
+  port: number | null;
+}
+
+export interface AppProfiles {
+  [appId: string]: LaunchProfile[];
+}
+
+export interface ConfigIssue {
+  field: "path" | "command";
+  message: string;
+}
+
+export interface ProjectReadme {
+  file_name: string;
+  content: string;
+  truncated: boolean;
 }
 
 export interface ProxyServiceStatus {
@@ -14,15 +95,172 @@ export interface ProxyServiceStatus {
   caddy_running: boolean;
 }
 
+// What run_doctor_fix can do about a failed check
+export type DoctorFix =
+  | { type: "install_proxy" }
+  | { type: "start_proxy" }
+  | { type: "enable_dns_responder" }
+  | { type: "network_workarounds" }
+  | { type: "start_docker" }
+  | { type: "open_url"; url: string };
+
+export interface DoctorCheck {
+  check:
+    | "bun"
+    | "node"
+    | "path"
+    | "proxy_port"
+    | "dns_port"
+    | "proxy"
+    | "resolver"
+    | "network"
+    | "docker";
+  label: string;
+  status: "ok" | "warning" | "error";
+  message: string;
+  fix: DoctorFix | null;
+}
+
+export interface UninstallReport {
+  cleaned: string[];
+  remaining: string[];
+  caddy_running: boolean;
+}
+
 export interface ProxyRoute {
   subdomain: string;
   port: number;
+  // Unix ms when a temporary route is taken down
+  expires_at?: number;
+  // Also serves *.<subdomain>.<hostname>.local
+  wildcard?: boolean;
+  // Left out while they're Caddy's defaults
+  headers?: ForwardHeaders;
+  timeouts?: ProxyTimeouts;
+  // What the proxy speaks to the app
+  upstream?: "http" | "h2c" | "https_insecure";
+  // Unset compresses static sites only
+  compress?: boolean;
+  // Bigger request bodies get 413
+  max_body_bytes?: number;
+  // Requests per second before the app's relay answers 429
+  rate_limit?: number;
+  // Unpublished, but keeps its subdomain and settings
+  disabled?: boolean;
+  // Also served over HTTPS, signed by Caddy's local CA
+  tls?: boolean;
+}
+
+// Seconds Caddy waits on an app; unset keeps its defaults
+export interface ProxyTimeouts {
+  read_secs: number | null;
+  write_secs: number | null;
+  idle_secs: number | null;
+  // No response buffering, for server-sent events
+  streaming: boolean;
+}
+
+// Host and X-Forwarded-* headers a route sends its app
+export interface ForwardHeaders {
+  // localhost:<port> as Host instead of the .local name
+  rewrite_host: boolean;
+  forwarded_for: boolean;
+  forwarded_proto: boolean;
+  forwarded_host: boolean;
+}
+
+export interface DeleteOptions {
+  // Drop the app's output and captured requests
+  logs: boolean;
+  // Artifact directories to move to the Trash, e.g. "node_modules"
+  trash: string[];
+  // Worktree apps only: remove the checkout too
+  worktree: boolean;
+}
+
+// What happens to a running app when My Little Apps quits
+export type QuitBehavior = "stop" | "keep_running";
+
+// A newer release of My Little Apps
+export interface UpdateInfo {
+  version: string;
+  current_version: string;
+  notes: string | null;
+  date: string | null;
+}
+
+// restoring and done come from the relaunched version
+export type UpdateStage =
+  | "downloading"
+  | "installing"
+  | "restarting"
+  | "restoring"
+  | "done";
+
+export interface UpdateProgress {
+  stage: UpdateStage;
+  downloaded: number;
+  total: number | null;
+}
+
+// A backend panic or failed background task, saved under crashes/
+export interface CrashReport {
+  id: string;
+  kind: "panic" | "task_error";
+  message: string;
+  location: string | null;
+  thread: string | null;
+  version: string;
+  os: string;
+  time: number;
+  events: string[];
+  dismissed: boolean;
+}
+
+// One line of the backend's internal tracing log
+export interface InternalLogEntry {
+  timestamp: string;
+  level: "ERROR" | "WARN" | "INFO" | "DEBUG" | "TRACE";
+  target: string;
+  message: string;
+  // Outermost first, like "launch_app{app=api}"
+  spans: string[];
+  fields: Record<string, unknown>;
 }
 
 export interface RunningApps {
   [id: string]: number;
 }
 
+// Where a starting app was when it took longer than it is allowed to
+export type StartPhase = "starting" | "building";
+
+// A build an app serves that is missing (changed: null) or older than its
+// sources, `changed` being the newest source file
+export interface StaleBuild {
+  artifact: string;
+  changed: string | null;
+}
+
+// Saved config a running app hasn't picked up yet
+export type ConfigChange = "command" | "env" | "port" | "subdomain";
+
+export interface RestartNeeded {
+  [id: string]: ConfigChange[];
+}
+
+export interface GitInfo {
+  branch: string | null;
+  upstream: string | null;
+  dirty: boolean;
+  ahead: number;
+  behind: number;
+}
+
+export interface AppsGitInfo {
+  [id: string]: GitInfo;
+}
+
 export interface AppUsage {
   cpu: number;
   memory: number;
@@ -32,6 +270,11 @@ export interface AppsUsage {
   [id: string]: AppUsage;
 }
 
+// Unix ms of each app's last request through the proxy; unused apps are missing
+export interface LastAccessed {
+  [id: string]: number;
+}
+
 export interface LogEntry {
   type: "stdout" | "stderr";
   message: string;
@@ -40,3 +283,310 @@ export interface LogEntry {
 export interface AppLogs {
   [id: string]: LogEntry[];
 }
+
+export interface Worktree {
+  path: string;
+  branch: string | null;
+}
+
+export interface RepoBranches {
+  branches: string[];
+  worktrees: Worktree[];
+}
+
+// A search_apps hit; `positions` index the characters of `field` that matched
+export interface AppMatch {
+  id: string;
+  score: number;
+  field: "name" | "subdomain" | "path" | "tags";
+  positions: number[];
+}
+
+export interface AppLockStatus {
+  enabled: boolean;
+  // "system" is Touch ID, falling back to the login password
+  method: "system" | "passcode";
+}
+
+export type ChannelTarget =
+  // An empty server means ntfy.sh
+  | { type: "ntfy"; server: string; topic: string; token: string | null }
+  | { type: "webhook"; url: string };
+
+export interface NotificationChannel {
+  // Empty until saved
+  id: string;
+  name: string;
+  target: ChannelTarget;
+  crashes: boolean;
+  long_tasks: boolean;
+}
+
+export interface NotificationSettings {
+  channels: NotificationChannel[];
+  // Builds and update steps running at least this long notify when done
+  long_task_secs: number;
+}
+
+// Global settings by key, see get_settings / set_setting
+export interface Settings {
+  // Ports picked for apps without one; end is excluded
+  port_range: { start: number; end: number };
+  // Lines of output kept per app
+  log_retention: number;
+  default_browser: BrowserPreference;
+  notifications: NotificationSettings;
+}
+
+// Payload of settings-changed
+export type SettingChange = {
+  [K in keyof Settings]: { key: K; value: Settings[K] };
+}[keyof Settings];
+
+export type LogSink =
+  | { type: "loki"; url: string }
+  | { type: "ndjson"; url: string };
+
+export interface LogForwardingSettings {
+  enabled: boolean;
+  sink: LogSink | null;
+  // Sent as the Authorization header
+  authorization: string | null;
+  batch_size: number;
+  flush_ms: number;
+  // Mirror output into macOS unified logging
+  unified_log: boolean;
+}
+
+export interface LogForwardingStatus {
+  active: boolean;
+  // Lines lost to a full queue or an unreachable collector
+  dropped: number;
+}
+
+// A machine on either end of a remote control pairing
+export interface PairingInfo {
+  id: string;
+  name: string;
+  // Set for hosts this machine controls
+  address: string | null;
+  // Unix seconds; set for devices that control this machine
+  paired_at: number | null;
+}
+
+export interface RemoteControlStatus {
+  // Set while this machine accepts paired devices
+  port: number | null;
+  devices: PairingInfo[];
+  hosts: PairingInfo[];
+}
+
+// An app on a paired host; port is set while it runs
+export interface RemoteApp {
+  id: string;
+  name: string;
+  port: number | null;
+}
+
+// An app of a saved session, with the profile it was running (null: none)
+export interface SessionApp {
+  app_id: string;
+  profile: string | null;
+}
+
+// A named set of apps to run together
+export interface Session {
+  name: string;
+  apps: SessionApp[];
+}
+
+// Switches to `session` (null: stops every app) on a weekly schedule, `days`
+// holding a bit per weekday from Monday, or when a macOS Focus mode turns on
+export type SessionTrigger = { id: number; session: string | null } & (
+  | { kind: "schedule"; days: number; at: string }
+  | { kind: "focus"; mode: string }
+);
+
+// One app's outcome from start_apps / stop_apps
+export interface BulkResult {
+  id: string;
+  port: number | null;
+  error: AppError | null;
+}
+
+export interface WorktreeApp {
+  path: string;
+  branch: string;
+  subdomain: string;
+}
+
+export interface DiskUsage {
+  total: number;
+  node_modules: number;
+  build: number;
+  cache: number;
+  computed_at: number;
+}
+
+export interface CleanReport {
+  dry_run: boolean;
+  targets: { target: string; bytes: number }[];
+  reclaimed: number;
+}
+
+export interface WorkspacePackage {
+  name: string;
+  path: string;
+  command: string;
+  subdomain: string;
+}
+
+export interface DetectedProject {
+  runtime: "node" | "rust" | "go" | "python";
+  name: string | null;
+  command: string | null;
+  build_command: string | null;
+}
+
+export interface ServicePreset {
+  id: string;
+  name: string;
+  image: string;
+  port: number;
+  supports_local: boolean;
+}
+
+export interface ServiceApp {
+  name: string;
+  path: string;
+  command: string;
+  port: number;
+  service: string;
+}
+
+export interface RemoteAppInput {
+  name: string;
+  host: string;
+  path: string;
+  command: string;
+  port: number | null;
+}
+
+export interface BenchmarkReport {
+  requests: number;
+  errors: number;
+  status_codes: Record<string, number>;
+  duration_ms: number;
+  requests_per_sec: number;
+  // Milliseconds
+  latency: { p50: number; p90: number; p99: number; max: number };
+}
+
+export type UptimeRange = "24h" | "7d" | "30d";
+
+export interface Incident {
+  // Unix milliseconds; ended_at is null while the app is still failing
+  started_at: number;
+  ended_at: number | null;
+  failed_checks: number;
+  error: string;
+}
+
+export interface Uptime {
+  health_path: string;
+  checks: number;
+  uptime_percent: number | null;
+  avg_response_ms: number | null;
+  // Most recent first
+  incidents: Incident[];
+}
+
+export type AppEventKind =
+  | "started"
+  | "stopped"
+  | "crashed"
+  | "config_changed"
+  | "route_added"
+  | "route_removed"
+  | "health_down"
+  | "health_up";
+
+// One entry of an app's activity timeline; also sent as `app-event`
+export interface AppEvent {
+  app_id: string;
+  at: number;
+  kind: AppEventKind;
+  // e.g. the port it started on or the exit code
+  detail: string | null;
+}
+
+export interface ProcessInfo {
+  pid: number;
+  parent: number | null;
+  name: string;
+  command: string[];
+  cwd: string | null;
+  env: Record<string, string>;
+}
+
+export type EnvSource = "dotenv" | "profile" | "command";
+
+// not_exported: in .env but not the process env, e.g. read by the framework
+export type DriftKind = "missing" | "changed" | "removed" | "not_exported";
+
+export interface EnvDrift {
+  key: string;
+  kind: DriftKind;
+  source: EnvSource | null;
+  expected: string | null;
+  actual: string | null;
+}
+
+export interface EnvDiff {
+  pid: number;
+  matched: number;
+  drift: EnvDrift[];
+  restart_needed: boolean;
+}
+
+export interface ListeningSocket {
+  pid: number;
+  process: string;
+  address: string;
+  port: number;
+}
+
+export interface ListeningPort extends ListeningSocket {
+  cwd: string | null;
+  // Set when the listener belongs to one of our running apps
+  app_id: string | null;
+}
+
+// What watching an app's project would cover, ignore files applied
+export interface WatchPlan {
+  dirs: string[];
+  ignored: string[];
+  files: number;
+  truncated: boolean;
+}
+
+export interface ProcessInspection {
+  processes: ProcessInfo[];
+  sockets: ListeningSocket[];
+}
+
+// What Tauri commands reject with; `kind` says what went wrong
+export type AppError =
+  | { kind: "not_found"; message: string }
+  | { kind: "port_unavailable"; port: number; message: string }
+  | {
+      kind: "subdomain_taken";
+      subdomain: string;
+      app_id: string;
+      app_name: string;
+      message: string;
+    }
+  | { kind: "proxy_unavailable"; message: string }
+  | { kind: "permission_denied"; message: string }
+  | { kind: "spawn_failed"; message: string; stderr: string }
+  | { kind: "other"; message: string };